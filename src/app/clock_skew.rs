@@ -0,0 +1,97 @@
+//! Local clock against GitHub's. Cursors and the `created_at > since` comparisons assume the
+//! two roughly agree; a local clock running behind silently hides everything newer than it.
+
+use anyhow::Result;
+use chrono::Duration;
+
+use crate::{
+    domain::latency::format_latency,
+    ports::{ClockPort, GhClientPort},
+};
+
+/// Skews above this get a warning.
+pub const CLOCK_SKEW_WARN_SECONDS: i64 = 2 * 60;
+/// Skews above this are an error: polls would miss or repeat events.
+pub const CLOCK_SKEW_ERROR_SECONDS: i64 = 15 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockSkewLevel {
+    Ok,
+    Warn,
+    Error,
+}
+
+/// Local time minus GitHub's; negative when the local clock is behind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockSkew(pub Duration);
+
+impl ClockSkew {
+    pub fn level(&self) -> ClockSkewLevel {
+        let seconds = self.0.num_seconds().abs();
+        if seconds > CLOCK_SKEW_ERROR_SECONDS {
+            ClockSkewLevel::Error
+        } else if seconds > CLOCK_SKEW_WARN_SECONDS {
+            ClockSkewLevel::Warn
+        } else {
+            ClockSkewLevel::Ok
+        }
+    }
+}
+
+impl std::fmt::Display for ClockSkew {
+    /// `local clock is 30m00s behind GitHub`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let direction = if self.0 < Duration::zero() {
+            "behind"
+        } else {
+            "ahead of"
+        };
+        write!(
+            f,
+            "local clock is {} {direction} GitHub",
+            format_latency(self.0.abs())
+        )
+    }
+}
+
+/// `clock` against `gh`'s server time; `None` when the client cannot report it.
+pub async fn measure_clock_skew<C, K>(gh: &C, clock: &K) -> Result<Option<ClockSkew>>
+where
+    C: GhClientPort,
+    K: ClockPort,
+{
+    let Some(server_now) = gh.server_time().await? else {
+        return Ok(None);
+    };
+    Ok(Some(ClockSkew(clock.now() - server_now)))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::{ClockSkew, ClockSkewLevel};
+
+    #[test]
+    fn level_follows_the_thresholds_in_both_directions() {
+        let level = |seconds| ClockSkew(Duration::seconds(seconds)).level();
+        assert_eq!(level(0), ClockSkewLevel::Ok);
+        assert_eq!(level(-120), ClockSkewLevel::Ok);
+        assert_eq!(level(121), ClockSkewLevel::Warn);
+        assert_eq!(level(-900), ClockSkewLevel::Warn);
+        assert_eq!(level(-901), ClockSkewLevel::Error);
+        assert_eq!(level(3600), ClockSkewLevel::Error);
+    }
+
+    #[test]
+    fn display_says_which_way_the_local_clock_is_off() {
+        assert_eq!(
+            ClockSkew(Duration::minutes(-30)).to_string(),
+            "local clock is 30m00s behind GitHub"
+        );
+        assert_eq!(
+            ClockSkew(Duration::seconds(150)).to_string(),
+            "local clock is 2m30s ahead of GitHub"
+        );
+    }
+}
@@ -0,0 +1,350 @@
+use anyhow::Result;
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use crate::{domain::events::WatchEvent, ports::TimelineQueryPort};
+
+/// Entries in a feed when the caller does not ask for another count.
+pub const DEFAULT_FEED_LIMIT: usize = 100;
+const FEED_ID: &str = "urn:gh-watch:timeline";
+const ENTRY_ID_PREFIX: &str = "urn:gh-watch:event:";
+
+/// The newest `limit` stored events of `repos` (every repository when empty), newest first.
+pub fn load_feed_events<S>(state: &S, repos: &[String], limit: usize) -> Result<Vec<WatchEvent>>
+where
+    S: TimelineQueryPort + ?Sized,
+{
+    state.load_timeline_events_filtered(DateTime::<Utc>::MIN_UTC, None, repos, &[], limit)
+}
+
+/// Atom (RFC 4287) document with one entry per event, newest first. The feed is dated by
+/// its newest event, or `generated_at` when there are none, so an unchanged timeline
+/// renders byte for byte the same.
+pub fn render_atom_feed(events: &[WatchEvent], generated_at: DateTime<Utc>) -> String {
+    let mut events = events.iter().collect::<Vec<_>>();
+    events.sort_by(|a, b| {
+        b.created_at
+            .cmp(&a.created_at)
+            .then_with(|| a.event_key().cmp(&b.event_key()))
+    });
+    let updated = events
+        .first()
+        .map_or(generated_at, |event| event.created_at);
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    xml.push_str(&format!("  <id>{FEED_ID}</id>\n"));
+    xml.push_str("  <title>gh-watch timeline</title>\n");
+    xml.push_str(&format!("  <updated>{}</updated>\n", atom_date(updated)));
+    xml.push_str(&format!(
+        "  <generator version=\"{}\">gh-watch</generator>\n",
+        env!("CARGO_PKG_VERSION")
+    ));
+    for event in events {
+        let title = match event.display_number() {
+            Some(number) => format!("[{}] #{number} {}", event.repo, event.title),
+            None => format!("[{}] {}", event.repo, event.title),
+        };
+        xml.push_str("  <entry>\n");
+        xml.push_str(&format!(
+            "    <id>{ENTRY_ID_PREFIX}{}</id>\n",
+            escape_xml(&iri_escape(&event.event_key()))
+        ));
+        xml.push_str(&format!("    <title>{}</title>\n", escape_xml(&title)));
+        xml.push_str(&format!(
+            "    <updated>{}</updated>\n",
+            atom_date(event.created_at)
+        ));
+        xml.push_str(&format!(
+            "    <link rel=\"alternate\" href=\"{}\"/>\n",
+            escape_xml(&event.url)
+        ));
+        xml.push_str(&format!(
+            "    <author><name>{}</name></author>\n",
+            escape_xml(&event.actor)
+        ));
+        xml.push_str(&format!(
+            "    <category term=\"{}\"/>\n",
+            event.kind.as_str()
+        ));
+        xml.push_str("  </entry>\n");
+    }
+    xml.push_str("</feed>\n");
+    xml
+}
+
+fn atom_date(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Not allowed anywhere in XML 1.0, not even as character references.
+            ch if (ch as u32) < 0x20 && !matches!(ch, '\t' | '\n' | '\r') => {}
+            ch => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// Percent-encodes what may not appear in a URN, e.g. the brackets of `dependabot[bot]`.
+fn iri_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~:/@!$&'()*+,;=".contains(&byte) {
+            escaped.push(byte as char);
+        } else {
+            escaped.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::domain::events::EventKind;
+
+    fn event(id: &str, kind: EventKind, title: &str, minute: u32) -> WatchEvent {
+        WatchEvent {
+            event_id: id.to_string(),
+            repo: "acme/api".to_string(),
+            kind,
+            actor: "dev".to_string(),
+            title: title.to_string(),
+            url: format!("https://github.com/acme/api/issues/7?from={id}&x=1"),
+            created_at: Utc.with_ymd_and_hms(2026, 3, 2, 9, minute, 0).unwrap(),
+            source_item_id: id.to_string(),
+            subject_author: None,
+            requested_reviewer: None,
+            mentions: Vec::new(),
+            team_mentions: Vec::new(),
+            subject_number: Some(7),
+            subject_url: None,
+            account: None,
+            body: None,
+        }
+    }
+
+    fn generated_at() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 3, 0, 0, 0).unwrap()
+    }
+
+    #[derive(Debug)]
+    struct Element {
+        name: String,
+        attributes: Vec<(String, String)>,
+        text: String,
+        children: Vec<Element>,
+    }
+
+    impl Element {
+        fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+            self.children.iter().filter(move |child| child.name == name)
+        }
+
+        fn only_child(&self, name: &str) -> &Element {
+            let found = self
+                .children
+                .iter()
+                .filter(|child| child.name == name)
+                .collect::<Vec<_>>();
+            assert_eq!(found.len(), 1, "<{}> needs exactly one <{name}>", self.name);
+            found[0]
+        }
+
+        fn attribute(&self, name: &str) -> Option<&str> {
+            self.attributes
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.as_str())
+        }
+    }
+
+    fn unescape(text: &str) -> String {
+        for (index, _) in text.match_indices('&') {
+            let entity = &text[index..text[index..].find(';').map(|end| index + end + 1).unwrap()];
+            assert!(
+                ["&amp;", "&lt;", "&gt;", "&quot;", "&apos;"].contains(&entity),
+                "unknown entity {entity}"
+            );
+        }
+        text.replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&amp;", "&")
+    }
+
+    /// Parses the element subset `render_atom_feed` emits, failing on anything that is not
+    /// well-formed XML (unbalanced tags, raw `<` or `&` in text, unknown entities).
+    fn parse_xml(xml: &str) -> Element {
+        let body = xml
+            .strip_prefix("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n")
+            .expect("XML declaration");
+        let mut stack = vec![Element {
+            name: String::new(),
+            attributes: Vec::new(),
+            text: String::new(),
+            children: Vec::new(),
+        }];
+        let mut rest = body;
+        while let Some(start) = rest.find('<') {
+            let text = &rest[..start];
+            assert!(!text.contains('>'), "raw > in {text:?}");
+            stack.last_mut().unwrap().text.push_str(&unescape(text));
+            let end = rest[start..].find('>').unwrap() + start;
+            let tag = &rest[start + 1..end];
+            rest = &rest[end + 1..];
+
+            if let Some(name) = tag.strip_prefix('/') {
+                let element = stack.pop().unwrap();
+                assert_eq!(element.name, name, "unbalanced tags");
+                stack.last_mut().unwrap().children.push(element);
+                continue;
+            }
+            let self_closing = tag.ends_with('/');
+            let tag = tag.trim_end_matches('/');
+            let (name, mut attrs) = tag.split_once(' ').unwrap_or((tag, ""));
+            let mut attributes = Vec::new();
+            while let Some((key, value)) = attrs.trim_start().split_once("=\"") {
+                let close = value.find('"').unwrap();
+                attributes.push((key.to_string(), unescape(&value[..close])));
+                attrs = &value[close + 1..];
+            }
+            assert!(attrs.trim().is_empty(), "malformed attributes in <{tag}>");
+            let element = Element {
+                name: name.to_string(),
+                attributes,
+                text: String::new(),
+                children: Vec::new(),
+            };
+            if self_closing {
+                stack.last_mut().unwrap().children.push(element);
+            } else {
+                stack.push(element);
+            }
+        }
+        assert!(rest.trim().is_empty());
+        assert_eq!(stack.len(), 1, "unclosed elements");
+        let mut document = stack.pop().unwrap();
+        assert_eq!(document.children.len(), 1, "one root element");
+        document.children.pop().unwrap()
+    }
+
+    fn assert_atom_date(element: &Element) {
+        DateTime::parse_from_rfc3339(&element.text)
+            .unwrap_or_else(|err| panic!("{:?} is not an RFC 3339 date: {err}", element.text));
+    }
+
+    /// The RFC 4287 constraints on the elements the feed uses: feed and entries need exactly
+    /// one `id` (an absolute IRI), `title` and `updated`; every entry needs an author when the
+    /// feed has none, and links need an `href`.
+    fn assert_valid_atom(xml: &str) -> Element {
+        let feed = parse_xml(xml);
+        assert_eq!(feed.name, "feed");
+        assert_eq!(feed.attribute("xmlns"), Some("http://www.w3.org/2005/Atom"));
+        assert!(feed.only_child("id").text.starts_with("urn:"));
+        assert!(!feed.only_child("title").text.is_empty());
+        assert_atom_date(feed.only_child("updated"));
+
+        let mut ids = std::collections::HashSet::new();
+        for entry in feed.children_named("entry") {
+            let id = &entry.only_child("id").text;
+            assert!(
+                id.starts_with("urn:") && !id.contains(['[', ']', ' ']),
+                "{id}"
+            );
+            assert!(ids.insert(id.clone()), "duplicate entry id {id}");
+            assert!(!entry.only_child("title").text.is_empty());
+            assert_atom_date(entry.only_child("updated"));
+            assert!(!entry
+                .only_child("author")
+                .only_child("name")
+                .text
+                .is_empty());
+            for link in entry.children_named("link") {
+                assert!(link.attribute("href").is_some_and(|href| !href.is_empty()));
+            }
+        }
+        feed
+    }
+
+    #[test]
+    fn feed_is_valid_atom_with_one_entry_per_event_newest_first() {
+        let events = vec![
+            event("old", EventKind::IssueCreated, "Crash on <empty> config", 1),
+            event("new", EventKind::PrCreated, "Use \"retry\" & backoff", 5),
+        ];
+
+        let xml = render_atom_feed(&events, generated_at());
+        let feed = assert_valid_atom(&xml);
+
+        assert_eq!(feed.only_child("updated").text, "2026-03-02T09:05:00Z");
+        let entries = feed.children_named("entry").collect::<Vec<_>>();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries[0].only_child("id").text,
+            "urn:gh-watch:event:acme/api:pr_created:new"
+        );
+        assert_eq!(
+            entries[0].only_child("title").text,
+            "[acme/api] #7 Use \"retry\" & backoff"
+        );
+        assert_eq!(
+            entries[0].only_child("link").attribute("href"),
+            Some("https://github.com/acme/api/issues/7?from=new&x=1")
+        );
+        assert_eq!(
+            entries[0].only_child("author").only_child("name").text,
+            "dev"
+        );
+        assert_eq!(
+            entries[0].only_child("category").attribute("term"),
+            Some("pr_created")
+        );
+        assert_eq!(
+            entries[1].only_child("title").text,
+            "[acme/api] #7 Crash on <empty> config"
+        );
+        assert_eq!(
+            entries[1].only_child("updated").text,
+            "2026-03-02T09:01:00Z"
+        );
+    }
+
+    #[test]
+    fn entry_ids_escape_characters_iris_do_not_allow() {
+        let mut requested = event(
+            "9001:dependabot[bot]",
+            EventKind::PrReviewRequested,
+            "Bump",
+            1,
+        );
+        requested.actor = "dependabot[bot]".to_string();
+
+        let xml = render_atom_feed(&[requested], generated_at());
+        let feed = assert_valid_atom(&xml);
+
+        assert_eq!(
+            feed.only_child("entry").only_child("id").text,
+            "urn:gh-watch:event:acme/api:pr_review_requested:9001:dependabot%5Bbot%5D"
+        );
+    }
+
+    #[test]
+    fn empty_feed_is_dated_by_generation_time() {
+        let feed = assert_valid_atom(&render_atom_feed(&[], generated_at()));
+
+        assert_eq!(feed.only_child("updated").text, "2026-03-03T00:00:00Z");
+        assert_eq!(feed.children_named("entry").count(), 0);
+    }
+}
@@ -0,0 +1,152 @@
+use crate::{
+    config::{Config, DEFAULT_FILTER_PROFILE},
+    ports::FilterProfilePort,
+};
+
+/// `config` with the filter profile selected by `filter-profile switch` made active; every
+/// poll and webhook delivery resolves its filters through this, so the selection applies
+/// without a restart.
+pub fn config_with_filter_profile<P>(config: &Config, filter_profile: &P) -> Config
+where
+    P: FilterProfilePort,
+{
+    let mut poll_config = config.clone();
+    let selected = match filter_profile.load_active_filter_profile() {
+        Ok(selected) => selected,
+        Err(err) => {
+            tracing::warn!(error = %err, "failed to load active filter profile");
+            return poll_config;
+        }
+    };
+
+    match selected.as_deref() {
+        None => {}
+        Some(DEFAULT_FILTER_PROFILE) => poll_config.active_filter_profile = None,
+        Some(name) if config.filter_profiles.contains_key(name) => {
+            poll_config.active_filter_profile = Some(name.to_string());
+        }
+        Some(name) => {
+            tracing::warn!(
+                profile = %name,
+                "active filter profile is not defined in config; using configured filters"
+            );
+        }
+    }
+    poll_config
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use anyhow::{anyhow, Result};
+
+    use super::config_with_filter_profile;
+    use crate::{
+        config::{parse_config, Config},
+        ports::FilterProfilePort,
+    };
+
+    struct FakeFilterProfile {
+        selected: Mutex<Result<Option<String>, String>>,
+    }
+
+    impl FakeFilterProfile {
+        fn new(selected: Option<&str>) -> Self {
+            Self {
+                selected: Mutex::new(Ok(selected.map(str::to_string))),
+            }
+        }
+
+        fn failing() -> Self {
+            Self {
+                selected: Mutex::new(Err("disk gone".to_string())),
+            }
+        }
+    }
+
+    impl FilterProfilePort for FakeFilterProfile {
+        fn load_active_filter_profile(&self) -> Result<Option<String>> {
+            self.selected
+                .lock()
+                .unwrap()
+                .clone()
+                .map_err(|err| anyhow!(err))
+        }
+
+        fn save_active_filter_profile(&self, name: &str) -> Result<()> {
+            *self.selected.lock().unwrap() = Ok(Some(name.to_string()));
+            Ok(())
+        }
+    }
+
+    fn config() -> Config {
+        parse_config(
+            r#"
+active_filter_profile = "work"
+
+[filters]
+only_involving_me = false
+
+[filter_profiles.work]
+only_involving_me = true
+
+[filter_profiles.evening]
+ignore_actors = ["dependabot[bot]"]
+
+[[repositories]]
+name = "acme/api"
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn switching_profile_changes_filters_for_next_poll() {
+        let config = config();
+        let filter_profile = FakeFilterProfile::new(None);
+        assert!(
+            config_with_filter_profile(&config, &filter_profile)
+                .active_filters()
+                .only_involving_me
+        );
+
+        filter_profile
+            .save_active_filter_profile("evening")
+            .unwrap();
+        let poll_config = config_with_filter_profile(&config, &filter_profile);
+
+        assert_eq!(
+            poll_config.active_filter_profile.as_deref(),
+            Some("evening")
+        );
+        assert_eq!(
+            poll_config.active_filters().ignore_actors,
+            vec!["dependabot[bot]".to_string()]
+        );
+        assert!(!poll_config.active_filters().only_involving_me);
+    }
+
+    #[test]
+    fn default_profile_selects_top_level_filters() {
+        let config = config();
+        let poll_config =
+            config_with_filter_profile(&config, &FakeFilterProfile::new(Some("default")));
+
+        assert_eq!(poll_config.active_filter_profile, None);
+        assert!(!poll_config.active_filters().only_involving_me);
+    }
+
+    #[test]
+    fn unknown_or_unreadable_profile_falls_back_to_configured_filters() {
+        let config = config();
+
+        for filter_profile in [
+            FakeFilterProfile::new(Some("removed")),
+            FakeFilterProfile::failing(),
+        ] {
+            let poll_config = config_with_filter_profile(&config, &filter_profile);
+            assert_eq!(poll_config.active_filter_profile.as_deref(), Some("work"));
+        }
+    }
+}
@@ -1,3 +1,10 @@
+pub mod clock_skew;
+pub mod feed;
+pub mod filter_profile;
 pub mod notification_test;
 pub mod poll_once;
+pub mod read_sync;
+pub mod report;
+pub mod timeline_export;
+pub mod viewer_teams;
 pub mod watch_loop;
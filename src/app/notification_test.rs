@@ -18,7 +18,7 @@ where
 {
     let event = build_test_event();
     let dispatch_result = notifier
-        .notify(&NotificationPayload::Event(event.clone()), true)
+        .notify(&NotificationPayload::Event(Box::new(event.clone())), true)
         .context("failed to send test notification")?;
 
     Ok(NotificationTestOutcome {
@@ -41,5 +41,10 @@ fn build_test_event() -> WatchEvent {
         subject_author: Some("gh-watch".to_string()),
         requested_reviewer: None,
         mentions: Vec::new(),
+        team_mentions: Vec::new(),
+        subject_number: None,
+        subject_url: None,
+        account: None,
+        body: None,
     }
 }
@@ -1,34 +1,114 @@
-use anyhow::{anyhow, Context, Result};
+use anyhow::{Context, Result};
 use chrono::{Duration, Utc};
-use serde::Serialize;
-use std::{collections::HashSet, time::Duration as StdDuration};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    time::Duration as StdDuration,
+};
 
 use crate::{
-    config::Config,
-    domain::events::{event_matches_notification_filters, EventKind, WatchEvent},
+    app::viewer_teams::resolve_viewer_teams,
+    config::{split_org_team, Config, FiltersConfig},
+    domain::events::{
+        event_matches_milestone_filter, event_matches_notification_filters,
+        event_matches_review_state_filter, EventKind, Urgency, WatchEvent,
+    },
+    domain::path_filter::any_path_matches,
+    domain::{
+        error::GhRateLimitError,
+        failure::{
+            FailureRecord, FAILURE_CONTEXT_ATTEMPT_COUNT, FAILURE_CONTEXT_RENAMED_TO,
+            FAILURE_CONTEXT_STATUS_CODE, FAILURE_KIND_REPO_POLL,
+        },
+        redact::redact_secrets,
+    },
     ports::{
-        ClockPort, GhClientPort, NotificationDigest, NotificationPayload, NotifierPort,
-        PollStatePort, RepoPersistBatch,
+        ClockPort, GhClientPort, MyPrState, NotificationDigest, NotificationPayload, NotifierPort,
+        PendingReviewRequests, PollStatePort, PrFileChange, RepoAccess, RepoAccessMark,
+        RepoEventFetch, RepoPersistBatch, RetentionPolicy, ReviewThreadState,
     },
 };
 
 const POLL_OVERLAP_SECONDS: i64 = 300;
+/// Repositories per batched GraphQL request when `poll.use_graphql_batch` is on.
+const GRAPHQL_BATCH_SIZE: usize = 10;
 const REPO_FETCH_MAX_ATTEMPTS: usize = 3;
+/// How long a repository that answered 404 or 403 is skipped before it is tried again.
+const INACCESSIBLE_REPO_RETRY_MINUTES: i64 = 60;
+/// Results taken from each `startup_search_queries` entry.
+const STARTUP_SEARCH_LIMIT: usize = 50;
 const REPO_FETCH_RETRY_BACKOFFS_SECONDS: [u64; REPO_FETCH_MAX_ATTEMPTS - 1] = [1, 2];
+/// How long changed files cached in the state db are trusted by path filters; a later push
+/// may touch other files.
+const PR_FILES_CACHE_TTL_MINUTES: i64 = 60;
 
-#[derive(Debug, Clone, Default, Serialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RepoFetchFailure {
     pub repo: String,
     pub message: String,
+    /// Becomes `FailureRecord::context`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub context: HashMap<String, String>,
 }
 
-#[derive(Debug, Clone, Default, Serialize)]
+/// Printed by `once --json` and written to `--output-file`; its JSON shape is pinned by
+/// `tests/fixtures/poll_outcome_v1.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PollOutcome {
+    /// Clock time at the start of the poll.
+    pub polled_at: chrono::DateTime<Utc>,
+    /// Config file the poll ran with; empty unless a CLI command fills it in.
+    pub config_path: String,
     pub notified_count: usize,
+    /// New events left unnotified for being older than `notifications.max_event_age_hours`.
+    #[serde(default)]
+    pub stale_skipped_count: usize,
     pub bootstrap_repos: usize,
     pub notified_events: Vec<WatchEvent>,
     pub timeline_events: Vec<WatchEvent>,
+    /// Repositories fetched successfully this poll; the failed ones are in `fetch_failures`.
+    pub fetched_repos: Vec<String>,
     pub fetch_failures: Vec<RepoFetchFailure>,
+    pub api_calls: u64,
+    /// gh calls of bootstrap fetches answered from the on-disk response cache.
+    pub response_cache_hits: u64,
+    pub rate_limit_remaining: Option<u64>,
+}
+
+/// Every repository fetch in a poll failed; keeps the failures so callers can tell
+/// which repositories were affected.
+#[derive(Debug, Clone)]
+pub struct AllRepoFetchesFailedError {
+    pub failures: Vec<RepoFetchFailure>,
+}
+
+impl std::fmt::Display for AllRepoFetchesFailedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let details = self
+            .failures
+            .iter()
+            .map(|failure| format!("{}: {}", failure.repo, failure.message))
+            .collect::<Vec<_>>()
+            .join(" | ");
+        write!(f, "all repository fetches failed: {details}")
+    }
+}
+
+impl std::error::Error for AllRepoFetchesFailedError {}
+
+impl PollOutcome {
+    /// `api: 312 calls/cycle, 4200 remaining`, omitting the quota when it is unknown and
+    /// adding `, 40 cached` when the response cache answered some calls.
+    pub fn api_usage_summary(&self) -> String {
+        let mut summary = format!("api: {} calls/cycle", self.api_calls);
+        if self.response_cache_hits > 0 {
+            summary.push_str(&format!(", {} cached", self.response_cache_hits));
+        }
+        if let Some(remaining) = self.rate_limit_remaining {
+            summary.push_str(&format!(", {remaining} remaining"));
+        }
+        summary
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -37,17 +117,27 @@ struct RepoPollPlan {
     since: chrono::DateTime<Utc>,
     poll_started_at: chrono::DateTime<Utc>,
     is_bootstrap: bool,
+    /// Events arrived in a webhook delivery instead of being polled.
+    pushed: bool,
     allowed_event_kinds: Vec<EventKind>,
+    watch_review_threads: bool,
+    /// `RepoEventFetch::cached_at` of the fetch; the cursor stops there instead of at
+    /// `poll_started_at`.
+    cached_at: Option<chrono::DateTime<Utc>>,
 }
 
 enum RepoFetchResult {
     Fetched {
         plan: RepoPollPlan,
         events: Vec<WatchEvent>,
+        review_requests: Vec<PendingReviewRequests>,
+        review_threads: Vec<ReviewThreadState>,
+        my_prs: Vec<MyPrState>,
     },
     Failed {
         repo_name: String,
         error_message: String,
+        context: HashMap<String, String>,
     },
 }
 
@@ -55,6 +145,8 @@ struct RepoEventProcessingContext<'a, S> {
     config: &'a Config,
     state: &'a S,
     viewer_login: Option<String>,
+    viewer_teams: Vec<String>,
+    team_members: Vec<String>,
 }
 
 struct PollPlanner<'a, S, K> {
@@ -76,35 +168,51 @@ where
         }
     }
 
-    fn build(&self) -> Result<Vec<RepoPollPlan>> {
+    /// Renamed repositories are polled under their new name; inaccessible ones are skipped
+    /// until `INACCESSIBLE_REPO_RETRY_MINUTES` have passed since they last failed.
+    fn build(&self, access_marks: &HashMap<String, RepoAccessMark>) -> Result<Vec<RepoPollPlan>> {
         let mut plans = Vec::new();
+        let cursors = self
+            .state
+            .load_cursors_all()
+            .context("failed to load polling cursors")?;
 
         for repo in self.config.repositories.iter().filter(|r| r.enabled) {
-            let cursor = self
-                .state
-                .get_cursor(&repo.name)
-                .with_context(|| format!("failed to load cursor for {}", repo.name))?;
-
+            let repo_name = current_repo_name(access_marks, &repo.name);
             let poll_started_at = self.clock.now();
-            let allowed_event_kinds = repo
-                .event_kinds
-                .clone()
-                .unwrap_or_else(|| self.config.filters.event_kinds.clone());
+            if let Some(RepoAccessMark::Inaccessible { marked_at, .. }) =
+                access_marks.get(&repo_name)
+            {
+                if poll_started_at - *marked_at < Duration::minutes(INACCESSIBLE_REPO_RETRY_MINUTES)
+                {
+                    tracing::debug!(repo = %repo_name, "skipping inaccessible repository");
+                    continue;
+                }
+            }
+
+            let cursor = cursors.get(&repo_name).copied();
+            let allowed_event_kinds = self.config.event_kinds_for(repo);
 
             match cursor {
                 Some(cursor) => plans.push(RepoPollPlan {
-                    repo_name: repo.name.clone(),
+                    repo_name: repo_name.clone(),
                     since: with_fixed_overlap(cursor),
                     poll_started_at,
                     is_bootstrap: false,
+                    pushed: false,
                     allowed_event_kinds,
+                    watch_review_threads: repo.watch_review_threads,
+                    cached_at: None,
                 }),
                 None => plans.push(RepoPollPlan {
-                    repo_name: repo.name.clone(),
-                    since: bootstrap_since(poll_started_at, self.config.bootstrap_lookback_hours),
+                    repo_name,
+                    since: hours_before(poll_started_at, self.config.bootstrap_lookback_hours),
                     poll_started_at,
                     is_bootstrap: true,
+                    pushed: false,
                     allowed_event_kinds,
+                    watch_review_threads: repo.watch_review_threads,
+                    cached_at: None,
                 }),
             }
         }
@@ -128,37 +236,118 @@ where
 
     async fn collect(&self, plans: Vec<RepoPollPlan>) -> Vec<RepoFetchResult> {
         let mut results = Vec::new();
+        if self.config.poll.use_graphql_batch {
+            for batch in plans.chunks(GRAPHQL_BATCH_SIZE) {
+                results.extend(self.fetch_batch(batch.to_vec()).await);
+            }
+            return results;
+        }
         for plan in plans {
             results.push(self.fetch_with_retry(plan).await);
         }
         results
     }
 
+    /// One batched request for the whole chunk; repositories it does not return, and those
+    /// whose review threads fail, go through `fetch_with_retry` instead.
+    async fn fetch_batch(&self, plans: Vec<RepoPollPlan>) -> Vec<RepoFetchResult> {
+        let timeout_seconds = self.config.poll.timeout_seconds * plans.len() as u64;
+        let repos = plans
+            .iter()
+            .map(|plan| (plan.repo_name.as_str(), plan.since))
+            .collect::<Vec<_>>();
+        let fetched = tokio::time::timeout(
+            StdDuration::from_secs(timeout_seconds),
+            self.gh.batch_fetch_repo_events(&repos),
+        )
+        .await;
+        let mut batch = match fetched {
+            Ok(Ok(fetches)) => fetches,
+            Ok(Err(err)) => {
+                tracing::warn!(error = %err, "batched repository fetch failed");
+                HashMap::new()
+            }
+            Err(_) => {
+                tracing::warn!("batched repository fetch timed out after {timeout_seconds}s");
+                HashMap::new()
+            }
+        };
+
+        let mut results = Vec::new();
+        for plan in plans {
+            let result = match batch.remove(&plan.repo_name) {
+                Some(fetch) => self.finish_batched(plan, fetch).await,
+                None => self.fetch_with_retry(plan).await,
+            };
+            results.push(result);
+        }
+        results
+    }
+
+    async fn finish_batched(&self, plan: RepoPollPlan, fetch: RepoEventFetch) -> RepoFetchResult {
+        let RepoEventFetch {
+            mut events,
+            review_requests,
+            ..
+        } = fetch;
+        if !plan.watch_review_threads {
+            return RepoFetchResult::Fetched {
+                plan,
+                events,
+                review_requests,
+                review_threads: Vec::new(),
+                my_prs: Vec::new(),
+            };
+        }
+
+        let timeout = StdDuration::from_secs(self.config.poll.timeout_seconds);
+        match tokio::time::timeout(timeout, self.gh.fetch_review_threads(&plan.repo_name)).await {
+            Ok(Ok(threads)) => {
+                events.extend(threads.resolved_events);
+                RepoFetchResult::Fetched {
+                    plan,
+                    events,
+                    review_requests,
+                    review_threads: threads.states,
+                    my_prs: Vec::new(),
+                }
+            }
+            _ => self.fetch_with_retry(plan).await,
+        }
+    }
+
     async fn fetch_with_retry(&self, plan: RepoPollPlan) -> RepoFetchResult {
         let timeout = StdDuration::from_secs(self.config.poll.timeout_seconds);
         let timeout_seconds = self.config.poll.timeout_seconds;
-        let mut fetched_events: Option<Vec<WatchEvent>> = None;
+        let mut fetched: Option<(RepoEventFetch, Vec<ReviewThreadState>)> = None;
         let mut last_error = String::new();
+        let mut context = HashMap::new();
 
         for attempt in 1..=REPO_FETCH_MAX_ATTEMPTS {
-            let result = tokio::time::timeout(
-                timeout,
-                self.gh.fetch_repo_events(&plan.repo_name, plan.since),
-            )
-            .await;
+            let result = tokio::time::timeout(timeout, self.fetch_repo(&plan)).await;
 
             match result {
-                Ok(Ok(events)) => {
-                    fetched_events = Some(events);
+                Ok(Ok(repo_items)) => {
+                    fetched = Some(repo_items);
                     break;
                 }
                 Ok(Err(err)) => {
-                    last_error = err.to_string();
+                    last_error = redact_secrets(&err.to_string());
+                    context = err
+                        .chain()
+                        .find_map(|cause| cause.downcast_ref::<GhRateLimitError>())
+                        .map(GhRateLimitError::context)
+                        .unwrap_or_default();
                 }
                 Err(_) => {
                     last_error = format!("repo polling timed out after {timeout_seconds}s");
+                    context = HashMap::new();
                 }
             }
+            context.insert(
+                FAILURE_CONTEXT_ATTEMPT_COUNT.to_string(),
+                attempt.to_string(),
+            );
 
             if attempt < REPO_FETCH_MAX_ATTEMPTS {
                 let backoff_index = attempt - 1;
@@ -167,14 +356,48 @@ where
             }
         }
 
-        match fetched_events {
-            Some(events) => RepoFetchResult::Fetched { plan, events },
+        match fetched {
+            Some((fetch, review_threads)) => RepoFetchResult::Fetched {
+                plan: RepoPollPlan {
+                    cached_at: fetch.cached_at,
+                    ..plan
+                },
+                events: fetch.events,
+                review_requests: fetch.review_requests,
+                review_threads,
+                my_prs: Vec::new(),
+            },
             None => RepoFetchResult::Failed {
                 repo_name: plan.repo_name,
                 error_message: last_error,
+                context,
             },
         }
     }
+
+    /// Repository events plus, when `watch_review_threads` is set, resolved-thread events and
+    /// the resolve state of every thread seen.
+    async fn fetch_repo(
+        &self,
+        plan: &RepoPollPlan,
+    ) -> Result<(RepoEventFetch, Vec<ReviewThreadState>)> {
+        let mut fetch = if plan.is_bootstrap {
+            self.gh
+                .fetch_bootstrap_repo_events(&plan.repo_name, plan.since)
+                .await?
+        } else {
+            self.gh
+                .fetch_repo_events(&plan.repo_name, plan.since)
+                .await?
+        };
+        if !plan.watch_review_threads {
+            return Ok((fetch, Vec::new()));
+        }
+
+        let threads = self.gh.fetch_review_threads(&plan.repo_name).await?;
+        fetch.events.extend(threads.resolved_events);
+        Ok((fetch, threads.states))
+    }
 }
 
 struct RepoBatchProcessor<'a, S> {
@@ -185,12 +408,20 @@ impl<'a, S> RepoBatchProcessor<'a, S>
 where
     S: PollStatePort,
 {
-    fn new(config: &'a Config, state: &'a S, viewer_login: Option<String>) -> Self {
+    fn new(
+        config: &'a Config,
+        state: &'a S,
+        viewer_login: Option<String>,
+        viewer_teams: Vec<String>,
+        team_members: Vec<String>,
+    ) -> Self {
         Self {
             context: RepoEventProcessingContext {
                 config,
                 state,
                 viewer_login,
+                viewer_teams,
+                team_members,
             },
         }
     }
@@ -202,15 +433,35 @@ where
         fetch_result: RepoFetchResult,
     ) -> Result<()> {
         match fetch_result {
-            RepoFetchResult::Fetched { plan, events } => {
-                self.persist_and_collect(outcome, notify_candidates, plan, events)?;
+            RepoFetchResult::Fetched {
+                plan,
+                events,
+                review_requests,
+                review_threads,
+                my_prs,
+            } => {
+                outcome.fetched_repos.push(plan.repo_name.clone());
+                self.persist_and_collect(
+                    outcome,
+                    notify_candidates,
+                    plan,
+                    RepoEventFetch {
+                        events,
+                        review_requests,
+                        cached_at: None,
+                    },
+                    review_threads,
+                    my_prs,
+                )?;
             }
             RepoFetchResult::Failed {
                 repo_name,
                 error_message,
+                context,
             } => outcome.fetch_failures.push(RepoFetchFailure {
                 repo: repo_name,
                 message: error_message,
+                context,
             }),
         }
 
@@ -222,9 +473,12 @@ where
         outcome: &mut PollOutcome,
         notify_candidates: &mut Vec<WatchEvent>,
         plan: RepoPollPlan,
-        events: Vec<WatchEvent>,
+        fetch: RepoEventFetch,
+        review_threads: Vec<ReviewThreadState>,
+        my_prs: Vec<MyPrState>,
     ) -> Result<()> {
-        let mut events = events
+        let mut events = fetch
+            .events
             .into_iter()
             .filter(|event| event.created_at <= plan.poll_started_at)
             .collect::<Vec<_>>();
@@ -234,17 +488,32 @@ where
                 event_matches_notification_filters(
                     event,
                     &plan.allowed_event_kinds,
-                    &self.context.config.filters.ignore_actors,
-                    self.context.config.filters.only_involving_me,
+                    &self.context.config.active_filters().ignore_actors,
+                    self.context.config.active_filters().only_involving_me,
+                    self.context.config.active_filters().ignore_self,
                     self.context.viewer_login.as_deref(),
+                    &self.context.viewer_teams,
+                    &self.context.team_members,
+                ) && event_matches_milestone_filter(
+                    event,
+                    &self.context.config.active_filters().milestone_names,
+                ) && event_matches_review_state_filter(
+                    event,
+                    &self.context.config.active_filters().review_states,
                 )
             });
         }
 
         let batch = RepoPersistBatch {
             repo: plan.repo_name.clone(),
-            poll_started_at: plan.poll_started_at,
+            poll_started_at: plan
+                .cached_at
+                .map_or(plan.poll_started_at, |at| at.min(plan.poll_started_at)),
+            pushed: plan.pushed,
             events: events.clone(),
+            review_requests: fetch.review_requests,
+            review_threads,
+            my_prs,
         };
         let persist_result = self
             .context
@@ -268,7 +537,17 @@ where
             return Ok(());
         }
 
-        notify_candidates.extend(newly_logged_events);
+        let config = self.context.config;
+        let oldest_notified = hours_before(
+            plan.poll_started_at,
+            config.notifications.max_event_age_hours,
+        );
+        let (fresh, stale): (Vec<_>, Vec<_>) = newly_logged_events
+            .into_iter()
+            .filter(|event| config.notifies_kind(&plan.repo_name, &event.kind))
+            .partition(|event| event.created_at >= oldest_notified);
+        outcome.stale_skipped_count += stale.len();
+        notify_candidates.extend(fresh);
 
         Ok(())
     }
@@ -281,6 +560,25 @@ pub async fn poll_once<C, S, N, K>(
     notifier: &N,
     clock: &K,
 ) -> Result<PollOutcome>
+where
+    C: GhClientPort,
+    S: PollStatePort,
+    N: NotifierPort,
+    K: ClockPort,
+{
+    poll_once_as(config, gh, state, notifier, clock, None).await
+}
+
+/// `poll_once` for a caller that already knows the viewer's login, e.g. from `check_auth`,
+/// so `only_involving_me` does not ask gh for it on every poll.
+pub async fn poll_once_as<C, S, N, K>(
+    config: &Config,
+    gh: &C,
+    state: &S,
+    notifier: &N,
+    clock: &K,
+    viewer_login: Option<&str>,
+) -> Result<PollOutcome>
 where
     C: GhClientPort,
     S: PollStatePort,
@@ -288,26 +586,62 @@ where
     K: ClockPort,
 {
     let now = clock.now();
-    state.cleanup_old(config.retention_days, now)?;
+    state.cleanup_old(&retention_policy(config), now)?;
 
-    let viewer_login = if config.filters.only_involving_me {
-        Some(
-            gh.viewer_login()
-                .await
-                .context("failed to resolve viewer login for only_involving_me filter")?,
-        )
+    let filters = config.active_filters();
+    let viewer_login = if filters.only_involving_me || filters.ignore_self {
+        Some(match viewer_login {
+            Some(login) => login.to_string(),
+            None => gh.viewer_login().await.context(
+                "failed to resolve viewer login for only_involving_me/ignore_self filters",
+            )?,
+        })
     } else {
         None
     };
+    let (viewer_teams, team_members) = if filters.only_involving_me {
+        (
+            resolve_viewer_teams(gh, state, now).await,
+            resolve_team_members(filters, gh).await,
+        )
+    } else {
+        (Vec::new(), Vec::new())
+    };
 
-    let plans = PollPlanner::new(config, state, clock).build()?;
-    let fetch_results = RepoEventCollector::new(config, gh).collect(plans).await;
+    let access_marks = state
+        .load_repo_access_marks()
+        .context("failed to load repository access marks")?;
+    let plans = PollPlanner::new(config, state, clock).build(&access_marks)?;
+    let calls_before = gh.api_call_count();
+    let response_cache_hits_before = gh.response_cache_hits();
+    let mut fetch_results = RepoEventCollector::new(config, gh).collect(plans).await;
+    check_repo_access(
+        config,
+        gh,
+        state,
+        notifier,
+        now,
+        &access_marks,
+        &mut fetch_results,
+    )
+    .await;
+    seed_bootstrap_from_search(config, gh, &mut fetch_results).await;
+    attach_my_prs(config, gh, &mut fetch_results).await;
+    let api_calls = gh.api_call_count().saturating_sub(calls_before);
+    let response_cache_hits = gh
+        .response_cache_hits()
+        .saturating_sub(response_cache_hits_before);
+    let rate_limit_remaining = gh.rate_limit_remaining().await.unwrap_or_else(|err| {
+        tracing::debug!(error = %err, "rate limit lookup failed");
+        None
+    });
     let fetched_repo_count = fetch_results
         .iter()
         .filter(|result| matches!(result, RepoFetchResult::Fetched { .. }))
         .count();
 
     let mut outcome = PollOutcome {
+        polled_at: now,
         bootstrap_repos: fetch_results
             .iter()
             .filter(|result| {
@@ -323,30 +657,468 @@ where
                 )
             })
             .count(),
+        api_calls,
+        response_cache_hits,
+        rate_limit_remaining,
         ..PollOutcome::default()
     };
 
     let mut notify_candidates = Vec::new();
-    let processor = RepoBatchProcessor::new(config, state, viewer_login);
+    let processor =
+        RepoBatchProcessor::new(config, state, viewer_login, viewer_teams, team_members);
     for fetch_result in fetch_results {
         processor.apply(&mut outcome, &mut notify_candidates, fetch_result)?;
     }
 
+    for failure in &outcome.fetch_failures {
+        let record = failure.context.iter().fold(
+            FailureRecord::new(
+                FAILURE_KIND_REPO_POLL,
+                failure.repo.as_str(),
+                now,
+                failure.message.as_str(),
+            ),
+            |record, (key, value)| record.with_context(key.as_str(), value.as_str()),
+        );
+        if let Err(err) = state.record_failure(&record) {
+            tracing::warn!(error = %err, repo = %failure.repo, "failed to record repo failure");
+        }
+    }
+
     if fetched_repo_count == 0 && !outcome.fetch_failures.is_empty() {
-        let details = outcome
-            .fetch_failures
-            .iter()
-            .map(|failure| format!("{}: {}", failure.repo, failure.message))
-            .collect::<Vec<_>>()
-            .join(" | ");
-        return Err(anyhow!("all repository fetches failed: {details}"));
+        return Err(AllRepoFetchesFailedError {
+            failures: outcome.fetch_failures,
+        }
+        .into());
     }
 
+    let notify_candidates = retain_path_filtered(config, gh, state, now, notify_candidates).await;
     dispatch_notifications(config, notifier, &mut outcome, notify_candidates)?;
+    record_notified(state, &outcome, clock.now());
+
+    Ok(outcome)
+}
+
+/// Stores events pushed by a webhook delivery and notifies about the new ones, with the
+/// same filters as a poll. Events of repositories that are not configured, disabled, or not
+/// bootstrapped by a poll yet are dropped, and polling cursors are left where they were so
+/// the next poll still fills whatever the webhooks missed.
+#[allow(clippy::too_many_arguments)]
+pub fn ingest_pushed_events<S, N>(
+    config: &Config,
+    state: &S,
+    notifier: &N,
+    viewer_login: Option<String>,
+    viewer_teams: Vec<String>,
+    team_members: Vec<String>,
+    events: Vec<WatchEvent>,
+    now: chrono::DateTime<Utc>,
+) -> Result<PollOutcome>
+where
+    S: PollStatePort,
+    N: NotifierPort,
+{
+    let cursors = state
+        .load_cursors_all()
+        .context("failed to load polling cursors")?;
+    let mut events_by_repo = BTreeMap::<String, Vec<WatchEvent>>::new();
+    for event in events {
+        let Some(repo) = config
+            .repositories
+            .iter()
+            .find(|repo| repo.enabled && repo.name.eq_ignore_ascii_case(&event.repo))
+        else {
+            tracing::debug!(repo = %event.repo, "ignoring pushed event of unwatched repository");
+            continue;
+        };
+        // Polling stores events under the configured spelling; keep the event keys equal.
+        let event = WatchEvent {
+            repo: repo.name.clone(),
+            ..event
+        };
+        events_by_repo
+            .entry(repo.name.clone())
+            .or_default()
+            .push(event);
+    }
 
+    let mut outcome = PollOutcome {
+        polled_at: now,
+        ..PollOutcome::default()
+    };
+    let mut notify_candidates = Vec::new();
+    let processor =
+        RepoBatchProcessor::new(config, state, viewer_login, viewer_teams, team_members);
+    for (repo_name, events) in events_by_repo {
+        let Some(cursor) = cursors.get(&repo_name).copied() else {
+            tracing::debug!(repo = %repo_name, "ignoring pushed events until the first poll");
+            continue;
+        };
+        let Some(repo) = config
+            .repositories
+            .iter()
+            .find(|repo| repo.name == repo_name)
+        else {
+            continue;
+        };
+        let plan = RepoPollPlan {
+            repo_name: repo_name.clone(),
+            since: cursor,
+            poll_started_at: now,
+            is_bootstrap: false,
+            pushed: true,
+            allowed_event_kinds: config.event_kinds_for(repo),
+            watch_review_threads: false,
+            cached_at: None,
+        };
+        processor.apply(
+            &mut outcome,
+            &mut notify_candidates,
+            RepoFetchResult::Fetched {
+                plan,
+                events,
+                review_requests: Vec::new(),
+                review_threads: Vec::new(),
+                my_prs: Vec::new(),
+            },
+        )?;
+        state
+            .set_cursor(&repo_name, cursor)
+            .with_context(|| format!("failed to restore polling cursor for {repo_name}"))?;
+    }
+
+    dispatch_notifications(config, notifier, &mut outcome, notify_candidates)?;
+    record_notified(state, &outcome, now);
     Ok(outcome)
 }
 
+/// `repo` after following the renames recorded for it.
+pub fn current_repo_name(access_marks: &HashMap<String, RepoAccessMark>, repo: &str) -> String {
+    let mut name = repo.to_string();
+    let mut seen = HashSet::from([name.clone()]);
+    while let Some(RepoAccessMark::Renamed { new_name, .. }) = access_marks.get(&name) {
+        if !seen.insert(new_name.clone()) {
+            break;
+        }
+        name = new_name.clone();
+    }
+    name
+}
+
+/// Asks GitHub about every repository whose fetch failed. A renamed one has its state moved
+/// to the new name, which later polls fetch instead; one answering 404 or 403 is skipped for
+/// `INACCESSIBLE_REPO_RETRY_MINUTES` at a time and alerted about once, until a fetch of it
+/// succeeds again.
+async fn check_repo_access<C, S, N>(
+    config: &Config,
+    gh: &C,
+    state: &S,
+    notifier: &N,
+    now: chrono::DateTime<Utc>,
+    access_marks: &HashMap<String, RepoAccessMark>,
+    fetch_results: &mut [RepoFetchResult],
+) where
+    C: GhClientPort,
+    S: PollStatePort,
+    N: NotifierPort,
+{
+    let timeout = StdDuration::from_secs(config.poll.timeout_seconds);
+    for result in fetch_results {
+        let (repo_name, error_message, context) = match result {
+            RepoFetchResult::Fetched { plan, .. } => {
+                if matches!(
+                    access_marks.get(&plan.repo_name),
+                    Some(RepoAccessMark::Inaccessible { .. })
+                ) {
+                    if let Err(err) = state.clear_repo_access_mark(&plan.repo_name) {
+                        tracing::warn!(error = %err, repo = %plan.repo_name, "failed to clear repository access mark");
+                    }
+                }
+                continue;
+            }
+            RepoFetchResult::Failed {
+                repo_name,
+                error_message,
+                context,
+            } => (repo_name, error_message, context),
+        };
+
+        let access = match tokio::time::timeout(timeout, gh.probe_repo_access(repo_name)).await {
+            Ok(Ok(access)) => access,
+            Ok(Err(err)) => {
+                tracing::debug!(error = %err, repo = %repo_name, "repository access probe failed");
+                continue;
+            }
+            Err(_) => continue,
+        };
+        match access {
+            RepoAccess::Accessible => {}
+            RepoAccess::Renamed { new_name } => {
+                if let Err(err) = state.mark_repo_renamed(repo_name, &new_name, now) {
+                    tracing::warn!(error = %err, repo = %repo_name, "failed to record repository rename");
+                    continue;
+                }
+                error_message.push_str(&format!(
+                    "; renamed to {new_name}, which the next poll fetches (update [[repositories]] in the config)"
+                ));
+                context.insert(FAILURE_CONTEXT_RENAMED_TO.to_string(), new_name);
+            }
+            RepoAccess::Inaccessible { status_code } => {
+                let first = match state.mark_repo_inaccessible(repo_name, status_code, now) {
+                    Ok(first) => first,
+                    Err(err) => {
+                        tracing::warn!(error = %err, repo = %repo_name, "failed to record inaccessible repository");
+                        continue;
+                    }
+                };
+                error_message.push_str(&format!(
+                    "; no longer accessible (HTTP {status_code}), retrying in {INACCESSIBLE_REPO_RETRY_MINUTES} minutes"
+                ));
+                context.insert(
+                    FAILURE_CONTEXT_STATUS_CODE.to_string(),
+                    status_code.to_string(),
+                );
+                if first && config.notifications.enabled {
+                    let message = format!("{repo_name} is no longer accessible");
+                    if let Err(err) = notifier.notify(&NotificationPayload::Alert(message), false) {
+                        tracing::warn!(error = %err, repo = %repo_name, "failed to send inaccessible repository alert");
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Adds `startup_search_queries` results to the repositories bootstrapping in this poll, so
+/// they are stored with the bootstrap batch and never notified. A failed search only warns.
+async fn seed_bootstrap_from_search<C>(
+    config: &Config,
+    gh: &C,
+    fetch_results: &mut [RepoFetchResult],
+) where
+    C: GhClientPort,
+{
+    if config.startup_search_queries.is_empty() {
+        return;
+    }
+    let bootstrapping = fetch_results
+        .iter()
+        .filter_map(|result| match result {
+            RepoFetchResult::Fetched { plan, .. } if plan.is_bootstrap => {
+                Some(plan.repo_name.clone())
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let mut seeded = HashMap::<String, Vec<WatchEvent>>::new();
+    for search in &config.startup_search_queries {
+        let repos = bootstrapping
+            .iter()
+            .filter(|repo| search.repos.is_empty() || search.repos.contains(repo))
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        if repos.is_empty() {
+            continue;
+        }
+        match gh
+            .search_issues(&search.query, &repos, STARTUP_SEARCH_LIMIT)
+            .await
+        {
+            Ok(events) => {
+                for event in events {
+                    seeded.entry(event.repo.clone()).or_default().push(event);
+                }
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, query = %search.query, "startup search failed");
+            }
+        }
+    }
+
+    for result in fetch_results {
+        if let RepoFetchResult::Fetched { plan, events, .. } = result {
+            if let Some(found) = seeded.remove(&plan.repo_name) {
+                events.extend(found);
+            }
+        }
+    }
+}
+
+/// Adds the viewer's open pull requests to each fetched repository when `watch_my_prs` is
+/// on. Readiness events are dated no later than the poll start so the cutoff keeps them; a
+/// failed search leaves the last seen states untouched until the next poll.
+async fn attach_my_prs<C>(config: &Config, gh: &C, fetch_results: &mut [RepoFetchResult])
+where
+    C: GhClientPort,
+{
+    if !config.watch_my_prs {
+        return;
+    }
+    let repos = fetch_results
+        .iter()
+        .filter_map(|result| match result {
+            RepoFetchResult::Fetched { plan, .. } => Some(plan.repo_name.as_str()),
+            RepoFetchResult::Failed { .. } => None,
+        })
+        .collect::<Vec<_>>();
+    if repos.is_empty() {
+        return;
+    }
+
+    let timeout = StdDuration::from_secs(config.poll.timeout_seconds);
+    let mut fetches = match tokio::time::timeout(timeout, gh.fetch_my_open_prs(&repos)).await {
+        Ok(Ok(fetches)) => fetches,
+        Ok(Err(err)) => {
+            tracing::warn!(error = %err, "my open pull requests fetch failed");
+            return;
+        }
+        Err(_) => {
+            tracing::warn!(
+                "my open pull requests fetch timed out after {}s",
+                config.poll.timeout_seconds
+            );
+            return;
+        }
+    };
+
+    for result in fetch_results {
+        if let RepoFetchResult::Fetched {
+            plan,
+            events,
+            my_prs,
+            ..
+        } = result
+        {
+            let Some(fetch) = fetches.remove(&plan.repo_name) else {
+                continue;
+            };
+            events.extend(fetch.events.into_iter().map(|mut event| {
+                event.created_at = event.created_at.min(plan.poll_started_at);
+                event
+            }));
+            *my_prs = fetch.states;
+        }
+    }
+}
+
+/// Configured `team_members` plus the members of `auto_fetch_org_team`, without duplicates.
+/// Listing members needs the `read:org` scope, so a lookup that fails leaves only the
+/// configured members and logs a warning instead of failing the poll.
+pub async fn resolve_team_members<C>(filters: &FiltersConfig, gh: &C) -> Vec<String>
+where
+    C: GhClientPort,
+{
+    let mut members = filters.team_members.clone();
+    if let Some(org_team) = filters.auto_fetch_org_team.as_deref() {
+        let fetched = match split_org_team(org_team) {
+            Ok((org, Some(team))) => gh.list_team_members(org, team).await,
+            Ok((org, None)) => gh.list_org_members(org).await,
+            Err(err) => Err(err),
+        };
+        match fetched {
+            Ok(fetched) => members.extend(fetched),
+            Err(err) => tracing::warn!(
+                error = %err,
+                org_team,
+                "failed to fetch team members; only the configured team_members count"
+            ),
+        }
+    }
+
+    let mut seen = HashSet::new();
+    members.retain(|member| seen.insert(member.to_ascii_lowercase()));
+    members
+}
+
+/// Drops pull request events of repositories with path filters when the pull request
+/// changes no matching file. Changed files are looked up once per pull request and poll,
+/// from the state db cache or gh; when both fail the events are kept.
+async fn retain_path_filtered<C, S>(
+    config: &Config,
+    gh: &C,
+    state: &S,
+    now: chrono::DateTime<Utc>,
+    notify_candidates: Vec<WatchEvent>,
+) -> Vec<WatchEvent>
+where
+    C: GhClientPort,
+    S: PollStatePort,
+{
+    let mut touches_by_pr = HashMap::<(String, i64), bool>::new();
+    let mut retained = Vec::with_capacity(notify_candidates.len());
+    for event in notify_candidates {
+        let patterns = config
+            .repositories
+            .iter()
+            .find(|repo| repo.name == event.repo)
+            .map(|repo| config.path_filters_for(repo))
+            .unwrap_or_default();
+        let pr_number = event.pull_request_number().filter(|_| !patterns.is_empty());
+        let Some(pr_number) = pr_number else {
+            retained.push(event);
+            continue;
+        };
+
+        let key = (event.repo.clone(), pr_number);
+        let touches = match touches_by_pr.get(&key) {
+            Some(touches) => *touches,
+            None => {
+                let touches = match pr_file_changes(gh, state, &event, pr_number, now).await {
+                    Some(files) => {
+                        any_path_matches(patterns, files.iter().map(|file| file.filename.as_str()))
+                    }
+                    None => true,
+                };
+                touches_by_pr.insert(key, touches);
+                touches
+            }
+        };
+        if touches {
+            retained.push(event);
+        } else {
+            tracing::debug!(
+                event_key = %event.event_key(),
+                "skipping notification for pull request outside path_filters"
+            );
+        }
+    }
+    retained
+}
+
+async fn pr_file_changes<C, S>(
+    gh: &C,
+    state: &S,
+    event: &WatchEvent,
+    pr_number: i64,
+    now: chrono::DateTime<Utc>,
+) -> Option<Vec<PrFileChange>>
+where
+    C: GhClientPort,
+    S: PollStatePort,
+{
+    let cached_since = now - Duration::minutes(PR_FILES_CACHE_TTL_MINUTES);
+    match state.load_pr_file_changes(&event.repo, pr_number, cached_since) {
+        Ok(Some(files)) => return Some(files),
+        Ok(None) => {}
+        Err(err) => {
+            tracing::warn!(error = %err, repo = %event.repo, pr_number, "failed to load cached pull request files");
+        }
+    }
+
+    let files = match gh.fetch_pr_file_changes(&event.repo, pr_number).await {
+        Ok(files) => files,
+        Err(err) => {
+            tracing::warn!(error = %err, repo = %event.repo, pr_number, "pull request files fetch failed; notifying anyway");
+            return None;
+        }
+    };
+    if let Err(err) = state.save_pr_file_changes(&event.event_key(), pr_number, &files) {
+        tracing::warn!(error = %err, repo = %event.repo, pr_number, "failed to cache pull request files");
+    }
+    Some(files)
+}
+
 fn dispatch_notifications<N>(
     config: &Config,
     notifier: &N,
@@ -363,15 +1135,24 @@ where
     sort_notification_candidates(&mut notify_candidates);
     outcome.notified_events = notify_candidates.clone();
 
+    // High-urgency events each get their own notification; the rest share one as before.
+    let (urgent, mut notify_candidates): (Vec<_>, Vec<_>) = notify_candidates
+        .into_iter()
+        .partition(|event| event.kind.default_urgency() == Urgency::High);
     let include_url = config.notifications.include_url;
+    for event in urgent {
+        notify_event(notifier, event, include_url)?;
+        outcome.notified_count += 1;
+    }
+
+    if notify_candidates.is_empty() {
+        return Ok(());
+    }
     if notify_candidates.len() == 1 {
         let event = notify_candidates
-            .into_iter()
-            .next()
+            .pop()
             .expect("single candidate must exist");
-        notifier
-            .notify(&NotificationPayload::Event(event.clone()), include_url)
-            .with_context(|| format!("notification failed for {}", event.event_key()))?;
+        notify_event(notifier, event, include_url)?;
     } else {
         let digest = NotificationDigest {
             total_events: notify_candidates.len(),
@@ -391,6 +1172,45 @@ where
     Ok(())
 }
 
+/// Stamps the events of `outcome` that were notified about, for `config doctor --latency`.
+/// Only warns on failure: the notifications already went out.
+fn record_notified<S>(state: &S, outcome: &PollOutcome, notified_at: chrono::DateTime<Utc>)
+where
+    S: PollStatePort,
+{
+    if outcome.notified_events.is_empty() {
+        return;
+    }
+    let event_keys = outcome
+        .notified_events
+        .iter()
+        .map(WatchEvent::event_key)
+        .collect::<Vec<_>>();
+    if let Err(err) = state.mark_events_notified(&event_keys, notified_at) {
+        tracing::warn!(error = %err, "failed to record notification times");
+    }
+}
+
+fn notify_event<N>(notifier: &N, event: WatchEvent, include_url: bool) -> Result<()>
+where
+    N: NotifierPort,
+{
+    let event_key = event.event_key();
+    notifier
+        .notify(&NotificationPayload::Event(Box::new(event)), include_url)
+        .with_context(|| format!("notification failed for {event_key}"))?;
+    Ok(())
+}
+
+fn retention_policy(config: &Config) -> RetentionPolicy {
+    RetentionPolicy {
+        default_days: config.retention_days,
+        days_by_kind: config.retention.days_by_kind.clone(),
+        keep_unread: config.retention.keep_unread,
+        max_read_history: Some(config.max_notification_history),
+    }
+}
+
 fn sort_notification_candidates(events: &mut [WatchEvent]) {
     events.sort_by(|a, b| {
         b.created_at
@@ -399,8 +1219,8 @@ fn sort_notification_candidates(events: &mut [WatchEvent]) {
     });
 }
 
-fn bootstrap_since(now: chrono::DateTime<Utc>, lookback_hours: u64) -> chrono::DateTime<Utc> {
-    let bounded_hours = lookback_hours.min(i64::MAX as u64) as i64;
+fn hours_before(now: chrono::DateTime<Utc>, hours: u64) -> chrono::DateTime<Utc> {
+    let bounded_hours = hours.min(i64::MAX as u64) as i64;
     now.checked_sub_signed(Duration::hours(bounded_hours))
         .unwrap_or(now)
 }
@@ -0,0 +1,182 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{Config, SyncConfig},
+    ports::{GhClientPort, ReadSyncStatePort},
+};
+
+/// File kept in the `[sync]` gist.
+pub const SYNC_FILE_NAME: &str = "gh-watch-sync.json";
+const SYNC_FORMAT_VERSION: u32 = 1;
+
+/// Contents of `SYNC_FILE_NAME`: when each event key was first read on any machine.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct SyncSnapshot {
+    version: u32,
+    read: BTreeMap<String, DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncOutcome {
+    /// Timeline events read elsewhere that are now read here too.
+    pub newly_read: Vec<String>,
+    /// Whether the remote copy was missing marks and had to be rewritten.
+    pub pushed: bool,
+}
+
+/// Pulls the remote read marks, merges them into the state db and pushes the union back
+/// when the remote copy lacks any of them. Marks older than `retention_days` are dropped.
+pub async fn sync_read_state<C, S>(
+    config: &Config,
+    sync: &SyncConfig,
+    gh: &C,
+    state: &S,
+    now: DateTime<Utc>,
+) -> Result<SyncOutcome>
+where
+    C: GhClientPort,
+    S: ReadSyncStatePort + ?Sized,
+{
+    let remote = match gh.read_gist_file(&sync.gist_id, SYNC_FILE_NAME).await? {
+        Some(content) => parse_snapshot(&content)?,
+        None => SyncSnapshot::default(),
+    };
+
+    let cutoff = now - Duration::days(config.retention_days.into());
+    let mut merged = merge_read_marks(state.load_read_marks()?, &remote.read);
+    merged.retain(|_, read_at| *read_at >= cutoff);
+    let newly_read = state.merge_read_marks(&merged)?;
+
+    let snapshot = SyncSnapshot {
+        version: SYNC_FORMAT_VERSION,
+        read: merged.into_iter().collect(),
+    };
+    let pushed = snapshot != remote;
+    if pushed {
+        let content = serde_json::to_string(&snapshot)?;
+        gh.write_gist_file(&sync.gist_id, SYNC_FILE_NAME, &content)
+            .await?;
+    }
+
+    Ok(SyncOutcome { newly_read, pushed })
+}
+
+fn parse_snapshot(content: &str) -> Result<SyncSnapshot> {
+    let snapshot: SyncSnapshot =
+        serde_json::from_str(content).with_context(|| format!("invalid {SYNC_FILE_NAME}"))?;
+    if snapshot.version > SYNC_FORMAT_VERSION {
+        return Err(anyhow!(
+            "{SYNC_FILE_NAME} was written by a newer gh-watch (format {})",
+            snapshot.version
+        ));
+    }
+    Ok(snapshot)
+}
+
+/// Union of both sides; a key read on both keeps the earlier read time.
+fn merge_read_marks(
+    mut local: HashMap<String, DateTime<Utc>>,
+    remote: &BTreeMap<String, DateTime<Utc>>,
+) -> HashMap<String, DateTime<Utc>> {
+    for (event_key, remote_read_at) in remote {
+        local
+            .entry(event_key.clone())
+            .and_modify(|read_at| *read_at = (*read_at).min(*remote_read_at))
+            .or_insert(*remote_read_at);
+    }
+    local
+}
+
+/// Decides when `watch` syncs: after every poll, or once `interval_minutes` have passed.
+#[derive(Debug, Clone)]
+pub struct SyncSchedule {
+    interval: Option<Duration>,
+    last_synced_at: Option<DateTime<Utc>>,
+}
+
+impl SyncSchedule {
+    pub fn new(sync: &SyncConfig) -> Self {
+        Self {
+            interval: sync
+                .interval_minutes
+                .map(|minutes| Duration::minutes(minutes as i64)),
+            last_synced_at: None,
+        }
+    }
+
+    /// Whether a round trip is due at `now`; a due check counts as the sync attempt, so a
+    /// failing remote is retried on the next interval rather than after every poll.
+    pub fn take_due(&mut self, now: DateTime<Utc>) -> bool {
+        let due = match (self.interval, self.last_synced_at) {
+            (Some(interval), Some(last)) => now - last >= interval,
+            _ => true,
+        };
+        if due {
+            self.last_synced_at = Some(now);
+        }
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+
+    use super::{merge_read_marks, parse_snapshot, SyncSchedule};
+    use crate::config::{SyncBackend, SyncConfig};
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 2, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn merge_keeps_every_read_key_at_its_earliest_read_time() {
+        let local = HashMap::from([("a".to_string(), at(5)), ("b".to_string(), at(3))]);
+        let remote = BTreeMap::from([("a".to_string(), at(4)), ("c".to_string(), at(6))]);
+
+        let merged = merge_read_marks(local, &remote);
+
+        assert_eq!(
+            merged,
+            HashMap::from([
+                ("a".to_string(), at(4)),
+                ("b".to_string(), at(3)),
+                ("c".to_string(), at(6)),
+            ])
+        );
+    }
+
+    #[test]
+    fn snapshot_from_a_newer_format_is_rejected() {
+        let err = parse_snapshot(r#"{"version":2,"read":{}}"#).unwrap_err();
+
+        assert!(err.to_string().contains("newer gh-watch"), "{err}");
+        assert!(parse_snapshot(r#"{"version":1,"read":{"k":"2025-01-02T03:00:00Z"}}"#).is_ok());
+    }
+
+    #[test]
+    fn schedule_syncs_after_every_poll_without_an_interval() {
+        let mut every_poll = SyncSchedule::new(&SyncConfig {
+            backend: SyncBackend::Gist,
+            gist_id: "abc".to_string(),
+            interval_minutes: None,
+        });
+        assert!(every_poll.take_due(at(1)));
+        assert!(every_poll.take_due(at(1)));
+
+        let mut hourly = SyncSchedule::new(&SyncConfig {
+            backend: SyncBackend::Gist,
+            gist_id: "abc".to_string(),
+            interval_minutes: Some(60),
+        });
+        assert!(hourly.take_due(at(1)));
+        assert!(!hourly.take_due(at(1) + Duration::minutes(59)));
+        assert!(hourly.take_due(at(2)));
+    }
+}
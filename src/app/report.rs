@@ -0,0 +1,543 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use chrono::{DateTime, TimeZone, Utc};
+use serde::Serialize;
+
+use crate::{
+    domain::events::WatchEvent,
+    ports::{RepoReadCounts, TimelineQueryPort},
+};
+
+const EVENT_PAGE_SIZE: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestFormat {
+    Markdown,
+    Text,
+}
+
+/// Repositories and actors a report is restricted to; an empty list matches everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventScope {
+    pub repos: Vec<String>,
+    pub actors: Vec<String>,
+}
+
+/// Every stored event created at or after `since` and before `before`, newest first.
+pub fn load_events_between<S>(
+    state: &S,
+    since: DateTime<Utc>,
+    before: Option<DateTime<Utc>>,
+) -> Result<Vec<WatchEvent>>
+where
+    S: TimelineQueryPort + ?Sized,
+{
+    load_scoped_events_between(state, since, before, &EventScope::default())
+}
+
+/// `load_events_between` limited to the repositories and actors of `scope`.
+pub fn load_scoped_events_between<S>(
+    state: &S,
+    since: DateTime<Utc>,
+    before: Option<DateTime<Utc>>,
+    scope: &EventScope,
+) -> Result<Vec<WatchEvent>>
+where
+    S: TimelineQueryPort + ?Sized,
+{
+    let mut events = Vec::new();
+    let mut before = before;
+    loop {
+        let page = state.load_timeline_events_filtered(
+            since,
+            before,
+            &scope.repos,
+            &scope.actors,
+            EVENT_PAGE_SIZE,
+        )?;
+        let is_last = page.len() < EVENT_PAGE_SIZE;
+        before = page.last().map(|event| event.created_at);
+        events.extend(page);
+        if is_last {
+            return Ok(events);
+        }
+    }
+}
+
+/// Activity digest of `events` created since `since`: read/unread totals, per-kind counts
+/// and unread counts per repository from `read_counts`, followed by one bullet per event,
+/// in one section per repository when `split_by_repo` is set. The title names the
+/// repositories and actors of `scope`. Times are shown in `timezone`.
+pub fn render_digest<Tz>(
+    events: &[WatchEvent],
+    read_counts: &[RepoReadCounts],
+    since: DateTime<Utc>,
+    timezone: &Tz,
+    format: DigestFormat,
+    split_by_repo: bool,
+    scope: &EventScope,
+) -> String
+where
+    Tz: TimeZone,
+    Tz::Offset: std::fmt::Display,
+{
+    let mut events = events.iter().collect::<Vec<_>>();
+    events.sort_by(|a, b| {
+        a.created_at
+            .cmp(&b.created_at)
+            .then_with(|| a.event_id.cmp(&b.event_id))
+    });
+    let mut by_repo = BTreeMap::<&str, Vec<&WatchEvent>>::new();
+    let mut by_kind = BTreeMap::<&str, usize>::new();
+    for event in &events {
+        by_repo.entry(event.repo.as_str()).or_default().push(event);
+        *by_kind.entry(event.kind.as_str()).or_default() += 1;
+    }
+
+    let markdown = format == DigestFormat::Markdown;
+    let since = since
+        .with_timezone(timezone)
+        .format("%Y-%m-%d %H:%M %:z")
+        .to_string();
+    let mut title = String::from("Activity");
+    if !scope.repos.is_empty() {
+        title.push_str(&format!(" in {}", scope.repos.join(", ")));
+    }
+    if !scope.actors.is_empty() {
+        let actors = scope
+            .actors
+            .iter()
+            .map(|actor| format!("@{actor}"))
+            .collect::<Vec<_>>();
+        title.push_str(&format!(" by {}", actors.join(", ")));
+    }
+    let mut lines = Vec::new();
+    if markdown {
+        lines.push(format!("# {} since {since}", escape_markdown(&title)));
+        lines.push(String::new());
+    } else {
+        lines.push(format!("{title} since {since}"));
+    }
+    lines.push(format!(
+        "{} events in {} repositories",
+        events.len(),
+        by_repo.len()
+    ));
+    if events.is_empty() {
+        return lines.join("\n") + "\n";
+    }
+    let read_total = read_counts.iter().map(|counts| counts.read).sum::<usize>();
+    let unread_total = read_counts
+        .iter()
+        .map(|counts| counts.unread)
+        .sum::<usize>();
+    lines.push(format!("{read_total} read, {unread_total} unread"));
+    lines.push(String::new());
+    lines.extend(
+        by_kind
+            .iter()
+            .map(|(kind, count)| format!("- {kind}: {count}")),
+    );
+    if unread_total > 0 {
+        lines.push(String::new());
+        lines.push(if markdown {
+            "## Unread".to_string()
+        } else {
+            "Unread:".to_string()
+        });
+        lines.extend(
+            read_counts
+                .iter()
+                .filter(|counts| counts.unread > 0)
+                .map(|counts| {
+                    let repo = if markdown {
+                        escape_markdown(&counts.repo)
+                    } else {
+                        counts.repo.clone()
+                    };
+                    format!("- {repo}: {}", counts.unread)
+                }),
+        );
+    }
+
+    let bullet = |event: &WatchEvent, with_repo: bool| {
+        let time = event
+            .created_at
+            .with_timezone(timezone)
+            .format("%m-%d %H:%M");
+        let repo = if with_repo {
+            format!("{} · ", event.repo)
+        } else {
+            String::new()
+        };
+        let title = match event.display_number() {
+            Some(number) => format!("#{number} {}", event.title),
+            None => event.title.clone(),
+        };
+        let title = if markdown {
+            format!("[{}]({})", escape_markdown(&title), event.url)
+        } else {
+            format!("{title} <{}>", event.url)
+        };
+        format!(
+            "- {time} · {repo}{} · @{} · {title}",
+            event.kind, event.actor
+        )
+    };
+
+    if split_by_repo {
+        for (repo, repo_events) in &by_repo {
+            lines.push(String::new());
+            lines.push(if markdown {
+                format!("## {}", escape_markdown(repo))
+            } else {
+                format!("{repo}:")
+            });
+            lines.extend(repo_events.iter().map(|event| bullet(event, false)));
+        }
+    } else {
+        lines.push(String::new());
+        lines.push(if markdown {
+            "## Events".to_string()
+        } else {
+            "Events:".to_string()
+        });
+        lines.extend(events.iter().map(|event| bullet(event, true)));
+    }
+
+    lines.join("\n") + "\n"
+}
+
+/// `report --format json`: the totals `render_digest` prints, plus every event in `events`
+/// oldest first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReportOutput {
+    pub since: DateTime<Utc>,
+    pub repos: Vec<String>,
+    pub actors: Vec<String>,
+    pub read: usize,
+    pub unread: usize,
+    pub by_kind: BTreeMap<String, usize>,
+    pub unread_by_repo: BTreeMap<String, usize>,
+    pub events: Vec<WatchEvent>,
+}
+
+pub fn build_report_output(
+    events: &[WatchEvent],
+    read_counts: &[RepoReadCounts],
+    since: DateTime<Utc>,
+    scope: &EventScope,
+) -> ReportOutput {
+    let mut events = events.to_vec();
+    events.sort_by(|a, b| {
+        a.created_at
+            .cmp(&b.created_at)
+            .then_with(|| a.event_id.cmp(&b.event_id))
+    });
+    let mut by_kind = BTreeMap::new();
+    for event in &events {
+        *by_kind.entry(event.kind.as_str().to_string()).or_default() += 1;
+    }
+
+    ReportOutput {
+        since,
+        repos: scope.repos.clone(),
+        actors: scope.actors.clone(),
+        read: read_counts.iter().map(|counts| counts.read).sum(),
+        unread: read_counts.iter().map(|counts| counts.unread).sum(),
+        by_kind,
+        unread_by_repo: read_counts
+            .iter()
+            .filter(|counts| counts.unread > 0)
+            .map(|counts| (counts.repo.clone(), counts.unread))
+            .collect(),
+        events,
+    }
+}
+
+/// Backslash-escapes characters that Markdown would treat as formatting or link syntax.
+pub fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if matches!(
+            ch,
+            '\\' | '`'
+                | '*'
+                | '_'
+                | '{'
+                | '}'
+                | '['
+                | ']'
+                | '<'
+                | '>'
+                | '('
+                | ')'
+                | '#'
+                | '+'
+                | '-'
+                | '.'
+                | '!'
+                | '|'
+                | '~'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::domain::events::EventKind;
+
+    fn event(id: &str, repo: &str, kind: EventKind, title: &str, minute: u32) -> WatchEvent {
+        WatchEvent {
+            event_id: id.to_string(),
+            repo: repo.to_string(),
+            kind,
+            actor: "dev".to_string(),
+            title: title.to_string(),
+            url: format!("https://github.com/{repo}/issues/7"),
+            created_at: Utc.with_ymd_and_hms(2026, 3, 2, 9, minute, 0).unwrap(),
+            source_item_id: id.to_string(),
+            subject_author: None,
+            requested_reviewer: None,
+            mentions: Vec::new(),
+            team_mentions: Vec::new(),
+            subject_number: None,
+            subject_url: None,
+            account: None,
+            body: None,
+        }
+    }
+
+    fn since() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 1, 9, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn escape_markdown_escapes_link_and_emphasis_syntax() {
+        assert_eq!(
+            escape_markdown("fix [docs](x) *now*"),
+            "fix \\[docs\\]\\(x\\) \\*now\\*"
+        );
+        assert_eq!(escape_markdown("plain words"), "plain words");
+    }
+
+    #[test]
+    fn markdown_digest_lists_events_oldest_first_with_counts() {
+        let events = vec![
+            event("b", "acme/web", EventKind::PrCreated, "Add [beta]", 30),
+            event("a", "acme/api", EventKind::IssueCreated, "Crash", 10),
+        ];
+
+        let digest = render_digest(
+            &events,
+            &[],
+            since(),
+            &Utc,
+            DigestFormat::Markdown,
+            false,
+            &EventScope::default(),
+        );
+
+        assert_eq!(
+            digest,
+            "# Activity since 2026-03-01 09:00 +00:00\n\
+             \n\
+             2 events in 2 repositories\n\
+             0 read, 0 unread\n\
+             \n\
+             - issue_created: 1\n\
+             - pr_created: 1\n\
+             \n\
+             ## Events\n\
+             - 03-02 09:10 · acme/api · issue_created · @dev · [\\#7 Crash](https://github.com/acme/api/issues/7)\n\
+             - 03-02 09:30 · acme/web · pr_created · @dev · [\\#7 Add \\[beta\\]](https://github.com/acme/web/issues/7)\n"
+        );
+    }
+
+    #[test]
+    fn split_by_repo_groups_events_under_repo_sections() {
+        let events = vec![
+            event("a", "acme/web", EventKind::IssueCreated, "One", 10),
+            event("b", "acme/api", EventKind::IssueCreated, "Two", 20),
+        ];
+
+        let digest = render_digest(
+            &events,
+            &[],
+            since(),
+            &Utc,
+            DigestFormat::Markdown,
+            true,
+            &EventScope::default(),
+        );
+
+        let api = digest.find("## acme/api").expect("api section");
+        let web = digest.find("## acme/web").expect("web section");
+        assert!(api < web);
+        assert!(digest.contains("- 03-02 09:20 · issue_created · @dev · [\\#7 Two]"));
+        assert!(!digest.contains("## Events"));
+    }
+
+    #[test]
+    fn text_digest_prints_raw_titles_and_urls() {
+        let events = vec![event(
+            "a",
+            "acme/api",
+            EventKind::IssueCreated,
+            "Fix *it*",
+            10,
+        )];
+
+        let digest = render_digest(
+            &events,
+            &[],
+            since(),
+            &Utc,
+            DigestFormat::Text,
+            false,
+            &EventScope::default(),
+        );
+
+        assert!(digest.starts_with("Activity since 2026-03-01 09:00 +00:00\n"));
+        assert!(digest.contains(
+            "- 03-02 09:10 · acme/api · issue_created · @dev · #7 Fix *it* <https://github.com/acme/api/issues/7>"
+        ));
+    }
+
+    #[test]
+    fn empty_digest_only_has_the_header() {
+        let digest = render_digest(
+            &[],
+            &[],
+            since(),
+            &Utc,
+            DigestFormat::Markdown,
+            true,
+            &EventScope::default(),
+        );
+
+        assert_eq!(
+            digest,
+            "# Activity since 2026-03-01 09:00 +00:00\n\n0 events in 0 repositories\n"
+        );
+    }
+
+    #[test]
+    fn digest_totals_reads_and_lists_repositories_with_unread_events() {
+        let events = vec![
+            event("a", "acme/api", EventKind::IssueCreated, "One", 10),
+            event("b", "acme/web", EventKind::IssueCreated, "Two", 20),
+            event("c", "acme/web", EventKind::IssueCreated, "Three", 30),
+        ];
+        let read_counts = vec![
+            RepoReadCounts {
+                repo: "acme/api".to_string(),
+                read: 1,
+                unread: 0,
+            },
+            RepoReadCounts {
+                repo: "acme/web".to_string(),
+                read: 0,
+                unread: 2,
+            },
+        ];
+
+        let text = render_digest(
+            &events,
+            &read_counts,
+            since(),
+            &Utc,
+            DigestFormat::Text,
+            false,
+            &EventScope::default(),
+        );
+
+        assert!(text.contains(
+            "3 events in 2 repositories\n1 read, 2 unread\n\n- issue_created: 3\n\nUnread:\n- acme/web: 2\n\nEvents:\n"
+        ));
+        let markdown = render_digest(
+            &events,
+            &read_counts,
+            since(),
+            &Utc,
+            DigestFormat::Markdown,
+            false,
+            &EventScope::default(),
+        );
+        assert!(markdown.contains("## Unread\n- acme/web: 2\n"));
+        assert!(!markdown.contains("acme/api: 0"));
+    }
+
+    #[test]
+    fn scoped_digest_names_repos_and_actors_in_the_title() {
+        let scope = EventScope {
+            repos: vec!["acme/api".to_string(), "acme/web".to_string()],
+            actors: vec!["alice".to_string()],
+        };
+
+        let markdown = render_digest(
+            &[],
+            &[],
+            since(),
+            &Utc,
+            DigestFormat::Markdown,
+            false,
+            &scope,
+        );
+        let text = render_digest(&[], &[], since(), &Utc, DigestFormat::Text, false, &scope);
+
+        assert!(markdown.starts_with(
+            "# Activity in acme/api, acme/web by @alice since 2026-03-01 09:00 +00:00\n"
+        ));
+        assert!(text.starts_with("Activity in acme/api, acme/web by @alice since 2026-03-01"));
+    }
+
+    #[test]
+    fn report_output_carries_the_digest_totals_and_events_oldest_first() {
+        let events = vec![
+            event("b", "acme/web", EventKind::PrCreated, "Two", 20),
+            event("a", "acme/api", EventKind::IssueCreated, "One", 10),
+        ];
+        let read_counts = vec![
+            RepoReadCounts {
+                repo: "acme/api".to_string(),
+                read: 1,
+                unread: 0,
+            },
+            RepoReadCounts {
+                repo: "acme/web".to_string(),
+                read: 0,
+                unread: 1,
+            },
+        ];
+
+        let output = build_report_output(&events, &read_counts, since(), &EventScope::default());
+
+        assert_eq!((output.read, output.unread), (1, 1));
+        assert_eq!(
+            output.by_kind,
+            BTreeMap::from([
+                ("issue_created".to_string(), 1),
+                ("pr_created".to_string(), 1)
+            ])
+        );
+        assert_eq!(
+            output.unread_by_repo,
+            BTreeMap::from([("acme/web".to_string(), 1)])
+        );
+        let ids = output
+            .events
+            .iter()
+            .map(|event| event.event_id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["a", "b"]);
+    }
+}
@@ -0,0 +1,104 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+
+use crate::{
+    app::report::load_events_between, domain::events::WatchEvent, ports::TimelineQueryPort,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimelineExportFormat {
+    /// One JSON array of events.
+    Json,
+    /// One event per line, as written by `state export`.
+    Ndjson,
+    /// `created_at,repo,kind,actor,title,url` with a header row.
+    Csv,
+}
+
+/// Writes every stored event to `writer`, oldest first, and returns the number written.
+pub fn write_timeline<S>(
+    state: &S,
+    writer: &mut dyn Write,
+    format: TimelineExportFormat,
+) -> Result<usize>
+where
+    S: TimelineQueryPort + ?Sized,
+{
+    let mut events = load_events_between(state, DateTime::<Utc>::MIN_UTC, None)?;
+    events.reverse();
+
+    match format {
+        TimelineExportFormat::Json => {
+            serde_json::to_writer_pretty(&mut *writer, &events)?;
+            writer.write_all(b"\n")?;
+        }
+        TimelineExportFormat::Ndjson => {
+            for event in &events {
+                serde_json::to_writer(&mut *writer, event)?;
+                writer.write_all(b"\n")?;
+            }
+        }
+        TimelineExportFormat::Csv => {
+            writer.write_all(b"created_at,repo,kind,actor,title,url\n")?;
+            for event in &events {
+                writeln!(writer, "{}", csv_row(event))?;
+            }
+        }
+    }
+    writer.flush()?;
+    Ok(events.len())
+}
+
+/// `write_timeline` into a new file at `path`, replacing any existing one.
+pub fn export_timeline<S>(state: &S, path: &Path, format: TimelineExportFormat) -> Result<usize>
+where
+    S: TimelineQueryPort + ?Sized,
+{
+    let file =
+        File::create(path).with_context(|| format!("failed to create {}", path.display()))?;
+    write_timeline(state, &mut BufWriter::new(file), format)
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+fn csv_row(event: &WatchEvent) -> String {
+    [
+        event.created_at.to_rfc3339(),
+        event.repo.clone(),
+        event.kind.as_str().to_string(),
+        event.actor.clone(),
+        event.title.clone(),
+        event.url.clone(),
+    ]
+    .iter()
+    .map(|field| csv_field(field))
+    .collect::<Vec<_>>()
+    .join(",")
+}
+
+/// Quotes `field` when it holds a comma, quote or line break, doubling inner quotes.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::csv_field;
+
+    #[test]
+    fn csv_fields_are_quoted_only_when_needed() {
+        assert_eq!(csv_field("acme/api"), "acme/api");
+        assert_eq!(csv_field("Fix a, b"), "\"Fix a, b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+}
@@ -0,0 +1,61 @@
+//! The viewer's team memberships, for `@org/team` mentions under `only_involving_me`.
+//! `gh api user/teams` needs the `read:org` scope, so a lookup that fails leaves matching to
+//! the viewer's login instead of failing the poll.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+
+use crate::ports::{GhClientPort, ViewerTeamsPort};
+
+/// How long fetched teams are trusted before `user/teams` is asked again.
+pub const VIEWER_TEAMS_TTL_HOURS: i64 = 24;
+
+/// The cached teams while younger than `VIEWER_TEAMS_TTL_HOURS`, otherwise freshly fetched and
+/// cached. Without a usable answer the viewer has no teams and a warning is logged.
+pub async fn resolve_viewer_teams<C, S>(gh: &C, state: &S, now: DateTime<Utc>) -> Vec<String>
+where
+    C: GhClientPort,
+    S: ViewerTeamsPort,
+{
+    match state.load_viewer_teams() {
+        Ok(Some((teams, fetched_at)))
+            if now - fetched_at < Duration::hours(VIEWER_TEAMS_TTL_HOURS) =>
+        {
+            return teams;
+        }
+        Ok(_) => {}
+        Err(err) => tracing::warn!(error = %err, "failed to load cached viewer teams"),
+    }
+
+    match gh.viewer_teams().await {
+        Ok(teams) => {
+            if let Err(err) = state.save_viewer_teams(&teams, now) {
+                tracing::warn!(error = %err, "failed to cache viewer teams");
+            }
+            teams
+        }
+        Err(err) => {
+            tracing::warn!(
+                error = %err,
+                "failed to resolve viewer teams; team mentions will not count as involving you"
+            );
+            Vec::new()
+        }
+    }
+}
+
+/// Drops the cached teams and fetches them again, for `config doctor --refresh-teams`.
+pub async fn refresh_viewer_teams<C, S>(
+    gh: &C,
+    state: &S,
+    now: DateTime<Utc>,
+) -> Result<Vec<String>>
+where
+    C: GhClientPort,
+    S: ViewerTeamsPort,
+{
+    state.clear_viewer_teams()?;
+    let teams = gh.viewer_teams().await?;
+    state.save_viewer_teams(&teams, now)?;
+    Ok(teams)
+}
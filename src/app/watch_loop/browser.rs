@@ -1,34 +1,110 @@
-use std::process::Command;
+use std::{fmt, future::Future, process::Stdio, time::Duration};
 
 use anyhow::{anyhow, Result};
+use tokio::process::Command;
+
+use crate::domain::command_line::url_command;
+
+/// How long a launcher may run before the TUI stops waiting; the child is left running.
+pub(super) const OPEN_COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A launcher that neither exited nor failed within [`OPEN_COMMAND_TIMEOUT`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct OpenTimedOut {
+    pub(super) launcher: String,
+    pub(super) after: Duration,
+}
+
+impl fmt::Display for OpenTimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} did not exit within {}s (left running)",
+            self.launcher,
+            self.after.as_secs_f64()
+        )
+    }
+}
+
+impl std::error::Error for OpenTimedOut {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LaunchStatus {
+    Succeeded,
+    Failed,
+    TimedOut,
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct OpenCommandResult {
-    success: bool,
+    status: LaunchStatus,
     stderr: String,
 }
 
-fn run_open_command(command: &mut Command) -> OpenCommandResult {
-    match command.output() {
-        Ok(output) => OpenCommandResult {
-            success: output.status.success(),
+/// Waits at most `timeout` for `command`; on timeout the child is dropped without being
+/// killed, so a launcher that stays in the foreground keeps running detached.
+async fn run_open_command(mut command: Command, timeout: Duration) -> OpenCommandResult {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(false);
+    let child = match command.spawn() {
+        Ok(child) => child,
+        Err(err) => {
+            return OpenCommandResult {
+                status: LaunchStatus::Failed,
+                stderr: err.to_string(),
+            }
+        }
+    };
+
+    match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(Ok(output)) => OpenCommandResult {
+            status: if output.status.success() {
+                LaunchStatus::Succeeded
+            } else {
+                LaunchStatus::Failed
+            },
             stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
         },
-        Err(err) => OpenCommandResult {
-            success: false,
+        Ok(Err(err)) => OpenCommandResult {
+            status: LaunchStatus::Failed,
             stderr: err.to_string(),
         },
+        Err(_) => OpenCommandResult {
+            status: LaunchStatus::TimedOut,
+            stderr: String::new(),
+        },
     }
 }
 
-pub(super) fn open_url_in_browser(url: &str) -> Result<()> {
+fn timed_out(launcher: &str, after: Duration) -> anyhow::Error {
+    anyhow::Error::new(OpenTimedOut {
+        launcher: launcher.to_string(),
+        after,
+    })
+}
+
+/// Opens `url` without blocking the caller's runtime thread; a launcher that hangs past
+/// [`OPEN_COMMAND_TIMEOUT`] yields an [`OpenTimedOut`] error instead of a launch failure.
+/// `browser_command` (`display.browser_command`) replaces the platform defaults entirely.
+pub(crate) async fn open_url_in_browser(url: &str, browser_command: Option<&str>) -> Result<()> {
+    let timeout = OPEN_COMMAND_TIMEOUT;
+
+    if let Some(raw) = browser_command {
+        return open_url_with_command(raw, url, timeout).await;
+    }
+
     #[cfg(target_os = "macos")]
     {
         let mut cmd = Command::new("open");
         cmd.arg(url);
-        let result = run_open_command(&mut cmd);
-        if result.success {
-            return Ok(());
+        let result = run_open_command(cmd, timeout).await;
+        match result.status {
+            LaunchStatus::Succeeded => return Ok(()),
+            LaunchStatus::TimedOut => return Err(timed_out("open", timeout)),
+            LaunchStatus::Failed => {}
         }
         if !result.stderr.is_empty() {
             tracing::debug!(url = %url, stderr = %result.stderr, "open command failed");
@@ -44,17 +120,21 @@ pub(super) fn open_url_in_browser(url: &str) -> Result<()> {
             url,
             detect_wsl(),
             browser_env.as_deref(),
-            run_linux_open_backend,
-        );
+            timeout,
+            |backend, url, browser_env| run_linux_open_backend(backend, url, browser_env, timeout),
+        )
+        .await;
     }
 
     #[cfg(target_os = "windows")]
     {
         let mut cmd = Command::new("cmd");
         cmd.args(["/C", "start", ""]).arg(url);
-        let result = run_open_command(&mut cmd);
-        if result.success {
-            return Ok(());
+        let result = run_open_command(cmd, timeout).await;
+        match result.status {
+            LaunchStatus::Succeeded => return Ok(()),
+            LaunchStatus::TimedOut => return Err(timed_out("start", timeout)),
+            LaunchStatus::Failed => {}
         }
         if !result.stderr.is_empty() {
             tracing::debug!(url = %url, stderr = %result.stderr, "start command failed");
@@ -67,6 +147,30 @@ pub(super) fn open_url_in_browser(url: &str) -> Result<()> {
     Err(anyhow!("unsupported OS for opening URLs"))
 }
 
+async fn open_url_with_command(raw: &str, url: &str, timeout: Duration) -> Result<()> {
+    let (bin, args) =
+        url_command(raw, url).ok_or_else(|| anyhow!("invalid display.browser_command: {raw}"))?;
+    let mut cmd = Command::new(&bin);
+    cmd.args(args);
+    let result = run_open_command(cmd, timeout).await;
+    match result.status {
+        LaunchStatus::Succeeded => Ok(()),
+        LaunchStatus::TimedOut => Err(timed_out("display.browser_command", timeout)),
+        LaunchStatus::Failed => {
+            if !result.stderr.is_empty() {
+                tracing::debug!(
+                    command = %bin,
+                    stderr = %result.stderr,
+                    "display.browser_command failed"
+                );
+            }
+            Err(anyhow!(
+                "failed to open URL with display.browser_command: {url}"
+            ))
+        }
+    }
+}
+
 #[cfg(target_os = "linux")]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum LinuxOpenBackend {
@@ -75,14 +179,28 @@ enum LinuxOpenBackend {
 }
 
 #[cfg(target_os = "linux")]
-fn open_url_on_linux<F>(
-    url: &str,
+impl LinuxOpenBackend {
+    fn label(self) -> &'static str {
+        match self {
+            Self::BrowserEnv => "$BROWSER",
+            Self::XdgOpen => "xdg-open",
+        }
+    }
+}
+
+/// Tries the backends in order. A timeout ends the attempt: the hung launcher may still
+/// open the page, so falling through to the next backend could open it twice.
+#[cfg(target_os = "linux")]
+async fn open_url_on_linux<'a, F, Fut>(
+    url: &'a str,
     is_wsl: bool,
-    browser_env: Option<&str>,
+    browser_env: Option<&'a str>,
+    timeout: Duration,
     mut runner: F,
 ) -> Result<()>
 where
-    F: FnMut(LinuxOpenBackend, &str, Option<&str>) -> bool,
+    F: FnMut(LinuxOpenBackend, &'a str, Option<&'a str>) -> Fut,
+    Fut: Future<Output = LaunchStatus>,
 {
     let browser_env = browser_env.and_then(|value| {
         let trimmed = value.trim();
@@ -93,132 +211,64 @@ where
         }
     });
 
-    if is_wsl {
-        if let Some(browser_env) = browser_env {
-            if runner(LinuxOpenBackend::BrowserEnv, url, Some(browser_env)) {
-                return Ok(());
-            }
-            if runner(LinuxOpenBackend::XdgOpen, url, None) {
-                return Ok(());
-            }
-            return Err(anyhow!(
-                "failed to open URL in WSL with $BROWSER and xdg-open: {url}"
-            ));
-        }
-
-        if runner(LinuxOpenBackend::XdgOpen, url, None) {
-            return Ok(());
+    let backends: &[LinuxOpenBackend] = match (is_wsl, browser_env) {
+        (true, Some(_)) => &[LinuxOpenBackend::BrowserEnv, LinuxOpenBackend::XdgOpen],
+        _ => &[LinuxOpenBackend::XdgOpen],
+    };
+    for &backend in backends {
+        let env = (backend == LinuxOpenBackend::BrowserEnv)
+            .then_some(browser_env)
+            .flatten();
+        match runner(backend, url, env).await {
+            LaunchStatus::Succeeded => return Ok(()),
+            LaunchStatus::TimedOut => return Err(timed_out(backend.label(), timeout)),
+            LaunchStatus::Failed => {}
         }
-        return Err(anyhow!("failed to open URL in WSL with xdg-open: {url}"));
     }
 
-    if runner(LinuxOpenBackend::XdgOpen, url, None) {
-        return Ok(());
+    match (is_wsl, browser_env) {
+        (true, Some(_)) => Err(anyhow!(
+            "failed to open URL in WSL with $BROWSER and xdg-open: {url}"
+        )),
+        (true, None) => Err(anyhow!("failed to open URL in WSL with xdg-open: {url}")),
+        (false, _) => Err(anyhow!("failed to open URL with xdg-open: {url}")),
     }
-
-    Err(anyhow!("failed to open URL with xdg-open: {url}"))
 }
 
 #[cfg(target_os = "linux")]
-fn run_linux_open_backend(backend: LinuxOpenBackend, url: &str, browser_env: Option<&str>) -> bool {
+async fn run_linux_open_backend(
+    backend: LinuxOpenBackend,
+    url: &str,
+    browser_env: Option<&str>,
+    timeout: Duration,
+) -> LaunchStatus {
     match backend {
-        LinuxOpenBackend::BrowserEnv => browser_env
-            .and_then(|raw| browser_command_from_env(raw, url))
-            .map(|(bin, args)| {
-                let mut cmd = Command::new(&bin);
-                cmd.args(args);
-                let result = run_open_command(&mut cmd);
-                if !result.success && !result.stderr.is_empty() {
-                    tracing::debug!(
-                        command = %bin,
-                        stderr = %result.stderr,
-                        "linux browser open command failed"
-                    );
-                }
-                result.success
-            })
-            .unwrap_or(false),
+        LinuxOpenBackend::BrowserEnv => {
+            let Some((bin, args)) = browser_env.and_then(|raw| url_command(raw, url)) else {
+                return LaunchStatus::Failed;
+            };
+            let mut cmd = Command::new(&bin);
+            cmd.args(args);
+            let result = run_open_command(cmd, timeout).await;
+            if result.status == LaunchStatus::Failed && !result.stderr.is_empty() {
+                tracing::debug!(
+                    command = %bin,
+                    stderr = %result.stderr,
+                    "linux browser open command failed"
+                );
+            }
+            result.status
+        }
         LinuxOpenBackend::XdgOpen => {
             let mut cmd = Command::new("xdg-open");
             cmd.arg(url);
-            let result = run_open_command(&mut cmd);
-            if !result.success && !result.stderr.is_empty() {
+            let result = run_open_command(cmd, timeout).await;
+            if result.status == LaunchStatus::Failed && !result.stderr.is_empty() {
                 tracing::debug!(stderr = %result.stderr, "linux xdg-open command failed");
             }
-            result.success
-        }
-    }
-}
-
-#[cfg(target_os = "linux")]
-fn browser_command_from_env(raw: &str, url: &str) -> Option<(String, Vec<String>)> {
-    let mut tokens = split_shell_words(raw)?;
-    let has_placeholder = tokens.iter().any(|token| token.contains("%s"));
-
-    for token in &mut tokens {
-        if token.contains("%s") {
-            *token = token.replace("%s", url);
-        }
-    }
-
-    if !has_placeholder {
-        tokens.push(url.to_string());
-    }
-
-    let bin = tokens.remove(0);
-    Some((bin, tokens))
-}
-
-#[cfg(target_os = "linux")]
-fn split_shell_words(raw: &str) -> Option<Vec<String>> {
-    let mut tokens = Vec::new();
-    let mut current = String::new();
-    let mut active_quote: Option<char> = None;
-    let mut escaped = false;
-
-    for ch in raw.chars() {
-        if escaped {
-            current.push(ch);
-            escaped = false;
-            continue;
-        }
-
-        if let Some(quote) = active_quote {
-            if ch == quote {
-                active_quote = None;
-            } else if quote == '"' && ch == '\\' {
-                escaped = true;
-            } else {
-                current.push(ch);
-            }
-            continue;
-        }
-
-        match ch {
-            '\'' | '"' => active_quote = Some(ch),
-            '\\' => escaped = true,
-            c if c.is_whitespace() => {
-                if !current.is_empty() {
-                    tokens.push(std::mem::take(&mut current));
-                }
-            }
-            _ => current.push(ch),
+            result.status
         }
     }
-
-    if escaped || active_quote.is_some() {
-        return None;
-    }
-
-    if !current.is_empty() {
-        tokens.push(current);
-    }
-
-    if tokens.is_empty() {
-        return None;
-    }
-
-    Some(tokens)
 }
 
 #[cfg(target_os = "linux")]
@@ -264,44 +314,69 @@ fn read_proc_wsl_hint() -> Option<String> {
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
+    use super::{LaunchStatus, OpenTimedOut};
+
     #[cfg(unix)]
-    #[test]
-    fn open_command_capture_collects_stderr_for_failed_process() {
-        let mut cmd = std::process::Command::new("sh");
+    #[tokio::test]
+    async fn open_command_capture_collects_stderr_for_failed_process() {
+        let mut cmd = tokio::process::Command::new("sh");
         cmd.args(["-c", "printf 'launcher missing\\n' >&2; exit 1"]);
 
-        let result = super::run_open_command(&mut cmd);
-        assert!(!result.success);
+        let result = super::run_open_command(cmd, Duration::from_secs(5)).await;
+        assert_eq!(result.status, LaunchStatus::Failed);
         assert_eq!(result.stderr, "launcher missing");
     }
 
-    #[cfg(target_os = "linux")]
-    #[test]
-    fn browser_command_from_env_replaces_percent_s_placeholder() {
-        let url = "https://example.com/placeholder";
-        let (bin, args) = super::browser_command_from_env("w3m %s --title=%s", url)
-            .expect("browser command should parse");
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn browser_command_runs_quoted_script_path_with_spaces() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let app_dir = dir.path().join("Google Chrome.app");
+        std::fs::create_dir(&app_dir).unwrap();
+        let script = app_dir.join("Google Chrome");
+        let marker = dir.path().join("marker.txt");
+        std::fs::write(
+            &script,
+            format!(
+                "#!/bin/sh\nprintf '%s %s' \"$1\" \"$2\" > '{}'\n",
+                marker.display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let command = format!("\"{}\" --profile-directory=Work %s", script.display());
+        super::open_url_with_command(&command, "https://example.com/pr/1", Duration::from_secs(5))
+            .await
+            .expect("browser command should succeed");
 
-        assert_eq!(bin, "w3m");
         assert_eq!(
-            args,
-            vec![
-                "https://example.com/placeholder".to_string(),
-                "--title=https://example.com/placeholder".to_string()
-            ]
+            std::fs::read_to_string(&marker).unwrap(),
+            "--profile-directory=Work https://example.com/pr/1"
         );
     }
 
     #[cfg(target_os = "linux")]
-    #[test]
-    fn linux_open_wsl_failure_after_browser_and_xdg_open_returns_expected_error() {
+    #[tokio::test]
+    async fn linux_open_wsl_failure_after_browser_and_xdg_open_returns_expected_error() {
         let url = "https://example.com/wsl-fail";
         let mut calls = Vec::new();
 
-        let err = super::open_url_on_linux(url, true, Some("firefox"), |backend, _url, _| {
-            calls.push(backend);
-            false
-        })
+        let err = super::open_url_on_linux(
+            url,
+            true,
+            Some("firefox"),
+            Duration::from_secs(5),
+            |backend, _url, _| {
+                calls.push(backend);
+                std::future::ready(LaunchStatus::Failed)
+            },
+        )
+        .await
         .expect_err("wsl browser and xdg-open failures should bubble up");
 
         assert_eq!(
@@ -316,4 +391,67 @@ mod tests {
             format!("failed to open URL in WSL with $BROWSER and xdg-open: {url}")
         );
     }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn linux_open_timeout_stops_before_xdg_open_fallback() {
+        let mut calls = Vec::new();
+
+        let err = super::open_url_on_linux(
+            "https://example.com/hung",
+            true,
+            Some("firefox"),
+            Duration::from_secs(5),
+            |backend, _url, _| {
+                calls.push(backend);
+                std::future::ready(LaunchStatus::TimedOut)
+            },
+        )
+        .await
+        .expect_err("a hung launcher should be reported");
+
+        assert_eq!(calls, vec![super::LinuxOpenBackend::BrowserEnv]);
+        assert!(err.downcast_ref::<OpenTimedOut>().is_some());
+        assert_eq!(
+            err.to_string(),
+            "$BROWSER did not exit within 5s (left running)"
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn hung_browser_script_times_out_while_the_loop_keeps_redrawing() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("hung-browser");
+        std::fs::write(&script, "#!/bin/sh\nexec sleep 30\n").unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let browser_env = script.display().to_string();
+
+        let open = tokio::spawn(async move {
+            super::run_linux_open_backend(
+                super::LinuxOpenBackend::BrowserEnv,
+                "https://example.com/hung",
+                Some(&browser_env),
+                Duration::from_millis(300),
+            )
+            .await
+        });
+        tokio::pin!(open);
+        let mut redraw = tokio::time::interval(Duration::from_millis(20));
+        let mut redraws = 0;
+        let status = loop {
+            tokio::select! {
+                status = &mut open => break status.unwrap(),
+                _ = redraw.tick() => redraws += 1,
+            }
+        };
+
+        assert_eq!(status, LaunchStatus::TimedOut);
+        assert!(
+            redraws >= 5,
+            "loop stalled while the launcher ran: {redraws} redraws"
+        );
+    }
 }
@@ -0,0 +1,34 @@
+#[cfg(feature = "clipboard")]
+use std::cell::RefCell;
+
+use anyhow::Result;
+
+/// The system clipboard, held for the whole session: on X11 and Wayland the copied text is
+/// served by this process and disappears once the handle is dropped.
+#[derive(Default)]
+pub(super) struct SessionClipboard {
+    #[cfg(feature = "clipboard")]
+    handle: RefCell<Option<arboard::Clipboard>>,
+}
+
+impl SessionClipboard {
+    #[cfg(feature = "clipboard")]
+    pub(super) fn copy(&self, text: &str) -> Result<()> {
+        let mut handle = self.handle.borrow_mut();
+        let clipboard = match handle.as_mut() {
+            Some(clipboard) => clipboard,
+            None => handle.insert(arboard::Clipboard::new()?),
+        };
+        if let Err(err) = clipboard.set_text(text) {
+            // Reconnect on the next yank, e.g. after the display server restarted.
+            *handle = None;
+            return Err(err.into());
+        }
+        Ok(())
+    }
+
+    #[cfg(not(feature = "clipboard"))]
+    pub(super) fn copy(&self, _text: &str) -> Result<()> {
+        Err(anyhow::anyhow!("built without the `clipboard` feature"))
+    }
+}
@@ -0,0 +1,180 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+
+use crate::{domain::events::WatchEvent, ports::GhClientPort};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum GhPrAction {
+    View,
+    Checkout,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct GhCommand {
+    /// Picks the account gh runs as.
+    pub(super) repo: String,
+    pub(super) args: Vec<String>,
+    pub(super) cwd: Option<PathBuf>,
+}
+
+impl GhCommand {
+    pub(super) fn display(&self) -> String {
+        format!("gh {}", self.args.join(" "))
+    }
+}
+
+/// PR number from a `https://github.com/<owner>/<repo>/pull/<n>` URL, ignoring fragments and
+/// trailing path segments such as `/files`.
+pub(super) fn pr_number_from_url(url: &str) -> Option<u64> {
+    let path = url.split(['#', '?']).next().unwrap_or(url);
+    let mut segments = path.split('/');
+    segments.find(|segment| *segment == "pull")?;
+    segments.next()?.parse().ok()
+}
+
+/// `None` when the event does not point at a pull request.
+pub(super) fn build_gh_pr_command(
+    action: GhPrAction,
+    event: &WatchEvent,
+    local_path: Option<&Path>,
+) -> Option<GhCommand> {
+    let number = pr_number_from_url(&event.url)?.to_string();
+    let (args, cwd) = match action {
+        GhPrAction::View => (
+            vec![
+                "pr",
+                "view",
+                number.as_str(),
+                "--repo",
+                event.repo.as_str(),
+                "--web",
+            ],
+            None,
+        ),
+        GhPrAction::Checkout => (
+            vec![
+                "pr",
+                "checkout",
+                number.as_str(),
+                "--repo",
+                event.repo.as_str(),
+            ],
+            local_path.map(Path::to_path_buf),
+        ),
+    };
+    Some(GhCommand {
+        repo: event.repo.clone(),
+        args: args.into_iter().map(str::to_string).collect(),
+        cwd,
+    })
+}
+
+/// Runs `command` with the configured gh binary and the repository's account; hands the
+/// command back so the loop can report it.
+pub(super) async fn run_gh_command<C>(gh: &C, command: GhCommand) -> (GhCommand, Result<()>)
+where
+    C: GhClientPort,
+{
+    let result = gh
+        .run_gh_command(&command.repo, &command.args, command.cwd.as_deref())
+        .await
+        .map_err(|err| anyhow!("{} failed: {err:#}", command.display()));
+    (command, result)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use chrono::{TimeZone, Utc};
+
+    use super::{build_gh_pr_command, pr_number_from_url, GhPrAction};
+    use crate::domain::events::{EventKind, WatchEvent};
+
+    fn event_with_url(url: &str) -> WatchEvent {
+        WatchEvent {
+            event_id: "pr:1".to_string(),
+            repo: "acme/api".to_string(),
+            kind: EventKind::PrCreated,
+            actor: "alice".to_string(),
+            title: "Add endpoint".to_string(),
+            url: url.to_string(),
+            created_at: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+            source_item_id: "1".to_string(),
+            subject_author: None,
+            requested_reviewer: None,
+            mentions: Vec::new(),
+            team_mentions: Vec::new(),
+            subject_number: None,
+            subject_url: None,
+            account: None,
+            body: None,
+        }
+    }
+
+    #[test]
+    fn pr_number_is_parsed_from_pull_urls_only() {
+        assert_eq!(
+            pr_number_from_url("https://github.com/acme/api/pull/42"),
+            Some(42)
+        );
+        assert_eq!(
+            pr_number_from_url("https://github.com/acme/api/pull/42#discussion_r1"),
+            Some(42)
+        );
+        assert_eq!(
+            pr_number_from_url("https://github.com/acme/api/pull/42/files?w=1"),
+            Some(42)
+        );
+        assert_eq!(
+            pr_number_from_url("https://github.com/acme/api/issues/42"),
+            None
+        );
+        assert_eq!(
+            pr_number_from_url("https://github.com/acme/api/pull/"),
+            None
+        );
+    }
+
+    #[test]
+    fn view_command_targets_pr_in_browser() {
+        let event = event_with_url("https://github.com/acme/api/pull/7#issuecomment-3");
+
+        let command = build_gh_pr_command(GhPrAction::View, &event, Some(Path::new("/src/api")))
+            .expect("pull request url");
+
+        assert_eq!(
+            command.args,
+            vec!["pr", "view", "7", "--repo", "acme/api", "--web"]
+        );
+        assert_eq!(command.cwd, None);
+        assert_eq!(command.display(), "gh pr view 7 --repo acme/api --web");
+    }
+
+    #[test]
+    fn checkout_command_runs_in_repo_local_path() {
+        let event = event_with_url("https://github.com/acme/api/pull/7");
+
+        let command =
+            build_gh_pr_command(GhPrAction::Checkout, &event, Some(Path::new("/src/api")))
+                .expect("pull request url");
+
+        assert_eq!(
+            command.args,
+            vec!["pr", "checkout", "7", "--repo", "acme/api"]
+        );
+        assert_eq!(command.cwd.as_deref(), Some(Path::new("/src/api")));
+    }
+
+    #[test]
+    fn non_pr_url_builds_no_command() {
+        let event = event_with_url("https://github.com/acme/api/issues/7");
+
+        assert_eq!(build_gh_pr_command(GhPrAction::View, &event, None), None);
+        assert_eq!(
+            build_gh_pr_command(GhPrAction::Checkout, &event, None),
+            None
+        );
+    }
+}
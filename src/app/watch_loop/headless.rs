@@ -0,0 +1,234 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use super::{
+    idle_alert::{idle_alert_message, IdleAlertTracker},
+    poll_result::enabled_repository_names,
+    poll_schedule::{PollSchedule, SystemJitter},
+    review_reminders::{review_reminders_enabled, send_due_review_reminders},
+    shutdown_signal,
+    weekly_summary::{send_due_weekly_summary, weekly_summary_tracker},
+    WatchOptions,
+};
+use crate::{
+    app::{
+        filter_profile::config_with_filter_profile,
+        poll_once::{poll_once_as, PollOutcome},
+        read_sync::{sync_read_state, SyncSchedule},
+    },
+    config::Config,
+    domain::redact::redact_secrets,
+    ports::{
+        ClockPort, FilterProfilePort, GhClientPort, NotificationPayload, NotifierPort,
+        WatchStatePort,
+    },
+};
+
+/// `watch --no-tui`: polls on the same schedule as the TUI and logs one line per cycle, for
+/// running under a service manager. Stops on Ctrl-C / SIGINT or SIGTERM.
+pub async fn run_headless_watch<C, S, N, K, P>(
+    config: &Config,
+    gh: &C,
+    state: &S,
+    notifier: &N,
+    clock: &K,
+    filter_profile: &P,
+    options: WatchOptions,
+) -> Result<()>
+where
+    C: GhClientPort,
+    S: WatchStatePort,
+    N: NotifierPort,
+    K: ClockPort,
+    P: FilterProfilePort,
+{
+    let repo_count = enabled_repository_names(config).len();
+    let mut schedule = PollSchedule::new(
+        Duration::from_secs(config.interval_seconds),
+        config.poll.jitter_seconds,
+        SystemJitter::default(),
+    );
+    let mut delay = schedule.first_delay(options.initial_poll.is_some());
+    let mut idle_tracker = options
+        .idle_alert
+        .map(|policy| IdleAlertTracker::new(policy, clock.now()));
+    let mut summary_tracker = weekly_summary_tracker(config, state, clock.now())?;
+    let mut sync_schedule = config.sync.as_ref().map(SyncSchedule::new);
+    let viewer_login = match &options.viewer_login {
+        Some(login) => Some(login.clone()),
+        None if summary_tracker.is_some() || review_reminders_enabled(config) => {
+            gh.viewer_login().await.ok()
+        }
+        None => None,
+    };
+    println!(
+        "{} watching {repo_count} repos every {}s",
+        log_timestamp(clock.now()),
+        config.interval_seconds
+    );
+
+    // Pinned once and raced against the poll work too: tokio replaces the default handlers
+    // when the signal is first awaited, so a SIGTERM during a poll must not go unobserved.
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
+    loop {
+        tokio::select! {
+            () = tokio::time::sleep(delay) => {}
+            () = &mut shutdown => break,
+        }
+        delay = schedule.next_delay();
+
+        let cycle = async {
+            let poll_config = config_with_filter_profile(config, filter_profile);
+            let result = poll_once_as(
+                &poll_config,
+                gh,
+                state,
+                notifier,
+                clock,
+                viewer_login.as_deref(),
+            )
+            .await;
+            let now = clock.now();
+            match &result {
+                Ok(_) => println!("{}", headless_poll_line(now, &result)),
+                Err(_) => eprintln!("{}", headless_poll_line(now, &result)),
+            }
+
+            let new_event_count = result
+                .as_ref()
+                .map(|outcome| outcome.timeline_events.len())
+                .unwrap_or(0);
+            if let Some(tracker) = idle_tracker.as_mut() {
+                tracker.record_events(new_event_count, now);
+                if let Some(idle_for) = tracker.take_due_alert(now) {
+                    let message = idle_alert_message(repo_count, idle_for);
+                    if let Err(err) = notifier.notify(&NotificationPayload::Alert(message), false) {
+                        eprintln!("{} idle alert failed: {err:#}", log_timestamp(now));
+                    }
+                }
+            }
+            if let Some(tracker) = summary_tracker.as_mut() {
+                match send_due_weekly_summary(
+                    tracker,
+                    state,
+                    notifier,
+                    viewer_login.as_deref(),
+                    now,
+                ) {
+                    Ok(true) => println!("{} weekly summary sent", log_timestamp(now)),
+                    Ok(false) => {}
+                    Err(err) => eprintln!("{} weekly summary failed: {err:#}", log_timestamp(now)),
+                }
+            }
+            match send_due_review_reminders(
+                config,
+                gh,
+                state,
+                notifier,
+                viewer_login.as_deref(),
+                now,
+            )
+            .await
+            {
+                Ok(0) => {}
+                Ok(sent) => println!("{} review reminders sent: {sent}", log_timestamp(now)),
+                Err(err) => eprintln!("{} review reminders failed: {err:#}", log_timestamp(now)),
+            }
+            match notifier.drain_notification_queue() {
+                Ok(0) => {}
+                Ok(sent) => println!("{} held notifications sent: {sent}", log_timestamp(now)),
+                Err(err) => eprintln!(
+                    "{} sending held notifications failed: {err:#}",
+                    log_timestamp(now)
+                ),
+            }
+            if let (Some(sync), Some(schedule)) = (&config.sync, sync_schedule.as_mut()) {
+                if schedule.take_due(now) {
+                    match sync_read_state(config, sync, gh, state, now).await {
+                        Ok(outcome) if outcome.newly_read.is_empty() => {}
+                        Ok(outcome) => println!(
+                            "{} sync: {} events read elsewhere",
+                            log_timestamp(now),
+                            outcome.newly_read.len()
+                        ),
+                        Err(err) => eprintln!("{} sync failed: {err:#}", log_timestamp(now)),
+                    }
+                }
+            }
+        };
+        tokio::select! {
+            () = cycle => {}
+            () = &mut shutdown => break,
+        }
+    }
+
+    println!("{} stopped", log_timestamp(clock.now()));
+    Ok(())
+}
+
+fn log_timestamp(now: DateTime<Utc>) -> String {
+    now.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+pub(super) fn headless_poll_line(now: DateTime<Utc>, result: &Result<PollOutcome>) -> String {
+    let timestamp = log_timestamp(now);
+    match result {
+        Ok(outcome) => {
+            let mut line = format!(
+                "{timestamp} poll ok: new={} notified={} | {}",
+                outcome.timeline_events.len(),
+                outcome.notified_count,
+                outcome.api_usage_summary()
+            );
+            for failure in &outcome.fetch_failures {
+                line.push_str(&format!(" | {} failed: {}", failure.repo, failure.message));
+            }
+            line
+        }
+        Err(err) => format!(
+            "{timestamp} poll failed: {}",
+            redact_secrets(&format!("{err:#}"))
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+    use chrono::{TimeZone, Utc};
+
+    use super::headless_poll_line;
+    use crate::app::poll_once::{PollOutcome, RepoFetchFailure};
+
+    #[test]
+    fn poll_line_summarizes_outcome_and_failures() {
+        let now = Utc.with_ymd_and_hms(2025, 1, 2, 3, 4, 5).unwrap();
+        let outcome = PollOutcome {
+            notified_count: 2,
+            api_calls: 12,
+            rate_limit_remaining: Some(4900),
+            fetch_failures: vec![RepoFetchFailure {
+                repo: "acme/api".to_string(),
+                message: "timeout".to_string(),
+                ..RepoFetchFailure::default()
+            }],
+            ..PollOutcome::default()
+        };
+
+        assert_eq!(
+            headless_poll_line(now, &Ok(outcome)),
+            "2025-01-02T03:04:05Z poll ok: new=0 notified=2 | api: 12 calls/cycle, 4900 remaining | acme/api failed: timeout"
+        );
+        assert_eq!(
+            headless_poll_line(now, &Err(anyhow!("all repositories failed"))),
+            "2025-01-02T03:04:05Z poll failed: all repositories failed"
+        );
+        assert_eq!(
+            headless_poll_line(now, &Err(anyhow!("gh: Bearer ghp_abc123 was rejected"))),
+            "2025-01-02T03:04:05Z poll failed: gh: Bearer *** was rejected"
+        );
+    }
+}
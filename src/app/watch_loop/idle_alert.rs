@@ -0,0 +1,136 @@
+use chrono::{DateTime, Duration, Utc};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdleAlertPolicy {
+    pub threshold: Duration,
+    pub cooldown: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct IdleAlertTracker {
+    policy: IdleAlertPolicy,
+    started_at: DateTime<Utc>,
+    last_event_at: Option<DateTime<Utc>>,
+    last_alert_at: Option<DateTime<Utc>>,
+}
+
+impl IdleAlertTracker {
+    pub(super) fn new(policy: IdleAlertPolicy, started_at: DateTime<Utc>) -> Self {
+        Self {
+            policy,
+            started_at,
+            last_event_at: None,
+            last_alert_at: None,
+        }
+    }
+
+    pub(super) fn record_events(&mut self, new_event_count: usize, now: DateTime<Utc>) {
+        if new_event_count == 0 {
+            return;
+        }
+
+        self.last_event_at = Some(now);
+        self.last_alert_at = None;
+    }
+
+    pub(super) fn take_due_alert(&mut self, now: DateTime<Utc>) -> Option<Duration> {
+        let idle_for = now - self.last_event_at.unwrap_or(self.started_at);
+        if idle_for < self.policy.threshold {
+            return None;
+        }
+
+        if self
+            .last_alert_at
+            .is_some_and(|alerted_at| now - alerted_at < self.policy.cooldown)
+        {
+            return None;
+        }
+
+        self.last_alert_at = Some(now);
+        Some(idle_for)
+    }
+}
+
+pub(super) fn idle_alert_message(repo_count: usize, idle_for: Duration) -> String {
+    let idle = if idle_for.num_hours() > 0 {
+        format!("{}h", idle_for.num_hours())
+    } else {
+        format!("{}m", idle_for.num_minutes())
+    };
+    format!("No new events in {repo_count} repos for {idle}")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+
+    use super::{idle_alert_message, IdleAlertPolicy, IdleAlertTracker};
+
+    fn policy() -> IdleAlertPolicy {
+        IdleAlertPolicy {
+            threshold: Duration::hours(4),
+            cooldown: Duration::hours(1),
+        }
+    }
+
+    #[test]
+    fn alert_fires_only_after_threshold_is_exceeded() {
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let mut tracker = IdleAlertTracker::new(policy(), start);
+
+        assert_eq!(tracker.take_due_alert(start + Duration::minutes(239)), None);
+        assert_eq!(
+            tracker.take_due_alert(start + Duration::hours(4)),
+            Some(Duration::hours(4))
+        );
+    }
+
+    #[test]
+    fn repeat_alerts_wait_for_cooldown() {
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let mut tracker = IdleAlertTracker::new(policy(), start);
+
+        assert!(tracker.take_due_alert(start + Duration::hours(5)).is_some());
+        assert!(tracker
+            .take_due_alert(start + Duration::hours(5) + Duration::minutes(59))
+            .is_none());
+        assert_eq!(
+            tracker.take_due_alert(start + Duration::hours(6)),
+            Some(Duration::hours(6))
+        );
+    }
+
+    #[test]
+    fn new_events_reset_idle_window_and_cooldown() {
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let mut tracker = IdleAlertTracker::new(policy(), start);
+        assert!(tracker.take_due_alert(start + Duration::hours(5)).is_some());
+
+        let event_at = start + Duration::hours(5) + Duration::minutes(10);
+        tracker.record_events(0, event_at);
+        assert!(tracker
+            .take_due_alert(event_at + Duration::hours(1))
+            .is_some());
+
+        tracker.record_events(2, event_at + Duration::hours(1));
+        assert!(tracker
+            .take_due_alert(event_at + Duration::hours(4))
+            .is_none());
+        assert_eq!(
+            tracker.take_due_alert(event_at + Duration::hours(5)),
+            Some(Duration::hours(4))
+        );
+    }
+
+    #[test]
+    fn alert_message_uses_hours_or_minutes() {
+        assert_eq!(
+            idle_alert_message(3, Duration::hours(4) + Duration::minutes(20)),
+            "No new events in 3 repos for 4h"
+        );
+        assert_eq!(
+            idle_alert_message(1, Duration::minutes(45)),
+            "No new events in 1 repos for 45m"
+        );
+    }
+}
@@ -1,46 +1,106 @@
-use std::{future::Future, pin::Pin, time::Duration};
+use std::{collections::HashMap, future::Future, path::PathBuf, pin::Pin, time::Duration};
 
-use anyhow::Result;
-use futures_util::StreamExt;
+use anyhow::{anyhow, Result};
+use futures_util::{stream::FuturesUnordered, StreamExt};
 use tokio::time::MissedTickBehavior;
 
 use crate::{
-    app::poll_once::{poll_once, PollOutcome},
+    app::{
+        filter_profile::config_with_filter_profile,
+        poll_once::{poll_once_as, resolve_team_members, PollOutcome},
+        read_sync::{sync_read_state, SyncOutcome, SyncSchedule},
+        viewer_teams::resolve_viewer_teams,
+    },
     config::Config,
-    ports::{ClockPort, GhClientPort, NotifierPort, WatchStatePort},
-    ui::tui::{TerminalUi, TuiModel},
+    domain::subject_state::{SubjectRef, SubjectState},
+    ports::{
+        ClockPort, FilterProfilePort, GhClientPort, NotificationPayload, NotifierPort,
+        ViewStatePort, WatchStatePort,
+    },
+    ui::tui::{Keymap, TerminalUi, TuiModel, NOTIFICATION_INDICATOR_SECONDS},
 };
 
 mod browser;
+mod clipboard;
+mod gh_command;
+mod headless;
+mod idle_alert;
 mod poll_result;
+mod poll_schedule;
 mod poll_state;
+mod review_reminders;
 mod stream_controller;
+mod subject_state;
+mod weekly_summary;
 
-use browser::open_url_in_browser;
-use poll_result::{apply_poll_result, enabled_repository_names};
+pub(crate) use browser::open_url_in_browser;
+use clipboard::SessionClipboard;
+use gh_command::{run_gh_command, GhCommand};
+pub use headless::run_headless_watch;
+pub use idle_alert::IdleAlertPolicy;
+use idle_alert::{idle_alert_message, IdleAlertTracker};
+use poll_result::{apply_initial_poll, apply_poll_result, enabled_repository_names};
+use poll_schedule::{PollSchedule, SystemJitter};
 use poll_state::PollExecutionState;
-use stream_controller::{handle_stream_event, LoopControl};
+use review_reminders::send_due_review_reminders;
+use stream_controller::{
+    finish_open_url, finish_run_gh, handle_stream_event, InputActions, LoopControl,
+};
+use subject_state::{distinct_subjects, refresh_subject_states};
+use weekly_summary::{send_due_weekly_summary, weekly_summary_tracker};
 
 const SPINNER_REDRAW_INTERVAL_MS: u64 = 120;
 
-type PollFuture<'a> = Pin<Box<dyn Future<Output = Result<PollOutcome>> + 'a>>;
+/// Work the loop awaits alongside input so a slow gh call never freezes keys or redraws.
+type InFlight<'a, T> = Pin<Box<dyn Future<Output = T> + 'a>>;
+type PollFuture<'a> = InFlight<'a, Result<PollOutcome>>;
+
+#[derive(Debug, Default)]
+pub struct WatchOptions {
+    pub idle_alert: Option<IdleAlertPolicy>,
+    /// Outcome of a poll already run before the TUI started; the loop then waits a full
+    /// (jittered) interval before polling again.
+    pub initial_poll: Option<PollOutcome>,
+    /// Shown in the status line until the first scheduled poll completes.
+    pub startup_notice: Option<String>,
+    /// Login `check_auth` reported; saves asking gh for it at startup and on every poll.
+    pub viewer_login: Option<String>,
+}
 
-pub async fn run_watch<C, S, N, K>(
+#[allow(clippy::too_many_arguments)]
+pub async fn run_watch<C, S, N, K, P, V>(
     config: &Config,
     gh: &C,
     state: &S,
     notifier: &N,
     clock: &K,
+    filter_profile: &P,
+    view_state: &V,
+    options: WatchOptions,
 ) -> Result<()>
 where
     C: GhClientPort,
     S: WatchStatePort,
     N: NotifierPort,
     K: ClockPort,
+    P: FilterProfilePort,
+    V: ViewStatePort,
 {
     let mut ui = TerminalUi::new()?;
     let mut model = TuiModel::new(config.timeline_limit);
-    let viewer_login = match gh.viewer_login().await {
+    model.keyboard_shortcuts = Keymap::from_config(&config.keymap)?;
+    model.layout = config.display.layout;
+    model.kind_labels = config.display.kind_labels;
+    match view_state.load_split_ratio() {
+        Ok(Some(ratio)) => model.set_split_ratio(ratio),
+        Ok(None) => {}
+        Err(err) => tracing::warn!(error = %err, "failed to load view state"),
+    }
+    let viewer_login = match options.viewer_login.clone() {
+        Some(login) => Ok(login),
+        None => gh.viewer_login().await,
+    };
+    let viewer_login = match viewer_login {
         Ok(login) => Some(login),
         Err(err) => {
             tracing::warn!(
@@ -50,8 +110,13 @@ where
             None
         }
     };
-    model.set_viewer_login(viewer_login);
+    model.set_viewer_login(viewer_login.clone());
+    if config.active_filters().only_involving_me {
+        model.set_viewer_teams(resolve_viewer_teams(gh, state, clock.now()).await);
+    }
+    model.set_team_members(resolve_team_members(config.active_filters(), gh).await);
     model.watched_repositories = enabled_repository_names(config);
+    model.group_repos_by_topic = config.display.group_repos_by_topic;
     let timeline = state.load_timeline_events(config.timeline_limit)?;
     let timeline_keys = timeline
         .iter()
@@ -60,20 +125,99 @@ where
     let read_event_keys = state.load_read_event_keys(&timeline_keys)?;
     model.replace_timeline(timeline);
     model.replace_read_event_keys(read_event_keys);
-    model.status_line = "ready".to_string();
-    model.next_poll_at = Some(clock.now());
+    let initial_poll_done = options.initial_poll.is_some();
+    let mut schedule = PollSchedule::new(
+        Duration::from_secs(config.interval_seconds),
+        config.poll.jitter_seconds,
+        SystemJitter::default(),
+    );
+    let first_delay = schedule.first_delay(initial_poll_done);
+    match options.initial_poll {
+        Some(outcome) => apply_initial_poll(outcome, &mut model, clock),
+        None => model.status_line = "ready".to_string(),
+    }
+    model.next_poll_at = Some(next_poll_at(clock, first_delay));
+    if let Some(notice) = options.startup_notice {
+        model.status_notice = Some(match model.status_notice.take() {
+            Some(existing) => format!("{existing} | {notice}"),
+            None => notice,
+        });
+    }
+    // Without an initial poll the startup poll begins right away, so the notice is kept
+    // through its result instead of being cleared before it was ever visible.
+    let mut keep_notice_for_next_result = !initial_poll_done;
+    model.is_notification_suppressed = notifier.is_notification_suppressed();
     ui.draw(&mut model)?;
+    let mut in_flight_subject_states = None;
+    if config.display.resolve_subject_state {
+        in_flight_subject_states = Some(subject_state_lookup(gh, state, &model, clock));
+    }
+    if config.display.group_repos_by_topic {
+        model.repo_topics = resolve_repo_topics(config, gh).await;
+        ui.draw(&mut model)?;
+    }
 
-    let mut interval = tokio::time::interval(Duration::from_secs(config.interval_seconds));
-    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let next_poll = tokio::time::sleep(first_delay);
+    tokio::pin!(next_poll);
     let mut spinner_interval =
         tokio::time::interval(Duration::from_millis(SPINNER_REDRAW_INTERVAL_MS));
     spinner_interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
     spinner_interval.tick().await;
+    let local_path_for = |repo: &str| {
+        config
+            .repositories
+            .iter()
+            .find(|repository| repository.name == repo)
+            .and_then(|repository| repository.local_path.as_ref())
+            .map(PathBuf::from)
+    };
+    let (open_result_tx, mut open_results) = tokio::sync::mpsc::unbounded_channel();
+    // Launchers run on their own task so a hung $BROWSER cannot stall redraws.
+    let spawn_open_url = |url: &str| -> Result<()> {
+        let url = url.to_string();
+        let browser_command = config.display.browser_command.clone();
+        let tx = open_result_tx.clone();
+        tokio::spawn(async move {
+            let result = open_url_in_browser(&url, browser_command.as_deref()).await;
+            let _ = tx.send((url, result));
+        });
+        Ok(())
+    };
+    // gh commands are queued here and run below alongside input; `gh pr checkout` can take
+    // as long as a fetch.
+    let (gh_command_tx, mut gh_commands) = tokio::sync::mpsc::unbounded_channel();
+    let queue_gh_command = |command: &GhCommand| -> Result<()> {
+        gh_command_tx.send(command.clone()).map_err(|_| {
+            anyhow!(
+                "{} failed: the watch loop is shutting down",
+                command.display()
+            )
+        })
+    };
+    let mut running_gh_commands = FuturesUnordered::new();
+    let clipboard = SessionClipboard::default();
+    let input_actions = InputActions {
+        open_url: &spawn_open_url,
+        run_gh: &queue_gh_command,
+        local_path_for: &local_path_for,
+        copy_to_clipboard: &|text| clipboard.copy(text),
+        yank_fallback_print: config.notifications.yank_fallback_print,
+        save_split_ratio: &|ratio| view_state.save_split_ratio(ratio),
+        resize_debounce: Duration::from_millis(config.debounce_resize_ms),
+    };
+    let mut idle_tracker = options
+        .idle_alert
+        .map(|policy| IdleAlertTracker::new(policy, clock.now()));
+    let mut summary_tracker = weekly_summary_tracker(config, state, clock.now())?;
+    let mut sync_schedule = config.sync.as_ref().map(SyncSchedule::new);
     let mut reader = crossterm::event::EventStream::new();
     let mut poll_state = PollExecutionState::default();
     let mut in_flight_poll: Option<PollFuture<'_>> = None;
-    poll_state.request_poll();
+    let mut in_flight_reminders: Option<InFlight<'_, Result<usize>>> = None;
+    let mut in_flight_sync: Option<InFlight<'_, Result<SyncOutcome>>> = None;
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
+    let mut indicator_expires_at: Option<std::time::Instant> = None;
 
     loop {
         if poll_state.start_poll() {
@@ -81,38 +225,115 @@ where
             model.poll_started_at = Some(clock.now());
             model.queued_refresh = poll_state.queued_refresh();
             model.status_line = "polling".to_string();
+            let poll_config = config_with_filter_profile(config, filter_profile);
+            model.active_filter_profile = poll_config.active_filter_profile.clone();
             ui.draw(&mut model)?;
-            in_flight_poll = Some(Box::pin(poll_once(config, gh, state, notifier, clock)));
+            let viewer_login = viewer_login.as_deref();
+            in_flight_poll = Some(Box::pin(async move {
+                poll_once_as(&poll_config, gh, state, notifier, clock, viewer_login).await
+            }));
         }
 
         tokio::select! {
-            _ = interval.tick() => {
+            () = &mut shutdown => break,
+            () = &mut next_poll => {
+                let delay = schedule.next_delay();
+                next_poll.as_mut().reset(tokio::time::Instant::now() + delay);
+                model.next_poll_at = Some(next_poll_at(clock, delay));
                 poll_state.request_poll();
                 model.queued_refresh = poll_state.queued_refresh();
                 if model.is_polling {
                     ui.draw(&mut model)?;
                 }
             }
+            () = sleep_until_deadline(indicator_expires_at), if indicator_expires_at.is_some() => {
+                indicator_expires_at = None;
+                ui.draw(&mut model)?;
+            }
+            () = sleep_until_deadline(model.resize_deadline), if model.resize_deadline.is_some() => {
+                if let Some(size) = model.take_due_resize(std::time::Instant::now()) {
+                    ui.resize_callback(size, &mut model)?;
+                }
+            }
             _ = spinner_interval.tick(), if model.is_polling => {
                 ui.draw(&mut model)?;
             }
-            poll_result = async {
-                match in_flight_poll.as_mut() {
-                    Some(fut) => Some(fut.await),
-                    None => None,
+            result = finish_in_flight(&mut in_flight_poll), if in_flight_poll.is_some() => {
+                let new_event_count = result
+                    .as_ref()
+                    .map(|outcome| outcome.timeline_events.len())
+                    .unwrap_or(0);
+                if !std::mem::take(&mut keep_notice_for_next_result) {
+                    model.status_notice = None;
                 }
-            }, if in_flight_poll.is_some() => {
-                let result = poll_result.expect("poll future must exist when branch is active");
-                in_flight_poll = None;
-
                 apply_poll_result(result, &mut model, clock);
+                if config.display.resolve_subject_state && in_flight_subject_states.is_none() {
+                    in_flight_subject_states = Some(subject_state_lookup(gh, state, &model, clock));
+                }
+                if model.notification_indicator_active(clock.now()) {
+                    // Redraw once more to clear the indicator when it runs out.
+                    indicator_expires_at = Some(
+                        std::time::Instant::now()
+                            + Duration::from_secs(NOTIFICATION_INDICATOR_SECONDS.unsigned_abs()),
+                    );
+                }
+                if let Some(tracker) = idle_tracker.as_mut() {
+                    tracker.record_events(new_event_count, clock.now());
+                    if let Some(idle_for) = tracker.take_due_alert(clock.now()) {
+                        let message =
+                            idle_alert_message(model.watched_repositories.len(), idle_for);
+                        if let Err(err) = notifier.notify(&NotificationPayload::Alert(message), false)
+                        {
+                            tracing::warn!(error = %err, "idle alert notification failed");
+                            model.status_line = format!("idle alert failed: {err}");
+                        }
+                    }
+                }
+                if let Some(tracker) = summary_tracker.as_mut() {
+                    if let Err(err) = send_due_weekly_summary(
+                        tracker,
+                        state,
+                        notifier,
+                        viewer_login.as_deref(),
+                        clock.now(),
+                    ) {
+                        tracing::warn!(error = %err, "weekly summary notification failed");
+                        model.status_line = format!("weekly summary failed: {err}");
+                    }
+                }
+                if in_flight_reminders.is_none() {
+                    let viewer_login = viewer_login.as_deref();
+                    let now = clock.now();
+                    in_flight_reminders = Some(Box::pin(send_due_review_reminders(
+                        config,
+                        gh,
+                        state,
+                        notifier,
+                        viewer_login,
+                        now,
+                    )));
+                }
+                if let Err(err) = notifier.drain_notification_queue() {
+                    tracing::warn!(error = %err, "sending held notifications failed");
+                    model.status_line = format!("held notifications failed: {err}");
+                }
+                model.is_notification_suppressed = notifier.is_notification_suppressed();
+                if let (Some(sync), Some(schedule)) = (&config.sync, sync_schedule.as_mut()) {
+                    if in_flight_sync.is_none() && schedule.take_due(clock.now()) {
+                        in_flight_sync = Some(Box::pin(sync_read_state(
+                            config,
+                            sync,
+                            gh,
+                            state,
+                            clock.now(),
+                        )));
+                    }
+                }
                 let queued_for_immediate_next = poll_state.finish_poll_and_take_next_request();
 
                 model.is_polling = poll_state.in_flight();
                 model.poll_started_at = None;
                 model.queued_refresh = poll_state.queued_refresh();
-                model.next_poll_at =
-                    Some(clock.now() + chrono::Duration::seconds(config.interval_seconds as i64));
 
                 if queued_for_immediate_next {
                     model.status_line = format!("{} | queued refresh", model.status_line);
@@ -120,6 +341,40 @@ where
 
                 ui.draw(&mut model)?;
             }
+            result = finish_in_flight(&mut in_flight_reminders), if in_flight_reminders.is_some() => {
+                if let Err(err) = result {
+                    tracing::warn!(error = %err, "review reminder notification failed");
+                    model.status_line = format!("review reminder failed: {err}");
+                    ui.draw(&mut model)?;
+                }
+            }
+            result = finish_in_flight(&mut in_flight_sync), if in_flight_sync.is_some() => {
+                match result {
+                    Ok(outcome) => {
+                        model.extend_read_event_keys(outcome.newly_read.into_iter().collect())
+                    }
+                    Err(err) => {
+                        tracing::warn!(error = %err, "read state sync failed");
+                        model.status_line = format!("sync failed: {err}");
+                    }
+                }
+                ui.draw(&mut model)?;
+            }
+            result = finish_in_flight(&mut in_flight_subject_states), if in_flight_subject_states.is_some() => {
+                apply_subject_states(result, &mut model);
+                ui.draw(&mut model)?;
+            }
+            Some((url, result)) = open_results.recv() => {
+                finish_open_url(&mut model, &url, result);
+                ui.draw(&mut model)?;
+            }
+            Some(command) = gh_commands.recv() => {
+                running_gh_commands.push(run_gh_command(gh, command));
+            }
+            Some((command, result)) = running_gh_commands.next(), if !running_gh_commands.is_empty() => {
+                finish_run_gh(&mut model, &command, result);
+                ui.draw(&mut model)?;
+            }
             maybe_event = reader.next() => {
                 let terminal_area = ui.terminal_area().unwrap_or_default();
                 match handle_stream_event(
@@ -128,7 +383,7 @@ where
                     state,
                     clock,
                     terminal_area,
-                    &open_url_in_browser,
+                    &input_actions,
                 ) {
                     LoopControl::Quit => break,
                     LoopControl::RequestPoll => {
@@ -149,3 +404,95 @@ where
 
     Ok(())
 }
+
+/// Output of the future in `in_flight`, which is cleared once it completes; the `select!`
+/// branch awaiting it must be guarded by `in_flight.is_some()`.
+async fn finish_in_flight<T>(in_flight: &mut Option<InFlight<'_, T>>) -> T {
+    let output = match in_flight.as_mut() {
+        Some(fut) => fut.await,
+        None => std::future::pending().await,
+    };
+    *in_flight = None;
+    output
+}
+
+/// Looks up the states of the subjects in the timeline as it is now.
+fn subject_state_lookup<'a, C, S, K>(
+    gh: &'a C,
+    state: &'a S,
+    model: &TuiModel,
+    clock: &K,
+) -> InFlight<'a, Result<HashMap<SubjectRef, SubjectState>>>
+where
+    C: GhClientPort,
+    S: WatchStatePort,
+    K: ClockPort,
+{
+    let subjects = distinct_subjects(model.all_events());
+    let now = clock.now();
+    Box::pin(async move { refresh_subject_states(gh, state, &subjects, now).await })
+}
+
+/// Failures keep the states already shown and surface in the status line.
+fn apply_subject_states(result: Result<HashMap<SubjectRef, SubjectState>>, model: &mut TuiModel) {
+    match result {
+        Ok(subject_states) => model.replace_subject_states(subject_states),
+        Err(err) => {
+            tracing::warn!(error = %err, "subject state lookup failed");
+            model.status_line = format!("subject state lookup failed: {err}");
+        }
+    }
+}
+
+/// `topic_tags` of each enabled repository, or its GitHub topics when it sets none. A failed
+/// lookup leaves that repository without topics.
+async fn resolve_repo_topics<C>(config: &Config, gh: &C) -> HashMap<String, Vec<String>>
+where
+    C: GhClientPort,
+{
+    let mut topics = HashMap::new();
+    for repo in config.repositories.iter().filter(|repo| repo.enabled) {
+        let repo_topics = match &repo.topic_tags {
+            Some(tags) => tags.clone(),
+            None => match gh.fetch_repository_topics(&repo.name).await {
+                Ok(fetched) => fetched,
+                Err(err) => {
+                    tracing::warn!(repo = %repo.name, error = %err, "topic lookup failed");
+                    Vec::new()
+                }
+            },
+        };
+        topics.insert(repo.name.clone(), repo_topics);
+    }
+    topics
+}
+
+/// Resolves on Ctrl-C or, on Unix, SIGTERM, so the watch loops can end like on quit.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        if let Ok(mut terminate) = signal(SignalKind::terminate()) {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = terminate.recv() => {}
+            }
+            return;
+        }
+    }
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+async fn sleep_until_deadline(deadline: Option<std::time::Instant>) {
+    if let Some(deadline) = deadline {
+        tokio::time::sleep_until(tokio::time::Instant::from_std(deadline)).await;
+    }
+}
+
+fn next_poll_at<K>(clock: &K, delay: Duration) -> chrono::DateTime<chrono::Utc>
+where
+    K: ClockPort,
+{
+    clock.now() + chrono::Duration::from_std(delay).unwrap_or(chrono::Duration::MAX)
+}
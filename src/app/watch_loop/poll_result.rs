@@ -1,6 +1,11 @@
 use anyhow::Result;
 
-use crate::{app::poll_once::PollOutcome, config::Config, ports::ClockPort, ui::tui::TuiModel};
+use crate::{
+    app::poll_once::{AllRepoFetchesFailedError, PollOutcome},
+    config::Config,
+    ports::ClockPort,
+    ui::tui::{ActiveTab, TuiModel},
+};
 
 pub(super) fn enabled_repository_names(config: &Config) -> Vec<String> {
     config
@@ -19,37 +24,80 @@ where
         Ok(outcome) => {
             let new_count = outcome.timeline_events.len();
             let repo_failure_count = outcome.fetch_failures.len();
+            let (streaks, recovered) = model.failure_streaks.advance(
+                outcome.fetched_repos.iter().map(String::as_str),
+                outcome
+                    .fetch_failures
+                    .iter()
+                    .map(|failure| failure.repo.as_str()),
+            );
+            model.failure_streaks = streaks;
+            if !recovered.is_empty() {
+                model.status_notice = Some(format!("recovered: {}", recovered.join(", ")));
+            }
+            model.api_usage = Some(outcome.api_usage_summary());
             if new_count > 0 {
                 model.push_timeline(outcome.timeline_events);
             }
             if repo_failure_count > 0 {
                 model.failure_count += repo_failure_count as u64;
-                model.status_line =
-                    format!("ok (new={new_count}, repo_failures={repo_failure_count})");
+                if model.active_tab != ActiveTab::Repositories {
+                    model.error_badge_count += repo_failure_count as u64;
+                }
+                model.set_status_line(format!(
+                    "ok (new={new_count}, repo_failures={repo_failure_count})"
+                ));
             } else {
-                model.status_line = format!("ok (new={new_count})");
+                model.set_status_line(format!("ok (new={new_count})"));
             }
             model.last_success_at = Some(clock.now());
+            if outcome.notified_count > 0 {
+                model.last_notification_at = Some(clock.now());
+            }
+            model.is_polling_first_time = false;
         }
         Err(err) => {
+            if let Some(all_failed) = err.downcast_ref::<AllRepoFetchesFailedError>() {
+                (model.failure_streaks, _) = model.failure_streaks.advance(
+                    [],
+                    all_failed
+                        .failures
+                        .iter()
+                        .map(|failure| failure.repo.as_str()),
+                );
+            }
             model.failure_count += 1;
-            model.status_line = format!("poll failed: {err}");
+            model.set_status_line(format!("poll failed: {err}"));
         }
     }
 }
 
+pub(super) fn apply_initial_poll<K>(outcome: PollOutcome, model: &mut TuiModel, clock: &K)
+where
+    K: ClockPort,
+{
+    let bootstrapped = outcome.timeline_events.len();
+    apply_poll_result(Ok(outcome), model, clock);
+    model.status_notice = Some(format!("bootstrapped {bootstrapped} events"));
+}
+
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use anyhow::anyhow;
     use chrono::{TimeZone, Utc};
 
-    use super::{apply_poll_result, enabled_repository_names};
+    use super::{apply_initial_poll, apply_poll_result, enabled_repository_names};
     use crate::{
-        app::poll_once::{PollOutcome, RepoFetchFailure},
-        config::{Config, FiltersConfig, NotificationConfig, PollConfig, RepositoryConfig},
+        app::poll_once::{AllRepoFetchesFailedError, PollOutcome, RepoFetchFailure},
+        config::{
+            Config, DisplayConfig, FiltersConfig, KeymapConfig, NetworkConfig, NotificationConfig,
+            PollConfig, RemindersConfig, RepositoryConfig, RetentionConfig,
+        },
         domain::events::{EventKind, WatchEvent},
         ports::ClockPort,
-        ui::tui::TuiModel,
+        ui::tui::{ActiveTab, TuiModel},
     };
 
     struct FixedClock {
@@ -75,6 +123,11 @@ mod tests {
             subject_author: Some("dev".to_string()),
             requested_reviewer: None,
             mentions: Vec::new(),
+            team_mentions: Vec::new(),
+            subject_number: None,
+            subject_url: None,
+            account: None,
+            body: None,
         }
     }
 
@@ -82,30 +135,67 @@ mod tests {
     fn enabled_repository_names_keeps_config_order_and_filters_disabled() {
         let config = Config {
             interval_seconds: 300,
+            i_know_what_im_doing: false,
             bootstrap_lookback_hours: 24,
             timeline_limit: 500,
             retention_days: 90,
+            max_notification_history: 10_000,
+            debounce_resize_ms: 50,
+            retention: RetentionConfig::default(),
+            reminders: RemindersConfig::default(),
+            sync: None,
             state_db_path: None,
+            accounts: Vec::new(),
+            startup_search_queries: Vec::new(),
+            watch_my_prs: false,
             repositories: vec![
                 RepositoryConfig {
                     name: "acme/one".to_string(),
                     enabled: true,
                     event_kinds: None,
+                    local_path: None,
+                    watch_review_threads: false,
+                    account: None,
+                    gh_profile: None,
+                    path_filters: None,
+                    topic_tags: None,
+                    notification_kinds: HashMap::new(),
                 },
                 RepositoryConfig {
                     name: "acme/two".to_string(),
                     enabled: false,
                     event_kinds: None,
+                    local_path: None,
+                    watch_review_threads: false,
+                    account: None,
+                    gh_profile: None,
+                    path_filters: None,
+                    topic_tags: None,
+                    notification_kinds: HashMap::new(),
                 },
                 RepositoryConfig {
                     name: "acme/three".to_string(),
                     enabled: true,
                     event_kinds: None,
+                    local_path: None,
+                    watch_review_threads: false,
+                    account: None,
+                    gh_profile: None,
+                    path_filters: None,
+                    topic_tags: None,
+                    notification_kinds: HashMap::new(),
                 },
             ],
             notifications: NotificationConfig::default(),
+            notification_schedule: None,
             filters: FiltersConfig::default(),
+            default_event_kinds: None,
+            filter_profiles: HashMap::new(),
+            active_filter_profile: None,
             poll: PollConfig::default(),
+            network: NetworkConfig::default(),
+            display: DisplayConfig::default(),
+            keymap: KeymapConfig::default(),
         };
 
         let watched = enabled_repository_names(&config);
@@ -150,6 +240,7 @@ mod tests {
             fetch_failures: vec![RepoFetchFailure {
                 repo: "acme/web".to_string(),
                 message: "boom".to_string(),
+                ..RepoFetchFailure::default()
             }],
             ..PollOutcome::default()
         };
@@ -158,4 +249,166 @@ mod tests {
         assert_eq!(model.status_line, "ok (new=1, repo_failures=1)");
         assert_eq!(model.failure_count, 1);
     }
+
+    #[test]
+    fn repo_fetch_failures_raise_the_error_badge_unless_repositories_is_shown() {
+        let clock = FixedClock {
+            now: Utc.with_ymd_and_hms(2025, 1, 8, 12, 0, 0).unwrap(),
+        };
+        let failures = || PollOutcome {
+            fetch_failures: ["acme/web", "acme/api"]
+                .into_iter()
+                .map(|repo| RepoFetchFailure {
+                    repo: repo.to_string(),
+                    message: "boom".to_string(),
+                    ..RepoFetchFailure::default()
+                })
+                .collect(),
+            ..PollOutcome::default()
+        };
+        let mut model = TuiModel::new(10);
+
+        apply_poll_result(Ok(failures()), &mut model, &clock);
+        apply_poll_result(Err(anyhow!("boom")), &mut model, &clock);
+        assert_eq!(model.error_badge_count, 2);
+
+        apply_poll_result(Ok(failures()), &mut model, &clock);
+        assert_eq!(model.error_badge_count, 4);
+
+        model.clear_error_badge();
+        model.set_active_tab(ActiveTab::Repositories);
+        apply_poll_result(Ok(failures()), &mut model, &clock);
+        assert_eq!(model.error_badge_count, 0);
+    }
+
+    #[test]
+    fn watch_records_api_usage_of_last_successful_poll() {
+        let clock = FixedClock {
+            now: Utc.with_ymd_and_hms(2025, 1, 8, 12, 0, 0).unwrap(),
+        };
+        let mut model = TuiModel::new(10);
+
+        let outcome = PollOutcome {
+            api_calls: 312,
+            rate_limit_remaining: Some(4200),
+            ..PollOutcome::default()
+        };
+        apply_poll_result(Ok(outcome), &mut model, &clock);
+        apply_poll_result(Err(anyhow!("boom")), &mut model, &clock);
+
+        assert_eq!(
+            model.api_usage.as_deref(),
+            Some("api: 312 calls/cycle, 4200 remaining")
+        );
+    }
+
+    #[test]
+    fn initial_poll_shows_bootstrapped_events_immediately() {
+        let clock = FixedClock {
+            now: Utc.with_ymd_and_hms(2025, 1, 8, 12, 0, 0).unwrap(),
+        };
+        let mut model = TuiModel::new(10);
+        model.replace_timeline(vec![timeline_event("ev-a", clock.now)]);
+
+        let outcome = PollOutcome {
+            timeline_events: vec![
+                timeline_event("ev-a", clock.now),
+                timeline_event("ev-b", clock.now),
+            ],
+            ..PollOutcome::default()
+        };
+        apply_initial_poll(outcome, &mut model, &clock);
+
+        assert_eq!(model.status_line, "ok (new=2)");
+        assert_eq!(
+            model.status_notice.as_deref(),
+            Some("bootstrapped 2 events")
+        );
+        assert_eq!(model.timeline.len(), 2);
+        assert_eq!(model.last_success_at, Some(clock.now));
+        assert!(!model.is_polling_first_time);
+    }
+
+    fn repo_failure(repo: &str) -> RepoFetchFailure {
+        RepoFetchFailure {
+            repo: repo.to_string(),
+            message: "boom".to_string(),
+            ..RepoFetchFailure::default()
+        }
+    }
+
+    #[test]
+    fn apply_poll_result_tracks_failure_streaks_and_reports_recovery() {
+        let clock = FixedClock {
+            now: Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+        };
+        let mut model = TuiModel::new(10);
+        for _ in 0..2 {
+            let outcome = PollOutcome {
+                fetched_repos: vec!["acme/web".to_string()],
+                fetch_failures: vec![repo_failure("acme/api")],
+                ..PollOutcome::default()
+            };
+            apply_poll_result(Ok(outcome), &mut model, &clock);
+        }
+        apply_poll_result(
+            Err(AllRepoFetchesFailedError {
+                failures: vec![repo_failure("acme/api"), repo_failure("acme/web")],
+            }
+            .into()),
+            &mut model,
+            &clock,
+        );
+        apply_poll_result(Err(anyhow!("state store down")), &mut model, &clock);
+        assert_eq!(model.failure_streaks.get("acme/api"), 3);
+        assert_eq!(model.failure_streaks.get("acme/web"), 1);
+        assert_eq!(model.status_notice, None);
+
+        let outcome = PollOutcome {
+            fetched_repos: vec!["acme/api".to_string(), "acme/web".to_string()],
+            ..PollOutcome::default()
+        };
+        apply_poll_result(Ok(outcome), &mut model, &clock);
+
+        assert_eq!(model.failure_streaks.worst(), None);
+        assert_eq!(
+            model.status_notice.as_deref(),
+            Some("recovered: acme/api, acme/web")
+        );
+    }
+
+    #[test]
+    fn apply_poll_result_stamps_polls_that_sent_notifications() {
+        let clock = FixedClock {
+            now: Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+        };
+        let mut model = TuiModel::new(10);
+
+        apply_poll_result(Ok(PollOutcome::default()), &mut model, &clock);
+        assert_eq!(model.last_notification_at, None);
+
+        let outcome = PollOutcome {
+            notified_count: 1,
+            ..PollOutcome::default()
+        };
+        apply_poll_result(Ok(outcome), &mut model, &clock);
+        assert_eq!(model.last_notification_at, Some(clock.now));
+    }
+
+    #[test]
+    fn apply_poll_result_counts_repeated_status_messages() {
+        let clock = FixedClock {
+            now: Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+        };
+        let mut model = TuiModel::new(10);
+        for _ in 0..3 {
+            apply_poll_result(Err(anyhow!("network down")), &mut model, &clock);
+        }
+        assert_eq!(model.status_line, "poll failed: network down");
+        assert_eq!(model.status_repeat(), 3);
+
+        apply_poll_result(Ok(PollOutcome::default()), &mut model, &clock);
+        assert_eq!(model.status_line, "ok (new=0)");
+        assert_eq!(model.status_repeat(), 1);
+    }
 }
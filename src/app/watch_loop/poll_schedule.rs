@@ -0,0 +1,148 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+use crate::config::MIN_INTERVAL_SECONDS;
+
+/// Source of the per-cycle offset added to the poll interval.
+pub(super) trait JitterSource {
+    /// Returns an offset in `-max_seconds..=max_seconds`.
+    fn offset_seconds(&mut self, max_seconds: u64) -> i64;
+}
+
+#[derive(Debug, Default)]
+pub(super) struct SystemJitter {
+    seed: RandomState,
+    counter: u64,
+}
+
+impl JitterSource for SystemJitter {
+    fn offset_seconds(&mut self, max_seconds: u64) -> i64 {
+        if max_seconds == 0 {
+            return 0;
+        }
+
+        self.counter = self.counter.wrapping_add(1);
+        let mut hasher = self.seed.build_hasher();
+        hasher.write_u64(self.counter);
+        let span = max_seconds.saturating_mul(2).saturating_add(1);
+        (hasher.finish() % span) as i64 - max_seconds as i64
+    }
+}
+
+/// Delays between scheduled polls: the interval shifted by a fresh jitter offset each cycle,
+/// never shorter than the interval or `MIN_INTERVAL_SECONDS`, whichever is less.
+#[derive(Debug)]
+pub(super) struct PollSchedule<J> {
+    interval: Duration,
+    jitter_seconds: u64,
+    source: J,
+}
+
+impl<J> PollSchedule<J>
+where
+    J: JitterSource,
+{
+    pub(super) fn new(interval: Duration, jitter_seconds: u64, source: J) -> Self {
+        Self {
+            interval,
+            jitter_seconds,
+            source,
+        }
+    }
+
+    /// Delay before the first scheduled poll; a poll that already ran at startup counts as
+    /// the first cycle.
+    pub(super) fn first_delay(&mut self, initial_poll_done: bool) -> Duration {
+        if initial_poll_done {
+            self.next_delay()
+        } else {
+            Duration::ZERO
+        }
+    }
+
+    pub(super) fn next_delay(&mut self) -> Duration {
+        let offset = self.source.offset_seconds(self.jitter_seconds);
+        let magnitude = Duration::from_secs(offset.unsigned_abs());
+        let delay = if offset >= 0 {
+            self.interval.saturating_add(magnitude)
+        } else {
+            self.interval.saturating_sub(magnitude)
+        };
+        delay.max(self.interval.min(Duration::from_secs(MIN_INTERVAL_SECONDS)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::VecDeque, time::Duration};
+
+    use super::{JitterSource, PollSchedule, SystemJitter};
+
+    struct ScriptedJitter {
+        offsets: VecDeque<i64>,
+        requested_max: Vec<u64>,
+    }
+
+    impl JitterSource for ScriptedJitter {
+        fn offset_seconds(&mut self, max_seconds: u64) -> i64 {
+            self.requested_max.push(max_seconds);
+            self.offsets.pop_front().unwrap_or(0)
+        }
+    }
+
+    fn scripted(offsets: &[i64]) -> ScriptedJitter {
+        ScriptedJitter {
+            offsets: offsets.iter().copied().collect(),
+            requested_max: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn next_delay_applies_each_jitter_offset_to_the_interval() {
+        let mut schedule = PollSchedule::new(Duration::from_secs(300), 20, scripted(&[15, -20, 0]));
+
+        let delays = (0..3).map(|_| schedule.next_delay()).collect::<Vec<_>>();
+
+        assert_eq!(
+            delays,
+            vec![
+                Duration::from_secs(315),
+                Duration::from_secs(280),
+                Duration::from_secs(300)
+            ]
+        );
+        assert_eq!(schedule.source.requested_max, vec![20, 20, 20]);
+    }
+
+    #[test]
+    fn next_delay_never_drops_below_the_interval_floor() {
+        let mut schedule = PollSchedule::new(Duration::from_secs(300), 290, scripted(&[-290]));
+        assert_eq!(schedule.next_delay(), Duration::from_secs(30));
+
+        // An interval allowed below the floor by i_know_what_im_doing is its own floor.
+        let mut schedule = PollSchedule::new(Duration::from_secs(10), 9, scripted(&[-9]));
+        assert_eq!(schedule.next_delay(), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn first_delay_waits_a_jittered_cycle_only_after_an_initial_poll() {
+        let mut schedule = PollSchedule::new(Duration::from_secs(60), 5, scripted(&[-5]));
+
+        assert_eq!(schedule.first_delay(false), Duration::ZERO);
+        assert_eq!(schedule.first_delay(true), Duration::from_secs(55));
+    }
+
+    #[test]
+    fn system_jitter_stays_within_bounds() {
+        let mut jitter = SystemJitter::default();
+
+        assert_eq!(jitter.offset_seconds(0), 0);
+        for _ in 0..200 {
+            let offset = jitter.offset_seconds(7);
+            assert!((-7..=7).contains(&offset));
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{
+    app::report::load_events_between,
+    config::Config,
+    domain::events::{EventKind, WatchEvent},
+    ports::{
+        GhClientPort, NotificationPayload, NotifierPort, ReminderStatePort, ReviewReminder,
+        TimelineQueryPort,
+    },
+};
+
+/// A pull request is reminded about at most once in this many hours.
+const REMINDER_INTERVAL_HOURS: i64 = 24;
+
+/// Whether `[reminders]` asks for review request reminders that can actually be sent.
+pub(super) fn review_reminders_enabled(config: &Config) -> bool {
+    config.notifications.enabled && config.reminders.review_request_after_days.is_some()
+}
+
+/// Reminds `viewer_login` of review requests older than `reminders.review_request_after_days`
+/// on pull requests that are still open; returns how many reminders went out.
+pub(super) async fn send_due_review_reminders<C, S, N>(
+    config: &Config,
+    gh: &C,
+    state: &S,
+    notifier: &N,
+    viewer_login: Option<&str>,
+    now: DateTime<Utc>,
+) -> Result<usize>
+where
+    C: GhClientPort,
+    S: TimelineQueryPort + ReminderStatePort,
+    N: NotifierPort,
+{
+    let (Some(after_days), Some(login)) =
+        (config.reminders.review_request_after_days, viewer_login)
+    else {
+        return Ok(0);
+    };
+    if !config.notifications.enabled {
+        return Ok(0);
+    }
+
+    let events = load_events_between(
+        state,
+        now - Duration::days(config.retention_days.into()),
+        None,
+    )?;
+    let mut sent = 0;
+    for request in pending_review_requests(&events, login) {
+        let Some(pr_number) = request.display_number() else {
+            continue;
+        };
+        let waiting = now - request.created_at;
+        if waiting < Duration::days(after_days.into()) {
+            continue;
+        }
+        let reminded_at = state.load_review_reminded_at(&request.repo, pr_number)?;
+        if !reminder_is_due(reminded_at, now) {
+            continue;
+        }
+        match gh.is_pull_request_open(&request.repo, pr_number).await {
+            Ok(true) => {}
+            Ok(false) => continue,
+            Err(err) => {
+                tracing::warn!(
+                    error = %err,
+                    repo = %request.repo,
+                    pr_number,
+                    "pull request state lookup failed"
+                );
+                continue;
+            }
+        }
+
+        let reminder = ReviewReminder {
+            request: request.clone(),
+            waiting_days: waiting.num_days(),
+        };
+        notifier.notify(
+            &NotificationPayload::ReviewReminderDue(Box::new(reminder)),
+            config.notifications.include_url,
+        )?;
+        state.save_review_reminded_at(&request.repo, pr_number, now)?;
+        sent += 1;
+    }
+    Ok(sent)
+}
+
+fn reminder_is_due(reminded_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    reminded_at.is_none_or(|at| now - at >= Duration::hours(REMINDER_INTERVAL_HOURS))
+}
+
+/// The first review request to `login` on each pull request that `login` has not reviewed
+/// since, oldest first.
+pub(super) fn pending_review_requests<'a>(
+    events: &'a [WatchEvent],
+    login: &str,
+) -> Vec<&'a WatchEvent> {
+    let mut events = events.iter().collect::<Vec<_>>();
+    events.sort_by_key(|event| event.created_at);
+
+    let mut pending = HashMap::new();
+    for event in events {
+        let subject = (event.repo.as_str(), event.display_number());
+        match event.kind {
+            EventKind::PrReviewRequested
+                if event
+                    .requested_reviewer
+                    .as_deref()
+                    .is_some_and(|reviewer| reviewer.eq_ignore_ascii_case(login)) =>
+            {
+                pending.entry(subject).or_insert(event);
+            }
+            EventKind::PrReviewSubmitted
+            | EventKind::PrReviewApproved
+            | EventKind::PrReviewChangesRequested
+                if event.actor.eq_ignore_ascii_case(login) =>
+            {
+                pending.remove(&subject);
+            }
+            _ => {}
+        }
+    }
+
+    let mut pending = pending.into_values().collect::<Vec<_>>();
+    pending.sort_by_key(|event| event.created_at);
+    pending
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+
+    use super::{pending_review_requests, reminder_is_due};
+    use crate::domain::events::{EventKind, WatchEvent};
+
+    fn at(day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, day, hour, 0, 0).unwrap()
+    }
+
+    fn event(
+        id: &str,
+        number: i64,
+        kind: EventKind,
+        actor: &str,
+        reviewer: Option<&str>,
+        created_at: DateTime<Utc>,
+    ) -> WatchEvent {
+        WatchEvent {
+            event_id: id.to_string(),
+            repo: "acme/api".to_string(),
+            kind,
+            actor: actor.to_string(),
+            title: "Change".to_string(),
+            url: format!("https://github.com/acme/api/pull/{number}"),
+            created_at,
+            source_item_id: id.to_string(),
+            subject_author: None,
+            requested_reviewer: reviewer.map(str::to_string),
+            mentions: Vec::new(),
+            team_mentions: Vec::new(),
+            subject_number: Some(number),
+            subject_url: None,
+            account: None,
+            body: None,
+        }
+    }
+
+    #[test]
+    fn reminders_are_throttled_to_once_a_day() {
+        let now = at(10, 9);
+
+        assert!(reminder_is_due(None, now));
+        assert!(!reminder_is_due(Some(now - Duration::hours(23)), now));
+        assert!(reminder_is_due(Some(now - Duration::hours(24)), now));
+    }
+
+    #[test]
+    fn review_by_the_viewer_resolves_the_request() {
+        let events = vec![
+            event(
+                "r1",
+                1,
+                EventKind::PrReviewRequested,
+                "bob",
+                Some("Me"),
+                at(1, 9),
+            ),
+            event(
+                "r2",
+                2,
+                EventKind::PrReviewRequested,
+                "bob",
+                Some("me"),
+                at(2, 9),
+            ),
+            event("v2", 2, EventKind::PrReviewApproved, "me", None, at(3, 9)),
+            event(
+                "o1",
+                1,
+                EventKind::PrReviewSubmitted,
+                "carol",
+                None,
+                at(3, 10),
+            ),
+            event(
+                "x3",
+                3,
+                EventKind::PrReviewRequested,
+                "bob",
+                Some("carol"),
+                at(2, 9),
+            ),
+        ];
+
+        let pending = pending_review_requests(&events, "me")
+            .into_iter()
+            .map(|event| event.event_id.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(pending, vec!["r1"]);
+    }
+
+    #[test]
+    fn request_after_the_viewers_review_is_pending_again_from_its_own_time() {
+        let events = vec![
+            event(
+                "r1",
+                1,
+                EventKind::PrReviewRequested,
+                "bob",
+                Some("me"),
+                at(1, 9),
+            ),
+            event(
+                "v1",
+                1,
+                EventKind::PrReviewChangesRequested,
+                "me",
+                None,
+                at(2, 9),
+            ),
+            event(
+                "r1b",
+                1,
+                EventKind::PrReviewRequested,
+                "bob",
+                Some("me"),
+                at(4, 9),
+            ),
+            event(
+                "r1c",
+                1,
+                EventKind::PrReviewRequested,
+                "bob",
+                Some("me"),
+                at(5, 9),
+            ),
+        ];
+
+        let pending = pending_review_requests(&events, "me");
+
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].event_id, "r1b");
+    }
+}
@@ -1,13 +1,43 @@
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
 use anyhow::Result;
-use crossterm::event::Event;
+use crossterm::event::{Event, KeyCode};
 use ratatui::layout::Rect;
 
+use super::{
+    browser::OpenTimedOut,
+    gh_command::{build_gh_pr_command, GhCommand, GhPrAction},
+};
 use crate::{
-    ports::{ClockPort, TimelineReadMarkPort},
-    ui::tui::{handle_input, parse_input, parse_mouse_input, InputCommand, TuiModel},
+    domain::events::WatchEvent,
+    ports::{ClockPort, TimelineQueryPort, TimelineReadMarkPort},
+    ui::tui::{
+        handle_input, parse_input, parse_mouse_input, ActiveTab, ContextMenuAction, FocusedPane,
+        InputCommand, TuiModel, MAX_TIMELINE_HISTORY,
+    },
 };
 
 const ESC_DOUBLE_PRESS_WINDOW_MS: i64 = 1500;
+const HISTORY_PAGE_SIZE: usize = 200;
+const SEARCH_PAGE_SIZE: usize = 200;
+
+pub(super) struct InputActions<'a> {
+    /// Starts opening a URL without waiting for the launcher.
+    pub(super) open_url: &'a dyn Fn(&str) -> Result<()>,
+    /// Queues a gh command; it runs outside the input handler.
+    pub(super) run_gh: &'a dyn Fn(&GhCommand) -> Result<()>,
+    /// `repositories[].local_path` of a watched repository.
+    pub(super) local_path_for: &'a dyn Fn(&str) -> Option<PathBuf>,
+    pub(super) copy_to_clipboard: &'a dyn Fn(&str) -> Result<()>,
+    /// Shows the text in the status line when the clipboard is unavailable, to copy by hand.
+    pub(super) yank_fallback_print: bool,
+    /// Persists the split layout's timeline width percentage.
+    pub(super) save_split_ratio: &'a dyn Fn(u16) -> Result<()>,
+    pub(super) resize_debounce: Duration,
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(super) enum LoopControl {
@@ -23,21 +53,38 @@ pub(super) fn handle_stream_event<S, K>(
     state: &S,
     clock: &K,
     terminal_area: Rect,
-    open_url: &dyn Fn(&str) -> Result<()>,
+    actions: &InputActions<'_>,
 ) -> LoopControl
 where
-    S: TimelineReadMarkPort,
+    S: TimelineQueryPort + TimelineReadMarkPort,
     K: ClockPort,
 {
     match maybe_event {
         Some(Ok(Event::Key(key))) => {
-            let cmd = parse_input(key);
+            if model.search_input.is_some() {
+                return handle_search_prompt_key(key.code, model, state);
+            }
+            let split = model.split_ratio_for(terminal_area.width).is_some();
+            if !split {
+                model.focused_pane = FocusedPane::Timeline;
+            }
+            let cmd = match parse_input(key, &model.keyboard_shortcuts) {
+                InputCommand::NextTab if split => InputCommand::SwitchPane,
+                cmd => cmd,
+            };
+            if model.context_menu.is_some() {
+                return handle_context_menu_key(cmd, model, state, clock, actions);
+            }
             if cmd != InputCommand::EscapePressed {
                 model.esc_armed_until = None;
             }
 
             match cmd {
                 InputCommand::Quit => LoopControl::Quit,
+                InputCommand::EscapePressed if model.has_active_search() => {
+                    model.clear_search();
+                    LoopControl::Redraw
+                }
                 InputCommand::EscapePressed => {
                     let now = clock.now();
                     if model
@@ -53,36 +100,115 @@ where
                     }
                 }
                 InputCommand::Refresh => LoopControl::RequestPoll,
-                InputCommand::OpenSelectedUrl => {
-                    let Some(url) = model
-                        .timeline
-                        .get(model.selected)
-                        .map(|event| event.url.clone())
-                    else {
+                InputCommand::OpenSelectedUrl => open_selected_url(model, state, clock, actions),
+                InputCommand::GhViewSelected => {
+                    let Some(event) = model.timeline.get(model.selected).cloned() else {
                         return LoopControl::Continue;
                     };
 
-                    match open_url(&url) {
-                        Ok(()) => {
-                            model.status_line = format!("opened: {url}");
+                    match build_gh_pr_command(GhPrAction::View, &event, None) {
+                        Some(command) => run_gh(model, actions, &command),
+                        None => open_url(model, actions, &event.url),
+                    }
+                    mark_selected_event_read(model, state, clock);
+                    LoopControl::Redraw
+                }
+                InputCommand::GhCheckoutSelected => {
+                    let Some(event) = model.timeline.get(model.selected).cloned() else {
+                        return LoopControl::Continue;
+                    };
+
+                    let local_path = (actions.local_path_for)(&event.repo);
+                    match build_gh_pr_command(GhPrAction::Checkout, &event, local_path.as_deref()) {
+                        Some(command) if command.cwd.is_some() => run_gh(model, actions, &command),
+                        Some(_) => {
+                            model.status_line = format!(
+                                "gh pr checkout failed: no repositories[].local_path for {}",
+                                event.repo
+                            );
                         }
-                        Err(err) => {
-                            model.status_line = format!("open failed: {err}");
+                        None => {
+                            model.status_line =
+                                format!("gh pr checkout failed: not a pull request: {}", event.url);
                         }
                     }
-                    mark_selected_event_read(model, state, clock);
                     LoopControl::Redraw
                 }
-                InputCommand::ToggleHelp | InputCommand::NextTab | InputCommand::PrevTab => {
+                InputCommand::YankUrl | InputCommand::YankEventKey => {
+                    let Some(text) = model.timeline.get(model.selected).map(|event| {
+                        if cmd == InputCommand::YankUrl {
+                            event.url.clone()
+                        } else {
+                            event.event_key()
+                        }
+                    }) else {
+                        return LoopControl::Continue;
+                    };
+
+                    yank(model, actions, &text);
+                    LoopControl::Redraw
+                }
+                InputCommand::ToggleSplitPane => {
+                    model.toggle_split_pane(terminal_area.width);
+                    LoopControl::Redraw
+                }
+                InputCommand::ToggleHelp
+                | InputCommand::SwitchPane
+                | InputCommand::NextTab
+                | InputCommand::PrevTab
+                | InputCommand::ToggleGroupByRepo
+                | InputCommand::ToggleKindFilter(_)
+                | InputCommand::ClearKindFilter
+                | InputCommand::ToggleHideClosed => {
                     handle_input(model, cmd);
                     LoopControl::Redraw
                 }
+                InputCommand::NarrowSplit | InputCommand::WidenSplit => {
+                    if model.split_ratio_for(terminal_area.width).is_none()
+                        || !model.step_split_ratio(cmd == InputCommand::WidenSplit)
+                    {
+                        return LoopControl::Continue;
+                    }
+                    if let Err(err) = (actions.save_split_ratio)(model.split_ratio()) {
+                        tracing::warn!(error = %err, "failed to persist split ratio");
+                        model.status_line = format!("view state save failed: {err}");
+                    }
+                    LoopControl::Redraw
+                }
+                InputCommand::MarkAllRead if model.active_tab == ActiveTab::Timeline => {
+                    mark_all_events_read(model, state, clock);
+                    LoopControl::Redraw
+                }
+                InputCommand::StartSearch => {
+                    if model.active_tab.supports_timeline_navigation() {
+                        model.search_input = Some(String::new());
+                    }
+                    LoopControl::Redraw
+                }
+                InputCommand::LoadOlderHistory => {
+                    if model.active_tab.supports_timeline_navigation() {
+                        load_older_history(model, state);
+                    }
+                    LoopControl::Redraw
+                }
+                InputCommand::PageDown
+                    if model.active_tab.supports_timeline_navigation()
+                        && model.focused_pane == FocusedPane::Timeline
+                        && model.selected + 1 >= model.timeline.len() =>
+                {
+                    load_older_history(model, state);
+                    handle_input(model, cmd);
+                    mark_selected_event_read(model, state, clock);
+                    LoopControl::Redraw
+                }
                 InputCommand::ScrollUp
                 | InputCommand::ScrollDown
                 | InputCommand::PageUp
                 | InputCommand::PageDown
                 | InputCommand::JumpTop
                 | InputCommand::JumpBottom
+                | InputCommand::NextSearchResult
+                | InputCommand::PrevSearchResult
                 | InputCommand::SelectIndex(_) => {
                     handle_input(model, cmd);
                     if model.active_tab.supports_timeline_navigation() {
@@ -90,16 +216,47 @@ where
                     }
                     LoopControl::Redraw
                 }
-                InputCommand::None => LoopControl::Continue,
+                InputCommand::MarkAllRead
+                | InputCommand::OpenContextMenu { .. }
+                | InputCommand::ContextMenuPick(_)
+                | InputCommand::DismissContextMenu
+                | InputCommand::None => LoopControl::Continue,
             }
         }
         Some(Ok(Event::Mouse(mouse))) => {
-            let cmd = parse_mouse_input(mouse, terminal_area, model);
+            let now = clock.now();
+            let cmd = parse_mouse_input(mouse, terminal_area, model, now);
             match cmd {
+                InputCommand::ScrollUp | InputCommand::ScrollDown
+                    if model.context_menu.is_some() =>
+                {
+                    handle_input(model, cmd);
+                    LoopControl::Redraw
+                }
+                InputCommand::ContextMenuPick(entry) => {
+                    model.context_menu = None;
+                    match ContextMenuAction::ALL.get(entry) {
+                        Some(&action) => {
+                            run_context_menu_action(action, model, state, clock, actions)
+                        }
+                        None => LoopControl::Redraw,
+                    }
+                }
+                InputCommand::OpenContextMenu { .. } | InputCommand::DismissContextMenu => {
+                    handle_input(model, cmd);
+                    LoopControl::Redraw
+                }
+                InputCommand::OpenSelectedUrl => {
+                    model.last_click = None;
+                    open_selected_url(model, state, clock, actions)
+                }
                 InputCommand::ScrollUp
                 | InputCommand::ScrollDown
                 | InputCommand::SelectIndex(_) => {
                     handle_input(model, cmd);
+                    if let InputCommand::SelectIndex(index) = cmd {
+                        model.last_click = Some((index, now));
+                    }
                     if model.active_tab.supports_timeline_navigation() {
                         mark_selected_event_read(model, state, clock);
                     }
@@ -108,7 +265,13 @@ where
                 _ => LoopControl::Continue,
             }
         }
-        Some(Ok(Event::Resize(_, _))) => LoopControl::Redraw,
+        Some(Ok(Event::Resize(width, height))) => {
+            if actions.resize_debounce.is_zero() {
+                return LoopControl::Redraw;
+            }
+            model.defer_resize((width, height), Instant::now(), actions.resize_debounce);
+            LoopControl::Continue
+        }
         Some(Ok(_)) => LoopControl::Continue,
         Some(Err(err)) => {
             tracing::warn!(error = %err, "input stream failed");
@@ -121,6 +284,273 @@ where
     }
 }
 
+/// Keys typed into the `/` prompt edit the query; Enter searches the state db and Esc
+/// closes the prompt without searching.
+fn handle_search_prompt_key<S>(code: KeyCode, model: &mut TuiModel, state: &S) -> LoopControl
+where
+    S: TimelineQueryPort,
+{
+    let Some(query) = model.search_input.as_mut() else {
+        return LoopControl::Continue;
+    };
+    match code {
+        KeyCode::Char(ch) => query.push(ch),
+        KeyCode::Backspace => {
+            query.pop();
+        }
+        KeyCode::Esc => model.search_input = None,
+        KeyCode::Enter => {
+            let query = model.search_input.take().unwrap_or_default();
+            search_history(model, state, &query);
+        }
+        _ => return LoopControl::Continue,
+    }
+    LoopControl::Redraw
+}
+
+/// Arrows move the menu cursor, Enter runs the highlighted entry, and Esc or `q` close it.
+fn handle_context_menu_key<S, K>(
+    cmd: InputCommand,
+    model: &mut TuiModel,
+    state: &S,
+    clock: &K,
+    actions: &InputActions<'_>,
+) -> LoopControl
+where
+    S: TimelineReadMarkPort,
+    K: ClockPort,
+{
+    match cmd {
+        InputCommand::ScrollUp | InputCommand::ScrollDown => {
+            handle_input(model, cmd);
+            LoopControl::Redraw
+        }
+        InputCommand::OpenSelectedUrl => {
+            let action = model.context_menu_action();
+            model.context_menu = None;
+            match action {
+                Some(action) => run_context_menu_action(action, model, state, clock, actions),
+                None => LoopControl::Redraw,
+            }
+        }
+        InputCommand::EscapePressed | InputCommand::Quit => {
+            handle_input(model, InputCommand::DismissContextMenu);
+            LoopControl::Redraw
+        }
+        _ => LoopControl::Continue,
+    }
+}
+
+fn run_context_menu_action<S, K>(
+    action: ContextMenuAction,
+    model: &mut TuiModel,
+    state: &S,
+    clock: &K,
+    actions: &InputActions<'_>,
+) -> LoopControl
+where
+    S: TimelineReadMarkPort,
+    K: ClockPort,
+{
+    match action {
+        ContextMenuAction::Open => open_selected_url(model, state, clock, actions),
+        ContextMenuAction::CopyUrl => {
+            let Some(url) = model
+                .timeline
+                .get(model.selected)
+                .map(|event| event.url.clone())
+            else {
+                return LoopControl::Redraw;
+            };
+
+            yank(model, actions, &url);
+            LoopControl::Redraw
+        }
+        ContextMenuAction::MarkRead => {
+            mark_selected_event_read(model, state, clock);
+            LoopControl::Redraw
+        }
+    }
+}
+
+fn open_selected_url<S, K>(
+    model: &mut TuiModel,
+    state: &S,
+    clock: &K,
+    actions: &InputActions<'_>,
+) -> LoopControl
+where
+    S: TimelineReadMarkPort,
+    K: ClockPort,
+{
+    let Some(url) = model
+        .timeline
+        .get(model.selected)
+        .map(|event| event.url.clone())
+    else {
+        return LoopControl::Continue;
+    };
+
+    open_url(model, actions, &url);
+    mark_selected_event_read(model, state, clock);
+    LoopControl::Redraw
+}
+
+fn yank(model: &mut TuiModel, actions: &InputActions<'_>, text: &str) {
+    match (actions.copy_to_clipboard)(text) {
+        Ok(()) => {
+            model.status_line = format!("copied: {text}");
+        }
+        Err(err) if actions.yank_fallback_print => {
+            model.status_line = format!("{text} (clipboard unavailable: {err})");
+        }
+        Err(err) => {
+            model.status_line = format!("clipboard unavailable: {err}");
+        }
+    }
+}
+
+/// `open_url` only launches; the outcome arrives later through [`finish_open_url`].
+fn open_url(model: &mut TuiModel, actions: &InputActions<'_>, url: &str) {
+    match (actions.open_url)(url) {
+        Ok(()) => {
+            model.status_line = format!("opening: {url}");
+        }
+        Err(err) => finish_open_url(model, url, Err(err)),
+    }
+}
+
+pub(super) fn finish_open_url(model: &mut TuiModel, url: &str, result: Result<()>) {
+    model.status_line = match result {
+        Ok(()) => format!("opened: {url}"),
+        Err(err) if err.downcast_ref::<OpenTimedOut>().is_some() => {
+            format!("open timed out: {err}")
+        }
+        Err(err) => format!("open failed: {err}"),
+    };
+}
+
+fn run_gh(model: &mut TuiModel, actions: &InputActions<'_>, command: &GhCommand) {
+    match (actions.run_gh)(command) {
+        Ok(()) => model.status_line = format!("running: {}", command.display()),
+        Err(err) => finish_run_gh(model, command, Err(err)),
+    }
+}
+
+/// Failures keep gh's stderr in the status line.
+pub(super) fn finish_run_gh(model: &mut TuiModel, command: &GhCommand, result: Result<()>) {
+    match result {
+        Ok(()) => {
+            model.status_line = format!("ran: {}", command.display());
+            model.status_notice = Some(model.status_line.clone());
+        }
+        Err(err) => {
+            model.status_line = format!("{err:#}");
+        }
+    }
+}
+
+/// Pages older events from the store into the session timeline; nothing is persisted.
+fn load_older_history<S>(model: &mut TuiModel, state: &S)
+where
+    S: TimelineQueryPort,
+{
+    let capacity = model.history_capacity_left().min(HISTORY_PAGE_SIZE);
+    if capacity == 0 {
+        model.status_notice = Some(format!(
+            "history limit reached ({MAX_TIMELINE_HISTORY} events)"
+        ));
+        return;
+    }
+    let Some(before) = model.oldest_loaded_at() else {
+        return;
+    };
+
+    let result = state
+        .load_timeline_events_before(before, capacity)
+        .and_then(|events| {
+            let keys = events.iter().map(WatchEvent::event_key).collect::<Vec<_>>();
+            Ok((events, state.load_read_event_keys(&keys)?))
+        });
+    match result {
+        Ok((events, read_event_keys)) => {
+            model.extend_read_event_keys(read_event_keys);
+            let added = model.append_history(events);
+            model.status_notice = Some(if added == 0 {
+                "no older events".to_string()
+            } else {
+                format!("loaded {added} older events")
+            });
+        }
+        Err(err) => {
+            tracing::warn!(error = %err, "failed to load older events");
+            model.status_line = format!("history load failed: {err}");
+        }
+    }
+}
+
+/// Loads the stored events matching `query` into the session timeline, so the search
+/// reaches past the events held in memory; nothing is persisted.
+fn search_history<S>(model: &mut TuiModel, state: &S, query: &str)
+where
+    S: TimelineQueryPort,
+{
+    if query.trim().is_empty() {
+        return;
+    }
+
+    let result = state
+        .search_events(query, SEARCH_PAGE_SIZE)
+        .and_then(|events| {
+            let keys = events.iter().map(WatchEvent::event_key).collect::<Vec<_>>();
+            Ok((events, state.load_read_event_keys(&keys)?))
+        });
+    match result {
+        Ok((events, read_event_keys)) => {
+            model.extend_read_event_keys(read_event_keys);
+            let held = model.apply_search_matches(events);
+            model.status_notice = Some(if held == 0 {
+                format!("no events matched: {query}")
+            } else {
+                format!("{held} events matched: {query}")
+            });
+        }
+        Err(err) => {
+            tracing::warn!(error = %err, "failed to search stored events");
+            model.status_line = format!("search failed: {err}");
+        }
+    }
+}
+
+/// Marks every stored event read, then reloads the read keys of the loaded timeline so rows
+/// marked elsewhere in the meantime show up read as well.
+fn mark_all_events_read<S, K>(model: &mut TuiModel, state: &S, clock: &K)
+where
+    S: TimelineQueryPort + TimelineReadMarkPort,
+    K: ClockPort,
+{
+    let result = state
+        .mark_all_timeline_events_read(clock.now())
+        .and_then(|marked| {
+            let keys = model
+                .all_events()
+                .iter()
+                .map(WatchEvent::event_key)
+                .collect::<Vec<_>>();
+            Ok((marked, state.load_read_event_keys(&keys)?))
+        });
+    match result {
+        Ok((marked, read_event_keys)) => {
+            model.replace_read_event_keys(read_event_keys);
+            model.status_line = format!("marked {marked} events read");
+        }
+        Err(err) => {
+            tracing::warn!(error = %err, "failed to mark all events read");
+            model.status_line = format!("read mark failed: {err}");
+        }
+    }
+}
+
 pub(super) fn mark_selected_event_read<S, K>(model: &mut TuiModel, state: &S, clock: &K)
 where
     S: TimelineReadMarkPort,
@@ -1,4 +1,9 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
 use anyhow::{anyhow, Result};
 use chrono::{TimeZone, Utc};
@@ -7,17 +12,19 @@ use crossterm::event::{
 };
 use ratatui::layout::Rect;
 
-use super::{handle_stream_event, LoopControl};
+use super::{finish_open_url, finish_run_gh, handle_stream_event, InputActions, LoopControl};
+use crate::app::watch_loop::{browser::OpenTimedOut, gh_command::GhCommand};
 use crate::{
     domain::events::{EventKind, WatchEvent},
-    ports::{ClockPort, TimelineReadMarkPort},
-    ui::tui::{ActiveTab, TuiModel},
+    ports::{ClockPort, RepoReadCounts, TimelineQueryPort, TimelineReadMarkPort},
+    ui::tui::{ActiveTab, FocusedPane, TuiModel, MAX_SPLIT_RATIO},
 };
 
 #[derive(Clone, Default)]
 struct FakeState {
     marked_read_event_keys: Arc<Mutex<Vec<String>>>,
     fail_mark_read: Arc<Mutex<bool>>,
+    stored_events: Arc<Mutex<Vec<WatchEvent>>>,
 }
 
 impl FakeState {
@@ -28,6 +35,124 @@ impl FakeState {
     fn set_mark_read_error(&self, should_fail: bool) {
         *self.fail_mark_read.lock().unwrap() = should_fail;
     }
+
+    fn set_stored_events(&self, events: Vec<WatchEvent>) {
+        *self.stored_events.lock().unwrap() = events;
+    }
+}
+
+impl TimelineQueryPort for FakeState {
+    fn load_timeline_events(&self, limit: usize) -> Result<Vec<WatchEvent>> {
+        let mut events = self.stored_events.lock().unwrap().clone();
+        events.sort_by_key(|event| std::cmp::Reverse(event.created_at));
+        events.truncate(limit);
+        Ok(events)
+    }
+
+    fn load_timeline_events_before(
+        &self,
+        before: chrono::DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<WatchEvent>> {
+        let mut events = self.load_timeline_events(usize::MAX)?;
+        events.retain(|event| event.created_at <= before);
+        events.truncate(limit);
+        Ok(events)
+    }
+
+    fn find_events_page(
+        &self,
+        since: chrono::DateTime<Utc>,
+        before: Option<chrono::DateTime<Utc>>,
+        limit: usize,
+    ) -> Result<Vec<WatchEvent>> {
+        let mut events = self.load_timeline_events(usize::MAX)?;
+        events.retain(|event| {
+            event.created_at >= since && before.is_none_or(|before| event.created_at < before)
+        });
+        events.truncate(limit);
+        Ok(events)
+    }
+
+    fn load_timeline_events_filtered(
+        &self,
+        since: chrono::DateTime<Utc>,
+        before: Option<chrono::DateTime<Utc>>,
+        repos: &[String],
+        actors: &[String],
+        limit: usize,
+    ) -> Result<Vec<WatchEvent>> {
+        let mut events = self.find_events_page(since, before, usize::MAX)?;
+        events.retain(|event| {
+            (repos.is_empty() || repos.contains(&event.repo))
+                && (actors.is_empty()
+                    || actors
+                        .iter()
+                        .any(|actor| actor.eq_ignore_ascii_case(&event.actor)))
+        });
+        events.truncate(limit);
+        Ok(events)
+    }
+
+    fn search_events(&self, query: &str, limit: usize) -> Result<Vec<WatchEvent>> {
+        let terms = query
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>();
+        let mut events = self.load_timeline_events(usize::MAX)?;
+        events.retain(|event| {
+            let text = format!(
+                "{} {} {} {}",
+                event.repo,
+                event.actor,
+                event.title,
+                event.body.as_deref().unwrap_or_default()
+            )
+            .to_lowercase();
+            !terms.is_empty() && terms.iter().all(|term| text.contains(term.as_str()))
+        });
+        events.truncate(limit);
+        Ok(events)
+    }
+
+    fn load_read_event_keys(&self, event_keys: &[String]) -> Result<HashSet<String>> {
+        let marked = self.marked_read_event_keys.lock().unwrap();
+        Ok(event_keys
+            .iter()
+            .filter(|key| marked.contains(key))
+            .cloned()
+            .collect())
+    }
+
+    fn get_event_by_key(&self, event_key: &str) -> Result<Option<WatchEvent>> {
+        Ok(self
+            .stored_events
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|event| event.event_key() == event_key)
+            .cloned())
+    }
+
+    fn get_events_by_keys(&self, event_keys: &[String]) -> Result<Vec<WatchEvent>> {
+        let mut seen = HashSet::new();
+        let mut events = Vec::new();
+        for key in event_keys {
+            if seen.insert(key.as_str()) {
+                events.extend(self.get_event_by_key(key)?);
+            }
+        }
+        Ok(events)
+    }
+
+    fn count_read_events_by_repo(
+        &self,
+        _since: chrono::DateTime<Utc>,
+        _repos: &[String],
+        _actors: &[String],
+    ) -> Result<Vec<RepoReadCounts>> {
+        Ok(Vec::new())
+    }
 }
 
 impl TimelineReadMarkPort for FakeState {
@@ -45,6 +170,22 @@ impl TimelineReadMarkPort for FakeState {
             .push(event_key.to_string());
         Ok(())
     }
+
+    fn mark_all_timeline_events_read(&self, _read_at: chrono::DateTime<Utc>) -> Result<usize> {
+        if *self.fail_mark_read.lock().unwrap() {
+            return Err(anyhow!("state store down"));
+        }
+        let mut marked = self.marked_read_event_keys.lock().unwrap();
+        let mut count = 0;
+        for event in self.stored_events.lock().unwrap().iter() {
+            let key = event.event_key();
+            if !marked.contains(&key) {
+                marked.push(key);
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
 }
 
 struct FixedClock {
@@ -57,6 +198,8 @@ impl ClockPort for FixedClock {
     }
 }
 
+const RESIZE_DEBOUNCE: Duration = Duration::from_millis(50);
+
 fn test_area() -> Rect {
     Rect::new(0, 0, 120, 40)
 }
@@ -69,6 +212,38 @@ fn open_ok(_url: &str) -> Result<()> {
     Ok(())
 }
 
+fn clipboard_ok(_text: &str) -> Result<()> {
+    Ok(())
+}
+
+fn clipboard_missing(_text: &str) -> Result<()> {
+    Err(anyhow!("no display"))
+}
+
+fn gh_ok(_command: &GhCommand) -> Result<()> {
+    Ok(())
+}
+
+fn no_local_path(_repo: &str) -> Option<PathBuf> {
+    None
+}
+
+fn save_split_ratio_ok(_percent: u16) -> Result<()> {
+    Ok(())
+}
+
+fn actions<'a>(open_url: &'a dyn Fn(&str) -> Result<()>) -> InputActions<'a> {
+    InputActions {
+        open_url,
+        run_gh: &gh_ok,
+        local_path_for: &no_local_path,
+        copy_to_clipboard: &clipboard_ok,
+        yank_fallback_print: false,
+        save_split_ratio: &save_split_ratio_ok,
+        resize_debounce: RESIZE_DEBOUNCE,
+    }
+}
+
 fn timeline_event(id: &str, created_at: chrono::DateTime<Utc>) -> WatchEvent {
     WatchEvent {
         event_id: id.to_string(),
@@ -82,6 +257,11 @@ fn timeline_event(id: &str, created_at: chrono::DateTime<Utc>) -> WatchEvent {
         subject_author: Some("dev".to_string()),
         requested_reviewer: None,
         mentions: Vec::new(),
+        team_mentions: Vec::new(),
+        subject_number: None,
+        subject_url: None,
+        account: None,
+        body: None,
     }
 }
 
@@ -102,7 +282,7 @@ fn stream_error_sets_status_and_redraws() {
         &state,
         &clock,
         test_area(),
-        &open_ok,
+        &actions(&open_ok),
     );
 
     assert_eq!(control, LoopControl::Redraw);
@@ -126,13 +306,59 @@ fn enter_open_failure_updates_status_and_requests_redraw() {
         &state,
         &clock,
         test_area(),
-        &open_fail,
+        &actions(&open_fail),
     );
 
     assert_eq!(control, LoopControl::Redraw);
     assert_eq!(model.status_line, "open failed: launcher missing");
 }
 
+#[test]
+fn enter_reports_opening_until_the_launcher_finishes() {
+    let state = FakeState::default();
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 9, 0, 0, 0).unwrap(),
+    };
+    let mut model = TuiModel::new(10);
+    model.timeline = vec![timeline_event("ev-open", clock.now)];
+
+    let key = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+    handle_stream_event(
+        Some(Ok(Event::Key(key))),
+        &mut model,
+        &state,
+        &clock,
+        test_area(),
+        &actions(&open_ok),
+    );
+    assert_eq!(model.status_line, "opening: https://example.com/ev-open");
+
+    finish_open_url(&mut model, "https://example.com/ev-open", Ok(()));
+    assert_eq!(model.status_line, "opened: https://example.com/ev-open");
+}
+
+#[test]
+fn open_timeout_is_reported_apart_from_launch_failure() {
+    let mut model = TuiModel::new(10);
+    let url = "https://example.com/hung";
+
+    finish_open_url(
+        &mut model,
+        url,
+        Err(anyhow::Error::new(OpenTimedOut {
+            launcher: "$BROWSER".to_string(),
+            after: Duration::from_secs(5),
+        })),
+    );
+    assert_eq!(
+        model.status_line,
+        "open timed out: $BROWSER did not exit within 5s (left running)"
+    );
+
+    finish_open_url(&mut model, url, Err(anyhow!("launcher missing")));
+    assert_eq!(model.status_line, "open failed: launcher missing");
+}
+
 #[test]
 fn mouse_selection_marks_selected_event_as_read() {
     let state = FakeState::default();
@@ -157,7 +383,7 @@ fn mouse_selection_marks_selected_event_as_read() {
         &state,
         &clock,
         test_area(),
-        &open_ok,
+        &actions(&open_ok),
     );
 
     assert_eq!(control, LoopControl::Redraw);
@@ -188,7 +414,7 @@ fn read_mark_failure_keeps_event_unread_and_sets_status() {
         &state,
         &clock,
         test_area(),
-        &open_ok,
+        &actions(&open_ok),
     );
 
     assert_eq!(control, LoopControl::Redraw);
@@ -220,7 +446,7 @@ fn navigation_on_my_pr_tab_marks_selected_event_as_read() {
         &state,
         &clock,
         test_area(),
-        &open_ok,
+        &actions(&open_ok),
     );
 
     assert_eq!(control, LoopControl::Redraw);
@@ -230,3 +456,817 @@ fn navigation_on_my_pr_tab_marks_selected_event_as_read() {
         vec![model.timeline[1].event_key()]
     );
 }
+
+#[test]
+fn y_copies_selected_url_and_sets_status() {
+    let state = FakeState::default();
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 9, 0, 0, 0).unwrap(),
+    };
+    let mut model = TuiModel::new(10);
+    model.timeline = vec![timeline_event("ev-yank", clock.now)];
+    let copied = Mutex::new(Vec::new());
+    let copy = |text: &str| -> Result<()> {
+        copied.lock().unwrap().push(text.to_string());
+        Ok(())
+    };
+
+    let key = KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE);
+    let control = handle_stream_event(
+        Some(Ok(Event::Key(key))),
+        &mut model,
+        &state,
+        &clock,
+        test_area(),
+        &InputActions {
+            open_url: &open_ok,
+            run_gh: &gh_ok,
+            local_path_for: &no_local_path,
+            copy_to_clipboard: &copy,
+            yank_fallback_print: false,
+            save_split_ratio: &save_split_ratio_ok,
+            resize_debounce: RESIZE_DEBOUNCE,
+        },
+    );
+
+    assert_eq!(control, LoopControl::Redraw);
+    assert_eq!(
+        *copied.lock().unwrap(),
+        vec!["https://example.com/ev-yank".to_string()]
+    );
+    assert_eq!(model.status_line, "copied: https://example.com/ev-yank");
+    assert!(state.marked_read_event_keys().is_empty());
+}
+
+#[test]
+fn shift_y_copies_selected_event_key() {
+    let state = FakeState::default();
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 9, 0, 0, 0).unwrap(),
+    };
+    let mut model = TuiModel::new(10);
+    let event = timeline_event("ev-yank-key", clock.now);
+    let event_key = event.event_key();
+    model.timeline = vec![event];
+
+    let key = KeyEvent::new(KeyCode::Char('Y'), KeyModifiers::SHIFT);
+    let control = handle_stream_event(
+        Some(Ok(Event::Key(key))),
+        &mut model,
+        &state,
+        &clock,
+        test_area(),
+        &actions(&open_ok),
+    );
+
+    assert_eq!(control, LoopControl::Redraw);
+    assert_eq!(model.status_line, format!("copied: {event_key}"));
+}
+
+#[test]
+fn yank_without_clipboard_sets_status_and_skips_fallback_by_default() {
+    let state = FakeState::default();
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 9, 0, 0, 0).unwrap(),
+    };
+    let mut model = TuiModel::new(10);
+    model.timeline = vec![timeline_event("ev-headless", clock.now)];
+
+    let key = KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE);
+    let control = handle_stream_event(
+        Some(Ok(Event::Key(key))),
+        &mut model,
+        &state,
+        &clock,
+        test_area(),
+        &InputActions {
+            open_url: &open_ok,
+            run_gh: &gh_ok,
+            local_path_for: &no_local_path,
+            copy_to_clipboard: &clipboard_missing,
+            yank_fallback_print: false,
+            save_split_ratio: &save_split_ratio_ok,
+            resize_debounce: RESIZE_DEBOUNCE,
+        },
+    );
+
+    assert_eq!(control, LoopControl::Redraw);
+    assert_eq!(model.status_line, "clipboard unavailable: no display");
+}
+
+#[test]
+fn yank_without_clipboard_shows_the_text_in_the_status_line_when_enabled() {
+    let state = FakeState::default();
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 9, 0, 0, 0).unwrap(),
+    };
+    let mut model = TuiModel::new(10);
+    model.timeline = vec![timeline_event("ev-fallback", clock.now)];
+
+    let key = KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE);
+    handle_stream_event(
+        Some(Ok(Event::Key(key))),
+        &mut model,
+        &state,
+        &clock,
+        test_area(),
+        &InputActions {
+            open_url: &open_ok,
+            run_gh: &gh_ok,
+            local_path_for: &no_local_path,
+            copy_to_clipboard: &clipboard_missing,
+            yank_fallback_print: true,
+            save_split_ratio: &save_split_ratio_ok,
+            resize_debounce: RESIZE_DEBOUNCE,
+        },
+    );
+
+    assert_eq!(
+        model.status_line,
+        "https://example.com/ev-fallback (clipboard unavailable: no display)"
+    );
+}
+
+#[test]
+fn yank_with_empty_timeline_is_ignored() {
+    let state = FakeState::default();
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 9, 0, 0, 0).unwrap(),
+    };
+    let mut model = TuiModel::new(10);
+
+    let key = KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE);
+    let control = handle_stream_event(
+        Some(Ok(Event::Key(key))),
+        &mut model,
+        &state,
+        &clock,
+        test_area(),
+        &actions(&open_ok),
+    );
+
+    assert_eq!(control, LoopControl::Continue);
+}
+
+#[test]
+fn page_down_at_bottom_loads_older_events_beyond_timeline_limit() {
+    let state = FakeState::default();
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 10, 0, 0, 0).unwrap(),
+    };
+    let stored = (0..5)
+        .map(|idx| {
+            timeline_event(
+                &format!("ev-{idx}"),
+                clock.now - chrono::Duration::hours(idx),
+            )
+        })
+        .collect::<Vec<_>>();
+    state.set_stored_events(stored.clone());
+    let mut model = TuiModel::new(2);
+    model.replace_timeline(state.load_timeline_events(2).unwrap());
+    model.selected = 1;
+    model.sync_selected_event_key();
+
+    let page_down = KeyEvent::new(KeyCode::PageDown, KeyModifiers::NONE);
+    let control = handle_stream_event(
+        Some(Ok(Event::Key(page_down))),
+        &mut model,
+        &state,
+        &clock,
+        test_area(),
+        &actions(&open_ok),
+    );
+
+    assert_eq!(control, LoopControl::Redraw);
+    assert_eq!(model.timeline.len(), 5);
+    assert_eq!(model.selected, 2);
+    assert_eq!(
+        model.status_notice.as_deref(),
+        Some("loaded 3 older events")
+    );
+
+    let load_more = KeyEvent::new(KeyCode::Char('L'), KeyModifiers::SHIFT);
+    handle_stream_event(
+        Some(Ok(Event::Key(load_more))),
+        &mut model,
+        &state,
+        &clock,
+        test_area(),
+        &actions(&open_ok),
+    );
+    assert_eq!(model.timeline.len(), 5);
+    assert_eq!(model.status_notice.as_deref(), Some("no older events"));
+
+    model.push_timeline(vec![timeline_event(
+        "ev-new",
+        clock.now + chrono::Duration::minutes(1),
+    )]);
+    assert_eq!(model.timeline.len(), 5);
+    assert_eq!(model.timeline[0].event_id, "ev-new");
+}
+
+#[test]
+fn rapid_resizes_coalesce_into_one_redraw_at_the_last_size() {
+    let state = FakeState::default();
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 10, 0, 0, 0).unwrap(),
+    };
+    let mut model = TuiModel::new(10);
+
+    for size in [(100, 30), (110, 32), (120, 40)] {
+        let control = handle_stream_event(
+            Some(Ok(Event::Resize(size.0, size.1))),
+            &mut model,
+            &state,
+            &clock,
+            test_area(),
+            &actions(&open_ok),
+        );
+        assert_eq!(control, LoopControl::Continue);
+    }
+    assert_eq!(model.pending_resize, Some((120, 40)));
+
+    let deadline = model.resize_deadline.expect("resize must be pending");
+    assert_eq!(
+        model.take_due_resize(deadline - Duration::from_millis(1)),
+        None
+    );
+    assert_eq!(model.take_due_resize(deadline), Some((120, 40)));
+    assert_eq!(model.take_due_resize(deadline), None);
+    assert!(model.resize_deadline.is_none());
+}
+
+#[test]
+fn single_resize_passes_through_after_the_debounce_window() {
+    let mut model = TuiModel::new(10);
+    let start = Instant::now();
+
+    model.defer_resize((80, 24), start, RESIZE_DEBOUNCE);
+
+    assert_eq!(model.take_due_resize(start), None);
+    assert_eq!(
+        model.take_due_resize(start + RESIZE_DEBOUNCE),
+        Some((80, 24))
+    );
+}
+
+#[test]
+fn zero_debounce_redraws_immediately() {
+    let state = FakeState::default();
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 10, 0, 0, 0).unwrap(),
+    };
+    let mut model = TuiModel::new(10);
+    let actions = InputActions {
+        resize_debounce: Duration::ZERO,
+        ..actions(&open_ok)
+    };
+
+    let control = handle_stream_event(
+        Some(Ok(Event::Resize(80, 24))),
+        &mut model,
+        &state,
+        &clock,
+        test_area(),
+        &actions,
+    );
+
+    assert_eq!(control, LoopControl::Redraw);
+    assert!(model.pending_resize.is_none());
+}
+
+fn pr_event(id: &str, created_at: chrono::DateTime<Utc>) -> WatchEvent {
+    WatchEvent {
+        kind: EventKind::PrCreated,
+        url: "https://github.com/acme/api/pull/42".to_string(),
+        ..timeline_event(id, created_at)
+    }
+}
+
+fn press(
+    key: char,
+    model: &mut TuiModel,
+    state: &FakeState,
+    actions: &InputActions<'_>,
+) -> LoopControl {
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 9, 0, 0, 0).unwrap(),
+    };
+    let key = KeyEvent::new(KeyCode::Char(key), KeyModifiers::NONE);
+    handle_stream_event(
+        Some(Ok(Event::Key(key))),
+        model,
+        state,
+        &clock,
+        test_area(),
+        actions,
+    )
+}
+
+#[test]
+fn x_views_pr_with_gh_and_falls_back_to_browser_for_other_urls() {
+    let state = FakeState::default();
+    let now = Utc.with_ymd_and_hms(2025, 1, 9, 0, 0, 0).unwrap();
+    let mut model = TuiModel::new(10);
+    model.timeline = vec![pr_event("ev-pr", now), timeline_event("ev-issue", now)];
+    let ran = Mutex::new(Vec::new());
+    let run_gh = |command: &GhCommand| -> Result<()> {
+        ran.lock().unwrap().push(command.clone());
+        Ok(())
+    };
+    let opened = Mutex::new(Vec::new());
+    let open_url = |url: &str| -> Result<()> {
+        opened.lock().unwrap().push(url.to_string());
+        Ok(())
+    };
+    let actions = InputActions {
+        open_url: &open_url,
+        run_gh: &run_gh,
+        ..actions(&open_ok)
+    };
+
+    assert_eq!(
+        press('x', &mut model, &state, &actions),
+        LoopControl::Redraw
+    );
+    let ran = ran.lock().unwrap().clone();
+    assert_eq!(ran.len(), 1);
+    assert_eq!(ran[0].display(), "gh pr view 42 --repo acme/api --web");
+    assert_eq!(
+        model.status_line,
+        "running: gh pr view 42 --repo acme/api --web"
+    );
+    finish_run_gh(&mut model, &ran[0], Ok(()));
+    assert_eq!(
+        model.status_notice.as_deref(),
+        Some("ran: gh pr view 42 --repo acme/api --web")
+    );
+    assert!(opened.lock().unwrap().is_empty());
+
+    model.selected = 1;
+    press('x', &mut model, &state, &actions);
+    assert_eq!(
+        *opened.lock().unwrap(),
+        vec!["https://example.com/ev-issue".to_string()]
+    );
+    assert_eq!(state.marked_read_event_keys().len(), 2);
+}
+
+#[test]
+fn c_checks_out_pr_in_repo_local_path() {
+    let state = FakeState::default();
+    let now = Utc.with_ymd_and_hms(2025, 1, 9, 0, 0, 0).unwrap();
+    let mut model = TuiModel::new(10);
+    model.timeline = vec![pr_event("ev-pr", now)];
+    let ran = Mutex::new(Vec::new());
+    let run_gh = |command: &GhCommand| -> Result<()> {
+        ran.lock().unwrap().push(command.clone());
+        Ok(())
+    };
+    let local_path_for = |repo: &str| (repo == "acme/api").then(|| PathBuf::from("/src/api"));
+    let actions = InputActions {
+        run_gh: &run_gh,
+        local_path_for: &local_path_for,
+        ..actions(&open_ok)
+    };
+
+    press('c', &mut model, &state, &actions);
+
+    let ran = ran.lock().unwrap().clone();
+    assert_eq!(ran.len(), 1);
+    assert_eq!(ran[0].display(), "gh pr checkout 42 --repo acme/api");
+    assert_eq!(ran[0].cwd.as_deref(), Some(Path::new("/src/api")));
+}
+
+#[test]
+fn c_reports_missing_local_path_non_pr_and_gh_stderr() {
+    let state = FakeState::default();
+    let now = Utc.with_ymd_and_hms(2025, 1, 9, 0, 0, 0).unwrap();
+    let mut model = TuiModel::new(10);
+    model.timeline = vec![pr_event("ev-pr", now), timeline_event("ev-issue", now)];
+
+    press('c', &mut model, &state, &actions(&open_ok));
+    assert_eq!(
+        model.status_line,
+        "gh pr checkout failed: no repositories[].local_path for acme/api"
+    );
+
+    let local_path_for = |_repo: &str| Some(PathBuf::from("/src/api"));
+    let run_gh = |command: &GhCommand| -> Result<()> {
+        Err(anyhow!(
+            "{} failed: uncommitted changes would be overwritten",
+            command.display()
+        ))
+    };
+    let failing = InputActions {
+        run_gh: &run_gh,
+        local_path_for: &local_path_for,
+        ..actions(&open_ok)
+    };
+    press('c', &mut model, &state, &failing);
+    assert_eq!(
+        model.status_line,
+        "gh pr checkout 42 --repo acme/api failed: uncommitted changes would be overwritten"
+    );
+
+    model.selected = 1;
+    press('c', &mut model, &state, &failing);
+    assert_eq!(
+        model.status_line,
+        "gh pr checkout failed: not a pull request: https://example.com/ev-issue"
+    );
+}
+
+fn mouse_at(
+    button: MouseButton,
+    row: u16,
+    model: &mut TuiModel,
+    state: &FakeState,
+    now: chrono::DateTime<Utc>,
+    actions: &InputActions<'_>,
+) -> LoopControl {
+    let mouse = MouseEvent {
+        kind: MouseEventKind::Down(button),
+        column: 2,
+        row,
+        modifiers: KeyModifiers::NONE,
+    };
+    handle_stream_event(
+        Some(Ok(Event::Mouse(mouse))),
+        model,
+        state,
+        &FixedClock { now },
+        test_area(),
+        actions,
+    )
+}
+
+#[test]
+fn double_click_opens_the_row_using_the_injected_clock() {
+    let state = FakeState::default();
+    let now = Utc.with_ymd_and_hms(2025, 1, 9, 0, 0, 0).unwrap();
+    let mut model = TuiModel::new(10);
+    model.timeline = vec![timeline_event("ev-1", now), timeline_event("ev-2", now)];
+    let opened = Mutex::new(Vec::new());
+    let open_url = |url: &str| -> Result<()> {
+        opened.lock().unwrap().push(url.to_string());
+        Ok(())
+    };
+    let actions = actions(&open_url);
+
+    mouse_at(MouseButton::Left, 9, &mut model, &state, now, &actions);
+    let later = now + chrono::Duration::milliseconds(600);
+    mouse_at(MouseButton::Left, 9, &mut model, &state, later, &actions);
+    assert!(opened.lock().unwrap().is_empty());
+
+    let control = mouse_at(
+        MouseButton::Left,
+        9,
+        &mut model,
+        &state,
+        later + chrono::Duration::milliseconds(300),
+        &actions,
+    );
+    assert_eq!(control, LoopControl::Redraw);
+    assert_eq!(
+        *opened.lock().unwrap(),
+        vec!["https://example.com/ev-2".to_string()]
+    );
+    assert_eq!(model.status_line, "opening: https://example.com/ev-2");
+    assert_eq!(model.last_click, None);
+}
+
+#[test]
+fn context_menu_runs_the_highlighted_entry_on_enter() {
+    let state = FakeState::default();
+    let now = Utc.with_ymd_and_hms(2025, 1, 9, 0, 0, 0).unwrap();
+    let mut model = TuiModel::new(10);
+    model.timeline = vec![timeline_event("ev-1", now), timeline_event("ev-2", now)];
+    let actions = actions(&open_fail);
+
+    mouse_at(MouseButton::Right, 9, &mut model, &state, now, &actions);
+    assert_eq!(model.selected, 1);
+    assert!(model.context_menu.is_some());
+    assert!(state.marked_read_event_keys().is_empty());
+
+    press('j', &mut model, &state, &actions);
+    let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+    let control = handle_stream_event(
+        Some(Ok(Event::Key(enter))),
+        &mut model,
+        &state,
+        &FixedClock { now },
+        test_area(),
+        &actions,
+    );
+    assert_eq!(control, LoopControl::Redraw);
+    assert_eq!(model.context_menu, None);
+    assert_eq!(model.status_line, "copied: https://example.com/ev-2");
+
+    mouse_at(MouseButton::Right, 8, &mut model, &state, now, &actions);
+    assert_eq!(
+        press('q', &mut model, &state, &actions),
+        LoopControl::Redraw,
+        "q closes the menu instead of quitting"
+    );
+    assert_eq!(model.context_menu, None);
+    assert!(state.marked_read_event_keys().is_empty());
+}
+
+fn press_in(
+    key: char,
+    area: Rect,
+    model: &mut TuiModel,
+    state: &FakeState,
+    actions: &InputActions<'_>,
+) -> LoopControl {
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 9, 0, 0, 0).unwrap(),
+    };
+    let key = KeyEvent::new(KeyCode::Char(key), KeyModifiers::NONE);
+    handle_stream_event(
+        Some(Ok(Event::Key(key))),
+        model,
+        state,
+        &clock,
+        area,
+        actions,
+    )
+}
+
+#[test]
+fn brackets_step_the_split_ratio_and_persist_it_only_when_split() {
+    let state = FakeState::default();
+    let mut model = TuiModel::new(10);
+    let saved = Mutex::new(Vec::new());
+    let save_split_ratio = |percent: u16| -> Result<()> {
+        saved.lock().unwrap().push(percent);
+        Ok(())
+    };
+    let actions = InputActions {
+        save_split_ratio: &save_split_ratio,
+        ..actions(&open_ok)
+    };
+    let wide = Rect::new(0, 0, 200, 40);
+
+    assert_eq!(
+        press_in(']', test_area(), &mut model, &state, &actions),
+        LoopControl::Continue,
+        "a 120-column terminal stays stacked"
+    );
+    assert_eq!(
+        press_in(']', wide, &mut model, &state, &actions),
+        LoopControl::Redraw
+    );
+    press_in('[', wide, &mut model, &state, &actions);
+    press_in('[', wide, &mut model, &state, &actions);
+    assert_eq!(*saved.lock().unwrap(), vec![65, 60, 55]);
+
+    model.set_split_ratio(MAX_SPLIT_RATIO);
+    assert_eq!(
+        press_in(']', wide, &mut model, &state, &actions),
+        LoopControl::Continue
+    );
+    assert_eq!(saved.lock().unwrap().len(), 3);
+}
+
+#[test]
+fn mouse_hit_testing_follows_the_split_layout() {
+    let state = FakeState::default();
+    let now = Utc.with_ymd_and_hms(2025, 1, 9, 0, 0, 0).unwrap();
+    let mut model = TuiModel::new(10);
+    model.timeline = vec![timeline_event("ev-1", now), timeline_event("ev-2", now)];
+    let wide = Rect::new(0, 0, 200, 40);
+    let click = |column| MouseEvent {
+        kind: MouseEventKind::Down(MouseButton::Left),
+        column,
+        row: 9,
+        modifiers: KeyModifiers::NONE,
+    };
+    let clock = FixedClock { now };
+
+    handle_stream_event(
+        Some(Ok(Event::Mouse(click(150)))),
+        &mut model,
+        &state,
+        &clock,
+        wide,
+        &actions(&open_ok),
+    );
+    assert_eq!(model.selected, 0, "the detail pane is not the timeline");
+
+    handle_stream_event(
+        Some(Ok(Event::Mouse(click(100)))),
+        &mut model,
+        &state,
+        &clock,
+        wide,
+        &actions(&open_ok),
+    );
+    assert_eq!(model.selected, 1);
+}
+
+#[test]
+fn backslash_toggles_the_split_layout_and_tab_moves_focus_within_it() {
+    let state = FakeState::default();
+    let now = Utc.with_ymd_and_hms(2025, 1, 9, 0, 0, 0).unwrap();
+    let mut model = TuiModel::new(10);
+    model.replace_timeline(vec![
+        timeline_event("ev-1", now),
+        timeline_event("ev-2", now),
+    ]);
+    let clock = FixedClock { now };
+    let press = |code: KeyCode, model: &mut TuiModel| {
+        let key = KeyEvent::new(code, KeyModifiers::NONE);
+        handle_stream_event(
+            Some(Ok(Event::Key(key))),
+            model,
+            &state,
+            &clock,
+            test_area(),
+            &actions(&open_ok),
+        )
+    };
+
+    press(KeyCode::Tab, &mut model);
+    assert_eq!(
+        model.active_tab,
+        ActiveTab::MyPr,
+        "stacked: Tab switches tabs"
+    );
+    model.set_active_tab(ActiveTab::Timeline);
+
+    press(KeyCode::Char('\\'), &mut model);
+    assert!(model.split_ratio_for(test_area().width).is_some());
+    press(KeyCode::Tab, &mut model);
+    assert_eq!(model.active_tab, ActiveTab::Timeline);
+    assert_eq!(model.focused_pane, FocusedPane::Detail);
+
+    press(KeyCode::Down, &mut model);
+    press(KeyCode::Down, &mut model);
+    assert_eq!(model.selected, 0, "the detail pane scrolls instead");
+    assert_eq!(model.detail_scroll, 2);
+
+    press(KeyCode::Tab, &mut model);
+    press(KeyCode::Down, &mut model);
+    assert_eq!(model.selected, 1);
+    assert_eq!(model.detail_scroll, 0, "a new selection starts at the top");
+
+    press(KeyCode::Tab, &mut model);
+    press(KeyCode::Char('\\'), &mut model);
+    assert!(model.split_ratio_for(test_area().width).is_none());
+    assert_eq!(model.focused_pane, FocusedPane::Timeline);
+}
+
+#[test]
+fn a_marks_every_stored_event_read_on_the_timeline_tab_only() {
+    let state = FakeState::default();
+    let now = Utc.with_ymd_and_hms(2025, 1, 9, 0, 0, 0).unwrap();
+    let stored = vec![
+        timeline_event("ev-all-1", now),
+        timeline_event("ev-all-2", now - chrono::Duration::minutes(1)),
+        timeline_event("ev-all-3", now - chrono::Duration::minutes(2)),
+    ];
+    state.set_stored_events(stored.clone());
+    let mut model = TuiModel::new(10);
+    model.replace_timeline(stored[..2].to_vec());
+
+    model.set_active_tab(ActiveTab::MyPr);
+    assert_eq!(
+        press('a', &mut model, &state, &actions(&open_ok)),
+        LoopControl::Continue
+    );
+    assert!(state.marked_read_event_keys().is_empty());
+
+    model.set_active_tab(ActiveTab::Timeline);
+    assert_eq!(
+        press('a', &mut model, &state, &actions(&open_ok)),
+        LoopControl::Redraw
+    );
+    assert_eq!(model.status_line, "marked 3 events read");
+    assert_eq!(state.marked_read_event_keys().len(), 3);
+    assert!(stored[..2]
+        .iter()
+        .all(|event| model.is_event_read(&event.event_key())));
+
+    press('a', &mut model, &state, &actions(&open_ok));
+    assert_eq!(model.status_line, "marked 0 events read");
+}
+
+fn press_code(
+    code: KeyCode,
+    model: &mut TuiModel,
+    state: &FakeState,
+    actions: &InputActions<'_>,
+) -> LoopControl {
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 9, 0, 0, 0).unwrap(),
+    };
+    handle_stream_event(
+        Some(Ok(Event::Key(KeyEvent::new(code, KeyModifiers::NONE)))),
+        model,
+        state,
+        &clock,
+        test_area(),
+        actions,
+    )
+}
+
+#[test]
+fn slash_search_loads_matching_events_beyond_the_timeline_and_selects_the_newest() {
+    let state = FakeState::default();
+    let now = Utc.with_ymd_and_hms(2025, 1, 10, 0, 0, 0).unwrap();
+    let mut stored = (0..5)
+        .map(|idx| timeline_event(&format!("ev-{idx}"), now - chrono::Duration::hours(idx)))
+        .collect::<Vec<_>>();
+    stored.push(WatchEvent {
+        body: Some("Needs a circuit breaker around the poller".to_string()),
+        ..timeline_event("ev-old", now - chrono::Duration::days(30))
+    });
+    state.set_stored_events(stored);
+    let mut model = TuiModel::new(2);
+    model.replace_timeline(state.load_timeline_events(2).unwrap());
+    let actions = actions(&open_ok);
+
+    assert_eq!(
+        press('/', &mut model, &state, &actions),
+        LoopControl::Redraw
+    );
+    for ch in "Circuit q".chars() {
+        press(ch, &mut model, &state, &actions);
+    }
+    press_code(KeyCode::Backspace, &mut model, &state, &actions);
+    press_code(KeyCode::Backspace, &mut model, &state, &actions);
+    assert_eq!(model.search_input.as_deref(), Some("Circuit"));
+    assert_eq!(model.timeline.len(), 2);
+
+    assert_eq!(
+        press_code(KeyCode::Enter, &mut model, &state, &actions),
+        LoopControl::Redraw
+    );
+    assert_eq!(model.search_input, None);
+    assert_eq!(model.timeline.len(), 3);
+    assert_eq!(model.timeline[model.selected].event_id, "ev-old");
+    assert_eq!(
+        model.status_notice.as_deref(),
+        Some("1 events matched: Circuit")
+    );
+
+    press('/', &mut model, &state, &actions);
+    press('q', &mut model, &state, &actions);
+    assert_eq!(
+        press_code(KeyCode::Esc, &mut model, &state, &actions),
+        LoopControl::Redraw
+    );
+    assert_eq!(model.search_input, None);
+    assert_eq!(model.timeline.len(), 3);
+}
+
+#[test]
+fn n_and_shift_n_step_through_slash_search_matches_until_esc_ends_the_search() {
+    let state = FakeState::default();
+    let now = Utc.with_ymd_and_hms(2025, 1, 10, 0, 0, 0).unwrap();
+    let titled = |id: &str, hours: i64, title: &str| WatchEvent {
+        title: title.to_string(),
+        ..timeline_event(id, now - chrono::Duration::hours(hours))
+    };
+    state.set_stored_events(vec![
+        titled("ev-0", 0, "retry budget"),
+        titled("ev-1", 1, "unrelated"),
+        titled("ev-2", 2, "retry jitter"),
+        titled("ev-3", 3, "unrelated"),
+        titled("ev-4", 4, "retry backoff"),
+    ]);
+    let mut model = TuiModel::new(10);
+    model.replace_timeline(state.load_timeline_events(10).unwrap());
+    let actions = actions(&open_ok);
+
+    for ch in "/retry".chars() {
+        press(ch, &mut model, &state, &actions);
+    }
+    press_code(KeyCode::Enter, &mut model, &state, &actions);
+    assert_eq!(model.search_results, Some(vec![0, 2, 4]));
+    assert_eq!(model.selected, 0);
+
+    press('n', &mut model, &state, &actions);
+    press('n', &mut model, &state, &actions);
+    assert_eq!(model.selected, 4);
+    press('n', &mut model, &state, &actions);
+    assert_eq!(model.selected, 0);
+    press('N', &mut model, &state, &actions);
+    assert_eq!(model.selected, 4);
+
+    model.push_timeline(vec![titled("ev-new", -1, "unrelated")]);
+    assert_eq!(model.search_results, Some(vec![1, 3, 5]));
+    assert_eq!(model.search_position(), Some((3, 3)));
+
+    assert_eq!(
+        press_code(KeyCode::Esc, &mut model, &state, &actions),
+        LoopControl::Redraw
+    );
+    assert_eq!(model.search_results, None);
+    assert_eq!(model.esc_armed_until, None);
+    press('n', &mut model, &state, &actions);
+    assert_eq!(model.selected, 5);
+}
@@ -0,0 +1,157 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{
+    domain::{
+        events::WatchEvent,
+        subject_state::{SubjectRef, SubjectState},
+    },
+    ports::{GhClientPort, SubjectStatePort},
+};
+
+/// Subjects looked up per poll; the rest wait for the next one.
+const SUBJECT_STATE_LOOKUP_LIMIT: usize = 50;
+/// How long a looked-up state is trusted before it is looked up again.
+const SUBJECT_STATE_TTL_MINUTES: i64 = 30;
+
+/// States of `subjects` for `display.resolve_subject_state`: cached ones from the state db,
+/// after looking up (at most `SUBJECT_STATE_LOOKUP_LIMIT`, in the given order) those never
+/// fetched or fetched more than `SUBJECT_STATE_TTL_MINUTES` ago.
+pub(super) async fn refresh_subject_states<C, S>(
+    gh: &C,
+    state: &S,
+    subjects: &[SubjectRef],
+    now: DateTime<Utc>,
+) -> Result<HashMap<SubjectRef, SubjectState>>
+where
+    C: GhClientPort,
+    S: SubjectStatePort,
+{
+    let cached = state.load_subject_states(subjects)?;
+    let stale = stale_subjects(subjects, &cached, now);
+
+    let mut states = cached
+        .into_iter()
+        .map(|(subject, (subject_state, _))| (subject, subject_state))
+        .collect::<HashMap<_, _>>();
+    if stale.is_empty() {
+        return Ok(states);
+    }
+
+    let fetched = gh.fetch_subject_states(&stale).await?;
+    state.save_subject_states(&fetched, now)?;
+    states.extend(fetched);
+    Ok(states)
+}
+
+/// Subjects of `events` in event order, each once.
+pub(super) fn distinct_subjects(events: &[WatchEvent]) -> Vec<SubjectRef> {
+    let mut seen = HashSet::new();
+    events
+        .iter()
+        .filter_map(SubjectRef::of)
+        .filter(|subject| seen.insert(subject.clone()))
+        .collect()
+}
+
+fn stale_subjects(
+    subjects: &[SubjectRef],
+    cached: &HashMap<SubjectRef, (SubjectState, DateTime<Utc>)>,
+    now: DateTime<Utc>,
+) -> Vec<SubjectRef> {
+    subjects
+        .iter()
+        .filter(|subject| {
+            cached.get(*subject).is_none_or(|(_, fetched_at)| {
+                now - *fetched_at >= Duration::minutes(SUBJECT_STATE_TTL_MINUTES)
+            })
+        })
+        .take(SUBJECT_STATE_LOOKUP_LIMIT)
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+
+    use super::{distinct_subjects, stale_subjects, SUBJECT_STATE_LOOKUP_LIMIT};
+    use crate::domain::{
+        events::{EventKind, WatchEvent},
+        subject_state::{SubjectRef, SubjectState},
+    };
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 10, 9, 0, 0).unwrap()
+    }
+
+    fn subject(number: i64) -> SubjectRef {
+        SubjectRef {
+            repo: "acme/api".to_string(),
+            number,
+        }
+    }
+
+    fn event(id: &str, number: Option<i64>) -> WatchEvent {
+        WatchEvent {
+            event_id: id.to_string(),
+            repo: "acme/api".to_string(),
+            kind: EventKind::IssueCommentCreated,
+            actor: "bob".to_string(),
+            title: "Change".to_string(),
+            url: "https://github.com/acme/api".to_string(),
+            created_at: now(),
+            source_item_id: id.to_string(),
+            subject_author: None,
+            requested_reviewer: None,
+            mentions: Vec::new(),
+            team_mentions: Vec::new(),
+            subject_number: number,
+            subject_url: None,
+            account: None,
+            body: None,
+        }
+    }
+
+    #[test]
+    fn subjects_are_taken_once_in_event_order() {
+        let events = [
+            event("1", Some(7)),
+            event("2", None),
+            event("3", Some(3)),
+            event("4", Some(7)),
+        ];
+
+        assert_eq!(distinct_subjects(&events), vec![subject(7), subject(3)]);
+    }
+
+    #[test]
+    fn missing_and_expired_states_are_looked_up_up_to_the_limit() {
+        let cached = HashMap::from([
+            (
+                subject(1),
+                (SubjectState::Open, now() - Duration::minutes(5)),
+            ),
+            (
+                subject(2),
+                (SubjectState::Merged, now() - Duration::hours(2)),
+            ),
+        ]);
+
+        assert_eq!(
+            stale_subjects(&[subject(1), subject(2), subject(3)], &cached, now()),
+            vec![subject(2), subject(3)]
+        );
+
+        let many = (0..SUBJECT_STATE_LOOKUP_LIMIT as i64 + 10)
+            .map(subject)
+            .collect::<Vec<_>>();
+        let stale = stale_subjects(&many, &HashMap::new(), now());
+        assert_eq!(stale.len(), SUBJECT_STATE_LOOKUP_LIMIT);
+        assert_eq!(stale[0], subject(0));
+    }
+}
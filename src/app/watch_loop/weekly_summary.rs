@@ -0,0 +1,312 @@
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Datelike, Days, Duration, Local, TimeZone, Utc};
+
+use super::review_reminders::pending_review_requests;
+use crate::{
+    app::report::load_events_between,
+    config::{
+        parse_summary_schedule, parse_summary_timezone, Config, SummarySchedule, SummaryTimezone,
+    },
+    domain::events::WatchEvent,
+    ports::{NotificationPayload, NotifierPort, SummaryStatePort, TimelineQueryPort},
+};
+
+const SUMMARY_WINDOW_DAYS: i64 = 7;
+const TOP_REPO_COUNT: usize = 3;
+
+#[derive(Debug, Clone)]
+pub(super) struct WeeklySummaryTracker {
+    schedule: SummarySchedule,
+    timezone: SummaryTimezone,
+    /// Slots at or before this were already sent, or passed before the first watch started.
+    covered_until: DateTime<Utc>,
+}
+
+impl WeeklySummaryTracker {
+    pub(super) fn new(
+        schedule: SummarySchedule,
+        timezone: SummaryTimezone,
+        last_sent_at: Option<DateTime<Utc>>,
+        started_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            schedule,
+            timezone,
+            covered_until: last_sent_at.unwrap_or(started_at),
+        }
+    }
+
+    /// The latest slot that passed since the last send. A slot missed while the machine
+    /// slept is returned on the first check after waking; older missed slots are dropped.
+    pub(super) fn take_due_slot(&mut self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let slot = last_slot(self.schedule, self.timezone, now)?;
+        if slot <= self.covered_until {
+            return None;
+        }
+
+        self.covered_until = now;
+        Some(slot)
+    }
+}
+
+/// Tracker for `[notifications.summary]`, or `None` when it is not configured or
+/// notifications are disabled.
+pub(super) fn weekly_summary_tracker<S>(
+    config: &Config,
+    state: &S,
+    now: DateTime<Utc>,
+) -> Result<Option<WeeklySummaryTracker>>
+where
+    S: SummaryStatePort,
+{
+    let Some(summary) = config
+        .notifications
+        .summary
+        .as_ref()
+        .filter(|_| config.notifications.enabled)
+    else {
+        return Ok(None);
+    };
+    let schedule = parse_summary_schedule(&summary.schedule)
+        .ok_or_else(|| anyhow!("invalid notifications.summary.schedule"))?;
+    let timezone = parse_summary_timezone(&summary.timezone)
+        .ok_or_else(|| anyhow!("invalid notifications.summary.timezone"))?;
+    Ok(Some(WeeklySummaryTracker::new(
+        schedule,
+        timezone,
+        state.load_summary_sent_at()?,
+        now,
+    )))
+}
+
+/// Sends the summary of the week before the due slot, if any; returns whether one went out.
+pub(super) fn send_due_weekly_summary<S, N>(
+    tracker: &mut WeeklySummaryTracker,
+    state: &S,
+    notifier: &N,
+    viewer_login: Option<&str>,
+    now: DateTime<Utc>,
+) -> Result<bool>
+where
+    S: TimelineQueryPort + SummaryStatePort,
+    N: NotifierPort,
+{
+    let Some(slot) = tracker.take_due_slot(now) else {
+        return Ok(false);
+    };
+    let events = load_events_between(
+        state,
+        slot - Duration::days(SUMMARY_WINDOW_DAYS),
+        Some(slot),
+    )?;
+    let message = weekly_summary_message(&events, viewer_login);
+    notifier.notify(&NotificationPayload::Summary(message), false)?;
+    state.save_summary_sent_at(now)?;
+    Ok(true)
+}
+
+pub(super) fn weekly_summary_message(events: &[WatchEvent], viewer_login: Option<&str>) -> String {
+    let mut by_kind = BTreeMap::<&str, usize>::new();
+    let mut by_repo = BTreeMap::<&str, usize>::new();
+    for event in events {
+        *by_kind.entry(event.kind.as_str()).or_default() += 1;
+        *by_repo.entry(event.repo.as_str()).or_default() += 1;
+    }
+
+    let mut lines = vec![format!("Last week: {} events", events.len())];
+    if !events.is_empty() {
+        lines.push(join_by_count(by_kind, usize::MAX, |kind, count| {
+            format!("{kind} {count}")
+        }));
+        lines.push(format!(
+            "Top repos: {}",
+            join_by_count(by_repo, TOP_REPO_COUNT, |repo, count| format!(
+                "{repo} ({count})"
+            ))
+        ));
+    }
+    if let Some(login) = viewer_login {
+        lines.push(format!(
+            "Review requests waiting on you: {}",
+            pending_review_requests(events, login).len()
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Highest counts first, ties alphabetically.
+fn join_by_count(
+    counts: BTreeMap<&str, usize>,
+    limit: usize,
+    label: impl Fn(&str, usize) -> String,
+) -> String {
+    let mut counts = counts.into_iter().collect::<Vec<_>>();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    counts
+        .into_iter()
+        .take(limit)
+        .map(|(name, count)| label(name, count))
+        .collect::<Vec<_>>()
+        .join(" · ")
+}
+
+fn last_slot(
+    schedule: SummarySchedule,
+    timezone: SummaryTimezone,
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    match timezone {
+        SummaryTimezone::Local => last_slot_in(schedule, &Local, now),
+        SummaryTimezone::Fixed(offset) => last_slot_in(schedule, &offset, now),
+    }
+}
+
+/// Latest `schedule` instant at or before `now`, reading the weekday and time in `timezone`.
+fn last_slot_in<Tz>(
+    schedule: SummarySchedule,
+    timezone: &Tz,
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>>
+where
+    Tz: TimeZone,
+{
+    let local_now = now.with_timezone(timezone).naive_local();
+    let days_back = (local_now.weekday().num_days_from_monday() + 7
+        - schedule.weekday.num_days_from_monday())
+        % 7;
+    let mut slot = local_now
+        .date()
+        .checked_sub_days(Days::new(days_back.into()))?
+        .and_time(schedule.time);
+    if slot > local_now {
+        slot = slot.checked_sub_days(Days::new(7))?;
+    }
+    // A slot inside a DST gap fires once the clocks have jumped past it.
+    timezone
+        .from_local_datetime(&slot)
+        .earliest()
+        .or_else(|| {
+            timezone
+                .from_local_datetime(&(slot + Duration::hours(1)))
+                .earliest()
+        })
+        .map(|slot| slot.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, FixedOffset, NaiveTime, TimeZone, Utc, Weekday};
+
+    use super::{weekly_summary_message, WeeklySummaryTracker};
+    use crate::{
+        config::{SummarySchedule, SummaryTimezone},
+        domain::events::{EventKind, WatchEvent},
+    };
+
+    fn monday_nine() -> SummarySchedule {
+        SummarySchedule {
+            weekday: Weekday::Mon,
+            time: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+        }
+    }
+
+    fn utc() -> SummaryTimezone {
+        SummaryTimezone::Fixed(FixedOffset::east_opt(0).unwrap())
+    }
+
+    // 2025-01-06 is a Monday.
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, day, hour, minute, 0).unwrap()
+    }
+
+    fn event(repo: &str, kind: EventKind, actor: &str, reviewer: Option<&str>) -> WatchEvent {
+        WatchEvent {
+            event_id: format!("{repo}-{kind}-{actor}"),
+            repo: repo.to_string(),
+            kind,
+            actor: actor.to_string(),
+            title: "Change".to_string(),
+            url: format!("https://github.com/{repo}/pull/1"),
+            created_at: at(8, 12, 0),
+            source_item_id: "1".to_string(),
+            subject_author: None,
+            requested_reviewer: reviewer.map(str::to_string),
+            mentions: Vec::new(),
+            team_mentions: Vec::new(),
+            subject_number: Some(1),
+            subject_url: None,
+            account: None,
+            body: None,
+        }
+    }
+
+    #[test]
+    fn summary_is_due_once_per_slot() {
+        let mut tracker = WeeklySummaryTracker::new(monday_nine(), utc(), None, at(3, 12, 0));
+
+        assert_eq!(tracker.take_due_slot(at(6, 8, 59)), None);
+        assert_eq!(tracker.take_due_slot(at(6, 9, 0)), Some(at(6, 9, 0)));
+        assert_eq!(tracker.take_due_slot(at(6, 9, 5)), None);
+        assert_eq!(tracker.take_due_slot(at(13, 9, 1)), Some(at(13, 9, 0)));
+    }
+
+    #[test]
+    fn slot_missed_while_asleep_is_sent_on_next_wake() {
+        let last_sent = at(6, 9, 2);
+        let mut tracker =
+            WeeklySummaryTracker::new(monday_nine(), utc(), Some(last_sent), at(15, 7, 30));
+
+        // Asleep through Monday 13th 09:00, woke on Wednesday.
+        assert_eq!(tracker.take_due_slot(at(15, 7, 30)), Some(at(13, 9, 0)));
+        assert_eq!(tracker.take_due_slot(at(15, 8, 0)), None);
+    }
+
+    #[test]
+    fn restart_after_sending_does_not_resend() {
+        let mut tracker =
+            WeeklySummaryTracker::new(monday_nine(), utc(), Some(at(6, 9, 2)), at(6, 10, 0));
+
+        assert_eq!(tracker.take_due_slot(at(6, 10, 0)), None);
+    }
+
+    #[test]
+    fn first_start_after_a_slot_waits_for_the_next_one() {
+        let mut tracker = WeeklySummaryTracker::new(monday_nine(), utc(), None, at(8, 12, 0));
+
+        assert_eq!(tracker.take_due_slot(at(8, 12, 0)), None);
+        assert_eq!(tracker.take_due_slot(at(13, 9, 0)), Some(at(13, 9, 0)));
+    }
+
+    #[test]
+    fn slot_is_read_in_the_configured_timezone() {
+        let tokyo = SummaryTimezone::Fixed(FixedOffset::east_opt(9 * 3600).unwrap());
+        let mut tracker = WeeklySummaryTracker::new(monday_nine(), tokyo, None, at(3, 12, 0));
+
+        // Monday 09:00 in Tokyo is Monday 00:00 UTC.
+        assert_eq!(tracker.take_due_slot(at(6, 0, 30)), Some(at(6, 0, 0)));
+    }
+
+    #[test]
+    fn message_counts_kinds_top_repos_and_open_review_requests() {
+        let events = vec![
+            event("acme/api", EventKind::PrCreated, "bob", None),
+            event("acme/api", EventKind::IssueCreated, "bob", None),
+            event("acme/web", EventKind::PrReviewRequested, "bob", Some("Me")),
+            event("acme/cli", EventKind::PrReviewRequested, "bob", Some("me")),
+            event("acme/cli", EventKind::PrReviewApproved, "me", None),
+            event("acme/docs", EventKind::PrCreated, "bob", None),
+        ];
+
+        let message = weekly_summary_message(&events, Some("me"));
+
+        assert_eq!(
+            message,
+            "Last week: 6 events\n\
+             pr_created 2 · pr_review_requested 2 · issue_created 1 · pr_review_approved 1\n\
+             Top repos: acme/api (2) · acme/cli (2) · acme/docs (1)\n\
+             Review requests waiting on you: 1"
+        );
+    }
+}
@@ -1,13 +1,20 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 
+use chrono::{DateTime, Utc};
 use clap::{Parser, Subcommand, ValueEnum};
 
 #[derive(Debug, Parser)]
 #[command(
     name = "gh-watch",
+    version,
     about = "Watch GitHub PRs/issues and notify on updates"
 )]
 pub(crate) struct Cli {
+    #[arg(long, global = true)]
+    pub(crate) profile: Option<String>,
+    /// How failures are written to stderr; `json` prints one {"code", "message", "hint"} object
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub(crate) error_format: OutputFormat,
     #[command(subcommand)]
     pub(crate) command: Commands,
 }
@@ -19,6 +26,26 @@ pub(crate) enum Commands {
         config: Option<PathBuf>,
         #[arg(long)]
         interval_seconds: Option<u64>,
+        #[arg(long, value_parser = parse_duration)]
+        alert_after_idle: Option<Duration>,
+        #[arg(long, value_parser = parse_duration, default_value = "1h")]
+        alert_idleness_cooldown: Duration,
+        #[arg(long)]
+        poll_once_then_watch: bool,
+        #[arg(long, requires = "poll_once_then_watch")]
+        json: bool,
+        /// Log poll results instead of drawing the TUI (for launchd and other service managers)
+        #[arg(long)]
+        no_tui: bool,
+        /// Write the stored timeline here when watch stops (quit, Ctrl-C or SIGTERM)
+        #[arg(long)]
+        export_on_exit: Option<PathBuf>,
+        /// Format of the --export-on-exit file
+        #[arg(long, value_enum, default_value_t = ExportFormat::Ndjson, requires = "export_on_exit")]
+        export_format: ExportFormat,
+        /// Log each repository fetch and gh call with its response size to stderr
+        #[arg(long)]
+        verbose_poll: bool,
     },
     Check {
         #[arg(long)]
@@ -31,6 +58,15 @@ pub(crate) enum Commands {
         dry_run: bool,
         #[arg(long)]
         json: bool,
+        /// Also write the poll result as JSON to this file (replaced atomically)
+        #[arg(long)]
+        output_file: Option<PathBuf>,
+        /// Append the JSON result to --output-file as one NDJSON line instead of replacing it
+        #[arg(long, requires = "output_file")]
+        append: bool,
+        /// Fetch everything from GitHub even when `poll.cache_ttl_seconds` enables the response cache
+        #[arg(long)]
+        no_cache: bool,
     },
     Init {
         #[arg(long)]
@@ -39,23 +75,306 @@ pub(crate) enum Commands {
         force: bool,
         #[arg(long)]
         reset_state: bool,
+        #[arg(long, conflicts_with_all = ["from_starred", "reset_state"])]
+        from_org: Option<String>,
+        #[arg(long, conflicts_with = "reset_state")]
+        from_starred: bool,
+        #[arg(long)]
+        include_archived: bool,
+        #[arg(long)]
+        limit: Option<usize>,
+        #[arg(long)]
+        yes: bool,
+        /// Start from a built-in template (see --list-templates)
+        #[arg(long, conflicts_with_all = ["from_org", "from_starred", "reset_state", "template_url"])]
+        template: Option<String>,
+        /// Download a template over HTTPS and write it once it parses as a valid config and
+        /// is confirmed (or --yes is given)
+        #[arg(long, conflicts_with_all = ["from_org", "from_starred", "reset_state"])]
+        template_url: Option<String>,
+        /// Print the built-in templates and exit
+        #[arg(long, exclusive = true)]
+        list_templates: bool,
     },
     Config {
         #[command(subcommand)]
         command: ConfigCommands,
     },
+    Events {
+        #[arg(long)]
+        config: Option<PathBuf>,
+        /// Full-text search of repositories, actors, titles and bodies instead of listing recent events
+        #[arg(long, conflicts_with_all = ["since", "page_token"])]
+        search: Option<String>,
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+        /// How far back to list events
+        #[arg(long, value_parser = parse_duration, default_value = "7d")]
+        since: Duration,
+        /// Continue listing older events from the token printed by the previous page
+        #[arg(long, value_parser = parse_page_token)]
+        page_token: Option<DateTime<Utc>>,
+    },
+    /// Run a GitHub issue search and print the results as events
+    Search {
+        /// Search qualifiers, e.g. "is:open assignee:@me"
+        query: String,
+        #[arg(long)]
+        config: Option<PathBuf>,
+        /// Restrict the search to this repository; repeat for several
+        #[arg(long = "repo")]
+        repos: Vec<String>,
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+    /// Print events to stdout as they are stored, e.g. to pipe into other tools
+    Stream {
+        #[arg(long)]
+        config: Option<PathBuf>,
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+    /// Write a digest of recent events, e.g. for a standup
+    Report {
+        #[arg(long)]
+        config: Option<PathBuf>,
+        #[arg(long, value_parser = parse_duration, default_value = "1d")]
+        since: Duration,
+        #[arg(long, value_enum, default_value_t = ReportFormat::Markdown)]
+        format: ReportFormat,
+        /// Write the digest here instead of stdout (replacing the file)
+        #[arg(long, visible_alias = "output-file")]
+        out: Option<PathBuf>,
+        /// Add the digest to the end of --out instead of replacing it; JSON becomes one NDJSON line
+        #[arg(long, requires = "out")]
+        append: bool,
+        /// Gzip the written file, adding `.gz` to --out unless it already ends in it
+        #[arg(long, requires = "out")]
+        compress: bool,
+        /// Open the written file with the OS default application
+        #[arg(long, requires = "out")]
+        open: bool,
+        /// One section per repository instead of a single event list
+        #[arg(long, value_enum)]
+        split_by: Option<ReportSplit>,
+        /// Only events of this repository; repeat for several
+        #[arg(long = "repo")]
+        repos: Vec<String>,
+        /// Only events by this login; repeat for several
+        #[arg(long = "actor")]
+        actors: Vec<String>,
+    },
+    /// Receive GitHub webhooks and notify right away; run `watch` alongside to fill gaps
+    Serve {
+        #[arg(long)]
+        config: Option<PathBuf>,
+        /// Address to listen on, e.g. 0.0.0.0:8788
+        #[arg(long, default_value = "127.0.0.1:8788")]
+        listen: String,
+        /// File holding the secret the webhook was registered with, for checking
+        /// X-Hub-Signature-256; defaults to the GH_WATCH_WEBHOOK_SECRET environment variable
+        #[arg(long)]
+        secret_file: Option<PathBuf>,
+        /// Also serve the newest 100 timeline events as an Atom feed at /feed.atom
+        #[arg(long)]
+        feed: bool,
+    },
+    /// Write the stored timeline as an Atom feed for feed readers
+    Feed {
+        #[arg(long)]
+        config: Option<PathBuf>,
+        /// Write the feed here instead of stdout
+        #[arg(long)]
+        out: Option<PathBuf>,
+        /// Only events of this repository; repeat for several
+        #[arg(long = "repo")]
+        repos: Vec<String>,
+        #[arg(long, default_value_t = 100)]
+        limit: usize,
+    },
+    State {
+        #[command(subcommand)]
+        command: StateCommands,
+    },
+    /// Share read state with other machines through the `[sync]` gist
+    Sync {
+        #[command(subcommand)]
+        command: SyncCommands,
+    },
+    Profile {
+        #[command(subcommand)]
+        command: ProfileCommands,
+    },
+    FilterProfile {
+        #[command(subcommand)]
+        command: FilterProfileCommands,
+    },
+    /// Generate a macOS launchd user agent that runs `watch --no-tui`
+    Launchd {
+        #[arg(long)]
+        config: Option<PathBuf>,
+        /// Copy the plist to ~/Library/LaunchAgents and load it with launchctl
+        #[arg(long, conflicts_with = "output")]
+        install: bool,
+        /// Start the agent right after installing it
+        #[arg(long, requires = "install")]
+        start: bool,
+        /// Write the plist here instead of stdout
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// Directory for the agent's stdout/stderr logs (default: ~/Library/Logs/gh-watch)
+        #[arg(long)]
+        log_dir: Option<PathBuf>,
+    },
+    /// Print environment details and a dry-run poll summary to attach to bug reports
+    Diagnose {
+        #[arg(long)]
+        config: Option<PathBuf>,
+        /// Write the report to this file instead of stdout
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// Keep repository names instead of replacing them with owner/REPO_N
+        #[arg(long)]
+        no_redact: bool,
+    },
     #[command(name = "commands")]
     Guide,
     Completion {
         #[arg(value_enum)]
         shell: CompletionShell,
     },
+    /// Verify the completion script is installed where the shell loads it from
+    CompletionCheck {
+        #[arg(value_enum)]
+        shell: CompletionShell,
+        /// Regenerate the script into the expected location before checking
+        #[arg(long)]
+        fix: bool,
+    },
 }
 
 #[derive(Debug, Subcommand)]
 pub(crate) enum ConfigCommands {
     Open,
     Path,
+    Reset {
+        #[arg(long)]
+        key: Option<String>,
+        #[arg(long)]
+        force: bool,
+    },
+    Defaults,
+    Schema,
+    Doctor {
+        #[arg(long)]
+        config: Option<PathBuf>,
+        /// Also report how long notifications took over the last 7 days
+        #[arg(long)]
+        latency: bool,
+        /// Drop the cached team memberships and look them up again
+        #[arg(long)]
+        refresh_teams: bool,
+    },
+    Rollback {
+        #[arg(long)]
+        to: Option<String>,
+        #[arg(long)]
+        config: Option<PathBuf>,
+    },
+    ShowKeymap {
+        #[arg(long)]
+        config: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum ProfileCommands {
+    List,
+}
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum FilterProfileCommands {
+    List {
+        #[arg(long)]
+        config: Option<PathBuf>,
+    },
+    Switch {
+        name: String,
+        #[arg(long)]
+        config: Option<PathBuf>,
+    },
+    Create {
+        name: String,
+        #[arg(long)]
+        from: Option<String>,
+        #[arg(long)]
+        config: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum SyncCommands {
+    /// Merge the remote read state now instead of waiting for the next poll
+    Now {
+        #[arg(long)]
+        config: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum StateCommands {
+    Check {
+        #[arg(long)]
+        config: Option<PathBuf>,
+        #[arg(long)]
+        repair: bool,
+    },
+    Vacuum {
+        #[arg(long)]
+        config: Option<PathBuf>,
+        #[arg(long)]
+        compress: bool,
+    },
+    Export {
+        #[arg(long)]
+        config: Option<PathBuf>,
+        /// Write NDJSON here instead of stdout
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    Import {
+        #[arg(long)]
+        config: Option<PathBuf>,
+        /// NDJSON file produced by `gh-watch state export`
+        input: PathBuf,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum OutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum ReportFormat {
+    Markdown,
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum ExportFormat {
+    Json,
+    Ndjson,
+    Csv,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum ReportSplit {
+    Repo,
 }
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
@@ -66,3 +385,76 @@ pub(crate) enum CompletionShell {
     #[value(alias = "powershell")]
     Pwsh,
 }
+
+pub(crate) fn parse_duration(raw: &str) -> Result<Duration, String> {
+    let raw = raw.trim();
+    let split_at = raw
+        .find(|ch: char| !ch.is_ascii_digit())
+        .unwrap_or(raw.len());
+    let (value, unit) = raw.split_at(split_at);
+    let value = value
+        .parse::<u64>()
+        .map_err(|_| format!("invalid duration: {raw} (use e.g. 30m, 4h, 1d)"))?;
+    let unit_seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 60 * 60 * 24,
+        _ => return Err(format!("invalid duration: {raw} (use e.g. 30m, 4h, 1d)")),
+    };
+    let seconds = value
+        .checked_mul(unit_seconds)
+        .ok_or_else(|| format!("duration is too long: {raw}"))?;
+    if seconds == 0 {
+        return Err(format!("duration must be > 0: {raw}"));
+    }
+    Ok(Duration::from_secs(seconds))
+}
+
+pub(crate) fn parse_page_token(raw: &str) -> Result<DateTime<Utc>, String> {
+    DateTime::parse_from_rfc3339(raw.trim())
+        .map(|at| at.with_timezone(&Utc))
+        .map_err(|_| format!("invalid page token: {raw} (expected an RFC 3339 timestamp)"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::{TimeZone, Utc};
+
+    use super::{parse_duration, parse_page_token};
+
+    #[test]
+    fn parse_duration_accepts_unit_suffixes() {
+        assert_eq!(parse_duration("45s"), Ok(Duration::from_secs(45)));
+        assert_eq!(parse_duration("30m"), Ok(Duration::from_secs(1800)));
+        assert_eq!(parse_duration("4h"), Ok(Duration::from_secs(4 * 3600)));
+        assert_eq!(parse_duration("1d"), Ok(Duration::from_secs(86400)));
+    }
+
+    #[test]
+    fn parse_duration_rejects_missing_unit_zero_and_garbage() {
+        assert!(parse_duration("4").is_err());
+        assert!(parse_duration("0h").is_err());
+        assert!(parse_duration("h").is_err());
+        assert!(parse_duration("4w").is_err());
+    }
+
+    #[test]
+    fn parse_duration_rejects_overflowing_values() {
+        assert_eq!(
+            parse_duration("99999999999999999d"),
+            Err("duration is too long: 99999999999999999d".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_page_token_accepts_rfc3339_only() {
+        assert_eq!(
+            parse_page_token("2025-01-20T12:00:00+09:00"),
+            Ok(Utc.with_ymd_and_hms(2025, 1, 20, 3, 0, 0).unwrap())
+        );
+        assert!(parse_page_token("2025-01-20").is_err());
+    }
+}
@@ -1,9 +1,20 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 
 use crate::{
-    cli::state::{open_state_store, resolve_state_db_path},
-    config::{Config, ResolvedConfigPath},
-    infra::{gh_client::GhCliClient, notifier::DesktopNotifier},
+    app::clock_skew::{measure_clock_skew, ClockSkewLevel},
+    cli::{
+        error::ClassifiedError,
+        state::{open_state_store, resolve_state_db_path},
+        SystemClock,
+    },
+    config::{
+        api_budget_warning, estimated_hourly_api_calls, Config, ResolvedConfigPath,
+        GH_API_HOURLY_LIMIT,
+    },
+    infra::{
+        gh_client::{AccountGhClients, GhCliClient},
+        notifier::DesktopNotifier,
+    },
     ports::{GhClientPort, NotifierPort},
 };
 
@@ -12,10 +23,13 @@ pub(crate) async fn run(cfg: Config, resolved_config: ResolvedConfigPath) -> Res
         eprintln!("{warning}");
     }
 
-    let gh = GhCliClient::default();
-    gh.check_auth()
-        .await
-        .context("GitHub authentication is invalid. Run `gh auth login -h github.com`.")?;
+    let gh_probe = GhCliClient::default().probe().await?;
+    if let Some(warning) = gh_probe.warning() {
+        eprintln!("warning: {warning}");
+    }
+
+    let gh = AccountGhClients::from_config(&cfg, GhCliClient::default())?;
+    let auth = gh.check_auth().await.context(ClassifiedError::auth())?;
 
     let notifier = DesktopNotifier::from_notification_config(&cfg.notifications);
     for warning in notifier.startup_warnings() {
@@ -23,7 +37,7 @@ pub(crate) async fn run(cfg: Config, resolved_config: ResolvedConfigPath) -> Res
     }
     notifier
         .check_health()
-        .context("Notification backend check failed")?;
+        .context(ClassifiedError::notifier())?;
 
     let state_path = resolve_state_db_path(&cfg)?;
     let _store = open_state_store(&state_path)?;
@@ -33,7 +47,38 @@ pub(crate) async fn run(cfg: Config, resolved_config: ResolvedConfigPath) -> Res
         resolved_config.path.display(),
         resolved_config.source
     );
-    println!("gh auth: ok");
+    println!("gh: {gh_probe}");
+    println!("gh auth: ok (@{} on {})", auth.login, auth.hostname);
+    if !auth.scopes.is_empty() {
+        println!("gh scopes: {}", auth.scopes.join(", "));
+    }
+    for account in gh.account_names() {
+        println!("gh auth ({account}): ok");
+    }
+    for profile in gh.profile_names() {
+        println!("gh auth (gh profile {profile}): ok");
+    }
+    match gh.rate_limit_remaining().await {
+        Ok(Some(remaining)) => println!("api remaining: {remaining}"),
+        Ok(None) => {}
+        Err(err) => println!("api remaining: unknown ({err:#})"),
+    }
+    match measure_clock_skew(&gh, &SystemClock).await {
+        Ok(Some(skew)) => match skew.level() {
+            ClockSkewLevel::Ok => println!("clock: ok"),
+            ClockSkewLevel::Warn => println!("warning: clock: {skew}"),
+            ClockSkewLevel::Error => bail!("clock: {skew}; fix the system time before polling"),
+        },
+        Ok(None) => {}
+        Err(err) => println!("clock: unknown ({err:#})"),
+    }
+    println!(
+        "api budget: ~{} calls/hour (limit {GH_API_HOURLY_LIMIT})",
+        estimated_hourly_api_calls(&cfg)
+    );
+    if let Some(warning) = api_budget_warning(&cfg) {
+        println!("{warning}");
+    }
     println!("notifier: ok");
     println!("state db: {}", state_path.display());
     Ok(())
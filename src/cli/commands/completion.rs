@@ -1,22 +1,206 @@
-use std::io;
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+};
 
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
 use clap::CommandFactory;
 use clap_complete::{generate, Shell};
+use directories::BaseDirs;
 
 use crate::cli::args::{Cli, CompletionShell};
 
+const BIN_NAME: &str = "gh-watch";
+/// Subcommands every generated script mentions; a script missing them is stale or truncated.
+const SANITY_SUBCOMMANDS: [&str; 2] = ["watch", "once"];
+
 pub(crate) fn run(shell: CompletionShell) -> Result<()> {
-    let mut cmd = Cli::command();
-    generate(
-        to_clap_shell(shell),
-        &mut cmd,
-        "gh-watch",
-        &mut io::stdout(),
-    );
+    io::Write::write_all(&mut io::stdout(), &render_script(shell))?;
+    Ok(())
+}
+
+pub(crate) fn check(shell: CompletionShell, fix: bool) -> Result<()> {
+    let path = completion_path(shell)?;
+    if fix {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        fs::write(&path, render_script(shell))
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        println!("wrote {}", path.display());
+    }
+
+    let checks = run_checks(shell, &path);
+    let name_width = checks
+        .iter()
+        .map(|check| check.name.len())
+        .max()
+        .unwrap_or(0);
+    for check in &checks {
+        let result = if check.passed { "pass" } else { "FAIL" };
+        println!("{:<name_width$}  {result}  {}", check.name, check.detail);
+    }
+
+    let failed = checks.iter().filter(|check| !check.passed).count();
+    if failed > 0 {
+        return Err(anyhow!(
+            "{failed} completion check(s) failed; run `gh-watch completion-check {} --fix` to reinstall",
+            shell_name(shell)
+        ));
+    }
     Ok(())
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CompletionCheck {
+    name: &'static str,
+    passed: bool,
+    detail: String,
+}
+
+impl CompletionCheck {
+    fn new(name: &'static str, outcome: Result<String, String>) -> Self {
+        let (passed, detail) = match outcome {
+            Ok(detail) => (true, detail),
+            Err(detail) => (false, detail),
+        };
+        Self {
+            name,
+            passed,
+            detail,
+        }
+    }
+}
+
+/// Runs the checks in order; each one after the first only runs once its predecessor passed.
+fn run_checks(shell: CompletionShell, path: &Path) -> Vec<CompletionCheck> {
+    let mut checks = vec![CompletionCheck::new("exists", check_exists(path))];
+    if !checks[0].passed {
+        return checks;
+    }
+
+    let script = fs::read_to_string(path);
+    checks.push(CompletionCheck::new(
+        "readable",
+        script
+            .as_ref()
+            .map(|script| format!("{} bytes", script.len()))
+            .map_err(|err| err.to_string()),
+    ));
+    let Ok(script) = script else {
+        return checks;
+    };
+
+    checks.push(CompletionCheck::new("contents", check_contents(&script)));
+    if matches!(shell, CompletionShell::Bash) && checks.iter().all(|check| check.passed) {
+        checks.push(CompletionCheck::new(
+            "registered",
+            check_bash_registration(path),
+        ));
+    }
+    checks
+}
+
+fn check_exists(path: &Path) -> Result<String, String> {
+    if path.is_file() {
+        Ok(path.display().to_string())
+    } else {
+        Err(format!("{} not found", path.display()))
+    }
+}
+
+fn check_contents(script: &str) -> Result<String, String> {
+    let missing = SANITY_SUBCOMMANDS
+        .iter()
+        .filter(|subcommand| !script.contains(*subcommand))
+        .copied()
+        .collect::<Vec<_>>();
+    if missing.is_empty() {
+        Ok(format!("mentions {}", SANITY_SUBCOMMANDS.join(", ")))
+    } else {
+        Err(format!("missing subcommands: {}", missing.join(", ")))
+    }
+}
+
+/// Sources the script in a clean bash and asks bash whether `gh-watch` now has a completion.
+fn check_bash_registration(path: &Path) -> Result<String, String> {
+    let output = Command::new("bash")
+        .arg("--norc")
+        .arg("-c")
+        .arg(format!("source \"$1\" && complete -p {BIN_NAME}"))
+        .arg("bash")
+        .arg(path)
+        .output()
+        .map_err(|err| format!("failed to run bash: {err}"))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(format!(
+            "`complete -p {BIN_NAME}` failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+fn completion_path(shell: CompletionShell) -> Result<PathBuf> {
+    let home = BaseDirs::new()
+        .map(|dirs| dirs.home_dir().to_path_buf())
+        .ok_or_else(|| anyhow!("could not determine home directory"))?;
+    Ok(completion_path_in(
+        shell,
+        &home,
+        xdg_dir("XDG_DATA_HOME"),
+        xdg_dir("XDG_CONFIG_HOME"),
+    ))
+}
+
+/// The XDG spec treats unset, empty and relative values alike: fall back to the default.
+fn xdg_dir(key: &str) -> Option<PathBuf> {
+    env::var_os(key)
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+}
+
+/// Where each shell looks for the script without extra rc-file setup, except zsh and pwsh,
+/// which use the locations the README tells users to add to `fpath` and `$PROFILE`.
+fn completion_path_in(
+    shell: CompletionShell,
+    home: &Path,
+    xdg_data_home: Option<PathBuf>,
+    xdg_config_home: Option<PathBuf>,
+) -> PathBuf {
+    match shell {
+        CompletionShell::Bash => xdg_data_home
+            .unwrap_or_else(|| home.join(".local/share"))
+            .join("bash-completion/completions")
+            .join(BIN_NAME),
+        CompletionShell::Zsh => home.join(".zfunc").join(format!("_{BIN_NAME}")),
+        CompletionShell::Fish => xdg_config_home
+            .unwrap_or_else(|| home.join(".config"))
+            .join("fish/completions")
+            .join(format!("{BIN_NAME}.fish")),
+        CompletionShell::Pwsh => home.join(format!(".{BIN_NAME}.ps1")),
+    }
+}
+
+fn render_script(shell: CompletionShell) -> Vec<u8> {
+    let mut cmd = Cli::command();
+    let mut script = Vec::new();
+    generate(to_clap_shell(shell), &mut cmd, BIN_NAME, &mut script);
+    script
+}
+
+fn shell_name(shell: CompletionShell) -> &'static str {
+    match shell {
+        CompletionShell::Bash => "bash",
+        CompletionShell::Zsh => "zsh",
+        CompletionShell::Fish => "fish",
+        CompletionShell::Pwsh => "pwsh",
+    }
+}
+
 fn to_clap_shell(shell: CompletionShell) -> Shell {
     match shell {
         CompletionShell::Bash => Shell::Bash,
@@ -25,3 +209,69 @@ fn to_clap_shell(shell: CompletionShell) -> Shell {
         CompletionShell::Pwsh => Shell::PowerShell,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use tempfile::tempdir;
+
+    use super::{check_contents, completion_path_in, render_script, run_checks};
+    use crate::cli::args::CompletionShell;
+
+    #[test]
+    fn missing_file_fails_existence_and_skips_later_checks() {
+        let dir = tempdir().unwrap();
+
+        let checks = run_checks(CompletionShell::Zsh, &dir.path().join("_gh-watch"));
+
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].name, "exists");
+        assert!(!checks[0].passed);
+    }
+
+    #[test]
+    fn generated_script_passes_existence_and_content_checks() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("gh-watch.fish");
+        fs::write(&path, render_script(CompletionShell::Fish)).unwrap();
+
+        let checks = run_checks(CompletionShell::Fish, &path);
+
+        let names = checks.iter().map(|check| check.name).collect::<Vec<_>>();
+        assert_eq!(names, ["exists", "readable", "contents"]);
+        assert!(checks.iter().all(|check| check.passed), "{checks:?}");
+    }
+
+    #[test]
+    fn content_check_names_missing_subcommands() {
+        assert_eq!(
+            check_contents("complete -c gh-watch -a watch"),
+            Err("missing subcommands: once".to_string())
+        );
+        assert!(check_contents("watch once").is_ok());
+    }
+
+    #[test]
+    fn completion_paths_follow_xdg_overrides() {
+        let home = Path::new("/home/me");
+
+        assert_eq!(
+            completion_path_in(CompletionShell::Bash, home, None, None),
+            home.join(".local/share/bash-completion/completions/gh-watch")
+        );
+        assert_eq!(
+            completion_path_in(
+                CompletionShell::Fish,
+                home,
+                None,
+                Some("/xdg/config".into())
+            ),
+            Path::new("/xdg/config/fish/completions/gh-watch.fish")
+        );
+        assert_eq!(
+            completion_path_in(CompletionShell::Zsh, home, Some("/xdg/data".into()), None),
+            home.join(".zfunc/_gh-watch")
+        );
+    }
+}
@@ -1,17 +1,68 @@
-use std::{ffi::OsStr, path::Path, process::Command};
+use std::{
+    ffi::OsStr,
+    fs,
+    io::{self, BufRead, Write},
+    path::Path,
+    process::Stdio,
+    time::Duration,
+};
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::Utc;
+use tokio::process::Command;
+use toml_edit::{DocumentMut, Item};
 
-use crate::{cli::args::ConfigCommands, config::resolve_config_path_with_source};
+use crate::{
+    app::{
+        clock_skew::{measure_clock_skew, ClockSkewLevel},
+        viewer_teams::refresh_viewer_teams,
+    },
+    cli::{
+        args::ConfigCommands,
+        error::ClassifiedError,
+        state::{open_state_store, resolve_state_db_path},
+        SystemClock,
+    },
+    config::{
+        find_unknown_config_keys, load_config, parse_config, resolve_config_path_with_source,
+        schema::{config_schema, config_schema_violations},
+        stability_warnings, Config, DEFAULT_FIELD_VALUES,
+    },
+    domain::latency::{format_latency, latency_hints, LatencySummary},
+    infra::{
+        config_backup::{
+            find_config_backup, list_config_backups, restore_config_backup,
+            write_config_with_backup, ConfigBackup,
+        },
+        gh_client::{GhCliClient, ProxySettings},
+    },
+    ui::tui::Keymap,
+};
 
-pub(crate) fn run(command: ConfigCommands) -> Result<()> {
+const EXAMPLE_CONFIG: &str = include_str!("../../../config.example.toml");
+const OPENER_TIMEOUT: Duration = Duration::from_secs(5);
+const LATENCY_WINDOW_DAYS: i64 = 7;
+
+pub(crate) async fn run(command: ConfigCommands) -> Result<()> {
     match command {
-        ConfigCommands::Open => run_open_cmd(),
+        ConfigCommands::Open => run_open_cmd().await,
         ConfigCommands::Path => run_path_cmd(),
+        ConfigCommands::Reset { key, force } => run_reset_cmd(key.as_deref(), force),
+        ConfigCommands::Defaults => run_defaults_cmd(),
+        ConfigCommands::Schema => run_schema_cmd(),
+        ConfigCommands::Doctor {
+            config,
+            latency,
+            refresh_teams,
+        } => run_doctor_cmd(config.as_deref(), latency, refresh_teams).await,
+        ConfigCommands::Rollback { to, config } => {
+            run_rollback_cmd(to.as_deref(), config.as_deref())
+        }
+        ConfigCommands::ShowKeymap { config } => run_show_keymap_cmd(config.as_deref()),
     }
 }
 
-fn run_open_cmd() -> Result<()> {
+async fn run_open_cmd() -> Result<()> {
     let resolved = resolve_config_path_with_source(None)?;
     if !resolved.path.exists() {
         return Err(anyhow!(
@@ -21,7 +72,7 @@ fn run_open_cmd() -> Result<()> {
         ));
     }
 
-    open_config_file(&resolved.path)
+    open_config_file(&resolved.path).await
 }
 
 fn run_path_cmd() -> Result<()> {
@@ -30,20 +81,382 @@ fn run_path_cmd() -> Result<()> {
     Ok(())
 }
 
-fn open_config_file(path: &Path) -> Result<()> {
+fn run_reset_cmd(key: Option<&str>, force: bool) -> Result<()> {
+    let resolved = resolve_config_path_with_source(None)?;
+    let before = fs::read_to_string(&resolved.path).with_context(|| {
+        format!(
+            "failed to read config: {} (source: {}, run `gh-watch init` to create it)",
+            resolved.path.display(),
+            resolved.source
+        )
+    })?;
+
+    let after = match key {
+        Some(key) => reset_config_key(&before, key)?,
+        None => reset_config_all(&before)?,
+    };
+
+    let diff = render_line_diff(&before, &after);
+    if diff.is_empty() {
+        println!("no changes: {}", resolved.path.display());
+        return Ok(());
+    }
+
+    println!("--- {}", resolved.path.display());
+    println!("+++ {} (reset)", resolved.path.display());
+    print!("{diff}");
+
+    if !force && !confirm("apply these changes? [y/N] ")? {
+        println!("aborted (use --force to skip confirmation)");
+        return Ok(());
+    }
+
+    let backup = write_config_with_backup(&resolved.path, &after, Utc::now())?;
+    print_backup(backup.as_ref());
+    println!("reset config: {}", resolved.path.display());
+    Ok(())
+}
+
+fn run_defaults_cmd() -> Result<()> {
+    for (key, value) in DEFAULT_FIELD_VALUES {
+        println!("{key} = {value}");
+    }
+    Ok(())
+}
+
+fn run_schema_cmd() -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(&config_schema())?);
+    Ok(())
+}
+
+async fn run_doctor_cmd(config: Option<&Path>, latency: bool, refresh_teams: bool) -> Result<()> {
+    let resolved = resolve_config_path_with_source(config)?;
+    let src = fs::read_to_string(&resolved.path).with_context(|| {
+        format!(
+            "failed to read config: {} (source: {}, run `gh-watch init` to create it)",
+            resolved.path.display(),
+            resolved.source
+        )
+    })?;
+    println!(
+        "config: {} (source: {})",
+        resolved.path.display(),
+        resolved.source
+    );
+
+    for unknown in find_unknown_config_keys(&src)? {
+        println!("warning: {unknown}");
+    }
+    for violation in config_schema_violations(&src)? {
+        if !violation.is_unknown_key() {
+            println!("warning: schema: {violation}");
+        }
+    }
+
+    let cfg = parse_config(&src)?;
+    for warning in stability_warnings(&cfg) {
+        println!("warning: {warning}");
+    }
+    match GhCliClient::default().probe().await {
+        Ok(gh_probe) => {
+            println!("gh: {gh_probe}");
+            if let Some(warning) = gh_probe.warning() {
+                println!("warning: gh: {warning}");
+            }
+        }
+        Err(err) => println!("warning: {err}"),
+    }
+    if let Some(proxy) = ProxySettings::from_network_config(&cfg.network) {
+        let display_url = proxy.display_url();
+        let gh = GhCliClient::default().with_proxy(Some(proxy));
+        match gh.probe_api().await {
+            Ok(latency) => println!(
+                "proxy: {display_url} ok (HEAD api.github.com in {} ms)",
+                latency.as_millis()
+            ),
+            Err(err) => println!("warning: proxy: {display_url} failed: {err:#}"),
+        }
+    }
+    match measure_clock_skew(&GhCliClient::default(), &SystemClock).await {
+        Ok(Some(skew)) => match skew.level() {
+            ClockSkewLevel::Ok => {}
+            ClockSkewLevel::Warn => println!("warning: clock: {skew}"),
+            ClockSkewLevel::Error => bail!("clock: {skew}; fix the system time before polling"),
+        },
+        Ok(None) => {}
+        Err(err) => println!("warning: clock: not compared with GitHub ({err:#})"),
+    }
+    if latency {
+        print_notification_latency(&cfg)?;
+    }
+    if refresh_teams {
+        let state = open_state_store(&resolve_state_db_path(&cfg)?)?;
+        match refresh_viewer_teams(&GhCliClient::default(), &state, Utc::now()).await {
+            Ok(teams) if teams.is_empty() => println!("teams: none (refreshed)"),
+            Ok(teams) => println!("teams: {} (refreshed)", teams.join(", ")),
+            Err(err) => {
+                println!("warning: teams: {err:#}; team mentions will not count as involving you")
+            }
+        }
+    }
+    println!("config: ok");
+    Ok(())
+}
+
+/// p50/p95 of creation-to-notification and logging-to-notification over `LATENCY_WINDOW_DAYS`.
+fn print_notification_latency(cfg: &Config) -> Result<()> {
+    let state_path = resolve_state_db_path(cfg)?;
+    if !state_path.exists() {
+        println!("latency: no state db at {}", state_path.display());
+        return Ok(());
+    }
+    let state = open_state_store(&state_path)?;
+    let since = Utc::now() - chrono::Duration::days(LATENCY_WINDOW_DAYS);
+    let timings = state.load_notification_timings(since)?;
+    let since_created = LatencySummary::from_samples(
+        timings
+            .iter()
+            .map(|timing| timing.notified_at - timing.created_at)
+            .collect(),
+    );
+    let since_observed = LatencySummary::from_samples(
+        timings
+            .iter()
+            .map(|timing| timing.notified_at - timing.observed_at)
+            .collect(),
+    );
+    let (Some(since_created), Some(since_observed)) = (since_created, since_observed) else {
+        println!("latency: no notifications in the last {LATENCY_WINDOW_DAYS} days");
+        return Ok(());
+    };
+    println!(
+        "latency: {} notified events in the last {LATENCY_WINDOW_DAYS} days",
+        since_created.samples
+    );
+    for (label, summary) in [
+        ("created -> notified", since_created),
+        ("logged -> notified", since_observed),
+    ] {
+        println!(
+            "latency: {label} p50={} p95={}",
+            format_latency(summary.p50),
+            format_latency(summary.p95)
+        );
+    }
+    for hint in latency_hints(
+        &since_created,
+        &since_observed,
+        cfg.interval_seconds,
+        cfg.notification_schedule.is_some(),
+    ) {
+        println!("hint: latency: {hint}");
+    }
+    Ok(())
+}
+
+fn run_show_keymap_cmd(config: Option<&Path>) -> Result<()> {
+    let cfg = load_config(config).context(ClassifiedError::config())?;
+    let keymap = Keymap::from_config(&cfg.keymap)?;
+    for (action, keys) in keymap.entries() {
+        if keys.is_empty() {
+            println!("{action} = (unbound)");
+        } else {
+            println!("{action} = {}", keys.join(", "));
+        }
+    }
+    println!("escape_quit = Esc Esc (fixed)");
+    println!("kind_filter = 1-9, 0 clears (fixed)");
+    Ok(())
+}
+
+fn run_rollback_cmd(to: Option<&str>, config: Option<&Path>) -> Result<()> {
+    let resolved = resolve_config_path_with_source(config)?;
+    let backups = list_config_backups(&resolved.path)?;
+    if backups.is_empty() {
+        println!("no config backups: {}", resolved.path.display());
+        return Ok(());
+    }
+
+    println!("backups of {}:", resolved.path.display());
+    for backup in &backups {
+        println!("  {}", backup.timestamp);
+    }
+
+    let selected = find_config_backup(&resolved.path, to)?;
+    let previous = restore_config_backup(&selected, &resolved.path, Utc::now(), |src| {
+        parse_config(src).map(|_| ())
+    })?;
+    print_backup(previous.as_ref());
+    println!(
+        "restored config: {} (from {})",
+        resolved.path.display(),
+        selected.timestamp
+    );
+    Ok(())
+}
+
+pub(crate) fn print_backup(backup: Option<&ConfigBackup>) {
+    if let Some(backup) = backup {
+        println!("backup: {}", backup.path.display());
+    }
+}
+
+/// Shows the `[[repositories]]` renames as a diff of the config at `path` and applies them
+/// once confirmed; returns whether the file was rewritten.
+pub(crate) fn offer_repository_renames(path: &Path, renames: &[(String, String)]) -> Result<bool> {
+    let before = fs::read_to_string(path)
+        .with_context(|| format!("failed to read config: {}", path.display()))?;
+    let after = rename_repositories(&before, renames)?;
+    let diff = render_line_diff(&before, &after);
+    if diff.is_empty() {
+        return Ok(false);
+    }
+
+    for (from, to) in renames {
+        println!("{from} was renamed to {to} on GitHub");
+    }
+    println!("--- {}", path.display());
+    println!("+++ {} (renamed repositories)", path.display());
+    print!("{diff}");
+    if !confirm("apply these changes? [y/N] ")? {
+        return Ok(false);
+    }
+
+    let backup = write_config_with_backup(path, &after, Utc::now())?;
+    print_backup(backup.as_ref());
+    println!("updated config: {}", path.display());
+    Ok(true)
+}
+
+fn confirm(prompt: &str) -> Result<bool> {
+    print!("{prompt}");
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+fn reset_config_key(src: &str, key: &str) -> Result<String> {
+    let default_value = DEFAULT_FIELD_VALUES
+        .iter()
+        .find(|(candidate, _)| *candidate == key)
+        .map(|(_, value)| *value)
+        .ok_or_else(|| {
+            anyhow!("unknown config key: {key} (run `gh-watch config defaults` to list keys)")
+        })?;
+
+    let mut doc = src
+        .parse::<DocumentMut>()
+        .context("failed to parse config TOML")?;
+    let default_item = parse_toml_item(default_value)?;
+
+    let mut parts = key.split('.').collect::<Vec<_>>();
+    let leaf = parts.pop().expect("dotted key has at least one part");
+    let mut table = doc.as_table_mut() as &mut dyn toml_edit::TableLike;
+    for part in parts {
+        table = table
+            .entry(part)
+            .or_insert(toml_edit::table())
+            .as_table_like_mut()
+            .ok_or_else(|| anyhow!("config key `{part}` is not a table"))?;
+    }
+    table.insert(leaf, default_item);
+
+    Ok(doc.to_string())
+}
+
+/// `src` with each `[[repositories]].name` found as the first of a `renames` pair replaced by
+/// the second.
+fn rename_repositories(src: &str, renames: &[(String, String)]) -> Result<String> {
+    let mut doc = src
+        .parse::<DocumentMut>()
+        .context("failed to parse config TOML")?;
+    if let Some(repositories) = doc
+        .get_mut("repositories")
+        .and_then(Item::as_array_of_tables_mut)
+    {
+        for repository in repositories.iter_mut() {
+            let Some(name) = repository.get("name").and_then(Item::as_str) else {
+                continue;
+            };
+            if let Some((_, to)) = renames.iter().find(|(from, _)| from == name) {
+                repository["name"] = toml_edit::value(to.as_str());
+            }
+        }
+    }
+    Ok(doc.to_string())
+}
+
+fn reset_config_all(src: &str) -> Result<String> {
+    let current = src.parse::<DocumentMut>().context(
+        "failed to parse config TOML; cannot keep [[repositories]] (use `gh-watch init --force`)",
+    )?;
+    let mut doc = EXAMPLE_CONFIG
+        .parse::<DocumentMut>()
+        .context("failed to parse bundled config.example.toml")?;
+
+    doc.remove("repositories");
+    if let Some(repositories) = current.get("repositories") {
+        doc.insert("repositories", repositories.clone());
+    }
+
+    Ok(doc.to_string())
+}
+
+fn parse_toml_item(raw: &str) -> Result<Item> {
+    let doc = format!("value = {raw}")
+        .parse::<DocumentMut>()
+        .with_context(|| format!("invalid default TOML value: {raw}"))?;
+    Ok(doc["value"].clone())
+}
+
+fn render_line_diff(before: &str, after: &str) -> String {
+    let old = before.lines().collect::<Vec<_>>();
+    let new = after.lines().collect::<Vec<_>>();
+
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            out.push_str(&format!("-{}\n", old[i]));
+            i += 1;
+        } else {
+            out.push_str(&format!("+{}\n", new[j]));
+            j += 1;
+        }
+    }
+    out
+}
+
+async fn open_config_file(path: &Path) -> Result<()> {
     if let Some(raw) = std::env::var_os("VISUAL") {
-        if try_editor_command(&raw, path)? {
+        if try_editor_command(&raw, path).await? {
             return Ok(());
         }
     }
 
     if let Some(raw) = std::env::var_os("EDITOR") {
-        if try_editor_command(&raw, path)? {
+        if try_editor_command(&raw, path).await? {
             return Ok(());
         }
     }
 
-    if try_os_default_opener(path)? {
+    if try_os_default_opener(path).await? {
         return Ok(());
     }
 
@@ -53,7 +466,8 @@ fn open_config_file(path: &Path) -> Result<()> {
     ))
 }
 
-fn try_editor_command(raw: &OsStr, path: &Path) -> Result<bool> {
+/// Editors are interactive and may legitimately run for a long time, so they are not timed.
+async fn try_editor_command(raw: &OsStr, path: &Path) -> Result<bool> {
     let raw = raw.to_string_lossy();
     let mut tokens = raw.split_whitespace();
     let Some(bin) = tokens.next() else {
@@ -63,42 +477,141 @@ fn try_editor_command(raw: &OsStr, path: &Path) -> Result<bool> {
     let mut cmd = Command::new(bin);
     cmd.args(tokens);
     cmd.arg(path);
-    let ok = cmd.status().map(|status| status.success()).unwrap_or(false);
+    let ok = cmd
+        .status()
+        .await
+        .map(|status| status.success())
+        .unwrap_or(false);
     Ok(ok)
 }
 
-fn try_os_default_opener(path: &Path) -> Result<bool> {
+/// A default opener that has not exited after [`OPENER_TIMEOUT`] is left running detached
+/// and reported as a timeout rather than a launch failure.
+async fn try_os_default_opener(path: &Path) -> Result<bool> {
     #[cfg(target_os = "macos")]
-    {
-        let ok = Command::new("open")
-            .arg(path)
-            .status()
-            .map(|status| status.success())
-            .unwrap_or(false);
-        return Ok(ok);
-    }
-
+    let (label, mut cmd) = ("open", Command::new("open"));
     #[cfg(target_os = "linux")]
-    {
-        let ok = Command::new("xdg-open")
-            .arg(path)
-            .status()
-            .map(|status| status.success())
-            .unwrap_or(false);
-        return Ok(ok);
-    }
-
+    let (label, mut cmd) = ("xdg-open", Command::new("xdg-open"));
     #[cfg(target_os = "windows")]
+    let (label, mut cmd) = {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", "start", ""]);
+        ("start", cmd)
+    };
+
+    #[cfg(any(target_os = "macos", target_os = "linux", target_os = "windows"))]
     {
-        let ok = Command::new("cmd")
-            .args(["/C", "start", ""])
-            .arg(path)
-            .status()
-            .map(|status| status.success())
-            .unwrap_or(false);
-        return Ok(ok);
+        cmd.arg(path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .kill_on_drop(false);
+        let Ok(mut child) = cmd.spawn() else {
+            return Ok(false);
+        };
+        return match tokio::time::timeout(OPENER_TIMEOUT, child.wait()).await {
+            Ok(status) => Ok(status.map(|status| status.success()).unwrap_or(false)),
+            Err(_) => Err(anyhow!(
+                "timed out opening config: {label} did not exit within {}s (left running): {}",
+                OPENER_TIMEOUT.as_secs(),
+                path.display()
+            )),
+        };
     }
 
     #[allow(unreachable_code)]
     Ok(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{rename_repositories, render_line_diff, reset_config_all, reset_config_key};
+    use crate::config::{parse_config, DEFAULT_FIELD_VALUES};
+
+    const USER_CONFIG: &str = r#"interval_seconds = 60 # fast
+
+[notifications]
+enabled = false
+include_url = true
+
+[[repositories]]
+name = "acme/api"
+"#;
+
+    #[test]
+    fn reset_key_restores_default_and_keeps_other_lines() {
+        let out = reset_config_key(USER_CONFIG, "notifications.enabled").unwrap();
+
+        let cfg = parse_config(&out).unwrap();
+        assert!(cfg.notifications.enabled);
+        assert_eq!(cfg.interval_seconds, 60);
+        assert!(out.contains("interval_seconds = 60 # fast"));
+        assert_eq!(
+            render_line_diff(USER_CONFIG, &out),
+            "-enabled = false\n+enabled = true\n"
+        );
+    }
+
+    #[test]
+    fn rename_repositories_changes_only_the_matching_name() {
+        let src = format!("{USER_CONFIG}\n[[repositories]]\nname = \"acme/web\"\n");
+        let out = rename_repositories(
+            &src,
+            &[("acme/api".to_string(), "acme/platform".to_string())],
+        )
+        .unwrap();
+
+        let cfg = parse_config(&out).unwrap();
+        assert_eq!(cfg.repositories[0].name, "acme/platform");
+        assert_eq!(cfg.repositories[1].name, "acme/web");
+        assert_eq!(
+            render_line_diff(&src, &out),
+            "-name = \"acme/api\"\n+name = \"acme/platform\"\n"
+        );
+    }
+
+    #[test]
+    fn reset_key_creates_missing_table() {
+        let out = reset_config_key(USER_CONFIG, "poll.timeout_seconds").unwrap();
+
+        assert_eq!(parse_config(&out).unwrap().poll.timeout_seconds, 30);
+        assert!(out.contains("[poll]"));
+    }
+
+    #[test]
+    fn reset_key_rejects_unknown_key() {
+        let err = reset_config_key(USER_CONFIG, "notifications.sound").unwrap_err();
+        assert!(err.to_string().contains("gh-watch config defaults"));
+    }
+
+    #[test]
+    fn reset_all_uses_example_defaults_and_keeps_repositories() {
+        let out = reset_config_all(USER_CONFIG).unwrap();
+
+        let cfg = parse_config(&out).unwrap();
+        assert_eq!(cfg.interval_seconds, 300);
+        assert!(cfg.notifications.enabled);
+        assert_eq!(cfg.repositories.len(), 1);
+        assert_eq!(cfg.repositories[0].name, "acme/api");
+        assert!(!out.contains("owner/repo-one"));
+
+        let diff = render_line_diff(USER_CONFIG, &out);
+        assert!(diff.contains("-interval_seconds = 60 # fast\n"));
+        assert!(diff.contains("+interval_seconds = 300\n"));
+        assert!(!diff.contains("name = \"acme/api\""));
+    }
+
+    #[test]
+    fn every_default_field_value_round_trips_through_parse_config() {
+        let mut out = USER_CONFIG.to_string();
+        for (key, _) in DEFAULT_FIELD_VALUES {
+            out = reset_config_key(&out, key).unwrap();
+        }
+
+        let cfg = parse_config(&out).unwrap();
+        assert_eq!(cfg.interval_seconds, 300);
+        assert!(cfg.notifications.enabled);
+        assert!(cfg.retention.days_by_kind.is_empty());
+        assert_eq!(cfg.poll.timeout_seconds, 30);
+    }
+}
@@ -0,0 +1,348 @@
+use std::{
+    cmp::Reverse,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::{Context, Result};
+
+use crate::{
+    app::poll_once::poll_once,
+    cli::{
+        commands::once::DryRunStateStore,
+        state::{open_state_store, resolve_state_db_path},
+        SystemClock,
+    },
+    config::{load_config_with_path, resolve_config_path_with_source, Config},
+    infra::{
+        gh_client::{AccountGhClients, GhCliClient},
+        notifier::{DesktopNotifier, NoopNotifier},
+        state_sqlite::SqliteStateStore,
+    },
+    ports::{GhClientPort, NotifierPort},
+};
+
+const RECENT_FAILURE_LIMIT: usize = 3;
+
+pub(crate) struct DiagnoseOptions {
+    pub(crate) config: Option<PathBuf>,
+    pub(crate) output: Option<PathBuf>,
+    pub(crate) no_redact: bool,
+}
+
+pub(crate) async fn run(options: DiagnoseOptions) -> Result<()> {
+    let diagnosis = diagnose(options.config.as_deref()).await;
+    let mut report = diagnosis.render();
+    if !options.no_redact {
+        report = RepoRedactor::new(diagnosis.repos.iter().map(String::as_str)).redact(&report);
+    }
+
+    match options.output {
+        Some(path) => {
+            fs::write(&path, &report)
+                .with_context(|| format!("failed to write diagnosis to {}", path.display()))?;
+            println!("wrote diagnosis to {}", path.display());
+        }
+        None => print!("{report}"),
+    }
+    Ok(())
+}
+
+/// Report lines plus every repository name they may mention, for redaction.
+#[derive(Debug, Default)]
+struct Diagnosis {
+    lines: Vec<String>,
+    repos: Vec<String>,
+}
+
+impl Diagnosis {
+    fn field(&mut self, name: &str, value: impl std::fmt::Display) {
+        self.lines.push(format!("{name}: {value}"));
+    }
+
+    fn item(&mut self, value: impl std::fmt::Display) {
+        self.lines.push(format!("  - {value}"));
+    }
+
+    fn render(&self) -> String {
+        format!("```text\n{}\n```\n", self.lines.join("\n"))
+    }
+}
+
+async fn diagnose(config: Option<&Path>) -> Diagnosis {
+    let mut diagnosis = Diagnosis::default();
+    diagnosis.field("gh-watch", env!("CARGO_PKG_VERSION"));
+    diagnosis.field("os", os_description());
+    match GhCliClient::default().probe().await {
+        Ok(probe) => {
+            diagnosis.field("gh", &probe);
+            if let Some(warning) = probe.warning() {
+                diagnosis.field("gh warning", warning);
+            }
+        }
+        Err(err) => diagnosis.field("gh", format_args!("error: {err}")),
+    }
+
+    let loaded = match load_config_with_path(config) {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            if let Ok(resolved) = resolve_config_path_with_source(config) {
+                diagnosis.field(
+                    "config",
+                    format_args!("{} (source: {})", resolved.path.display(), resolved.source),
+                );
+            }
+            // Only the outermost message: parse errors quote the offending line, which
+            // may hold a token.
+            diagnosis.field("config status", format_args!("error: {err}"));
+            return diagnosis;
+        }
+    };
+    let cfg = loaded.config;
+    diagnosis.field(
+        "config",
+        format_args!(
+            "{} (source: {})",
+            loaded.resolved_path.path.display(),
+            loaded.resolved_path.source
+        ),
+    );
+    diagnosis.field(
+        "config status",
+        format_args!("ok ({} repositories)", cfg.repositories.len()),
+    );
+    diagnosis
+        .repos
+        .extend(cfg.repositories.iter().map(|repo| repo.name.clone()));
+
+    let notifier = DesktopNotifier::from_notification_config(&cfg.notifications);
+    diagnosis.field("notification backend", notifier.backend_name());
+    diagnosis.field(
+        "notification health",
+        match notifier.check_health() {
+            Ok(()) => "ok".to_string(),
+            Err(err) => format!("failed ({err:#})"),
+        },
+    );
+
+    let store = match resolve_state_db_path(&cfg).and_then(|path| open_state_store(&path)) {
+        Ok(store) => store,
+        Err(err) => {
+            diagnosis.field("state db", format_args!("error: {err:#}"));
+            return diagnosis;
+        }
+    };
+    diagnose_state(&mut diagnosis, &store);
+    diagnose_poll(&mut diagnosis, &cfg, &store).await;
+    diagnosis
+}
+
+fn diagnose_state(diagnosis: &mut Diagnosis, store: &SqliteStateStore) {
+    match store.stored_schema_version() {
+        Ok(version) => {
+            diagnosis.field("state db schema", version.as_deref().unwrap_or("(missing)"))
+        }
+        Err(err) => diagnosis.field("state db schema", format_args!("error: {err:#}")),
+    }
+
+    match store.load_recent_failures(RECENT_FAILURE_LIMIT) {
+        Ok(failures) if failures.is_empty() => diagnosis.field("recent failures", "none"),
+        Ok(failures) => {
+            diagnosis.field("recent failures", failures.len());
+            for failure in failures {
+                diagnosis.item(format_args!(
+                    "{} {} {}: {}",
+                    failure.failed_at.to_rfc3339(),
+                    failure.kind,
+                    failure.repo,
+                    failure.message
+                ));
+                diagnosis.repos.push(failure.repo);
+            }
+        }
+        Err(err) => diagnosis.field("recent failures", format_args!("error: {err:#}")),
+    }
+}
+
+async fn diagnose_poll(diagnosis: &mut Diagnosis, cfg: &Config, store: &SqliteStateStore) {
+    let gh = match AccountGhClients::from_config(cfg, GhCliClient::default()) {
+        Ok(gh) => gh,
+        Err(err) => {
+            diagnosis.field("gh auth", format_args!("error: {err:#}"));
+            return;
+        }
+    };
+    match gh.check_auth().await {
+        Ok(auth) => diagnosis.field("gh auth", format_args!("ok ({})", auth.hostname)),
+        Err(err) => {
+            diagnosis.field("gh auth", format_args!("failed ({err:#})"));
+            return;
+        }
+    }
+
+    let dry_run_state = DryRunStateStore::new(store);
+    match poll_once(cfg, &gh, &dry_run_state, &NoopNotifier, &SystemClock).await {
+        Ok(outcome) => {
+            diagnosis.field(
+                "dry-run poll",
+                format_args!(
+                    "{} fetched, {} failed, {} timeline events, {} api calls",
+                    outcome.fetched_repos.len(),
+                    outcome.fetch_failures.len(),
+                    outcome.timeline_events.len(),
+                    outcome.api_calls
+                ),
+            );
+            for failure in outcome.fetch_failures {
+                diagnosis.item(format_args!("{}: {}", failure.repo, failure.message));
+                diagnosis.repos.push(failure.repo);
+            }
+            diagnosis.repos.extend(outcome.fetched_repos);
+        }
+        Err(err) => diagnosis.field("dry-run poll", format_args!("failed ({err:#})")),
+    }
+}
+
+fn os_description() -> String {
+    let version = if cfg!(target_os = "macos") {
+        command_output("sw_vers", &["-productVersion"])
+    } else {
+        command_output("uname", &["-r"])
+    };
+    format!(
+        "{} {} ({})",
+        std::env::consts::OS,
+        version.as_deref().unwrap_or("(unknown version)"),
+        std::env::consts::ARCH
+    )
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8(output.stdout).ok()?;
+    Some(text.trim().to_string()).filter(|text| !text.is_empty())
+}
+
+/// Replaces repository names with `owner/REPO_N`, numbered in the order they were first
+/// seen. Matching ignores ASCII case and stops at name boundaries, so `acme/api` does not
+/// clobber `acme/api-v2`.
+struct RepoRedactor {
+    /// Lowercased name and its placeholder, longest name first.
+    replacements: Vec<(String, String)>,
+}
+
+impl RepoRedactor {
+    fn new<'a>(repos: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut replacements: Vec<(String, String)> = Vec::new();
+        for repo in repos {
+            let name = repo.to_ascii_lowercase();
+            if !replacements.iter().any(|(known, _)| *known == name) {
+                let placeholder = format!("owner/REPO_{}", replacements.len() + 1);
+                replacements.push((name, placeholder));
+            }
+        }
+        replacements.sort_by_key(|(name, _)| Reverse(name.len()));
+        Self { replacements }
+    }
+
+    fn redact(&self, text: &str) -> String {
+        self.replacements
+            .iter()
+            .fold(text.to_string(), |text, (name, placeholder)| {
+                replace_repo(&text, name, placeholder)
+            })
+    }
+}
+
+fn replace_repo(text: &str, name: &str, placeholder: &str) -> String {
+    let lowered = text.to_ascii_lowercase();
+    let mut redacted = String::with_capacity(text.len());
+    let mut copied = 0;
+    let mut search_from = 0;
+    while let Some(offset) = lowered[search_from..].find(name) {
+        let start = search_from + offset;
+        let end = start + name.len();
+        search_from = end;
+        if starts_name(&text[..start]) && ends_name(&text[end..]) {
+            redacted.push_str(&text[copied..start]);
+            redacted.push_str(placeholder);
+            copied = end;
+        }
+    }
+    redacted.push_str(&text[copied..]);
+    redacted
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')
+}
+
+fn starts_name(before: &str) -> bool {
+    before.chars().next_back().is_none_or(|c| !is_name_char(c))
+}
+
+/// A trailing `.` only continues the name when more name characters follow it.
+fn ends_name(after: &str) -> bool {
+    let mut chars = after.chars();
+    match chars.next() {
+        None => true,
+        Some('.') => chars.next().is_none_or(|c| !is_name_char(c)),
+        Some(c) => !is_name_char(c),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RepoRedactor;
+
+    #[test]
+    fn repos_are_numbered_in_first_seen_order() {
+        let redactor = RepoRedactor::new(["acme/web", "acme/api", "ACME/Web"]);
+
+        assert_eq!(
+            redactor.redact("acme/api failed after acme/web"),
+            "owner/REPO_2 failed after owner/REPO_1"
+        );
+    }
+
+    #[test]
+    fn matching_ignores_case_and_covers_urls() {
+        let redactor = RepoRedactor::new(["acme/api"]);
+
+        assert_eq!(
+            redactor.redact("gh: GET repos/Acme/API/pulls: 404 (https://github.com/acme/api)"),
+            "gh: GET repos/owner/REPO_1/pulls: 404 (https://github.com/owner/REPO_1)"
+        );
+    }
+
+    #[test]
+    fn longer_names_sharing_a_prefix_are_left_alone() {
+        let redactor = RepoRedactor::new(["acme/api"]);
+
+        assert_eq!(
+            redactor.redact("acme/api-v2 and acme/api.js and myacme/api"),
+            "acme/api-v2 and acme/api.js and myacme/api"
+        );
+    }
+
+    #[test]
+    fn overlapping_names_each_get_their_own_placeholder() {
+        let redactor = RepoRedactor::new(["acme/api", "acme/api-v2"]);
+
+        assert_eq!(
+            redactor.redact("acme/api-v2, acme/api."),
+            "owner/REPO_2, owner/REPO_1."
+        );
+    }
+
+    #[test]
+    fn no_repos_leaves_text_unchanged() {
+        let text = "```text\ngh-watch: 0.1.0\n```\n";
+
+        assert_eq!(RepoRedactor::new([]).redact(text), text);
+    }
+}
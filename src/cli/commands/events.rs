@@ -0,0 +1,66 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+use crate::{
+    cli::state::{open_state_store, resolve_state_db_path},
+    config::Config,
+    domain::events::WatchEvent,
+    ports::TimelineQueryPort,
+};
+
+pub(crate) fn run_search(cfg: Config, search: &str, limit: usize) -> Result<()> {
+    let state_path = resolve_state_db_path(&cfg)?;
+    let state = open_state_store(&state_path)?;
+
+    let events = state.search_events(search, limit)?;
+    if events.is_empty() {
+        println!("no events matched: {search}");
+        return Ok(());
+    }
+
+    print_events(&events);
+    Ok(())
+}
+
+pub(crate) fn run_list(
+    cfg: Config,
+    since: DateTime<Utc>,
+    page_token: Option<DateTime<Utc>>,
+    limit: usize,
+) -> Result<()> {
+    let state_path = resolve_state_db_path(&cfg)?;
+    let state = open_state_store(&state_path)?;
+
+    let events = state.find_events_page(since, page_token, limit)?;
+    if events.is_empty() {
+        println!("no events since {}", since.to_rfc3339());
+        return Ok(());
+    }
+
+    print_events(&events);
+    if events.len() == limit {
+        if let Some(last) = events.last() {
+            println!("next page: --page-token {}", last.created_at.to_rfc3339());
+        }
+    }
+    Ok(())
+}
+
+pub(super) fn print_events(events: &[WatchEvent]) {
+    for event in events {
+        println!("{}", format_event(event));
+    }
+}
+
+/// Two lines: `<created_at> <repo> <kind> @<actor> <title>`, then the indented URL.
+pub(super) fn format_event(event: &WatchEvent) -> String {
+    format!(
+        "{} {} {} @{} {}\n  {}",
+        event.created_at.to_rfc3339(),
+        event.repo,
+        event.kind,
+        event.actor,
+        event.title,
+        event.url
+    )
+}
@@ -0,0 +1,30 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+
+use crate::{
+    app::feed::{load_feed_events, render_atom_feed},
+    cli::state::{open_state_store, resolve_state_db_path},
+    config::Config,
+};
+
+pub(crate) struct FeedOptions {
+    pub(crate) out: Option<PathBuf>,
+    pub(crate) repos: Vec<String>,
+    pub(crate) limit: usize,
+}
+
+pub(crate) fn run(cfg: Config, options: FeedOptions) -> Result<()> {
+    let state = open_state_store(&resolve_state_db_path(&cfg)?)?;
+    let events = load_feed_events(&state, &options.repos, options.limit)?;
+    let feed = render_atom_feed(&events, Utc::now());
+
+    let Some(out) = options.out else {
+        print!("{feed}");
+        return Ok(());
+    };
+    fs::write(&out, feed).with_context(|| format!("failed to write {}", out.display()))?;
+    println!("wrote {} entries to {}", events.len(), out.display());
+    Ok(())
+}
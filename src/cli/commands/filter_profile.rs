@@ -0,0 +1,178 @@
+use std::{fs, path::Path};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use toml_edit::{DocumentMut, Item, Table};
+
+use crate::{
+    cli::{
+        args::FilterProfileCommands, commands::config::print_backup, error::ClassifiedError,
+        state::resolve_filter_profile_path,
+    },
+    config::{
+        load_config_with_path, parse_config, resolve_config_path_with_source,
+        validate_filter_profile_name, Config, DEFAULT_FILTER_PROFILE,
+    },
+    infra::{config_backup::write_config_with_backup, filter_profile_file::FilterProfileFile},
+    ports::FilterProfilePort,
+};
+
+pub(crate) fn run(command: FilterProfileCommands) -> Result<()> {
+    match command {
+        FilterProfileCommands::List { config } => run_list_cmd(config.as_deref()),
+        FilterProfileCommands::Switch { name, config } => run_switch_cmd(&name, config.as_deref()),
+        FilterProfileCommands::Create { name, from, config } => {
+            run_create_cmd(&name, from.as_deref(), config.as_deref())
+        }
+    }
+}
+
+fn run_list_cmd(config: Option<&Path>) -> Result<()> {
+    let cfg = load_config_with_path(config)
+        .context(ClassifiedError::config())?
+        .config;
+    let active = active_filter_profile_name(&cfg)?;
+
+    let mut names = cfg.filter_profiles.keys().cloned().collect::<Vec<_>>();
+    names.sort();
+    names.insert(0, DEFAULT_FILTER_PROFILE.to_string());
+    for name in names {
+        let marker = if name == active { "*" } else { " " };
+        println!("{marker} {name}");
+    }
+    Ok(())
+}
+
+fn run_switch_cmd(name: &str, config: Option<&Path>) -> Result<()> {
+    let cfg = load_config_with_path(config)
+        .context(ClassifiedError::config())?
+        .config;
+    if name != DEFAULT_FILTER_PROFILE && !cfg.filter_profiles.contains_key(name) {
+        return Err(anyhow!(
+            "unknown filter profile: {name} (run `gh-watch filter-profile list`)"
+        ));
+    }
+
+    let file = FilterProfileFile::new(resolve_filter_profile_path(&cfg)?);
+    file.save_active_filter_profile(name)?;
+    println!("active filter profile: {name} (applies from the next poll)");
+    Ok(())
+}
+
+fn run_create_cmd(name: &str, from: Option<&str>, config: Option<&Path>) -> Result<()> {
+    validate_filter_profile_name(name)?;
+
+    let resolved = resolve_config_path_with_source(config)?;
+    let src = fs::read_to_string(&resolved.path).with_context(|| {
+        format!(
+            "failed to read config: {} (source: {}, run `gh-watch init` to create it)",
+            resolved.path.display(),
+            resolved.source
+        )
+    })?;
+
+    let updated = create_filter_profile(&src, name, from)?;
+    parse_config(&updated)?;
+    let backup = write_config_with_backup(&resolved.path, &updated, Utc::now())?;
+    print_backup(backup.as_ref());
+    println!(
+        "created filter profile: {name} ({})",
+        resolved.path.display()
+    );
+    Ok(())
+}
+
+fn active_filter_profile_name(cfg: &Config) -> Result<String> {
+    let file = FilterProfileFile::new(resolve_filter_profile_path(cfg)?);
+    let selected = file
+        .load_active_filter_profile()?
+        .filter(|name| name == DEFAULT_FILTER_PROFILE || cfg.filter_profiles.contains_key(name));
+    Ok(selected
+        .or_else(|| cfg.active_filter_profile.clone())
+        .unwrap_or_else(|| DEFAULT_FILTER_PROFILE.to_string()))
+}
+
+fn create_filter_profile(src: &str, name: &str, from: Option<&str>) -> Result<String> {
+    let mut doc = src
+        .parse::<DocumentMut>()
+        .context("failed to parse config TOML")?;
+
+    let exists = doc
+        .get("filter_profiles")
+        .and_then(Item::as_table_like)
+        .is_some_and(|profiles| profiles.contains_key(name));
+    if exists {
+        return Err(anyhow!("filter profile already exists: {name}"));
+    }
+
+    let source = match from.unwrap_or(DEFAULT_FILTER_PROFILE) {
+        DEFAULT_FILTER_PROFILE => doc.get("filters"),
+        other => Some(
+            doc.get("filter_profiles")
+                .and_then(|profiles| profiles.get(other))
+                .ok_or_else(|| anyhow!("unknown filter profile: {other}"))?,
+        ),
+    };
+    let mut profile = Table::new();
+    if let Some(source) = source.and_then(Item::as_table_like) {
+        for (key, value) in source.iter() {
+            profile.insert(key, value.clone());
+        }
+    }
+
+    let profiles = doc
+        .entry("filter_profiles")
+        .or_insert(toml_edit::table())
+        .as_table_mut()
+        .ok_or_else(|| anyhow!("config key `filter_profiles` is not a table"))?;
+    profiles.set_implicit(true);
+    profiles.insert(name, Item::Table(profile));
+
+    Ok(doc.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::create_filter_profile;
+    use crate::config::parse_config;
+
+    const SRC: &str = r#"[filters]
+only_involving_me = true
+
+[filter_profiles.evening]
+ignore_actors = ["dependabot[bot]"]
+
+[[repositories]]
+name = "acme/api"
+"#;
+
+    #[test]
+    fn create_copies_default_filters_when_from_is_omitted() {
+        let updated = create_filter_profile(SRC, "work", None).unwrap();
+
+        let cfg = parse_config(&updated).unwrap();
+        assert!(cfg.filter_profiles["work"].only_involving_me);
+        assert!(updated.contains("[filter_profiles.work]"));
+    }
+
+    #[test]
+    fn create_copies_named_profile() {
+        let updated = create_filter_profile(SRC, "night", Some("evening")).unwrap();
+
+        let cfg = parse_config(&updated).unwrap();
+        assert_eq!(
+            cfg.filter_profiles["night"].ignore_actors,
+            vec!["dependabot[bot]".to_string()]
+        );
+        assert!(!cfg.filter_profiles["night"].only_involving_me);
+    }
+
+    #[test]
+    fn create_rejects_existing_or_unknown_source_profile() {
+        let err = create_filter_profile(SRC, "evening", None).unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+
+        let err = create_filter_profile(SRC, "night", Some("missing")).unwrap_err();
+        assert!(err.to_string().contains("unknown filter profile: missing"));
+    }
+}
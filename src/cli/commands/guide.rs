@@ -2,19 +2,45 @@ use anyhow::Result;
 
 const GUIDE: &str = "\
 Core Commands
-  gh-watch watch [--config <path>] [--interval-seconds <n>]
-  gh-watch once [--config <path>] [--dry-run] [--json]
+  gh-watch watch [--config <path>] [--interval-seconds <n>] [--alert-after-idle <duration>] [--alert-idleness-cooldown <duration>] [--poll-once-then-watch [--json]] [--no-tui] [--export-on-exit <path> [--export-format json|ndjson|csv]] [--verbose-poll]
+  gh-watch once [--config <path>] [--dry-run] [--json] [--output-file <path> [--append]] [--no-cache]
   gh-watch check [--config <path>]
   gh-watch init [--path <path>] [--force] [--reset-state]
+  gh-watch init --from-org <org> | --from-starred [--include-archived] [--limit <n>] [--yes]
+  gh-watch init --template <name> | --template-url <url> [--path <path>] [--force]
+  gh-watch init --list-templates
   gh-watch config open
   gh-watch config path
+  gh-watch config reset [--key <dotted.key>] [--force]
+  gh-watch config defaults
+  gh-watch config schema
+  gh-watch config doctor [--config <path>] [--latency]
+  gh-watch config rollback [--to <timestamp>] [--config <path>]
+  gh-watch config show-keymap [--config <path>]
+  gh-watch launchd [--config <path>] [--output <path>] [--log-dir <dir>] [--install [--start]]
+  gh-watch events [--search <query> | --since <duration> [--page-token <token>]] [--config <path>] [--limit <n>]
+  gh-watch search <query> [--repo <owner/name>]... [--config <path>] [--limit <n>] [--format text|json]
+  gh-watch stream [--format text|json] [--config <path>]
+  gh-watch report [--since <duration>] [--format markdown|text|json] [--split-by repo] [--repo <owner/name>]... [--actor <login>]... [--out <path> [--append] [--compress] [--open]] [--config <path>]
+  gh-watch serve [--secret-file <path>] [--listen <addr>] [--feed] [--config <path>]
+  gh-watch feed [--out <path>] [--repo <owner/name>]... [--limit <n>] [--config <path>]
+  gh-watch state check [--config <path>] [--repair]
+  gh-watch state vacuum [--config <path>] [--compress]
+  gh-watch state export [--config <path>] [--output <path>]
+  gh-watch state import [--config <path>] <input>
+  gh-watch sync now [--config <path>]
+  gh-watch diagnose [--config <path>] [--output <path>] [--no-redact]
+  gh-watch profile list
+  gh-watch filter-profile list|switch <name>|create <name> [--from <name>] [--config <path>]
   gh-watch commands
   gh-watch completion <shell>
+  gh-watch completion-check <shell> [--fix]
 
 Tab Completion
   Generate a shell completion script and load it in your shell.
   Example:
     gh-watch completion zsh > ~/.zfunc/_gh-watch
+    gh-watch completion-check zsh
 ";
 
 pub(crate) fn run() -> Result<()> {
@@ -1,27 +1,186 @@
 use std::{
+    collections::HashMap,
     fs,
     path::{Path, PathBuf},
 };
 
 use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+
+mod templates;
 
 use crate::{
-    cli::state::{open_state_store, remove_state_db_files, resolve_state_db_path_for_reset},
-    config::installed_config_path,
+    cli::{
+        commands::config::print_backup,
+        repo_picker::{
+            confirm, fetch_repo_candidates, offer_topic_tags, print_repo_candidates,
+            topic_tags_value,
+        },
+        state::{open_state_store, remove_state_db_files, resolve_state_db_path_for_reset},
+    },
+    config::{active_profile, installed_config_path, parse_config, profile_config_path, Config},
+    infra::{
+        config_backup::write_config_with_backup,
+        gh_client::{RepoCandidate, RepoCandidateSource},
+        template_download::download_template,
+    },
 };
 
-pub(crate) fn run(path: Option<PathBuf>, force: bool) -> Result<()> {
-    let path = path.unwrap_or(installed_config_path()?);
+pub(crate) use templates::DEFAULT_TEMPLATE;
+
+const EXAMPLE_CONFIG: &str = include_str!("../../../config.example.toml");
+
+pub(crate) struct RepoCandidateOptions {
+    pub(crate) source: RepoCandidateSource,
+    pub(crate) include_archived: bool,
+    pub(crate) limit: Option<usize>,
+    pub(crate) yes: bool,
+}
+
+pub(crate) fn run(path: Option<PathBuf>, force: bool, template: &str) -> Result<()> {
+    let template = templates::find_template(template)?;
+    write_new_config(path, force, template.content)
+}
+
+/// Writes the template at `url` once it parses as a valid config and, unless `yes`, the
+/// user confirms it; the commands it would run are listed first.
+pub(crate) fn run_from_url(path: Option<PathBuf>, force: bool, url: &str, yes: bool) -> Result<()> {
+    let content = download_template(url)?;
+    let config = parse_config(&content)
+        .with_context(|| format!("template at {url} is not a valid gh-watch config"))?;
+    let commands = command_fields(&config);
+    if !commands.is_empty() {
+        println!("the template runs these commands:");
+        for (key, command) in &commands {
+            println!("  {key} = {command}");
+        }
+    }
+    if !yes && !confirm(&format!("write the template from {url}? [y/N] "))? {
+        println!("aborted (use --yes to skip confirmation)");
+        return Ok(());
+    }
+    write_new_config(path, force, &content)
+}
+
+/// Config keys whose value gh-watch executes, with their values.
+fn command_fields(config: &Config) -> Vec<(&'static str, &str)> {
+    config
+        .display
+        .browser_command
+        .as_deref()
+        .map(|command| ("display.browser_command", command))
+        .into_iter()
+        .collect()
+}
+
+pub(crate) fn list_templates() -> Result<()> {
+    let width = templates::TEMPLATES
+        .iter()
+        .map(|template| template.name.len())
+        .max()
+        .unwrap_or_default();
+    for template in &templates::TEMPLATES {
+        println!("{:width$}  {}", template.name, template.description);
+    }
+    Ok(())
+}
+
+fn write_new_config(path: Option<PathBuf>, force: bool, content: &str) -> Result<()> {
+    let path = resolve_init_path(path)?;
     prepare_init_target(&path, force)?;
 
-    fs::write(&path, include_str!("../../../config.example.toml"))
-        .with_context(|| format!("failed to write config: {}", path.display()))?;
+    let backup = write_config_with_backup(&path, content, Utc::now())?;
+    print_backup(backup.as_ref());
 
     println!("created config: {}", path.display());
     println!("next: edit [[repositories]] in the config file");
     Ok(())
 }
 
+pub(crate) async fn run_from_gh(
+    path: Option<PathBuf>,
+    force: bool,
+    options: RepoCandidateOptions,
+) -> Result<()> {
+    let path = resolve_init_path(path)?;
+    if path.exists() && !force {
+        return Err(anyhow!(
+            "config already exists: {} (use --force to overwrite)",
+            path.display()
+        ));
+    }
+
+    let candidates =
+        fetch_repo_candidates(&options.source, options.include_archived, options.limit).await?;
+    print_repo_candidates(&candidates);
+    if !options.yes
+        && !confirm(&format!(
+            "watch these {} repositories? [y/N] ",
+            candidates.len()
+        ))?
+    {
+        println!("aborted (use --yes to skip confirmation)");
+        return Ok(());
+    }
+
+    let topic_tags = if options.yes {
+        HashMap::new()
+    } else {
+        let names = candidates
+            .iter()
+            .map(|candidate| candidate.full_name.clone())
+            .collect::<Vec<_>>();
+        offer_topic_tags(&names).await?
+    };
+
+    prepare_init_target(&path, force)?;
+    let backup = write_config_with_backup(
+        &path,
+        &config_with_repositories(&candidates, &topic_tags),
+        Utc::now(),
+    )?;
+    print_backup(backup.as_ref());
+
+    println!(
+        "created config: {} ({} repositories)",
+        path.display(),
+        candidates.len()
+    );
+    Ok(())
+}
+
+fn resolve_init_path(path: Option<PathBuf>) -> Result<PathBuf> {
+    Ok(match (path, active_profile()?) {
+        (Some(path), _) => path,
+        (None, Some(profile)) => profile_config_path(&profile)?,
+        (None, None) => installed_config_path()?,
+    })
+}
+
+fn config_with_repositories(
+    candidates: &[RepoCandidate],
+    topic_tags: &HashMap<String, Vec<String>>,
+) -> String {
+    let base = EXAMPLE_CONFIG
+        .find("[[repositories]]")
+        .map(|idx| &EXAMPLE_CONFIG[..idx])
+        .unwrap_or(EXAMPLE_CONFIG);
+    let mut out = base.to_string();
+    for (idx, candidate) in candidates.iter().enumerate() {
+        if idx > 0 {
+            out.push('\n');
+        }
+        out.push_str(&format!(
+            "[[repositories]]\nname = \"{}\"\nenabled = true\n",
+            candidate.full_name
+        ));
+        if let Some(topics) = topic_tags.get(&candidate.full_name) {
+            out.push_str(&format!("topic_tags = {}\n", topic_tags_value(topics)));
+        }
+    }
+    out
+}
+
 pub(crate) fn run_reset_state(config_path: Option<PathBuf>) -> Result<()> {
     let state_db_path = resolve_state_db_path_for_reset(config_path.as_deref())?;
     remove_state_db_files(&state_db_path)?;
@@ -50,3 +209,47 @@ fn prepare_init_target(path: &Path, force: bool) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::config_with_repositories;
+    use crate::{config::parse_config, infra::gh_client::RepoCandidate};
+
+    #[test]
+    fn config_with_repositories_replaces_example_repositories() {
+        let topic_tags = HashMap::from([(
+            "acme/web".to_string(),
+            vec!["rust".to_string(), "internal".to_string()],
+        )]);
+        let content = config_with_repositories(
+            &[
+                RepoCandidate {
+                    full_name: "acme/api".to_string(),
+                    archived: false,
+                },
+                RepoCandidate {
+                    full_name: "acme/web".to_string(),
+                    archived: false,
+                },
+            ],
+            &topic_tags,
+        );
+
+        let cfg = parse_config(&content).unwrap();
+        let names = cfg
+            .repositories
+            .iter()
+            .map(|repo| repo.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["acme/api", "acme/web"]);
+        assert!(cfg.repositories.iter().all(|repo| repo.enabled));
+        assert_eq!(cfg.repositories[0].topic_tags, None);
+        assert_eq!(
+            cfg.repositories[1].topic_tags,
+            Some(vec!["rust".to_string(), "internal".to_string()])
+        );
+        assert!(content.contains("[notifications]"));
+    }
+}
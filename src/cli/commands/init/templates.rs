@@ -0,0 +1,196 @@
+//! Built-in configs for `gh-watch init --template <name>`.
+
+use anyhow::{anyhow, Result};
+
+use super::EXAMPLE_CONFIG;
+
+#[derive(Debug)]
+pub(crate) struct ConfigTemplate {
+    pub(crate) name: &'static str,
+    pub(crate) description: &'static str,
+    pub(crate) content: &'static str,
+}
+
+pub(crate) const DEFAULT_TEMPLATE: &str = "default";
+
+const MINIMAL_CONFIG: &str = r#"# gh-watch config: new pull requests and review activity only.
+interval_seconds = 120
+bootstrap_lookback_hours = 24
+retention_days = 30
+
+[notifications]
+enabled = true
+include_url = true
+
+[filters]
+# Pull request events only; add "issue_created" or "issue_comment_created" to follow issues too.
+event_kinds = ["pr_created", "pr_review_requested", "pr_review_submitted", "pr_review_approved", "pr_review_changes_requested", "pr_merged"]
+
+[[repositories]]
+name = "owner/repo-one"
+enabled = true
+"#;
+
+const ALL_EVENTS_CONFIG: &str = r#"# gh-watch config: every event kind, polled every minute.
+interval_seconds = 60
+bootstrap_lookback_hours = 24
+retention_days = 90
+# my_pr_approved / my_pr_checks_passed for your open PRs (one GraphQL search per poll)
+watch_my_prs = true
+
+[notifications]
+enabled = true
+include_url = true
+
+[filters]
+event_kinds = ["pr_created", "issue_created", "issue_comment_created", "pr_review_comment_created", "pr_review_requested", "pr_review_submitted", "pr_review_approved", "pr_review_changes_requested", "pr_review_dismissed", "pr_merged", "pr_milestone_added", "issue_milestone_added", "review_thread_resolved", "my_pr_approved", "my_pr_checks_passed"]
+
+[poll]
+timeout_seconds = 30
+# A fast poll repeats many identical lookups; reuse their responses for a while.
+api_cache_ttl_seconds = 30
+
+[[repositories]]
+name = "owner/repo-one"
+enabled = true
+# review_thread_resolved events (one GraphQL request per poll)
+watch_review_threads = true
+"#;
+
+const BOTS_FILTERED_CONFIG: &str = r#"# gh-watch config: ignores activity from common bots.
+interval_seconds = 300
+bootstrap_lookback_hours = 24
+retention_days = 90
+
+[notifications]
+enabled = true
+include_url = true
+
+[filters]
+# Logins match exactly; add your own automation accounts here.
+ignore_actors = ["dependabot[bot]", "renovate[bot]", "github-actions[bot]", "codecov[bot]", "pre-commit-ci[bot]", "mergify[bot]", "copilot-pull-request-reviewer[bot]"]
+
+[[repositories]]
+name = "owner/repo-one"
+enabled = true
+"#;
+
+const TEAM_CONFIG: &str = r#"# gh-watch config: only activity involving you or your team.
+interval_seconds = 300
+bootstrap_lookback_hours = 24
+retention_days = 90
+
+[notifications]
+enabled = true
+include_url = true
+
+[filters]
+only_involving_me = true
+# Replace with your organization and team slug, or just "my-org" for every member.
+auto_fetch_org_team = "my-org/my-team"
+# team_members = ["alice", "bob"]  # extra logins that count as the team
+
+[[repositories]]
+name = "my-org/repo-one"
+enabled = true
+"#;
+
+pub(crate) const TEMPLATES: [ConfigTemplate; 5] = [
+    ConfigTemplate {
+        name: DEFAULT_TEMPLATE,
+        description: "the annotated example config with every section",
+        content: EXAMPLE_CONFIG,
+    },
+    ConfigTemplate {
+        name: "minimal",
+        description: "pull request events only, polled every 2 minutes",
+        content: MINIMAL_CONFIG,
+    },
+    ConfigTemplate {
+        name: "all-events",
+        description: "every event kind, polled every minute",
+        content: ALL_EVENTS_CONFIG,
+    },
+    ConfigTemplate {
+        name: "bots-filtered",
+        description: "ignores dependabot, renovate, github-actions and other common bots",
+        content: BOTS_FILTERED_CONFIG,
+    },
+    ConfigTemplate {
+        name: "team",
+        description: "only activity involving you or a placeholder org/team",
+        content: TEAM_CONFIG,
+    },
+];
+
+pub(crate) fn find_template(name: &str) -> Result<&'static ConfigTemplate> {
+    TEMPLATES
+        .iter()
+        .find(|template| template.name == name)
+        .ok_or_else(|| {
+            let names = TEMPLATES
+                .iter()
+                .map(|template| template.name)
+                .collect::<Vec<_>>()
+                .join(", ");
+            anyhow!("unknown template: {name} (available: {names})")
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_template, TEMPLATES};
+    use crate::{
+        config::{parse_config, schema::config_schema_violations},
+        domain::events::EventKind,
+    };
+
+    #[test]
+    fn every_template_parses_as_a_valid_config() {
+        for template in &TEMPLATES {
+            let cfg = parse_config(template.content)
+                .unwrap_or_else(|err| panic!("template {}: {err:#}", template.name));
+            assert!(!cfg.repositories.is_empty(), "template {}", template.name);
+        }
+    }
+
+    #[test]
+    fn every_template_satisfies_the_config_schema() {
+        for template in &TEMPLATES {
+            let violations = config_schema_violations(template.content).unwrap();
+            assert_eq!(violations, Vec::new(), "template {}", template.name);
+        }
+    }
+
+    #[test]
+    fn templates_configure_what_their_names_promise() {
+        let minimal = parse_config(find_template("minimal").unwrap().content).unwrap();
+        assert!(!minimal.filters.event_kinds.is_empty());
+        assert!(minimal
+            .filters
+            .event_kinds
+            .iter()
+            .all(|kind| kind.as_str().starts_with("pr_")));
+
+        let all_events = parse_config(find_template("all-events").unwrap().content).unwrap();
+        assert_eq!(all_events.filters.event_kinds, EventKind::ALL.to_vec());
+        assert!(all_events.interval_seconds < minimal.interval_seconds);
+
+        let bots = parse_config(find_template("bots-filtered").unwrap().content).unwrap();
+        assert!(bots
+            .filters
+            .ignore_actors
+            .contains(&"dependabot[bot]".to_string()));
+
+        let team = parse_config(find_template("team").unwrap().content).unwrap();
+        assert!(team.filters.only_involving_me);
+        assert!(team.filters.auto_fetch_org_team.is_some());
+    }
+
+    #[test]
+    fn unknown_template_lists_the_available_ones() {
+        let err = find_template("nope").unwrap_err().to_string();
+
+        assert!(err.contains("minimal, all-events"), "{err}");
+    }
+}
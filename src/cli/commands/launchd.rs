@@ -0,0 +1,318 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::{anyhow, Context, Result};
+use directories::BaseDirs;
+
+use crate::{
+    cli::error::ClassifiedError,
+    config::{active_profile, load_config_with_path},
+};
+
+pub(crate) const AGENT_LABEL: &str = "com.gh-watch.agent";
+/// launchd starts agents with a minimal PATH; `gh` is usually installed by Homebrew.
+const AGENT_PATH: &str = "/opt/homebrew/bin:/usr/local/bin:/usr/bin:/bin:/usr/sbin:/sbin";
+
+pub(crate) struct LaunchdOptions {
+    pub(crate) config: Option<PathBuf>,
+    pub(crate) install: bool,
+    pub(crate) start: bool,
+    pub(crate) output: Option<PathBuf>,
+    pub(crate) log_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LaunchAgent {
+    pub(crate) binary: PathBuf,
+    pub(crate) config: PathBuf,
+    pub(crate) profile: Option<String>,
+    pub(crate) log_dir: PathBuf,
+}
+
+impl LaunchAgent {
+    fn program_arguments(&self) -> Vec<String> {
+        let mut args = vec![self.binary.display().to_string()];
+        if let Some(profile) = &self.profile {
+            args.extend(["--profile".to_string(), profile.clone()]);
+        }
+        args.extend([
+            "watch".to_string(),
+            "--no-tui".to_string(),
+            "--config".to_string(),
+            self.config.display().to_string(),
+        ]);
+        args
+    }
+
+    pub(crate) fn render_plist(&self) -> String {
+        let program_arguments = self
+            .program_arguments()
+            .iter()
+            .map(|arg| format!("    <string>{}</string>\n", xml_escape(arg)))
+            .collect::<String>();
+        let stdout_path = self.log_dir.join("gh-watch.out.log");
+        let stderr_path = self.log_dir.join("gh-watch.err.log");
+
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+  <key>Label</key>
+  <string>{AGENT_LABEL}</string>
+  <key>ProgramArguments</key>
+  <array>
+{program_arguments}  </array>
+  <key>EnvironmentVariables</key>
+  <dict>
+    <key>PATH</key>
+    <string>{AGENT_PATH}</string>
+  </dict>
+  <key>KeepAlive</key>
+  <true/>
+  <key>RunAtLoad</key>
+  <true/>
+  <key>StandardOutPath</key>
+  <string>{}</string>
+  <key>StandardErrorPath</key>
+  <string>{}</string>
+</dict>
+</plist>
+"#,
+            xml_escape(&stdout_path.display().to_string()),
+            xml_escape(&stderr_path.display().to_string()),
+        )
+    }
+}
+
+pub(crate) fn run(options: LaunchdOptions) -> Result<()> {
+    let loaded =
+        load_config_with_path(options.config.as_deref()).context(ClassifiedError::config())?;
+    let config = absolute_path(&loaded.resolved_path.path)?;
+    let binary = env::current_exe().context("could not determine current executable path")?;
+    let log_dir = match options.log_dir {
+        Some(dir) => absolute_path(&dir)?,
+        None => home_dir()?.join("Library/Logs/gh-watch"),
+    };
+    let agent = LaunchAgent {
+        binary,
+        config,
+        profile: active_profile()?,
+        log_dir,
+    };
+    let plist = agent.render_plist();
+
+    if options.install {
+        return install(&agent, &plist, options.start);
+    }
+
+    match options.output {
+        Some(output) => {
+            fs::write(&output, &plist)
+                .with_context(|| format!("failed to write {}", output.display()))?;
+            println!("wrote: {}", output.display());
+            let installed = launch_agents_dir()?.join(plist_file_name());
+            println!("install it with:");
+            println!("  mkdir -p {}", shell_quote(&agent.log_dir));
+            println!("  cp {} {}", shell_quote(&output), shell_quote(&installed));
+            print_launchctl_commands(&installed);
+        }
+        None => print!("{plist}"),
+    }
+    Ok(())
+}
+
+fn install(agent: &LaunchAgent, plist: &str, start: bool) -> Result<()> {
+    if !cfg!(target_os = "macos") {
+        return Err(anyhow!("launchd --install is only supported on macOS"));
+    }
+
+    let agents_dir = launch_agents_dir()?;
+    fs::create_dir_all(&agents_dir)
+        .with_context(|| format!("failed to create {}", agents_dir.display()))?;
+    fs::create_dir_all(&agent.log_dir)
+        .with_context(|| format!("failed to create {}", agent.log_dir.display()))?;
+    let installed = agents_dir.join(plist_file_name());
+    fs::write(&installed, plist)
+        .with_context(|| format!("failed to write {}", installed.display()))?;
+    println!("installed: {}", installed.display());
+
+    run_launchctl(&["load", "-w", &installed.display().to_string()])?;
+    if start {
+        run_launchctl(&["start", AGENT_LABEL])?;
+    }
+    println!("logs: {}", agent.log_dir.display());
+    println!("manage it with:");
+    print_launchctl_commands(&installed);
+    Ok(())
+}
+
+fn run_launchctl(args: &[&str]) -> Result<()> {
+    println!("$ launchctl {}", args.join(" "));
+    let status = Command::new("launchctl")
+        .args(args)
+        .status()
+        .context("failed to run launchctl")?;
+    if !status.success() {
+        return Err(anyhow!("launchctl {} failed: {status}", args.join(" ")));
+    }
+    Ok(())
+}
+
+fn print_launchctl_commands(installed: &Path) {
+    let installed = shell_quote(installed);
+    println!("  launchctl load -w {installed}");
+    println!("  launchctl start {AGENT_LABEL}");
+    println!("  launchctl stop {AGENT_LABEL}");
+    println!("  launchctl unload -w {installed}");
+}
+
+fn plist_file_name() -> String {
+    format!("{AGENT_LABEL}.plist")
+}
+
+fn launch_agents_dir() -> Result<PathBuf> {
+    Ok(home_dir()?.join("Library/LaunchAgents"))
+}
+
+fn home_dir() -> Result<PathBuf> {
+    BaseDirs::new()
+        .map(|dirs| dirs.home_dir().to_path_buf())
+        .ok_or_else(|| anyhow!("could not determine home directory"))
+}
+
+/// launchd does not resolve relative paths against the directory the plist was generated in.
+fn absolute_path(path: &Path) -> Result<PathBuf> {
+    if path.is_absolute() {
+        return Ok(path.to_path_buf());
+    }
+    Ok(env::current_dir()
+        .context("could not determine current directory")?
+        .join(path))
+}
+
+fn shell_quote(path: &Path) -> String {
+    let raw = path.display().to_string();
+    if raw
+        .chars()
+        .all(|ch| ch.is_ascii_alphanumeric() || "/._-~".contains(ch))
+    {
+        return raw;
+    }
+    format!("'{}'", raw.replace('\'', r"'\''"))
+}
+
+fn xml_escape(raw: &str) -> String {
+    raw.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::{shell_quote, LaunchAgent, AGENT_LABEL};
+
+    fn agent() -> LaunchAgent {
+        LaunchAgent {
+            binary: PathBuf::from("/opt/homebrew/bin/gh-watch"),
+            config: PathBuf::from("/Users/me/.config/gh-watch/config.toml"),
+            profile: None,
+            log_dir: PathBuf::from("/Users/me/Library/Logs/gh-watch"),
+        }
+    }
+
+    fn value_after_key<'a>(plist: &'a str, key: &str) -> &'a str {
+        let key_line = format!("<key>{key}</key>");
+        let rest = &plist[plist.find(&key_line).expect(key) + key_line.len()..];
+        rest.trim_start().lines().next().unwrap().trim()
+    }
+
+    #[test]
+    fn plist_has_expected_structure() {
+        let plist = agent().render_plist();
+
+        assert!(plist.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?>"#));
+        assert!(plist.contains(r#"<plist version="1.0">"#));
+        assert!(plist.trim_end().ends_with("</dict>\n</plist>"));
+        assert_eq!(
+            plist.matches("<dict>").count(),
+            plist.matches("</dict>").count()
+        );
+        assert_eq!(
+            value_after_key(&plist, "Label"),
+            format!("<string>{AGENT_LABEL}</string>")
+        );
+        assert_eq!(value_after_key(&plist, "KeepAlive"), "<true/>");
+        assert_eq!(value_after_key(&plist, "RunAtLoad"), "<true/>");
+        assert_eq!(
+            value_after_key(&plist, "PATH"),
+            "<string>/opt/homebrew/bin:/usr/local/bin:/usr/bin:/bin:/usr/sbin:/sbin</string>"
+        );
+        assert_eq!(
+            value_after_key(&plist, "StandardOutPath"),
+            "<string>/Users/me/Library/Logs/gh-watch/gh-watch.out.log</string>"
+        );
+        assert_eq!(
+            value_after_key(&plist, "StandardErrorPath"),
+            "<string>/Users/me/Library/Logs/gh-watch/gh-watch.err.log</string>"
+        );
+    }
+
+    #[test]
+    fn program_arguments_run_headless_watch_with_binary_and_config() {
+        let plist = agent().render_plist();
+
+        let start = plist.find("<key>ProgramArguments</key>").unwrap();
+        let array = &plist[start..plist[start..].find("</array>").unwrap() + start];
+        let args = array
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("<string>"))
+            .filter_map(|line| line.strip_suffix("</string>"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            args,
+            vec![
+                "/opt/homebrew/bin/gh-watch",
+                "watch",
+                "--no-tui",
+                "--config",
+                "/Users/me/.config/gh-watch/config.toml",
+            ]
+        );
+    }
+
+    #[test]
+    fn program_arguments_keep_profile_and_escape_paths() {
+        let plist = LaunchAgent {
+            config: PathBuf::from("/Users/me/R&D <work>/config.toml"),
+            profile: Some("work".to_string()),
+            ..agent()
+        }
+        .render_plist();
+
+        assert!(plist.contains(
+            "    <string>/opt/homebrew/bin/gh-watch</string>\n    <string>--profile</string>\n    <string>work</string>\n    <string>watch</string>"
+        ));
+        assert!(plist.contains("<string>/Users/me/R&amp;D &lt;work&gt;/config.toml</string>"));
+        assert!(!plist.contains("R&D"));
+    }
+
+    #[test]
+    fn shell_quote_only_quotes_when_needed() {
+        assert_eq!(
+            shell_quote(Path::new("/Users/me/Library/LaunchAgents/a.plist")),
+            "/Users/me/Library/LaunchAgents/a.plist"
+        );
+        assert_eq!(
+            shell_quote(Path::new("/Users/me/My Agents/it's.plist")),
+            r"'/Users/me/My Agents/it'\''s.plist'"
+        );
+    }
+}
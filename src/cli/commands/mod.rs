@@ -1,7 +1,19 @@
 pub(crate) mod check;
 pub(crate) mod completion;
 pub(crate) mod config;
+pub(crate) mod diagnose;
+pub(crate) mod events;
+pub(crate) mod feed;
+pub(crate) mod filter_profile;
 pub(crate) mod guide;
 pub(crate) mod init;
+pub(crate) mod launchd;
 pub(crate) mod once;
+pub(crate) mod profile;
+pub(crate) mod report;
+pub(crate) mod search;
+pub(crate) mod serve;
+pub(crate) mod state;
+pub(crate) mod stream;
+pub(crate) mod sync;
 pub(crate) mod watch;
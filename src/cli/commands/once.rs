@@ -1,26 +1,46 @@
+use std::{
+    collections::HashMap,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 
 use crate::{
-    app::poll_once::poll_once,
+    app::{
+        filter_profile::config_with_filter_profile,
+        poll_once::{poll_once, PollOutcome},
+    },
     cli::{
-        state::{open_state_store, resolve_state_db_path},
+        error::{ClassifiedError, ErrorClass},
+        state::{
+            open_response_cache, open_state_store, resolve_filter_profile_path,
+            resolve_state_db_path,
+        },
         SystemClock,
     },
     config::{Config, ResolvedConfigPath},
-    infra::{gh_client::GhCliClient, notifier::DesktopNotifier},
+    domain::failure::FailureRecord,
+    infra::{
+        filter_profile_file::FilterProfileFile,
+        gh_client::{AccountGhClients, GhCliClient},
+        notifier::DesktopNotifier,
+    },
     ports::{
-        CursorPort, GhClientPort, NotifierPort, PersistBatchResult, RepoBatchPort,
-        RepoPersistBatch, RetentionPort,
+        CursorPort, FailureLogPort, GhClientPort, NotifierPort, PersistBatchResult, PollStatePort,
+        RepoBatchPort, RepoPersistBatch, RetentionPolicy, RetentionPort, ViewerTeamsPort,
     },
 };
 
-struct DryRunStateStore<'a, S> {
+/// Reads through to the real store but drops every write; used by `--dry-run` and `diagnose`.
+pub(crate) struct DryRunStateStore<'a, S> {
     inner: &'a S,
 }
 
 impl<'a, S> DryRunStateStore<'a, S> {
-    fn new(inner: &'a S) -> Self {
+    pub(crate) fn new(inner: &'a S) -> Self {
         Self { inner }
     }
 }
@@ -36,15 +56,27 @@ where
     fn set_cursor(&self, _repo: &str, _at: DateTime<Utc>) -> Result<()> {
         Ok(())
     }
+
+    fn load_cursors_all(&self) -> Result<HashMap<String, DateTime<Utc>>> {
+        self.inner.load_cursors_all()
+    }
+
+    fn load_cursors_for(&self, repos: &[String]) -> Result<HashMap<String, DateTime<Utc>>> {
+        self.inner.load_cursors_for(repos)
+    }
 }
 
 impl<S> RetentionPort for DryRunStateStore<'_, S>
 where
-    S: Sync,
+    S: RetentionPort,
 {
-    fn cleanup_old(&self, _retention_days: u32, _now: DateTime<Utc>) -> Result<()> {
+    fn cleanup_old(&self, _policy: &RetentionPolicy, _now: DateTime<Utc>) -> Result<()> {
         Ok(())
     }
+
+    fn count_delivered_read_events(&self) -> Result<usize> {
+        self.inner.count_delivered_read_events()
+    }
 }
 
 impl<S> RepoBatchPort for DryRunStateStore<'_, S>
@@ -56,20 +88,72 @@ where
     }
 }
 
+impl<S> FailureLogPort for DryRunStateStore<'_, S>
+where
+    S: FailureLogPort,
+{
+    fn record_failure(&self, _failure: &FailureRecord) -> Result<()> {
+        Ok(())
+    }
+
+    fn get_failure_count_for_repo(&self, repo: &str, since: DateTime<Utc>) -> Result<usize> {
+        self.inner.get_failure_count_for_repo(repo, since)
+    }
+}
+
+impl<S> ViewerTeamsPort for DryRunStateStore<'_, S>
+where
+    S: ViewerTeamsPort,
+{
+    fn load_viewer_teams(&self) -> Result<Option<(Vec<String>, DateTime<Utc>)>> {
+        self.inner.load_viewer_teams()
+    }
+
+    fn save_viewer_teams(&self, _teams: &[String], _fetched_at: DateTime<Utc>) -> Result<()> {
+        Ok(())
+    }
+
+    fn clear_viewer_teams(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+pub(crate) struct OnceOptions {
+    pub(crate) dry_run: bool,
+    pub(crate) json: bool,
+    pub(crate) output_file: Option<PathBuf>,
+    pub(crate) append: bool,
+    /// Skip the on-disk response cache even when `poll.cache_ttl_seconds` is set.
+    pub(crate) no_cache: bool,
+}
+
 pub(crate) async fn run(
     cfg: Config,
     resolved_config: ResolvedConfigPath,
-    dry_run: bool,
-    json: bool,
+    options: OnceOptions,
 ) -> Result<()> {
+    let OnceOptions {
+        dry_run,
+        json,
+        output_file,
+        append,
+        no_cache,
+    } = options;
+    let cfg = config_with_filter_profile(
+        &cfg,
+        &FilterProfileFile::new(resolve_filter_profile_path(&cfg)?),
+    );
     for warning in crate::config::stability_warnings(&cfg) {
         eprintln!("{warning}");
     }
 
-    let gh = GhCliClient::default();
-    gh.check_auth()
-        .await
-        .context("GitHub authentication is invalid. Run `gh auth login -h github.com`.")?;
+    let mut gh = AccountGhClients::from_config(&cfg, GhCliClient::default())?;
+    if !no_cache {
+        if let Some(cache) = open_response_cache(&cfg)? {
+            gh = gh.with_response_cache(cache);
+        }
+    }
+    gh.check_auth().await.context(ClassifiedError::auth())?;
 
     let state_path = resolve_state_db_path(&cfg)?;
     let state = open_state_store(&state_path)?;
@@ -80,33 +164,112 @@ pub(crate) async fn run(
     }
     notifier
         .check_health()
-        .context("Notification backend check failed")?;
+        .context(ClassifiedError::notifier())?;
 
+    let report = PollReport {
+        resolved_config: &resolved_config,
+        json,
+        dry_run,
+    };
+    let mut stdout = io::stdout();
     let outcome = if dry_run {
         let dry_run_state = DryRunStateStore::new(&state);
-        poll_once(&cfg, &gh, &dry_run_state, &notifier, &SystemClock).await?
+        run_initial_poll(&cfg, &gh, &dry_run_state, &notifier, &report, &mut stdout).await?
     } else {
-        poll_once(&cfg, &gh, &state, &notifier, &SystemClock).await?
+        run_initial_poll(&cfg, &gh, &state, &notifier, &report, &mut stdout).await?
     };
 
-    if json {
-        println!("{}", serde_json::to_string(&outcome)?);
-    } else {
-        println!(
-            "config: {} (source: {})",
-            resolved_config.path.display(),
-            resolved_config.source
-        );
-        println!("notified: {}", outcome.notified_count);
-        println!("bootstrap_repos: {}", outcome.bootstrap_repos);
-        println!("repo_fetch_failures: {}", outcome.fetch_failures.len());
-        for failure in &outcome.fetch_failures {
-            println!("- {}: {}", failure.repo, failure.message);
-        }
-        if dry_run {
-            println!("mode: dry-run (state unchanged)");
-        }
+    if let Some(path) = output_file {
+        write_output_file(&path, &outcome, append)
+            .with_context(|| format!("failed to write poll result to {}", path.display()))?;
+    }
+
+    if !outcome.fetch_failures.is_empty() {
+        return Err(ClassifiedError::new(
+            ErrorClass::PartialFailure,
+            format!(
+                "{} of {} repository fetches failed",
+                outcome.fetch_failures.len(),
+                outcome.fetch_failures.len() + outcome.fetched_repos.len()
+            ),
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Replaces `path` through a sibling `.tmp` file and a rename so readers never see a
+/// partial document; with `append`, adds one NDJSON line instead.
+fn write_output_file(path: &Path, outcome: &PollOutcome, append: bool) -> Result<()> {
+    let json = serde_json::to_string(outcome)?;
+    if append {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        file.write_all(format!("{json}\n").as_bytes())?;
+        return Ok(());
     }
 
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_path);
+    fs::write(&tmp_path, format!("{json}\n"))?;
+    fs::rename(&tmp_path, path).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp_path);
+    })?;
+    Ok(())
+}
+
+pub(crate) struct PollReport<'a> {
+    pub(crate) resolved_config: &'a ResolvedConfigPath,
+    pub(crate) json: bool,
+    pub(crate) dry_run: bool,
+}
+
+/// Runs one poll and writes its report; shared by `once` and `watch --poll-once-then-watch`.
+pub(crate) async fn run_initial_poll<C, S, N>(
+    cfg: &Config,
+    gh: &C,
+    state: &S,
+    notifier: &N,
+    report: &PollReport<'_>,
+    out: &mut dyn Write,
+) -> Result<PollOutcome>
+where
+    C: GhClientPort,
+    S: PollStatePort,
+    N: NotifierPort,
+{
+    let mut outcome = poll_once(cfg, gh, state, notifier, &SystemClock).await?;
+    outcome.config_path = report.resolved_config.path.display().to_string();
+    write_poll_report(out, &outcome, report)?;
+    Ok(outcome)
+}
+
+fn write_poll_report(
+    out: &mut dyn Write,
+    outcome: &PollOutcome,
+    report: &PollReport<'_>,
+) -> Result<()> {
+    if report.json {
+        writeln!(out, "{}", serde_json::to_string(outcome)?)?;
+        return Ok(());
+    }
+
+    writeln!(
+        out,
+        "config: {} (source: {})",
+        report.resolved_config.path.display(),
+        report.resolved_config.source
+    )?;
+    writeln!(out, "notified: {}", outcome.notified_count)?;
+    writeln!(out, "bootstrap_repos: {}", outcome.bootstrap_repos)?;
+    writeln!(out, "repo_fetch_failures: {}", outcome.fetch_failures.len())?;
+    writeln!(out, "{}", outcome.api_usage_summary())?;
+    for failure in &outcome.fetch_failures {
+        writeln!(out, "- {}: {}", failure.repo, failure.message)?;
+    }
+    if report.dry_run {
+        writeln!(out, "mode: dry-run (state unchanged)")?;
+    }
     Ok(())
 }
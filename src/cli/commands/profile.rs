@@ -0,0 +1,34 @@
+use anyhow::Result;
+
+use crate::{
+    cli::args::ProfileCommands,
+    config::{active_profile, list_profiles, profiles_dir},
+};
+
+pub(crate) fn run(command: ProfileCommands) -> Result<()> {
+    match command {
+        ProfileCommands::List => run_list_cmd(),
+    }
+}
+
+fn run_list_cmd() -> Result<()> {
+    let profiles = list_profiles()?;
+    if profiles.is_empty() {
+        println!(
+            "no profiles found in {} (run `gh-watch --profile <name> init`)",
+            profiles_dir()?.display()
+        );
+        return Ok(());
+    }
+
+    let active = active_profile()?;
+    for profile in profiles {
+        let marker = if active.as_deref() == Some(profile.as_str()) {
+            "*"
+        } else {
+            " "
+        };
+        println!("{marker} {profile}");
+    }
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Local, Utc};
+use flate2::{write::GzEncoder, Compression};
+
+use crate::{
+    app::{
+        report::{
+            build_report_output, load_scoped_events_between, render_digest, DigestFormat,
+            EventScope,
+        },
+        watch_loop::open_url_in_browser,
+    },
+    cli::{
+        args::ReportFormat,
+        state::{open_state_store, resolve_state_db_path},
+    },
+    config::Config,
+    ports::TimelineQueryPort,
+};
+
+pub(crate) struct ReportOptions {
+    pub(crate) since: DateTime<Utc>,
+    pub(crate) format: ReportFormat,
+    pub(crate) out: Option<PathBuf>,
+    pub(crate) append: bool,
+    pub(crate) compress: bool,
+    pub(crate) open: bool,
+    pub(crate) split_by_repo: bool,
+    pub(crate) scope: EventScope,
+}
+
+pub(crate) async fn run(cfg: Config, options: ReportOptions) -> Result<()> {
+    if options.format == ReportFormat::Json && options.split_by_repo {
+        bail!("--split-by applies to the markdown and text formats only");
+    }
+    let state_path = resolve_state_db_path(&cfg)?;
+    let state = open_state_store(&state_path)?;
+
+    let events = load_scoped_events_between(&state, options.since, None, &options.scope)?;
+    let read_counts = state.count_read_events_by_repo(
+        options.since,
+        &options.scope.repos,
+        &options.scope.actors,
+    )?;
+
+    let format = match options.format {
+        ReportFormat::Markdown => DigestFormat::Markdown,
+        ReportFormat::Text => DigestFormat::Text,
+        ReportFormat::Json => {
+            let output = build_report_output(&events, &read_counts, options.since, &options.scope);
+            return write_report(
+                &format!("{}\n", serde_json::to_string(&output)?),
+                events.len(),
+                &options,
+            )
+            .await;
+        }
+    };
+    let digest = render_digest(
+        &events,
+        &read_counts,
+        options.since,
+        &Local,
+        format,
+        options.split_by_repo,
+        &options.scope,
+    );
+    write_report(&digest, events.len(), &options).await
+}
+
+async fn write_report(report: &str, event_count: usize, options: &ReportOptions) -> Result<()> {
+    let Some(out) = options.out.clone() else {
+        print!("{report}");
+        return Ok(());
+    };
+    let out = if options.compress { gz_path(out) } else { out };
+    write_report_file(&out, report, options.append, options.compress)
+        .with_context(|| format!("failed to write {}", out.display()))?;
+    let verb = if options.append { "appended" } else { "wrote" };
+    println!("{verb} {event_count} events to {}", out.display());
+
+    if options.open {
+        let path = out.canonicalize().unwrap_or(out);
+        open_url_in_browser(&path.display().to_string(), None).await?;
+    }
+    Ok(())
+}
+
+/// Appending to a gzip file adds another gzip member, which `gzip -d` reads as one stream.
+fn write_report_file(path: &Path, report: &str, append: bool, compress: bool) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(path)?;
+    if compress {
+        let mut encoder = GzEncoder::new(file, Compression::default());
+        encoder.write_all(report.as_bytes())?;
+        encoder.finish()?;
+    } else {
+        file.write_all(report.as_bytes())?;
+    }
+    Ok(())
+}
+
+/// `path` with `.gz` appended unless it already ends in it.
+fn gz_path(path: PathBuf) -> PathBuf {
+    if path.extension().is_some_and(|extension| extension == "gz") {
+        return path;
+    }
+    let mut gz = path.into_os_string();
+    gz.push(".gz");
+    PathBuf::from(gz)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::gz_path;
+
+    #[test]
+    fn gz_path_adds_the_extension_once() {
+        assert_eq!(
+            gz_path(PathBuf::from("out/digest.md")),
+            PathBuf::from("out/digest.md.gz")
+        );
+        assert_eq!(
+            gz_path(PathBuf::from("digest.md.gz")),
+            PathBuf::from("digest.md.gz")
+        );
+    }
+}
@@ -0,0 +1,38 @@
+use anyhow::{anyhow, Context, Result};
+
+use crate::{
+    cli::{args::OutputFormat, error::ClassifiedError},
+    config::{validate_repo_name, Config},
+    infra::gh_client::{AccountGhClients, GhCliClient},
+    ports::GhClientPort,
+};
+
+use super::events::print_events;
+
+pub(crate) async fn run(
+    cfg: Config,
+    query: &str,
+    repos: &[String],
+    limit: usize,
+    format: OutputFormat,
+) -> Result<()> {
+    if query.trim().is_empty() {
+        return Err(anyhow!("search query must not be empty"));
+    }
+    for repo in repos {
+        validate_repo_name(repo)?;
+    }
+
+    let gh = AccountGhClients::from_config(&cfg, GhCliClient::default())?;
+    gh.check_auth().await.context(ClassifiedError::auth())?;
+
+    let repos = repos.iter().map(String::as_str).collect::<Vec<_>>();
+    let events = gh.search_issues(query, &repos, limit).await?;
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string(&events)?),
+        OutputFormat::Text if events.is_empty() => println!("no search results: {query}"),
+        OutputFormat::Text => print_events(&events),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,184 @@
+use std::{path::Path, sync::Arc};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{SecondsFormat, Utc};
+use tokio::sync::mpsc;
+
+use crate::{
+    app::{
+        feed::{load_feed_events, render_atom_feed, DEFAULT_FEED_LIMIT},
+        filter_profile::config_with_filter_profile,
+        poll_once::{ingest_pushed_events, resolve_team_members},
+        viewer_teams::resolve_viewer_teams,
+    },
+    cli::{
+        error::ClassifiedError,
+        state::{open_state_store, resolve_filter_profile_path, resolve_state_db_path},
+    },
+    config::Config,
+    domain::redact::Redactor,
+    infra::{
+        filter_profile_file::FilterProfileFile,
+        gh_client::{normalize_webhook_payload, AccountGhClients, GhCliClient},
+        notifier::DesktopNotifier,
+        webhook_server::{feed_token, WebhookDelivery, WebhookServer},
+    },
+    ports::{GhClientPort, NotifierPort, PollStatePort},
+};
+
+/// Deliveries buffered while the previous one is still being stored.
+const DELIVERY_QUEUE_SIZE: usize = 64;
+/// Read when `--secret-file` is not given.
+const SECRET_ENV: &str = "GH_WATCH_WEBHOOK_SECRET";
+
+/// The webhook secret from `secret_file`, else from `GH_WATCH_WEBHOOK_SECRET`. It is never
+/// taken as an argument, which other local users could read with `ps`.
+pub(crate) fn load_secret(secret_file: Option<&Path>) -> Result<String> {
+    let secret = match secret_file {
+        Some(path) => std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read webhook secret from {}", path.display()))?,
+        None => std::env::var(SECRET_ENV)
+            .map_err(|_| anyhow!("set {SECRET_ENV} or pass --secret-file <path>"))?,
+    };
+    Ok(secret.trim_end_matches(['\r', '\n']).to_string())
+}
+
+pub(crate) async fn run(cfg: Config, listen: &str, secret: &str, feed: bool) -> Result<()> {
+    for warning in crate::config::stability_warnings(&cfg) {
+        eprintln!("{warning}");
+    }
+
+    let gh = AccountGhClients::from_config(&cfg, GhCliClient::default())?;
+    let auth = gh.check_auth().await.context(ClassifiedError::auth())?;
+    let filter_profile = FilterProfileFile::new(resolve_filter_profile_path(&cfg)?);
+    let mut delivery_cfg = config_with_filter_profile(&cfg, &filter_profile);
+    let mut team_members = serve_team_members(&delivery_cfg, &gh).await;
+
+    let state = Arc::new(open_state_store(&resolve_state_db_path(&cfg)?)?);
+    let notifier = DesktopNotifier::from_notification_config(&cfg.notifications);
+    for warning in notifier.startup_warnings() {
+        eprintln!("notification backend warning: {warning}");
+    }
+    notifier
+        .check_health()
+        .context(ClassifiedError::notifier())?;
+
+    let mut server = WebhookServer::bind(listen, secret).await?;
+    if feed {
+        let state = Arc::clone(&state);
+        server = server.with_feed(Arc::new(move || {
+            let events = load_feed_events(state.as_ref(), &[], DEFAULT_FEED_LIMIT)?;
+            Ok(render_atom_feed(&events, Utc::now()))
+        }));
+    }
+    let feed_path = if feed {
+        format!(", feed: /feed.atom?token={}", feed_token(secret.as_bytes()))
+    } else {
+        String::new()
+    };
+    println!(
+        "{} listening for GitHub webhooks on {} (health check: /healthz{feed_path})",
+        log_timestamp(),
+        server.local_addr()?,
+    );
+
+    let redactor = Redactor::new([secret]);
+    let (sender, mut deliveries) = mpsc::channel(DELIVERY_QUEUE_SIZE);
+    let mut server = tokio::spawn(server.run(sender));
+    loop {
+        let delivery = tokio::select! {
+            delivery = deliveries.recv() => delivery,
+            result = &mut server => {
+                return result.context("webhook server stopped")?;
+            }
+            _ = tokio::signal::ctrl_c() => break,
+        };
+        let Some(delivery) = delivery else {
+            break;
+        };
+        // Re-read per delivery, like each watch poll, so `filter-profile switch` applies.
+        let next_cfg = config_with_filter_profile(&cfg, &filter_profile);
+        if next_cfg.active_filter_profile != delivery_cfg.active_filter_profile {
+            team_members = serve_team_members(&next_cfg, &gh).await;
+        }
+        delivery_cfg = next_cfg;
+        let cfg = &delivery_cfg;
+        // Cached for a day in the state db, so a long-running serve still notices changes.
+        let viewer_teams = if cfg.active_filters().only_involving_me {
+            resolve_viewer_teams(&gh, state.as_ref(), Utc::now()).await
+        } else {
+            Vec::new()
+        };
+        match ingest_delivery(
+            cfg,
+            state.as_ref(),
+            &notifier,
+            &auth.login,
+            &viewer_teams,
+            &team_members,
+            &delivery,
+        ) {
+            Ok(line) => println!("{} {line}", log_timestamp()),
+            Err(err) => eprintln!(
+                "{} {} delivery {} failed: {}",
+                log_timestamp(),
+                delivery.event,
+                delivery.delivery_id.as_deref().unwrap_or("-"),
+                redactor.redact(&format!("{err:#}"))
+            ),
+        }
+    }
+
+    server.abort();
+    println!("{} stopped", log_timestamp());
+    Ok(())
+}
+
+async fn serve_team_members<C>(cfg: &Config, gh: &C) -> Vec<String>
+where
+    C: GhClientPort,
+{
+    if cfg.active_filters().only_involving_me {
+        resolve_team_members(cfg.active_filters(), gh).await
+    } else {
+        Vec::new()
+    }
+}
+
+fn ingest_delivery<S, N>(
+    cfg: &Config,
+    state: &S,
+    notifier: &N,
+    viewer_login: &str,
+    viewer_teams: &[String],
+    team_members: &[String],
+    delivery: &WebhookDelivery,
+) -> Result<String>
+where
+    S: PollStatePort,
+    N: NotifierPort,
+{
+    let events = normalize_webhook_payload(&delivery.event, &delivery.body)?;
+    let received = events.len();
+    let outcome = ingest_pushed_events(
+        cfg,
+        state,
+        notifier,
+        Some(viewer_login.to_string()),
+        viewer_teams.to_vec(),
+        team_members.to_vec(),
+        events,
+        Utc::now(),
+    )?;
+    Ok(format!(
+        "{} delivery {}: {received} events, {} new, {} notifications",
+        delivery.event,
+        delivery.delivery_id.as_deref().unwrap_or("-"),
+        outcome.timeline_events.len(),
+        outcome.notified_count
+    ))
+}
+
+fn log_timestamp() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+}
@@ -0,0 +1,158 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::{
+    cli::{
+        args::StateCommands,
+        error::ClassifiedError,
+        state::{open_state_store, resolve_state_db_path},
+    },
+    config::load_config_with_path,
+    ports::RetentionPort,
+};
+
+pub(crate) fn run(command: StateCommands) -> Result<()> {
+    match command {
+        StateCommands::Check { config, repair } => run_check_cmd(config.as_deref(), repair),
+        StateCommands::Vacuum { config, compress } => run_vacuum_cmd(config.as_deref(), compress),
+        StateCommands::Export { config, output } => {
+            run_export_cmd(config.as_deref(), output.as_deref())
+        }
+        StateCommands::Import { config, input } => run_import_cmd(config.as_deref(), &input),
+    }
+}
+
+fn run_check_cmd(config: Option<&Path>, repair: bool) -> Result<()> {
+    let loaded = load_config_with_path(config).context(ClassifiedError::config())?;
+    let state_path = resolve_state_db_path(&loaded.config)?;
+    let state = open_state_store(&state_path)?;
+    let configured_repos = loaded
+        .config
+        .repositories
+        .iter()
+        .map(|repo| repo.name.clone())
+        .collect::<Vec<_>>();
+
+    let report = if repair {
+        state.repair(&configured_repos)?
+    } else {
+        state.check(&configured_repos)?
+    };
+
+    println!("state db: {}", state_path.display());
+    if report.integrity_errors.is_empty() {
+        println!("integrity_check: ok");
+    } else {
+        println!(
+            "integrity_check: {} problem(s)",
+            report.integrity_errors.len()
+        );
+        for message in &report.integrity_errors {
+            println!("- {message}");
+        }
+    }
+    print_findings("unparsable_payloads", &report.unparsable_event_keys);
+    print_findings("orphaned_read_flags", &report.orphaned_flag_event_keys);
+    print_findings("stale_cursors", &report.stale_cursor_repos);
+    print_findings("event_key_collisions", &report.event_key_collisions);
+    print_findings("orphaned_duplicates", &report.orphaned_duplicate_event_keys);
+    println!(
+        "delivered_read_events: {} (max_notification_history {})",
+        state.count_delivered_read_events()?,
+        loaded.config.max_notification_history
+    );
+
+    if report.is_clean() {
+        println!("result: clean");
+        return Ok(());
+    }
+
+    if repair {
+        println!(
+            "repaired: removed {} event row(s), cleared {} read flag(s), removed {} cursor(s)",
+            report.unparsable_event_keys.len() + report.orphaned_duplicate_event_keys.len(),
+            report.orphaned_flag_event_keys.len(),
+            report.stale_cursor_repos.len()
+        );
+        if !report.integrity_errors.is_empty() {
+            return Err(anyhow!(
+                "sqlite integrity_check still reports problems (run `gh-watch init --reset-state`)"
+            ));
+        }
+        if !report.event_key_collisions.is_empty() {
+            println!(
+                "kept {} event key collision(s): both events stay stored under their old keys",
+                report.event_key_collisions.len()
+            );
+        }
+        return Ok(());
+    }
+
+    Err(anyhow!(
+        "state db has problems (run `gh-watch state check --repair`)"
+    ))
+}
+
+fn print_findings(label: &str, findings: &[String]) {
+    println!("{label}: {}", findings.len());
+    for finding in findings {
+        println!("- {finding}");
+    }
+}
+
+fn run_vacuum_cmd(config: Option<&Path>, compress: bool) -> Result<()> {
+    let loaded = load_config_with_path(config).context(ClassifiedError::config())?;
+    let state_path = resolve_state_db_path(&loaded.config)?;
+    let state = open_state_store(&state_path)?;
+
+    if compress {
+        let compressed = state.compress_plain_payloads()?;
+        println!("compressed {compressed} event payloads");
+    }
+
+    state.vacuum()?;
+    println!("vacuumed state db: {}", state_path.display());
+    Ok(())
+}
+
+fn run_export_cmd(config: Option<&Path>, output: Option<&Path>) -> Result<()> {
+    let loaded = load_config_with_path(config).context(ClassifiedError::config())?;
+    let state_path = resolve_state_db_path(&loaded.config)?;
+    let state = open_state_store(&state_path)?;
+
+    match output {
+        Some(output) => {
+            let file = File::create(output)
+                .with_context(|| format!("failed to create export file: {}", output.display()))?;
+            let exported = state.export_to_json(&mut BufWriter::new(file))?;
+            println!("exported {exported} events to {}", output.display());
+        }
+        None => {
+            let stdout = io::stdout();
+            let mut writer = BufWriter::new(stdout.lock());
+            state.export_to_json(&mut writer)?;
+            writer.flush()?;
+        }
+    }
+    Ok(())
+}
+
+fn run_import_cmd(config: Option<&Path>, input: &Path) -> Result<()> {
+    let loaded = load_config_with_path(config).context(ClassifiedError::config())?;
+    let state_path = resolve_state_db_path(&loaded.config)?;
+    let state = open_state_store(&state_path)?;
+
+    let mut file = File::open(input)
+        .with_context(|| format!("failed to open import file: {}", input.display()))?;
+    let summary = state.import_from_json(&mut file)?;
+    println!(
+        "imported: total_read={} inserted={} skipped_duplicate={} failed={}",
+        summary.total_read, summary.inserted, summary.skipped_duplicate, summary.failed
+    );
+    Ok(())
+}
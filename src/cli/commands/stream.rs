@@ -0,0 +1,51 @@
+use std::io::{self, Write};
+
+use anyhow::Result;
+use chrono::Utc;
+use futures_util::StreamExt;
+
+use crate::{
+    cli::{
+        args::OutputFormat,
+        state::{open_state_store, resolve_state_db_path},
+    },
+    config::Config,
+    ports::EventStreamPort,
+};
+
+use super::events::format_event;
+
+/// Prints events as they are stored until interrupted or stdout closes; JSON is one event
+/// per line.
+pub(crate) async fn run(cfg: Config, format: OutputFormat) -> Result<()> {
+    let state = open_state_store(&resolve_state_db_path(&cfg)?)?;
+    let events = state.watch_event_stream(Utc::now());
+    futures_util::pin_mut!(events);
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+
+    let mut stdout = io::stdout();
+    loop {
+        let event = tokio::select! {
+            _ = &mut ctrl_c => return Ok(()),
+            event = events.next() => event,
+        };
+        let event = match event {
+            Some(Ok(event)) => event,
+            Some(Err(err)) => {
+                eprintln!("warning: failed to read new events: {err:#}");
+                continue;
+            }
+            None => return Ok(()),
+        };
+        let line = match format {
+            OutputFormat::Json => serde_json::to_string(&event)?,
+            OutputFormat::Text => format_event(&event),
+        };
+        match writeln!(stdout, "{line}").and_then(|()| stdout.flush()) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::BrokenPipe => return Ok(()),
+            Err(err) => return Err(err.into()),
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+
+use crate::{
+    app::read_sync::sync_read_state,
+    cli::{
+        args::SyncCommands,
+        error::ClassifiedError,
+        state::{open_state_store, resolve_state_db_path},
+    },
+    config::{load_config_with_path, Config},
+    infra::gh_client::{AccountGhClients, GhCliClient},
+    ports::GhClientPort,
+};
+
+pub(crate) async fn run(command: SyncCommands) -> Result<()> {
+    match command {
+        SyncCommands::Now { config } => {
+            let loaded =
+                load_config_with_path(config.as_deref()).context(ClassifiedError::config())?;
+            run_now(loaded.config).await
+        }
+    }
+}
+
+async fn run_now(cfg: Config) -> Result<()> {
+    let sync = cfg
+        .sync
+        .as_ref()
+        .ok_or_else(|| anyhow!("sync is not configured (add a [sync] section)"))?;
+
+    let gh = AccountGhClients::from_config(&cfg, GhCliClient::default())?;
+    gh.check_auth().await.context(ClassifiedError::auth())?;
+    let state = open_state_store(&resolve_state_db_path(&cfg)?)?;
+
+    let outcome = sync_read_state(&cfg, sync, &gh, &state, Utc::now()).await?;
+    println!(
+        "synced read state with gist {}: {} events read elsewhere, remote {}",
+        sync.gist_id,
+        outcome.newly_read.len(),
+        if outcome.pushed {
+            "updated"
+        } else {
+            "already up to date"
+        }
+    );
+    Ok(())
+}
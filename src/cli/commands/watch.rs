@@ -1,17 +1,68 @@
-use anyhow::{Context, Result};
+use std::{
+    fs,
+    io::{self, IsTerminal},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
 
 use crate::{
-    app::watch_loop::run_watch,
+    app::{
+        clock_skew::{measure_clock_skew, ClockSkewLevel},
+        filter_profile::config_with_filter_profile,
+        poll_once::current_repo_name,
+        timeline_export::{export_timeline, TimelineExportFormat},
+        watch_loop::{run_headless_watch, run_watch, IdleAlertPolicy, WatchOptions},
+    },
     cli::{
-        state::{open_state_store, resolve_state_db_path},
+        commands::{
+            config::{offer_repository_renames, print_backup},
+            once::{run_initial_poll, PollReport},
+        },
+        error::{ClassifiedError, ErrorClass},
+        repo_picker::{
+            enable_repositories, fetch_repo_candidates, offer_topic_tags, print_repo_candidates,
+            prompt, select_repo_candidates,
+        },
+        state::{
+            open_response_cache, open_state_store, resolve_filter_profile_path,
+            resolve_state_db_path, resolve_view_state_path,
+        },
         SystemClock,
     },
-    config::{Config, ResolvedConfigPath},
-    infra::{gh_client::GhCliClient, notifier::DesktopNotifier},
-    ports::{GhClientPort, NotifierPort},
+    config::{
+        parse_config, parse_notification_schedule, Config, IntervalClamp, NotificationSchedule,
+        ResolvedConfigPath,
+    },
+    infra::{
+        config_backup::write_config_with_backup,
+        filter_profile_file::FilterProfileFile,
+        gh_client::{AccountGhClients, GhCliClient, RepoCandidateSource},
+        gh_client_cache::CachedGhClient,
+        notifier::{DesktopNotifier, ScheduledNotifier},
+        state_sqlite::SqliteStateStore,
+        view_state_file::ViewStateFile,
+    },
+    ports::{CursorPort, GhClientPort, NotifierPort},
 };
 
-pub(crate) async fn run(cfg: Config, resolved_config: ResolvedConfigPath) -> Result<()> {
+pub(crate) struct WatchStartup {
+    pub(crate) idle_alert: Option<IdleAlertPolicy>,
+    pub(crate) poll_once_then_watch: bool,
+    pub(crate) json: bool,
+    pub(crate) no_tui: bool,
+    pub(crate) interval_clamp: Option<IntervalClamp>,
+    /// Where and how to write the timeline once the watch loop has stopped.
+    pub(crate) export_on_exit: Option<(PathBuf, TimelineExportFormat)>,
+    pub(crate) verbose_poll: bool,
+}
+
+pub(crate) async fn run(
+    mut cfg: Config,
+    resolved_config: ResolvedConfigPath,
+    startup: WatchStartup,
+) -> Result<()> {
     eprintln!(
         "config: {} (source: {})",
         resolved_config.path.display(),
@@ -20,14 +71,39 @@ pub(crate) async fn run(cfg: Config, resolved_config: ResolvedConfigPath) -> Res
     for warning in crate::config::stability_warnings(&cfg) {
         eprintln!("{warning}");
     }
+    if let Some(clamp) = startup.interval_clamp {
+        eprintln!("WARNING: {clamp}");
+    }
+    let interactive = !startup.no_tui && io::stdin().is_terminal();
+    let has_repositories = cfg.repositories.iter().any(|repo| repo.enabled);
+    if !has_repositories && !interactive {
+        return Err(no_repositories_error(&resolved_config.path).into());
+    }
+
+    let gh_probe = GhCliClient::default().probe().await?;
+    eprintln!("gh: {gh_probe}");
+    if let Some(warning) = gh_probe.warning() {
+        eprintln!("WARNING: {warning}");
+    }
 
-    let gh = GhCliClient::default();
-    gh.check_auth()
-        .await
-        .context("GitHub authentication is invalid. Run `gh auth login -h github.com`.")?;
+    let mut gh = AccountGhClients::from_config(
+        &cfg,
+        GhCliClient::default().with_request_logging(startup.verbose_poll),
+    )?;
+    if let Some(cache) = open_response_cache(&cfg)? {
+        gh = gh.with_response_cache(cache);
+    }
+    let auth = gh.check_auth().await.context(ClassifiedError::auth())?;
+    check_clock_skew(&gh).await?;
+    if !has_repositories {
+        pick_first_repositories(&mut cfg, &resolved_config.path).await?;
+    }
 
     let state_path = resolve_state_db_path(&cfg)?;
     let state = open_state_store(&state_path)?;
+    follow_repository_renames(&mut cfg, &resolved_config, &state, interactive)?;
+    let filter_profile = FilterProfileFile::new(resolve_filter_profile_path(&cfg)?);
+    let view_state = ViewStateFile::new(resolve_view_state_path(&cfg)?);
 
     let notifier = DesktopNotifier::from_notification_config(&cfg.notifications);
     for warning in notifier.startup_warnings() {
@@ -35,7 +111,217 @@ pub(crate) async fn run(cfg: Config, resolved_config: ResolvedConfigPath) -> Res
     }
     notifier
         .check_health()
-        .context("Notification backend check failed")?;
+        .context(ClassifiedError::notifier())?;
+    let notifier = ScheduledNotifier::new(notifier, SystemClock, notification_schedule(&cfg)?);
+
+    let context = WatchContext {
+        cfg: &cfg,
+        resolved_config: &resolved_config,
+        state: &state,
+        notifier: &notifier,
+        filter_profile: &filter_profile,
+        view_state: &view_state,
+        viewer_login: auth.login,
+    };
+    let export_on_exit = startup.export_on_exit.clone();
+    let result = match cfg.poll.api_cache_ttl_seconds {
+        Some(ttl) => {
+            let gh = CachedGhClient::new(gh, std::time::Duration::from_secs(ttl));
+            watch_with(&context, &gh, startup).await
+        }
+        None => watch_with(&context, &gh, startup).await,
+    };
+    if let Some((path, format)) = export_on_exit {
+        // Best effort: a failed export must not turn a clean stop into an error.
+        match export_timeline(&state, &path, format) {
+            Ok(written) => eprintln!("exported {written} events to {}", path.display()),
+            Err(err) => eprintln!("WARNING: timeline export failed: {err:#}"),
+        }
+    }
+    result
+}
 
-    run_watch(&cfg, &gh, &state, &notifier, &SystemClock).await
+/// Compares the local clock with GitHub's once per session: a small skew is only reported,
+/// one large enough to break cursors stops the watch. A failed lookup does not.
+async fn check_clock_skew<C: GhClientPort>(gh: &C) -> Result<()> {
+    match measure_clock_skew(gh, &SystemClock).await {
+        Ok(Some(skew)) => match skew.level() {
+            ClockSkewLevel::Ok => {}
+            ClockSkewLevel::Warn => eprintln!("WARNING: {skew}"),
+            ClockSkewLevel::Error => {
+                bail!("{skew}; fix the system time before watching")
+            }
+        },
+        Ok(None) => {}
+        Err(err) => tracing::warn!(error = %err, "clock skew check failed"),
+    }
+    Ok(())
+}
+
+/// Repositories picked from an organization or the starred list, with the config at `path`
+/// rewritten to watch them. Picking nothing leaves the config alone and fails like a
+/// non-interactive start would.
+async fn pick_first_repositories(cfg: &mut Config, path: &Path) -> Result<()> {
+    println!("{} has no enabled repositories", path.display());
+    let owner = prompt("pick from an organization (name) or your starred repositories (empty): ")?;
+    let source = if owner.is_empty() {
+        RepoCandidateSource::Starred
+    } else {
+        RepoCandidateSource::Organization(owner)
+    };
+    let candidates = fetch_repo_candidates(&source, false, Some(FIRST_RUN_CANDIDATE_LIMIT)).await?;
+    print_repo_candidates(&candidates);
+    let names = select_repo_candidates(&candidates)?
+        .into_iter()
+        .map(|candidate| candidate.full_name)
+        .collect::<Vec<_>>();
+    if names.is_empty() {
+        return Err(no_repositories_error(path).into());
+    }
+    let topic_tags = offer_topic_tags(&names).await?;
+
+    let before = fs::read_to_string(path)
+        .with_context(|| format!("failed to read config: {}", path.display()))?;
+    let after = enable_repositories(&before, &names, &topic_tags)?;
+    let backup = write_config_with_backup(path, &after, Utc::now())?;
+    print_backup(backup.as_ref());
+    println!(
+        "updated config: {} ({} repositories)",
+        path.display(),
+        names.len()
+    );
+    cfg.repositories = parse_config(&after)?.repositories;
+    Ok(())
+}
+
+/// How many candidates the first-run prompt lists.
+const FIRST_RUN_CANDIDATE_LIMIT: usize = 100;
+
+fn no_repositories_error(path: &Path) -> ClassifiedError {
+    ClassifiedError::new(
+        ErrorClass::Config,
+        format!("no enabled repositories in {}", path.display()),
+    )
+    .with_hint("Add [[repositories]] entries, or run `gh-watch watch` in a terminal to pick some.")
+}
+
+/// Points `[[repositories]]` entries at the names GitHub renamed their repositories to. Polls
+/// follow a rename either way, so without a terminal to confirm on this only warns.
+fn follow_repository_renames(
+    cfg: &mut Config,
+    resolved_config: &ResolvedConfigPath,
+    state: &SqliteStateStore,
+    interactive: bool,
+) -> Result<()> {
+    let marks = state.load_repo_access_marks()?;
+    let renames = cfg
+        .repositories
+        .iter()
+        .map(|repo| (repo.name.clone(), current_repo_name(&marks, &repo.name)))
+        .filter(|(from, to)| from != to)
+        .collect::<Vec<_>>();
+    if renames.is_empty() {
+        return Ok(());
+    }
+
+    if !interactive || !offer_repository_renames(&resolved_config.path, &renames)? {
+        for (from, to) in &renames {
+            eprintln!(
+                "WARNING: {from} was renamed to {to} on GitHub; polling {to}, but [[repositories]] in {} still names {from}",
+                resolved_config.path.display()
+            );
+        }
+        return Ok(());
+    }
+
+    for repo in &mut cfg.repositories {
+        if let Some((_, to)) = renames.iter().find(|(from, _)| *from == repo.name) {
+            repo.name = to.clone();
+        }
+    }
+    for (from, _) in &renames {
+        state.clear_repo_access_mark(from)?;
+    }
+    Ok(())
+}
+
+/// Set to `1` to send notifications regardless of `[notification_schedule]`.
+const IGNORE_SCHEDULE_ENV: &str = "GH_WATCH_IGNORE_SCHEDULE";
+
+fn notification_schedule(cfg: &Config) -> Result<Option<NotificationSchedule>> {
+    if std::env::var(IGNORE_SCHEDULE_ENV).is_ok_and(|value| value == "1") {
+        return Ok(None);
+    }
+    cfg.notification_schedule
+        .as_ref()
+        .map(parse_notification_schedule)
+        .transpose()
+}
+
+struct WatchContext<'a> {
+    cfg: &'a Config,
+    resolved_config: &'a ResolvedConfigPath,
+    state: &'a SqliteStateStore,
+    notifier: &'a ScheduledNotifier<DesktopNotifier, SystemClock>,
+    filter_profile: &'a FilterProfileFile,
+    view_state: &'a ViewStateFile,
+    viewer_login: String,
+}
+
+async fn watch_with<C>(context: &WatchContext<'_>, gh: &C, startup: WatchStartup) -> Result<()>
+where
+    C: GhClientPort,
+{
+    let initial_poll = if startup.poll_once_then_watch {
+        let report = PollReport {
+            resolved_config: context.resolved_config,
+            json: startup.json,
+            dry_run: false,
+        };
+        Some(
+            run_initial_poll(
+                &config_with_filter_profile(context.cfg, context.filter_profile),
+                gh,
+                context.state,
+                context.notifier,
+                &report,
+                &mut io::stderr(),
+            )
+            .await?,
+        )
+    } else {
+        None
+    };
+
+    let options = WatchOptions {
+        idle_alert: startup.idle_alert,
+        initial_poll,
+        startup_notice: startup
+            .interval_clamp
+            .map(|clamp| format!("warning: {clamp}")),
+        viewer_login: Some(context.viewer_login.clone()),
+    };
+    if startup.no_tui {
+        return run_headless_watch(
+            context.cfg,
+            gh,
+            context.state,
+            context.notifier,
+            &SystemClock,
+            context.filter_profile,
+            options,
+        )
+        .await;
+    }
+    run_watch(
+        context.cfg,
+        gh,
+        context.state,
+        context.notifier,
+        &SystemClock,
+        context.filter_profile,
+        context.view_state,
+        options,
+    )
+    .await
 }
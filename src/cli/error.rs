@@ -0,0 +1,193 @@
+use std::{fmt, path::Path};
+
+use serde_json::{json, Value};
+
+use super::args::OutputFormat;
+use crate::domain::redact::redact_secrets;
+
+const AUTH_HINT: &str = "Run `gh auth login -h github.com` or set GH_TOKEN.";
+const CONFIG_HINT: &str = "Run `gh-watch config doctor` to see what is wrong with the config.";
+
+/// Failure classes with their own exit code; anything unclassified exits with 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ErrorClass {
+    /// Some repositories could not be fetched; the poll result was still written.
+    PartialFailure,
+    Auth,
+    Config,
+    StateDb,
+    Notifier,
+}
+
+impl ErrorClass {
+    pub(crate) fn exit_code(self) -> i32 {
+        match self {
+            Self::PartialFailure => 2,
+            Self::Auth => 3,
+            Self::Config => 4,
+            Self::StateDb => 5,
+            Self::Notifier => 6,
+        }
+    }
+
+    /// The `code` field of the `--error-format json` envelope.
+    pub(crate) fn code(self) -> &'static str {
+        match self {
+            Self::PartialFailure => "partial_failure",
+            Self::Auth => "auth",
+            Self::Config => "config",
+            Self::StateDb => "state_db",
+            Self::Notifier => "notifier",
+        }
+    }
+}
+
+/// Marks an error with its `ErrorClass`, either as anyhow context or as the error itself.
+/// `exit_code_for_error` downcasts to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ClassifiedError {
+    pub(crate) class: ErrorClass,
+    pub(crate) message: String,
+    pub(crate) hint: Option<String>,
+}
+
+impl ClassifiedError {
+    pub(crate) fn new(class: ErrorClass, message: impl Into<String>) -> Self {
+        Self {
+            class,
+            message: message.into(),
+            hint: None,
+        }
+    }
+
+    pub(crate) fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+
+    pub(crate) fn auth() -> Self {
+        Self::new(ErrorClass::Auth, "GitHub authentication is invalid").with_hint(AUTH_HINT)
+    }
+
+    pub(crate) fn config() -> Self {
+        Self::new(ErrorClass::Config, "failed to load config").with_hint(CONFIG_HINT)
+    }
+
+    pub(crate) fn state_db(path: &Path) -> Self {
+        Self::new(
+            ErrorClass::StateDb,
+            format!("failed to open state db: {}", path.display()),
+        )
+    }
+
+    pub(crate) fn notifier() -> Self {
+        Self::new(ErrorClass::Notifier, "Notification backend check failed")
+    }
+}
+
+impl fmt::Display for ClassifiedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ClassifiedError {}
+
+pub(crate) fn classify(err: &anyhow::Error) -> Option<&ClassifiedError> {
+    err.downcast_ref::<ClassifiedError>()
+}
+
+/// Writes `err` to stderr: the anyhow chain plus any hint as text, or the envelope as JSON.
+pub(crate) fn report_error(err: &anyhow::Error, format: OutputFormat) {
+    match format {
+        OutputFormat::Text => {
+            eprintln!("{}", redact_secrets(&format!("{err:#}")));
+            if let Some(hint) = classify(err).and_then(|classified| classified.hint.as_deref()) {
+                eprintln!("hint: {hint}");
+            }
+        }
+        OutputFormat::Json => eprintln!("{}", error_envelope(err)),
+    }
+}
+
+/// `{"code", "message", "hint"}`; unclassified errors get code `error` and a null hint.
+pub(crate) fn error_envelope(err: &anyhow::Error) -> Value {
+    let classified = classify(err);
+    json!({
+        "code": classified.map_or("error", |classified| classified.class.code()),
+        "message": redact_secrets(&format!("{err:#}")),
+        "hint": classified.and_then(|classified| classified.hint.as_deref()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::{anyhow, Context};
+
+    use super::*;
+
+    #[test]
+    fn classify_finds_the_marker_under_outer_context() {
+        let err = Err::<(), _>(anyhow!("gh exited with 4"))
+            .context(ClassifiedError::auth())
+            .context("while starting up")
+            .unwrap_err();
+
+        assert_eq!(classify(&err).map(|c| c.class), Some(ErrorClass::Auth));
+        assert_eq!(
+            format!("{err:#}"),
+            "while starting up: GitHub authentication is invalid: gh exited with 4"
+        );
+    }
+
+    #[test]
+    fn classify_finds_a_marker_used_as_the_error_itself() {
+        let err = anyhow::Error::new(ClassifiedError::new(
+            ErrorClass::PartialFailure,
+            "1 of 2 repository fetches failed",
+        ));
+
+        assert_eq!(
+            classify(&err).map(|c| c.class.exit_code()),
+            Some(ErrorClass::PartialFailure.exit_code())
+        );
+    }
+
+    #[test]
+    fn unclassified_errors_have_no_marker() {
+        let err = anyhow!("boom");
+
+        assert!(classify(&err).is_none());
+        assert_eq!(
+            error_envelope(&err),
+            json!({"code": "error", "message": "boom", "hint": null})
+        );
+    }
+
+    #[test]
+    fn envelope_message_masks_credentials() {
+        let err = anyhow!("gh failed: Authorization: token gho_abc123");
+
+        assert_eq!(
+            error_envelope(&err)["message"],
+            "gh failed: Authorization: token ***"
+        );
+    }
+
+    #[test]
+    fn notifier_failures_exit_with_6_and_report_the_notifier_code() {
+        let err = Err::<(), _>(anyhow!("osascript not found"))
+            .context(ClassifiedError::notifier())
+            .unwrap_err();
+
+        assert_eq!(crate::cli::exit_code_for_error(&err), 6);
+        assert_eq!(
+            error_envelope(&err),
+            json!({
+                "code": "notifier",
+                "message": "Notification backend check failed: osascript not found",
+                "hint": null,
+            })
+        );
+    }
+}
@@ -1,14 +1,24 @@
 mod args;
 mod commands;
+mod error;
+mod repo_picker;
 mod state;
 
-use anyhow::Result;
+use std::process::ExitCode;
+
+use anyhow::{Context, Result};
 use chrono::Utc;
 use clap::Parser;
 
-use crate::{config::load_config_with_path, ports::ClockPort};
+use crate::{
+    app::{report::EventScope, timeline_export::TimelineExportFormat, watch_loop::IdleAlertPolicy},
+    config::{apply_interval_override, load_config_with_path, validate_profile_name},
+    infra::gh_client::RepoCandidateSource,
+    ports::ClockPort,
+};
 
 use args::{Cli, Commands};
+use error::ClassifiedError;
 
 #[derive(Debug, Clone, Copy)]
 pub(crate) struct SystemClock;
@@ -19,50 +29,271 @@ impl ClockPort for SystemClock {
     }
 }
 
-pub fn exit_code_for_error(_err: &anyhow::Error) -> i32 {
-    1
+/// Exit code contract: 0 ok, 2 partial repository failures, 3 auth, 4 config, 5 state db,
+/// 6 notifier, 1 anything else.
+pub fn exit_code_for_error(err: &anyhow::Error) -> i32 {
+    error::classify(err).map_or(1, |classified| classified.class.exit_code())
 }
 
-pub async fn run() -> Result<()> {
+/// Builds the tokio runtime itself: `--profile` is handed to config resolution through
+/// `GH_WATCH_PROFILE`, which must be set while the process is still single-threaded.
+pub fn run() -> ExitCode {
     let cli = Cli::parse();
+    let error_format = cli.error_format;
+    let result = apply_profile(&cli).and_then(|()| {
+        tokio::runtime::Runtime::new()
+            .context("failed to start the tokio runtime")?
+            .block_on(run_cli(cli))
+    });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            error::report_error(&err, error_format);
+            ExitCode::from(exit_code_for_error(&err) as u8)
+        }
+    }
+}
 
+fn apply_profile(cli: &Cli) -> Result<()> {
+    if let Some(profile) = &cli.profile {
+        validate_profile_name(profile)?;
+        std::env::set_var("GH_WATCH_PROFILE", profile);
+    }
+    Ok(())
+}
+
+async fn run_cli(cli: Cli) -> Result<()> {
     match cli.command {
         Commands::Watch {
             config,
             interval_seconds,
+            alert_after_idle,
+            alert_idleness_cooldown,
+            poll_once_then_watch,
+            json,
+            no_tui,
+            export_on_exit,
+            export_format,
+            verbose_poll,
         } => {
-            let loaded = load_config_with_path(config.as_deref())?;
+            let loaded =
+                load_config_with_path(config.as_deref()).context(ClassifiedError::config())?;
             let mut cfg = loaded.config;
-            if let Some(interval) = interval_seconds {
-                cfg.interval_seconds = interval;
-            }
-            commands::watch::run(cfg, loaded.resolved_path).await
+            let interval_clamp = apply_interval_override(&mut cfg, interval_seconds)?;
+            let idle_alert = alert_after_idle
+                .map(|threshold| -> Result<IdleAlertPolicy> {
+                    Ok(IdleAlertPolicy {
+                        threshold: chrono::Duration::from_std(threshold)?,
+                        cooldown: chrono::Duration::from_std(alert_idleness_cooldown)?,
+                    })
+                })
+                .transpose()?;
+            let startup = commands::watch::WatchStartup {
+                idle_alert,
+                poll_once_then_watch,
+                json,
+                no_tui,
+                interval_clamp,
+                export_on_exit: export_on_exit.map(|path| {
+                    let format = match export_format {
+                        args::ExportFormat::Json => TimelineExportFormat::Json,
+                        args::ExportFormat::Ndjson => TimelineExportFormat::Ndjson,
+                        args::ExportFormat::Csv => TimelineExportFormat::Csv,
+                    };
+                    (path, format)
+                }),
+                verbose_poll,
+            };
+            commands::watch::run(cfg, loaded.resolved_path, startup).await
         }
         Commands::Check { config } => {
-            let loaded = load_config_with_path(config.as_deref())?;
+            let loaded =
+                load_config_with_path(config.as_deref()).context(ClassifiedError::config())?;
             commands::check::run(loaded.config, loaded.resolved_path).await
         }
         Commands::Once {
             config,
             dry_run,
             json,
+            output_file,
+            append,
+            no_cache,
         } => {
-            let loaded = load_config_with_path(config.as_deref())?;
-            commands::once::run(loaded.config, loaded.resolved_path, dry_run, json).await
+            let loaded =
+                load_config_with_path(config.as_deref()).context(ClassifiedError::config())?;
+            commands::once::run(
+                loaded.config,
+                loaded.resolved_path,
+                commands::once::OnceOptions {
+                    dry_run,
+                    json,
+                    output_file,
+                    append,
+                    no_cache,
+                },
+            )
+            .await
         }
         Commands::Init {
             path,
             force,
             reset_state,
+            from_org,
+            from_starred,
+            include_archived,
+            limit,
+            yes,
+            template,
+            template_url,
+            list_templates,
         } => {
+            if list_templates {
+                return commands::init::list_templates();
+            }
+            let source = match (from_org, from_starred) {
+                (Some(org), _) => Some(RepoCandidateSource::Organization(org)),
+                (None, true) => Some(RepoCandidateSource::Starred),
+                (None, false) => None,
+            };
             if reset_state {
                 commands::init::run_reset_state(path)
+            } else if let Some(source) = source {
+                commands::init::run_from_gh(
+                    path,
+                    force,
+                    commands::init::RepoCandidateOptions {
+                        source,
+                        include_archived,
+                        limit,
+                        yes,
+                    },
+                )
+                .await
+            } else if let Some(url) = template_url {
+                commands::init::run_from_url(path, force, &url, yes)
             } else {
-                commands::init::run(path, force)
+                let template = template
+                    .as_deref()
+                    .unwrap_or(commands::init::DEFAULT_TEMPLATE);
+                commands::init::run(path, force, template)
+            }
+        }
+        Commands::Config { command } => commands::config::run(command).await,
+        Commands::Events {
+            config,
+            search,
+            limit,
+            since,
+            page_token,
+        } => {
+            let loaded =
+                load_config_with_path(config.as_deref()).context(ClassifiedError::config())?;
+            match search {
+                Some(search) => commands::events::run_search(loaded.config, &search, limit),
+                None => {
+                    let since = Utc::now() - chrono::Duration::from_std(since)?;
+                    commands::events::run_list(loaded.config, since, page_token, limit)
+                }
             }
         }
-        Commands::Config { command } => commands::config::run(command),
+        Commands::Search {
+            query,
+            config,
+            repos,
+            limit,
+            format,
+        } => {
+            let loaded =
+                load_config_with_path(config.as_deref()).context(ClassifiedError::config())?;
+            commands::search::run(loaded.config, &query, &repos, limit, format).await
+        }
+        Commands::Stream { config, format } => {
+            let loaded =
+                load_config_with_path(config.as_deref()).context(ClassifiedError::config())?;
+            commands::stream::run(loaded.config, format).await
+        }
+        Commands::Report {
+            config,
+            since,
+            format,
+            out,
+            append,
+            compress,
+            open,
+            split_by,
+            repos,
+            actors,
+        } => {
+            let loaded =
+                load_config_with_path(config.as_deref()).context(ClassifiedError::config())?;
+            let options = commands::report::ReportOptions {
+                since: Utc::now() - chrono::Duration::from_std(since)?,
+                format,
+                out,
+                append,
+                compress,
+                open,
+                split_by_repo: split_by == Some(args::ReportSplit::Repo),
+                scope: EventScope { repos, actors },
+            };
+            commands::report::run(loaded.config, options).await
+        }
+        Commands::Feed {
+            config,
+            out,
+            repos,
+            limit,
+        } => {
+            let loaded =
+                load_config_with_path(config.as_deref()).context(ClassifiedError::config())?;
+            commands::feed::run(
+                loaded.config,
+                commands::feed::FeedOptions { out, repos, limit },
+            )
+        }
+        Commands::Serve {
+            config,
+            listen,
+            secret_file,
+            feed,
+        } => {
+            let secret = commands::serve::load_secret(secret_file.as_deref())
+                .context(ClassifiedError::config())?;
+            let loaded =
+                load_config_with_path(config.as_deref()).context(ClassifiedError::config())?;
+            commands::serve::run(loaded.config, &listen, &secret, feed).await
+        }
+        Commands::State { command } => commands::state::run(command),
+        Commands::Sync { command } => commands::sync::run(command).await,
+        Commands::Profile { command } => commands::profile::run(command),
+        Commands::FilterProfile { command } => commands::filter_profile::run(command),
+        Commands::Launchd {
+            config,
+            install,
+            start,
+            output,
+            log_dir,
+        } => commands::launchd::run(commands::launchd::LaunchdOptions {
+            config,
+            install,
+            start,
+            output,
+            log_dir,
+        }),
+        Commands::Diagnose {
+            config,
+            output,
+            no_redact,
+        } => {
+            commands::diagnose::run(commands::diagnose::DiagnoseOptions {
+                config,
+                output,
+                no_redact,
+            })
+            .await
+        }
         Commands::Guide => commands::guide::run(),
         Commands::Completion { shell } => commands::completion::run(shell),
+        Commands::CompletionCheck { shell, fix } => commands::completion::check(shell, fix),
     }
 }
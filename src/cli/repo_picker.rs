@@ -0,0 +1,223 @@
+//! Choosing repositories to watch from a GitHub organization or the starred list, shared by
+//! `init --from-org/--from-starred` and the first-run prompt of `watch`.
+
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, Write},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use toml_edit::{Array, ArrayOfTables, DocumentMut, Item, Table};
+
+use crate::{
+    infra::gh_client::{GhCliClient, RepoCandidate, RepoCandidateSource},
+    ports::GhClientPort,
+};
+
+/// Candidates from `source`; an empty list is an error since there is nothing to pick.
+pub(crate) async fn fetch_repo_candidates(
+    source: &RepoCandidateSource,
+    include_archived: bool,
+    limit: Option<usize>,
+) -> Result<Vec<RepoCandidate>> {
+    let candidates = GhCliClient::default()
+        .list_repo_candidates(source, include_archived, limit)
+        .await?;
+    if candidates.is_empty() {
+        return Err(anyhow!("no repositories found to watch"));
+    }
+    Ok(candidates)
+}
+
+/// One numbered line per candidate; the numbers are the ones `parse_selection` takes.
+pub(crate) fn print_repo_candidates(candidates: &[RepoCandidate]) {
+    let width = candidates.len().to_string().len();
+    for (idx, candidate) in candidates.iter().enumerate() {
+        let archived = if candidate.archived {
+            " (archived)"
+        } else {
+            ""
+        };
+        println!("  {:>width$}. {}{archived}", idx + 1, candidate.full_name);
+    }
+}
+
+/// Asks until the answer parses; an empty answer selects nothing.
+pub(crate) fn select_repo_candidates(candidates: &[RepoCandidate]) -> Result<Vec<RepoCandidate>> {
+    loop {
+        let answer = prompt("repositories to watch (e.g. 1,3-5 or all; empty to cancel): ")?;
+        match parse_selection(&answer, candidates.len()) {
+            Ok(indices) => {
+                return Ok(indices
+                    .into_iter()
+                    .map(|idx| candidates[idx].clone())
+                    .collect())
+            }
+            Err(err) => println!("{err}"),
+        }
+    }
+}
+
+/// GitHub topics of `names` to store as `topic_tags`, once confirmed; repositories without
+/// topics are left out. Lookups that fail only print a warning.
+pub(crate) async fn offer_topic_tags(names: &[String]) -> Result<HashMap<String, Vec<String>>> {
+    let mut topic_tags = HashMap::new();
+    if !confirm("fetch their GitHub topics as topic_tags? [y/N] ")? {
+        return Ok(topic_tags);
+    }
+
+    let gh = GhCliClient::default();
+    for name in names {
+        match gh.fetch_repository_topics(name).await {
+            Ok(topics) if topics.is_empty() => {}
+            Ok(topics) => {
+                println!("  {name}: {}", topics.join(", "));
+                topic_tags.insert(name.clone(), topics);
+            }
+            Err(err) => println!("  {name}: {err:#}"),
+        }
+    }
+    Ok(topic_tags)
+}
+
+/// `topic_tags = [...]` value for a `[[repositories]]` entry.
+pub(crate) fn topic_tags_value(topics: &[String]) -> Item {
+    toml_edit::value(topics.iter().map(String::as_str).collect::<Array>())
+}
+
+pub(crate) fn confirm(prompt_text: &str) -> Result<bool> {
+    Ok(matches!(prompt(prompt_text)?.as_str(), "y" | "Y" | "yes"))
+}
+
+/// Prints `prompt_text` and returns the trimmed line typed after it.
+pub(crate) fn prompt(prompt_text: &str) -> Result<String> {
+    print!("{prompt_text}");
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(answer.trim().to_string())
+}
+
+/// Zero-based indices for 1-based numbers and `a-b` ranges separated by commas or spaces, or
+/// every index for `all`; each index once, in the order given.
+fn parse_selection(input: &str, count: usize) -> Result<Vec<usize>> {
+    if input.eq_ignore_ascii_case("all") {
+        return Ok((0..count).collect());
+    }
+
+    let number = |raw: &str| -> Result<usize> {
+        match raw.trim().parse::<usize>() {
+            Ok(number) if (1..=count).contains(&number) => Ok(number - 1),
+            _ => bail!("not a repository number between 1 and {count}: {raw}"),
+        }
+    };
+    let mut selected = Vec::new();
+    for token in input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|token| !token.is_empty())
+    {
+        let (first, last) = match token.split_once('-') {
+            Some((first, last)) => (number(first)?, number(last)?),
+            None => (number(token)?, number(token)?),
+        };
+        if first > last {
+            bail!("range runs backwards: {token}");
+        }
+        for idx in first..=last {
+            if !selected.contains(&idx) {
+                selected.push(idx);
+            }
+        }
+    }
+    Ok(selected)
+}
+
+/// `src` with each of `names` enabled: an existing `[[repositories]]` entry gets
+/// `enabled = true`, any other name a new entry at the end. Names in `topic_tags` also get
+/// their `topic_tags` set.
+pub(crate) fn enable_repositories(
+    src: &str,
+    names: &[String],
+    topic_tags: &HashMap<String, Vec<String>>,
+) -> Result<String> {
+    let mut doc = src
+        .parse::<DocumentMut>()
+        .context("failed to parse config TOML")?;
+    let repositories = doc
+        .entry("repositories")
+        .or_insert(Item::ArrayOfTables(ArrayOfTables::new()))
+        .as_array_of_tables_mut()
+        .ok_or_else(|| anyhow!("config key `repositories` is not an array of tables"))?;
+
+    for name in names {
+        let existing = repositories
+            .iter_mut()
+            .find(|repository| repository.get("name").and_then(Item::as_str) == Some(name));
+        let repository = match existing {
+            Some(repository) => repository,
+            None => {
+                let mut repository = Table::new();
+                repository["name"] = toml_edit::value(name.as_str());
+                repositories.push(repository);
+                repositories
+                    .iter_mut()
+                    .last()
+                    .expect("a repository was just pushed")
+            }
+        };
+        repository["enabled"] = toml_edit::value(true);
+        if let Some(topics) = topic_tags.get(name) {
+            repository["topic_tags"] = topic_tags_value(topics);
+        }
+    }
+    Ok(doc.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{enable_repositories, parse_selection};
+    use crate::config::parse_config;
+
+    #[test]
+    fn selection_takes_numbers_ranges_and_all() {
+        assert_eq!(parse_selection("3, 1-2 2", 4).unwrap(), vec![2, 0, 1]);
+        assert_eq!(parse_selection("ALL", 3).unwrap(), vec![0, 1, 2]);
+        assert_eq!(parse_selection("", 3).unwrap(), Vec::<usize>::new());
+        assert!(parse_selection("0", 3).is_err());
+        assert!(parse_selection("2-4", 3).is_err());
+        assert!(parse_selection("3-1", 3).is_err());
+        assert!(parse_selection("web", 3).is_err());
+    }
+
+    #[test]
+    fn enabling_flips_disabled_entries_and_appends_new_ones() {
+        let src =
+            "interval_seconds = 300\n\n[[repositories]]\nname = \"acme/api\"\nenabled = false\n";
+
+        let topic_tags = HashMap::from([("acme/web".to_string(), vec!["rust".to_string()])]);
+
+        let out = enable_repositories(
+            src,
+            &["acme/api".to_string(), "acme/web".to_string()],
+            &topic_tags,
+        )
+        .unwrap();
+
+        let cfg = parse_config(&out).unwrap();
+        let repositories = cfg
+            .repositories
+            .iter()
+            .map(|repo| (repo.name.as_str(), repo.enabled))
+            .collect::<Vec<_>>();
+        assert_eq!(repositories, vec![("acme/api", true), ("acme/web", true)]);
+        assert_eq!(cfg.repositories[0].topic_tags, None);
+        assert_eq!(
+            cfg.repositories[1].topic_tags,
+            Some(vec!["rust".to_string()])
+        );
+        assert!(out.starts_with("interval_seconds = 300\n"));
+    }
+}
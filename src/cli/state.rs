@@ -2,15 +2,25 @@ use std::{
     ffi::OsString,
     fs,
     path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
 };
 
 use anyhow::{anyhow, Context, Result};
 
 use crate::{
+    cli::error::{ClassifiedError, ErrorClass},
     config::{default_state_db_path, parse_config, resolve_config_path_with_source, Config},
-    infra::state_sqlite::{SqliteStateStore, StateSchemaMismatchError},
+    infra::{
+        gh_client_cache::DiskGhCache,
+        state_sqlite::{SqliteStateStore, StateSchemaMismatchError},
+    },
 };
 
+const ACTIVE_FILTER_PROFILE_FILE: &str = "active_filter_profile";
+const VIEW_STATE_FILE: &str = "view_state.toml";
+const RESPONSE_CACHE_DIR: &str = "gh-cache";
+
 pub(crate) fn resolve_state_db_path(cfg: &Config) -> Result<PathBuf> {
     match &cfg.state_db_path {
         Some(raw) => Ok(PathBuf::from(raw)),
@@ -18,6 +28,25 @@ pub(crate) fn resolve_state_db_path(cfg: &Config) -> Result<PathBuf> {
     }
 }
 
+pub(crate) fn resolve_filter_profile_path(cfg: &Config) -> Result<PathBuf> {
+    Ok(resolve_state_db_path(cfg)?.with_file_name(ACTIVE_FILTER_PROFILE_FILE))
+}
+
+pub(crate) fn resolve_view_state_path(cfg: &Config) -> Result<PathBuf> {
+    Ok(resolve_state_db_path(cfg)?.with_file_name(VIEW_STATE_FILE))
+}
+
+/// The on-disk response cache next to the state db; `None` while `poll.cache_ttl_seconds`
+/// is 0.
+pub(crate) fn open_response_cache(cfg: &Config) -> Result<Option<Arc<DiskGhCache>>> {
+    if cfg.poll.cache_ttl_seconds == 0 {
+        return Ok(None);
+    }
+    let dir = resolve_state_db_path(cfg)?.with_file_name(RESPONSE_CACHE_DIR);
+    let cache = DiskGhCache::open(dir, Duration::from_secs(cfg.poll.cache_ttl_seconds))?;
+    Ok(Some(Arc::new(cache)))
+}
+
 pub(crate) fn resolve_state_db_path_for_reset(config_path: Option<&Path>) -> Result<PathBuf> {
     let resolved = resolve_config_path_with_source(config_path)?;
     if !resolved.path.exists() {
@@ -74,12 +103,13 @@ fn state_db_sidecar_path(path: &Path, suffix: &str) -> PathBuf {
 pub(crate) fn open_state_store(path: &Path) -> Result<SqliteStateStore> {
     SqliteStateStore::new(path).map_err(|err| {
         if err.downcast_ref::<StateSchemaMismatchError>().is_some() {
-            anyhow!(
-                "state db schema is incompatible: {} (run `gh-watch init --reset-state`)",
-                path.display()
+            anyhow!(ClassifiedError::new(
+                ErrorClass::StateDb,
+                format!("state db schema is incompatible: {}", path.display()),
             )
+            .with_hint("Run `gh-watch init --reset-state`."))
         } else {
-            err
+            err.context(ClassifiedError::state_db(path))
         }
     })
 }
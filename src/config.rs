@@ -1,35 +1,142 @@
 use std::{
+    collections::{HashMap, HashSet},
     env,
     fmt::{Display, Formatter},
     fs,
+    ops::RangeInclusive,
     path::{Path, PathBuf},
 };
 
 use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Datelike, FixedOffset, NaiveTime, TimeZone, Timelike, Weekday};
+use crossterm::event::KeyCode;
 use directories::BaseDirs;
 use serde::Deserialize;
 
-use crate::domain::events::EventKind;
+use crate::domain::{
+    command_line::split_shell_words,
+    events::{EventKind, ReviewState},
+};
+
+pub mod schema;
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     #[serde(default = "default_interval_seconds")]
     pub interval_seconds: u64,
+    /// Allows `interval_seconds` below `MIN_INTERVAL_SECONDS` instead of clamping it.
+    #[serde(default)]
+    pub i_know_what_im_doing: bool,
     #[serde(default = "default_bootstrap_lookback_hours")]
     pub bootstrap_lookback_hours: u64,
     #[serde(default = "default_timeline_limit")]
     pub timeline_limit: usize,
     #[serde(default = "default_retention_days")]
     pub retention_days: u32,
+    /// Delivered and read events kept in the state db; the oldest beyond this are pruned
+    /// even within `retention_days`. Unread events are never pruned by it.
+    #[serde(default = "default_max_notification_history")]
+    pub max_notification_history: usize,
+    /// Quiet period after the last terminal resize event before the TUI redraws.
+    #[serde(default = "default_debounce_resize_ms")]
+    pub debounce_resize_ms: u64,
+    #[serde(default)]
+    pub retention: RetentionConfig,
+    #[serde(default)]
+    pub reminders: RemindersConfig,
+    #[serde(default)]
+    pub sync: Option<SyncConfig>,
     pub state_db_path: Option<String>,
+    /// Extra GitHub logins that `repositories[].account` can route a repository through.
+    #[serde(default)]
+    pub accounts: Vec<AccountConfig>,
     pub repositories: Vec<RepositoryConfig>,
+    /// Issue searches whose results seed the timeline of repositories bootstrapping in a poll.
+    #[serde(default)]
+    pub startup_search_queries: Vec<StartupSearchQuery>,
+    /// Emit `my_pr_approved` and `my_pr_checks_passed` for the viewer's open pull requests
+    /// in watched repositories; costs one GraphQL search per poll for every 10 repositories.
+    #[serde(default)]
+    pub watch_my_prs: bool,
     #[serde(default)]
     pub notifications: NotificationConfig,
+    /// Days and hours notifications are sent; `watch` holds them back outside it.
+    #[serde(default)]
+    pub notification_schedule: Option<NotificationScheduleConfig>,
     #[serde(default)]
     pub filters: FiltersConfig,
+    /// Event kinds for repositories without `event_kinds` when the active filters list
+    /// none either; `None` lets every kind through.
+    #[serde(default)]
+    pub default_event_kinds: Option<Vec<EventKind>>,
+    #[serde(default)]
+    pub filter_profiles: HashMap<String, FiltersConfig>,
+    pub active_filter_profile: Option<String>,
     #[serde(default)]
     pub poll: PollConfig,
+    #[serde(default)]
+    pub network: NetworkConfig,
+    #[serde(default)]
+    pub display: DisplayConfig,
+    #[serde(default)]
+    pub keymap: KeymapConfig,
+}
+
+/// Polling floor applied to `interval_seconds` unless `i_know_what_im_doing = true`.
+pub const MIN_INTERVAL_SECONDS: u64 = 30;
+
+/// GitHub's hourly core REST quota for an authenticated user.
+pub const GH_API_HOURLY_LIMIT: u64 = 5000;
+
+/// Fewest gh calls one repository costs per poll: the first page of the four
+/// pull/issue listings plus the two comment listings.
+const MIN_API_CALLS_PER_REPO_POLL: u64 = 6;
+
+/// Name that selects the top-level `[filters]` table.
+pub const DEFAULT_FILTER_PROFILE: &str = "default";
+
+impl Config {
+    pub fn active_filters(&self) -> &FiltersConfig {
+        self.active_filter_profile
+            .as_deref()
+            .and_then(|name| self.filter_profiles.get(name))
+            .unwrap_or(&self.filters)
+    }
+
+    /// Kinds `repo` may emit: its own `event_kinds`, else the active filters' `event_kinds`,
+    /// else `default_event_kinds`. Empty means every kind.
+    pub fn event_kinds_for(&self, repo: &RepositoryConfig) -> Vec<EventKind> {
+        if let Some(kinds) = &repo.event_kinds {
+            return kinds.clone();
+        }
+        let filter_kinds = &self.active_filters().event_kinds;
+        if !filter_kinds.is_empty() {
+            return filter_kinds.clone();
+        }
+        self.default_event_kinds.clone().unwrap_or_default()
+    }
+
+    /// Whether a recorded `kind` event of the repository named `repo` is notified about: its
+    /// `notification_kinds` entry, else the `notifications.kinds` one, else yes. Events must
+    /// still pass the filters to be recorded at all.
+    pub fn notifies_kind(&self, repo: &str, kind: &EventKind) -> bool {
+        self.repositories
+            .iter()
+            .find(|repository| repository.name == repo)
+            .and_then(|repository| repository.notification_kinds.get(kind))
+            .or_else(|| self.notifications.kinds.get(kind))
+            .copied()
+            .unwrap_or(true)
+    }
+
+    /// Globs a pull request of `repo` must touch to be notified about: its own
+    /// `path_filters`, else the active filters' `global_path_filters`. Empty means any.
+    pub fn path_filters_for<'a>(&'a self, repo: &'a RepositoryConfig) -> &'a [String] {
+        repo.path_filters
+            .as_deref()
+            .unwrap_or(&self.active_filters().global_path_filters)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -50,6 +157,7 @@ pub enum ConfigPathSource {
     EnvironmentVariable,
     CurrentDirectory,
     BinaryDirectory,
+    Profile,
 }
 
 impl ConfigPathSource {
@@ -59,6 +167,7 @@ impl ConfigPathSource {
             Self::EnvironmentVariable => "GH_WATCH_CONFIG",
             Self::CurrentDirectory => "./config.toml",
             Self::BinaryDirectory => "binary-directory",
+            Self::Profile => "profile",
         }
     }
 }
@@ -77,6 +186,57 @@ pub struct RepositoryConfig {
     pub enabled: bool,
     #[serde(default)]
     pub event_kinds: Option<Vec<EventKind>>,
+    /// Local clone used as the working directory for `gh pr checkout`.
+    #[serde(default)]
+    pub local_path: Option<String>,
+    /// Emit `review_thread_resolved` events from GraphQL `reviewThreads`; costs one extra
+    /// request per poll.
+    #[serde(default)]
+    pub watch_review_threads: bool,
+    /// `[[accounts]]` name whose credentials fetch this repository; unset uses the default login.
+    #[serde(default)]
+    pub account: Option<String>,
+    /// A login stored by `gh auth login` (gh 2.40+ keeps several per host) whose token
+    /// fetches this repository; an alternative to `account` that needs no `[[accounts]]`.
+    #[serde(default)]
+    pub gh_profile: Option<String>,
+    /// Globs such as `src/**`; pull request events are only notified when the pull request
+    /// changes a matching file. Overrides `filters.global_path_filters`.
+    #[serde(default)]
+    pub path_filters: Option<Vec<String>>,
+    /// Labels for `display.group_repos_by_topic`; unset looks up the repository's GitHub
+    /// topics at startup.
+    #[serde(default)]
+    pub topic_tags: Option<Vec<String>>,
+    /// Per-kind overrides of `notifications.kinds` for this repository.
+    #[serde(default)]
+    pub notification_kinds: HashMap<EventKind, bool>,
+}
+
+/// A GitHub login other than the default `gh auth` one, selected by exactly one of
+/// `gh_config_dir` (a separate `gh auth login` profile) or `token_env`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AccountConfig {
+    pub name: String,
+    #[serde(default = "default_account_host")]
+    pub host: String,
+    /// Passed to gh as `GH_CONFIG_DIR`.
+    #[serde(default)]
+    pub gh_config_dir: Option<String>,
+    /// Environment variable holding the account's token.
+    #[serde(default)]
+    pub token_env: Option<String>,
+}
+
+/// A GitHub issue search such as `is:open assignee:@me`. `repos` narrows it to those
+/// watched repositories; empty means every watched repository.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StartupSearchQuery {
+    pub query: String,
+    #[serde(default)]
+    pub repos: Vec<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -86,6 +246,21 @@ pub struct NotificationConfig {
     pub enabled: bool,
     #[serde(default = "default_true")]
     pub include_url: bool,
+    #[serde(default)]
+    pub yank_fallback_print: bool,
+    #[serde(default)]
+    pub summary: Option<SummaryNotificationConfig>,
+    /// Event kinds to notify about (`true`) or only record in the timeline (`false`); kinds
+    /// left out are notified. `[[repositories]].notification_kinds` overrides single kinds.
+    #[serde(default)]
+    pub kinds: HashMap<EventKind, bool>,
+    /// Events created longer ago than this are recorded in the timeline but never notified,
+    /// wherever the cursor stands.
+    #[serde(default = "default_max_event_age_hours")]
+    pub max_event_age_hours: u64,
+    /// Longest excerpt of a comment or review body added to its notification, in chars.
+    #[serde(default = "default_body_max_chars")]
+    pub body_max_chars: usize,
 }
 
 impl Default for NotificationConfig {
@@ -93,10 +268,116 @@ impl Default for NotificationConfig {
         Self {
             enabled: true,
             include_url: true,
+            yank_fallback_print: false,
+            summary: None,
+            kinds: HashMap::new(),
+            max_event_age_hours: default_max_event_age_hours(),
+            body_max_chars: default_body_max_chars(),
         }
     }
 }
 
+/// `[notifications.summary]`: a weekly activity summary sent by `watch`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SummaryNotificationConfig {
+    /// Weekday and time of day, e.g. `Mon 09:00`; see `parse_summary_schedule`.
+    pub schedule: String,
+    /// `local`, `UTC` or a fixed offset such as `+09:00`; see `parse_summary_timezone`.
+    #[serde(default = "default_summary_timezone")]
+    pub timezone: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SummarySchedule {
+    pub weekday: Weekday,
+    pub time: NaiveTime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SummaryTimezone {
+    Local,
+    Fixed(FixedOffset),
+}
+
+/// Parses `notifications.summary.schedule`: an English weekday (`Mon` or `Monday`, any case)
+/// and a 24-hour `HH:MM` time separated by whitespace.
+pub fn parse_summary_schedule(raw: &str) -> Option<SummarySchedule> {
+    let mut parts = raw.split_whitespace();
+    let (Some(weekday), Some(time), None) = (parts.next(), parts.next(), parts.next()) else {
+        return None;
+    };
+    Some(SummarySchedule {
+        weekday: weekday.parse().ok()?,
+        time: NaiveTime::parse_from_str(time, "%H:%M").ok()?,
+    })
+}
+
+/// Parses `notifications.summary.timezone`: `local`, `UTC`, or an offset like `+09:00`.
+pub fn parse_summary_timezone(raw: &str) -> Option<SummaryTimezone> {
+    let raw = raw.trim();
+    if raw.eq_ignore_ascii_case("local") {
+        return Some(SummaryTimezone::Local);
+    }
+    if raw.eq_ignore_ascii_case("utc") {
+        return Some(SummaryTimezone::Fixed(FixedOffset::east_opt(0)?));
+    }
+    raw.parse().ok().map(SummaryTimezone::Fixed)
+}
+
+/// `[notification_schedule]`: when `watch` may send notifications, in local time.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NotificationScheduleConfig {
+    /// English weekdays (`Mon` or `Monday`, any case); empty means every day.
+    #[serde(default)]
+    pub enabled_days: Vec<String>,
+    /// First and last hour of the day, both inclusive, e.g. `[9, 18]` for 09:00-18:59.
+    #[serde(default = "default_enabled_hours")]
+    pub enabled_hours: [u8; 2],
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotificationSchedule {
+    pub enabled_days: Vec<Weekday>,
+    pub enabled_hours: RangeInclusive<u8>,
+}
+
+impl NotificationSchedule {
+    /// Whether notifications may be sent at `at`, judged by its own day and hour.
+    pub fn allows<Tz: TimeZone>(&self, at: &DateTime<Tz>) -> bool {
+        let day_enabled = self.enabled_days.is_empty() || self.enabled_days.contains(&at.weekday());
+        let hour = u8::try_from(at.hour()).unwrap_or(u8::MAX);
+        day_enabled && self.enabled_hours.contains(&hour)
+    }
+}
+
+/// Parses `[notification_schedule]`: weekday names as in `parse_summary_schedule` and an
+/// hour range within 0-23 whose start is not after its end.
+pub fn parse_notification_schedule(
+    raw: &NotificationScheduleConfig,
+) -> Result<NotificationSchedule> {
+    let enabled_days = raw
+        .enabled_days
+        .iter()
+        .map(|day| {
+            day.trim().parse::<Weekday>().map_err(|_| {
+                anyhow!("invalid notification_schedule.enabled_days entry '{day}' (expected a weekday such as `Mon`)")
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let [start, end] = raw.enabled_hours;
+    if start > 23 || end > 23 || start > end {
+        return Err(anyhow!(
+            "invalid notification_schedule.enabled_hours [{start}, {end}] (expected [start, end] within 0-23 with start <= end)"
+        ));
+    }
+    Ok(NotificationSchedule {
+        enabled_days,
+        enabled_hours: start..=end,
+    })
+}
+
 #[derive(Debug, Clone, Deserialize, Default)]
 #[serde(deny_unknown_fields)]
 pub struct FiltersConfig {
@@ -106,6 +387,243 @@ pub struct FiltersConfig {
     pub ignore_actors: Vec<String>,
     #[serde(default)]
     pub only_involving_me: bool,
+    /// Drop events the viewer caused, e.g. their own comments.
+    #[serde(default)]
+    pub ignore_self: bool,
+    #[serde(default)]
+    pub milestone_names: Vec<String>,
+    /// Review states to notify about; empty means every state.
+    #[serde(default)]
+    pub review_states: Vec<ReviewState>,
+    /// Logins that count as "me" for `only_involving_me`.
+    #[serde(default)]
+    pub team_members: Vec<String>,
+    /// `org` or `org/team` whose members are fetched and added to `team_members`.
+    #[serde(default)]
+    pub auto_fetch_org_team: Option<String>,
+    /// `path_filters` of repositories that set none; empty notifies regardless of paths.
+    #[serde(default)]
+    pub global_path_filters: Vec<String>,
+}
+
+/// TUI key bindings by action. An unset action keeps its `DEFAULT_KEYMAP` keys; an empty
+/// list unbinds it. `Esc` (press twice to quit) and the `0`-`9` kind filter keys are fixed.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct KeymapConfig {
+    pub quit: Option<Vec<String>>,
+    pub refresh: Option<Vec<String>>,
+    pub help: Option<Vec<String>>,
+    pub next_tab: Option<Vec<String>>,
+    pub prev_tab: Option<Vec<String>>,
+    pub open: Option<Vec<String>>,
+    pub gh_view: Option<Vec<String>>,
+    pub gh_checkout: Option<Vec<String>>,
+    pub yank: Option<Vec<String>>,
+    pub yank_event_key: Option<Vec<String>>,
+    pub scroll_up: Option<Vec<String>>,
+    pub scroll_down: Option<Vec<String>>,
+    pub page_up: Option<Vec<String>>,
+    pub page_down: Option<Vec<String>>,
+    pub top: Option<Vec<String>>,
+    pub bottom: Option<Vec<String>>,
+    pub group_by_repo: Option<Vec<String>>,
+    pub load_older: Option<Vec<String>>,
+    pub next_match: Option<Vec<String>>,
+    pub prev_match: Option<Vec<String>>,
+    pub split_narrower: Option<Vec<String>>,
+    pub split_wider: Option<Vec<String>>,
+    pub split_pane: Option<Vec<String>>,
+    pub hide_closed: Option<Vec<String>>,
+    pub mark_all_read: Option<Vec<String>>,
+    pub search: Option<Vec<String>>,
+}
+
+/// Built-in key bindings, in help overlay order.
+pub const DEFAULT_KEYMAP: &[(&str, &[&str])] = &[
+    ("quit", &["q"]),
+    ("refresh", &["r"]),
+    ("help", &["?"]),
+    ("next_tab", &["Tab"]),
+    ("prev_tab", &["BackTab"]),
+    ("open", &["Enter"]),
+    ("gh_view", &["x"]),
+    ("gh_checkout", &["c"]),
+    ("yank", &["y"]),
+    ("yank_event_key", &["Y"]),
+    ("scroll_up", &["Up", "k"]),
+    ("scroll_down", &["Down", "j"]),
+    ("page_up", &["PageUp"]),
+    ("page_down", &["PageDown"]),
+    ("top", &["Home"]),
+    ("bottom", &["End", "G"]),
+    ("group_by_repo", &["g"]),
+    ("load_older", &["L"]),
+    ("next_match", &["n"]),
+    ("prev_match", &["N"]),
+    ("split_narrower", &["["]),
+    ("split_wider", &["]"]),
+    ("split_pane", &["\\"]),
+    ("hide_closed", &["H"]),
+    ("mark_all_read", &["a"]),
+    ("search", &["/"]),
+];
+
+impl KeymapConfig {
+    /// Keys configured for `action`, or `None` when it keeps the default.
+    pub fn keys_for(&self, action: &str) -> Option<&[String]> {
+        let keys = match action {
+            "quit" => &self.quit,
+            "refresh" => &self.refresh,
+            "help" => &self.help,
+            "next_tab" => &self.next_tab,
+            "prev_tab" => &self.prev_tab,
+            "open" => &self.open,
+            "gh_view" => &self.gh_view,
+            "gh_checkout" => &self.gh_checkout,
+            "yank" => &self.yank,
+            "yank_event_key" => &self.yank_event_key,
+            "scroll_up" => &self.scroll_up,
+            "scroll_down" => &self.scroll_down,
+            "page_up" => &self.page_up,
+            "page_down" => &self.page_down,
+            "top" => &self.top,
+            "bottom" => &self.bottom,
+            "group_by_repo" => &self.group_by_repo,
+            "load_older" => &self.load_older,
+            "next_match" => &self.next_match,
+            "prev_match" => &self.prev_match,
+            "split_narrower" => &self.split_narrower,
+            "split_wider" => &self.split_wider,
+            "split_pane" => &self.split_pane,
+            "hide_closed" => &self.hide_closed,
+            "mark_all_read" => &self.mark_all_read,
+            "search" => &self.search,
+            _ => return None,
+        };
+        keys.as_deref()
+    }
+}
+
+/// Named keys accepted in `[keymap]`, spelled like `crossterm::event::KeyCode` variants.
+const NAMED_KEY_CODES: &[(&str, KeyCode)] = &[
+    ("Backspace", KeyCode::Backspace),
+    ("Enter", KeyCode::Enter),
+    ("Left", KeyCode::Left),
+    ("Right", KeyCode::Right),
+    ("Up", KeyCode::Up),
+    ("Down", KeyCode::Down),
+    ("Home", KeyCode::Home),
+    ("End", KeyCode::End),
+    ("PageUp", KeyCode::PageUp),
+    ("PageDown", KeyCode::PageDown),
+    ("Tab", KeyCode::Tab),
+    ("BackTab", KeyCode::BackTab),
+    ("Delete", KeyCode::Delete),
+    ("Insert", KeyCode::Insert),
+    ("Esc", KeyCode::Esc),
+    ("Space", KeyCode::Char(' ')),
+];
+
+/// Parses a `[keymap]` entry: a single character, `F1`-`F12`, or a `NAMED_KEY_CODES` name.
+pub fn parse_key_code(raw: &str) -> Option<KeyCode> {
+    if let Some((_, code)) = NAMED_KEY_CODES.iter().find(|(name, _)| *name == raw) {
+        return Some(*code);
+    }
+    if let Some(number) = raw.strip_prefix('F').and_then(|n| n.parse::<u8>().ok()) {
+        return (1..=12).contains(&number).then_some(KeyCode::F(number));
+    }
+    let mut chars = raw.chars();
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) if !ch.is_whitespace() && !ch.is_control() => Some(KeyCode::Char(ch)),
+        _ => None,
+    }
+}
+
+/// Inverse of `parse_key_code`.
+pub fn key_code_label(code: KeyCode) -> String {
+    if let Some((name, _)) = NAMED_KEY_CODES.iter().find(|(_, named)| *named == code) {
+        return (*name).to_string();
+    }
+    match code {
+        KeyCode::F(number) => format!("F{number}"),
+        KeyCode::Char(ch) => ch.to_string(),
+        other => format!("{other:?}"),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    InvalidKeyCode { action: String, key: String },
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidKeyCode { action, key } => write!(
+                f,
+                "invalid key code '{key}' for keymap.{action} (use a single character, F1-F12, or one of: {})",
+                NAMED_KEY_CODES
+                    .iter()
+                    .map(|(name, _)| *name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Splits `auto_fetch_org_team` into the organization and optional team slug.
+pub fn split_org_team(raw: &str) -> Result<(&str, Option<&str>)> {
+    let mut parts = raw.split('/');
+    let org = parts.next().unwrap_or("");
+    let team = parts.next();
+    let valid = !org.is_empty() && team != Some("") && parts.next().is_none();
+    if !valid {
+        return Err(anyhow!(
+            "invalid auto_fetch_org_team: {raw} (expected `org` or `org/team`)"
+        ));
+    }
+    Ok((org, team))
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct RetentionConfig {
+    #[serde(default)]
+    pub keep_unread: bool,
+    #[serde(default)]
+    pub days_by_kind: HashMap<EventKind, u32>,
+}
+
+/// `[reminders]`: nudges sent by `watch` about work waiting on the viewer.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct RemindersConfig {
+    /// Days after a review request to the viewer, with no review by them since, before it is
+    /// reminded about once a day while the pull request stays open. Unset disables it.
+    #[serde(default)]
+    pub review_request_after_days: Option<u32>,
+}
+
+/// `[sync]`: shares read marks with gh-watch on other machines.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SyncConfig {
+    pub backend: SyncBackend,
+    /// Existing gist the default gh login can edit; gh-watch keeps one file in it.
+    pub gist_id: String,
+    /// Minutes between round trips while watching; unset syncs after every poll.
+    #[serde(default)]
+    pub interval_minutes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncBackend {
+    Gist,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -113,16 +631,207 @@ pub struct FiltersConfig {
 pub struct PollConfig {
     #[serde(default = "default_poll_timeout_seconds")]
     pub timeout_seconds: u64,
+    #[serde(default)]
+    pub api_cache_ttl_seconds: Option<u64>,
+    /// Seconds the responses of bootstrap fetches are kept on disk next to the state db, so
+    /// a restart soon after does not download them again; `0` disables the cache.
+    #[serde(default)]
+    pub cache_ttl_seconds: u64,
+    /// Randomizes each watch cycle's delay by up to this many seconds either way.
+    #[serde(default)]
+    pub jitter_seconds: u64,
+    /// Fetch enabled repositories through batched GraphQL queries instead of one REST
+    /// sweep per repository.
+    #[serde(default)]
+    pub use_graphql_batch: bool,
 }
 
 impl Default for PollConfig {
     fn default() -> Self {
         Self {
             timeout_seconds: default_poll_timeout_seconds(),
+            api_cache_ttl_seconds: None,
+            cache_ttl_seconds: 0,
+            jitter_seconds: 0,
+            use_graphql_batch: false,
+        }
+    }
+}
+
+/// Outbound proxy for gh; gh-watch itself makes no direct HTTP requests.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct NetworkConfig {
+    /// `http`, `https`, `socks5` or `socks5h` URL; credentials may be given as userinfo.
+    #[serde(default)]
+    pub proxy: Option<String>,
+    /// Hosts, domains (`.example.com`) or CIDRs that bypass `proxy`.
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct DisplayConfig {
+    /// Command that opens event URLs on every OS, e.g. `open -a Firefox %s`; `%s` is the
+    /// URL, which is appended when the command has no placeholder.
+    #[serde(default)]
+    pub browser_command: Option<String>,
+    #[serde(default)]
+    pub layout: DisplayLayout,
+    #[serde(default)]
+    pub kind_labels: KindLabelMode,
+    /// Looks up whether the issues and pull requests in the timeline are still open, for
+    /// the `[merged]`/`[closed]` badges and the `hide_closed` view filter.
+    #[serde(default)]
+    pub resolve_subject_state: bool,
+    /// Lists the Repositories tab under each repository's topics (`topic_tags` or GitHub's).
+    #[serde(default)]
+    pub group_repos_by_topic: bool,
+}
+
+/// TUI arrangement: `auto` splits the timeline and a detail pane side by side on
+/// terminals at least `SPLIT_LAYOUT_MIN_WIDTH` columns wide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DisplayLayout {
+    #[default]
+    Auto,
+    Stacked,
+    Split,
+}
+
+pub const SPLIT_LAYOUT_MIN_WIDTH: u16 = 160;
+
+/// Type column text: `short` abbreviations (`I-CMT`), `long` names (`Issue comment`) or
+/// Nerd Font `icon`s, which fall back to `short` when the terminal lacks the glyphs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum KindLabelMode {
+    #[default]
+    Short,
+    Long,
+    Icon,
+}
+
+const PROXY_SCHEMES: &[&str] = &["http", "https", "socks5", "socks5h"];
+
+/// Checks that `raw` is `scheme://[user[:password]@]host[:port]` with a supported scheme.
+pub fn validate_proxy_url(raw: &str) -> Result<()> {
+    let invalid = |reason: &str| {
+        anyhow!(
+            "network.proxy {} is invalid: {reason}",
+            redact_proxy_url(raw)
+        )
+    };
+    let (scheme, rest) = raw
+        .split_once("://")
+        .ok_or_else(|| invalid("expected scheme://host:port"))?;
+    if !PROXY_SCHEMES.contains(&scheme) {
+        return Err(invalid(&format!(
+            "unsupported scheme '{scheme}' (use one of: {})",
+            PROXY_SCHEMES.join(", ")
+        )));
+    }
+    let authority = rest.trim_end_matches('/');
+    let host_port = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let (host, port) = match host_port.strip_prefix('[') {
+        Some(bracketed) => {
+            let (host, after) = bracketed
+                .split_once(']')
+                .ok_or_else(|| invalid("unterminated IPv6 address"))?;
+            (host, after.strip_prefix(':'))
         }
+        None => match host_port.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (host_port, None),
+        },
+    };
+    if host.is_empty() || host_port.contains('/') {
+        return Err(invalid("expected scheme://host:port"));
+    }
+    if let Some(port) = port {
+        port.parse::<u16>()
+            .map_err(|_| invalid(&format!("invalid port '{port}'")))?;
+    }
+    Ok(())
+}
+
+/// Replaces proxy credentials with `***` so the URL can be printed.
+pub fn redact_proxy_url(raw: &str) -> String {
+    let Some((scheme, rest)) = raw.split_once("://") else {
+        return raw.to_string();
+    };
+    match rest.rsplit_once('@') {
+        Some((_, host)) => format!("{scheme}://***@{host}"),
+        None => raw.to_string(),
     }
 }
 
+pub const DEFAULT_FIELD_VALUES: &[(&str, &str)] = &[
+    ("interval_seconds", "300"),
+    ("i_know_what_im_doing", "false"),
+    ("bootstrap_lookback_hours", "24"),
+    ("timeline_limit", "500"),
+    ("retention_days", "90"),
+    ("max_notification_history", "10000"),
+    ("debounce_resize_ms", "50"),
+    ("watch_my_prs", "false"),
+    ("retention.keep_unread", "false"),
+    ("retention.days_by_kind", "{}"),
+    ("notifications.enabled", "true"),
+    ("notifications.include_url", "true"),
+    ("notifications.yank_fallback_print", "false"),
+    ("notifications.kinds", "{}"),
+    ("notifications.max_event_age_hours", "48"),
+    ("notifications.body_max_chars", "200"),
+    ("filters.event_kinds", "[]"),
+    ("filters.ignore_actors", "[]"),
+    ("filters.only_involving_me", "false"),
+    ("filters.ignore_self", "false"),
+    ("filters.milestone_names", "[]"),
+    ("filters.review_states", "[]"),
+    ("filters.team_members", "[]"),
+    ("filters.global_path_filters", "[]"),
+    ("poll.timeout_seconds", "30"),
+    ("poll.cache_ttl_seconds", "0"),
+    ("poll.jitter_seconds", "0"),
+    ("poll.use_graphql_batch", "false"),
+    ("display.layout", r#""auto""#),
+    ("display.kind_labels", r#""short""#),
+    ("display.resolve_subject_state", "false"),
+    ("display.group_repos_by_topic", "false"),
+    ("network.no_proxy", "[]"),
+    ("keymap.quit", r#"["q"]"#),
+    ("keymap.refresh", r#"["r"]"#),
+    ("keymap.help", r#"["?"]"#),
+    ("keymap.next_tab", r#"["Tab"]"#),
+    ("keymap.prev_tab", r#"["BackTab"]"#),
+    ("keymap.open", r#"["Enter"]"#),
+    ("keymap.gh_view", r#"["x"]"#),
+    ("keymap.gh_checkout", r#"["c"]"#),
+    ("keymap.yank", r#"["y"]"#),
+    ("keymap.yank_event_key", r#"["Y"]"#),
+    ("keymap.scroll_up", r#"["Up", "k"]"#),
+    ("keymap.scroll_down", r#"["Down", "j"]"#),
+    ("keymap.page_up", r#"["PageUp"]"#),
+    ("keymap.page_down", r#"["PageDown"]"#),
+    ("keymap.top", r#"["Home"]"#),
+    ("keymap.bottom", r#"["End", "G"]"#),
+    ("keymap.group_by_repo", r#"["g"]"#),
+    ("keymap.load_older", r#"["L"]"#),
+    ("keymap.next_match", r#"["n"]"#),
+    ("keymap.prev_match", r#"["N"]"#),
+    ("keymap.split_narrower", r#"["["]"#),
+    ("keymap.split_wider", r#"["]"]"#),
+    ("keymap.split_pane", r#"["\\"]"#),
+    ("keymap.hide_closed", r#"["H"]"#),
+    ("keymap.mark_all_read", r#"["a"]"#),
+    ("keymap.search", r#"["/"]"#),
+];
+
 fn default_interval_seconds() -> u64 {
     300
 }
@@ -131,6 +840,14 @@ fn default_bootstrap_lookback_hours() -> u64 {
     24
 }
 
+fn default_max_event_age_hours() -> u64 {
+    48
+}
+
+fn default_body_max_chars() -> usize {
+    200
+}
+
 fn default_timeline_limit() -> usize {
     500
 }
@@ -139,6 +856,26 @@ fn default_retention_days() -> u32 {
     90
 }
 
+fn default_max_notification_history() -> usize {
+    10_000
+}
+
+fn default_debounce_resize_ms() -> u64 {
+    50
+}
+
+fn default_account_host() -> String {
+    "github.com".to_string()
+}
+
+fn default_enabled_hours() -> [u8; 2] {
+    [0, 23]
+}
+
+fn default_summary_timezone() -> String {
+    "local".to_string()
+}
+
 fn default_true() -> bool {
     true
 }
@@ -147,12 +884,344 @@ fn default_poll_timeout_seconds() -> u64 {
     30
 }
 
+/// Dotted key paths accepted by `Config`; `*` matches any map key.
+const KNOWN_CONFIG_KEYS: &[&str] = &[
+    "interval_seconds",
+    "i_know_what_im_doing",
+    "bootstrap_lookback_hours",
+    "timeline_limit",
+    "retention_days",
+    "max_notification_history",
+    "debounce_resize_ms",
+    "state_db_path",
+    "watch_my_prs",
+    "accounts.name",
+    "accounts.host",
+    "accounts.gh_config_dir",
+    "accounts.token_env",
+    "retention.keep_unread",
+    "retention.days_by_kind.*",
+    "reminders.review_request_after_days",
+    "sync.backend",
+    "sync.gist_id",
+    "sync.interval_minutes",
+    "repositories.name",
+    "repositories.enabled",
+    "repositories.event_kinds",
+    "repositories.local_path",
+    "repositories.watch_review_threads",
+    "repositories.account",
+    "repositories.gh_profile",
+    "repositories.path_filters",
+    "repositories.topic_tags",
+    "repositories.notification_kinds.*",
+    "startup_search_queries.query",
+    "startup_search_queries.repos",
+    "notifications.enabled",
+    "notifications.include_url",
+    "notifications.yank_fallback_print",
+    "notifications.kinds.*",
+    "notifications.max_event_age_hours",
+    "notifications.body_max_chars",
+    "notifications.summary.schedule",
+    "notifications.summary.timezone",
+    "notification_schedule.enabled_days",
+    "notification_schedule.enabled_hours",
+    "filters.event_kinds",
+    "default_event_kinds",
+    "filters.ignore_actors",
+    "filters.only_involving_me",
+    "filters.ignore_self",
+    "filters.milestone_names",
+    "filters.review_states",
+    "filters.team_members",
+    "filters.auto_fetch_org_team",
+    "filters.global_path_filters",
+    "poll.timeout_seconds",
+    "poll.api_cache_ttl_seconds",
+    "poll.cache_ttl_seconds",
+    "poll.jitter_seconds",
+    "poll.use_graphql_batch",
+    "network.proxy",
+    "network.no_proxy",
+    "display.browser_command",
+    "display.layout",
+    "display.kind_labels",
+    "display.resolve_subject_state",
+    "display.group_repos_by_topic",
+    "filter_profiles.*.event_kinds",
+    "filter_profiles.*.ignore_actors",
+    "filter_profiles.*.only_involving_me",
+    "filter_profiles.*.ignore_self",
+    "filter_profiles.*.milestone_names",
+    "filter_profiles.*.review_states",
+    "filter_profiles.*.team_members",
+    "filter_profiles.*.auto_fetch_org_team",
+    "filter_profiles.*.global_path_filters",
+    "active_filter_profile",
+    "keymap.quit",
+    "keymap.refresh",
+    "keymap.help",
+    "keymap.next_tab",
+    "keymap.prev_tab",
+    "keymap.open",
+    "keymap.gh_view",
+    "keymap.gh_checkout",
+    "keymap.yank",
+    "keymap.yank_event_key",
+    "keymap.scroll_up",
+    "keymap.scroll_down",
+    "keymap.page_up",
+    "keymap.page_down",
+    "keymap.top",
+    "keymap.bottom",
+    "keymap.group_by_repo",
+    "keymap.load_older",
+    "keymap.next_match",
+    "keymap.prev_match",
+    "keymap.split_narrower",
+    "keymap.split_wider",
+    "keymap.split_pane",
+    "keymap.hide_closed",
+    "keymap.mark_all_read",
+    "keymap.search",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownConfigKey {
+    pub path: String,
+    pub line: usize,
+    pub column: usize,
+    pub suggestion: Option<String>,
+}
+
+impl Display for UnknownConfigKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "unknown key `{}` at line {}, column {}",
+            self.path, self.line, self.column
+        )?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, " (did you mean `{suggestion}`?)")?;
+        }
+        Ok(())
+    }
+}
+
+pub fn find_unknown_config_keys(src: &str) -> Result<Vec<UnknownConfigKey>> {
+    let doc = toml_edit::Document::parse(src).context("failed to parse config TOML")?;
+    let mut unknown = Vec::new();
+    collect_unknown_keys(src, doc.as_table(), &mut Vec::new(), &mut unknown);
+    Ok(unknown)
+}
+
+fn collect_unknown_keys<'a>(
+    src: &str,
+    table: &'a dyn toml_edit::TableLike,
+    path: &mut Vec<&'a str>,
+    unknown: &mut Vec<UnknownConfigKey>,
+) {
+    for (key, item) in table.iter() {
+        path.push(key);
+        if KNOWN_CONFIG_KEYS
+            .iter()
+            .any(|known| key_path_matches(known, path, false))
+        {
+            // Leaf value; its type is checked by serde.
+        } else if KNOWN_CONFIG_KEYS
+            .iter()
+            .any(|known| key_path_matches(known, path, true))
+        {
+            for child in child_tables(item) {
+                collect_unknown_keys(src, child, path, unknown);
+            }
+        } else {
+            let offset = table
+                .get_key_value(key)
+                .and_then(|(key, _)| key.span())
+                .map(|span| span.start)
+                .unwrap_or(0);
+            let (line, column) = line_column(src, offset);
+            unknown.push(UnknownConfigKey {
+                path: path.join("."),
+                line,
+                column,
+                suggestion: suggest_known_key(path),
+            });
+        }
+        path.pop();
+    }
+}
+
+fn child_tables(item: &toml_edit::Item) -> Vec<&dyn toml_edit::TableLike> {
+    if let Some(tables) = item.as_array_of_tables() {
+        return tables
+            .iter()
+            .map(|table| table as &dyn toml_edit::TableLike)
+            .collect();
+    }
+    if let Some(array) = item.as_array() {
+        return array
+            .iter()
+            .filter_map(|value| value.as_inline_table())
+            .map(|table| table as &dyn toml_edit::TableLike)
+            .collect();
+    }
+    item.as_table_like().into_iter().collect()
+}
+
+fn key_path_matches(known: &str, path: &[&str], prefix_only: bool) -> bool {
+    let known = known.split('.').collect::<Vec<_>>();
+    let len_ok = if prefix_only {
+        path.len() < known.len()
+    } else {
+        path.len() == known.len()
+    };
+    len_ok
+        && known
+            .iter()
+            .zip(path)
+            .all(|(known, part)| *known == "*" || known == part)
+}
+
+fn suggest_known_key(path: &[&str]) -> Option<String> {
+    let (typo, parent) = path.split_last()?;
+    KNOWN_CONFIG_KEYS
+        .iter()
+        .filter_map(|known| {
+            let known = known.split('.').collect::<Vec<_>>();
+            let candidate = *known.get(parent.len())?;
+            let same_parent = known
+                .iter()
+                .zip(parent)
+                .all(|(known, part)| *known == "*" || known == part);
+            (same_parent && candidate != "*").then_some(candidate)
+        })
+        .map(|candidate| (edit_distance(typo, candidate), candidate))
+        .filter(|(distance, _)| *distance <= 3)
+        .min()
+        .map(|(_, candidate)| {
+            let mut suggested = parent.to_vec();
+            suggested.push(candidate);
+            suggested.join(".")
+        })
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut prev = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(prev[j + 1] + 1).min(current[j] + 1);
+        }
+        prev = current;
+    }
+    prev[b.len()]
+}
+
+fn line_column(src: &str, offset: usize) -> (usize, usize) {
+    let before = &src[..offset.min(src.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.chars().rev().take_while(|ch| *ch != '\n').count() + 1;
+    (line, column)
+}
+
 pub fn parse_config(src: &str) -> Result<Config> {
-    let cfg: Config = toml::from_str(src).context("failed to parse config TOML")?;
+    let mut cfg: Config = toml::from_str(src).map_err(|err| {
+        let unknown = find_unknown_config_keys(src).unwrap_or_default();
+        if unknown.is_empty() {
+            return anyhow::Error::new(err).context("failed to parse config TOML");
+        }
+        let keys = unknown
+            .iter()
+            .map(|key| key.path.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        anyhow::Error::new(err).context(format!(
+            "failed to parse config TOML (unknown keys: {keys}; run `gh-watch config doctor` for suggestions)"
+        ))
+    })?;
+    expand_path_fields(&mut cfg)?;
     validate_config(&cfg)?;
     Ok(cfg)
 }
 
+fn expand_path_fields(cfg: &mut Config) -> Result<()> {
+    if let Some(raw) = cfg.state_db_path.as_deref() {
+        cfg.state_db_path = Some(expand_path_value("state_db_path", raw)?);
+    }
+    for repo in &mut cfg.repositories {
+        if let Some(raw) = repo.local_path.as_deref() {
+            repo.local_path = Some(expand_path_value("repositories.local_path", raw)?);
+        }
+    }
+    for account in &mut cfg.accounts {
+        if let Some(raw) = account.gh_config_dir.as_deref() {
+            account.gh_config_dir = Some(expand_path_value("accounts.gh_config_dir", raw)?);
+        }
+    }
+    Ok(())
+}
+
+/// Expands a leading `~` plus `${VAR}`, `$VAR` and `%VAR%` references.
+/// Substituted values are inserted verbatim and are not expanded again.
+fn expand_path_value(field: &str, raw: &str) -> Result<String> {
+    let mut out = String::with_capacity(raw.len());
+    let mut rest = raw;
+
+    if let Some(after_tilde) = raw.strip_prefix('~') {
+        if after_tilde.is_empty() || after_tilde.starts_with(['/', '\\']) {
+            let home = home_dir()?;
+            out.push_str(&home.to_string_lossy());
+            rest = after_tilde;
+        }
+    }
+
+    while let Some(pos) = rest.find(['$', '%']) {
+        out.push_str(&rest[..pos]);
+        let marker = &rest[pos..];
+
+        let reference = if let Some(braced) = marker.strip_prefix("${") {
+            let end = braced
+                .find('}')
+                .ok_or_else(|| anyhow!("{field} has an unterminated ${{...}} reference: {raw}"))?;
+            Some((&braced[..end], end + 3))
+        } else if let Some(bare) = marker.strip_prefix('$') {
+            let len = env_var_name_len(bare);
+            (len > 0).then(|| (&bare[..len], len + 1))
+        } else {
+            let windows = &marker[1..];
+            let len = env_var_name_len(windows);
+            (len > 0 && windows[len..].starts_with('%')).then(|| (&windows[..len], len + 2))
+        };
+
+        let Some((name, consumed)) = reference else {
+            out.push_str(&marker[..1]);
+            rest = &marker[1..];
+            continue;
+        };
+
+        let value = env::var(name)
+            .map_err(|_| anyhow!("{field} references unset environment variable {name}: {raw}"))?;
+        out.push_str(&value);
+        rest = &marker[consumed..];
+    }
+
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn env_var_name_len(src: &str) -> usize {
+    src.char_indices()
+        .take_while(|(idx, ch)| {
+            ch.is_ascii_alphabetic() || *ch == '_' || (*idx > 0 && ch.is_ascii_digit())
+        })
+        .count()
+}
+
 pub fn load_config(path: Option<&Path>) -> Result<Config> {
     let loaded = load_config_with_path(path)?;
     Ok(loaded.config)
@@ -193,6 +1262,13 @@ pub fn resolve_config_path_with_source(path: Option<&Path>) -> Result<ResolvedCo
         });
     }
 
+    if let Some(profile) = active_profile()? {
+        return Ok(ResolvedConfigPath {
+            path: profile_config_path(&profile)?,
+            source: ConfigPathSource::Profile,
+        });
+    }
+
     let cwd_path = current_directory_config_path()?;
     if cwd_path.exists() {
         return Ok(ResolvedConfigPath {
@@ -223,6 +1299,13 @@ pub fn resolution_candidates() -> Result<Vec<ResolvedConfigPath>> {
             source: ConfigPathSource::EnvironmentVariable,
         });
     }
+    if let Some(profile) = active_profile()? {
+        candidates.push(ResolvedConfigPath {
+            path: profile_config_path(&profile)?,
+            source: ConfigPathSource::Profile,
+        });
+        return Ok(candidates);
+    }
     candidates.push(ResolvedConfigPath {
         path: current_directory_config_path()?,
         source: ConfigPathSource::CurrentDirectory,
@@ -245,6 +1328,78 @@ pub fn installed_config_path() -> Result<PathBuf> {
     Ok(dir.join("config.toml"))
 }
 
+pub fn active_profile() -> Result<Option<String>> {
+    let Some(raw) = env::var_os("GH_WATCH_PROFILE") else {
+        return Ok(None);
+    };
+    if raw.is_empty() {
+        return Ok(None);
+    }
+
+    let name = raw.to_string_lossy().to_string();
+    validate_profile_name(&name)?;
+    Ok(Some(name))
+}
+
+pub fn validate_profile_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_');
+    if !valid {
+        return Err(anyhow!(
+            "invalid profile name: {name} (use letters, digits, '-' or '_')"
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_filter_profile_name(name: &str) -> Result<()> {
+    if name == DEFAULT_FILTER_PROFILE {
+        return Err(anyhow!(
+            "filter profile name '{DEFAULT_FILTER_PROFILE}' is reserved for [filters]"
+        ));
+    }
+    validate_profile_name(name)
+}
+
+pub fn profiles_dir() -> Result<PathBuf> {
+    #[cfg(windows)]
+    {
+        let appdata = env::var_os("APPDATA").ok_or_else(|| anyhow!("APPDATA is not set"))?;
+        return Ok(PathBuf::from(appdata).join("gh-watch"));
+    }
+
+    #[cfg(not(windows))]
+    {
+        Ok(home_dir()?.join(".config").join("gh-watch"))
+    }
+}
+
+pub fn profile_config_path(profile: &str) -> Result<PathBuf> {
+    Ok(profiles_dir()?.join(profile).join("config.toml"))
+}
+
+pub fn list_profiles() -> Result<Vec<String>> {
+    let dir = profiles_dir()?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut profiles = Vec::new();
+    for entry in fs::read_dir(&dir)
+        .with_context(|| format!("failed to read profiles dir: {}", dir.display()))?
+    {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if validate_profile_name(&name).is_ok() && entry.path().join("config.toml").is_file() {
+            profiles.push(name);
+        }
+    }
+    profiles.sort();
+    Ok(profiles)
+}
+
 fn gh_watch_config_path_from_env() -> Option<PathBuf> {
     let raw = env::var_os("GH_WATCH_CONFIG")?;
     if raw.is_empty() {
@@ -259,23 +1414,25 @@ fn current_directory_config_path() -> Result<PathBuf> {
 }
 
 pub fn default_state_db_path() -> Result<PathBuf> {
+    let mut dir = default_state_dir()?;
+    if let Some(profile) = active_profile()? {
+        dir.push(profile);
+    }
+    Ok(dir.join("state.db"))
+}
+
+fn default_state_dir() -> Result<PathBuf> {
     #[cfg(windows)]
     {
         let local_appdata =
             env::var_os("LOCALAPPDATA").ok_or_else(|| anyhow!("LOCALAPPDATA is not set"))?;
-        return Ok(PathBuf::from(local_appdata)
-            .join("gh-watch")
-            .join("state.db"));
+        return Ok(PathBuf::from(local_appdata).join("gh-watch"));
     }
 
     #[cfg(not(windows))]
     {
         let home = home_dir()?;
-        Ok(home
-            .join(".local")
-            .join("share")
-            .join("gh-watch")
-            .join("state.db"))
+        Ok(home.join(".local").join("share").join("gh-watch"))
     }
 }
 
@@ -285,6 +1442,87 @@ fn home_dir() -> Result<PathBuf> {
         .ok_or_else(|| anyhow!("could not determine home directory"))
 }
 
+/// Rejects unknown key names and keys configured for more than one action. A configured key
+/// may shadow another action's default binding.
+fn validate_keymap(keymap: &KeymapConfig) -> Result<()> {
+    let mut bound_by: HashMap<KeyCode, &str> = HashMap::new();
+    for (action, _) in DEFAULT_KEYMAP {
+        for key in keymap.keys_for(action).unwrap_or_default() {
+            let code = parse_key_code(key).ok_or_else(|| ConfigError::InvalidKeyCode {
+                action: action.to_string(),
+                key: key.clone(),
+            })?;
+            if let Some(other) = bound_by.insert(code, action) {
+                if other != *action {
+                    return Err(anyhow!(
+                        "key '{key}' is bound to both keymap.{other} and keymap.{action}"
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_accounts(cfg: &Config) -> Result<()> {
+    let mut names = HashSet::new();
+    for account in &cfg.accounts {
+        let name = account.name.as_str();
+        if name.trim().is_empty() {
+            return Err(anyhow!("accounts.name must not be empty"));
+        }
+        if !names.insert(name) {
+            return Err(anyhow!("account '{name}' is defined more than once"));
+        }
+        if account.host.trim().is_empty() {
+            return Err(anyhow!("account '{name}' has an empty host"));
+        }
+        match (
+            account.gh_config_dir.as_deref(),
+            account.token_env.as_deref(),
+        ) {
+            (Some(_), None) => {}
+            (None, Some(var)) if !var.trim().is_empty() => {}
+            (None, Some(_)) => {
+                return Err(anyhow!("account '{name}' has an empty token_env"));
+            }
+            _ => {
+                return Err(anyhow!(
+                    "account '{name}' must set exactly one of gh_config_dir or token_env"
+                ));
+            }
+        }
+    }
+
+    for repo in &cfg.repositories {
+        if let Some(account) = repo.account.as_deref() {
+            if !names.contains(account) {
+                return Err(anyhow!(
+                    "repository '{}' uses account '{account}', which is not defined in [[accounts]]",
+                    repo.name
+                ));
+            }
+        }
+        match (repo.account.as_deref(), repo.gh_profile.as_deref()) {
+            (Some(_), Some(_)) => {
+                return Err(anyhow!(
+                    "repository '{}' must not set both account and gh_profile",
+                    repo.name
+                ));
+            }
+            (None, Some(profile)) if profile.trim().is_empty() => {
+                return Err(anyhow!(
+                    "repository '{}' has an empty gh_profile",
+                    repo.name
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
 fn validate_config(cfg: &Config) -> Result<()> {
     if cfg.repositories.is_empty() {
         return Err(anyhow!("repositories must contain at least one entry"));
@@ -294,6 +1532,22 @@ fn validate_config(cfg: &Config) -> Result<()> {
         validate_repo_name(&repo.name)?;
     }
 
+    validate_accounts(cfg)?;
+
+    for search in &cfg.startup_search_queries {
+        if search.query.trim().is_empty() {
+            return Err(anyhow!("startup_search_queries.query must not be empty"));
+        }
+        for repo in &search.repos {
+            if !cfg.repositories.iter().any(|watched| &watched.name == repo) {
+                return Err(anyhow!(
+                    "startup search '{}' names repository '{repo}', which is not in [[repositories]]",
+                    search.query
+                ));
+            }
+        }
+    }
+
     if cfg.interval_seconds == 0 {
         return Err(anyhow!("interval_seconds must be >= 1"));
     }
@@ -302,6 +1556,14 @@ fn validate_config(cfg: &Config) -> Result<()> {
         return Err(anyhow!("bootstrap_lookback_hours must be >= 1"));
     }
 
+    if cfg.notifications.max_event_age_hours == 0 {
+        return Err(anyhow!("notifications.max_event_age_hours must be >= 1"));
+    }
+
+    if cfg.notifications.body_max_chars == 0 {
+        return Err(anyhow!("notifications.body_max_chars must be >= 1"));
+    }
+
     if cfg.timeline_limit == 0 {
         return Err(anyhow!("timeline_limit must be >= 1"));
     }
@@ -310,23 +1572,184 @@ fn validate_config(cfg: &Config) -> Result<()> {
         return Err(anyhow!("poll.timeout_seconds must be >= 1"));
     }
 
+    if cfg.poll.jitter_seconds > 0 && cfg.poll.jitter_seconds >= cfg.interval_seconds {
+        return Err(anyhow!(
+            "poll.jitter_seconds ({}) must be less than interval_seconds ({})",
+            cfg.poll.jitter_seconds,
+            cfg.interval_seconds
+        ));
+    }
+
+    if cfg
+        .default_event_kinds
+        .as_ref()
+        .is_some_and(|kinds| kinds.is_empty())
+    {
+        return Err(anyhow!(
+            "default_event_kinds must not be empty (omit it to allow every kind)"
+        ));
+    }
+
+    for name in cfg.filter_profiles.keys() {
+        validate_filter_profile_name(name)?;
+    }
+
+    if let Some(active) = cfg.active_filter_profile.as_deref() {
+        if !cfg.filter_profiles.contains_key(active) {
+            return Err(anyhow!(
+                "active_filter_profile '{active}' is not defined in [filter_profiles]"
+            ));
+        }
+    }
+
+    validate_keymap(&cfg.keymap)?;
+
+    for filters in std::iter::once(&cfg.filters).chain(cfg.filter_profiles.values()) {
+        if let Some(org_team) = filters.auto_fetch_org_team.as_deref() {
+            split_org_team(org_team)?;
+        }
+    }
+
+    if cfg.poll.api_cache_ttl_seconds == Some(0) {
+        return Err(anyhow!("poll.api_cache_ttl_seconds must be >= 1"));
+    }
+
+    if let Some(proxy) = cfg.network.proxy.as_deref() {
+        validate_proxy_url(proxy)?;
+    }
+
+    if let Some(summary) = &cfg.notifications.summary {
+        if parse_summary_schedule(&summary.schedule).is_none() {
+            return Err(anyhow!(
+                "invalid notifications.summary.schedule '{}' (expected a weekday and HH:MM, e.g. `Mon 09:00`)",
+                summary.schedule
+            ));
+        }
+        if parse_summary_timezone(&summary.timezone).is_none() {
+            return Err(anyhow!(
+                "invalid notifications.summary.timezone '{}' (use `local`, `UTC` or an offset like `+09:00`)",
+                summary.timezone
+            ));
+        }
+    }
+
+    if let Some(schedule) = &cfg.notification_schedule {
+        parse_notification_schedule(schedule)?;
+    }
+
+    if cfg.max_notification_history == 0 {
+        return Err(anyhow!("max_notification_history must be at least 1"));
+    }
+
+    if cfg.reminders.review_request_after_days == Some(0) {
+        return Err(anyhow!(
+            "reminders.review_request_after_days must be at least 1"
+        ));
+    }
+
+    if let Some(sync) = &cfg.sync {
+        if sync.gist_id.is_empty() || !sync.gist_id.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(anyhow!(
+                "invalid sync.gist_id '{}' (expected the id from the gist URL)",
+                sync.gist_id
+            ));
+        }
+        if sync.interval_minutes == Some(0) {
+            return Err(anyhow!("sync.interval_minutes must be at least 1"));
+        }
+    }
+
+    if let Some(command) = cfg.display.browser_command.as_deref() {
+        if split_shell_words(command).is_none() {
+            return Err(anyhow!(
+                "display.browser_command must be a non-empty command with balanced quotes"
+            ));
+        }
+    }
+
     Ok(())
 }
 
 pub fn stability_warnings(cfg: &Config) -> Vec<String> {
     let mut warnings = Vec::new();
 
-    if cfg.interval_seconds < 30 {
-        warnings.push(format!(
-            "stability warning: interval_seconds={} is short; recommend >= 30 for reliable polling",
-            cfg.interval_seconds
-        ));
+    if cfg.interval_seconds < MIN_INTERVAL_SECONDS {
+        if cfg.i_know_what_im_doing {
+            warnings.push(format!(
+                "stability warning: interval_seconds={} is short; recommend >= {MIN_INTERVAL_SECONDS} for reliable polling",
+                cfg.interval_seconds
+            ));
+        } else {
+            warnings.push(format!(
+                "stability warning: interval_seconds={} is below the floor; watch uses >= {MIN_INTERVAL_SECONDS} unless i_know_what_im_doing = true",
+                cfg.interval_seconds
+            ));
+        }
     }
 
     warnings
 }
 
-fn validate_repo_name(repo: &str) -> Result<()> {
+/// Lower bound on gh calls per hour for the enabled repositories at `interval_seconds`.
+pub fn estimated_hourly_api_calls(cfg: &Config) -> u64 {
+    let repos = cfg.repositories.iter().filter(|repo| repo.enabled).count() as u64;
+    let polls_per_hour = 3600 / cfg.interval_seconds.max(1);
+    repos * MIN_API_CALLS_PER_REPO_POLL * polls_per_hour.max(1)
+}
+
+pub fn api_budget_warning(cfg: &Config) -> Option<String> {
+    let estimated = estimated_hourly_api_calls(cfg);
+    (estimated > GH_API_HOURLY_LIMIT).then(|| {
+        format!(
+            "api budget warning: ~{estimated} calls/hour for {} repositories every {}s exceeds the {GH_API_HOURLY_LIMIT} req/hour limit; raise interval_seconds",
+            cfg.repositories.iter().filter(|repo| repo.enabled).count(),
+            cfg.interval_seconds
+        )
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntervalClamp {
+    pub requested: u64,
+    pub effective: u64,
+}
+
+impl Display for IntervalClamp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "interval {}s is below the {}s floor; polling every {}s instead (set i_know_what_im_doing = true to allow it)",
+            self.requested, MIN_INTERVAL_SECONDS, self.effective
+        )
+    }
+}
+
+/// Applies `--interval-seconds` over the config value, then clamps the result to
+/// `MIN_INTERVAL_SECONDS` unless the config opts out.
+pub fn apply_interval_override(
+    cfg: &mut Config,
+    override_seconds: Option<u64>,
+) -> Result<Option<IntervalClamp>> {
+    if let Some(seconds) = override_seconds {
+        if seconds == 0 {
+            return Err(anyhow!("--interval-seconds must be >= 1"));
+        }
+        cfg.interval_seconds = seconds;
+    }
+
+    if cfg.interval_seconds >= MIN_INTERVAL_SECONDS || cfg.i_know_what_im_doing {
+        return Ok(None);
+    }
+
+    let clamp = IntervalClamp {
+        requested: cfg.interval_seconds,
+        effective: MIN_INTERVAL_SECONDS,
+    };
+    cfg.interval_seconds = clamp.effective;
+    Ok(Some(clamp))
+}
+
+pub(crate) fn validate_repo_name(repo: &str) -> Result<()> {
     let mut parts = repo.split('/');
     let owner = parts.next().unwrap_or("");
     let name = parts.next().unwrap_or("");
@@ -0,0 +1,544 @@
+use anyhow::{Context, Result};
+use serde_json::{json, Map, Value};
+
+use super::DEFAULT_KEYMAP;
+use crate::domain::events::EventKind;
+
+/// Bumped whenever a key is added, removed or changes type, so the `$id` changes and
+/// editors drop a cached copy of the previous schema.
+pub const CONFIG_SCHEMA_VERSION: u32 = 13;
+
+const REVIEW_STATES: [&str; 4] = ["approved", "changes_requested", "commented", "dismissed"];
+
+/// JSON Schema (draft 2020-12) of the config file, printed by `gh-watch config schema`.
+/// Maintained by hand next to the serde structs; the tests keep it in step with
+/// `KNOWN_CONFIG_KEYS` and `DEFAULT_FIELD_VALUES`.
+pub fn config_schema() -> Value {
+    let event_kinds: Vec<&str> = EventKind::ALL.iter().map(EventKind::as_str).collect();
+    let keymap: Map<String, Value> = DEFAULT_KEYMAP
+        .iter()
+        .map(|(action, keys)| {
+            let schema = json!({ "type": "array", "items": { "type": "string" }, "default": keys });
+            (action.to_string(), schema)
+        })
+        .collect();
+    let notifications = json!({
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "enabled": boolean(true, "Send desktop notifications."),
+            "include_url": boolean(true, "Include the event URL in notifications."),
+            "yank_fallback_print": boolean(false, "Show the yanked text in the status line when copying to the clipboard fails."),
+            "kinds": { "$ref": "#/$defs/notification_kinds" },
+            "max_event_age_hours": { "type": "integer", "minimum": 1, "default": 48, "description": "Events older than this many hours are recorded but never notified." },
+            "body_max_chars": { "type": "integer", "minimum": 1, "default": 200, "description": "Longest comment or review excerpt added to a notification, in characters." },
+            "summary": {
+                "type": "object",
+                "additionalProperties": false,
+                "required": ["schedule"],
+                "properties": {
+                    "schedule": string("Weekday and time of day, e.g. Mon 09:00."),
+                    "timezone": { "type": "string", "default": "local", "description": "local, UTC or an offset such as +09:00." }
+                }
+            }
+        }
+    });
+    let notification_kinds = json!({
+        "type": "object",
+        "description": "Whether to notify about each event kind; kinds left out are notified.",
+        "propertyNames": { "enum": event_kinds },
+        "additionalProperties": { "type": "boolean" },
+        "default": {}
+    });
+
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": format!("urn:gh-watch:config-schema:v{CONFIG_SCHEMA_VERSION}"),
+        "title": "gh-watch config",
+        "type": "object",
+        "additionalProperties": false,
+        "required": ["repositories"],
+        "properties": {
+            "interval_seconds": integer(300, "Seconds between polls; clamped to 30 unless i_know_what_im_doing is set."),
+            "i_know_what_im_doing": boolean(false, "Allow interval_seconds below 30."),
+            "bootstrap_lookback_hours": integer(24, "How far back the first poll of a repository looks."),
+            "timeline_limit": integer(500, "Events loaded into the TUI timeline."),
+            "retention_days": integer(90, "Days events are kept in the state db."),
+            "max_notification_history": integer(10000, "Delivered and read events kept in the state db."),
+            "debounce_resize_ms": integer(50, "Quiet period after a terminal resize before redrawing."),
+            "retention": {
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "keep_unread": boolean(false, "Never prune unread events."),
+                    "days_by_kind": {
+                        "type": "object",
+                        "description": "Retention days per event kind.",
+                        "propertyNames": { "enum": event_kinds },
+                        "additionalProperties": { "type": "integer", "minimum": 0 },
+                        "default": {}
+                    }
+                }
+            },
+            "reminders": {
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "review_request_after_days": optional_integer("Days before a pending review request is reminded about daily.")
+                }
+            },
+            "sync": {
+                "type": "object",
+                "additionalProperties": false,
+                "required": ["backend", "gist_id"],
+                "properties": {
+                    "backend": { "enum": ["gist"] },
+                    "gist_id": string("Existing gist the default gh login can edit."),
+                    "interval_minutes": optional_integer("Minutes between syncs while watching; unset syncs after every poll.")
+                }
+            },
+            "state_db_path": string("Path of the SQLite state db."),
+            "accounts": {
+                "type": "array",
+                "description": "Extra GitHub logins that repositories[].account can route a repository through.",
+                "items": {
+                    "type": "object",
+                    "additionalProperties": false,
+                    "required": ["name"],
+                    "properties": {
+                        "name": string("Name referenced by repositories[].account."),
+                        "host": { "type": "string", "default": "github.com" },
+                        "gh_config_dir": string("Passed to gh as GH_CONFIG_DIR."),
+                        "token_env": string("Environment variable holding the account's token.")
+                    }
+                }
+            },
+            "repositories": {
+                "type": "array",
+                "minItems": 1,
+                "items": {
+                    "type": "object",
+                    "additionalProperties": false,
+                    "required": ["name"],
+                    "properties": {
+                        "name": { "type": "string", "pattern": "^[^/\\s]+/[^/\\s]+$", "description": "owner/repo" },
+                        "enabled": boolean(true, "Poll this repository."),
+                        "event_kinds": { "$ref": "#/$defs/event_kinds" },
+                        "local_path": string("Local clone used as the working directory for gh pr checkout."),
+                        "watch_review_threads": boolean(false, "Emit review_thread_resolved events; costs one extra request per poll."),
+                        "account": string("[[accounts]] name whose credentials fetch this repository."),
+                        "gh_profile": string("Stored gh login whose token fetches this repository."),
+                        "path_filters": { "type": "array", "items": { "type": "string" }, "description": "Globs a pull request must touch to be notified about; overrides filters.global_path_filters." },
+                        "topic_tags": { "type": "array", "items": { "type": "string" }, "description": "Labels the Repositories tab groups this repository under; unset uses its GitHub topics." },
+                        "notification_kinds": { "$ref": "#/$defs/notification_kinds" }
+                    }
+                }
+            },
+            "startup_search_queries": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "additionalProperties": false,
+                    "required": ["query"],
+                    "properties": {
+                        "query": string("GitHub issue search, e.g. is:open assignee:@me."),
+                        "repos": { "type": "array", "items": { "type": "string" }, "default": [] }
+                    }
+                }
+            },
+            "watch_my_prs": boolean(false, "Emit my_pr_approved and my_pr_checks_passed for the viewer's open pull requests."),
+            "notifications": notifications,
+            "notification_schedule": {
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "enabled_days": { "type": "array", "items": { "type": "string" }, "default": [], "description": "Weekdays notifications are sent on, e.g. Mon; empty means every day." },
+                    "enabled_hours": { "type": "array", "items": { "type": "integer", "minimum": 0, "maximum": 23 }, "minItems": 2, "maxItems": 2, "default": [0, 23], "description": "First and last hour notifications are sent, both inclusive." }
+                }
+            },
+            "filters": { "$ref": "#/$defs/filters" },
+            "default_event_kinds": { "$ref": "#/$defs/event_kinds" },
+            "filter_profiles": {
+                "type": "object",
+                "additionalProperties": { "$ref": "#/$defs/filters" }
+            },
+            "active_filter_profile": string("filter_profiles entry to apply; default selects [filters]."),
+            "poll": {
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "timeout_seconds": integer(30, "Timeout of one poll."),
+                    "api_cache_ttl_seconds": optional_integer("Seconds gh API lookups other than repository event fetches are cached."),
+                    "cache_ttl_seconds": integer(0, "Seconds bootstrap responses are cached on disk; 0 disables the cache."),
+                    "jitter_seconds": integer(0, "Randomizes each watch cycle's delay by up to this many seconds."),
+                    "use_graphql_batch": boolean(false, "Fetch repositories through batched GraphQL queries.")
+                }
+            },
+            "network": {
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "proxy": string("http, https, socks5 or socks5h proxy URL for gh."),
+                    "no_proxy": { "type": "array", "items": { "type": "string" }, "default": [] }
+                }
+            },
+            "display": {
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "browser_command": string("Command that opens event URLs; %s is the URL."),
+                    "layout": { "enum": ["auto", "stacked", "split"], "default": "auto" },
+                    "kind_labels": { "enum": ["short", "long", "icon"], "default": "short" },
+                    "resolve_subject_state": boolean(false, "Badge closed and merged issues and pull requests in the timeline."),
+                    "group_repos_by_topic": boolean(false, "Group the Repositories tab by repository topic.")
+                }
+            },
+            "keymap": {
+                "type": "object",
+                "additionalProperties": false,
+                "properties": keymap
+            }
+        },
+        "$defs": {
+            "notification_kinds": notification_kinds,
+            "event_kinds": {
+                "type": "array",
+                "items": { "enum": event_kinds },
+                "default": []
+            },
+            "filters": {
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "event_kinds": { "$ref": "#/$defs/event_kinds" },
+                    "ignore_actors": { "type": "array", "items": { "type": "string" }, "default": [] },
+                    "only_involving_me": boolean(false, "Only notify about events involving team_members."),
+                    "ignore_self": boolean(false, "Drop events whose actor is the viewer."),
+                    "milestone_names": { "type": "array", "items": { "type": "string" }, "default": [] },
+                    "review_states": { "type": "array", "items": { "enum": REVIEW_STATES }, "default": [] },
+                    "team_members": { "type": "array", "items": { "type": "string" }, "default": [] },
+                    "auto_fetch_org_team": string("org or org/team whose members are added to team_members."),
+                    "global_path_filters": { "type": "array", "items": { "type": "string" }, "default": [], "description": "Globs a pull request must touch to be notified about, for repositories without path_filters." }
+                }
+            }
+        }
+    })
+}
+
+fn integer(default: u64, description: &str) -> Value {
+    json!({ "type": "integer", "minimum": 0, "default": default, "description": description })
+}
+
+fn optional_integer(description: &str) -> Value {
+    json!({ "type": "integer", "minimum": 0, "description": description })
+}
+
+fn boolean(default: bool, description: &str) -> Value {
+    json!({ "type": "boolean", "default": default, "description": description })
+}
+
+fn string(description: &str) -> Value {
+    json!({ "type": "string", "description": description })
+}
+
+/// A place where a config file does not match `config_schema`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaViolation {
+    /// Location such as `repositories[1].event_kinds[0]`; empty for the document root.
+    pub path: String,
+    pub message: String,
+    unknown_key: bool,
+}
+
+impl SchemaViolation {
+    /// Whether this only reports a key the schema does not define, which
+    /// `find_unknown_config_keys` already reports with its line and a suggestion.
+    pub fn is_unknown_key(&self) -> bool {
+        self.unknown_key
+    }
+}
+
+impl std::fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.path.is_empty() {
+            f.write_str(&self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+/// Every place the TOML document `src` breaks `config_schema`, unlike serde which stops
+/// at the first error. Fails only when `src` is not TOML.
+pub fn config_schema_violations(src: &str) -> Result<Vec<SchemaViolation>> {
+    let doc: toml::Table = toml::from_str(src).context("failed to parse config TOML")?;
+    let doc = serde_json::to_value(doc)?;
+    let schema = config_schema();
+    let mut violations = Vec::new();
+    check(&schema, &schema, &doc, "", &mut violations);
+    Ok(violations)
+}
+
+/// Checks the subset of JSON Schema `config_schema` uses.
+fn check(root: &Value, schema: &Value, value: &Value, path: &str, out: &mut Vec<SchemaViolation>) {
+    let schema = resolve(root, schema);
+    let mut violation = |message: String| {
+        out.push(SchemaViolation {
+            path: path.to_string(),
+            message,
+            unknown_key: false,
+        })
+    };
+
+    if let Some(expected) = schema.get("type").and_then(Value::as_str) {
+        let matches = match expected {
+            "object" => value.is_object(),
+            "array" => value.is_array(),
+            "string" => value.is_string(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "boolean" => value.is_boolean(),
+            _ => true,
+        };
+        if !matches {
+            violation(format!("expected {expected}, found {}", describe(value)));
+            return;
+        }
+    }
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            violation(format!("{value} is not one of {}", join_values(allowed)));
+        }
+    }
+    if let (Some(minimum), Some(number)) = (
+        schema.get("minimum").and_then(Value::as_i64),
+        value.as_i64(),
+    ) {
+        if number < minimum {
+            violation(format!("{number} is below the minimum {minimum}"));
+        }
+    }
+    if let (Some(_), Some(text)) = (schema.get("pattern"), value.as_str()) {
+        // The only pattern is owner/repo on repositories[].name, checked without a regex engine.
+        let valid = text
+            .split_once('/')
+            .is_some_and(|(owner, repo)| is_repo_segment(owner) && is_repo_segment(repo));
+        if !valid {
+            violation(format!("\"{text}\" is not owner/repo"));
+        }
+    }
+
+    match value {
+        Value::Array(items) => {
+            if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+                if (items.len() as u64) < min {
+                    violation(format!("expected at least {min} entries"));
+                }
+            }
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    check(root, item_schema, item, &format!("{path}[{index}]"), out);
+                }
+            }
+        }
+        Value::Object(fields) => {
+            for required in schema
+                .get("required")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+            {
+                if !fields.contains_key(required) {
+                    violation(format!("missing required key `{required}`"));
+                }
+            }
+            let properties = schema.get("properties").and_then(Value::as_object);
+            let names = schema.get("propertyNames");
+            let additional = schema.get("additionalProperties");
+            for (key, field) in fields {
+                let field_path = join_path(path, key);
+                if let Some(names) = names {
+                    check(root, names, &Value::String(key.clone()), &field_path, out);
+                }
+                match (properties.and_then(|props| props.get(key)), additional) {
+                    (Some(field_schema), _) => check(root, field_schema, field, &field_path, out),
+                    (None, Some(Value::Bool(false))) => out.push(SchemaViolation {
+                        path: field_path,
+                        message: "unknown key".to_string(),
+                        unknown_key: true,
+                    }),
+                    (None, Some(field_schema)) if field_schema.is_object() => {
+                        check(root, field_schema, field, &field_path, out)
+                    }
+                    (None, _) => {}
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Follows a local `{"$ref": "#/$defs/<name>"}`.
+fn resolve<'a>(root: &'a Value, schema: &'a Value) -> &'a Value {
+    schema
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|reference| reference.strip_prefix("#/"))
+        .and_then(|pointer| root.pointer(&format!("/{pointer}")))
+        .unwrap_or(schema)
+}
+
+fn is_repo_segment(segment: &str) -> bool {
+    !segment.is_empty() && !segment.chars().any(char::is_whitespace)
+}
+
+fn join_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+fn join_values(values: &[Value]) -> String {
+    values
+        .iter()
+        .map(|value| {
+            value
+                .as_str()
+                .map_or_else(|| value.to_string(), str::to_string)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn describe(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(number) if number.is_f64() => "float",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "table",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::{config_schema, config_schema_violations, resolve};
+    use crate::config::{DEFAULT_FIELD_VALUES, KNOWN_CONFIG_KEYS};
+
+    /// Schema of the dotted config key `key`; `*` selects a map value.
+    fn schema_at<'a>(root: &'a Value, key: &str) -> Option<&'a Value> {
+        key.split('.').try_fold(root, |schema, segment| {
+            let mut schema = resolve(root, schema);
+            if let Some(items) = schema.get("items") {
+                schema = resolve(root, items);
+            }
+            let next = if segment == "*" {
+                schema.get("additionalProperties")
+            } else {
+                schema.get("properties")?.get(segment)
+            }?;
+            Some(resolve(root, next))
+        })
+    }
+
+    /// Dotted keys of every leaf the schema defines, in `KNOWN_CONFIG_KEYS` spelling.
+    fn leaf_keys(root: &Value, schema: &Value, prefix: &str, out: &mut Vec<String>) {
+        let mut schema = resolve(root, schema);
+        if let Some(items) = schema
+            .get("items")
+            .filter(|items| resolve(root, items).get("properties").is_some())
+        {
+            schema = resolve(root, items);
+        }
+        let children = schema.get("properties").and_then(Value::as_object);
+        let map_values = schema
+            .get("additionalProperties")
+            .filter(|value| value.is_object());
+        if children.is_none() && map_values.is_none() {
+            out.push(prefix.to_string());
+            return;
+        }
+        for (key, child) in children.into_iter().flatten() {
+            leaf_keys(root, child, &super::join_path(prefix, key), out);
+        }
+        if let Some(values) = map_values {
+            leaf_keys(root, values, &super::join_path(prefix, "*"), out);
+        }
+    }
+
+    #[test]
+    fn schema_defines_exactly_the_known_config_keys() {
+        let schema = config_schema();
+        let mut leaves = Vec::new();
+        leaf_keys(&schema, &schema, "", &mut leaves);
+        leaves.sort();
+        let mut known: Vec<String> = KNOWN_CONFIG_KEYS
+            .iter()
+            .map(|key| key.to_string())
+            .collect();
+        known.sort();
+        assert_eq!(leaves, known);
+    }
+
+    #[test]
+    fn schema_defaults_match_the_documented_defaults() {
+        let schema = config_schema();
+        for (key, value) in DEFAULT_FIELD_VALUES {
+            let expected: Value = serde_json::from_str(value)
+                .unwrap_or_else(|err| panic!("default of {key} is not JSON: {err}"));
+            let field = schema_at(&schema, key).unwrap_or_else(|| panic!("{key} not in schema"));
+            assert_eq!(field.get("default"), Some(&expected), "default of {key}");
+        }
+    }
+
+    #[test]
+    fn example_config_satisfies_the_schema() {
+        let example = include_str!("../../config.example.toml");
+        assert_eq!(config_schema_violations(example).unwrap(), Vec::new());
+    }
+
+    #[test]
+    fn violations_name_every_bad_value_with_its_path() {
+        let violations = config_schema_violations(
+            r#"
+interval_seconds = "often"
+
+[[repositories]]
+name = "acme"
+event_kinds = ["pr_created", "pr_craeted"]
+
+[display]
+layout = "grid"
+"#,
+        )
+        .unwrap();
+        let rendered: Vec<String> = violations.iter().map(ToString::to_string).collect();
+
+        assert_eq!(
+            rendered,
+            vec![
+                "display.layout: \"grid\" is not one of auto, stacked, split".to_string(),
+                "interval_seconds: expected integer, found string".to_string(),
+                "repositories[0].event_kinds[1]: \"pr_craeted\" is not one of pr_created, issue_created, issue_comment_created, pr_review_comment_created, pr_review_requested, pr_review_submitted, pr_review_approved, pr_review_changes_requested, pr_review_dismissed, pr_merged, pr_milestone_added, issue_milestone_added, review_thread_resolved, my_pr_approved, my_pr_checks_passed".to_string(),
+                "repositories[0].name: \"acme\" is not owner/repo".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_keys_are_flagged_separately() {
+        let violations =
+            config_schema_violations("[[repositories]]\nname = \"acme/api\"\nbranch = \"main\"\n")
+                .unwrap();
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].path, "repositories[0].branch");
+        assert!(violations[0].is_unknown_key());
+    }
+}
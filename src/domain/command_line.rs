@@ -0,0 +1,140 @@
+/// Splits a command line into words with POSIX-like quoting: single quotes are literal,
+/// double quotes allow `\` escapes, and a bare `\` escapes the next character.
+/// Returns `None` for unbalanced quotes, a trailing escape, or no words at all.
+pub fn split_shell_words(raw: &str) -> Option<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut active_quote: Option<char> = None;
+    let mut escaped = false;
+
+    for ch in raw.chars() {
+        if escaped {
+            current.push(ch);
+            escaped = false;
+            continue;
+        }
+
+        if let Some(quote) = active_quote {
+            if ch == quote {
+                active_quote = None;
+            } else if quote == '"' && ch == '\\' {
+                escaped = true;
+            } else {
+                current.push(ch);
+            }
+            continue;
+        }
+
+        match ch {
+            '\'' | '"' => active_quote = Some(ch),
+            '\\' => escaped = true,
+            c if c.is_whitespace() => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            _ => current.push(ch),
+        }
+    }
+
+    if escaped || active_quote.is_some() {
+        return None;
+    }
+
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    if tokens.is_empty() {
+        return None;
+    }
+
+    Some(tokens)
+}
+
+/// Builds `(program, args)` for opening `url` with `raw`: every `%s` is replaced by the URL,
+/// and the URL is appended as the last argument when there is no `%s`.
+pub fn url_command(raw: &str, url: &str) -> Option<(String, Vec<String>)> {
+    let mut tokens = split_shell_words(raw)?;
+    let has_placeholder = tokens.iter().any(|token| token.contains("%s"));
+
+    for token in &mut tokens {
+        if token.contains("%s") {
+            *token = token.replace("%s", url);
+        }
+    }
+
+    if !has_placeholder {
+        tokens.push(url.to_string());
+    }
+
+    let bin = tokens.remove(0);
+    Some((bin, tokens))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{split_shell_words, url_command};
+
+    #[test]
+    fn url_command_replaces_percent_s_placeholder() {
+        let url = "https://example.com/placeholder";
+        let (bin, args) = url_command("w3m %s --title=%s", url).expect("command should parse");
+
+        assert_eq!(bin, "w3m");
+        assert_eq!(
+            args,
+            vec![
+                "https://example.com/placeholder".to_string(),
+                "--title=https://example.com/placeholder".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn url_command_appends_url_after_quoted_macos_app_name() {
+        let url = "https://example.com/pr/1";
+        let (bin, args) = url_command(
+            r#"open -a "Google Chrome" --args --profile-directory=Work"#,
+            url,
+        )
+        .expect("command should parse");
+
+        assert_eq!(bin, "open");
+        assert_eq!(
+            args,
+            vec![
+                "-a",
+                "Google Chrome",
+                "--args",
+                "--profile-directory=Work",
+                "https://example.com/pr/1"
+            ]
+        );
+    }
+
+    #[test]
+    fn url_command_keeps_quoted_binary_path_with_spaces() {
+        let (bin, args) = url_command(
+            r#"'/Applications/Google Chrome.app/Contents/MacOS/Google Chrome' --new-window %s"#,
+            "https://example.com",
+        )
+        .expect("command should parse");
+
+        assert_eq!(
+            bin,
+            "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome"
+        );
+        assert_eq!(args, vec!["--new-window", "https://example.com"]);
+    }
+
+    #[test]
+    fn split_shell_words_rejects_unbalanced_quotes_and_blank_input() {
+        assert_eq!(split_shell_words(r#"open -a "Firefox"#), None);
+        assert_eq!(split_shell_words("   "), None);
+        assert_eq!(
+            split_shell_words(r"my\ browser"),
+            Some(vec!["my browser".to_string()])
+        );
+    }
+}
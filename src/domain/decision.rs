@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::{cmp::Reverse, collections::HashSet};
 
 use super::events::WatchEvent;
 
@@ -24,6 +24,6 @@ pub fn decide_notification(
 }
 
 pub fn sort_timeline_desc(mut events: Vec<WatchEvent>) -> Vec<WatchEvent> {
-    events.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    events.sort_by_key(|event| Reverse(event.created_at));
     events
 }
@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+
+use super::failure::{
+    FAILURE_CONTEXT_ENDPOINT, FAILURE_CONTEXT_RETRY_AFTER_SECONDS, FAILURE_CONTEXT_STATUS_CODE,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponseTooLargeError {
+    pub endpoint: String,
+    pub size_bytes: usize,
+    pub limit_bytes: usize,
+}
+
+impl std::fmt::Display for ResponseTooLargeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "gh response too large for {}: {} bytes (limit={} bytes)",
+            self.endpoint, self.size_bytes, self.limit_bytes
+        )
+    }
+}
+
+impl std::error::Error for ResponseTooLargeError {}
+
+/// GitHub refused a gh call because a primary or secondary rate limit was hit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GhRateLimitError {
+    pub endpoint: String,
+    pub status_code: Option<u16>,
+    /// Seconds until the limit resets, when GitHub reports it.
+    pub retry_after_seconds: Option<u64>,
+    pub stderr: String,
+}
+
+impl GhRateLimitError {
+    /// `endpoint`, `status_code` and `retry_after_seconds` for `FailureRecord::context`.
+    pub fn context(&self) -> HashMap<String, String> {
+        let mut context =
+            HashMap::from([(FAILURE_CONTEXT_ENDPOINT.to_string(), self.endpoint.clone())]);
+        if let Some(status_code) = self.status_code {
+            context.insert(
+                FAILURE_CONTEXT_STATUS_CODE.to_string(),
+                status_code.to_string(),
+            );
+        }
+        if let Some(retry_after) = self.retry_after_seconds {
+            context.insert(
+                FAILURE_CONTEXT_RETRY_AFTER_SECONDS.to_string(),
+                retry_after.to_string(),
+            );
+        }
+        context
+    }
+}
+
+impl std::fmt::Display for GhRateLimitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "GitHub rate limit hit for {}", self.endpoint)?;
+        if let Some(retry_after) = self.retry_after_seconds {
+            write!(f, " (resets in {retry_after}s)")?;
+        }
+        write!(f, ": {}", self.stderr)
+    }
+}
+
+impl std::error::Error for GhRateLimitError {}
+
+#[cfg(test)]
+mod tests {
+    use super::GhRateLimitError;
+    use crate::domain::failure::{
+        FAILURE_CONTEXT_RETRY_AFTER_SECONDS, FAILURE_CONTEXT_STATUS_CODE,
+    };
+
+    #[test]
+    fn rate_limit_context_omits_unknown_fields() {
+        let err = GhRateLimitError {
+            endpoint: "repos/acme/api/pulls".to_string(),
+            status_code: Some(403),
+            retry_after_seconds: None,
+            stderr: "API rate limit exceeded".to_string(),
+        };
+
+        let context = err.context();
+        assert_eq!(context[FAILURE_CONTEXT_STATUS_CODE], "403");
+        assert!(!context.contains_key(FAILURE_CONTEXT_RETRY_AFTER_SECONDS));
+    }
+}
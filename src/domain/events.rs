@@ -1,4 +1,8 @@
-use std::fmt::{Display, Formatter};
+use std::{
+    borrow::Cow,
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -12,10 +16,36 @@ pub enum EventKind {
     PrReviewCommentCreated,
     PrReviewRequested,
     PrReviewSubmitted,
+    PrReviewApproved,
+    PrReviewChangesRequested,
+    PrReviewDismissed,
     PrMerged,
+    PrMilestoneAdded,
+    IssueMilestoneAdded,
+    ReviewThreadResolved,
+    MyPrApproved,
+    MyPrChecksPassed,
 }
 
 impl EventKind {
+    pub const ALL: [EventKind; 15] = [
+        Self::PrCreated,
+        Self::IssueCreated,
+        Self::IssueCommentCreated,
+        Self::PrReviewCommentCreated,
+        Self::PrReviewRequested,
+        Self::PrReviewSubmitted,
+        Self::PrReviewApproved,
+        Self::PrReviewChangesRequested,
+        Self::PrReviewDismissed,
+        Self::PrMerged,
+        Self::PrMilestoneAdded,
+        Self::IssueMilestoneAdded,
+        Self::ReviewThreadResolved,
+        Self::MyPrApproved,
+        Self::MyPrChecksPassed,
+    ];
+
     pub fn as_str(&self) -> &'static str {
         match self {
             Self::PrCreated => "pr_created",
@@ -24,7 +54,77 @@ impl EventKind {
             Self::PrReviewCommentCreated => "pr_review_comment_created",
             Self::PrReviewRequested => "pr_review_requested",
             Self::PrReviewSubmitted => "pr_review_submitted",
+            Self::PrReviewApproved => "pr_review_approved",
+            Self::PrReviewChangesRequested => "pr_review_changes_requested",
+            Self::PrReviewDismissed => "pr_review_dismissed",
             Self::PrMerged => "pr_merged",
+            Self::PrMilestoneAdded => "pr_milestone_added",
+            Self::IssueMilestoneAdded => "issue_milestone_added",
+            Self::ReviewThreadResolved => "review_thread_resolved",
+            Self::MyPrApproved => "my_pr_approved",
+            Self::MyPrChecksPassed => "my_pr_checks_passed",
+        }
+    }
+
+    /// Merge-readiness changes of the viewer's own pull requests are sent on their own
+    /// instead of being folded into a digest.
+    pub fn default_urgency(&self) -> Urgency {
+        match self {
+            Self::MyPrApproved | Self::MyPrChecksPassed => Urgency::High,
+            _ => Urgency::Normal,
+        }
+    }
+
+    pub fn is_milestone(&self) -> bool {
+        matches!(self, Self::PrMilestoneAdded | Self::IssueMilestoneAdded)
+    }
+
+    /// Review state carried by a review event; `PrReviewSubmitted` is a plain comment review.
+    pub fn review_state(&self) -> Option<ReviewState> {
+        match self {
+            Self::PrReviewSubmitted => Some(ReviewState::Commented),
+            Self::PrReviewApproved => Some(ReviewState::Approved),
+            Self::PrReviewChangesRequested => Some(ReviewState::ChangesRequested),
+            Self::PrReviewDismissed => Some(ReviewState::Dismissed),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Urgency {
+    Normal,
+    High,
+}
+
+/// State of a submitted pull request review, as reported by `pulls/{number}/reviews`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewState {
+    Approved,
+    ChangesRequested,
+    Commented,
+    Dismissed,
+}
+
+impl ReviewState {
+    /// Parses the upper-case `state` field of the GitHub reviews API. `PENDING` has no state.
+    pub fn from_api(state: &str) -> Option<Self> {
+        match state {
+            "APPROVED" => Some(Self::Approved),
+            "CHANGES_REQUESTED" => Some(Self::ChangesRequested),
+            "COMMENTED" => Some(Self::Commented),
+            "DISMISSED" => Some(Self::Dismissed),
+            _ => None,
+        }
+    }
+
+    pub fn event_kind(self) -> EventKind {
+        match self {
+            Self::Approved => EventKind::PrReviewApproved,
+            Self::ChangesRequested => EventKind::PrReviewChangesRequested,
+            Self::Commented => EventKind::PrReviewSubmitted,
+            Self::Dismissed => EventKind::PrReviewDismissed,
         }
     }
 }
@@ -35,6 +135,40 @@ impl Display for EventKind {
     }
 }
 
+impl FromStr for EventKind {
+    type Err = ParseEventKindError;
+
+    /// Parses the snake_case name used in config files and the state db.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == value)
+            .ok_or_else(|| ParseEventKindError {
+                value: value.to_string(),
+            })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseEventKindError {
+    pub value: String,
+}
+
+impl Display for ParseEventKindError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown event kind `{}` (expected one of: ", self.value)?;
+        for (index, kind) in EventKind::ALL.iter().enumerate() {
+            if index > 0 {
+                f.write_str(", ")?;
+            }
+            f.write_str(kind.as_str())?;
+        }
+        f.write_str(")")
+    }
+}
+
+impl std::error::Error for ParseEventKindError {}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct WatchEvent {
     pub event_id: String,
@@ -49,22 +183,127 @@ pub struct WatchEvent {
     pub subject_author: Option<String>,
     #[serde(default)]
     pub requested_reviewer: Option<String>,
+    /// Lowercased `@login` mentions, see `domain::mentions::extract_mentions`.
     #[serde(default)]
     pub mentions: Vec<String>,
+    /// Lowercased `@org/team` mentions.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub team_mentions: Vec<String>,
+    /// Issue or pull request number; comment and review events carry their parent's.
+    #[serde(default)]
+    pub subject_number: Option<i64>,
+    /// Issue or pull request page; `url` is the most specific link, e.g. a comment anchor.
+    #[serde(default)]
+    pub subject_url: Option<String>,
+    /// `[[accounts]]` entry whose client fetched the event; `None` for the default gh login.
+    #[serde(default)]
+    pub account: Option<String>,
+    /// Markdown of a comment or review, summarized into notification excerpts.
+    /// Stored up to `MAX_STORED_BODY_BYTES`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
 }
 
+/// Longest `WatchEvent::body` kept in the state db; longer ones are cut to keep it small.
+pub const MAX_STORED_BODY_BYTES: usize = 16 * 1024;
+
+/// Format of `WatchEvent::event_key`; bump it whenever the format changes so stored
+/// events are rekeyed when the state db is next opened.
+pub const EVENT_KEY_VERSION: i64 = 1;
+
 impl WatchEvent {
+    /// The event as stored: `body` cut to `MAX_STORED_BODY_BYTES` on a char boundary.
+    pub fn for_storage(&self) -> Cow<'_, Self> {
+        match self.body.as_deref() {
+            Some(body) if body.len() > MAX_STORED_BODY_BYTES => {
+                let mut end = MAX_STORED_BODY_BYTES;
+                while !body.is_char_boundary(end) {
+                    end -= 1;
+                }
+                Cow::Owned(Self {
+                    body: Some(body[..end].to_string()),
+                    ..self.clone()
+                })
+            }
+            _ => Cow::Borrowed(self),
+        }
+    }
+
     pub fn event_key(&self) -> String {
         format!("{}:{}:{}", self.repo, self.kind, self.source_item_id)
     }
+
+    /// Item id and milestone title of a milestone event, whose `source_item_id` is `<item_id>:<title>`.
+    pub fn milestone_assignment(&self) -> Option<(&str, &str)> {
+        if !self.kind.is_milestone() {
+            return None;
+        }
+        self.source_item_id.split_once(':')
+    }
+
+    /// Parent issue or pull request page, falling back to `url` for events stored before
+    /// `subject_url` existed.
+    pub fn subject_url_or_url(&self) -> &str {
+        self.subject_url.as_deref().unwrap_or(&self.url)
+    }
+
+    /// `subject_number`, or the number in an `/issues/<n>` or `/pull/<n>` URL for events
+    /// stored before the field existed.
+    pub fn display_number(&self) -> Option<i64> {
+        self.subject_number.or_else(|| {
+            let path = self.url.split(['#', '?']).next().unwrap_or(&self.url);
+            let mut segments = path.split('/');
+            segments.find(|segment| matches!(*segment, "issues" | "pull"))?;
+            segments.next()?.parse().ok()
+        })
+    }
+
+    /// Number of the pull request the event is about; `None` for issue events.
+    pub fn pull_request_number(&self) -> Option<i64> {
+        self.subject_url_or_url()
+            .contains("/pull/")
+            .then(|| self.display_number())
+            .flatten()
+    }
+}
+
+pub fn event_matches_milestone_filter(event: &WatchEvent, milestone_names: &[String]) -> bool {
+    if milestone_names.is_empty() {
+        return true;
+    }
+    match event.milestone_assignment() {
+        Some((_, title)) => milestone_names.iter().any(|name| name == title),
+        None => true,
+    }
+}
+
+/// Review events pass when `review_states` is empty or lists their state; other kinds always pass.
+pub fn event_matches_review_state_filter(
+    event: &WatchEvent,
+    review_states: &[ReviewState],
+) -> bool {
+    if review_states.is_empty() {
+        return true;
+    }
+    match event.kind.review_state() {
+        Some(state) => review_states.contains(&state),
+        None => true,
+    }
 }
 
+/// `ignore_self` drops the viewer's own events once `viewer_login` is known; it combines
+/// with `only_involving_me` to keep what others do on the viewer's work. Under
+/// `only_involving_me` a mention of one of `viewer_teams` (`org/team`) involves the viewer.
+#[allow(clippy::too_many_arguments)]
 pub fn event_matches_notification_filters(
     event: &WatchEvent,
     allowed_event_kinds: &[EventKind],
     ignore_actors: &[String],
     only_involving_me: bool,
+    ignore_self: bool,
     viewer_login: Option<&str>,
+    viewer_teams: &[String],
+    team_members: &[String],
 ) -> bool {
     let kind_allowed = allowed_event_kinds.is_empty()
         || allowed_event_kinds.iter().any(|kind| kind == &event.kind);
@@ -72,7 +311,16 @@ pub fn event_matches_notification_filters(
         return false;
     }
 
-    if ignore_actors.iter().any(|actor| actor == &event.actor) {
+    if ignore_actors
+        .iter()
+        .any(|actor| actor.eq_ignore_ascii_case(&event.actor))
+    {
+        return false;
+    }
+
+    if ignore_self
+        && viewer_login.is_some_and(|viewer_login| event.actor.eq_ignore_ascii_case(viewer_login))
+    {
         return false;
     }
 
@@ -80,10 +328,30 @@ pub fn event_matches_notification_filters(
         return true;
     }
 
-    let Some(viewer_login) = viewer_login else {
-        return false;
-    };
-    event_involves_viewer(event, viewer_login)
+    viewer_login.is_some_and(|viewer_login| event_involves_viewer(event, viewer_login))
+        || event_mentions_viewer_team(event, viewer_teams)
+        || team_members
+            .iter()
+            .any(|member| event_involves_team_member(event, member))
+}
+
+fn event_mentions_viewer_team(event: &WatchEvent, viewer_teams: &[String]) -> bool {
+    event.team_mentions.iter().any(|mention| {
+        viewer_teams
+            .iter()
+            .any(|team| team.eq_ignore_ascii_case(mention))
+    })
+}
+
+fn event_involves_team_member(event: &WatchEvent, member: &str) -> bool {
+    [
+        Some(event.actor.as_str()),
+        event.subject_author.as_deref(),
+        event.requested_reviewer.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .any(|login| login.eq_ignore_ascii_case(member))
 }
 
 fn event_involves_viewer(event: &WatchEvent, viewer_login: &str) -> bool {
@@ -1,20 +1,35 @@
+use std::collections::{BTreeMap, HashMap};
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::redact::redact_secrets;
+
 pub const FAILURE_KIND_REPO_POLL: &str = "repo_poll";
 pub const FAILURE_KIND_NOTIFICATION: &str = "notification";
 pub const FAILURE_KIND_POLL_LOOP: &str = "poll_loop";
 pub const FAILURE_KIND_INPUT_STREAM: &str = "input_stream";
 
+/// Well-known `FailureRecord::context` keys.
+pub const FAILURE_CONTEXT_ENDPOINT: &str = "endpoint";
+pub const FAILURE_CONTEXT_STATUS_CODE: &str = "status_code";
+pub const FAILURE_CONTEXT_ATTEMPT_COUNT: &str = "attempt_count";
+pub const FAILURE_CONTEXT_RETRY_AFTER_SECONDS: &str = "retry_after_seconds";
+pub const FAILURE_CONTEXT_RENAMED_TO: &str = "renamed_to";
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FailureRecord {
     pub kind: String,
     pub repo: String,
     pub failed_at: DateTime<Utc>,
     pub message: String,
+    /// Structured metadata for monitoring, e.g. `status_code` or `retry_after_seconds`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub context: HashMap<String, String>,
 }
 
 impl FailureRecord {
+    /// `message` is stored with credentials masked, as are context values.
     pub fn new(
         kind: impl Into<String>,
         repo: impl Into<String>,
@@ -25,7 +40,130 @@ impl FailureRecord {
             kind: kind.into(),
             repo: repo.into(),
             failed_at,
-            message: message.into(),
+            message: redact_secrets(&message.into()),
+            context: HashMap::new(),
+        }
+    }
+
+    pub fn with_context(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.context
+            .insert(key.into(), redact_secrets(&value.into()));
+        self
+    }
+}
+
+/// Consecutive failed polls per repository. Repositories without a running streak are
+/// not stored, so an empty value means every repository's last poll succeeded.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FailureStreaks(BTreeMap<String, u32>);
+
+impl FailureStreaks {
+    /// Streaks after one more poll, plus the previously failing repos that succeeded in it.
+    /// Repos in neither list (e.g. disabled since) keep their streak unchanged.
+    pub fn advance<'a>(
+        &self,
+        succeeded: impl IntoIterator<Item = &'a str>,
+        failed: impl IntoIterator<Item = &'a str>,
+    ) -> (Self, Vec<String>) {
+        let mut streaks = self.0.clone();
+        let mut recovered = Vec::new();
+        for repo in succeeded {
+            if streaks.remove(repo).is_some() {
+                recovered.push(repo.to_string());
+            }
+        }
+        for repo in failed {
+            *streaks.entry(repo.to_string()).or_insert(0) += 1;
+        }
+        (Self(streaks), recovered)
+    }
+
+    pub fn get(&self, repo: &str) -> u32 {
+        self.0.get(repo).copied().unwrap_or(0)
+    }
+
+    /// Longest streak; ties go to the alphabetically first repo.
+    pub fn worst(&self) -> Option<(&str, u32)> {
+        self.0
+            .iter()
+            .map(|(repo, streak)| (repo.as_str(), *streak))
+            .fold(None, |worst, candidate| match worst {
+                Some((_, best)) if best >= candidate.1 => worst,
+                _ => Some(candidate),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::{FailureRecord, FailureStreaks, FAILURE_KIND_REPO_POLL};
+
+    #[test]
+    fn with_context_accumulates_and_overwrites_keys() {
+        let at = Utc.with_ymd_and_hms(2025, 1, 20, 12, 0, 0).unwrap();
+        let record = FailureRecord::new(FAILURE_KIND_REPO_POLL, "acme/api", at, "boom")
+            .with_context("attempt_count", "1")
+            .with_context("endpoint", "repos/acme/api/pulls")
+            .with_context("attempt_count", "3");
+
+        assert_eq!(record.context.len(), 2);
+        assert_eq!(record.context["attempt_count"], "3");
+        assert_eq!(record.context["endpoint"], "repos/acme/api/pulls");
+        assert!(
+            FailureRecord::new(FAILURE_KIND_REPO_POLL, "acme/api", at, "boom")
+                .context
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn messages_and_context_are_stored_masked() {
+        let at = Utc.with_ymd_and_hms(2025, 1, 20, 12, 0, 0).unwrap();
+        let record = FailureRecord::new(FAILURE_KIND_REPO_POLL, "acme/api", at, "GH_TOKEN=ghp_x")
+            .with_context("endpoint", "https://hooks.example.com/?secret=s");
+
+        assert_eq!(record.message, "GH_TOKEN=***");
+        assert_eq!(
+            record.context["endpoint"],
+            "https://hooks.example.com/?secret=***"
+        );
+    }
+
+    #[test]
+    fn failure_streaks_count_consecutive_failures_per_repo() {
+        let mut streaks = FailureStreaks::default();
+        for _ in 0..6 {
+            (streaks, _) = streaks.advance(["acme/web"], ["acme/api"]);
         }
+        (streaks, _) = streaks.advance([], ["acme/web"]);
+
+        assert_eq!(streaks.get("acme/api"), 6);
+        assert_eq!(streaks.get("acme/web"), 1);
+        assert_eq!(streaks.get("acme/docs"), 0);
+        assert_eq!(streaks.worst(), Some(("acme/api", 6)));
+    }
+
+    #[test]
+    fn failure_streaks_reset_on_success_and_report_recoveries() {
+        let (streaks, recovered) = FailureStreaks::default().advance([], ["acme/api", "acme/web"]);
+        assert!(recovered.is_empty());
+
+        let (streaks, recovered) = streaks.advance(["acme/api", "acme/docs"], []);
+        assert_eq!(recovered, vec!["acme/api".to_string()]);
+        assert_eq!(streaks.get("acme/api"), 0);
+        assert_eq!(streaks.worst(), Some(("acme/web", 1)));
+
+        let (streaks, recovered) = streaks.advance([], []);
+        assert!(recovered.is_empty());
+        assert_eq!(streaks.get("acme/web"), 1);
+    }
+
+    #[test]
+    fn failure_streaks_worst_breaks_ties_by_repo_name() {
+        let (streaks, _) = FailureStreaks::default().advance([], ["acme/web", "acme/api"]);
+        assert_eq!(streaks.worst(), Some(("acme/api", 1)));
+        assert_eq!(FailureStreaks::default().worst(), None);
     }
 }
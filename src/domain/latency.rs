@@ -0,0 +1,146 @@
+use chrono::Duration;
+
+/// Notification waits longer than this after an event was logged point at the notifier.
+pub const BACKLOG_THRESHOLD_SECONDS: i64 = 60;
+
+/// Median and 95th percentile of a set of latencies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencySummary {
+    pub samples: usize,
+    pub p50: Duration,
+    pub p95: Duration,
+}
+
+impl LatencySummary {
+    /// `None` without samples.
+    pub fn from_samples(mut samples: Vec<Duration>) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort();
+        Some(Self {
+            samples: samples.len(),
+            p50: percentile(&samples, 50),
+            p95: percentile(&samples, 95),
+        })
+    }
+}
+
+/// Nearest-rank percentile of the ascending, non-empty `sorted`.
+pub fn percentile(sorted: &[Duration], percent: u8) -> Duration {
+    let rank = (usize::from(percent) * sorted.len()).div_ceil(100);
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Likely causes of slow notifications given the time from creation on GitHub
+/// (`since_created`) and from logging (`since_observed`) to the notification.
+pub fn latency_hints(
+    since_created: &LatencySummary,
+    since_observed: &LatencySummary,
+    interval_seconds: u64,
+    has_notification_schedule: bool,
+) -> Vec<String> {
+    let mut hints = Vec::new();
+    let interval = Duration::seconds(i64::try_from(interval_seconds).unwrap_or(i64::MAX));
+    if since_created.p95 > interval * 2 {
+        hints.push(format!(
+            "p95 from creation is {}, over twice interval_seconds = {interval_seconds}; a shorter interval notifies sooner",
+            format_latency(since_created.p95)
+        ));
+    }
+    if since_observed.p95 > Duration::seconds(BACKLOG_THRESHOLD_SECONDS) {
+        hints.push(format!(
+            "p95 from logging is {}; the notification backend is slow or notifications queue up behind each other",
+            format_latency(since_observed.p95)
+        ));
+    }
+    if has_notification_schedule {
+        hints.push(
+            "[notification_schedule] holds notifications outside its hours; held ones are timed when they were queued, so quiet hours do not show here"
+                .to_string(),
+        );
+    }
+    hints
+}
+
+/// `850ms`, `42s`, `3m05s` or `2h10m`.
+pub fn format_latency(latency: Duration) -> String {
+    let seconds = latency.num_seconds();
+    if seconds < 1 {
+        return format!("{}ms", latency.num_milliseconds().max(0));
+    }
+    match seconds {
+        0..60 => format!("{seconds}s"),
+        60..3600 => format!("{}m{:02}s", seconds / 60, seconds % 60),
+        _ => format!("{}h{:02}m", seconds / 3600, seconds % 3600 / 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::{format_latency, latency_hints, percentile, LatencySummary};
+
+    fn seconds(values: &[i64]) -> Vec<Duration> {
+        values.iter().copied().map(Duration::seconds).collect()
+    }
+
+    fn summary(p50: i64, p95: i64) -> LatencySummary {
+        LatencySummary {
+            samples: 10,
+            p50: Duration::seconds(p50),
+            p95: Duration::seconds(p95),
+        }
+    }
+
+    #[test]
+    fn percentiles_use_the_nearest_rank() {
+        let sorted = seconds(&(1..=20).collect::<Vec<_>>());
+
+        assert_eq!(percentile(&sorted, 50), Duration::seconds(10));
+        assert_eq!(percentile(&sorted, 95), Duration::seconds(19));
+        assert_eq!(percentile(&sorted, 100), Duration::seconds(20));
+        assert_eq!(percentile(&sorted, 0), Duration::seconds(1));
+    }
+
+    #[test]
+    fn a_single_sample_is_every_percentile() {
+        let summary = LatencySummary::from_samples(seconds(&[7])).unwrap();
+
+        assert_eq!(summary.samples, 1);
+        assert_eq!(summary.p50, Duration::seconds(7));
+        assert_eq!(summary.p95, Duration::seconds(7));
+    }
+
+    #[test]
+    fn samples_are_sorted_before_ranking() {
+        let summary = LatencySummary::from_samples(seconds(&[30, 10, 20, 400])).unwrap();
+
+        assert_eq!(summary.p50, Duration::seconds(20));
+        assert_eq!(summary.p95, Duration::seconds(400));
+        assert_eq!(LatencySummary::from_samples(Vec::new()), None);
+    }
+
+    #[test]
+    fn hints_name_the_interval_and_backlog_past_their_thresholds() {
+        assert!(latency_hints(&summary(90, 600), &summary(1, 2), 300, false).is_empty());
+
+        let hints = latency_hints(&summary(400, 601), &summary(1, 61), 300, false);
+        assert_eq!(hints.len(), 2);
+        assert!(hints[0].contains("interval_seconds = 300"), "{}", hints[0]);
+        assert!(hints[0].contains("10m01s"), "{}", hints[0]);
+        assert!(hints[1].contains("1m01s"), "{}", hints[1]);
+
+        let hints = latency_hints(&summary(1, 2), &summary(1, 2), 300, true);
+        assert!(hints[0].contains("[notification_schedule]"));
+    }
+
+    #[test]
+    fn latencies_are_formatted_by_magnitude() {
+        assert_eq!(format_latency(Duration::milliseconds(850)), "850ms");
+        assert_eq!(format_latency(Duration::seconds(42)), "42s");
+        assert_eq!(format_latency(Duration::seconds(185)), "3m05s");
+        assert_eq!(format_latency(Duration::seconds(7800)), "2h10m");
+    }
+}
@@ -0,0 +1,171 @@
+//! Plain-text excerpts of comment and review markdown for notifications, which show text
+//! as-is: code blocks, images, HTML comments (issue templates) and list markup are dropped.
+
+/// `body` as one line of prose: fenced code, images and HTML comments removed, list,
+/// checklist, quote and heading markers stripped, links reduced to their text and whitespace
+/// collapsed. Cut to `max_chars` with `...`; empty when nothing readable is left.
+pub fn summarize_markdown(body: &str, max_chars: usize) -> String {
+    let mut words = Vec::new();
+    let mut fence: Option<(char, usize)> = None;
+    for line in strip_html_comments(body).lines() {
+        let trimmed = line.trim();
+        if let Some(open) = fence {
+            if closes_fence(trimmed, open) {
+                fence = None;
+            }
+            continue;
+        }
+        if let Some(open) = opens_fence(trimmed) {
+            fence = Some(open);
+            continue;
+        }
+        let text = strip_inline(strip_line_markers(trimmed));
+        words.extend(text.split_whitespace().map(str::to_string));
+    }
+    cap_chars(&words.join(" "), max_chars)
+}
+
+fn strip_html_comments(body: &str) -> String {
+    let mut text = String::with_capacity(body.len());
+    let mut rest = body;
+    while let Some(start) = rest.find("<!--") {
+        text.push_str(&rest[..start]);
+        match rest[start + 4..].find("-->") {
+            Some(end) => rest = &rest[start + 4 + end + 3..],
+            None => return text,
+        }
+    }
+    text.push_str(rest);
+    text
+}
+
+/// A run of at least three backticks or tildes; the block ends at a run of the same
+/// character that is at least as long, so shorter fences inside it are content.
+pub(super) fn opens_fence(line: &str) -> Option<(char, usize)> {
+    let marker = line.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+    let len = line.chars().take_while(|c| *c == marker).count();
+    (len >= 3).then_some((marker, len))
+}
+
+pub(super) fn closes_fence(line: &str, (marker, len): (char, usize)) -> bool {
+    let run = line.chars().take_while(|c| *c == marker).count();
+    run >= len && line[run * marker.len_utf8()..].trim().is_empty()
+}
+
+/// Quote, heading, list and checklist markers at the start of a line.
+fn strip_line_markers(mut line: &str) -> &str {
+    loop {
+        let stripped = line
+            .strip_prefix('>')
+            .or_else(|| {
+                let hashes = line.chars().take_while(|c| *c == '#').count();
+                (hashes > 0 && line[hashes..].starts_with(' ')).then(|| &line[hashes..])
+            })
+            .or_else(|| {
+                ["- ", "* ", "+ "]
+                    .iter()
+                    .find_map(|marker| line.strip_prefix(marker))
+            })
+            .or_else(|| {
+                let digits = line.chars().take_while(char::is_ascii_digit).count();
+                (digits > 0)
+                    .then(|| &line[digits..])
+                    .and_then(|rest| rest.strip_prefix(". ").or_else(|| rest.strip_prefix(") ")))
+            })
+            .or_else(|| {
+                ["[ ] ", "[x] ", "[X] "]
+                    .iter()
+                    .find_map(|marker| line.strip_prefix(marker))
+            });
+        match stripped {
+            Some(rest) => line = rest.trim_start(),
+            None => return line,
+        }
+    }
+}
+
+/// Images dropped, `[text](url)` reduced to `text`, and `**`, `__` and backticks removed.
+fn strip_inline(line: &str) -> String {
+    let mut text = String::with_capacity(line.len());
+    let mut rest = line;
+    while !rest.is_empty() {
+        let is_image = rest.starts_with("![");
+        let label_start = if is_image { 2 } else { 1 };
+        if is_image || rest.starts_with('[') {
+            if let Some((label, after)) = split_link(&rest[label_start..]) {
+                if !is_image {
+                    text.push_str(label);
+                }
+                rest = after;
+                continue;
+            }
+        }
+        let next = rest.chars().next().expect("rest is not empty");
+        text.push(next);
+        rest = &rest[next.len_utf8()..];
+    }
+    text.replace("**", "").replace("__", "").replace('`', "")
+}
+
+/// `label](target) rest` -> (`label`, ` rest`).
+fn split_link(text: &str) -> Option<(&str, &str)> {
+    let label_end = text.find("](")?;
+    let target_end = text[label_end + 2..].find(')')?;
+    Some((&text[..label_end], &text[label_end + 2 + target_end + 1..]))
+}
+
+fn cap_chars(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut capped = text
+        .chars()
+        .take(max_chars.saturating_sub(3))
+        .collect::<String>()
+        .trim_end()
+        .to_string();
+    capped.push_str("...");
+    capped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::summarize_markdown;
+
+    #[test]
+    fn code_blocks_are_dropped_including_nested_fences() {
+        let body = "Repro below:\n\n````md\n```rust\nfn main() {}\n```\n````\n\nFails on CI.";
+        assert_eq!(summarize_markdown(body, 200), "Repro below: Fails on CI.");
+        assert_eq!(
+            summarize_markdown("Before\n~~~\nunterminated\ncode", 200),
+            "Before"
+        );
+    }
+
+    #[test]
+    fn checklists_lists_quotes_and_headings_keep_only_their_text() {
+        let body = "## Checklist\n- [x] Tests added\n- [ ] Docs **updated**\n1. See [the guide](https://example.com/guide)\n> quoted `code`";
+        assert_eq!(
+            summarize_markdown(body, 200),
+            "Checklist Tests added Docs updated See the guide quoted code"
+        );
+    }
+
+    #[test]
+    fn images_and_html_comments_leave_nothing_behind() {
+        assert_eq!(
+            summarize_markdown("![screenshot](https://example.com/a.png)", 200),
+            ""
+        );
+        assert_eq!(
+            summarize_markdown("<!-- Describe the change -->\nLGTM\n<!-- trailing", 200),
+            "LGTM"
+        );
+    }
+
+    #[test]
+    fn long_text_is_capped_with_an_ellipsis() {
+        assert_eq!(summarize_markdown("one two three four", 10), "one two...");
+        assert_eq!(summarize_markdown("short", 10), "short");
+    }
+}
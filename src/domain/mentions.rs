@@ -0,0 +1,141 @@
+//! `@login` and `@org/team` mentions in titles and comment bodies, matched the way GitHub
+//! links them so `filters.only_involving_me` does not fire on emails or code.
+
+use super::markdown::{closes_fence, opens_fence};
+
+/// Longest GitHub login.
+const MAX_LOGIN_CHARS: usize = 39;
+
+/// Lowercased mentions in order of first appearance, without repeats.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Mentions {
+    pub users: Vec<String>,
+    /// `org/team`.
+    pub teams: Vec<String>,
+}
+
+/// Mentions outside fenced code blocks and inline code spans. An `@` right after a letter or
+/// digit is part of a word, e.g. an email address, and starts no mention.
+pub fn extract_mentions(text: &str) -> Mentions {
+    let mut mentions = Mentions::default();
+    let mut fence: Option<(char, usize)> = None;
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if let Some(open) = fence {
+            if closes_fence(trimmed, open) {
+                fence = None;
+            }
+            continue;
+        }
+        if let Some(open) = opens_fence(trimmed) {
+            fence = Some(open);
+            continue;
+        }
+        for prose in outside_code_spans(line) {
+            collect_mentions(prose, &mut mentions);
+        }
+    }
+    mentions
+}
+
+/// Parts of `line` between backtick code spans; an unmatched last backtick opens no span.
+fn outside_code_spans(line: &str) -> impl Iterator<Item = &str> {
+    let parts = line.split('`').collect::<Vec<_>>();
+    let unmatched_tail = parts.len() % 2 == 0;
+    let last = parts.len() - 1;
+    parts
+        .into_iter()
+        .enumerate()
+        .filter(move |(idx, _)| idx % 2 == 0 || (unmatched_tail && *idx == last))
+        .map(|(_, part)| part)
+}
+
+fn collect_mentions(text: &str, mentions: &mut Mentions) {
+    let mut previous: Option<char> = None;
+    for (idx, ch) in text.char_indices() {
+        let after_word = previous.is_some_and(char::is_alphanumeric);
+        previous = Some(ch);
+        if ch != '@' || after_word {
+            continue;
+        }
+        let rest = &text[idx + 1..];
+        let login = word_prefix(rest);
+        if !is_valid_login(login) {
+            continue;
+        }
+        let after_login = &rest[login.len()..];
+        if let Some(team_rest) = after_login.strip_prefix('/') {
+            let team = word_prefix(team_rest);
+            if !team.is_empty() {
+                push_unique(&mut mentions.teams, format!("{login}/{team}"));
+            }
+            continue;
+        }
+        push_unique(&mut mentions.users, login.to_string());
+    }
+}
+
+/// The run of login characters at the start of `text`; underscores are included so that a
+/// word like `@foo_bar`, which GitHub does not link, fails validation instead of matching
+/// `foo`.
+fn word_prefix(text: &str) -> &str {
+    let len = text
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+        .unwrap_or(text.len());
+    &text[..len]
+}
+
+/// GitHub login rules: 1-39 ASCII letters, digits or single hyphens, not at either end.
+fn is_valid_login(login: &str) -> bool {
+    !login.is_empty()
+        && login.len() <= MAX_LOGIN_CHARS
+        && login.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        && !login.starts_with('-')
+        && !login.ends_with('-')
+        && !login.contains("--")
+}
+
+fn push_unique(list: &mut Vec<String>, mention: String) {
+    let mention = mention.to_ascii_lowercase();
+    if !list.contains(&mention) {
+        list.push(mention);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::extract_mentions;
+
+    fn users(text: &str) -> Vec<String> {
+        extract_mentions(text).users
+    }
+
+    #[test]
+    fn emails_and_code_are_not_mentions() {
+        assert_eq!(users("mail foo@bar.com or ping @Alice"), vec!["alice"]);
+        assert_eq!(
+            users("run `npm i @types/node` then\n```\n@bob in code\n```\n@carol"),
+            vec!["carol"]
+        );
+        assert_eq!(users("a lone ` then @dave"), vec!["dave"]);
+    }
+
+    #[test]
+    fn logins_follow_github_rules() {
+        let long = "a".repeat(40);
+        assert_eq!(
+            users(&format!(
+                "@ok-name @-lead @trail- @dou--ble @under_score @{long} @{}",
+                &long[1..]
+            )),
+            vec!["ok-name".to_string(), long[1..].to_string()]
+        );
+    }
+
+    #[test]
+    fn mentions_are_lowercased_and_deduplicated_in_order() {
+        let mentions = extract_mentions("@Bob @alice @bob, @Acme/Core-Team and @acme/core-team");
+        assert_eq!(mentions.users, vec!["bob", "alice"]);
+        assert_eq!(mentions.teams, vec!["acme/core-team"]);
+    }
+}
@@ -1,3 +1,11 @@
+pub mod command_line;
 pub mod decision;
+pub mod error;
 pub mod events;
 pub mod failure;
+pub mod latency;
+pub mod markdown;
+pub mod mentions;
+pub mod path_filter;
+pub mod redact;
+pub mod subject_state;
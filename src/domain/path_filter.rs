@@ -0,0 +1,76 @@
+/// Whether `path` matches the glob `pattern`. Both are `/`-separated and anchored at the
+/// repository root; `*` and `?` stay within one path segment, and a `**` segment matches
+/// any number of segments, so `src/**` covers everything under `src`.
+pub fn glob_matches(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.split('/').collect::<Vec<_>>();
+    let path = path.split('/').collect::<Vec<_>>();
+    segments_match(&pattern, &path)
+}
+
+/// Whether any of `paths` matches any of `patterns`.
+pub fn any_path_matches<'a>(patterns: &[String], paths: impl IntoIterator<Item = &'a str>) -> bool {
+    paths
+        .into_iter()
+        .any(|path| patterns.iter().any(|pattern| glob_matches(pattern, path)))
+}
+
+fn segments_match(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| segments_match(rest, &path[skip..])),
+        Some((segment, rest)) => path.split_first().is_some_and(|(name, path_rest)| {
+            segment_matches(segment.as_bytes(), name.as_bytes()) && segments_match(rest, path_rest)
+        }),
+    }
+}
+
+fn segment_matches(pattern: &[u8], name: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some((b'*', rest)) => (0..=name.len()).any(|skip| segment_matches(rest, &name[skip..])),
+        Some((b'?', rest)) => !name.is_empty() && segment_matches(rest, &name[1..]),
+        Some((byte, rest)) => name
+            .split_first()
+            .is_some_and(|(first, name_rest)| first == byte && segment_matches(rest, name_rest)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{any_path_matches, glob_matches};
+
+    #[test]
+    fn literal_patterns_match_only_that_path() {
+        assert!(glob_matches("Cargo.toml", "Cargo.toml"));
+        assert!(!glob_matches("Cargo.toml", "crates/core/Cargo.toml"));
+        assert!(!glob_matches("src/lib.rs", "src/lib.rs.orig"));
+    }
+
+    #[test]
+    fn single_stars_and_question_marks_stay_within_a_segment() {
+        assert!(glob_matches("src/*.rs", "src/lib.rs"));
+        assert!(!glob_matches("src/*.rs", "src/cli/mod.rs"));
+        assert!(glob_matches("*/Cargo.toml", "core/Cargo.toml"));
+        assert!(glob_matches("docs/v?.md", "docs/v2.md"));
+        assert!(!glob_matches("docs/v?.md", "docs/v10.md"));
+    }
+
+    #[test]
+    fn double_stars_match_any_depth() {
+        assert!(glob_matches("src/**", "src/lib.rs"));
+        assert!(glob_matches("src/**", "src/cli/commands/once.rs"));
+        assert!(!glob_matches("src/**", "tests/src/lib.rs"));
+        assert!(glob_matches("**/*.md", "README.md"));
+        assert!(glob_matches("**/*.md", "docs/guide/setup.md"));
+        assert!(glob_matches("src/**/mod.rs", "src/mod.rs"));
+    }
+
+    #[test]
+    fn any_path_matches_needs_one_matching_pair() {
+        let patterns = vec!["src/**".to_string(), "Cargo.toml".to_string()];
+
+        assert!(any_path_matches(&patterns, ["README.md", "Cargo.toml"]));
+        assert!(!any_path_matches(&patterns, ["README.md", "docs/src/x.md"]));
+        assert!(!any_path_matches(&[], ["src/lib.rs"]));
+    }
+}
@@ -0,0 +1,66 @@
+use super::events::WatchEvent;
+
+/// Current state of the issue or pull request an event is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SubjectState {
+    Open,
+    Closed,
+    Merged,
+}
+
+impl SubjectState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Closed => "closed",
+            Self::Merged => "merged",
+        }
+    }
+
+    /// Accepts GraphQL's `OPEN`/`CLOSED`/`MERGED` as well as the lower-case names.
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.to_ascii_lowercase().as_str() {
+            "open" => Some(Self::Open),
+            "closed" => Some(Self::Closed),
+            "merged" => Some(Self::Merged),
+            _ => None,
+        }
+    }
+
+    /// Closed or merged: nothing left to act on.
+    pub fn is_resolved(self) -> bool {
+        matches!(self, Self::Closed | Self::Merged)
+    }
+}
+
+/// An issue or pull request, by repository and number.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SubjectRef {
+    pub repo: String,
+    pub number: i64,
+}
+
+impl SubjectRef {
+    /// `None` when the event's issue or pull request number is unknown.
+    pub fn of(event: &WatchEvent) -> Option<Self> {
+        event.display_number().map(|number| Self {
+            repo: event.repo.clone(),
+            number,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SubjectState;
+
+    #[test]
+    fn states_parse_graphql_and_lower_case_names() {
+        assert_eq!(SubjectState::parse("MERGED"), Some(SubjectState::Merged));
+        assert_eq!(SubjectState::parse("closed"), Some(SubjectState::Closed));
+        assert_eq!(SubjectState::parse("OPEN"), Some(SubjectState::Open));
+        assert_eq!(SubjectState::parse("DRAFT"), None);
+        assert!(SubjectState::Merged.is_resolved());
+        assert!(!SubjectState::Open.is_resolved());
+    }
+}
@@ -0,0 +1,272 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+
+pub const MAX_CONFIG_BACKUPS: usize = 5;
+
+const BACKUP_TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%S%3fZ";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigBackup {
+    pub timestamp: String,
+    pub path: PathBuf,
+}
+
+/// Writes `contents` to `path`, first copying any existing file to `<name>.bak.<timestamp>`.
+pub fn write_config_with_backup(
+    path: &Path,
+    contents: &str,
+    now: DateTime<Utc>,
+) -> Result<Option<ConfigBackup>> {
+    let backup = backup_config(path, now)?;
+    fs::write(path, contents)
+        .with_context(|| format!("failed to write config: {}", path.display()))?;
+    Ok(backup)
+}
+
+pub fn backup_config(path: &Path, now: DateTime<Utc>) -> Result<Option<ConfigBackup>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let timestamp = now.format(BACKUP_TIMESTAMP_FORMAT).to_string();
+    let backup_path = backup_path_for(path, &timestamp)?;
+    fs::copy(path, &backup_path).with_context(|| {
+        format!(
+            "failed to back up config: {} -> {}",
+            path.display(),
+            backup_path.display()
+        )
+    })?;
+    rotate_config_backups(path, MAX_CONFIG_BACKUPS)?;
+
+    Ok(Some(ConfigBackup {
+        timestamp,
+        path: backup_path,
+    }))
+}
+
+/// Backups of `path`, newest first.
+pub fn list_config_backups(path: &Path) -> Result<Vec<ConfigBackup>> {
+    let prefix = backup_prefix(path)?;
+    let dir = backup_dir(path);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut backups = Vec::new();
+    for entry in fs::read_dir(&dir)
+        .with_context(|| format!("failed to read config directory: {}", dir.display()))?
+    {
+        let entry = entry?;
+        let name = entry.file_name();
+        let Some(timestamp) = name.to_str().and_then(|name| name.strip_prefix(&prefix)) else {
+            continue;
+        };
+        if timestamp.is_empty() || !entry.file_type()?.is_file() {
+            continue;
+        }
+        backups.push(ConfigBackup {
+            timestamp: timestamp.to_string(),
+            path: entry.path(),
+        });
+    }
+    backups.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    Ok(backups)
+}
+
+pub fn rotate_config_backups(path: &Path, keep: usize) -> Result<()> {
+    for stale in list_config_backups(path)?.into_iter().skip(keep) {
+        fs::remove_file(&stale.path).with_context(|| {
+            format!(
+                "failed to remove old config backup: {}",
+                stale.path.display()
+            )
+        })?;
+    }
+    Ok(())
+}
+
+pub fn find_config_backup(path: &Path, timestamp: Option<&str>) -> Result<ConfigBackup> {
+    let backups = list_config_backups(path)?;
+    let found = match timestamp {
+        Some(timestamp) => backups
+            .into_iter()
+            .find(|backup| backup.timestamp == timestamp),
+        None => backups.into_iter().next(),
+    };
+    found.ok_or_else(|| match timestamp {
+        Some(timestamp) => anyhow!(
+            "no config backup for {timestamp}: {} (run `gh-watch config rollback` to list backups)",
+            path.display()
+        ),
+        None => anyhow!("no config backups found for {}", path.display()),
+    })
+}
+
+/// Restores `backup` to `path` once `validate` accepts its contents. The current file is
+/// backed up first, so a rollback can itself be rolled back.
+pub fn restore_config_backup<F>(
+    backup: &ConfigBackup,
+    path: &Path,
+    now: DateTime<Utc>,
+    validate: F,
+) -> Result<Option<ConfigBackup>>
+where
+    F: FnOnce(&str) -> Result<()>,
+{
+    let contents = fs::read_to_string(&backup.path)
+        .with_context(|| format!("failed to read config backup: {}", backup.path.display()))?;
+    validate(&contents).with_context(|| {
+        format!(
+            "config backup is not valid, not restoring: {}",
+            backup.path.display()
+        )
+    })?;
+
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("failed to create config directory: {}", parent.display())
+            })?;
+        }
+    }
+    write_config_with_backup(path, &contents, now)
+}
+
+fn backup_path_for(path: &Path, timestamp: &str) -> Result<PathBuf> {
+    Ok(backup_dir(path).join(format!("{}{timestamp}", backup_prefix(path)?)))
+}
+
+fn backup_dir(path: &Path) -> PathBuf {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+fn backup_prefix(path: &Path) -> Result<String> {
+    let name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| anyhow!("config path has no file name: {}", path.display()))?;
+    Ok(format!("{name}.bak."))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use anyhow::anyhow;
+    use chrono::{Duration, TimeZone, Utc};
+    use tempfile::tempdir;
+
+    use super::{
+        backup_config, find_config_backup, list_config_backups, restore_config_backup,
+        write_config_with_backup, MAX_CONFIG_BACKUPS,
+    };
+
+    #[test]
+    fn write_without_existing_file_creates_no_backup() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+
+        let backup = write_config_with_backup(&path, "a = 1\n", now).unwrap();
+
+        assert!(backup.is_none());
+        assert!(list_config_backups(&path).unwrap().is_empty());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "a = 1\n");
+    }
+
+    #[test]
+    fn backups_rotate_to_the_newest_limit() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "v = 0\n").unwrap();
+        fs::write(dir.path().join("other.toml.bak.20000101T000000000Z"), "").unwrap();
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+
+        for idx in 1..=MAX_CONFIG_BACKUPS + 2 {
+            let now = start + Duration::seconds(idx as i64);
+            write_config_with_backup(&path, &format!("v = {idx}\n"), now).unwrap();
+        }
+
+        let backups = list_config_backups(&path).unwrap();
+        assert_eq!(backups.len(), MAX_CONFIG_BACKUPS);
+        assert_eq!(backups[0].timestamp, "20250101T000007000Z");
+        assert_eq!(
+            fs::read_to_string(&backups[0].path).unwrap(),
+            format!("v = {}\n", MAX_CONFIG_BACKUPS + 1)
+        );
+        assert_eq!(
+            fs::read_to_string(&backups[MAX_CONFIG_BACKUPS - 1].path).unwrap(),
+            "v = 2\n"
+        );
+        assert!(dir
+            .path()
+            .join("other.toml.bak.20000101T000000000Z")
+            .exists());
+    }
+
+    #[test]
+    fn find_backup_defaults_to_newest_and_rejects_unknown_timestamp() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "v = 1\n").unwrap();
+        let first = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        backup_config(&path, first).unwrap();
+        backup_config(&path, first + Duration::minutes(1)).unwrap();
+
+        let newest = find_config_backup(&path, None).unwrap();
+        assert_eq!(newest.timestamp, "20250101T000100000Z");
+        let older = find_config_backup(&path, Some("20250101T000000000Z")).unwrap();
+        assert_eq!(older.timestamp, "20250101T000000000Z");
+        let err = find_config_backup(&path, Some("nope")).unwrap_err();
+        assert!(err.to_string().contains("no config backup for nope"));
+    }
+
+    #[test]
+    fn restore_into_missing_directory_creates_it() {
+        let dir = tempdir().unwrap();
+        let source = dir.path().join("config.toml");
+        fs::write(&source, "v = 1\n").unwrap();
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let backup = backup_config(&source, now).unwrap().unwrap();
+        let target = dir
+            .path()
+            .join("missing")
+            .join("nested")
+            .join("config.toml");
+
+        let previous = restore_config_backup(&backup, &target, now, |_| Ok(())).unwrap();
+
+        assert!(previous.is_none());
+        assert_eq!(fs::read_to_string(&target).unwrap(), "v = 1\n");
+    }
+
+    #[test]
+    fn restore_backs_up_current_file_and_skips_invalid_backups() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "v = 1\n").unwrap();
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let backup = backup_config(&path, now).unwrap().unwrap();
+        fs::write(&path, "v = 2\n").unwrap();
+
+        let err = restore_config_backup(&backup, &path, now, |_| Err(anyhow!("bad"))).unwrap_err();
+        assert!(err.to_string().contains("not restoring"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "v = 2\n");
+
+        let later = now + Duration::seconds(1);
+        let previous = restore_config_backup(&backup, &path, later, |_| Ok(()))
+            .unwrap()
+            .unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "v = 1\n");
+        assert_eq!(fs::read_to_string(previous.path).unwrap(), "v = 2\n");
+    }
+}
@@ -0,0 +1,53 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+
+use crate::ports::FilterProfilePort;
+
+#[derive(Debug, Clone)]
+pub struct FilterProfileFile {
+    path: PathBuf,
+}
+
+impl FilterProfileFile {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl FilterProfilePort for FilterProfileFile {
+    fn load_active_filter_profile(&self) -> Result<Option<String>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+
+        let raw = fs::read_to_string(&self.path).with_context(|| {
+            format!(
+                "failed to read active filter profile: {}",
+                self.path.display()
+            )
+        })?;
+        let name = raw.trim();
+        Ok((!name.is_empty()).then(|| name.to_string()))
+    }
+
+    fn save_active_filter_profile(&self, name: &str) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create directory: {}", parent.display()))?;
+        }
+        fs::write(&self.path, format!("{name}\n")).with_context(|| {
+            format!(
+                "failed to write active filter profile: {}",
+                self.path.display()
+            )
+        })
+    }
+}
@@ -0,0 +1,437 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::{
+    config::{AccountConfig, Config},
+    domain::{
+        events::WatchEvent,
+        subject_state::{SubjectRef, SubjectState},
+    },
+    infra::gh_client_cache::{DiskGhCache, InMemoryGhCache},
+    ports::{
+        AuthInfo, GhClientPort, MyPrFetch, PrFileChange, RepoAccess, RepoEventFetch,
+        ReviewThreadFetch,
+    },
+};
+
+use super::client::{AuthSource, GhCliClient, GhClientConfig, ProxySettings};
+
+/// One gh client per `[[accounts]]` entry and per `repositories[].gh_profile` plus the
+/// default login, routing every repository-scoped call through the repository's client.
+#[derive(Debug, Clone)]
+pub struct AccountGhClients {
+    default: GhCliClient,
+    accounts: Vec<(String, GhCliClient)>,
+    repo_accounts: HashMap<String, String>,
+    profiles: Vec<(String, GhCliClient)>,
+    repo_profiles: HashMap<String, String>,
+}
+
+impl AccountGhClients {
+    /// Builds account clients from `default`'s gh binary; every client uses `[network]`.
+    /// Token variables are read here, so an unset `token_env` fails before the first poll.
+    pub fn from_config(cfg: &Config, default: GhCliClient) -> Result<Self> {
+        let proxy = ProxySettings::from_network_config(&cfg.network);
+        let default = default.with_proxy(proxy.clone());
+        let accounts = cfg
+            .accounts
+            .iter()
+            .map(|account| {
+                let config = account_client_config(account, proxy.clone())
+                    .with_context(|| format!("invalid account '{}'", account.name))?;
+                Ok((account.name.clone(), default.for_account(config)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let repo_accounts = cfg
+            .repositories
+            .iter()
+            .filter_map(|repo| Some((repo.name.clone(), repo.account.clone()?)))
+            .collect();
+        let repo_profiles: HashMap<String, String> = cfg
+            .repositories
+            .iter()
+            .filter_map(|repo| Some((repo.name.clone(), repo.gh_profile.clone()?)))
+            .collect();
+        let mut profiles: Vec<(String, GhCliClient)> = Vec::new();
+        for profile in repo_profiles.values() {
+            if profiles.iter().all(|(name, _)| name != profile) {
+                let config = GhClientConfig {
+                    proxy: proxy.clone(),
+                    ..GhClientConfig::for_profile(profile)
+                };
+                profiles.push((profile.clone(), default.for_account(config)));
+            }
+        }
+        profiles.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        Ok(Self {
+            default,
+            accounts,
+            repo_accounts,
+            profiles,
+            repo_profiles,
+        })
+    }
+
+    pub fn account_names(&self) -> impl Iterator<Item = &str> {
+        self.accounts.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Distinct `repositories[].gh_profile` values, sorted.
+    pub fn profile_names(&self) -> impl Iterator<Item = &str> {
+        self.profiles.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Account configured for `repo`; `None` means the default login.
+    pub fn account_for_repo(&self, repo: &str) -> Option<&str> {
+        self.repo_accounts.get(repo).map(String::as_str)
+    }
+
+    /// gh profile configured for `repo`; `None` means an account or the default login.
+    pub fn profile_for_repo(&self, repo: &str) -> Option<&str> {
+        self.repo_profiles.get(repo).map(String::as_str)
+    }
+
+    /// Whether `a` and `b` are fetched through the same client instance.
+    pub fn share_client(&self, a: &str, b: &str) -> bool {
+        std::ptr::eq(self.client_for_repo(a).1, self.client_for_repo(b).1)
+    }
+
+    pub(crate) fn with_cache(self, cache: Arc<InMemoryGhCache>) -> Self {
+        Self {
+            default: self.default.with_cache(Arc::clone(&cache)),
+            accounts: self
+                .accounts
+                .into_iter()
+                .map(|(name, client)| (name, client.with_cache(Arc::clone(&cache))))
+                .collect(),
+            repo_accounts: self.repo_accounts,
+            profiles: self
+                .profiles
+                .into_iter()
+                .map(|(name, client)| (name, client.with_cache(Arc::clone(&cache))))
+                .collect(),
+            repo_profiles: self.repo_profiles,
+        }
+    }
+
+    pub fn with_response_cache(self, cache: Arc<DiskGhCache>) -> Self {
+        Self {
+            default: self.default.with_response_cache(Arc::clone(&cache)),
+            accounts: self
+                .accounts
+                .into_iter()
+                .map(|(name, client)| (name, client.with_response_cache(Arc::clone(&cache))))
+                .collect(),
+            repo_accounts: self.repo_accounts,
+            profiles: self
+                .profiles
+                .into_iter()
+                .map(|(name, client)| (name, client.with_response_cache(Arc::clone(&cache))))
+                .collect(),
+            repo_profiles: self.repo_profiles,
+        }
+    }
+
+    /// The client for `repo` and the label its events are tagged with: the account or
+    /// gh profile name, `None` for the default login.
+    fn client_for_repo(&self, repo: &str) -> (Option<&str>, &GhCliClient) {
+        if let Some(profile) = self.profile_for_repo(repo) {
+            let client = self
+                .profiles
+                .iter()
+                .find(|(name, _)| name == profile)
+                .map_or(&self.default, |(_, client)| client);
+            return (Some(profile), client);
+        }
+        let Some(account) = self.account_for_repo(repo) else {
+            return (None, &self.default);
+        };
+        let client = self
+            .accounts
+            .iter()
+            .find(|(name, _)| name == account)
+            .map_or(&self.default, |(_, client)| client);
+        (Some(account), client)
+    }
+}
+
+fn account_client_config(
+    account: &AccountConfig,
+    proxy: Option<ProxySettings>,
+) -> Result<GhClientConfig> {
+    let auth_source = match (
+        account.gh_config_dir.as_deref(),
+        account.token_env.as_deref(),
+    ) {
+        (Some(dir), None) => AuthSource::GhConfigDir(PathBuf::from(dir)),
+        (None, Some(var)) => {
+            let token = std::env::var(var).unwrap_or_default();
+            if token.trim().is_empty() {
+                return Err(anyhow!("token_env {var} is unset or empty"));
+            }
+            AuthSource::EnvToken(token.trim().to_string())
+        }
+        _ => return Err(anyhow!("set exactly one of gh_config_dir or token_env")),
+    };
+
+    Ok(GhClientConfig {
+        auth_source,
+        host: Some(account.host.clone()),
+        proxy,
+        ..GhClientConfig::default()
+    })
+}
+
+fn tag_account(events: &mut [WatchEvent], account: Option<&str>) {
+    for event in events {
+        event.account = account.map(str::to_string);
+    }
+}
+
+#[async_trait]
+impl GhClientPort for AccountGhClients {
+    /// Checks the default login, every account and every gh profile, naming the one that
+    /// failed. Returns the default login's details.
+    async fn check_auth(&self) -> Result<AuthInfo> {
+        let auth = self.default.check_auth().await?;
+        for (name, client) in &self.accounts {
+            client
+                .check_auth()
+                .await
+                .with_context(|| format!("authentication failed for account '{name}'"))?;
+        }
+        for (name, client) in &self.profiles {
+            client
+                .check_auth()
+                .await
+                .with_context(|| format!("authentication failed for gh profile '{name}'"))?;
+        }
+        Ok(auth)
+    }
+
+    async fn viewer_login(&self) -> Result<String> {
+        self.default.viewer_login().await
+    }
+
+    async fn fetch_repo_events(&self, repo: &str, since: DateTime<Utc>) -> Result<RepoEventFetch> {
+        let (account, client) = self.client_for_repo(repo);
+        let mut fetch = client.fetch_repo_events(repo, since).await?;
+        tag_account(&mut fetch.events, account);
+        Ok(fetch)
+    }
+
+    async fn fetch_bootstrap_repo_events(
+        &self,
+        repo: &str,
+        since: DateTime<Utc>,
+    ) -> Result<RepoEventFetch> {
+        let (account, client) = self.client_for_repo(repo);
+        let mut fetch = client.fetch_bootstrap_repo_events(repo, since).await?;
+        tag_account(&mut fetch.events, account);
+        Ok(fetch)
+    }
+
+    /// Batches per login, since one GraphQL request runs under one login.
+    async fn batch_fetch_repo_events(
+        &self,
+        repos: &[(&str, DateTime<Utc>)],
+    ) -> Result<HashMap<String, RepoEventFetch>> {
+        let mut groups: Vec<(Option<&str>, &GhCliClient, Vec<(&str, DateTime<Utc>)>)> = Vec::new();
+        for &(repo, since) in repos {
+            let (account, client) = self.client_for_repo(repo);
+            match groups
+                .iter_mut()
+                .find(|(_, grouped, _)| std::ptr::eq(*grouped, client))
+            {
+                Some((_, _, group)) => group.push((repo, since)),
+                None => groups.push((account, client, vec![(repo, since)])),
+            }
+        }
+
+        let mut fetches = HashMap::new();
+        for (account, client, group) in groups {
+            for (repo, mut fetch) in client.batch_fetch_repo_events(&group).await? {
+                tag_account(&mut fetch.events, account);
+                fetches.insert(repo, fetch);
+            }
+        }
+        Ok(fetches)
+    }
+
+    async fn list_org_members(&self, org: &str) -> Result<Vec<String>> {
+        self.default.list_org_members(org).await
+    }
+
+    async fn list_team_members(&self, org: &str, team: &str) -> Result<Vec<String>> {
+        self.default.list_team_members(org, team).await
+    }
+
+    async fn fetch_pr_reviews(
+        &self,
+        repo: &str,
+        pr_number: i64,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<WatchEvent>> {
+        let (account, client) = self.client_for_repo(repo);
+        let mut events = client.fetch_pr_reviews(repo, pr_number, since).await?;
+        tag_account(&mut events, account);
+        Ok(events)
+    }
+
+    async fn fetch_review_threads(&self, repo: &str) -> Result<ReviewThreadFetch> {
+        let (account, client) = self.client_for_repo(repo);
+        let mut fetch = client.fetch_review_threads(repo).await?;
+        tag_account(&mut fetch.resolved_events, account);
+        Ok(fetch)
+    }
+
+    /// Searches once per login, so `@me` is the login each repository is watched as.
+    async fn fetch_my_open_prs(&self, repos: &[&str]) -> Result<HashMap<String, MyPrFetch>> {
+        let mut groups: Vec<(Option<&str>, &GhCliClient, Vec<&str>)> = Vec::new();
+        for &repo in repos {
+            let (account, client) = self.client_for_repo(repo);
+            match groups
+                .iter_mut()
+                .find(|(_, grouped, _)| std::ptr::eq(*grouped, client))
+            {
+                Some((_, _, group)) => group.push(repo),
+                None => groups.push((account, client, vec![repo])),
+            }
+        }
+
+        let mut fetches = HashMap::new();
+        for (account, client, group) in groups {
+            for (repo, mut fetch) in client.fetch_my_open_prs(&group).await? {
+                tag_account(&mut fetch.events, account);
+                fetches.insert(repo, fetch);
+            }
+        }
+        Ok(fetches)
+    }
+
+    async fn is_pull_request_open(&self, repo: &str, pr_number: i64) -> Result<bool> {
+        let (_, client) = self.client_for_repo(repo);
+        client.is_pull_request_open(repo, pr_number).await
+    }
+
+    async fn fetch_pr_file_changes(&self, repo: &str, pr_number: i64) -> Result<Vec<PrFileChange>> {
+        let (_, client) = self.client_for_repo(repo);
+        client.fetch_pr_file_changes(repo, pr_number).await
+    }
+
+    async fn read_gist_file(&self, gist_id: &str, file_name: &str) -> Result<Option<String>> {
+        self.default.read_gist_file(gist_id, file_name).await
+    }
+
+    async fn write_gist_file(&self, gist_id: &str, file_name: &str, content: &str) -> Result<()> {
+        self.default
+            .write_gist_file(gist_id, file_name, content)
+            .await
+    }
+
+    /// Searches as the account of the first repository, or the default login without one.
+    async fn search_issues(
+        &self,
+        query: &str,
+        repos: &[&str],
+        limit: usize,
+    ) -> Result<Vec<WatchEvent>> {
+        let (account, client) = match repos.first() {
+            Some(repo) => self.client_for_repo(repo),
+            None => (None, &self.default),
+        };
+        let mut events = client.search_issues(query, repos, limit).await?;
+        tag_account(&mut events, account);
+        Ok(events)
+    }
+
+    fn api_call_count(&self) -> u64 {
+        self.default.api_call_count()
+            + self
+                .accounts
+                .iter()
+                .map(|(_, client)| client.api_call_count())
+                .sum::<u64>()
+            + self
+                .profiles
+                .iter()
+                .map(|(_, client)| client.api_call_count())
+                .sum::<u64>()
+    }
+
+    fn response_cache_hits(&self) -> u64 {
+        self.default.response_cache_hits()
+            + self
+                .accounts
+                .iter()
+                .map(|(_, client)| client.response_cache_hits())
+                .sum::<u64>()
+            + self
+                .profiles
+                .iter()
+                .map(|(_, client)| client.response_cache_hits())
+                .sum::<u64>()
+    }
+
+    /// Quota of the default login; each account has its own hourly limit.
+    async fn rate_limit_remaining(&self) -> Result<Option<u64>> {
+        self.default.rate_limit_remaining().await
+    }
+
+    async fn server_time(&self) -> Result<Option<DateTime<Utc>>> {
+        self.default.server_time().await
+    }
+
+    async fn probe_repo_access(&self, repo: &str) -> Result<RepoAccess> {
+        self.client_for_repo(repo).1.probe_repo_access(repo).await
+    }
+
+    async fn fetch_subject_states(
+        &self,
+        subjects: &[SubjectRef],
+    ) -> Result<HashMap<SubjectRef, SubjectState>> {
+        let mut groups: Vec<(&GhCliClient, Vec<SubjectRef>)> = Vec::new();
+        for subject in subjects {
+            let (_, client) = self.client_for_repo(&subject.repo);
+            match groups
+                .iter_mut()
+                .find(|(grouped, _)| std::ptr::eq(*grouped, client))
+            {
+                Some((_, group)) => group.push(subject.clone()),
+                None => groups.push((client, vec![subject.clone()])),
+            }
+        }
+
+        let mut states = HashMap::new();
+        for (client, group) in groups {
+            states.extend(client.fetch_subject_states(&group).await?);
+        }
+        Ok(states)
+    }
+
+    async fn fetch_repository_topics(&self, repo: &str) -> Result<Vec<String>> {
+        self.client_for_repo(repo)
+            .1
+            .fetch_repository_topics(repo)
+            .await
+    }
+
+    async fn viewer_teams(&self) -> Result<Vec<String>> {
+        self.default.viewer_teams().await
+    }
+
+    async fn run_gh_command(&self, repo: &str, args: &[String], cwd: Option<&Path>) -> Result<()> {
+        self.client_for_repo(repo)
+            .1
+            .run_gh_command(repo, args, cwd)
+            .await
+    }
+}
@@ -1,27 +1,302 @@
-use std::path::PathBuf;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Instant, SystemTime},
+};
 
 use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, SecondsFormat, Utc};
 use serde::Deserialize;
+use tokio::io::AsyncWriteExt;
 use tokio::process::Command;
+use tokio::sync::OnceCell;
 use tokio::time::{sleep, Duration};
 
-use crate::{domain::events::WatchEvent, ports::GhClientPort};
+use crate::{
+    config::{redact_proxy_url, NetworkConfig},
+    domain::{
+        error::{GhRateLimitError, ResponseTooLargeError},
+        events::WatchEvent,
+        redact::redact_secrets,
+        subject_state::{SubjectRef, SubjectState},
+    },
+    infra::gh_client_cache::{DiskGhCache, InMemoryGhCache},
+    ports::{
+        AuthInfo, GhClientPort, MyPrFetch, PrFileChange, RepoAccess, RepoEventFetch,
+        ReviewThreadFetch,
+    },
+};
 
 use super::{
-    models::{GhComment, GhIssue, GhPull},
-    normalize::{merge_pulls_by_id, normalize_events_from_items},
+    models::{GhComment, GhIssue, GhPull, GhReview, GhUser, RepoCandidate},
+    normalize::{
+        batch_alias, merge_issues_by_id, merge_pulls_by_id, normalize_batch_events_from_payload,
+        normalize_events_from_items, normalize_my_prs_from_payload, normalize_review_events,
+        normalize_review_threads_from_payload, normalize_search_events_from_payload,
+        normalize_subject_states_from_payload, normalize_viewer_teams_from_payload,
+        pending_review_requests, replace_inferred_review_events, subject_alias,
+    },
+    probe::{resolve_executable, GhProbe},
 };
 
 const PAGE_SIZE: usize = 100;
+/// Host gh talks to when neither the config nor `GH_HOST` names one.
+const DEFAULT_GH_HOST: &str = "github.com";
 const MAX_PAGES_PER_ENDPOINT: usize = 1000;
 const GH_EXEC_MAX_ATTEMPTS: usize = 5;
 const GH_EXEC_RETRY_BASE_MS: u64 = 20;
+const DEFAULT_MAX_RESPONSE_BYTES: usize = 10 * 1024 * 1024;
+/// GitHub asks clients to wait at least a minute after a secondary rate limit.
+const SECONDARY_RATE_LIMIT_RETRY_AFTER_SECONDS: u64 = 60;
+const MEMBER_LIST_TTL: Duration = Duration::from_secs(60 * 60);
+/// How long `is_pull_request_open` trusts a fetched pull request state.
+const PR_STATE_TTL: Duration = Duration::from_secs(60 * 60);
+/// Preview media type the topics endpoint was introduced under; still accepted.
+const TOPICS_ACCEPT_HEADER: &str = "Accept: application/vnd.github.mercy-preview+json";
+/// Open pull requests (most recently updated first) and threads per pull request that
+/// `watch_review_threads` inspects in one GraphQL request.
+const REVIEW_THREADS_QUERY: &str = "query($owner: String!, $name: String!) { \
+repository(owner: $owner, name: $name) { \
+pullRequests(states: OPEN, first: 50, orderBy: {field: UPDATED_AT, direction: DESC}) { nodes { \
+number title url updatedAt \
+reviewThreads(first: 100) { nodes { \
+id isResolved resolvedBy { login } comments(first: 1) { nodes { url author { login } } } \
+} } } } } }";
+/// The viewer's open pull requests matching `$q`, with the review decision and the combined
+/// check status of the head commit that `watch_my_prs` compares between polls.
+const MY_PRS_QUERY: &str = "query($q: String!) { \
+search(query: $q, type: ISSUE, first: 100) { nodes { ... on PullRequest { \
+number title url updatedAt author { login } repository { nameWithOwner } reviewDecision \
+commits(last: 1) { nodes { commit { oid statusCheckRollup { state } } } } \
+} } } }";
+/// Repositories per `watch_my_prs` search, keeping the query under GitHub's length limit.
+const MY_PRS_SEARCH_REPOS: usize = 10;
+
+/// Per-connection sizes of the batch events query, kept well under GraphQL's node limit
+/// for a full batch of repositories.
+const BATCH_PULLS: usize = 50;
+const BATCH_ISSUES: usize = 50;
+const BATCH_COMMENTS: usize = 30;
+const BATCH_REVIEWS: usize = 20;
+const BATCH_REVIEW_COMMENTS: usize = 20;
+const BATCH_REVIEW_REQUESTS: usize = 20;
+
+/// One GraphQL query aliasing `repository` once per entry of `repos`; fields are aliased to
+/// their REST names so the REST models can read the response.
+fn batch_repo_events_query(repos: &[(&str, DateTime<Utc>)]) -> Result<String> {
+    const COMMENT_FIELDS: &str =
+        "id: databaseId html_url: url created_at: createdAt body user: author { login }";
+
+    let mut query = String::from("query {");
+    for (index, (repo, since)) in repos.iter().enumerate() {
+        let (owner, name) = repo
+            .split_once('/')
+            .ok_or_else(|| anyhow!("invalid repository name: {repo}"))?;
+        let owner = serde_json::to_string(owner)?;
+        let name = serde_json::to_string(name)?;
+        let since = serde_json::to_string(&since.to_rfc3339())?;
+        let alias = batch_alias(index);
+        query.push_str(&format!(
+            " {alias}: repository(owner: {owner}, name: {name}) {{ \
+pullRequests(first: {BATCH_PULLS}, orderBy: {{field: UPDATED_AT, direction: DESC}}) {{ totalCount nodes {{ \
+id: databaseId number draft: isDraft title html_url: url created_at: createdAt \
+updated_at: updatedAt merged_at: mergedAt user: author {{ login }} merged_by: mergedBy {{ login }} \
+milestone {{ title due_on: dueOn }} \
+reviewRequests(first: {BATCH_REVIEW_REQUESTS}) {{ nodes {{ requestedReviewer {{ ... on User {{ login }} }} }} }} \
+comments(last: {BATCH_COMMENTS}) {{ totalCount nodes {{ {COMMENT_FIELDS} }} }} \
+reviews(last: {BATCH_REVIEWS}) {{ totalCount nodes {{ \
+id: databaseId state html_url: url submitted_at: submittedAt body user: author {{ login }} \
+comments(first: {BATCH_REVIEW_COMMENTS}) {{ totalCount nodes {{ {COMMENT_FIELDS} }} }} }} }} }} }} \
+issues(first: {BATCH_ISSUES}, orderBy: {{field: UPDATED_AT, direction: DESC}}, filterBy: {{since: {since}}}) {{ \
+totalCount nodes {{ id: databaseId number title html_url: url created_at: createdAt \
+updated_at: updatedAt user: author {{ login }} milestone {{ title due_on: dueOn }} \
+comments(last: {BATCH_COMMENTS}) {{ totalCount nodes {{ {COMMENT_FIELDS} }} }} }} }} }}"
+        ));
+    }
+    query.push_str(" }");
+    Ok(query)
+}
+
+/// One GraphQL query aliasing `repository { issueOrPullRequest }` once per entry of
+/// `subjects`.
+fn subject_states_query(subjects: &[SubjectRef]) -> Result<String> {
+    let mut query = String::from("query {");
+    for (index, subject) in subjects.iter().enumerate() {
+        let (owner, name) = subject
+            .repo
+            .split_once('/')
+            .ok_or_else(|| anyhow!("invalid repository name: {}", subject.repo))?;
+        let owner = serde_json::to_string(owner)?;
+        let name = serde_json::to_string(name)?;
+        let alias = subject_alias(index);
+        query.push_str(&format!(
+            " {alias}: repository(owner: {owner}, name: {name}) {{ \
+issueOrPullRequest(number: {}) {{ ... on Issue {{ state }} ... on PullRequest {{ state }} }} }}",
+            subject.number
+        ));
+    }
+    query.push_str(" }");
+    Ok(query)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GhClientConfig {
+    pub max_response_bytes: usize,
+    pub auth_source: AuthSource,
+    /// Passed to gh as `GH_HOST`; `None` leaves host selection to gh.
+    pub host: Option<String>,
+    pub proxy: Option<ProxySettings>,
+    /// A login stored by `gh auth login`. gh has no per-command `--user` flag, so the
+    /// client reads its token once with `gh auth token --user` and passes it as `GH_TOKEN`.
+    pub profile: Option<String>,
+    /// `watch --verbose-poll`: log every gh call and repository fetch to stderr.
+    pub log_requests: bool,
+}
+
+impl Default for GhClientConfig {
+    fn default() -> Self {
+        Self {
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
+            auth_source: AuthSource::default(),
+            host: None,
+            proxy: None,
+            profile: None,
+            log_requests: false,
+        }
+    }
+}
+
+impl GhClientConfig {
+    /// Requests as the stored gh login `name` instead of the active one.
+    pub fn for_profile(name: &str) -> Self {
+        Self {
+            profile: Some(name.to_string()),
+            ..Self::default()
+        }
+    }
+}
+
+/// Credentials the spawned `gh` processes use.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub enum AuthSource {
+    /// Whatever `gh auth login` stored.
+    #[default]
+    GhAuthStore,
+    /// Passed to every `gh` process as `GH_TOKEN`, for CI without `gh auth login`.
+    EnvToken(String),
+    /// A separate `gh auth login` profile, passed to gh as `GH_CONFIG_DIR`.
+    GhConfigDir(PathBuf),
+}
+
+impl AuthSource {
+    /// `GH_WATCH_GH_TOKEN`, then `GH_TOKEN`; empty values are ignored.
+    pub fn from_env() -> Self {
+        Self::from_tokens(
+            std::env::var("GH_WATCH_GH_TOKEN").ok(),
+            std::env::var("GH_TOKEN").ok(),
+        )
+    }
+
+    fn from_tokens(gh_watch_token: Option<String>, gh_token: Option<String>) -> Self {
+        [gh_watch_token, gh_token]
+            .into_iter()
+            .flatten()
+            .map(|token| token.trim().to_string())
+            .find(|token| !token.is_empty())
+            .map_or(Self::GhAuthStore, Self::EnvToken)
+    }
+}
+
+impl std::fmt::Debug for AuthSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::GhAuthStore => f.write_str("GhAuthStore"),
+            Self::EnvToken(_) => f.write_str("EnvToken(<redacted>)"),
+            Self::GhConfigDir(dir) => f.debug_tuple("GhConfigDir").field(dir).finish(),
+        }
+    }
+}
+
+/// `[network]` proxy, passed to gh as `HTTPS_PROXY`, `HTTP_PROXY` and `NO_PROXY`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ProxySettings {
+    pub url: String,
+    pub no_proxy: Vec<String>,
+}
+
+impl ProxySettings {
+    pub fn from_network_config(network: &NetworkConfig) -> Option<Self> {
+        network.proxy.as_ref().map(|url| Self {
+            url: url.clone(),
+            no_proxy: network.no_proxy.clone(),
+        })
+    }
+
+    /// `url` with credentials masked.
+    pub fn display_url(&self) -> String {
+        redact_proxy_url(&self.url)
+    }
+}
+
+impl std::fmt::Debug for ProxySettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProxySettings")
+            .field("url", &self.display_url())
+            .field("no_proxy", &self.no_proxy)
+            .finish()
+    }
+}
+
+/// gh reads `GH_TOKEN` for github.com and `*.ghe.com` only; other hosts need
+/// `GH_ENTERPRISE_TOKEN`.
+fn is_enterprise_server_host(host: &str) -> bool {
+    host != "github.com" && !host.ends_with(".ghe.com")
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepoCandidateSource {
+    Organization(String),
+    Starred,
+}
+
+/// Token of `GhClientConfig::profile`, looked up on the first gh call.
+#[derive(Clone, Default)]
+struct ProfileToken(Arc<OnceCell<String>>);
+
+impl std::fmt::Debug for ProfileToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = if self.0.initialized() {
+            "<redacted>"
+        } else {
+            "<unresolved>"
+        };
+        f.debug_tuple("ProfileToken").field(&state).finish()
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct GhCliClient {
     gh_bin: PathBuf,
+    config: GhClientConfig,
+    profile_token: ProfileToken,
+    cache: Option<Arc<InMemoryGhCache>>,
+    response_cache: Option<Arc<DiskGhCache>>,
+    /// Set on the copy serving `fetch_bootstrap_repo_events`, the only caller of
+    /// `response_cache`; holds the store time of the oldest response it served.
+    response_cache_hit_at: Option<Arc<Mutex<Option<SystemTime>>>>,
+    /// Response bytes of the repository fetch in progress, on the copy `fetch_repo_events`
+    /// makes when `log_requests` is set.
+    fetch_response_bytes: Option<Arc<AtomicU64>>,
+    api_calls: Arc<AtomicU64>,
+    response_cache_hits: Arc<AtomicU64>,
+    member_cache: Arc<InMemoryGhCache>,
+    pr_state_cache: Arc<InMemoryGhCache>,
 }
 
 impl Default for GhCliClient {
@@ -29,7 +304,10 @@ impl Default for GhCliClient {
         let gh_bin = std::env::var_os("GH_WATCH_GH_BIN")
             .map(PathBuf::from)
             .unwrap_or_else(|| PathBuf::from("gh"));
-        Self { gh_bin }
+        Self::new_with_bin(gh_bin).with_config(GhClientConfig {
+            auth_source: AuthSource::from_env(),
+            ..GhClientConfig::default()
+        })
     }
 }
 
@@ -37,32 +315,296 @@ impl GhCliClient {
     pub fn new_with_bin<P: Into<PathBuf>>(path: P) -> Self {
         Self {
             gh_bin: path.into(),
+            config: GhClientConfig::default(),
+            profile_token: ProfileToken::default(),
+            cache: None,
+            response_cache: None,
+            response_cache_hit_at: None,
+            fetch_response_bytes: None,
+            api_calls: Arc::new(AtomicU64::new(0)),
+            response_cache_hits: Arc::new(AtomicU64::new(0)),
+            member_cache: Arc::new(InMemoryGhCache::new(MEMBER_LIST_TTL)),
+            pr_state_cache: Arc::new(InMemoryGhCache::new(PR_STATE_TTL)),
+        }
+    }
+
+    pub fn with_config(mut self, config: GhClientConfig) -> Self {
+        self.config = config;
+        self.profile_token = ProfileToken::default();
+        self
+    }
+
+    pub fn with_proxy(mut self, proxy: Option<ProxySettings>) -> Self {
+        self.config.proxy = proxy;
+        self
+    }
+
+    pub fn with_request_logging(mut self, log_requests: bool) -> Self {
+        self.config.log_requests = log_requests;
+        self
+    }
+
+    pub(crate) fn with_cache(mut self, cache: Arc<InMemoryGhCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    pub fn with_response_cache(mut self, cache: Arc<DiskGhCache>) -> Self {
+        self.response_cache = Some(cache);
+        self
+    }
+
+    /// A client running the same gh binary with other credentials and its own call counter.
+    pub(super) fn for_account(&self, config: GhClientConfig) -> Self {
+        Self::new_with_bin(self.gh_bin.clone()).with_config(GhClientConfig {
+            log_requests: self.config.log_requests,
+            ..config
+        })
+    }
+
+    /// A copy that skips `poll.api_cache_ttl_seconds` caching, for event fetches: the cursor
+    /// advances to the poll start, so a page cached before it would hide whatever was
+    /// created in between for good.
+    fn without_memory_cache(&self) -> Self {
+        Self {
+            cache: None,
+            ..self.clone()
         }
     }
 
     async fn run_gh(&self, args: &[&str]) -> Result<String> {
+        let response = self.run_gh_memory_cached(args).await?;
+        if self.config.log_requests {
+            log_request_line(&gh_request_line(args, response.len()));
+        }
+        if let Some(bytes) = &self.fetch_response_bytes {
+            bytes.fetch_add(response.len() as u64, Ordering::Relaxed);
+        }
+        Ok(response)
+    }
+
+    async fn run_gh_memory_cached(&self, args: &[&str]) -> Result<String> {
+        let cache = self.cache.as_ref().filter(|_| args.first() == Some(&"api"));
+        let Some(cache) = cache else {
+            return self.run_gh_response_cached(args).await;
+        };
+
+        let endpoint = args.join(" ");
+        if let Some(response) = cache.get(&endpoint, Instant::now()) {
+            return Ok(response);
+        }
+
+        let response = self.run_gh_response_cached(args).await?;
+        cache.insert(&endpoint, response.clone(), Instant::now());
+        Ok(response)
+    }
+
+    /// Serves `gh api` calls of bootstrap fetches from the on-disk response cache when
+    /// one is set. Endpoints with a `since=` are left out: the lookback moves with the
+    /// clock, so their keys would never repeat.
+    async fn run_gh_response_cached(&self, args: &[&str]) -> Result<String> {
+        let cache = self
+            .response_cache
+            .as_ref()
+            .zip(self.response_cache_hit_at.as_ref());
+        let Some((cache, hit_at)) = cache.filter(|_| {
+            args.first() == Some(&"api") && !args.iter().any(|arg| arg.contains("since="))
+        }) else {
+            return self.run_gh_uncached(args).await;
+        };
+
+        let key = self.response_cache_key(args);
+        if let Some((response, stored_at)) = cache.get_entry(&key, SystemTime::now()) {
+            self.response_cache_hits.fetch_add(1, Ordering::Relaxed);
+            let mut hit_at = hit_at.lock().expect("response cache hit lock poisoned");
+            *hit_at = Some(hit_at.map_or(stored_at, |oldest| oldest.min(stored_at)));
+            return Ok(response);
+        }
+
+        let response = self.run_gh_uncached(args).await?;
+        cache.insert(&key, &response, SystemTime::now());
+        Ok(response)
+    }
+
+    /// `args` plus the host and credentials they run with, so logins never share entries.
+    fn response_cache_key(&self, args: &[&str]) -> String {
+        let auth = match &self.config.auth_source {
+            AuthSource::GhAuthStore => String::new(),
+            AuthSource::EnvToken(token) => format!("token:{token}"),
+            AuthSource::GhConfigDir(dir) => format!("config dir:{}", dir.display()),
+        };
+        [
+            self.config.host.as_deref().unwrap_or_default(),
+            self.config.profile.as_deref().unwrap_or_default(),
+            &auth,
+            &args.join(" "),
+        ]
+        .join("\n")
+    }
+
+    async fn run_gh_uncached(&self, args: &[&str]) -> Result<String> {
+        self.api_calls.fetch_add(1, Ordering::Relaxed);
+        self.run_gh_uncounted(args).await
+    }
+
+    /// Runs `gh api` with `body` on stdin, for `--input -`; a body passed as an argument
+    /// would hit the per-argument size limit of the OS (128 KiB on Linux).
+    async fn run_gh_with_body(&self, args: &[&str], body: &str) -> Result<String> {
+        self.api_calls.fetch_add(1, Ordering::Relaxed);
+        let output = self.run_gh_checked(args, Some(body)).await?;
+        Ok(String::from_utf8(output.stdout)?.trim().to_string())
+    }
+
+    /// Runs gh without counting it as an API call; used for the quota endpoint, which
+    /// GitHub does not charge against the limit.
+    async fn run_gh_uncounted(&self, args: &[&str]) -> Result<String> {
+        let output = self.run_gh_checked(args, None).await?;
+
+        if output.stdout.len() > self.config.max_response_bytes {
+            return Err(ResponseTooLargeError {
+                endpoint: args.join(" "),
+                size_bytes: output.stdout.len(),
+                limit_bytes: self.config.max_response_bytes,
+            }
+            .into());
+        }
+
+        Ok(String::from_utf8(output.stdout)?.trim().to_string())
+    }
+
+    /// Runs gh and turns a non-zero exit into a rate limit, proxy or generic error.
+    async fn run_gh_checked(
+        &self,
+        args: &[&str],
+        stdin: Option<&str>,
+    ) -> Result<std::process::Output> {
         let output = self
-            .run_gh_with_retry(args)
+            .run_gh_with_retry(args, stdin)
             .await
             .with_context(|| format!("failed to execute gh command: {:?}", args))?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
+            let stderr = stderr.trim();
+            if let Some((status_code, secondary)) = rate_limit_status(stderr) {
+                let endpoint = api_endpoint(args);
+                let retry_after_seconds = if secondary {
+                    Some(SECONDARY_RATE_LIMIT_RETRY_AFTER_SECONDS)
+                } else {
+                    let resource = if endpoint == "graphql" {
+                        "graphql"
+                    } else {
+                        "core"
+                    };
+                    self.rate_limit_reset_after(resource).await
+                };
+                return Err(GhRateLimitError {
+                    endpoint,
+                    status_code,
+                    retry_after_seconds,
+                    stderr: stderr.to_string(),
+                }
+                .into());
+            }
+            if let Some(proxy) = &self.config.proxy {
+                // Go reports failures to reach or authenticate with the proxy as `proxyconnect`.
+                if stderr.contains("proxyconnect") || stderr.contains("Proxy Authentication") {
+                    return Err(anyhow!(
+                        "could not connect through proxy {}: {stderr}",
+                        proxy.display_url()
+                    ));
+                }
+                return Err(anyhow!(
+                    "gh command failed via proxy {} (status={}): {stderr}",
+                    proxy.display_url(),
+                    output.status
+                ));
+            }
             return Err(anyhow!(
-                "gh command failed (status={}): {}",
-                output.status,
-                stderr.trim()
+                "gh command failed (status={}): {stderr}",
+                output.status
             ));
         }
 
-        Ok(String::from_utf8(output.stdout)?.trim().to_string())
+        Ok(output)
+    }
+
+    /// Seconds until the `resource` quota resets; `None` when gh cannot tell.
+    async fn rate_limit_reset_after(&self, resource: &str) -> Option<u64> {
+        let jq = format!(".resources.{resource}.reset");
+        let output = self
+            .run_gh_with_retry(&["api", "rate_limit", "--jq", &jq], None)
+            .await
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let reset = String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .parse::<i64>()
+            .ok()?;
+        Some((reset - Utc::now().timestamp()).max(0) as u64)
+    }
+
+    /// Token of the configured profile; `None` without one.
+    async fn profile_token(&self) -> Result<Option<&str>> {
+        let Some(profile) = self.config.profile.as_deref() else {
+            return Ok(None);
+        };
+        let token = self
+            .profile_token
+            .0
+            .get_or_try_init(|| async {
+                let mut command = Command::new(&self.gh_bin);
+                command.args(["auth", "token", "--user", profile]);
+                if let Some(host) = self.config.host.as_deref() {
+                    command.args(["--hostname", host]);
+                }
+                // With a token in the environment gh prints that one and ignores `--user`.
+                command
+                    .env_remove("GH_TOKEN")
+                    .env_remove("GH_ENTERPRISE_TOKEN");
+                if let AuthSource::GhConfigDir(dir) = &self.config.auth_source {
+                    command.env("GH_CONFIG_DIR", dir);
+                }
+                let output = command
+                    .output()
+                    .await
+                    .context("failed to execute gh auth token")?;
+                let token = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                if !output.status.success() || token.is_empty() {
+                    return Err(anyhow!(
+                        "gh has no stored login '{profile}'; run `gh auth login` as that user: {}",
+                        String::from_utf8_lossy(&output.stderr).trim()
+                    ));
+                }
+                Ok(token)
+            })
+            .await?;
+        Ok(Some(token.as_str()))
     }
 
-    async fn run_gh_with_retry(&self, args: &[&str]) -> std::io::Result<std::process::Output> {
+    async fn run_gh_with_retry(
+        &self,
+        args: &[&str],
+        stdin: Option<&str>,
+    ) -> std::io::Result<std::process::Output> {
+        let profile_token = self
+            .profile_token()
+            .await
+            .map_err(|err| std::io::Error::other(format!("{err:#}")))?;
         let mut attempt = 0usize;
         loop {
             attempt += 1;
-            match Command::new(&self.gh_bin).args(args).output().await {
+            let mut command = Command::new(&self.gh_bin);
+            command.args(args);
+            self.apply_auth_env(&mut command, profile_token);
+            self.apply_proxy_env(&mut command);
+            let output = match stdin {
+                Some(input) => output_with_stdin(command, input).await,
+                None => command.output().await,
+            };
+            match output {
                 Ok(output) => return Ok(output),
                 Err(err) if err.raw_os_error() == Some(26) && attempt < GH_EXEC_MAX_ATTEMPTS => {
                     let wait_ms = GH_EXEC_RETRY_BASE_MS * attempt as u64;
@@ -72,15 +614,95 @@ impl GhCliClient {
             }
         }
     }
+
+    fn apply_proxy_env(&self, command: &mut Command) {
+        let Some(proxy) = &self.config.proxy else {
+            return;
+        };
+        command
+            .env("HTTPS_PROXY", &proxy.url)
+            .env("HTTP_PROXY", &proxy.url);
+        if !proxy.no_proxy.is_empty() {
+            command.env("NO_PROXY", proxy.no_proxy.join(","));
+        }
+    }
+
+    fn apply_auth_env(&self, command: &mut Command, profile_token: Option<&str>) {
+        let host = self.config.host.as_deref();
+        if let Some(host) = host {
+            command.env("GH_HOST", host);
+        }
+        let token = match (&self.config.auth_source, profile_token) {
+            (_, Some(token)) => token,
+            (AuthSource::GhAuthStore, None) => return,
+            (AuthSource::EnvToken(token), None) => token,
+            (AuthSource::GhConfigDir(dir), None) => {
+                command.env("GH_CONFIG_DIR", dir);
+                return;
+            }
+        };
+        if host.is_some_and(is_enterprise_server_host) {
+            command.env("GH_ENTERPRISE_TOKEN", token);
+        } else {
+            command.env("GH_TOKEN", token);
+        }
+    }
 }
 
 #[async_trait]
 impl GhClientPort for GhCliClient {
-    async fn check_auth(&self) -> Result<()> {
-        self.run_gh(&["auth", "status"])
-            .await
-            .context("gh auth status failed")?;
-        Ok(())
+    async fn check_auth(&self) -> Result<AuthInfo> {
+        let hostname = self.config.host.as_deref().unwrap_or(DEFAULT_GH_HOST);
+        if let Some(profile) = self.config.profile.as_deref() {
+            self.profile_token().await?;
+            let login = self
+                .run_gh_uncached(&["api", "user", "--jq", ".login"])
+                .await
+                .with_context(|| format!("the token of gh login '{profile}' was rejected"))?;
+            return Ok(AuthInfo {
+                login,
+                scopes: Vec::new(),
+                hostname: hostname.to_string(),
+            });
+        }
+        match self.config.auth_source {
+            AuthSource::GhAuthStore | AuthSource::GhConfigDir(_) => {
+                let mut args = vec!["auth", "status"];
+                if let Some(host) = self.config.host.as_deref() {
+                    args.extend(["--hostname", host]);
+                }
+                self.api_calls.fetch_add(1, Ordering::Relaxed);
+                let output = self
+                    .run_gh_checked(&args, None)
+                    .await
+                    .context("gh auth status failed")?;
+                // gh before 2.40 printed the status to stderr, later releases to stdout.
+                let status = format!(
+                    "{}\n{}",
+                    String::from_utf8_lossy(&output.stdout),
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                match parse_auth_status(&status, self.config.host.as_deref()) {
+                    Some(auth) => Ok(auth),
+                    None => Ok(AuthInfo {
+                        login: self.viewer_login().await?,
+                        scopes: Vec::new(),
+                        hostname: hostname.to_string(),
+                    }),
+                }
+            }
+            AuthSource::EnvToken(_) => {
+                let login = self
+                    .run_gh_uncached(&["api", "user", "--jq", ".login"])
+                    .await
+                    .context("GH_TOKEN was rejected by `gh api user`")?;
+                Ok(AuthInfo {
+                    login,
+                    scopes: Vec::new(),
+                    hostname: hostname.to_string(),
+                })
+            }
+        }
     }
 
     async fn viewer_login(&self) -> Result<String> {
@@ -94,7 +716,434 @@ impl GhClientPort for GhCliClient {
         Ok(login)
     }
 
-    async fn fetch_repo_events(&self, repo: &str, since: DateTime<Utc>) -> Result<Vec<WatchEvent>> {
+    async fn fetch_bootstrap_repo_events(
+        &self,
+        repo: &str,
+        since: DateTime<Utc>,
+    ) -> Result<RepoEventFetch> {
+        if self.response_cache.is_none() {
+            return self.fetch_repo_events(repo, since).await;
+        }
+        let hit_at = Arc::new(Mutex::new(None));
+        let client = Self {
+            response_cache_hit_at: Some(Arc::clone(&hit_at)),
+            ..self.clone()
+        };
+        let mut fetch = client.fetch_repo_events(repo, since).await?;
+        fetch.cached_at = hit_at
+            .lock()
+            .expect("response cache hit lock poisoned")
+            .map(DateTime::<Utc>::from);
+        Ok(fetch)
+    }
+
+    async fn fetch_repo_events(&self, repo: &str, since: DateTime<Utc>) -> Result<RepoEventFetch> {
+        let client = self.without_memory_cache();
+        if !self.config.log_requests {
+            return client.fetch_rest_repo_events(repo, since).await;
+        }
+        let bytes = Arc::new(AtomicU64::new(0));
+        let client = Self {
+            fetch_response_bytes: Some(Arc::clone(&bytes)),
+            ..client
+        };
+        log_request_line(&poll_started_line(repo, since));
+        let started = Instant::now();
+        let fetch = client.fetch_rest_repo_events(repo, since).await?;
+        log_request_line(&poll_finished_line(
+            repo,
+            fetch.events.len(),
+            started.elapsed(),
+            bytes.load(Ordering::Relaxed),
+        ));
+        Ok(fetch)
+    }
+
+    /// Falls back to REST, one repository at a time, when the GraphQL request fails (for
+    /// example a token without GraphQL access) and for repositories it could not cover.
+    async fn batch_fetch_repo_events(
+        &self,
+        repos: &[(&str, DateTime<Utc>)],
+    ) -> Result<HashMap<String, RepoEventFetch>> {
+        let batch = async {
+            let query = format!("query={}", batch_repo_events_query(repos)?);
+            let payload = self
+                .without_memory_cache()
+                .run_gh(&["api", "graphql", "-f", &query])
+                .await?;
+            normalize_batch_events_from_payload(repos, &payload)
+        }
+        .await;
+        let mut fetches = batch.unwrap_or_else(|err| {
+            tracing::warn!(error = %err, "GraphQL batch fetch failed; falling back to REST");
+            HashMap::new()
+        });
+
+        for (repo, since) in repos {
+            if fetches.contains_key(*repo) {
+                continue;
+            }
+            match self.fetch_repo_events(repo, *since).await {
+                Ok(fetch) => {
+                    fetches.insert((*repo).to_string(), fetch);
+                }
+                Err(err) => {
+                    tracing::debug!(repo = %repo, error = %err, "REST fallback fetch failed");
+                }
+            }
+        }
+        Ok(fetches)
+    }
+
+    async fn list_org_members(&self, org: &str) -> Result<Vec<String>> {
+        self.fetch_member_logins(&format!("orgs/{org}/members?per_page={PAGE_SIZE}"))
+            .await
+            .with_context(|| format!("failed to list members of {org}"))
+    }
+
+    async fn list_team_members(&self, org: &str, team: &str) -> Result<Vec<String>> {
+        self.fetch_member_logins(&format!(
+            "orgs/{org}/teams/{team}/members?per_page={PAGE_SIZE}"
+        ))
+        .await
+        .with_context(|| format!("failed to list members of {org}/{team}"))
+    }
+
+    async fn fetch_pr_reviews(
+        &self,
+        repo: &str,
+        pr_number: i64,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<WatchEvent>> {
+        let reviews = self
+            .without_memory_cache()
+            .fetch_reviews(repo, pr_number)
+            .await?;
+        let mut events = normalize_review_events(repo, since, reviews, None);
+        events.sort_by_key(|event| event.created_at);
+        Ok(events)
+    }
+
+    async fn fetch_review_threads(&self, repo: &str) -> Result<ReviewThreadFetch> {
+        let (owner, name) = repo
+            .split_once('/')
+            .ok_or_else(|| anyhow!("invalid repository name: {repo}"))?;
+        let query = format!("query={REVIEW_THREADS_QUERY}");
+        let owner = format!("owner={owner}");
+        let name = format!("name={name}");
+        let payload = self
+            .run_gh(&["api", "graphql", "-f", &query, "-F", &owner, "-F", &name])
+            .await
+            .with_context(|| format!("failed to fetch review threads for {repo}"))?;
+
+        normalize_review_threads_from_payload(repo, &payload)
+            .with_context(|| format!("invalid review threads payload for {repo}"))
+    }
+
+    async fn fetch_my_open_prs(&self, repos: &[&str]) -> Result<HashMap<String, MyPrFetch>> {
+        let mut fetches = HashMap::new();
+        for chunk in repos.chunks(MY_PRS_SEARCH_REPOS) {
+            let query = format!("query={MY_PRS_QUERY}");
+            let q = format!("q={}", search_query("is:pr is:open author:@me", chunk));
+            let payload = self
+                .run_gh(&["api", "graphql", "-f", &query, "-f", &q])
+                .await
+                .with_context(|| format!("failed to fetch my open pull requests ({q})"))?;
+            fetches.extend(
+                normalize_my_prs_from_payload(&payload)
+                    .with_context(|| format!("invalid my pull requests payload ({q})"))?,
+            );
+        }
+        Ok(fetches)
+    }
+
+    /// Cached for `PR_STATE_TTL` regardless of `poll.api_cache_ttl_seconds`.
+    async fn is_pull_request_open(&self, repo: &str, pr_number: i64) -> Result<bool> {
+        let endpoint = format!("repos/{repo}/pulls/{pr_number}");
+        let state = match self.pr_state_cache.get(&endpoint, Instant::now()) {
+            Some(state) => state,
+            None => {
+                let state = self
+                    .run_gh_uncached(&["api", &endpoint, "--jq", ".state"])
+                    .await
+                    .with_context(|| format!("failed to fetch state of {repo}#{pr_number}"))?;
+                self.pr_state_cache
+                    .insert(&endpoint, state.clone(), Instant::now());
+                state
+            }
+        };
+        Ok(state.trim() == "open")
+    }
+
+    async fn fetch_pr_file_changes(&self, repo: &str, pr_number: i64) -> Result<Vec<PrFileChange>> {
+        let endpoint = format!("repos/{repo}/pulls/{pr_number}/files?per_page={PAGE_SIZE}");
+        let payload = self
+            .run_gh(&["api", "--paginate", "--slurp", &endpoint])
+            .await
+            .with_context(|| {
+                format!("failed to fetch files of {repo}#{pr_number} (endpoint={endpoint})")
+            })?;
+
+        let pages: Vec<Vec<PrFileChange>> = serde_json::from_str(&payload).with_context(|| {
+            format!("invalid files payload for {repo}#{pr_number} (endpoint={endpoint})")
+        })?;
+        Ok(pages.into_iter().flatten().collect())
+    }
+
+    async fn read_gist_file(&self, gist_id: &str, file_name: &str) -> Result<Option<String>> {
+        let endpoint = format!("gists/{gist_id}");
+        let filter = format!(
+            ".files[{}].content // empty",
+            serde_json::to_string(file_name)?
+        );
+        let content = self
+            .run_gh_uncached(&["api", &endpoint, "--jq", &filter])
+            .await
+            .with_context(|| format!("failed to read gist {gist_id}"))?;
+        Ok(Some(content).filter(|content| !content.is_empty()))
+    }
+
+    async fn write_gist_file(&self, gist_id: &str, file_name: &str, content: &str) -> Result<()> {
+        let endpoint = format!("gists/{gist_id}");
+        let body = serde_json::json!({ "files": { file_name: { "content": content } } });
+        // The response echoes every file in the gist; only the id is needed.
+        self.run_gh_with_body(
+            &[
+                "api", "-X", "PATCH", &endpoint, "--input", "-", "--jq", ".id",
+            ],
+            &body.to_string(),
+        )
+        .await
+        .with_context(|| format!("failed to write gist {gist_id}"))?;
+        Ok(())
+    }
+
+    async fn search_issues(
+        &self,
+        query: &str,
+        repos: &[&str],
+        limit: usize,
+    ) -> Result<Vec<WatchEvent>> {
+        let q = format!("q={}", search_query(query, repos));
+        let per_page = format!("per_page={}", limit.clamp(1, PAGE_SIZE));
+        let payload = self
+            .run_gh(&[
+                "api",
+                "-X",
+                "GET",
+                "search/issues",
+                "-f",
+                &q,
+                "-F",
+                &per_page,
+            ])
+            .await
+            .with_context(|| format!("failed to search issues ({q})"))?;
+
+        let mut events = normalize_search_events_from_payload(&payload)
+            .with_context(|| format!("invalid search payload ({q})"))?;
+        events.truncate(limit);
+        Ok(events)
+    }
+
+    fn api_call_count(&self) -> u64 {
+        self.api_calls.load(Ordering::Relaxed)
+    }
+
+    fn response_cache_hits(&self) -> u64 {
+        self.response_cache_hits.load(Ordering::Relaxed)
+    }
+
+    async fn rate_limit_remaining(&self) -> Result<Option<u64>> {
+        let remaining = self
+            .run_gh_uncounted(&["api", "rate_limit", "--jq", ".resources.core.remaining"])
+            .await
+            .context("failed to load rate limit")?;
+        let remaining = remaining
+            .parse::<u64>()
+            .with_context(|| format!("invalid rate limit remaining: {remaining}"))?;
+        Ok(Some(remaining))
+    }
+
+    /// `rate_limit` is free, and `--include` puts the response headers before its body.
+    async fn server_time(&self) -> Result<Option<DateTime<Utc>>> {
+        let response = self
+            .run_gh_uncounted(&["api", "--include", "rate_limit"])
+            .await
+            .context("failed to load GitHub server time")?;
+        Ok(date_header(&response))
+    }
+
+    /// gh follows GitHub's redirect for a renamed repository, so `full_name` is the new name.
+    async fn probe_repo_access(&self, repo: &str) -> Result<RepoAccess> {
+        let endpoint = format!("repos/{repo}");
+        match self.run_gh(&["api", &endpoint, "--jq", ".full_name"]).await {
+            Ok(full_name) if full_name.eq_ignore_ascii_case(repo) => Ok(RepoAccess::Accessible),
+            Ok(full_name) => Ok(RepoAccess::Renamed {
+                new_name: full_name,
+            }),
+            Err(err) if err.chain().any(|cause| cause.is::<GhRateLimitError>()) => Err(err),
+            Err(err) => match http_status_code(&format!("{err:#}")) {
+                Some(status_code @ (403 | 404)) => Ok(RepoAccess::Inaccessible { status_code }),
+                _ => Err(err),
+            },
+        }
+    }
+
+    /// Bypasses the response cache: callers keep their own copy for as long as they trust it.
+    async fn fetch_subject_states(
+        &self,
+        subjects: &[SubjectRef],
+    ) -> Result<HashMap<SubjectRef, SubjectState>> {
+        if subjects.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let query = format!("query={}", subject_states_query(subjects)?);
+        let payload = self
+            .run_gh_uncached(&["api", "graphql", "-f", &query])
+            .await
+            .context("failed to fetch issue and pull request states")?;
+        normalize_subject_states_from_payload(subjects, &payload)
+    }
+
+    /// Bypasses the response cache: the state db keeps the teams for a day.
+    async fn viewer_teams(&self) -> Result<Vec<String>> {
+        let endpoint = format!("user/teams?per_page={PAGE_SIZE}");
+        let payload = self
+            .run_gh_uncached(&["api", "--paginate", "--slurp", &endpoint])
+            .await
+            .context("failed to list the viewer's teams")?;
+        normalize_viewer_teams_from_payload(&payload)
+    }
+
+    async fn fetch_repository_topics(&self, repo: &str) -> Result<Vec<String>> {
+        let endpoint = format!("repos/{repo}/topics");
+        let output = self
+            .run_gh(&[
+                "api",
+                &endpoint,
+                "--header",
+                TOPICS_ACCEPT_HEADER,
+                "--jq",
+                ".names[]",
+            ])
+            .await
+            .with_context(|| format!("failed to fetch topics of {repo}"))?;
+        Ok(output
+            .lines()
+            .map(str::trim)
+            .filter(|topic| !topic.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+
+    async fn run_gh_command(&self, _repo: &str, args: &[String], cwd: Option<&Path>) -> Result<()> {
+        let profile_token = self.profile_token().await?;
+        let mut command = Command::new(&self.gh_bin);
+        command
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null());
+        if let Some(cwd) = cwd {
+            command.current_dir(cwd);
+        }
+        self.apply_auth_env(&mut command, profile_token);
+        self.apply_proxy_env(&mut command);
+
+        let output = command.output().await?;
+        if output.status.success() {
+            return Ok(());
+        }
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        if stderr.is_empty() {
+            return Err(anyhow!("{}", output.status));
+        }
+        Err(anyhow!(stderr))
+    }
+}
+
+impl GhCliClient {
+    /// First line of `gh --version`, e.g. `gh version 2.62.0 (2024-11-14)`.
+    pub async fn gh_version(&self) -> Result<String> {
+        let output = self.run_gh_uncounted(&["--version"]).await?;
+        Ok(output.lines().next().unwrap_or_default().to_string())
+    }
+
+    /// Finds the gh binary and reads its version. Fails only when the binary is missing;
+    /// an old or unreadable version is left to `GhProbe::warning`.
+    pub async fn probe(&self) -> Result<GhProbe> {
+        let path = resolve_executable(&self.gh_bin).ok_or_else(|| {
+            anyhow!(
+                "gh not found: {}; install gh (https://cli.github.com) or set GH_WATCH_GH_BIN",
+                self.gh_bin.display()
+            )
+        })?;
+        let version_line = self.gh_version().await.ok().filter(|line| !line.is_empty());
+        Ok(GhProbe { path, version_line })
+    }
+
+    /// Sends `HEAD /` to the API host and returns the round-trip time, gh startup included.
+    pub async fn probe_api(&self) -> Result<Duration> {
+        let started = Instant::now();
+        self.run_gh_uncounted(&["api", "--method", "HEAD", "--silent", "/"])
+            .await
+            .context("HEAD request to the GitHub API failed")?;
+        Ok(started.elapsed())
+    }
+
+    pub async fn list_repo_candidates(
+        &self,
+        source: &RepoCandidateSource,
+        include_archived: bool,
+        limit: Option<usize>,
+    ) -> Result<Vec<RepoCandidate>> {
+        let mut candidates = Vec::new();
+
+        for page in 1..=MAX_PAGES_PER_ENDPOINT {
+            let endpoint = match source {
+                RepoCandidateSource::Organization(org) => {
+                    format!(
+                        "orgs/{org}/repos?type=all&sort=full_name&per_page={PAGE_SIZE}&page={page}"
+                    )
+                }
+                RepoCandidateSource::Starred => {
+                    format!("user/starred?per_page={PAGE_SIZE}&page={page}")
+                }
+            };
+            let payload = self.run_gh(&["api", &endpoint]).await.with_context(|| {
+                format!("failed to fetch repository candidates (endpoint={endpoint})")
+            })?;
+            let page_items: Vec<RepoCandidate> =
+                serde_json::from_str(&payload).with_context(|| {
+                    format!("invalid repository candidates payload (endpoint={endpoint})")
+                })?;
+
+            let last_page = page_items.len() < PAGE_SIZE;
+            candidates.extend(
+                page_items
+                    .into_iter()
+                    .filter(|candidate| include_archived || !candidate.archived),
+            );
+            if let Some(limit) = limit {
+                if candidates.len() >= limit {
+                    candidates.truncate(limit);
+                    break;
+                }
+            }
+            if last_page {
+                break;
+            }
+        }
+
+        Ok(candidates)
+    }
+
+    /// Polls `repo` through the REST endpoints.
+    async fn fetch_rest_repo_events(
+        &self,
+        repo: &str,
+        since: DateTime<Utc>,
+    ) -> Result<RepoEventFetch> {
         let pulls_created = self
             .fetch_desc_until_since::<GhPull, _, _>(
                 repo,
@@ -127,7 +1176,7 @@ impl GhClientPort for GhCliClient {
 
         let pulls = merge_pulls_by_id(pulls_created, pulls_updated);
 
-        let issues = self
+        let issues_created = self
             .fetch_desc_until_since::<GhIssue, _, _>(
                 repo,
                 "issues",
@@ -142,6 +1191,23 @@ impl GhClientPort for GhCliClient {
             .await
             .with_context(|| format!("failed to fetch issues for {repo}"))?;
 
+        let issues_updated = self
+            .fetch_desc_until_since::<GhIssue, _, _>(
+                repo,
+                "issue updates",
+                since,
+                |page| {
+                    format!(
+                        "repos/{repo}/issues?state=all&sort=updated&direction=desc&per_page={PAGE_SIZE}&page={page}"
+                    )
+                },
+                |issue| issue.updated_at.unwrap_or(issue.created_at),
+            )
+            .await
+            .with_context(|| format!("failed to fetch issue updates for {repo}"))?;
+
+        let issues = merge_issues_by_id(issues_created, issues_updated);
+
         let since_rfc3339 = since.to_rfc3339();
         let issue_comments = self
             .fetch_paginated_comments(
@@ -161,6 +1227,16 @@ impl GhClientPort for GhCliClient {
             .await
             .with_context(|| format!("failed to fetch review comments for {repo}"))?;
 
+        let mut review_events = Vec::new();
+        for pr in pulls
+            .iter()
+            .filter(|pr| !pr.draft && pr.updated_at.unwrap_or(pr.created_at) > since)
+        {
+            let reviews = self.fetch_reviews(repo, pr.number_or_id()).await?;
+            review_events.extend(normalize_review_events(repo, since, reviews, Some(pr)));
+        }
+
+        let review_requests = pending_review_requests(since, &pulls);
         let mut events = normalize_events_from_items(
             repo,
             since,
@@ -169,12 +1245,15 @@ impl GhClientPort for GhCliClient {
             issue_comments,
             review_comments,
         );
-        events.sort_by(|a, b| a.created_at.cmp(&b.created_at));
-        Ok(events)
+        replace_inferred_review_events(&mut events, review_events);
+        events.sort_by_key(|event| event.created_at);
+        Ok(RepoEventFetch {
+            events,
+            review_requests,
+            cached_at: None,
+        })
     }
-}
 
-impl GhCliClient {
     async fn fetch_desc_until_since<T, E, C>(
         &self,
         repo: &str,
@@ -231,6 +1310,42 @@ impl GhCliClient {
         ))
     }
 
+    /// Member lists change rarely, so they are cached for an hour regardless of
+    /// `poll.api_cache_ttl_seconds`.
+    async fn fetch_member_logins(&self, endpoint: &str) -> Result<Vec<String>> {
+        let payload = match self.member_cache.get(endpoint, Instant::now()) {
+            Some(payload) => payload,
+            None => {
+                let payload = self
+                    .run_gh_uncached(&["api", "--paginate", "--slurp", endpoint])
+                    .await?;
+                self.member_cache
+                    .insert(endpoint, payload.clone(), Instant::now());
+                payload
+            }
+        };
+
+        let pages: Vec<Vec<GhUser>> = serde_json::from_str(&payload)
+            .with_context(|| format!("invalid members payload (endpoint={endpoint})"))?;
+        Ok(pages.into_iter().flatten().map(|user| user.login).collect())
+    }
+
+    async fn fetch_reviews(&self, repo: &str, pr_number: i64) -> Result<Vec<GhReview>> {
+        let endpoint = format!("repos/{repo}/pulls/{pr_number}/reviews?per_page={PAGE_SIZE}");
+        let payload = self
+            .run_gh(&["api", "--paginate", "--slurp", &endpoint])
+            .await
+            .with_context(|| {
+                format!("failed to fetch reviews for {repo}#{pr_number} (endpoint={endpoint})")
+            })?;
+
+        let pages: Vec<Vec<GhReview>> = serde_json::from_str(&payload).with_context(|| {
+            format!("invalid reviews payload for {repo}#{pr_number} (endpoint={endpoint})")
+        })?;
+
+        Ok(pages.into_iter().flatten().collect())
+    }
+
     async fn fetch_paginated_comments(
         &self,
         repo: &str,
@@ -253,3 +1368,382 @@ impl GhCliClient {
         Ok(pages.into_iter().flatten().collect())
     }
 }
+
+/// Search qualifiers of `query` narrowed to `repos`, e.g. `is:open repo:acme/api`.
+/// Active account in `gh auth status` output, on `host` when given. gh 2.40+ prints
+/// `Logged in to github.com account octocat (keyring)` and marks inactive accounts with
+/// `- Active account: false`; older releases print `Logged in to github.com as octocat (...)`.
+fn parse_auth_status(status: &str, host: Option<&str>) -> Option<AuthInfo> {
+    let mut accounts: Vec<(AuthInfo, bool)> = Vec::new();
+    for line in status.lines() {
+        let line = line.trim();
+        if let Some(rest) = line.split_once("Logged in to ").map(|(_, rest)| rest) {
+            let mut words = rest.split_whitespace();
+            let (Some(hostname), Some("account" | "as"), Some(login)) =
+                (words.next(), words.next(), words.next())
+            else {
+                continue;
+            };
+            let auth = AuthInfo {
+                login: login.to_string(),
+                scopes: Vec::new(),
+                hostname: hostname.to_string(),
+            };
+            accounts.push((auth, true));
+        } else if let Some((auth, active)) = accounts.last_mut() {
+            if let Some((_, value)) = line.split_once("Active account: ") {
+                *active = value.trim() == "true";
+            } else if let Some((_, value)) = line.split_once("Token scopes: ") {
+                auth.scopes = value
+                    .split(',')
+                    .map(|scope| scope.trim().trim_matches(['\'', '"']).to_string())
+                    .filter(|scope| !scope.is_empty() && scope != "none")
+                    .collect();
+            }
+        }
+    }
+    accounts
+        .into_iter()
+        .find(|(auth, active)| *active && host.is_none_or(|host| auth.hostname == host))
+        .map(|(auth, _)| auth)
+}
+
+fn search_query(query: &str, repos: &[&str]) -> String {
+    std::iter::once(query.trim())
+        .chain(repos.iter().map(|repo| repo.trim()))
+        .enumerate()
+        .filter(|(_, part)| !part.is_empty())
+        .map(|(index, part)| {
+            if index == 0 {
+                part.to_string()
+            } else {
+                format!("repo:{part}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `command.output()` with `input` written to its stdin.
+async fn output_with_stdin(
+    mut command: Command,
+    input: &str,
+) -> std::io::Result<std::process::Output> {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.as_bytes()).await?;
+    }
+    child.wait_with_output().await
+}
+
+/// `(HTTP status, is secondary limit)` when gh's stderr reports a rate limit, e.g.
+/// `gh: API rate limit exceeded for user ID 1. (HTTP 403)`.
+fn rate_limit_status(stderr: &str) -> Option<(Option<u16>, bool)> {
+    let lower = stderr.to_ascii_lowercase();
+    if !lower.contains("rate limit") {
+        return None;
+    }
+    Some((
+        http_status_code(&lower),
+        lower.contains("secondary rate limit"),
+    ))
+}
+
+/// Status of gh's `(HTTP 404)` error suffix.
+fn http_status_code(stderr: &str) -> Option<u16> {
+    stderr
+        .to_ascii_lowercase()
+        .split("http ")
+        .nth(1)
+        .and_then(|rest| rest.get(..3))
+        .and_then(|code| code.parse().ok())
+}
+
+/// `Date` header of a `gh api --include` response; headers end at the first blank line.
+fn date_header(response: &str) -> Option<DateTime<Utc>> {
+    response
+        .lines()
+        .take_while(|line| !line.trim().is_empty())
+        .find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.eq_ignore_ascii_case("date")
+                .then(|| DateTime::parse_from_rfc2822(value.trim()).ok())
+                .flatten()
+        })
+        .map(|date| date.with_timezone(&Utc))
+}
+
+/// Arguments longer than this are cut in `--verbose-poll` lines; GraphQL queries run to kilobytes.
+const LOGGED_ARG_MAX_CHARS: usize = 80;
+
+/// Writes a `--verbose-poll` line to stderr, or through tracing once a subscriber is set, so
+/// it never lands on the TUI's stdout.
+fn log_request_line(line: &str) {
+    let line = redact_secrets(line);
+    if tracing::dispatcher::has_been_set() {
+        tracing::info!("{line}");
+    } else {
+        eprintln!("{line}");
+    }
+}
+
+fn poll_started_line(repo: &str, since: DateTime<Utc>) -> String {
+    format!(
+        "[POLL] {repo} since={} endpoints=[pulls, issues, comments]",
+        since.to_rfc3339_opts(SecondsFormat::Secs, true)
+    )
+}
+
+fn poll_finished_line(repo: &str, events: usize, elapsed: Duration, bytes: u64) -> String {
+    format!(
+        "[POLL] {repo} -> {events} events in {}ms ({bytes}b)",
+        elapsed.as_millis()
+    )
+}
+
+fn gh_request_line(args: &[&str], bytes: usize) -> String {
+    let args = args
+        .iter()
+        .map(|arg| match arg.char_indices().nth(LOGGED_ARG_MAX_CHARS) {
+            Some((end, _)) => format!("{}...", &arg[..end]),
+            None => arg.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!("[POLL] gh {args} -> {bytes}b")
+}
+
+/// The path argument of a `gh api` invocation, skipping flags and their values.
+fn api_endpoint(args: &[&str]) -> String {
+    const FLAGS_WITH_VALUE: &[&str] = &[
+        "--method",
+        "-X",
+        "-f",
+        "-F",
+        "--field",
+        "--raw-field",
+        "--jq",
+        "-q",
+        "-H",
+        "--header",
+    ];
+    let mut rest = args.iter().skip_while(|arg| **arg == "api");
+    while let Some(arg) = rest.next() {
+        if FLAGS_WITH_VALUE.contains(arg) {
+            rest.next();
+        } else if !arg.starts_with('-') {
+            return arg.to_string();
+        }
+    }
+    args.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use tokio::time::Duration;
+
+    use super::{
+        api_endpoint, date_header, gh_request_line, http_status_code, parse_auth_status,
+        poll_finished_line, poll_started_line, rate_limit_status, search_query,
+        subject_states_query, AuthSource,
+    };
+    use crate::domain::subject_state::SubjectRef;
+
+    fn token(raw: &str) -> Option<String> {
+        Some(raw.to_string())
+    }
+
+    #[test]
+    fn gh_watch_token_takes_precedence_over_gh_token() {
+        assert_eq!(
+            AuthSource::from_tokens(token("override"), token("ci")),
+            AuthSource::EnvToken("override".to_string())
+        );
+        assert_eq!(
+            AuthSource::from_tokens(None, token("ci")),
+            AuthSource::EnvToken("ci".to_string())
+        );
+    }
+
+    #[test]
+    fn missing_or_blank_tokens_fall_back_to_gh_auth_store() {
+        assert_eq!(AuthSource::from_tokens(None, None), AuthSource::GhAuthStore);
+        assert_eq!(
+            AuthSource::from_tokens(token(" "), token("")),
+            AuthSource::GhAuthStore
+        );
+        assert_eq!(
+            AuthSource::from_tokens(token(""), token("ci")),
+            AuthSource::EnvToken("ci".to_string())
+        );
+    }
+
+    #[test]
+    fn debug_output_redacts_env_token() {
+        let debug = format!("{:?}", AuthSource::EnvToken("ghp_secret".to_string()));
+        assert!(!debug.contains("ghp_secret"));
+    }
+
+    #[test]
+    fn rate_limit_status_reads_http_status_and_secondary_limits() {
+        assert_eq!(
+            rate_limit_status("gh: API rate limit exceeded for user ID 1. (HTTP 403)"),
+            Some((Some(403), false))
+        );
+        assert_eq!(
+            rate_limit_status("gh: You have exceeded a secondary rate limit. (HTTP 429)"),
+            Some((Some(429), true))
+        );
+        assert_eq!(rate_limit_status("gh: Not Found (HTTP 404)"), None);
+    }
+
+    #[test]
+    fn verbose_poll_lines_name_the_repo_window_and_response_size() {
+        let since = Utc.with_ymd_and_hms(2025, 1, 9, 8, 30, 0).unwrap();
+        assert_eq!(
+            poll_started_line("acme/api", since),
+            "[POLL] acme/api since=2025-01-09T08:30:00Z endpoints=[pulls, issues, comments]"
+        );
+        assert_eq!(
+            poll_finished_line("acme/api", 3, Duration::from_millis(1250), 48213),
+            "[POLL] acme/api -> 3 events in 1250ms (48213b)"
+        );
+    }
+
+    #[test]
+    fn verbose_poll_request_lines_cut_long_arguments() {
+        assert_eq!(
+            gh_request_line(&["api", "repos/acme/api/pulls?page=1"], 512),
+            "[POLL] gh api repos/acme/api/pulls?page=1 -> 512b"
+        );
+        let query = format!("query={}", "x".repeat(200));
+        let line = gh_request_line(&["api", "graphql", "-f", &query], 9);
+        assert_eq!(
+            line,
+            format!("[POLL] gh api graphql -f query={}... -> 9b", "x".repeat(74))
+        );
+    }
+
+    #[test]
+    fn http_status_code_reads_the_gh_error_suffix() {
+        assert_eq!(
+            http_status_code("gh command failed (status=1): gh: Not Found (HTTP 404)"),
+            Some(404)
+        );
+        assert_eq!(http_status_code("connection refused"), None);
+    }
+
+    #[test]
+    fn date_header_is_read_from_the_headers_only() {
+        let response = "HTTP/2.0 200 OK\r\nContent-Type: application/json\r\ndate: Mon, 20 Jan 2025 00:10:00 GMT\r\n\r\n{\"date\": \"x\"}";
+        assert_eq!(
+            date_header(response),
+            Some(Utc.with_ymd_and_hms(2025, 1, 20, 0, 10, 0).unwrap())
+        );
+        assert_eq!(
+            date_header("HTTP/2.0 200 OK\n\nDate: Mon, 20 Jan 2025 00:10:00 GMT"),
+            None
+        );
+        assert_eq!(date_header("{\"resources\": {}}"), None);
+    }
+
+    #[test]
+    fn api_endpoint_skips_flags_and_their_values() {
+        assert_eq!(
+            api_endpoint(&[
+                "api",
+                "--paginate",
+                "--slurp",
+                "repos/acme/api/pulls?per_page=100"
+            ]),
+            "repos/acme/api/pulls?per_page=100"
+        );
+        assert_eq!(
+            api_endpoint(&["api", "graphql", "-f", "query=..."]),
+            "graphql"
+        );
+        assert_eq!(
+            api_endpoint(&["api", "--method", "HEAD", "--silent", "/"]),
+            "/"
+        );
+    }
+
+    #[test]
+    fn search_query_appends_repo_qualifiers() {
+        assert_eq!(
+            search_query(" is:open assignee:@me ", &["acme/api", "acme/web"]),
+            "is:open assignee:@me repo:acme/api repo:acme/web"
+        );
+        assert_eq!(search_query("label:bug", &[]), "label:bug");
+    }
+
+    #[test]
+    fn auth_status_picks_the_active_account_and_its_scopes() {
+        let status = "\
+github.com
+  ✓ Logged in to github.com account old-me (keyring)
+  - Active account: false
+  - Token scopes: 'repo'
+
+  ✓ Logged in to github.com account octocat (keyring)
+  - Active account: true
+  - Git operations protocol: https
+  - Token: gho_************************************
+  - Token scopes: 'gist', 'read:org', 'repo'
+";
+        let auth = parse_auth_status(status, None).expect("active account");
+        assert_eq!(auth.login, "octocat");
+        assert_eq!(auth.hostname, "github.com");
+        assert_eq!(auth.scopes, ["gist", "read:org", "repo"]);
+    }
+
+    #[test]
+    fn auth_status_reads_the_pre_2_40_format_and_filters_by_host() {
+        let status = "\
+github.com
+  ✓ Logged in to github.com as octocat (/home/me/.config/gh/hosts.yml)
+  ✓ Token scopes: gist, repo
+ghe.example.com
+  ✓ Logged in to ghe.example.com as corp-me (/home/me/.config/gh/hosts.yml)
+  ✓ Token scopes: none
+";
+        let auth = parse_auth_status(status, Some("ghe.example.com")).expect("host account");
+        assert_eq!(auth.login, "corp-me");
+        assert!(auth.scopes.is_empty());
+        assert_eq!(
+            parse_auth_status(status, None).unwrap().scopes,
+            ["gist", "repo"]
+        );
+        assert_eq!(parse_auth_status("You are not logged in", None), None);
+    }
+
+    #[test]
+    fn subject_states_query_aliases_one_lookup_per_subject() {
+        let subjects = [
+            SubjectRef {
+                repo: "acme/api".to_string(),
+                number: 7,
+            },
+            SubjectRef {
+                repo: "acme/web".to_string(),
+                number: 12,
+            },
+        ];
+
+        let query = subject_states_query(&subjects).unwrap();
+        assert!(query.starts_with("query { s0: repository(owner: \"acme\", name: \"api\")"));
+        assert!(query.contains("issueOrPullRequest(number: 7)"));
+        assert!(query.contains("s1: repository(owner: \"acme\", name: \"web\")"));
+        assert!(query.contains("... on PullRequest { state }"));
+        assert!(subject_states_query(&[SubjectRef {
+            repo: "no-slash".to_string(),
+            number: 1,
+        }])
+        .is_err());
+    }
+}
@@ -1,6 +1,18 @@
+mod accounts;
 mod client;
 mod models;
 mod normalize;
+mod probe;
+mod webhook;
 
-pub use client::GhCliClient;
-pub use normalize::normalize_events_from_payloads;
+pub use accounts::AccountGhClients;
+pub use client::{AuthSource, GhCliClient, GhClientConfig, ProxySettings, RepoCandidateSource};
+pub use models::RepoCandidate;
+pub use normalize::{
+    normalize_batch_events_from_payload, normalize_events_from_payloads,
+    normalize_my_prs_from_payload, normalize_review_events_from_payloads,
+    normalize_review_threads_from_payload, normalize_search_events_from_payload,
+    normalize_subject_states_from_payload, normalize_viewer_teams_from_payload,
+};
+pub use probe::{GhProbe, GhVersion, MIN_GH_VERSION};
+pub use webhook::{normalize_webhook_payload, WEBHOOK_EVENT_NAMES};
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::Deserialize;
 
@@ -21,6 +23,13 @@ pub(super) struct GhPull {
     pub(super) requested_reviewers: Vec<GhUser>,
     pub(super) merged_by: Option<GhUser>,
     pub(super) user: Option<GhUser>,
+    pub(super) milestone: Option<GhMilestone>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(super) struct GhMilestone {
+    pub(super) title: String,
+    pub(super) due_on: Option<DateTime<Utc>>,
 }
 
 impl GhPull {
@@ -29,15 +38,17 @@ impl GhPull {
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub(super) struct GhIssue {
     pub(super) id: i64,
     pub(super) number: Option<i64>,
     pub(super) title: String,
     pub(super) html_url: String,
     pub(super) created_at: DateTime<Utc>,
+    pub(super) updated_at: Option<DateTime<Utc>>,
     pub(super) user: Option<GhUser>,
     pub(super) pull_request: Option<serde_json::Value>,
+    pub(super) milestone: Option<GhMilestone>,
 }
 
 impl GhIssue {
@@ -57,3 +68,216 @@ pub(super) struct GhComment {
     pub(super) body: Option<String>,
     pub(super) user: Option<GhUser>,
 }
+
+#[derive(Debug, Deserialize)]
+pub(super) struct GhReview {
+    pub(super) id: i64,
+    pub(super) state: String,
+    pub(super) html_url: String,
+    pub(super) submitted_at: Option<DateTime<Utc>>,
+    pub(super) body: Option<String>,
+    pub(super) user: Option<GhUser>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct RepoCandidate {
+    pub full_name: String,
+    #[serde(default)]
+    pub archived: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct GhGraphqlNodes<T> {
+    pub(super) nodes: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct GhReviewThreadsResponse {
+    pub(super) data: GhReviewThreadsData,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct GhReviewThreadsData {
+    pub(super) repository: GhReviewThreadsRepository,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct GhReviewThreadsRepository {
+    pub(super) pull_requests: GhGraphqlNodes<GhThreadedPull>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct GhThreadedPull {
+    pub(super) number: i64,
+    pub(super) title: String,
+    pub(super) url: String,
+    pub(super) updated_at: DateTime<Utc>,
+    pub(super) review_threads: GhGraphqlNodes<GhReviewThread>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct GhReviewThread {
+    pub(super) id: String,
+    pub(super) is_resolved: bool,
+    pub(super) resolved_by: Option<GhUser>,
+    pub(super) comments: GhGraphqlNodes<GhThreadComment>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct GhThreadComment {
+    pub(super) url: String,
+    pub(super) author: Option<GhUser>,
+}
+
+/// `gh api graphql` response of the viewer's open pull requests search.
+#[derive(Debug, Deserialize)]
+pub(super) struct GhMyPrsResponse {
+    pub(super) data: GhMyPrsData,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct GhMyPrsData {
+    pub(super) search: GhGraphqlNodes<GhMyPr>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct GhMyPr {
+    pub(super) number: i64,
+    pub(super) title: String,
+    pub(super) url: String,
+    pub(super) updated_at: DateTime<Utc>,
+    pub(super) author: Option<GhUser>,
+    pub(super) repository: GhNamedRepository,
+    pub(super) review_decision: Option<String>,
+    pub(super) commits: GhGraphqlNodes<GhMyPrCommitNode>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct GhNamedRepository {
+    pub(super) name_with_owner: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct GhMyPrCommitNode {
+    pub(super) commit: GhMyPrCommit,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct GhMyPrCommit {
+    pub(super) oid: String,
+    pub(super) status_check_rollup: Option<GhStatusCheckRollup>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct GhStatusCheckRollup {
+    pub(super) state: String,
+}
+
+/// `gh api graphql` response of the subject state query; `data` is keyed by the
+/// per-subject alias (`s0`, `s1`, ...).
+#[derive(Debug, Deserialize)]
+pub(super) struct GhSubjectStatesResponse {
+    pub(super) data: Option<HashMap<String, Option<GhSubjectRepository>>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct GhSubjectRepository {
+    pub(super) issue_or_pull_request: Option<GhSubjectNode>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct GhSubjectNode {
+    pub(super) state: String,
+}
+
+/// Entry of `user/teams`.
+#[derive(Debug, Deserialize)]
+pub(super) struct GhTeam {
+    pub(super) slug: String,
+    pub(super) organization: GhUser,
+}
+
+/// `gh api graphql` response of the multi-repository events query; `data` is keyed by the
+/// per-repository alias (`r0`, `r1`, ...).
+#[derive(Debug, Deserialize)]
+pub(super) struct GhBatchResponse {
+    pub(super) data: Option<HashMap<String, Option<GhBatchRepository>>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct GhBatchRepository {
+    pub(super) pull_requests: GhBatchConnection<GhBatchPull>,
+    pub(super) issues: GhBatchConnection<GhBatchIssue>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct GhBatchConnection<T> {
+    pub(super) total_count: usize,
+    pub(super) nodes: Vec<T>,
+}
+
+impl<T> GhBatchConnection<T> {
+    pub(super) fn is_truncated(&self) -> bool {
+        self.total_count > self.nodes.len()
+    }
+}
+
+/// The query aliases GraphQL fields to REST names, so the REST models deserialize as is.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct GhBatchPull {
+    #[serde(flatten)]
+    pub(super) pull: GhPull,
+    pub(super) review_requests: GhGraphqlNodes<GhBatchReviewRequest>,
+    pub(super) comments: GhBatchConnection<GhComment>,
+    pub(super) reviews: GhBatchConnection<GhBatchReview>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct GhBatchReviewRequest {
+    pub(super) requested_reviewer: Option<GhBatchReviewer>,
+}
+
+/// `login` is absent for team reviewers, which the REST `requested_reviewers` list omits too.
+#[derive(Debug, Deserialize)]
+pub(super) struct GhBatchReviewer {
+    pub(super) login: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct GhBatchReview {
+    #[serde(flatten)]
+    pub(super) review: GhReview,
+    pub(super) comments: GhBatchConnection<GhComment>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct GhBatchIssue {
+    #[serde(flatten)]
+    pub(super) issue: GhIssue,
+    pub(super) comments: GhBatchConnection<GhComment>,
+}
+
+/// `search/issues` response; pull requests carry a `pull_request` object like in `issues`.
+#[derive(Debug, Deserialize)]
+pub(super) struct GhSearchResponse {
+    pub(super) items: Vec<GhSearchIssue>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct GhSearchIssue {
+    #[serde(flatten)]
+    pub(super) issue: GhIssue,
+    /// `https://api.github.com/repos/{owner}/{name}`.
+    pub(super) repository_url: String,
+}
@@ -3,9 +3,24 @@ use std::collections::{HashMap, HashSet};
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 
-use crate::domain::events::{EventKind, WatchEvent};
+use crate::{
+    domain::{
+        events::{EventKind, ReviewState, WatchEvent},
+        mentions::extract_mentions,
+        subject_state::{SubjectRef, SubjectState},
+    },
+    ports::{
+        MyPrFetch, MyPrState, PendingReviewRequests, RepoEventFetch, ReviewThreadFetch,
+        ReviewThreadState,
+    },
+};
 
-use super::models::{GhComment, GhIssue, GhPull, GhUser};
+use super::models::{
+    GhBatchConnection, GhBatchIssue, GhBatchPull, GhBatchRepository, GhBatchResponse,
+    GhBatchReview, GhComment, GhIssue, GhMilestone, GhMyPrsResponse, GhPull, GhReview,
+    GhReviewThreadsResponse, GhSearchResponse, GhSubjectStatesResponse, GhTeam, GhThreadedPull,
+    GhUser,
+};
 
 pub fn normalize_events_from_payloads(
     repo: &str,
@@ -26,10 +41,468 @@ pub fn normalize_events_from_payloads(
     let mut events =
         normalize_events_from_items(repo, since, pulls, issues, issue_comments, review_comments);
 
-    events.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+    events.sort_by_key(|event| event.created_at);
     Ok(events)
 }
 
+/// Normalizes a `pulls/{number}/reviews` payload for the pull request in `pull_json`.
+pub fn normalize_review_events_from_payloads(
+    repo: &str,
+    since: DateTime<Utc>,
+    pull_json: &str,
+    reviews_json: &str,
+) -> Result<Vec<WatchEvent>> {
+    let pull: GhPull = serde_json::from_str(pull_json).context("invalid pull payload")?;
+    let reviews: Vec<GhReview> =
+        serde_json::from_str(reviews_json).context("invalid reviews payload")?;
+
+    let mut events = normalize_review_events(repo, since, reviews, Some(&pull));
+    events.sort_by_key(|event| event.created_at);
+    Ok(events)
+}
+
+/// Normalizes a GraphQL `reviewThreads` response as returned by `gh api graphql`.
+pub fn normalize_review_threads_from_payload(
+    repo: &str,
+    payload_json: &str,
+) -> Result<ReviewThreadFetch> {
+    let response: GhReviewThreadsResponse =
+        serde_json::from_str(payload_json).context("invalid review threads payload")?;
+    Ok(normalize_review_threads(
+        repo,
+        response.data.repository.pull_requests.nodes,
+    ))
+}
+
+/// Normalizes a GraphQL search of the viewer's open pull requests, keyed by repository.
+/// Readiness events are dated by the pull request's `updatedAt` and keyed by its head
+/// commit, so a new push that gets approved or goes green again is news again.
+pub fn normalize_my_prs_from_payload(payload_json: &str) -> Result<HashMap<String, MyPrFetch>> {
+    let response: GhMyPrsResponse =
+        serde_json::from_str(payload_json).context("invalid my pull requests payload")?;
+
+    let mut fetches = HashMap::<String, MyPrFetch>::new();
+    for pr in response.data.search.nodes {
+        let repo = pr.repository.name_with_owner;
+        let head = pr.commits.nodes.into_iter().next().map(|node| node.commit);
+        let checks_state = head
+            .as_ref()
+            .and_then(|commit| commit.status_check_rollup.as_ref())
+            .map(|rollup| rollup.state.clone());
+        let head_oid = head.map(|commit| commit.oid).unwrap_or_default();
+        let author = user_login_or_unknown(pr.author.as_ref());
+        let fetch = fetches.entry(repo.clone()).or_default();
+
+        let ready = [
+            (
+                pr.review_decision.as_deref() == Some("APPROVED"),
+                EventKind::MyPrApproved,
+                "my-pr-approved",
+                "Approved",
+            ),
+            (
+                checks_state.as_deref() == Some("SUCCESS"),
+                EventKind::MyPrChecksPassed,
+                "my-pr-checks-passed",
+                "Checks passed",
+            ),
+        ];
+        for (is_ready, kind, id_prefix, label) in ready {
+            if !is_ready {
+                continue;
+            }
+            fetch.events.push(WatchEvent {
+                event_id: format!("{id_prefix}:{}:{head_oid}", pr.number),
+                repo: repo.clone(),
+                kind,
+                actor: author.clone(),
+                title: format!("{label}: {}", pr.title),
+                url: pr.url.clone(),
+                created_at: pr.updated_at,
+                source_item_id: format!("{}:{head_oid}", pr.number),
+                subject_author: Some(author.clone()),
+                requested_reviewer: None,
+                mentions: Vec::new(),
+                team_mentions: Vec::new(),
+                subject_number: Some(pr.number),
+                subject_url: Some(pr.url.clone()),
+                account: None,
+                body: None,
+            });
+        }
+        fetch.states.push(MyPrState {
+            pr_number: pr.number,
+            review_decision: pr.review_decision,
+            checks_state,
+        });
+    }
+    Ok(fetches)
+}
+
+/// Normalizes a `search/issues` response into one created event per result, newest first
+/// as returned by the search. Pull requests get their own `search-pr:` ids because the
+/// search reports issue ids, not the pull request ids used by `pr:` events.
+pub fn normalize_search_events_from_payload(payload_json: &str) -> Result<Vec<WatchEvent>> {
+    let response: GhSearchResponse =
+        serde_json::from_str(payload_json).context("invalid search payload")?;
+
+    Ok(response
+        .items
+        .into_iter()
+        .map(|item| {
+            let issue = item.issue;
+            let repo = item
+                .repository_url
+                .rsplit_once("/repos/")
+                .map_or(item.repository_url.as_str(), |(_, repo)| repo)
+                .to_string();
+            let (kind, id_prefix) = if issue.pull_request.is_some() {
+                (EventKind::PrCreated, "search-pr")
+            } else {
+                (EventKind::IssueCreated, "issue")
+            };
+            let actor = user_login_or_unknown(issue.user.as_ref());
+            let mentions = extract_mentions(&issue.title);
+            WatchEvent {
+                event_id: format!("{id_prefix}:{}", issue.id),
+                repo,
+                kind,
+                actor: actor.clone(),
+                mentions: mentions.users,
+                team_mentions: mentions.teams,
+                title: issue.title,
+                url: issue.html_url.clone(),
+                created_at: issue.created_at,
+                source_item_id: issue.id.to_string(),
+                subject_author: Some(actor),
+                requested_reviewer: None,
+                subject_number: issue.number,
+                subject_url: Some(issue.html_url),
+                account: None,
+                body: None,
+            }
+        })
+        .collect())
+}
+
+/// GraphQL alias of the `index`-th repository in a batch events query.
+pub(super) fn batch_alias(index: usize) -> String {
+    format!("r{index}")
+}
+
+pub(super) fn subject_alias(index: usize) -> String {
+    format!("s{index}")
+}
+
+/// Normalizes a subject state GraphQL response for `subjects`, in query order. Subjects
+/// whose alias or node is null, or whose state is unknown, are left out.
+pub fn normalize_subject_states_from_payload(
+    subjects: &[SubjectRef],
+    payload_json: &str,
+) -> Result<HashMap<SubjectRef, SubjectState>> {
+    let response: GhSubjectStatesResponse =
+        serde_json::from_str(payload_json).context("invalid subject states payload")?;
+    let mut data = response.data.unwrap_or_default();
+
+    let mut states = HashMap::new();
+    for (index, subject) in subjects.iter().enumerate() {
+        let Some(Some(repository)) = data.remove(&subject_alias(index)) else {
+            continue;
+        };
+        if let Some(state) = repository
+            .issue_or_pull_request
+            .and_then(|node| SubjectState::parse(&node.state))
+        {
+            states.insert(subject.clone(), state);
+        }
+    }
+    Ok(states)
+}
+
+/// Normalizes the slurped pages of `user/teams` to lowercased `org/team`, without repeats.
+pub fn normalize_viewer_teams_from_payload(payload_json: &str) -> Result<Vec<String>> {
+    let pages: Vec<Vec<GhTeam>> =
+        serde_json::from_str(payload_json).context("invalid user teams payload")?;
+    let mut teams = Vec::new();
+    for team in pages.into_iter().flatten() {
+        let team = format!("{}/{}", team.organization.login, team.slug).to_ascii_lowercase();
+        if !teams.contains(&team) {
+            teams.push(team);
+        }
+    }
+    Ok(teams)
+}
+
+/// Normalizes a multi-repository GraphQL events response for `repos`, in query order.
+/// Repositories whose alias is null, or whose results were cut off before their `since`,
+/// are left out so the caller can fetch them through REST instead.
+pub fn normalize_batch_events_from_payload(
+    repos: &[(&str, DateTime<Utc>)],
+    payload_json: &str,
+) -> Result<HashMap<String, RepoEventFetch>> {
+    let response: GhBatchResponse =
+        serde_json::from_str(payload_json).context("invalid batch events payload")?;
+    let mut data = response.data.unwrap_or_default();
+
+    let mut fetches = HashMap::new();
+    for (index, (repo, since)) in repos.iter().enumerate() {
+        let Some(Some(repository)) = data.remove(&batch_alias(index)) else {
+            continue;
+        };
+        if let Some(fetch) = normalize_batch_repository(repo, *since, repository) {
+            fetches.insert((*repo).to_string(), fetch);
+        }
+    }
+    Ok(fetches)
+}
+
+/// `None` when a connection holds more items than the query asked for and the ones
+/// returned do not reach back to `since`.
+fn normalize_batch_repository(
+    repo: &str,
+    since: DateTime<Utc>,
+    repository: GhBatchRepository,
+) -> Option<RepoEventFetch> {
+    let GhBatchRepository {
+        pull_requests,
+        issues: batch_issues,
+    } = repository;
+    let oldest_pull_is_new = pull_requests
+        .nodes
+        .last()
+        .is_some_and(|pr| pr.pull.updated_at.unwrap_or(pr.pull.created_at) > since);
+    // `issues` is filtered by `since` on the server, so any truncation loses events.
+    if (pull_requests.is_truncated() && oldest_pull_is_new) || batch_issues.is_truncated() {
+        return None;
+    }
+
+    let mut pulls = Vec::new();
+    let mut reviews_by_pull = Vec::new();
+    let mut issue_comments = Vec::new();
+    let mut review_comments = Vec::new();
+    for GhBatchPull {
+        mut pull,
+        review_requests,
+        comments,
+        reviews,
+    } in pull_requests.nodes
+    {
+        let updated_since = pull.updated_at.unwrap_or(pull.created_at) > since;
+        let reviews_cut_off = reviews.is_truncated()
+            && reviews
+                .nodes
+                .first()
+                .is_some_and(|review| review.review.submitted_at.is_some_and(|at| at > since));
+        if updated_since && (comments_cut_off(&comments, since) || reviews_cut_off) {
+            return None;
+        }
+
+        pull.requested_reviewers = review_requests
+            .nodes
+            .into_iter()
+            .filter_map(|request| request.requested_reviewer?.login)
+            .map(|login| GhUser { login })
+            .collect();
+        issue_comments.extend(comments.nodes.into_iter().map(|mut comment| {
+            comment.issue_url = Some(pull.html_url.clone());
+            comment
+        }));
+
+        let mut pull_reviews = Vec::new();
+        for GhBatchReview { review, comments } in reviews.nodes {
+            if updated_since && comments.is_truncated() {
+                return None;
+            }
+            review_comments.extend(comments.nodes.into_iter().map(|mut comment| {
+                comment.pull_request_url = Some(pull.html_url.clone());
+                comment.pull_request_review_id = Some(review.id);
+                comment
+            }));
+            pull_reviews.push(review);
+        }
+        reviews_by_pull.push(pull_reviews);
+        pulls.push(pull);
+    }
+
+    let mut issues = Vec::new();
+    for GhBatchIssue { issue, comments } in batch_issues.nodes {
+        if comments_cut_off(&comments, since) {
+            return None;
+        }
+        issue_comments.extend(comments.nodes.into_iter().map(|mut comment| {
+            comment.issue_url = Some(issue.html_url.clone());
+            comment
+        }));
+        issues.push(issue);
+    }
+
+    let mut review_events = Vec::new();
+    for (pull, reviews) in pulls.iter().zip(reviews_by_pull) {
+        if !pull.draft && pull.updated_at.unwrap_or(pull.created_at) > since {
+            review_events.extend(normalize_review_events(repo, since, reviews, Some(pull)));
+        }
+    }
+
+    let review_requests = pending_review_requests(since, &pulls);
+    let mut events =
+        normalize_events_from_items(repo, since, pulls, issues, issue_comments, review_comments);
+    replace_inferred_review_events(&mut events, review_events);
+    events.sort_by_key(|event| event.created_at);
+    Some(RepoEventFetch {
+        events,
+        review_requests,
+        cached_at: None,
+    })
+}
+
+/// Comments are requested newest-last, so the first node is the oldest returned.
+fn comments_cut_off(comments: &GhBatchConnection<GhComment>, since: DateTime<Utc>) -> bool {
+    comments.is_truncated()
+        && comments
+            .nodes
+            .first()
+            .is_some_and(|comment| comment.created_at > since)
+}
+
+/// GraphQL does not report when a thread was resolved, so resolved events are dated by the
+/// pull request's `updatedAt`, like review dismissals.
+fn normalize_review_threads(repo: &str, pulls: Vec<GhThreadedPull>) -> ReviewThreadFetch {
+    let mut fetch = ReviewThreadFetch::default();
+    for pr in pulls {
+        for thread in pr.review_threads.nodes {
+            fetch.states.push(ReviewThreadState {
+                thread_id: thread.id.clone(),
+                is_resolved: thread.is_resolved,
+            });
+            if !thread.is_resolved {
+                continue;
+            }
+
+            let first_comment = thread.comments.nodes.first();
+            fetch.resolved_events.push(WatchEvent {
+                event_id: format!("review-thread-resolved:{}", thread.id),
+                repo: repo.to_string(),
+                kind: EventKind::ReviewThreadResolved,
+                actor: user_login_or_unknown(thread.resolved_by.as_ref()),
+                title: format!("Thread resolved: {}", pr.title),
+                url: first_comment.map_or_else(|| pr.url.clone(), |comment| comment.url.clone()),
+                created_at: pr.updated_at,
+                source_item_id: thread.id,
+                subject_author: first_comment
+                    .and_then(|comment| comment.author.as_ref().map(|u| u.login.clone())),
+                requested_reviewer: None,
+                mentions: Vec::new(),
+                team_mentions: Vec::new(),
+                subject_number: Some(pr.number),
+                subject_url: Some(pr.url.clone()),
+                account: None,
+                body: None,
+            });
+        }
+    }
+    fetch
+}
+
+/// The reviews API has no dismissal timestamp, so a review submitted before `since` and
+/// dismissed since then is dated by the pull request's `updated_at` when `pull` is known.
+pub(super) fn normalize_review_events(
+    repo: &str,
+    since: DateTime<Utc>,
+    reviews: Vec<GhReview>,
+    pull: Option<&GhPull>,
+) -> Vec<WatchEvent> {
+    let pull_updated_at = pull.map(|pr| pr.updated_at.unwrap_or(pr.created_at));
+
+    reviews
+        .iter()
+        .filter_map(|review| {
+            let state = ReviewState::from_api(&review.state)?;
+            let submitted_at = review.submitted_at?;
+            let created_at = if submitted_at > since {
+                submitted_at
+            } else if state == ReviewState::Dismissed {
+                pull_updated_at.filter(|updated_at| *updated_at > since)?
+            } else {
+                return None;
+            };
+
+            let body = review.body.clone().unwrap_or_default();
+            let (id_prefix, title) = match state {
+                ReviewState::Commented => (
+                    "review-submitted",
+                    title_from_comment(review.body.as_deref(), "PR review submitted"),
+                ),
+                ReviewState::Approved => ("review-approved", review_title("Approved", pull)),
+                ReviewState::ChangesRequested => (
+                    "review-changes-requested",
+                    review_title("Changes requested", pull),
+                ),
+                ReviewState::Dismissed => {
+                    ("review-dismissed", review_title("Review dismissed", pull))
+                }
+            };
+
+            let mentions = extract_mentions(&body);
+            Some(WatchEvent {
+                event_id: format!("{id_prefix}:{}", review.id),
+                repo: repo.to_string(),
+                kind: state.event_kind(),
+                actor: user_login_or_unknown(review.user.as_ref()),
+                title,
+                url: review.html_url.clone(),
+                created_at,
+                source_item_id: review.id.to_string(),
+                subject_author: pull.and_then(|pr| pr.user.as_ref().map(|u| u.login.clone())),
+                requested_reviewer: None,
+                mentions: mentions.users,
+                team_mentions: mentions.teams,
+                subject_number: pull.and_then(|pr| pr.number),
+                subject_url: Some(
+                    pull.map_or_else(|| strip_anchor(&review.html_url), |pr| pr.html_url.clone()),
+                ),
+                account: None,
+                body: stored_body(review.body.as_deref()),
+            })
+        })
+        .collect()
+}
+
+/// Drops review submissions inferred from review comments when the reviews API reported the
+/// same review.
+pub(super) fn replace_inferred_review_events(
+    events: &mut Vec<WatchEvent>,
+    review_events: Vec<WatchEvent>,
+) {
+    let review_ids = review_events
+        .iter()
+        .map(|event| event.source_item_id.clone())
+        .collect::<HashSet<_>>();
+    events.retain(|event| {
+        event.kind != EventKind::PrReviewSubmitted || !review_ids.contains(&event.source_item_id)
+    });
+    events.extend(review_events);
+}
+
+/// The pull requests `normalize_events_from_items` emits `PrReviewRequested` for, with
+/// the reviewers still requested on each.
+pub(super) fn pending_review_requests(
+    since: DateTime<Utc>,
+    pulls: &[GhPull],
+) -> Vec<PendingReviewRequests> {
+    pulls
+        .iter()
+        .filter(|pr| !pr.draft && pr.updated_at.unwrap_or(pr.created_at) > since)
+        .map(|pr| PendingReviewRequests {
+            pull_id: pr.id.to_string(),
+            reviewers: pr
+                .requested_reviewers
+                .iter()
+                .map(|reviewer| reviewer.login.clone())
+                .collect(),
+        })
+        .collect()
+}
+
 pub(super) fn normalize_events_from_items(
     repo: &str,
     since: DateTime<Utc>,
@@ -70,6 +543,7 @@ pub(super) fn normalize_events_from_items(
             .filter(|pr| !pr.draft && pr.created_at > since)
             .map(|pr| {
                 let actor = user_login_or_unknown(pr.user.as_ref());
+                let mentions = extract_mentions(&pr.title);
                 WatchEvent {
                     event_id: format!("pr:{}", pr.id),
                     repo: repo.to_string(),
@@ -81,7 +555,12 @@ pub(super) fn normalize_events_from_items(
                     source_item_id: pr.id.to_string(),
                     subject_author: Some(actor),
                     requested_reviewer: None,
-                    mentions: extract_mentions(&pr.title),
+                    mentions: mentions.users,
+                    team_mentions: mentions.teams,
+                    subject_number: pr.number,
+                    subject_url: Some(pr.html_url.clone()),
+                    account: None,
+                    body: None,
                 }
             }),
     );
@@ -119,6 +598,11 @@ pub(super) fn normalize_events_from_items(
                     subject_author: author,
                     requested_reviewer: None,
                     mentions: Vec::new(),
+                    team_mentions: Vec::new(),
+                    subject_number: pr.number,
+                    subject_url: Some(pr.html_url.clone()),
+                    account: None,
+                    body: None,
                 })
             })
             .collect::<Vec<_>>(),
@@ -146,6 +630,11 @@ pub(super) fn normalize_events_from_items(
                 subject_author: pr.user.as_ref().map(|u| u.login.clone()),
                 requested_reviewer: Some(reviewer.login.clone()),
                 mentions: Vec::new(),
+                team_mentions: Vec::new(),
+                subject_number: pr.number,
+                subject_url: Some(pr.html_url.clone()),
+                account: None,
+                body: None,
             });
         }
     }
@@ -157,6 +646,7 @@ pub(super) fn normalize_events_from_items(
             .filter(|issue| issue.created_at > since)
             .map(|issue| {
                 let actor = user_login_or_unknown(issue.user.as_ref());
+                let mentions = extract_mentions(&issue.title);
                 WatchEvent {
                     event_id: format!("issue:{}", issue.id),
                     repo: repo.to_string(),
@@ -168,11 +658,74 @@ pub(super) fn normalize_events_from_items(
                     source_item_id: issue.id.to_string(),
                     subject_author: Some(actor),
                     requested_reviewer: None,
-                    mentions: extract_mentions(&issue.title),
+                    mentions: mentions.users,
+                    team_mentions: mentions.teams,
+                    subject_number: issue.number,
+                    subject_url: Some(issue.html_url.clone()),
+                    account: None,
+                    body: None,
                 }
             }),
     );
 
+    for pr in &pulls {
+        let Some(milestone) = pr.milestone.as_ref() else {
+            continue;
+        };
+        let updated_at = pr.updated_at.unwrap_or(pr.created_at);
+        if pr.draft || updated_at <= since {
+            continue;
+        }
+        // The item does not say who set the milestone; a webhook delivery fills it in.
+        events.push(WatchEvent {
+            event_id: format!("pr-milestone:{}:{}", pr.id, milestone.title),
+            repo: repo.to_string(),
+            kind: EventKind::PrMilestoneAdded,
+            actor: user_login_or_unknown(None),
+            title: milestone_event_title(milestone, &pr.title),
+            url: pr.html_url.clone(),
+            created_at: updated_at,
+            source_item_id: format!("{}:{}", pr.id, milestone.title),
+            subject_author: pr.user.as_ref().map(|u| u.login.clone()),
+            requested_reviewer: None,
+            mentions: Vec::new(),
+            team_mentions: Vec::new(),
+            subject_number: pr.number,
+            subject_url: Some(pr.html_url.clone()),
+            account: None,
+            body: None,
+        });
+    }
+
+    for issue in issues.iter().filter(|issue| issue.pull_request.is_none()) {
+        let Some(milestone) = issue.milestone.as_ref() else {
+            continue;
+        };
+        let updated_at = issue.updated_at.unwrap_or(issue.created_at);
+        if updated_at <= since {
+            continue;
+        }
+        // The item does not say who set the milestone; a webhook delivery fills it in.
+        events.push(WatchEvent {
+            event_id: format!("issue-milestone:{}:{}", issue.id, milestone.title),
+            repo: repo.to_string(),
+            kind: EventKind::IssueMilestoneAdded,
+            actor: user_login_or_unknown(None),
+            title: milestone_event_title(milestone, &issue.title),
+            url: issue.html_url.clone(),
+            created_at: updated_at,
+            source_item_id: format!("{}:{}", issue.id, milestone.title),
+            subject_author: issue.user.as_ref().map(|u| u.login.clone()),
+            requested_reviewer: None,
+            mentions: Vec::new(),
+            team_mentions: Vec::new(),
+            subject_number: issue.number,
+            subject_url: Some(issue.html_url.clone()),
+            account: None,
+            body: None,
+        });
+    }
+
     events.extend(
         issue_comments
             .iter()
@@ -182,16 +735,14 @@ pub(super) fn normalize_events_from_items(
             })
             .map(|comment| {
                 let body = comment.body.clone().unwrap_or_default();
-                let subject_author = comment
-                    .issue_url
-                    .as_deref()
-                    .and_then(parse_number_from_url)
-                    .and_then(|number| {
-                        pull_author_by_number
-                            .get(&number)
-                            .cloned()
-                            .or_else(|| issue_author_by_number.get(&number).cloned())
-                    });
+                let subject_number = comment.issue_url.as_deref().and_then(parse_number_from_url);
+                let subject_author = subject_number.and_then(|number| {
+                    pull_author_by_number
+                        .get(&number)
+                        .cloned()
+                        .or_else(|| issue_author_by_number.get(&number).cloned())
+                });
+                let mentions = extract_mentions(&body);
                 WatchEvent {
                     event_id: format!("issue-comment:{}", comment.id),
                     repo: repo.to_string(),
@@ -203,7 +754,12 @@ pub(super) fn normalize_events_from_items(
                     source_item_id: comment.id.to_string(),
                     subject_author,
                     requested_reviewer: None,
-                    mentions: extract_mentions(&body),
+                    mentions: mentions.users,
+                    team_mentions: mentions.teams,
+                    subject_number,
+                    subject_url: Some(strip_anchor(&comment.html_url)),
+                    account: None,
+                    body: stored_body(comment.body.as_deref()),
                 }
             }),
     );
@@ -217,13 +773,15 @@ pub(super) fn normalize_events_from_items(
         })
     {
         let body = comment.body.clone().unwrap_or_default();
-        let subject_author = comment
+        let subject_number = comment
             .pull_request_url
             .as_deref()
-            .and_then(parse_number_from_url)
-            .and_then(|number| pull_author_by_number.get(&number).cloned());
+            .and_then(parse_number_from_url);
+        let subject_author =
+            subject_number.and_then(|number| pull_author_by_number.get(&number).cloned());
         let actor = user_login_or_unknown(comment.user.as_ref());
 
+        let mentions = extract_mentions(&body);
         events.push(WatchEvent {
             event_id: format!("review-comment:{}", comment.id),
             repo: repo.to_string(),
@@ -235,7 +793,12 @@ pub(super) fn normalize_events_from_items(
             source_item_id: comment.id.to_string(),
             subject_author: subject_author.clone(),
             requested_reviewer: None,
-            mentions: extract_mentions(&body),
+            mentions: mentions.users.clone(),
+            team_mentions: mentions.teams.clone(),
+            subject_number,
+            subject_url: Some(strip_anchor(&comment.html_url)),
+            account: None,
+            body: stored_body(comment.body.as_deref()),
         });
 
         if let Some(review_id) = comment.pull_request_review_id {
@@ -251,7 +814,12 @@ pub(super) fn normalize_events_from_items(
                     source_item_id: review_id.to_string(),
                     subject_author: subject_author.clone(),
                     requested_reviewer: None,
-                    mentions: extract_mentions(&body),
+                    mentions: mentions.users,
+                    team_mentions: mentions.teams,
+                    subject_number,
+                    subject_url: Some(strip_anchor(&comment.html_url)),
+                    account: None,
+                    body: None,
                 });
             }
         }
@@ -271,6 +839,35 @@ pub(super) fn merge_pulls_by_id(created: Vec<GhPull>, updated: Vec<GhPull>) -> V
     pulls_by_id.into_values().collect()
 }
 
+pub(super) fn merge_issues_by_id(created: Vec<GhIssue>, updated: Vec<GhIssue>) -> Vec<GhIssue> {
+    let mut issues_by_id = HashMap::new();
+    for issue in created {
+        issues_by_id.insert(issue.id, issue);
+    }
+    for issue in updated {
+        issues_by_id.insert(issue.id, issue);
+    }
+    issues_by_id.into_values().collect()
+}
+
+fn milestone_event_title(milestone: &GhMilestone, item_title: &str) -> String {
+    match milestone.due_on {
+        Some(due_on) => format!(
+            "Milestone {} (due {}): {item_title}",
+            milestone.title,
+            due_on.format("%Y-%m-%d")
+        ),
+        None => format!("Milestone {}: {item_title}", milestone.title),
+    }
+}
+
+fn review_title(prefix: &str, pull: Option<&GhPull>) -> String {
+    match pull {
+        Some(pr) => format!("{prefix}: {}", pr.title),
+        None => prefix.to_string(),
+    }
+}
+
 fn title_from_comment(body: Option<&str>, fallback: &str) -> String {
     body.and_then(|b| b.lines().next())
         .map(str::trim)
@@ -279,6 +876,11 @@ fn title_from_comment(body: Option<&str>, fallback: &str) -> String {
         .unwrap_or_else(|| fallback.to_string())
 }
 
+/// Comment or review text kept on the event; blank bodies are dropped.
+fn stored_body(body: Option<&str>) -> Option<String> {
+    body.filter(|b| !b.trim().is_empty()).map(str::to_string)
+}
+
 fn truncate(input: &str, max_chars: usize) -> String {
     if input.chars().count() <= max_chars {
         return input.to_string();
@@ -296,6 +898,14 @@ fn user_login_or_unknown(user: Option<&GhUser>) -> String {
         .unwrap_or_else(|| "unknown".to_string())
 }
 
+/// Issue or pull request page of a comment or review `html_url` such as `.../pull/10#discussion_r41`.
+fn strip_anchor(html_url: &str) -> String {
+    html_url
+        .split_once('#')
+        .map_or(html_url, |(page, _)| page)
+        .to_string()
+}
+
 fn parse_number_from_url(url: &str) -> Option<i64> {
     let tail = url.rsplit('/').next()?;
     let tail = tail.split('?').next().unwrap_or(tail);
@@ -306,29 +916,3 @@ fn references_draft_pull(url: Option<&str>, draft_pull_numbers: &HashSet<i64>) -
     url.and_then(parse_number_from_url)
         .is_some_and(|number| draft_pull_numbers.contains(&number))
 }
-
-fn extract_mentions(text: &str) -> Vec<String> {
-    let mut mentions = Vec::new();
-    let bytes = text.as_bytes();
-    let mut idx = 0usize;
-    while idx < bytes.len() {
-        if bytes[idx] != b'@' {
-            idx += 1;
-            continue;
-        }
-        idx += 1;
-        let start = idx;
-        while idx < bytes.len() {
-            let ch = bytes[idx] as char;
-            if ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' {
-                idx += 1;
-                continue;
-            }
-            break;
-        }
-        if idx > start {
-            mentions.push(text[start..idx].to_string());
-        }
-    }
-    mentions
-}
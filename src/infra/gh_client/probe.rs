@@ -0,0 +1,205 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+/// Oldest gh that supports everything gh-watch calls (`gh api --slurp`).
+pub const MIN_GH_VERSION: GhVersion = GhVersion::new(2, 35, 0);
+
+/// gh features gh-watch relies on, with the release that added them.
+const VERSION_GATED_FEATURES: [(GhVersion, &str); 2] = [
+    (
+        MIN_GH_VERSION,
+        "comment, org member and repository listings (`gh api --slurp`)",
+    ),
+    (
+        GhVersion::new(2, 40, 0),
+        "`repositories[].gh_profile` (`gh auth token --user`)",
+    ),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GhVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl GhVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Reads `gh version 2.62.0 (2024-11-14)`; pre-release suffixes are ignored.
+    pub fn parse(version_line: &str) -> Option<Self> {
+        let raw = version_line.strip_prefix("gh version ")?;
+        let raw = raw.split_whitespace().next()?;
+        let mut parts = raw.split(['.', '-']).map(|part| part.parse::<u32>().ok());
+        Some(Self::new(parts.next()??, parts.next()??, parts.next()??))
+    }
+}
+
+impl fmt::Display for GhVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// The gh binary gh-watch will run, as found by `GhCliClient::probe`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GhProbe {
+    pub path: PathBuf,
+    /// First line of `gh --version`; `None` when it failed.
+    pub version_line: Option<String>,
+}
+
+impl GhProbe {
+    pub fn version(&self) -> Option<GhVersion> {
+        self.version_line.as_deref().and_then(GhVersion::parse)
+    }
+
+    /// Features the detected gh is too old for; empty when the version is unknown.
+    pub fn degraded_features(&self) -> Vec<&'static str> {
+        let Some(version) = self.version() else {
+            return Vec::new();
+        };
+        VERSION_GATED_FEATURES
+            .iter()
+            .filter(|(since, _)| version < *since)
+            .map(|(_, feature)| *feature)
+            .collect()
+    }
+
+    pub fn warning(&self) -> Option<String> {
+        if self.version_line.is_none() {
+            return Some(format!(
+                "could not read the gh version from {}; gh {MIN_GH_VERSION} or newer is required",
+                self.path.display()
+            ));
+        }
+        let version = self.version()?;
+        let degraded = self.degraded_features();
+        (!degraded.is_empty()).then(|| {
+            format!(
+                "gh {version} is too old for: {}; upgrade gh",
+                degraded.join(", ")
+            )
+        })
+    }
+}
+
+impl fmt::Display for GhProbe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.version_line {
+            Some(version_line) => write!(f, "{} ({version_line})", self.path.display()),
+            None => write!(f, "{} (version unknown)", self.path.display()),
+        }
+    }
+}
+
+/// Where `bin` resolves to: itself when it is a path, otherwise the first match on `PATH`.
+pub(super) fn resolve_executable(bin: &Path) -> Option<PathBuf> {
+    if bin.components().count() > 1 {
+        return bin.is_file().then(|| bin.to_path_buf());
+    }
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path).find_map(|dir| {
+        let candidate = dir.join(bin);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        let candidate = candidate.with_extension(std::env::consts::EXE_EXTENSION);
+        (cfg!(windows) && candidate.is_file()).then_some(candidate)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{resolve_executable, GhProbe, GhVersion, MIN_GH_VERSION};
+
+    fn probe(version_line: Option<&str>) -> GhProbe {
+        GhProbe {
+            path: PathBuf::from("/usr/bin/gh"),
+            version_line: version_line.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn parses_release_and_pre_release_versions() {
+        assert_eq!(
+            GhVersion::parse("gh version 2.62.0 (2024-11-14)"),
+            Some(GhVersion::new(2, 62, 0))
+        );
+        assert_eq!(
+            GhVersion::parse("gh version 2.63.0-pre.1 (2024-12-01)"),
+            Some(GhVersion::new(2, 63, 0))
+        );
+        assert_eq!(GhVersion::parse("gh version DEV"), None);
+        assert_eq!(GhVersion::parse("hub version 2.14.2"), None);
+    }
+
+    #[test]
+    fn current_gh_has_no_warning() {
+        let probe = probe(Some("gh version 2.62.0 (2024-11-14)"));
+
+        assert!(probe.degraded_features().is_empty());
+        assert_eq!(probe.warning(), None);
+        assert_eq!(
+            probe.to_string(),
+            "/usr/bin/gh (gh version 2.62.0 (2024-11-14))"
+        );
+    }
+
+    #[test]
+    fn old_gh_lists_every_degraded_feature() {
+        let probe = probe(Some("gh version 2.30.1 (2023-05-30)"));
+
+        assert_eq!(probe.degraded_features().len(), 2);
+        let warning = probe.warning().unwrap();
+        assert!(
+            warning.starts_with("gh 2.30.1 is too old for: "),
+            "{warning}"
+        );
+        assert!(warning.contains("gh api --slurp"), "{warning}");
+        assert!(warning.contains("gh_profile"), "{warning}");
+    }
+
+    #[test]
+    fn gh_between_the_gates_only_loses_the_newer_feature() {
+        let probe = probe(Some("gh version 2.37.0 (2023-10-17)"));
+
+        assert_eq!(
+            probe.degraded_features(),
+            vec!["`repositories[].gh_profile` (`gh auth token --user`)"]
+        );
+        assert!(GhVersion::new(2, 37, 0) >= MIN_GH_VERSION);
+    }
+
+    #[test]
+    fn unreadable_version_warns_without_listing_features() {
+        let probe = probe(None);
+
+        assert!(probe.degraded_features().is_empty());
+        assert!(probe
+            .warning()
+            .unwrap()
+            .starts_with("could not read the gh version from /usr/bin/gh"));
+        assert_eq!(probe.to_string(), "/usr/bin/gh (version unknown)");
+    }
+
+    #[test]
+    fn explicit_paths_must_exist() {
+        let dir = tempfile::tempdir().unwrap();
+        let gh = dir.path().join("gh");
+
+        assert_eq!(resolve_executable(&gh), None);
+        std::fs::write(&gh, "").unwrap();
+        assert_eq!(resolve_executable(&gh), Some(gh));
+    }
+}
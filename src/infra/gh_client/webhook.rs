@@ -0,0 +1,131 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use super::{
+    models::{GhComment, GhIssue, GhPull, GhReview, GhUser},
+    normalize::{normalize_events_from_items, normalize_review_events},
+};
+use crate::domain::events::{EventKind, WatchEvent};
+
+/// Webhook events `normalize_webhook_payload` understands; subscribe the hook to these.
+pub const WEBHOOK_EVENT_NAMES: [&str; 5] = [
+    "pull_request",
+    "issues",
+    "issue_comment",
+    "pull_request_review",
+    "pull_request_review_comment",
+];
+
+#[derive(Debug, Deserialize)]
+struct WebhookRepository {
+    full_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct WebhookPayload {
+    action: String,
+    repository: WebhookRepository,
+    pull_request: Option<GhPull>,
+    issue: Option<GhIssue>,
+    comment: Option<GhComment>,
+    review: Option<GhReview>,
+    requested_reviewer: Option<GhUser>,
+    sender: Option<GhUser>,
+}
+
+/// Events in a webhook delivery of `event_name` (the `X-GitHub-Event` header), shaped and
+/// keyed exactly like the polled ones so a later poll of the same activity is a no-op.
+/// Deliveries of other events or actions yield no events.
+pub fn normalize_webhook_payload(event_name: &str, payload_json: &str) -> Result<Vec<WatchEvent>> {
+    if !WEBHOOK_EVENT_NAMES.contains(&event_name) {
+        return Ok(Vec::new());
+    }
+    let payload: WebhookPayload = serde_json::from_str(payload_json)
+        .with_context(|| format!("invalid {event_name} webhook payload"))?;
+    let repo = payload.repository.full_name.as_str();
+    let since = DateTime::<Utc>::MIN_UTC;
+
+    let sender = payload.sender.map(|user| user.login);
+    let mut events: Vec<WatchEvent> = match (event_name, payload.action.as_str()) {
+        ("pull_request", action) => {
+            let Some(pr) = payload.pull_request else {
+                return Ok(Vec::new());
+            };
+            let kind = match action {
+                "opened" | "ready_for_review" => EventKind::PrCreated,
+                "closed" if pr.merged_at.is_some() => EventKind::PrMerged,
+                "review_requested" => EventKind::PrReviewRequested,
+                "milestoned" => EventKind::PrMilestoneAdded,
+                _ => return Ok(Vec::new()),
+            };
+            let requested = payload.requested_reviewer.map(|user| user.login);
+            normalize_events_from_items(repo, since, vec![pr], Vec::new(), Vec::new(), Vec::new())
+                .into_iter()
+                .filter(|event| event.kind == kind)
+                .filter(|event| {
+                    kind != EventKind::PrReviewRequested || event.requested_reviewer == requested
+                })
+                .collect()
+        }
+        ("issues", action) => {
+            let Some(issue) = payload.issue else {
+                return Ok(Vec::new());
+            };
+            let kind = match action {
+                "opened" => EventKind::IssueCreated,
+                "milestoned" => EventKind::IssueMilestoneAdded,
+                _ => return Ok(Vec::new()),
+            };
+            normalize_events_from_items(
+                repo,
+                since,
+                Vec::new(),
+                vec![issue],
+                Vec::new(),
+                Vec::new(),
+            )
+            .into_iter()
+            .filter(|event| event.kind == kind)
+            .collect()
+        }
+        ("issue_comment", "created") => {
+            let Some(comment) = payload.comment else {
+                return Ok(Vec::new());
+            };
+            let issues = payload.issue.into_iter().collect();
+            // The issue only supplies the parent author; it was announced when opened.
+            normalize_events_from_items(repo, since, Vec::new(), issues, vec![comment], Vec::new())
+                .into_iter()
+                .filter(|event| event.kind == EventKind::IssueCommentCreated)
+                .collect()
+        }
+        ("pull_request_review_comment", "created") => {
+            let Some(comment) = payload.comment else {
+                return Ok(Vec::new());
+            };
+            let pulls = payload.pull_request.into_iter().collect();
+            // The review itself arrives as its own `pull_request_review` delivery.
+            normalize_events_from_items(repo, since, pulls, Vec::new(), Vec::new(), vec![comment])
+                .into_iter()
+                .filter(|event| event.kind == EventKind::PrReviewCommentCreated)
+                .collect()
+        }
+        ("pull_request_review", "submitted" | "dismissed") => {
+            let Some(mut review) = payload.review else {
+                return Ok(Vec::new());
+            };
+            // Webhooks send the state in lower case; the reviews API in upper case.
+            review.state = review.state.to_ascii_uppercase();
+            normalize_review_events(repo, since, vec![review], payload.pull_request.as_ref())
+        }
+        _ => Vec::new(),
+    };
+    // Polled milestone events cannot tell who set the milestone; the delivery can.
+    if let Some(sender) = sender {
+        for event in events.iter_mut().filter(|event| event.kind.is_milestone()) {
+            event.actor = sender.clone();
+        }
+    }
+    Ok(events)
+}
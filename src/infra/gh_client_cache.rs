@@ -0,0 +1,565 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    domain::{
+        events::WatchEvent,
+        subject_state::{SubjectRef, SubjectState},
+    },
+    infra::gh_client::{AccountGhClients, GhCliClient},
+    ports::{
+        AuthInfo, GhClientPort, MyPrFetch, PrFileChange, RepoAccess, RepoEventFetch,
+        ReviewThreadFetch,
+    },
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GhCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+#[derive(Debug)]
+pub struct InMemoryGhCache {
+    ttl: Duration,
+    inner: Mutex<CacheInner>,
+}
+
+#[derive(Debug, Default)]
+struct CacheInner {
+    entries: HashMap<String, (String, Instant)>,
+    stats: GhCacheStats,
+}
+
+impl InMemoryGhCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            inner: Mutex::new(CacheInner::default()),
+        }
+    }
+
+    pub fn get(&self, endpoint: &str, now: Instant) -> Option<String> {
+        let mut inner = self.inner.lock().expect("gh cache lock poisoned");
+        let cached = inner
+            .entries
+            .get(endpoint)
+            .filter(|(_, stored_at)| now.saturating_duration_since(*stored_at) < self.ttl)
+            .map(|(response, _)| response.clone());
+
+        match cached {
+            Some(response) => {
+                inner.stats.hits += 1;
+                Some(response)
+            }
+            None => {
+                inner.entries.remove(endpoint);
+                inner.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Stores `response` and drops expired entries, which keys that never repeat (a `since=`
+    /// in the URL) would otherwise leave behind.
+    pub fn insert(&self, endpoint: &str, response: String, now: Instant) {
+        let mut inner = self.inner.lock().expect("gh cache lock poisoned");
+        inner
+            .entries
+            .retain(|_, (_, stored_at)| now.saturating_duration_since(*stored_at) < self.ttl);
+        inner.entries.insert(endpoint.to_string(), (response, now));
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.inner
+            .lock()
+            .expect("gh cache lock poisoned")
+            .entries
+            .len()
+    }
+
+    pub fn stats(&self) -> GhCacheStats {
+        self.inner.lock().expect("gh cache lock poisoned").stats
+    }
+}
+
+/// Total size the on-disk response cache may grow to before its oldest entries are evicted.
+const DISK_CACHE_MAX_BYTES: u64 = 64 * 1024 * 1024;
+const DISK_CACHE_ENTRY_EXTENSION: &str = "json";
+
+/// `gh api` responses kept on disk across runs, one file per request named after the
+/// SHA-256 of its key so credentials in the key are never written.
+#[derive(Debug)]
+pub struct DiskGhCache {
+    dir: PathBuf,
+    ttl: Duration,
+    max_bytes: u64,
+    /// Size of the entries at the last eviction plus everything written since.
+    size_bytes: Mutex<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DiskCacheEntry {
+    /// Seconds since the Unix epoch.
+    stored_at: u64,
+    response: String,
+}
+
+impl DiskGhCache {
+    pub fn open(dir: impl Into<PathBuf>, ttl: Duration) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("failed to create response cache dir {}", dir.display()))?;
+        let cache = Self {
+            dir,
+            ttl,
+            max_bytes: DISK_CACHE_MAX_BYTES,
+            size_bytes: Mutex::new(0),
+        };
+        cache.evict(SystemTime::now());
+        Ok(cache)
+    }
+
+    pub fn with_max_bytes(self, max_bytes: u64) -> Self {
+        let cache = Self { max_bytes, ..self };
+        cache.evict(SystemTime::now());
+        cache
+    }
+
+    /// The response stored for `key` less than the TTL before `now`; older entries are removed.
+    pub fn get(&self, key: &str, now: SystemTime) -> Option<String> {
+        self.get_entry(key, now).map(|(response, _)| response)
+    }
+
+    /// `get` plus when the response was stored.
+    pub fn get_entry(&self, key: &str, now: SystemTime) -> Option<(String, SystemTime)> {
+        let path = self.entry_path(key);
+        let raw = fs::read(&path).ok()?;
+        let entry = match serde_json::from_slice::<DiskCacheEntry>(&raw) {
+            Ok(entry) => entry,
+            Err(err) => {
+                tracing::debug!(error = %err, path = %path.display(), "dropping unreadable response cache entry");
+                let _ = fs::remove_file(&path);
+                return None;
+            }
+        };
+        let age = unix_seconds(now).saturating_sub(entry.stored_at);
+        if Duration::from_secs(age) >= self.ttl {
+            let _ = fs::remove_file(&path);
+            return None;
+        }
+        Some((
+            entry.response,
+            UNIX_EPOCH + Duration::from_secs(entry.stored_at),
+        ))
+    }
+
+    /// Stores `response` for `key`; failures only cost a later cache miss.
+    pub fn insert(&self, key: &str, response: &str, now: SystemTime) {
+        let entry = DiskCacheEntry {
+            stored_at: unix_seconds(now),
+            response: response.to_string(),
+        };
+        let path = self.entry_path(key);
+        let written = serde_json::to_vec(&entry)
+            .map_err(anyhow::Error::from)
+            .and_then(|raw| write_replacing(&path, &raw).map(|()| raw.len() as u64));
+        let written = match written {
+            Ok(written) => written,
+            Err(err) => {
+                tracing::debug!(error = %err, path = %path.display(), "failed to write response cache entry");
+                return;
+            }
+        };
+
+        let over_limit = {
+            let mut size_bytes = self
+                .size_bytes
+                .lock()
+                .expect("response cache lock poisoned");
+            *size_bytes += written;
+            *size_bytes > self.max_bytes
+        };
+        if over_limit {
+            self.evict(now);
+        }
+    }
+
+    /// Removes expired entries, then the oldest ones until the cache fits in `max_bytes`.
+    fn evict(&self, now: SystemTime) {
+        let mut entries = self.entries();
+        let expired_before = now.checked_sub(self.ttl).unwrap_or(UNIX_EPOCH);
+        entries.retain(|(path, _, modified)| {
+            let expired = *modified < expired_before;
+            if expired {
+                let _ = fs::remove_file(path);
+            }
+            !expired
+        });
+
+        entries.sort_by_key(|(_, _, modified)| *modified);
+        let mut total = entries.iter().map(|(_, len, _)| len).sum::<u64>();
+        for (path, len, _) in &entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            if fs::remove_file(path).is_ok() {
+                total -= len;
+            }
+        }
+        *self
+            .size_bytes
+            .lock()
+            .expect("response cache lock poisoned") = total;
+    }
+
+    /// Path, size and modification time of every entry file.
+    fn entries(&self) -> Vec<(PathBuf, u64, SystemTime)> {
+        let Ok(read_dir) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        read_dir
+            .flatten()
+            .filter(|entry| {
+                entry.path().extension().and_then(|ext| ext.to_str())
+                    == Some(DISK_CACHE_ENTRY_EXTENSION)
+            })
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                Some((entry.path(), metadata.len(), metadata.modified().ok()?))
+            })
+            .collect()
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!(
+            "{:x}.{DISK_CACHE_ENTRY_EXTENSION}",
+            Sha256::digest(key.as_bytes())
+        ))
+    }
+}
+
+fn unix_seconds(at: SystemTime) -> u64 {
+    at.duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs())
+}
+
+/// Writes through a sibling temporary file so concurrent readers never see half an entry.
+fn write_replacing(path: &Path, contents: &[u8]) -> Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, contents)?;
+    fs::rename(&tmp_path, path).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp_path);
+    })?;
+    Ok(())
+}
+
+/// Clients whose `gh api` responses can be served from an `InMemoryGhCache`.
+pub trait CacheableGhClient: GhClientPort {
+    fn with_cache(self, cache: Arc<InMemoryGhCache>) -> Self;
+}
+
+impl CacheableGhClient for GhCliClient {
+    fn with_cache(self, cache: Arc<InMemoryGhCache>) -> Self {
+        GhCliClient::with_cache(self, cache)
+    }
+}
+
+impl CacheableGhClient for AccountGhClients {
+    fn with_cache(self, cache: Arc<InMemoryGhCache>) -> Self {
+        AccountGhClients::with_cache(self, cache)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CachedGhClient<C = GhCliClient> {
+    inner: C,
+    cache: Arc<InMemoryGhCache>,
+}
+
+impl<C> CachedGhClient<C>
+where
+    C: CacheableGhClient,
+{
+    pub fn new(client: C, ttl: Duration) -> Self {
+        let cache = Arc::new(InMemoryGhCache::new(ttl));
+        Self {
+            inner: client.with_cache(Arc::clone(&cache)),
+            cache,
+        }
+    }
+
+    pub fn cache_stats(&self) -> GhCacheStats {
+        self.cache.stats()
+    }
+}
+
+#[async_trait]
+impl<C> GhClientPort for CachedGhClient<C>
+where
+    C: CacheableGhClient,
+{
+    async fn check_auth(&self) -> Result<AuthInfo> {
+        self.inner.check_auth().await
+    }
+
+    async fn viewer_login(&self) -> Result<String> {
+        self.inner.viewer_login().await
+    }
+
+    async fn fetch_repo_events(&self, repo: &str, since: DateTime<Utc>) -> Result<RepoEventFetch> {
+        self.inner.fetch_repo_events(repo, since).await
+    }
+
+    async fn fetch_bootstrap_repo_events(
+        &self,
+        repo: &str,
+        since: DateTime<Utc>,
+    ) -> Result<RepoEventFetch> {
+        self.inner.fetch_bootstrap_repo_events(repo, since).await
+    }
+
+    async fn batch_fetch_repo_events(
+        &self,
+        repos: &[(&str, DateTime<Utc>)],
+    ) -> Result<HashMap<String, RepoEventFetch>> {
+        self.inner.batch_fetch_repo_events(repos).await
+    }
+
+    async fn list_org_members(&self, org: &str) -> Result<Vec<String>> {
+        self.inner.list_org_members(org).await
+    }
+
+    async fn list_team_members(&self, org: &str, team: &str) -> Result<Vec<String>> {
+        self.inner.list_team_members(org, team).await
+    }
+
+    async fn fetch_pr_reviews(
+        &self,
+        repo: &str,
+        pr_number: i64,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<WatchEvent>> {
+        self.inner.fetch_pr_reviews(repo, pr_number, since).await
+    }
+
+    async fn fetch_review_threads(&self, repo: &str) -> Result<ReviewThreadFetch> {
+        self.inner.fetch_review_threads(repo).await
+    }
+
+    async fn fetch_my_open_prs(&self, repos: &[&str]) -> Result<HashMap<String, MyPrFetch>> {
+        self.inner.fetch_my_open_prs(repos).await
+    }
+
+    async fn is_pull_request_open(&self, repo: &str, pr_number: i64) -> Result<bool> {
+        self.inner.is_pull_request_open(repo, pr_number).await
+    }
+
+    async fn fetch_pr_file_changes(&self, repo: &str, pr_number: i64) -> Result<Vec<PrFileChange>> {
+        self.inner.fetch_pr_file_changes(repo, pr_number).await
+    }
+
+    async fn read_gist_file(&self, gist_id: &str, file_name: &str) -> Result<Option<String>> {
+        self.inner.read_gist_file(gist_id, file_name).await
+    }
+
+    async fn write_gist_file(&self, gist_id: &str, file_name: &str, content: &str) -> Result<()> {
+        self.inner
+            .write_gist_file(gist_id, file_name, content)
+            .await
+    }
+
+    async fn search_issues(
+        &self,
+        query: &str,
+        repos: &[&str],
+        limit: usize,
+    ) -> Result<Vec<WatchEvent>> {
+        self.inner.search_issues(query, repos, limit).await
+    }
+
+    fn api_call_count(&self) -> u64 {
+        self.inner.api_call_count()
+    }
+
+    fn response_cache_hits(&self) -> u64 {
+        self.inner.response_cache_hits()
+    }
+
+    async fn rate_limit_remaining(&self) -> Result<Option<u64>> {
+        self.inner.rate_limit_remaining().await
+    }
+
+    async fn server_time(&self) -> Result<Option<DateTime<Utc>>> {
+        self.inner.server_time().await
+    }
+
+    async fn probe_repo_access(&self, repo: &str) -> Result<RepoAccess> {
+        self.inner.probe_repo_access(repo).await
+    }
+
+    async fn fetch_subject_states(
+        &self,
+        subjects: &[SubjectRef],
+    ) -> Result<HashMap<SubjectRef, SubjectState>> {
+        self.inner.fetch_subject_states(subjects).await
+    }
+
+    async fn fetch_repository_topics(&self, repo: &str) -> Result<Vec<String>> {
+        self.inner.fetch_repository_topics(repo).await
+    }
+
+    async fn viewer_teams(&self) -> Result<Vec<String>> {
+        self.inner.viewer_teams().await
+    }
+
+    async fn run_gh_command(&self, repo: &str, args: &[String], cwd: Option<&Path>) -> Result<()> {
+        self.inner.run_gh_command(repo, args, cwd).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant, SystemTime};
+
+    use super::{DiskGhCache, GhCacheStats, InMemoryGhCache};
+
+    #[test]
+    fn repeated_endpoint_within_ttl_returns_cached_response() {
+        let cache = InMemoryGhCache::new(Duration::from_secs(60));
+        let now = Instant::now();
+
+        assert_eq!(cache.get("repos/acme/api/pulls", now), None);
+        cache.insert("repos/acme/api/pulls", "[1]".to_string(), now);
+
+        assert_eq!(
+            cache.get("repos/acme/api/pulls", now + Duration::from_secs(59)),
+            Some("[1]".to_string())
+        );
+        assert_eq!(cache.stats(), GhCacheStats { hits: 1, misses: 1 });
+    }
+
+    #[test]
+    fn expired_entry_is_a_miss() {
+        let cache = InMemoryGhCache::new(Duration::from_secs(60));
+        let now = Instant::now();
+        cache.insert("repos/acme/api/pulls", "[1]".to_string(), now);
+
+        assert_eq!(
+            cache.get("repos/acme/api/pulls", now + Duration::from_secs(60)),
+            None
+        );
+        assert_eq!(cache.stats(), GhCacheStats { hits: 0, misses: 1 });
+    }
+
+    #[test]
+    fn different_endpoints_use_independent_entries() {
+        let cache = InMemoryGhCache::new(Duration::from_secs(60));
+        let now = Instant::now();
+        cache.insert("repos/acme/api/pulls", "[1]".to_string(), now);
+        cache.insert("repos/acme/api/issues", "[2]".to_string(), now);
+
+        assert_eq!(
+            cache.get("repos/acme/api/pulls", now),
+            Some("[1]".to_string())
+        );
+        assert_eq!(
+            cache.get("repos/acme/api/issues", now),
+            Some("[2]".to_string())
+        );
+        assert_eq!(cache.get("repos/acme/web/pulls", now), None);
+    }
+
+    #[test]
+    fn inserting_drops_expired_entries_of_other_endpoints() {
+        let cache = InMemoryGhCache::new(Duration::from_secs(60));
+        let now = Instant::now();
+        cache.insert("comments?since=1", "[1]".to_string(), now);
+        cache.insert(
+            "comments?since=2",
+            "[2]".to_string(),
+            now + Duration::from_secs(30),
+        );
+
+        cache.insert(
+            "comments?since=3",
+            "[3]".to_string(),
+            now + Duration::from_secs(61),
+        );
+
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn disk_entries_expire_after_the_ttl() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskGhCache::open(dir.path(), Duration::from_secs(60)).unwrap();
+        let now = SystemTime::now();
+        cache.insert("repos/acme/api/pulls", "[1]", now);
+
+        assert_eq!(
+            cache.get("repos/acme/api/pulls", now + Duration::from_secs(59)),
+            Some("[1]".to_string())
+        );
+        assert_eq!(
+            cache.get("repos/acme/api/pulls", now + Duration::from_secs(60)),
+            None
+        );
+        // The stale entry was removed, so it stays a miss even for an earlier clock.
+        assert_eq!(cache.get("repos/acme/api/pulls", now), None);
+    }
+
+    #[test]
+    fn disk_entries_survive_reopening_and_keys_are_not_written() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = SystemTime::now();
+        DiskGhCache::open(dir.path(), Duration::from_secs(60))
+            .unwrap()
+            .insert("token:secret\napi user", "octocat", now);
+
+        let reopened = DiskGhCache::open(dir.path(), Duration::from_secs(60)).unwrap();
+        assert_eq!(
+            reopened.get("token:secret\napi user", now),
+            Some("octocat".to_string())
+        );
+        for entry in std::fs::read_dir(dir.path()).unwrap() {
+            let path = entry.unwrap().path();
+            assert!(!path.to_string_lossy().contains("secret"));
+            assert!(!std::fs::read_to_string(&path).unwrap().contains("secret"));
+        }
+    }
+
+    #[test]
+    fn oldest_disk_entries_are_evicted_over_the_size_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskGhCache::open(dir.path(), Duration::from_secs(3600))
+            .unwrap()
+            .with_max_bytes(1024);
+        let response = "x".repeat(300);
+        let now = SystemTime::now();
+        for page in 1..=5 {
+            cache.insert(&format!("page={page}"), &response, now);
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        let total = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().metadata().unwrap().len())
+            .sum::<u64>();
+        assert!(total <= 1024, "{total}");
+        assert_eq!(cache.get("page=1", now), None);
+        assert_eq!(cache.get("page=5", now), Some(response));
+    }
+}
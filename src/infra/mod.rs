@@ -1,3 +1,9 @@
+pub mod config_backup;
+pub mod filter_profile_file;
 pub mod gh_client;
+pub mod gh_client_cache;
 pub mod notifier;
 pub mod state_sqlite;
+pub mod template_download;
+pub mod view_state_file;
+pub mod webhook_server;
@@ -16,6 +16,16 @@ pub(super) enum DesktopBackendKind {
     Noop,
 }
 
+impl DesktopBackendKind {
+    pub(super) fn name(self) -> &'static str {
+        match self {
+            Self::MacOs => "macos (osascript)",
+            Self::WslBurntToast => "wsl (BurntToast)",
+            Self::Noop => "noop",
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(target_os = "macos", allow(dead_code))]
 pub(super) struct LinuxBackendSelection {
@@ -27,6 +27,7 @@ use super::{
 pub struct DesktopNotifier {
     backend: DesktopBackendKind,
     startup_warnings: Vec<String>,
+    body_max_chars: usize,
 }
 
 impl Default for DesktopNotifier {
@@ -36,12 +37,13 @@ impl Default for DesktopNotifier {
 }
 
 impl DesktopNotifier {
-    pub fn from_notification_config(_config: &NotificationConfig) -> Self {
+    pub fn from_notification_config(config: &NotificationConfig) -> Self {
         #[cfg(target_os = "macos")]
         {
             Self {
                 backend: DesktopBackendKind::MacOs,
                 startup_warnings: Vec::new(),
+                body_max_chars: config.body_max_chars,
             }
         }
 
@@ -56,6 +58,7 @@ impl DesktopNotifier {
             Self {
                 backend: selected.kind,
                 startup_warnings,
+                body_max_chars: config.body_max_chars,
             }
         }
 
@@ -64,6 +67,7 @@ impl DesktopNotifier {
             Self {
                 backend: DesktopBackendKind::Noop,
                 startup_warnings: vec![NON_MACOS_NOOP_WARNING.to_string()],
+                body_max_chars: config.body_max_chars,
             }
         }
     }
@@ -71,6 +75,11 @@ impl DesktopNotifier {
     pub fn startup_warnings(&self) -> Vec<String> {
         self.startup_warnings.clone()
     }
+
+    /// The backend picked for this platform, e.g. `noop`.
+    pub fn backend_name(&self) -> &'static str {
+        self.backend.name()
+    }
 }
 
 impl NotifierPort for DesktopNotifier {
@@ -106,7 +115,7 @@ impl NotifierPort for DesktopNotifier {
         include_url: bool,
     ) -> Result<NotificationDispatchResult> {
         let title = build_notification_title_from_payload(payload);
-        let body = build_notification_body_from_payload(payload, include_url);
+        let body = build_notification_body_from_payload(payload, include_url, self.body_max_chars);
 
         match self.backend {
             DesktopBackendKind::MacOs => {
@@ -153,6 +162,7 @@ mod tests {
         let notifier = DesktopNotifier {
             backend: DesktopBackendKind::WslBurntToast,
             startup_warnings: Vec::new(),
+            body_max_chars: 200,
         };
 
         assert_eq!(
@@ -1,10 +1,24 @@
 use crate::{
-    domain::events::WatchEvent,
-    ports::{NotificationDigest, NotificationDispatchResult, NotificationPayload},
+    domain::{events::WatchEvent, markdown::summarize_markdown},
+    ports::{NotificationDigest, NotificationDispatchResult, NotificationPayload, ReviewReminder},
 };
 
-pub fn build_notification_body(event: &WatchEvent, include_url: bool) -> String {
+/// Title and actor, an excerpt of the comment or review body when it has readable text, then
+/// the URL when `include_url`.
+pub fn build_notification_body(
+    event: &WatchEvent,
+    include_url: bool,
+    body_max_chars: usize,
+) -> String {
     let mut lines = vec![format!("{} by @{}", event.title, event.actor)];
+    let excerpt = event
+        .body
+        .as_deref()
+        .map(|body| summarize_markdown(body, body_max_chars))
+        .unwrap_or_default();
+    if !excerpt.is_empty() {
+        lines.push(excerpt);
+    }
     if include_url {
         lines.push(event.url.clone());
     }
@@ -37,20 +51,51 @@ fn build_digest_notification_body(digest: &NotificationDigest, include_url: bool
     lines.join("\n")
 }
 
+fn build_review_reminder_body(reminder: &ReviewReminder, include_url: bool) -> String {
+    let request = &reminder.request;
+    let days = match reminder.waiting_days {
+        1 => "1 day".to_string(),
+        days => format!("{days} days"),
+    };
+    let title = match request.display_number() {
+        Some(number) => format!("#{number} {}", request.title),
+        None => request.title.clone(),
+    };
+    let mut lines = vec![format!("Review requested {days} ago: {title}")];
+    if include_url {
+        lines.push(request.subject_url_or_url().to_string());
+    }
+    lines.join("\n")
+}
+
 pub(super) fn build_notification_title_from_payload(payload: &NotificationPayload) -> String {
     match payload {
         NotificationPayload::Event(event) => build_notification_title(event),
         NotificationPayload::Digest(_) => "gh-watch [digest]".to_string(),
+        NotificationPayload::Alert(_) => "gh-watch [alert]".to_string(),
+        NotificationPayload::Summary(_) => "gh-watch [weekly summary]".to_string(),
+        NotificationPayload::ReviewReminderDue(reminder) => {
+            format!("{} [review reminder]", reminder.request.repo)
+        }
     }
 }
 
 pub(super) fn build_notification_body_from_payload(
     payload: &NotificationPayload,
     include_url: bool,
+    body_max_chars: usize,
 ) -> String {
     match payload {
-        NotificationPayload::Event(event) => build_notification_body(event, include_url),
+        NotificationPayload::Event(event) => {
+            build_notification_body(event, include_url, body_max_chars)
+        }
         NotificationPayload::Digest(digest) => build_digest_notification_body(digest, include_url),
+        NotificationPayload::Alert(message) | NotificationPayload::Summary(message) => {
+            message.clone()
+        }
+        NotificationPayload::ReviewReminderDue(reminder) => {
+            build_review_reminder_body(reminder, include_url)
+        }
     }
 }
 
@@ -70,10 +115,13 @@ mod tests {
 
     use super::{
         build_digest_notification_body, build_notification_body,
-        build_notification_title_from_payload, dispatch_result,
+        build_notification_body_from_payload, build_notification_title_from_payload,
+        dispatch_result,
     };
     use crate::domain::events::{EventKind, WatchEvent};
-    use crate::ports::{NotificationDigest, NotificationDispatchResult, NotificationPayload};
+    use crate::ports::{
+        NotificationDigest, NotificationDispatchResult, NotificationPayload, ReviewReminder,
+    };
 
     fn sample_event() -> WatchEvent {
         WatchEvent {
@@ -88,6 +136,11 @@ mod tests {
             subject_author: Some("alice".to_string()),
             requested_reviewer: None,
             mentions: Vec::new(),
+            team_mentions: Vec::new(),
+            subject_number: None,
+            subject_url: None,
+            account: None,
+            body: None,
         }
     }
 
@@ -100,10 +153,33 @@ mod tests {
 
     #[test]
     fn notification_body_contains_url_when_requested() {
-        let body = build_notification_body(&sample_event(), true);
+        let body = build_notification_body(&sample_event(), true, 200);
         assert!(body.contains("https://example.com/pr/1"));
     }
 
+    #[test]
+    fn notification_body_adds_a_plain_text_excerpt_of_the_comment() {
+        let event = WatchEvent {
+            body: Some(
+                "- [x] Rebased\n\n```diff\n-old\n+new\n```\nPlease **re-review**.".to_string(),
+            ),
+            ..sample_event()
+        };
+        assert_eq!(
+            build_notification_body(&event, true, 200),
+            "Add feature by @alice\nRebased Please re-review.\nhttps://example.com/pr/1"
+        );
+
+        let image_only = WatchEvent {
+            body: Some("![screenshot](https://example.com/a.png)".to_string()),
+            ..sample_event()
+        };
+        assert_eq!(
+            build_notification_body(&image_only, false, 200),
+            "Add feature by @alice"
+        );
+    }
+
     #[test]
     fn digest_notification_title_is_fixed() {
         let payload = NotificationPayload::Digest(sample_digest(2, vec![sample_event()]));
@@ -113,6 +189,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn alert_notification_uses_fixed_title_and_message_body() {
+        let payload = NotificationPayload::Alert("No new events in 2 repos for 4h".to_string());
+        assert_eq!(
+            build_notification_title_from_payload(&payload),
+            "gh-watch [alert]"
+        );
+        assert_eq!(
+            build_notification_body_from_payload(&payload, true, 200),
+            "No new events in 2 repos for 4h"
+        );
+    }
+
+    #[test]
+    fn review_reminder_names_the_repo_and_how_long_the_request_waited() {
+        let payload = NotificationPayload::ReviewReminderDue(Box::new(ReviewReminder {
+            request: WatchEvent {
+                kind: EventKind::PrReviewRequested,
+                subject_number: Some(12),
+                ..sample_event()
+            },
+            waiting_days: 3,
+        }));
+        assert_eq!(
+            build_notification_title_from_payload(&payload),
+            "acme/api [review reminder]"
+        );
+        assert_eq!(
+            build_notification_body_from_payload(&payload, true, 200),
+            "Review requested 3 days ago: #12 Add feature\nhttps://example.com/pr/1"
+        );
+    }
+
     #[test]
     fn digest_notification_body_contains_total_samples_and_remaining_count() {
         let first = WatchEvent {
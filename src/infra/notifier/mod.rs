@@ -4,8 +4,10 @@ mod macos_osascript;
 mod message;
 mod noop;
 mod process_error;
+mod scheduled;
 mod wsl_burnttoast;
 
 pub use desktop::DesktopNotifier;
 pub use message::build_notification_body;
 pub use noop::NoopNotifier;
+pub use scheduled::ScheduledNotifier;
@@ -50,11 +50,16 @@ mod tests {
             subject_author: Some("alice".to_string()),
             requested_reviewer: None,
             mentions: Vec::new(),
+            team_mentions: Vec::new(),
+            subject_number: None,
+            subject_url: None,
+            account: None,
+            body: None,
         };
 
         let notifier = NoopNotifier;
         let result = notifier
-            .notify(&NotificationPayload::Event(event), true)
+            .notify(&NotificationPayload::Event(Box::new(event)), true)
             .expect("notify should succeed");
 
         assert_eq!(result, NotificationDispatchResult::Delivered);
@@ -0,0 +1,255 @@
+use std::sync::Mutex;
+
+use anyhow::Result;
+use chrono::Local;
+
+use crate::{
+    config::NotificationSchedule,
+    ports::{
+        ClockPort, NotificationClickSupport, NotificationDispatchResult, NotificationPayload,
+        NotifierPort,
+    },
+};
+
+/// Wraps a notifier so that outside `[notification_schedule]` notifications are queued
+/// instead of sent; `drain_notification_queue` delivers them once the schedule allows.
+pub struct ScheduledNotifier<N, K> {
+    inner: N,
+    clock: K,
+    schedule: Option<NotificationSchedule>,
+    queue: Mutex<Vec<(NotificationPayload, bool)>>,
+}
+
+impl<N, K> ScheduledNotifier<N, K>
+where
+    N: NotifierPort,
+    K: ClockPort,
+{
+    /// With no schedule every notification is sent straight away.
+    pub fn new(inner: N, clock: K, schedule: Option<NotificationSchedule>) -> Self {
+        Self {
+            inner,
+            clock,
+            schedule,
+            queue: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn queued_count(&self) -> usize {
+        self.queue.lock().map(|queue| queue.len()).unwrap_or(0)
+    }
+
+    fn is_open(&self) -> bool {
+        self.schedule
+            .as_ref()
+            .is_none_or(|schedule| schedule.allows(&self.clock.now().with_timezone(&Local)))
+    }
+}
+
+impl<N, K> NotifierPort for ScheduledNotifier<N, K>
+where
+    N: NotifierPort,
+    K: ClockPort,
+{
+    fn check_health(&self) -> Result<()> {
+        self.inner.check_health()
+    }
+
+    fn click_action_support(&self) -> NotificationClickSupport {
+        self.inner.click_action_support()
+    }
+
+    fn notify(
+        &self,
+        payload: &NotificationPayload,
+        include_url: bool,
+    ) -> Result<NotificationDispatchResult> {
+        if self.is_open() {
+            return self.inner.notify(payload, include_url);
+        }
+        self.queue
+            .lock()
+            .map_err(|_| anyhow::anyhow!("notification queue lock poisoned"))?
+            .push((payload.clone(), include_url));
+        Ok(NotificationDispatchResult::Delivered)
+    }
+
+    fn drain_notification_queue(&self) -> Result<usize> {
+        if !self.is_open() {
+            return Ok(0);
+        }
+        let queued = std::mem::take(
+            &mut *self
+                .queue
+                .lock()
+                .map_err(|_| anyhow::anyhow!("notification queue lock poisoned"))?,
+        );
+        let mut sent = 0;
+        let mut pending = queued.into_iter();
+        while let Some((payload, include_url)) = pending.next() {
+            if let Err(err) = self.inner.notify(&payload, include_url) {
+                // Keep what was not delivered for the next drain.
+                let mut queue = self
+                    .queue
+                    .lock()
+                    .map_err(|_| anyhow::anyhow!("notification queue lock poisoned"))?;
+                let newer = std::mem::take(&mut *queue);
+                queue.push((payload, include_url));
+                queue.extend(pending);
+                queue.extend(newer);
+                return Err(err);
+            }
+            sent += 1;
+        }
+        Ok(sent)
+    }
+
+    fn is_notification_suppressed(&self) -> bool {
+        !self.is_open()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use anyhow::Result;
+    use chrono::{DateTime, Local, TimeZone, Utc, Weekday};
+
+    use crate::{
+        config::NotificationSchedule,
+        ports::{
+            ClockPort, NotificationClickSupport, NotificationDispatchResult, NotificationPayload,
+            NotifierPort,
+        },
+    };
+
+    use super::ScheduledNotifier;
+
+    struct TestClock(Mutex<DateTime<Utc>>);
+
+    impl TestClock {
+        /// 2025-01-06 is a Monday.
+        fn at_local(day: u32, hour: u32) -> Self {
+            Self(Mutex::new(local(day, hour)))
+        }
+
+        fn set(&self, day: u32, hour: u32) {
+            *self.0.lock().unwrap() = local(day, hour);
+        }
+    }
+
+    impl ClockPort for &TestClock {
+        fn now(&self) -> DateTime<Utc> {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    fn local(day: u32, hour: u32) -> DateTime<Utc> {
+        Local
+            .with_ymd_and_hms(2025, 1, day, hour, 30, 0)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[derive(Default)]
+    struct RecordingNotifier {
+        sent: Mutex<Vec<NotificationPayload>>,
+    }
+
+    impl NotifierPort for &RecordingNotifier {
+        fn check_health(&self) -> Result<()> {
+            Ok(())
+        }
+
+        fn click_action_support(&self) -> NotificationClickSupport {
+            NotificationClickSupport::Unsupported
+        }
+
+        fn notify(
+            &self,
+            payload: &NotificationPayload,
+            _include_url: bool,
+        ) -> Result<NotificationDispatchResult> {
+            self.sent.lock().unwrap().push(payload.clone());
+            Ok(NotificationDispatchResult::Delivered)
+        }
+    }
+
+    fn weekdays_nine_to_six() -> NotificationSchedule {
+        NotificationSchedule {
+            enabled_days: vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+            ],
+            enabled_hours: 9..=18,
+        }
+    }
+
+    fn alert(text: &str) -> NotificationPayload {
+        NotificationPayload::Alert(text.to_string())
+    }
+
+    #[test]
+    fn within_the_schedule_notifications_go_straight_through() {
+        let inner = RecordingNotifier::default();
+        let clock = TestClock::at_local(6, 9);
+        let notifier = ScheduledNotifier::new(&inner, &clock, Some(weekdays_nine_to_six()));
+
+        notifier.notify(&alert("a"), true).unwrap();
+
+        assert!(!notifier.is_notification_suppressed());
+        assert_eq!(inner.sent.lock().unwrap().len(), 1);
+        assert_eq!(notifier.queued_count(), 0);
+    }
+
+    #[test]
+    fn off_hours_notifications_wait_for_the_schedule_to_open() {
+        let inner = RecordingNotifier::default();
+        let clock = TestClock::at_local(6, 19);
+        let notifier = ScheduledNotifier::new(&inner, &clock, Some(weekdays_nine_to_six()));
+
+        notifier.notify(&alert("a"), true).unwrap();
+        notifier.notify(&alert("b"), true).unwrap();
+
+        assert!(notifier.is_notification_suppressed());
+        assert_eq!(notifier.drain_notification_queue().unwrap(), 0);
+        assert!(inner.sent.lock().unwrap().is_empty());
+
+        clock.set(7, 8);
+        assert_eq!(notifier.drain_notification_queue().unwrap(), 0);
+
+        clock.set(7, 9);
+        assert_eq!(notifier.drain_notification_queue().unwrap(), 2);
+        assert_eq!(*inner.sent.lock().unwrap(), vec![alert("a"), alert("b")]);
+        assert_eq!(notifier.queued_count(), 0);
+    }
+
+    #[test]
+    fn disabled_days_hold_notifications_all_day() {
+        let inner = RecordingNotifier::default();
+        // Saturday, within the enabled hours.
+        let clock = TestClock::at_local(11, 12);
+        let notifier = ScheduledNotifier::new(&inner, &clock, Some(weekdays_nine_to_six()));
+
+        notifier.notify(&alert("a"), true).unwrap();
+
+        assert!(notifier.is_notification_suppressed());
+        assert_eq!(notifier.queued_count(), 1);
+    }
+
+    #[test]
+    fn without_a_schedule_nothing_is_held_back() {
+        let inner = RecordingNotifier::default();
+        let clock = TestClock::at_local(11, 3);
+        let notifier = ScheduledNotifier::new(&inner, &clock, None);
+
+        notifier.notify(&alert("a"), true).unwrap();
+
+        assert!(!notifier.is_notification_suppressed());
+        assert_eq!(inner.sent.lock().unwrap().len(), 1);
+    }
+}
@@ -1,18 +1,65 @@
-use std::{collections::HashSet, fs, path::Path, sync::Mutex};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fs,
+    io::{BufRead, BufReader, Read, Write},
+    path::Path,
+    sync::Mutex,
+};
 
 use anyhow::{anyhow, Context, Result};
 use chrono::{DateTime, Duration, Utc};
-use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use futures_util::{stream, Stream};
+use rusqlite::{params, params_from_iter, types::Value, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    domain::events::WatchEvent,
+    domain::{
+        events::{EventKind, WatchEvent, EVENT_KEY_VERSION},
+        failure::FailureRecord,
+        subject_state::{SubjectRef, SubjectState},
+    },
     ports::{
-        CursorPort, PersistBatchResult, RepoBatchPort, RepoPersistBatch, RetentionPort,
-        TimelineQueryPort, TimelineReadMarkPort,
+        CursorPort, EventStreamPort, FailureLogPort, PendingReviewRequests, PersistBatchResult,
+        PrFileChange, ReadSyncStatePort, ReminderStatePort, RepoAccessMark, RepoBatchPort,
+        RepoPersistBatch, RepoReadCounts, RetentionPolicy, RetentionPort, SubjectStatePort,
+        SummaryStatePort, TimelineQueryPort, TimelineReadMarkPort, ViewerTeamsPort,
+        WatchEventStreamConfig,
     },
 };
 
-const SCHEMA_VERSION: &str = "3";
+/// Per-repository tables whose rows follow a renamed repository.
+const REPO_SCOPED_TABLES: [&str; 6] = [
+    "repo_item_milestones",
+    "review_thread_states",
+    "my_pr_states",
+    "review_reminders",
+    "review_request_states",
+    "subject_states",
+];
+
+/// `notification_marks` row for `[notifications.summary]`.
+const WEEKLY_SUMMARY_MARK: &str = "weekly_summary";
+/// `reviewDecision` and `statusCheckRollup.state` values that make a PR ready to merge.
+const MY_PR_APPROVED: &str = "APPROVED";
+const MY_PR_CHECKS_SUCCESS: &str = "SUCCESS";
+
+const SCHEMA_VERSION: &str = "21";
+/// Rows read at a time while `watch_event_stream` catches up.
+const EVENT_STREAM_PAGE_SIZE: usize = 500;
+/// How long a connection waits for another process's write lock, e.g. `stream` next to `watch`.
+const BUSY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// `kind, repo, failed_at, message, context_json` from `failure_events`.
+type FailureRow = (String, String, String, String, Option<String>);
+const COMPRESSED_PAYLOAD_MARKER: &[u8] = b"zstd1:";
+const PAYLOAD_COMPRESSION_LEVEL: i32 = 3;
+
+/// `files_json` of `event_log_v2`: the changed files of the event's pull request.
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedPrFiles {
+    pr_number: i64,
+    files: Vec<PrFileChange>,
+}
 
 #[derive(Debug)]
 pub struct StateSchemaMismatchError {
@@ -39,8 +86,59 @@ impl std::fmt::Display for StateSchemaMismatchError {
 
 impl std::error::Error for StateSchemaMismatchError {}
 
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StateCheckReport {
+    pub integrity_errors: Vec<String>,
+    pub unparsable_event_keys: Vec<String>,
+    pub orphaned_flag_event_keys: Vec<String>,
+    pub stale_cursor_repos: Vec<String>,
+    /// `<key>: <stored key>, <stored key>…` for distinct events whose payloads produce the same key.
+    pub event_key_collisions: Vec<String>,
+    /// Stored keys of extra copies of an event whose payload is also stored under another key.
+    pub orphaned_duplicate_event_keys: Vec<String>,
+}
+
+impl StateCheckReport {
+    pub fn is_clean(&self) -> bool {
+        self.integrity_errors.is_empty()
+            && self.unparsable_event_keys.is_empty()
+            && self.orphaned_flag_event_keys.is_empty()
+            && self.stale_cursor_repos.is_empty()
+            && self.event_key_collisions.is_empty()
+            && self.orphaned_duplicate_event_keys.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub total_read: usize,
+    pub inserted: usize,
+    pub skipped_duplicate: usize,
+    pub failed: usize,
+}
+
+/// When a notified event was created on GitHub, logged, and notified about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotificationTiming {
+    pub created_at: DateTime<Utc>,
+    pub observed_at: DateTime<Utc>,
+    pub notified_at: DateTime<Utc>,
+}
+
 pub struct SqliteStateStore {
     conn: Mutex<Connection>,
+    event_stream: WatchEventStreamConfig,
+}
+
+/// Where a `watch_event_stream` has read up to.
+struct EventStreamCursor {
+    /// Highest rowid when the stream started; older rows are replayed only when observed
+    /// at or after `since`. Read on the first poll.
+    backlog_end: Option<i64>,
+    last_rowid: i64,
+    caught_up: bool,
+    pending: VecDeque<WatchEvent>,
+    interval: Option<tokio::time::Interval>,
 }
 
 impl SqliteStateStore {
@@ -54,23 +152,33 @@ impl SqliteStateStore {
         let conn = Connection::open(path)
             .with_context(|| format!("failed to open sqlite db: {}", path.display()))?;
         conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
 
         Self::ensure_schema(path, &conn)?;
         Ok(Self {
             conn: Mutex::new(conn),
+            event_stream: WatchEventStreamConfig::default(),
         })
     }
 
+    pub fn with_event_stream_config(mut self, config: WatchEventStreamConfig) -> Self {
+        self.event_stream = config;
+        self
+    }
+
     fn ensure_schema(path: &Path, conn: &Connection) -> Result<()> {
         if !Self::has_non_internal_tables(conn)? {
-            Self::init_schema_v3(conn)?;
+            Self::init_schema(conn)?;
             return Ok(());
         }
 
+        Self::migrate_schema(conn)?;
+
         if !Self::has_compatible_schema(conn)? {
             return Err(StateSchemaMismatchError::new(path).into());
         }
 
+        Self::rekey_events(conn)?;
         Ok(())
     }
 
@@ -105,23 +213,51 @@ SELECT EXISTS(
         Ok(exists == 1)
     }
 
-    fn has_compatible_schema(conn: &Connection) -> Result<bool> {
+    fn column_exists(conn: &Connection, table: &str, column: &str) -> Result<bool> {
+        let exists: i64 = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM pragma_table_info(?1) WHERE name = ?2)",
+            params![table, column],
+            |row| row.get(0),
+        )?;
+        Ok(exists == 1)
+    }
+
+    fn schema_version(conn: &Connection) -> Result<Option<String>> {
         if !Self::table_exists(conn, "schema_meta")? {
-            return Ok(false);
+            return Ok(None);
         }
 
-        let version: Option<String> = conn
+        let version = conn
             .query_row(
                 "SELECT value FROM schema_meta WHERE key = 'schema_version'",
                 [],
                 |row| row.get(0),
             )
             .optional()?;
-        if version.as_deref() != Some(SCHEMA_VERSION) {
+        Ok(version)
+    }
+
+    fn has_compatible_schema(conn: &Connection) -> Result<bool> {
+        if Self::schema_version(conn)?.as_deref() != Some(SCHEMA_VERSION) {
             return Ok(false);
         }
 
-        for table in ["polling_cursors_v2", "event_log_v2"] {
+        for table in [
+            "polling_cursors_v2",
+            "event_log_v2",
+            "event_search",
+            "repo_item_milestones",
+            "failure_events",
+            "review_thread_states",
+            "notification_marks",
+            "my_pr_states",
+            "review_reminders",
+            "read_sync_marks",
+            "review_request_states",
+            "repo_access_marks",
+            "subject_states",
+            "viewer_teams",
+        ] {
             if !Self::table_exists(conn, table)? {
                 return Ok(false);
             }
@@ -130,202 +266,2315 @@ SELECT EXISTS(
         Ok(true)
     }
 
-    fn init_schema_v3(conn: &Connection) -> Result<()> {
+    fn migrate_schema(conn: &Connection) -> Result<()> {
+        if !Self::table_exists(conn, "event_log_v2")? {
+            return Ok(());
+        }
+
+        if Self::schema_version(conn)?.as_deref() == Some("3") {
+            Self::migrate_v3_to_v4(conn)?;
+        }
+        if Self::schema_version(conn)?.as_deref() == Some("4") {
+            Self::migrate_v4_to_v5(conn)?;
+        }
+        if Self::schema_version(conn)?.as_deref() == Some("5") {
+            Self::migrate_v5_to_v6(conn)?;
+        }
+        if Self::schema_version(conn)?.as_deref() == Some("6") {
+            Self::migrate_v6_to_v7(conn)?;
+        }
+        if Self::schema_version(conn)?.as_deref() == Some("7") {
+            Self::migrate_v7_to_v8(conn)?;
+        }
+        if Self::schema_version(conn)?.as_deref() == Some("8") {
+            Self::migrate_v8_to_v9(conn)?;
+        }
+        if Self::schema_version(conn)?.as_deref() == Some("9") {
+            Self::migrate_v9_to_v10(conn)?;
+        }
+        if Self::schema_version(conn)?.as_deref() == Some("10") {
+            Self::migrate_v10_to_v11(conn)?;
+        }
+        if Self::schema_version(conn)?.as_deref() == Some("11") {
+            Self::migrate_v11_to_v12(conn)?;
+        }
+        if Self::schema_version(conn)?.as_deref() == Some("12") {
+            Self::migrate_v12_to_v13(conn)?;
+        }
+        if Self::schema_version(conn)?.as_deref() == Some("13") {
+            Self::migrate_v13_to_v14(conn)?;
+        }
+        if Self::schema_version(conn)?.as_deref() == Some("14") {
+            Self::migrate_v14_to_v15(conn)?;
+        }
+        if Self::schema_version(conn)?.as_deref() == Some("15") {
+            Self::migrate_v15_to_v16(conn)?;
+        }
+        if Self::schema_version(conn)?.as_deref() == Some("16") {
+            Self::migrate_v16_to_v17(conn)?;
+        }
+        if Self::schema_version(conn)?.as_deref() == Some("17") {
+            Self::migrate_v17_to_v18(conn)?;
+        }
+        if Self::schema_version(conn)?.as_deref() == Some("18") {
+            Self::migrate_v18_to_v19(conn)?;
+        }
+        if Self::schema_version(conn)?.as_deref() == Some("19") {
+            Self::migrate_v19_to_v20(conn)?;
+        }
+        if Self::schema_version(conn)?.as_deref() == Some("20") {
+            Self::migrate_v20_to_v21(conn)?;
+        }
+        Ok(())
+    }
+
+    fn migrate_v3_to_v4(conn: &Connection) -> Result<()> {
         conn.execute_batch(
             "
-CREATE TABLE IF NOT EXISTS schema_meta (
-  key TEXT PRIMARY KEY,
-  value TEXT NOT NULL
-);
+BEGIN;
 
-CREATE TABLE IF NOT EXISTS polling_cursors_v2 (
-  repo TEXT PRIMARY KEY,
-  last_polled_at TEXT NOT NULL
-);
+ALTER TABLE event_log_v2 ADD COLUMN kind TEXT NOT NULL DEFAULT '';
 
-CREATE TABLE IF NOT EXISTS event_log_v2 (
-  event_key TEXT PRIMARY KEY,
-  repo TEXT NOT NULL,
-  payload_json TEXT NOT NULL,
-  created_at TEXT NOT NULL,
-  observed_at TEXT NOT NULL,
-  delivered_at TEXT,
-  read_at TEXT
-);
+-- Corrupt payloads keep an empty kind and are left for `state check` to report.
+UPDATE event_log_v2
+SET kind = COALESCE(json_extract(payload_json, '$.kind'), '')
+WHERE json_valid(payload_json);
 
-CREATE INDEX IF NOT EXISTS idx_event_log_v2_created_at
-ON event_log_v2 (created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_event_log_v2_kind_created_at
+ON event_log_v2 (kind, created_at);
+
+UPDATE schema_meta SET value = '4' WHERE key = 'schema_version';
+
+COMMIT;
 ",
         )?;
+        Ok(())
+    }
 
-        conn.execute(
+    fn migrate_v4_to_v5(conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(
             "
-INSERT INTO schema_meta (key, value)
-VALUES ('schema_version', ?1)
-ON CONFLICT(key) DO UPDATE SET value = excluded.value
+CREATE VIRTUAL TABLE IF NOT EXISTS event_search
+USING fts5(event_key UNINDEXED, repo, actor, title, body);
 ",
-            params![SCHEMA_VERSION],
         )?;
 
+        let payloads = {
+            let mut stmt = tx.prepare("SELECT payload_json FROM event_log_v2")?;
+            let rows = stmt.query_map([], |row| row.get::<_, Value>(0))?;
+            rows.collect::<rusqlite::Result<Vec<_>>>()?
+        };
+        // Rows that no longer parse stay unindexed for `state check` to report.
+        for payload in payloads {
+            let Ok(event) = Self::parse_watch_event_payload(payload) else {
+                continue;
+            };
+            Self::index_event_for_search(&tx, &event)?;
+        }
+
+        tx.execute(
+            "UPDATE schema_meta SET value = '5' WHERE key = 'schema_version'",
+            [],
+        )?;
+        tx.commit()?;
         Ok(())
     }
 
-    fn parse_watch_event_payload(payload: String) -> Result<WatchEvent> {
-        Ok(serde_json::from_str(&payload)?)
-    }
-}
+    fn migrate_v5_to_v6(conn: &Connection) -> Result<()> {
+        conn.execute_batch(
+            "
+BEGIN;
 
-impl CursorPort for SqliteStateStore {
-    fn get_cursor(&self, repo: &str) -> Result<Option<DateTime<Utc>>> {
-        let conn = self.conn.lock().expect("sqlite mutex poisoned");
-        let value: Option<String> = conn
-            .query_row(
-                "SELECT last_polled_at FROM polling_cursors_v2 WHERE repo = ?1",
-                params![repo],
-                |row| row.get(0),
-            )
-            .optional()?;
+CREATE TABLE IF NOT EXISTS repo_item_milestones (
+  repo TEXT NOT NULL,
+  item_id TEXT NOT NULL,
+  milestone_title TEXT NOT NULL,
+  observed_at TEXT NOT NULL,
+  PRIMARY KEY (repo, item_id)
+);
 
-        value
-            .map(|v| DateTime::parse_from_rfc3339(&v).map(|dt| dt.with_timezone(&Utc)))
-            .transpose()
-            .map_err(Into::into)
+UPDATE schema_meta SET value = '6' WHERE key = 'schema_version';
+
+COMMIT;
+",
+        )?;
+        Ok(())
     }
 
-    fn set_cursor(&self, repo: &str, at: DateTime<Utc>) -> Result<()> {
-        let conn = self.conn.lock().expect("sqlite mutex poisoned");
-        conn.execute(
+    fn migrate_v6_to_v7(conn: &Connection) -> Result<()> {
+        conn.execute_batch(
             "
-INSERT INTO polling_cursors_v2 (repo, last_polled_at)
-VALUES (?1, ?2)
-ON CONFLICT(repo) DO UPDATE SET last_polled_at = excluded.last_polled_at
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS failure_events (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  kind TEXT NOT NULL,
+  repo TEXT NOT NULL,
+  failed_at TEXT NOT NULL,
+  message TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_failure_events_repo_failed_at
+ON failure_events (repo, failed_at);
+
+UPDATE schema_meta SET value = '7' WHERE key = 'schema_version';
+
+COMMIT;
 ",
-            params![repo, at.to_rfc3339()],
         )?;
         Ok(())
     }
-}
 
-impl TimelineQueryPort for SqliteStateStore {
-    fn load_timeline_events(&self, limit: usize) -> Result<Vec<WatchEvent>> {
-        let conn = self.conn.lock().expect("sqlite mutex poisoned");
-        let mut stmt = conn.prepare(
+    fn migrate_v7_to_v8(conn: &Connection) -> Result<()> {
+        conn.execute_batch(
             "
-SELECT payload_json
-FROM event_log_v2
-ORDER BY created_at DESC
-LIMIT ?1
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS review_thread_states (
+  repo TEXT NOT NULL,
+  thread_id TEXT NOT NULL,
+  is_resolved INTEGER NOT NULL,
+  observed_at TEXT NOT NULL,
+  PRIMARY KEY (repo, thread_id)
+);
+
+UPDATE schema_meta SET value = '8' WHERE key = 'schema_version';
+
+COMMIT;
 ",
         )?;
-
-        let rows = stmt.query_map(params![limit as i64], |row| row.get::<_, String>(0))?;
-        rows.map(|row| Self::parse_watch_event_payload(row?))
-            .collect::<Result<Vec<_>>>()
+        Ok(())
     }
 
-    fn load_read_event_keys(&self, event_keys: &[String]) -> Result<HashSet<String>> {
-        if event_keys.is_empty() {
-            return Ok(HashSet::new());
-        }
+    fn migrate_v8_to_v9(conn: &Connection) -> Result<()> {
+        conn.execute_batch(
+            "
+BEGIN;
 
-        let conn = self.conn.lock().expect("sqlite mutex poisoned");
-        let mut read_keys = HashSet::new();
+ALTER TABLE failure_events ADD COLUMN context_json TEXT;
 
-        for keys in event_keys.chunks(900) {
-            let placeholders = vec!["?"; keys.len()].join(", ");
-            let sql = format!(
-                "
-SELECT event_key
-FROM event_log_v2
-WHERE read_at IS NOT NULL
-  AND event_key IN ({placeholders})
-"
-            );
-            let mut stmt = conn.prepare(&sql)?;
-            let rows = stmt.query_map(params_from_iter(keys.iter()), |row| row.get(0))?;
-            for row in rows {
-                read_keys.insert(row?);
-            }
-        }
-        Ok(read_keys)
+UPDATE schema_meta SET value = '9' WHERE key = 'schema_version';
+
+COMMIT;
+",
+        )?;
+        Ok(())
     }
-}
 
-impl TimelineReadMarkPort for SqliteStateStore {
-    fn mark_timeline_event_read(&self, event_key: &str, read_at: DateTime<Utc>) -> Result<()> {
-        let conn = self.conn.lock().expect("sqlite mutex poisoned");
-        conn.execute(
+    fn migrate_v9_to_v10(conn: &Connection) -> Result<()> {
+        conn.execute_batch(
             "
-UPDATE event_log_v2
-SET read_at = COALESCE(read_at, ?2)
-WHERE event_key = ?1
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS notification_marks (
+  name TEXT PRIMARY KEY,
+  sent_at TEXT NOT NULL
+);
+
+UPDATE schema_meta SET value = '10' WHERE key = 'schema_version';
+
+COMMIT;
 ",
-            params![event_key, read_at.to_rfc3339()],
         )?;
         Ok(())
     }
-}
 
-impl RetentionPort for SqliteStateStore {
-    fn cleanup_old(&self, retention_days: u32, now: DateTime<Utc>) -> Result<()> {
-        let cutoff = now - Duration::days(retention_days as i64);
-        let conn = self.conn.lock().expect("sqlite mutex poisoned");
-        conn.execute(
-            "DELETE FROM event_log_v2 WHERE created_at < ?1",
-            params![cutoff.to_rfc3339()],
+    fn migrate_v10_to_v11(conn: &Connection) -> Result<()> {
+        conn.execute_batch(
+            "
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS my_pr_states (
+  repo TEXT NOT NULL,
+  pr_number INTEGER NOT NULL,
+  review_decision TEXT,
+  checks_state TEXT,
+  observed_at TEXT NOT NULL,
+  PRIMARY KEY (repo, pr_number)
+);
+
+UPDATE schema_meta SET value = '11' WHERE key = 'schema_version';
+
+COMMIT;
+",
         )?;
         Ok(())
     }
-}
 
-impl RepoBatchPort for SqliteStateStore {
-    fn persist_repo_batch(&self, batch: &RepoPersistBatch) -> Result<PersistBatchResult> {
-        let mut conn = self.conn.lock().expect("sqlite mutex poisoned");
-        let tx = conn.transaction()?;
-        tx.execute(
+    fn migrate_v11_to_v12(conn: &Connection) -> Result<()> {
+        conn.execute_batch(
             "
-INSERT INTO polling_cursors_v2 (repo, last_polled_at)
-VALUES (?1, ?2)
-ON CONFLICT(repo) DO UPDATE SET last_polled_at = excluded.last_polled_at
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS review_reminders (
+  repo TEXT NOT NULL,
+  pr_number INTEGER NOT NULL,
+  reminded_at TEXT NOT NULL,
+  PRIMARY KEY (repo, pr_number)
+);
+
+UPDATE schema_meta SET value = '12' WHERE key = 'schema_version';
+
+COMMIT;
 ",
-            params![batch.repo, batch.poll_started_at.to_rfc3339()],
         )?;
+        Ok(())
+    }
 
-        let mut result = PersistBatchResult::default();
-        for event in &batch.events {
-            if event.repo != batch.repo {
-                return Err(anyhow!(
-                    "repo batch mismatch: batch repo={} event repo={}",
-                    batch.repo,
-                    event.repo
-                ));
-            }
+    fn migrate_v12_to_v13(conn: &Connection) -> Result<()> {
+        conn.execute_batch(
+            "
+BEGIN;
 
-            let event_key = event.event_key();
-            let payload = serde_json::to_string(event)?;
-            let inserted = tx.execute(
-                "
-INSERT OR IGNORE INTO event_log_v2
-  (event_key, repo, payload_json, created_at, observed_at, delivered_at, read_at)
-VALUES
-  (?1, ?2, ?3, ?4, ?5, ?6, NULL)
+CREATE TABLE IF NOT EXISTS read_sync_marks (
+  event_key TEXT PRIMARY KEY,
+  read_at TEXT NOT NULL
+);
+
+UPDATE schema_meta SET value = '13' WHERE key = 'schema_version';
+
+COMMIT;
 ",
-                params![
-                    event_key,
-                    event.repo,
-                    payload,
-                    event.created_at.to_rfc3339(),
-                    batch.poll_started_at.to_rfc3339(),
-                    batch.poll_started_at.to_rfc3339(),
-                ],
-            )?;
+        )?;
+        Ok(())
+    }
 
-            if inserted == 1 {
-                result.newly_logged_event_keys.push(event_key.clone());
-            }
-        }
+    fn migrate_v13_to_v14(conn: &Connection) -> Result<()> {
+        conn.execute_batch(
+            "
+BEGIN;
 
-        tx.commit()?;
-        Ok(result)
+CREATE TABLE IF NOT EXISTS review_request_states (
+  repo TEXT NOT NULL,
+  request_id TEXT NOT NULL,
+  observed_at TEXT NOT NULL,
+  PRIMARY KEY (repo, request_id)
+);
+
+UPDATE schema_meta SET value = '14' WHERE key = 'schema_version';
+
+COMMIT;
+",
+        )?;
+        Ok(())
+    }
+
+    fn migrate_v14_to_v15(conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        if !Self::column_exists(&tx, "event_log_v2", "files_json")? {
+            tx.execute_batch("ALTER TABLE event_log_v2 ADD COLUMN files_json TEXT;")?;
+        }
+        tx.execute(
+            "UPDATE schema_meta SET value = '15' WHERE key = 'schema_version'",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn migrate_v15_to_v16(conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        if !Self::column_exists(&tx, "event_log_v2", "notified_at")? {
+            tx.execute_batch("ALTER TABLE event_log_v2 ADD COLUMN notified_at TEXT;")?;
+        }
+        tx.execute(
+            "UPDATE schema_meta SET value = '16' WHERE key = 'schema_version'",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Rows logged before `key_version` existed used the first key format.
+    fn migrate_v16_to_v17(conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        if !Self::column_exists(&tx, "event_log_v2", "key_version")? {
+            tx.execute_batch(
+                "ALTER TABLE event_log_v2 ADD COLUMN key_version INTEGER NOT NULL DEFAULT 1;",
+            )?;
+        }
+        tx.execute(
+            "UPDATE schema_meta SET value = '17' WHERE key = 'schema_version'",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn migrate_v17_to_v18(conn: &Connection) -> Result<()> {
+        conn.execute_batch(
+            "
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS repo_access_marks (
+  repo TEXT PRIMARY KEY,
+  new_name TEXT,
+  status_code INTEGER,
+  marked_at TEXT NOT NULL
+);
+
+UPDATE schema_meta SET value = '18' WHERE key = 'schema_version';
+
+COMMIT;
+",
+        )?;
+        Ok(())
+    }
+
+    fn migrate_v18_to_v19(conn: &Connection) -> Result<()> {
+        conn.execute_batch(
+            "
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS subject_states (
+  repo TEXT NOT NULL,
+  number INTEGER NOT NULL,
+  state TEXT NOT NULL,
+  fetched_at TEXT NOT NULL,
+  PRIMARY KEY (repo, number)
+);
+
+UPDATE schema_meta SET value = '19' WHERE key = 'schema_version';
+
+COMMIT;
+",
+        )?;
+        Ok(())
+    }
+
+    fn migrate_v19_to_v20(conn: &Connection) -> Result<()> {
+        conn.execute_batch(
+            "
+BEGIN;
+
+CREATE TABLE IF NOT EXISTS viewer_teams (
+  id INTEGER PRIMARY KEY CHECK (id = 1),
+  teams_json TEXT NOT NULL,
+  fetched_at TEXT NOT NULL
+);
+
+UPDATE schema_meta SET value = '20' WHERE key = 'schema_version';
+
+COMMIT;
+",
+        )?;
+        Ok(())
+    }
+
+    /// FTS5 tables cannot gain columns, so the search index is rebuilt with `body`.
+    fn migrate_v20_to_v21(conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(
+            "
+DROP TABLE IF EXISTS event_search;
+CREATE VIRTUAL TABLE event_search
+USING fts5(event_key UNINDEXED, repo, actor, title, body);
+",
+        )?;
+
+        let payloads = {
+            let mut stmt = tx.prepare("SELECT payload_json FROM event_log_v2")?;
+            let rows = stmt.query_map([], |row| row.get::<_, Value>(0))?;
+            rows.collect::<rusqlite::Result<Vec<_>>>()?
+        };
+        // Rows that no longer parse stay unindexed for `state check` to report.
+        for payload in payloads {
+            let Ok(event) = Self::parse_watch_event_payload(payload) else {
+                continue;
+            };
+            Self::index_event_for_search(&tx, &event)?;
+        }
+
+        tx.execute(
+            "UPDATE schema_meta SET value = '21' WHERE key = 'schema_version'",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Moves the cursor, logged events and per-repository rows of `repo` to `new_name`.
+    /// Rows `new_name` already has win; failure history keeps the name it was recorded under.
+    fn move_repo_rows(conn: &Connection, repo: &str, new_name: &str) -> Result<()> {
+        conn.execute(
+            "UPDATE OR IGNORE polling_cursors_v2 SET repo = ?2 WHERE repo = ?1",
+            params![repo, new_name],
+        )?;
+        conn.execute(
+            "DELETE FROM polling_cursors_v2 WHERE repo = ?1",
+            params![repo],
+        )?;
+        for table in REPO_SCOPED_TABLES {
+            conn.execute(
+                &format!("UPDATE OR IGNORE {table} SET repo = ?2 WHERE repo = ?1"),
+                params![repo, new_name],
+            )?;
+            conn.execute(
+                &format!("DELETE FROM {table} WHERE repo = ?1"),
+                params![repo],
+            )?;
+        }
+
+        let rows = {
+            let mut stmt =
+                conn.prepare("SELECT event_key, payload_json FROM event_log_v2 WHERE repo = ?1")?;
+            let rows = stmt.query_map(params![repo], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, Value>(1)?))
+            })?;
+            rows.collect::<rusqlite::Result<Vec<_>>>()?
+        };
+        for (old_key, payload) in rows {
+            let Ok(mut event) = Self::parse_watch_event_payload(payload) else {
+                continue;
+            };
+            event.repo = new_name.to_string();
+            let new_key = event.event_key();
+            let payload = compress_payload(&serde_json::to_vec(&event)?)?;
+            let moved = conn.execute(
+                "
+UPDATE OR IGNORE event_log_v2
+SET event_key = ?1, repo = ?2, payload_json = ?3, key_version = ?4
+WHERE event_key = ?5
+",
+                params![new_key, new_name, payload, EVENT_KEY_VERSION, old_key],
+            )?;
+            if moved == 0 {
+                conn.execute(
+                    "DELETE FROM event_log_v2 WHERE event_key = ?1",
+                    params![old_key],
+                )?;
+                conn.execute(
+                    "DELETE FROM event_search WHERE event_key = ?1",
+                    params![old_key],
+                )?;
+                continue;
+            }
+            conn.execute(
+                "UPDATE event_search SET event_key = ?1, repo = ?2 WHERE event_key = ?3",
+                params![new_key, new_name, old_key],
+            )?;
+            conn.execute(
+                "UPDATE OR IGNORE read_sync_marks SET event_key = ?1 WHERE event_key = ?2",
+                params![new_key, old_key],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Recomputes the keys of events stored under an older `EVENT_KEY_VERSION`, carrying their
+    /// search and read sync rows along. A row whose new key is already taken keeps its old key
+    /// and is left for `state check` to report.
+    fn rekey_events(conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        let stale = {
+            let mut stmt = tx.prepare(
+                "SELECT event_key, payload_json FROM event_log_v2 WHERE key_version < ?1",
+            )?;
+            let rows = stmt.query_map(params![EVENT_KEY_VERSION], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, Value>(1)?))
+            })?;
+            rows.collect::<rusqlite::Result<Vec<_>>>()?
+        };
+
+        for (old_key, payload) in stale {
+            let Ok(event) = Self::parse_watch_event_payload(payload) else {
+                continue;
+            };
+            let new_key = event.event_key();
+            let taken = new_key != old_key
+                && tx
+                    .query_row(
+                        "SELECT 1 FROM event_log_v2 WHERE event_key = ?1",
+                        params![new_key],
+                        |_| Ok(()),
+                    )
+                    .optional()?
+                    .is_some();
+            let key = if taken { &old_key } else { &new_key };
+            tx.execute(
+                "UPDATE event_log_v2 SET event_key = ?1, key_version = ?2 WHERE event_key = ?3",
+                params![key, EVENT_KEY_VERSION, old_key],
+            )?;
+            if key != &old_key {
+                tx.execute(
+                    "UPDATE event_search SET event_key = ?1 WHERE event_key = ?2",
+                    params![key, old_key],
+                )?;
+                tx.execute(
+                    "UPDATE OR IGNORE read_sync_marks SET event_key = ?1 WHERE event_key = ?2",
+                    params![key, old_key],
+                )?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Records a sighting of the review request `event`; returns whether it was the first.
+    fn observe_review_request(
+        conn: &Connection,
+        event: &WatchEvent,
+        observed_at: DateTime<Utc>,
+    ) -> Result<bool> {
+        let seen = conn
+            .query_row(
+                "SELECT 1 FROM review_request_states WHERE repo = ?1 AND request_id = ?2",
+                params![event.repo, event.source_item_id],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+        conn.execute(
+            "
+INSERT INTO review_request_states (repo, request_id, observed_at)
+VALUES (?1, ?2, ?3)
+ON CONFLICT(repo, request_id) DO UPDATE SET observed_at = excluded.observed_at
+",
+            params![event.repo, event.source_item_id, observed_at.to_rfc3339()],
+        )?;
+        Ok(!seen)
+    }
+
+    /// Forgets the sightings of requests on `pull` whose reviewer is no longer requested, so
+    /// requesting them again is news again.
+    fn forget_withdrawn_review_requests(
+        conn: &Connection,
+        repo: &str,
+        pull: &PendingReviewRequests,
+    ) -> Result<()> {
+        let prefix = format!("{}:", pull.pull_id);
+        let request_ids = {
+            let mut stmt = conn.prepare(
+                "SELECT request_id FROM review_request_states WHERE repo = ?1 AND substr(request_id, 1, ?2) = ?3",
+            )?;
+            let rows = stmt.query_map(params![repo, prefix.len() as i64, prefix], |row| {
+                row.get::<_, String>(0)
+            })?;
+            rows.collect::<rusqlite::Result<Vec<_>>>()?
+        };
+        for request_id in request_ids {
+            let reviewer = &request_id[prefix.len()..];
+            if pull.reviewers.iter().any(|pending| pending == reviewer) {
+                continue;
+            }
+            conn.execute(
+                "DELETE FROM review_request_states WHERE repo = ?1 AND request_id = ?2",
+                params![repo, request_id],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Drops the logged event stored under `event_key`, with its search entry, so the key
+    /// can be logged afresh.
+    fn forget_logged_event(conn: &Connection, event_key: &str) -> Result<()> {
+        conn.execute(
+            "DELETE FROM event_log_v2 WHERE event_key = ?1",
+            params![event_key],
+        )?;
+        conn.execute(
+            "DELETE FROM event_search WHERE event_key = ?1",
+            params![event_key],
+        )?;
+        Ok(())
+    }
+
+    /// Logs `event` as delivered unless its key is already present; returns whether it was new.
+    fn append_timeline_event(
+        conn: &Connection,
+        event: &WatchEvent,
+        observed_at: DateTime<Utc>,
+    ) -> Result<bool> {
+        let payload = compress_payload(&serde_json::to_vec(&event.for_storage())?)?;
+        let inserted = conn.execute(
+            "
+INSERT OR IGNORE INTO event_log_v2
+  (event_key, repo, kind, payload_json, created_at, observed_at, delivered_at, read_at, key_version)
+VALUES
+  (?1, ?2, ?3, ?4, ?5, ?6, ?7, NULL, ?8)
+",
+            params![
+                event.event_key(),
+                event.repo,
+                event.kind.as_str(),
+                payload,
+                event.created_at.to_rfc3339(),
+                observed_at.to_rfc3339(),
+                observed_at.to_rfc3339(),
+                EVENT_KEY_VERSION,
+            ],
+        )?;
+
+        if inserted == 1 {
+            Self::index_event_for_search(conn, event)?;
+        }
+        Ok(inserted == 1)
+    }
+
+    fn index_event_for_search(conn: &Connection, event: &WatchEvent) -> Result<()> {
+        conn.execute(
+            "
+INSERT INTO event_search (event_key, repo, actor, title, body)
+VALUES (?1, ?2, ?3, ?4, ?5)
+",
+            params![
+                event.event_key(),
+                event.repo,
+                event.actor,
+                event.title,
+                event.body
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn init_schema(conn: &Connection) -> Result<()> {
+        conn.execute_batch(
+            "
+CREATE TABLE IF NOT EXISTS schema_meta (
+  key TEXT PRIMARY KEY,
+  value TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS polling_cursors_v2 (
+  repo TEXT PRIMARY KEY,
+  last_polled_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS event_log_v2 (
+  event_key TEXT PRIMARY KEY,
+  repo TEXT NOT NULL,
+  kind TEXT NOT NULL,
+  payload_json TEXT NOT NULL,
+  created_at TEXT NOT NULL,
+  observed_at TEXT NOT NULL,
+  delivered_at TEXT,
+  read_at TEXT,
+  files_json TEXT,
+  notified_at TEXT,
+  key_version INTEGER NOT NULL DEFAULT 1
+);
+
+CREATE INDEX IF NOT EXISTS idx_event_log_v2_created_at
+ON event_log_v2 (created_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_event_log_v2_kind_created_at
+ON event_log_v2 (kind, created_at);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS event_search
+USING fts5(event_key UNINDEXED, repo, actor, title, body);
+
+CREATE TABLE IF NOT EXISTS repo_item_milestones (
+  repo TEXT NOT NULL,
+  item_id TEXT NOT NULL,
+  milestone_title TEXT NOT NULL,
+  observed_at TEXT NOT NULL,
+  PRIMARY KEY (repo, item_id)
+);
+
+CREATE TABLE IF NOT EXISTS failure_events (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  kind TEXT NOT NULL,
+  repo TEXT NOT NULL,
+  failed_at TEXT NOT NULL,
+  message TEXT NOT NULL,
+  context_json TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_failure_events_repo_failed_at
+ON failure_events (repo, failed_at);
+
+CREATE TABLE IF NOT EXISTS review_thread_states (
+  repo TEXT NOT NULL,
+  thread_id TEXT NOT NULL,
+  is_resolved INTEGER NOT NULL,
+  observed_at TEXT NOT NULL,
+  PRIMARY KEY (repo, thread_id)
+);
+
+CREATE TABLE IF NOT EXISTS notification_marks (
+  name TEXT PRIMARY KEY,
+  sent_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS my_pr_states (
+  repo TEXT NOT NULL,
+  pr_number INTEGER NOT NULL,
+  review_decision TEXT,
+  checks_state TEXT,
+  observed_at TEXT NOT NULL,
+  PRIMARY KEY (repo, pr_number)
+);
+
+CREATE TABLE IF NOT EXISTS review_reminders (
+  repo TEXT NOT NULL,
+  pr_number INTEGER NOT NULL,
+  reminded_at TEXT NOT NULL,
+  PRIMARY KEY (repo, pr_number)
+);
+
+CREATE TABLE IF NOT EXISTS read_sync_marks (
+  event_key TEXT PRIMARY KEY,
+  read_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS review_request_states (
+  repo TEXT NOT NULL,
+  request_id TEXT NOT NULL,
+  observed_at TEXT NOT NULL,
+  PRIMARY KEY (repo, request_id)
+);
+
+CREATE TABLE IF NOT EXISTS repo_access_marks (
+  repo TEXT PRIMARY KEY,
+  new_name TEXT,
+  status_code INTEGER,
+  marked_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS subject_states (
+  repo TEXT NOT NULL,
+  number INTEGER NOT NULL,
+  state TEXT NOT NULL,
+  fetched_at TEXT NOT NULL,
+  PRIMARY KEY (repo, number)
+);
+
+CREATE TABLE IF NOT EXISTS viewer_teams (
+  id INTEGER PRIMARY KEY CHECK (id = 1),
+  teams_json TEXT NOT NULL,
+  fetched_at TEXT NOT NULL
+);
+",
+        )?;
+
+        conn.execute(
+            "
+INSERT INTO schema_meta (key, value)
+VALUES ('schema_version', ?1)
+ON CONFLICT(key) DO UPDATE SET value = excluded.value
+",
+            params![SCHEMA_VERSION],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn compress_plain_payloads(&self) -> Result<usize> {
+        let mut conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let tx = conn.transaction()?;
+        let plain_rows = {
+            let mut stmt = tx.prepare(
+                "
+SELECT event_key, payload_json
+FROM event_log_v2
+WHERE typeof(payload_json) = 'text'
+",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?;
+            rows.collect::<rusqlite::Result<Vec<_>>>()?
+        };
+
+        for (event_key, payload) in &plain_rows {
+            tx.execute(
+                "UPDATE event_log_v2 SET payload_json = ?2 WHERE event_key = ?1",
+                params![event_key, compress_payload(payload.as_bytes())?],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(plain_rows.len())
+    }
+
+    pub fn check(&self, configured_repos: &[String]) -> Result<StateCheckReport> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        Self::collect_check_report(&conn, configured_repos)
+    }
+
+    pub fn repair(&self, configured_repos: &[String]) -> Result<StateCheckReport> {
+        let mut conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let tx = conn.transaction()?;
+        let report = Self::collect_check_report(&tx, configured_repos)?;
+
+        for event_key in &report.unparsable_event_keys {
+            tx.execute(
+                "DELETE FROM event_log_v2 WHERE event_key = ?1",
+                params![event_key],
+            )?;
+            tx.execute(
+                "DELETE FROM event_search WHERE event_key = ?1",
+                params![event_key],
+            )?;
+        }
+        for event_key in &report.orphaned_duplicate_event_keys {
+            tx.execute(
+                "DELETE FROM event_log_v2 WHERE event_key = ?1",
+                params![event_key],
+            )?;
+            tx.execute(
+                "DELETE FROM event_search WHERE event_key = ?1",
+                params![event_key],
+            )?;
+        }
+        for event_key in &report.orphaned_flag_event_keys {
+            tx.execute(
+                "UPDATE event_log_v2 SET read_at = NULL WHERE event_key = ?1",
+                params![event_key],
+            )?;
+        }
+        for repo in &report.stale_cursor_repos {
+            tx.execute(
+                "DELETE FROM polling_cursors_v2 WHERE repo = ?1",
+                params![repo],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(report)
+    }
+
+    fn collect_check_report(
+        conn: &Connection,
+        configured_repos: &[String],
+    ) -> Result<StateCheckReport> {
+        let mut report = StateCheckReport::default();
+
+        let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        for row in rows {
+            let message = row?;
+            if message != "ok" {
+                report.integrity_errors.push(message);
+            }
+        }
+
+        let mut stmt = conn.prepare(
+            "
+SELECT event_key, payload_json, delivered_at, read_at
+FROM event_log_v2
+ORDER BY event_key
+",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Value>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })?;
+        let mut stored_keys_by_key: HashMap<String, Vec<(String, String)>> = HashMap::new();
+        for row in rows {
+            let (event_key, payload, delivered_at, read_at) = row?;
+            let Ok(event) = Self::parse_watch_event_payload(payload) else {
+                report.unparsable_event_keys.push(event_key);
+                continue;
+            };
+            stored_keys_by_key
+                .entry(event.event_key())
+                .or_default()
+                .push((event_key.clone(), event.event_id));
+
+            let read_is_orphaned = match read_at {
+                Some(read_at) => {
+                    delivered_at.is_none() || DateTime::parse_from_rfc3339(&read_at).is_err()
+                }
+                None => false,
+            };
+            if read_is_orphaned {
+                report.orphaned_flag_event_keys.push(event_key);
+            }
+        }
+
+        let mut shared_keys = stored_keys_by_key
+            .into_iter()
+            .filter(|(_, stored)| stored.len() > 1)
+            .collect::<Vec<_>>();
+        shared_keys.sort();
+        for (key, stored) in shared_keys {
+            let event_ids = stored.iter().map(|(_, id)| id).collect::<HashSet<_>>();
+            if event_ids.len() > 1 {
+                let stored_keys = stored
+                    .iter()
+                    .map(|(stored_key, _)| stored_key.as_str())
+                    .collect::<Vec<_>>();
+                report
+                    .event_key_collisions
+                    .push(format!("{key}: {}", stored_keys.join(", ")));
+                continue;
+            }
+            // Keep the copy under the current key, or the first one when none has it yet.
+            let keep = stored
+                .iter()
+                .position(|(stored_key, _)| *stored_key == key)
+                .unwrap_or(0);
+            report.orphaned_duplicate_event_keys.extend(
+                stored
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(index, _)| *index != keep)
+                    .map(|(_, (stored_key, _))| stored_key),
+            );
+        }
+
+        let mut stmt = conn.prepare("SELECT repo FROM polling_cursors_v2 ORDER BY repo")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        for row in rows {
+            let repo = row?;
+            if !configured_repos.contains(&repo) {
+                report.stale_cursor_repos.push(repo);
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Timings of the events notified about at or after `since`, oldest notification first.
+    pub fn load_notification_timings(
+        &self,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<NotificationTiming>> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let mut stmt = conn.prepare(
+            "
+SELECT created_at, observed_at, notified_at
+FROM event_log_v2
+WHERE notified_at IS NOT NULL AND notified_at >= ?1
+ORDER BY notified_at ASC
+",
+        )?;
+        let rows = stmt.query_map(params![since.to_rfc3339()], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+        let parse = |raw: &str| -> Result<DateTime<Utc>> {
+            Ok(DateTime::parse_from_rfc3339(raw)?.with_timezone(&Utc))
+        };
+        rows.map(|row| {
+            let (created_at, observed_at, notified_at) = row?;
+            Ok(NotificationTiming {
+                created_at: parse(&created_at)?,
+                observed_at: parse(&observed_at)?,
+                notified_at: parse(&notified_at)?,
+            })
+        })
+        .collect()
+    }
+
+    /// Writes every logged event as NDJSON, oldest first, and returns the number written.
+    pub fn export_to_json(&self, writer: &mut dyn Write) -> Result<usize> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let mut stmt = conn.prepare(
+            "
+SELECT event_key, payload_json
+FROM event_log_v2
+ORDER BY created_at ASC, event_key ASC
+",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Value>(1)?))
+        })?;
+
+        let mut written = 0usize;
+        for row in rows {
+            let (event_key, payload) = row?;
+            let event = match Self::parse_watch_event_payload(payload) {
+                Ok(event) => event,
+                Err(err) => {
+                    tracing::warn!(error = %err, event_key = %event_key, "skipping unparsable event payload");
+                    continue;
+                }
+            };
+            serde_json::to_writer(&mut *writer, &event)?;
+            writer.write_all(b"\n")?;
+            written += 1;
+        }
+        writer.flush()?;
+        Ok(written)
+    }
+
+    /// Logs each NDJSON `WatchEvent` from `reader` as already delivered. Existing event keys
+    /// are skipped and unparsable lines are counted as failed.
+    pub fn import_from_json(&self, reader: &mut dyn Read) -> Result<ImportSummary> {
+        let observed_at = Utc::now();
+        let mut summary = ImportSummary::default();
+        let mut conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let tx = conn.transaction()?;
+
+        for (index, line) in BufReader::new(reader).lines().enumerate() {
+            let line = line.context("failed to read import stream")?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            summary.total_read += 1;
+
+            let event = match serde_json::from_str::<WatchEvent>(&line) {
+                Ok(event) => event,
+                Err(err) => {
+                    tracing::warn!(error = %err, line = index + 1, "skipping unparsable import line");
+                    summary.failed += 1;
+                    continue;
+                }
+            };
+            if Self::append_timeline_event(&tx, &event, observed_at)? {
+                summary.inserted += 1;
+            } else {
+                summary.skipped_duplicate += 1;
+            }
+        }
+
+        tx.commit()?;
+        Ok(summary)
+    }
+
+    /// Failures recorded at or after `since`, oldest first.
+    pub fn load_failures_since(&self, since: DateTime<Utc>) -> Result<Vec<FailureRecord>> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let mut stmt = conn.prepare(
+            "
+SELECT kind, repo, failed_at, message, context_json
+FROM failure_events
+WHERE failed_at >= ?1
+ORDER BY failed_at ASC, id ASC
+",
+        )?;
+        let rows = stmt.query_map(params![since.to_rfc3339()], Self::failure_row)?;
+        Self::parse_failure_rows(rows)
+    }
+
+    /// The `limit` most recently recorded failures, newest first.
+    pub fn load_recent_failures(&self, limit: usize) -> Result<Vec<FailureRecord>> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let mut stmt = conn.prepare(
+            "
+SELECT kind, repo, failed_at, message, context_json
+FROM failure_events
+ORDER BY failed_at DESC, id DESC
+LIMIT ?1
+",
+        )?;
+        let rows = stmt.query_map(params![limit as i64], Self::failure_row)?;
+        Self::parse_failure_rows(rows)
+    }
+
+    fn failure_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<FailureRow> {
+        Ok((
+            row.get(0)?,
+            row.get(1)?,
+            row.get(2)?,
+            row.get(3)?,
+            row.get(4)?,
+        ))
+    }
+
+    fn parse_failure_rows(
+        rows: impl Iterator<Item = rusqlite::Result<FailureRow>>,
+    ) -> Result<Vec<FailureRecord>> {
+        let mut failures = Vec::new();
+        for row in rows {
+            let (kind, repo, failed_at, message, context_json) = row?;
+            let failed_at = DateTime::parse_from_rfc3339(&failed_at)
+                .with_context(|| format!("invalid failed_at for {repo}: {failed_at}"))?
+                .with_timezone(&Utc);
+            let mut failure = FailureRecord::new(kind, repo, failed_at, message);
+            if let Some(context_json) = context_json {
+                failure.context = serde_json::from_str(&context_json)
+                    .with_context(|| format!("invalid failure context for {}", failure.repo))?;
+            }
+            failures.push(failure);
+        }
+        Ok(failures)
+    }
+
+    /// The schema version recorded in the database.
+    pub fn stored_schema_version(&self) -> Result<Option<String>> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        Self::schema_version(&conn)
+    }
+
+    /// Reads the next page for `cursor`, queueing its events and advancing `last_rowid`.
+    fn read_event_stream_page(
+        &self,
+        cursor: &mut EventStreamCursor,
+        since: DateTime<Utc>,
+    ) -> Result<()> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let backlog_end = match cursor.backlog_end {
+            Some(backlog_end) => backlog_end,
+            None => {
+                let backlog_end: i64 = conn.query_row(
+                    "SELECT COALESCE(MAX(rowid), 0) FROM event_log_v2",
+                    [],
+                    |row| row.get(0),
+                )?;
+                *cursor.backlog_end.insert(backlog_end)
+            }
+        };
+
+        let mut stmt = conn.prepare(
+            "
+SELECT rowid, event_key, payload_json
+FROM event_log_v2
+WHERE rowid > ?1 AND (rowid > ?2 OR observed_at >= ?3)
+ORDER BY rowid ASC
+LIMIT ?4
+",
+        )?;
+        let rows = stmt.query_map(
+            params![
+                cursor.last_rowid,
+                backlog_end,
+                since.to_rfc3339(),
+                EVENT_STREAM_PAGE_SIZE as i64
+            ],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Value>(2)?,
+                ))
+            },
+        )?;
+
+        let mut read = 0;
+        for row in rows {
+            let (rowid, event_key, payload) = row?;
+            read += 1;
+            cursor.last_rowid = rowid;
+            match Self::parse_watch_event_payload(payload) {
+                Ok(event) => cursor.pending.push_back(event),
+                Err(err) => {
+                    tracing::warn!(error = %err, event_key = %event_key, "skipping unparsable event payload");
+                }
+            }
+        }
+        cursor.caught_up = read < EVENT_STREAM_PAGE_SIZE;
+        Ok(())
+    }
+
+    pub fn vacuum(&self) -> Result<()> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        conn.execute_batch("VACUUM;")?;
+        Ok(())
+    }
+
+    fn parse_payload_rows(
+        rows: impl Iterator<Item = rusqlite::Result<(String, Value)>>,
+    ) -> Result<Vec<WatchEvent>> {
+        let mut events = Vec::new();
+        let mut skipped = 0usize;
+        for row in rows {
+            let (event_key, payload) = row?;
+            match Self::parse_watch_event_payload(payload) {
+                Ok(event) => events.push(event),
+                Err(err) => {
+                    skipped += 1;
+                    tracing::warn!(error = %err, event_key = %event_key, "skipping unparsable event payload");
+                }
+            }
+        }
+        if skipped > 0 {
+            tracing::warn!(
+                skipped,
+                "skipped unparsable event payloads (run `gh-watch state check --repair`)"
+            );
+        }
+        Ok(events)
+    }
+
+    fn parse_watch_event_payload(payload: Value) -> Result<WatchEvent> {
+        match payload {
+            Value::Text(json) => Ok(serde_json::from_str(&json)?),
+            Value::Blob(bytes) => {
+                let compressed = bytes
+                    .strip_prefix(COMPRESSED_PAYLOAD_MARKER)
+                    .ok_or_else(|| anyhow!("unknown event payload format"))?;
+                let json = zstd::decode_all(compressed)?;
+                Ok(serde_json::from_slice(&json)?)
+            }
+            other => Err(anyhow!(
+                "unexpected event payload type: {:?}",
+                other.data_type()
+            )),
+        }
+    }
+
+    fn parse_cursor_row(repo: String, value: String) -> Result<(String, DateTime<Utc>)> {
+        let at = DateTime::parse_from_rfc3339(&value)?.with_timezone(&Utc);
+        Ok((repo, at))
+    }
+}
+
+impl CursorPort for SqliteStateStore {
+    fn get_cursor(&self, repo: &str) -> Result<Option<DateTime<Utc>>> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let value: Option<String> = conn
+            .query_row(
+                "SELECT last_polled_at FROM polling_cursors_v2 WHERE repo = ?1",
+                params![repo],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        value
+            .map(|v| DateTime::parse_from_rfc3339(&v).map(|dt| dt.with_timezone(&Utc)))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    fn set_cursor(&self, repo: &str, at: DateTime<Utc>) -> Result<()> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        conn.execute(
+            "
+INSERT INTO polling_cursors_v2 (repo, last_polled_at)
+VALUES (?1, ?2)
+ON CONFLICT(repo) DO UPDATE SET last_polled_at = excluded.last_polled_at
+",
+            params![repo, at.to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    fn load_cursors_all(&self) -> Result<HashMap<String, DateTime<Utc>>> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let mut stmt = conn.prepare("SELECT repo, last_polled_at FROM polling_cursors_v2")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        rows.map(|row| {
+            let (repo, value) = row?;
+            Self::parse_cursor_row(repo, value)
+        })
+        .collect()
+    }
+
+    fn load_repo_access_marks(&self) -> Result<HashMap<String, RepoAccessMark>> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let mut stmt =
+            conn.prepare("SELECT repo, new_name, status_code, marked_at FROM repo_access_marks")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, Option<u16>>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })?;
+        let mut marks = HashMap::new();
+        for row in rows {
+            let (repo, new_name, status_code, marked_at) = row?;
+            let marked_at = DateTime::parse_from_rfc3339(&marked_at)?.with_timezone(&Utc);
+            let mark = match (new_name, status_code) {
+                (Some(new_name), _) => RepoAccessMark::Renamed {
+                    new_name,
+                    marked_at,
+                },
+                (None, Some(status_code)) => RepoAccessMark::Inaccessible {
+                    status_code,
+                    marked_at,
+                },
+                (None, None) => continue,
+            };
+            marks.insert(repo, mark);
+        }
+        Ok(marks)
+    }
+
+    fn mark_repo_renamed(&self, repo: &str, new_name: &str, at: DateTime<Utc>) -> Result<()> {
+        let mut conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let tx = conn.transaction()?;
+        tx.execute(
+            "
+INSERT INTO repo_access_marks (repo, new_name, status_code, marked_at)
+VALUES (?1, ?2, NULL, ?3)
+ON CONFLICT(repo) DO UPDATE SET
+  new_name = excluded.new_name,
+  status_code = NULL,
+  marked_at = excluded.marked_at
+",
+            params![repo, new_name, at.to_rfc3339()],
+        )?;
+        Self::move_repo_rows(&tx, repo, new_name)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn mark_repo_inaccessible(
+        &self,
+        repo: &str,
+        status_code: u16,
+        at: DateTime<Utc>,
+    ) -> Result<bool> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let marked = conn
+            .query_row(
+                "SELECT 1 FROM repo_access_marks WHERE repo = ?1 AND status_code IS NOT NULL",
+                params![repo],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+        conn.execute(
+            "
+INSERT INTO repo_access_marks (repo, new_name, status_code, marked_at)
+VALUES (?1, NULL, ?2, ?3)
+ON CONFLICT(repo) DO UPDATE SET
+  new_name = NULL,
+  status_code = excluded.status_code,
+  marked_at = excluded.marked_at
+",
+            params![repo, status_code, at.to_rfc3339()],
+        )?;
+        Ok(!marked)
+    }
+
+    fn clear_repo_access_mark(&self, repo: &str) -> Result<()> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        conn.execute(
+            "DELETE FROM repo_access_marks WHERE repo = ?1",
+            params![repo],
+        )?;
+        Ok(())
+    }
+
+    fn load_cursors_for(&self, repos: &[String]) -> Result<HashMap<String, DateTime<Utc>>> {
+        if repos.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let mut cursors = HashMap::new();
+
+        for chunk in repos.chunks(900) {
+            let placeholders = vec!["?"; chunk.len()].join(", ");
+            let sql = format!(
+                "
+SELECT repo, last_polled_at
+FROM polling_cursors_v2
+WHERE repo IN ({placeholders})
+"
+            );
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt.query_map(params_from_iter(chunk.iter()), |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?;
+            for row in rows {
+                let (repo, value) = row?;
+                let (repo, at) = Self::parse_cursor_row(repo, value)?;
+                cursors.insert(repo, at);
+            }
+        }
+        Ok(cursors)
+    }
+}
+
+impl EventStreamPort for SqliteStateStore {
+    /// Polls `event_log_v2` by rowid every `poll_interval_ms`. `VACUUM` may renumber rowids,
+    /// so restart streams after `gh-watch state vacuum`.
+    fn watch_event_stream(
+        &self,
+        since: DateTime<Utc>,
+    ) -> impl Stream<Item = Result<WatchEvent>> + Send + '_ {
+        let period = std::time::Duration::from_millis(self.event_stream.poll_interval_ms.max(1));
+        let cursor = EventStreamCursor {
+            backlog_end: None,
+            last_rowid: 0,
+            caught_up: false,
+            pending: VecDeque::new(),
+            interval: None,
+        };
+        stream::unfold(cursor, move |mut cursor| async move {
+            loop {
+                if let Some(event) = cursor.pending.pop_front() {
+                    return Some((Ok(event), cursor));
+                }
+                if cursor.caught_up {
+                    let interval = cursor.interval.get_or_insert_with(|| {
+                        let start = tokio::time::Instant::now() + period;
+                        let mut interval = tokio::time::interval_at(start, period);
+                        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                        interval
+                    });
+                    interval.tick().await;
+                }
+                if let Err(err) = self.read_event_stream_page(&mut cursor, since) {
+                    cursor.caught_up = true;
+                    return Some((Err(err), cursor));
+                }
+            }
+        })
+    }
+}
+
+/// Narrows an `event_log_v2` query to events of `repos` by `actors`; empty lists match all.
+fn push_scope_filter(
+    sql: &mut String,
+    values: &mut Vec<Value>,
+    repos: &[String],
+    actors: &[String],
+) {
+    if !repos.is_empty() {
+        let placeholders = vec!["?"; repos.len()].join(", ");
+        sql.push_str(&format!("\n  AND repo IN ({placeholders})"));
+        values.extend(repos.iter().cloned().map(Value::Text));
+    }
+    if !actors.is_empty() {
+        // The actor only lives in the compressed payload and the search index.
+        let placeholders = vec!["lower(?)"; actors.len()].join(", ");
+        sql.push_str(&format!(
+            "\n  AND event_key IN (SELECT event_key FROM event_search WHERE lower(actor) IN ({placeholders}))"
+        ));
+        values.extend(actors.iter().cloned().map(Value::Text));
+    }
+}
+
+impl TimelineQueryPort for SqliteStateStore {
+    fn load_timeline_events(&self, limit: usize) -> Result<Vec<WatchEvent>> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let mut stmt = conn.prepare(
+            "
+SELECT event_key, payload_json
+FROM event_log_v2
+ORDER BY created_at DESC
+LIMIT ?1
+",
+        )?;
+
+        let rows = stmt.query_map(params![limit as i64], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Value>(1)?))
+        })?;
+        Self::parse_payload_rows(rows)
+    }
+
+    fn load_timeline_events_before(
+        &self,
+        before: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<WatchEvent>> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let mut stmt = conn.prepare(
+            "
+SELECT event_key, payload_json
+FROM event_log_v2
+WHERE created_at <= ?1
+ORDER BY created_at DESC
+LIMIT ?2
+",
+        )?;
+
+        let rows = stmt.query_map(params![before.to_rfc3339(), limit as i64], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Value>(1)?))
+        })?;
+        Self::parse_payload_rows(rows)
+    }
+
+    fn find_events_page(
+        &self,
+        since: DateTime<Utc>,
+        before: Option<DateTime<Utc>>,
+        limit: usize,
+    ) -> Result<Vec<WatchEvent>> {
+        self.load_timeline_events_filtered(since, before, &[], &[], limit)
+    }
+
+    fn load_timeline_events_filtered(
+        &self,
+        since: DateTime<Utc>,
+        before: Option<DateTime<Utc>>,
+        repos: &[String],
+        actors: &[String],
+        limit: usize,
+    ) -> Result<Vec<WatchEvent>> {
+        let mut sql = String::from(
+            "
+SELECT event_key, payload_json
+FROM event_log_v2
+WHERE created_at >= ?
+  AND (? IS NULL OR created_at < ?)",
+        );
+        let before = before.map(|at| at.to_rfc3339());
+        let mut values = vec![
+            Value::Text(since.to_rfc3339()),
+            before.clone().map_or(Value::Null, Value::Text),
+            before.map_or(Value::Null, Value::Text),
+        ];
+        push_scope_filter(&mut sql, &mut values, repos, actors);
+        sql.push_str("\nORDER BY created_at DESC\nLIMIT ?\n");
+        values.push(Value::Integer(limit as i64));
+
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(values.iter()), |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Value>(1)?))
+        })?;
+        Self::parse_payload_rows(rows)
+    }
+
+    fn count_read_events_by_repo(
+        &self,
+        since: DateTime<Utc>,
+        repos: &[String],
+        actors: &[String],
+    ) -> Result<Vec<RepoReadCounts>> {
+        let mut sql = String::from(
+            "
+SELECT repo, SUM(read_at IS NOT NULL), SUM(read_at IS NULL)
+FROM event_log_v2
+WHERE created_at >= ?",
+        );
+        let mut values = vec![Value::Text(since.to_rfc3339())];
+        push_scope_filter(&mut sql, &mut values, repos, actors);
+        sql.push_str("\nGROUP BY repo\nORDER BY repo\n");
+
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(values.iter()), |row| {
+            Ok(RepoReadCounts {
+                repo: row.get(0)?,
+                read: row.get::<_, i64>(1)? as usize,
+                unread: row.get::<_, i64>(2)? as usize,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    fn load_read_event_keys(&self, event_keys: &[String]) -> Result<HashSet<String>> {
+        if event_keys.is_empty() {
+            return Ok(HashSet::new());
+        }
+
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let mut read_keys = HashSet::new();
+
+        for keys in event_keys.chunks(900) {
+            let placeholders = vec!["?"; keys.len()].join(", ");
+            let sql = format!(
+                "
+SELECT event_key
+FROM event_log_v2
+WHERE read_at IS NOT NULL
+  AND event_key IN ({placeholders})
+"
+            );
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt.query_map(params_from_iter(keys.iter()), |row| row.get(0))?;
+            for row in rows {
+                read_keys.insert(row?);
+            }
+        }
+        Ok(read_keys)
+    }
+
+    fn get_event_by_key(&self, event_key: &str) -> Result<Option<WatchEvent>> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let payload = conn
+            .query_row(
+                "SELECT payload_json FROM event_log_v2 WHERE event_key = ?1",
+                params![event_key],
+                |row| row.get::<_, Value>(0),
+            )
+            .optional()?;
+        payload.map(Self::parse_watch_event_payload).transpose()
+    }
+
+    fn get_events_by_keys(&self, event_keys: &[String]) -> Result<Vec<WatchEvent>> {
+        if event_keys.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let mut by_key = HashMap::new();
+
+        for keys in event_keys.chunks(900) {
+            let placeholders = vec!["?"; keys.len()].join(", ");
+            let sql = format!(
+                "
+SELECT event_key, payload_json
+FROM event_log_v2
+WHERE event_key IN ({placeholders})
+"
+            );
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt.query_map(params_from_iter(keys.iter()), |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, Value>(1)?))
+            })?;
+            for row in rows {
+                let (event_key, payload) = row?;
+                match Self::parse_watch_event_payload(payload) {
+                    Ok(event) => {
+                        by_key.insert(event_key, event);
+                    }
+                    Err(err) => {
+                        tracing::warn!(error = %err, event_key = %event_key, "skipping unparsable event payload");
+                    }
+                }
+            }
+        }
+        Ok(event_keys
+            .iter()
+            .filter_map(|key| by_key.remove(key))
+            .collect())
+    }
+
+    fn search_events(&self, query: &str, limit: usize) -> Result<Vec<WatchEvent>> {
+        let Some(match_query) = fts_match_query(query) else {
+            return Ok(Vec::new());
+        };
+
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let mut stmt = conn.prepare(
+            "
+SELECT e.event_key, e.payload_json
+FROM event_search s
+JOIN event_log_v2 e ON e.event_key = s.event_key
+WHERE event_search MATCH ?1
+ORDER BY e.created_at DESC
+LIMIT ?2
+",
+        )?;
+
+        let rows = stmt.query_map(params![match_query, limit as i64], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Value>(1)?))
+        })?;
+        Self::parse_payload_rows(rows)
+    }
+}
+
+impl TimelineReadMarkPort for SqliteStateStore {
+    fn mark_timeline_event_read(&self, event_key: &str, read_at: DateTime<Utc>) -> Result<()> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        conn.execute(
+            "
+UPDATE event_log_v2
+SET read_at = COALESCE(read_at, ?2)
+WHERE event_key = ?1
+",
+            params![event_key, read_at.to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    fn mark_all_timeline_events_read(&self, read_at: DateTime<Utc>) -> Result<usize> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let marked = conn.execute(
+            "
+UPDATE event_log_v2
+SET read_at = ?1
+WHERE read_at IS NULL
+",
+            params![read_at.to_rfc3339()],
+        )?;
+        Ok(marked)
+    }
+}
+
+impl RetentionPort for SqliteStateStore {
+    fn cleanup_old(&self, policy: &RetentionPolicy, now: DateTime<Utc>) -> Result<()> {
+        let unread_exemption = if policy.keep_unread {
+            " AND read_at IS NOT NULL"
+        } else {
+            ""
+        };
+
+        let mut conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let tx = conn.transaction()?;
+
+        let mut overridden_kinds = Vec::new();
+        for (kind, days) in &policy.days_by_kind {
+            let cutoff = now - Duration::days(*days as i64);
+            tx.execute(
+                &format!(
+                    "DELETE FROM event_log_v2 WHERE kind = ?1 AND created_at < ?2{unread_exemption}"
+                ),
+                params![kind.as_str(), cutoff.to_rfc3339()],
+            )?;
+            overridden_kinds.push(kind.as_str().to_string());
+        }
+
+        let cutoff = now - Duration::days(policy.default_days as i64);
+        let mut sql = format!("DELETE FROM event_log_v2 WHERE created_at < ?{unread_exemption}");
+        if !overridden_kinds.is_empty() {
+            let placeholders = vec!["?"; overridden_kinds.len()].join(", ");
+            sql.push_str(&format!(" AND kind NOT IN ({placeholders})"));
+        }
+        let mut values = vec![cutoff.to_rfc3339()];
+        values.extend(overridden_kinds);
+        tx.execute(&sql, params_from_iter(values.iter()))?;
+        if let Some(max_read_history) = policy.max_read_history {
+            tx.execute(
+                "
+DELETE FROM event_log_v2
+WHERE event_key IN (
+  SELECT event_key
+  FROM event_log_v2
+  WHERE delivered_at IS NOT NULL AND read_at IS NOT NULL
+  ORDER BY created_at DESC
+  LIMIT -1 OFFSET ?1
+)
+",
+                params![max_read_history as i64],
+            )?;
+        }
+        tx.execute(
+            "DELETE FROM event_search WHERE event_key NOT IN (SELECT event_key FROM event_log_v2)",
+            [],
+        )?;
+        tx.execute(
+            "DELETE FROM failure_events WHERE failed_at < ?1",
+            params![cutoff.to_rfc3339()],
+        )?;
+        tx.execute(
+            "DELETE FROM review_thread_states WHERE observed_at < ?1",
+            params![cutoff.to_rfc3339()],
+        )?;
+        tx.execute(
+            "DELETE FROM subject_states WHERE fetched_at < ?1",
+            params![cutoff.to_rfc3339()],
+        )?;
+        tx.execute(
+            "DELETE FROM my_pr_states WHERE observed_at < ?1",
+            params![cutoff.to_rfc3339()],
+        )?;
+        tx.execute(
+            "DELETE FROM review_request_states WHERE observed_at < ?1",
+            params![cutoff.to_rfc3339()],
+        )?;
+        tx.execute(
+            "DELETE FROM review_reminders WHERE reminded_at < ?1",
+            params![cutoff.to_rfc3339()],
+        )?;
+        tx.execute(
+            "DELETE FROM read_sync_marks WHERE read_at < ?1",
+            params![cutoff.to_rfc3339()],
+        )?;
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn count_delivered_read_events(&self) -> Result<usize> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM event_log_v2 WHERE delivered_at IS NOT NULL AND read_at IS NOT NULL",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+}
+
+impl FailureLogPort for SqliteStateStore {
+    fn record_failure(&self, failure: &FailureRecord) -> Result<()> {
+        let context_json = if failure.context.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_string(&failure.context)?)
+        };
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        conn.execute(
+            "
+INSERT INTO failure_events (kind, repo, failed_at, message, context_json)
+VALUES (?1, ?2, ?3, ?4, ?5)
+",
+            params![
+                failure.kind,
+                failure.repo,
+                failure.failed_at.to_rfc3339(),
+                failure.message,
+                context_json
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn get_failure_count_for_repo(&self, repo: &str, since: DateTime<Utc>) -> Result<usize> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM failure_events WHERE repo = ?1 AND failed_at >= ?2",
+            params![repo, since.to_rfc3339()],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+}
+
+impl SummaryStatePort for SqliteStateStore {
+    fn load_summary_sent_at(&self) -> Result<Option<DateTime<Utc>>> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let sent_at: Option<String> = conn
+            .query_row(
+                "SELECT sent_at FROM notification_marks WHERE name = ?1",
+                params![WEEKLY_SUMMARY_MARK],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        sent_at
+            .map(|v| DateTime::parse_from_rfc3339(&v).map(|dt| dt.with_timezone(&Utc)))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    fn save_summary_sent_at(&self, sent_at: DateTime<Utc>) -> Result<()> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        conn.execute(
+            "
+INSERT INTO notification_marks (name, sent_at)
+VALUES (?1, ?2)
+ON CONFLICT(name) DO UPDATE SET sent_at = excluded.sent_at
+",
+            params![WEEKLY_SUMMARY_MARK, sent_at.to_rfc3339()],
+        )?;
+        Ok(())
+    }
+}
+
+impl ReminderStatePort for SqliteStateStore {
+    fn load_review_reminded_at(&self, repo: &str, pr_number: i64) -> Result<Option<DateTime<Utc>>> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let reminded_at: Option<String> = conn
+            .query_row(
+                "SELECT reminded_at FROM review_reminders WHERE repo = ?1 AND pr_number = ?2",
+                params![repo, pr_number],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        reminded_at
+            .map(|v| DateTime::parse_from_rfc3339(&v).map(|dt| dt.with_timezone(&Utc)))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    fn save_review_reminded_at(
+        &self,
+        repo: &str,
+        pr_number: i64,
+        reminded_at: DateTime<Utc>,
+    ) -> Result<()> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        conn.execute(
+            "
+INSERT INTO review_reminders (repo, pr_number, reminded_at)
+VALUES (?1, ?2, ?3)
+ON CONFLICT(repo, pr_number) DO UPDATE SET reminded_at = excluded.reminded_at
+",
+            params![repo, pr_number, reminded_at.to_rfc3339()],
+        )?;
+        Ok(())
+    }
+}
+
+impl SubjectStatePort for SqliteStateStore {
+    fn load_subject_states(
+        &self,
+        subjects: &[SubjectRef],
+    ) -> Result<HashMap<SubjectRef, (SubjectState, DateTime<Utc>)>> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let mut stmt = conn.prepare(
+            "SELECT state, fetched_at FROM subject_states WHERE repo = ?1 AND number = ?2",
+        )?;
+        let mut states = HashMap::new();
+        for subject in subjects {
+            let row: Option<(String, String)> = stmt
+                .query_row(params![subject.repo, subject.number], |row| {
+                    Ok((row.get(0)?, row.get(1)?))
+                })
+                .optional()?;
+            let Some((state, fetched_at)) = row else {
+                continue;
+            };
+            let Some(state) = SubjectState::parse(&state) else {
+                continue;
+            };
+            let fetched_at = DateTime::parse_from_rfc3339(&fetched_at)?.with_timezone(&Utc);
+            states.insert(subject.clone(), (state, fetched_at));
+        }
+        Ok(states)
+    }
+
+    fn save_subject_states(
+        &self,
+        states: &HashMap<SubjectRef, SubjectState>,
+        fetched_at: DateTime<Utc>,
+    ) -> Result<()> {
+        let mut conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let tx = conn.transaction()?;
+        for (subject, state) in states {
+            tx.execute(
+                "
+INSERT INTO subject_states (repo, number, state, fetched_at)
+VALUES (?1, ?2, ?3, ?4)
+ON CONFLICT(repo, number) DO UPDATE SET
+  state = excluded.state,
+  fetched_at = excluded.fetched_at
+",
+                params![
+                    subject.repo,
+                    subject.number,
+                    state.as_str(),
+                    fetched_at.to_rfc3339()
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+impl ViewerTeamsPort for SqliteStateStore {
+    fn load_viewer_teams(&self) -> Result<Option<(Vec<String>, DateTime<Utc>)>> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let row: Option<(String, String)> = conn
+            .query_row(
+                "SELECT teams_json, fetched_at FROM viewer_teams WHERE id = 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+
+        row.map(|(teams_json, fetched_at)| {
+            let teams = serde_json::from_str(&teams_json)?;
+            let fetched_at = DateTime::parse_from_rfc3339(&fetched_at)?.with_timezone(&Utc);
+            Ok((teams, fetched_at))
+        })
+        .transpose()
+    }
+
+    fn save_viewer_teams(&self, teams: &[String], fetched_at: DateTime<Utc>) -> Result<()> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        conn.execute(
+            "
+INSERT INTO viewer_teams (id, teams_json, fetched_at)
+VALUES (1, ?1, ?2)
+ON CONFLICT(id) DO UPDATE SET
+  teams_json = excluded.teams_json,
+  fetched_at = excluded.fetched_at
+",
+            params![serde_json::to_string(teams)?, fetched_at.to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    fn clear_viewer_teams(&self) -> Result<()> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        conn.execute("DELETE FROM viewer_teams", [])?;
+        Ok(())
+    }
+}
+
+impl ReadSyncStatePort for SqliteStateStore {
+    fn load_read_marks(&self) -> Result<HashMap<String, DateTime<Utc>>> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let mut stmt = conn.prepare(
+            "
+SELECT event_key, read_at FROM event_log_v2 WHERE read_at IS NOT NULL
+UNION ALL
+SELECT event_key, read_at FROM read_sync_marks
+",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+
+        let mut marks = HashMap::new();
+        for row in rows {
+            let (event_key, read_at) = row?;
+            let read_at = DateTime::parse_from_rfc3339(&read_at)?.with_timezone(&Utc);
+            marks
+                .entry(event_key)
+                .and_modify(|earliest: &mut DateTime<Utc>| *earliest = (*earliest).min(read_at))
+                .or_insert(read_at);
+        }
+        Ok(marks)
+    }
+
+    fn merge_read_marks(&self, marks: &HashMap<String, DateTime<Utc>>) -> Result<Vec<String>> {
+        let mut conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let tx = conn.transaction()?;
+        let mut newly_read = Vec::new();
+        for (event_key, read_at) in marks {
+            tx.execute(
+                "
+INSERT INTO read_sync_marks (event_key, read_at)
+VALUES (?1, ?2)
+ON CONFLICT(event_key) DO UPDATE SET read_at = excluded.read_at
+",
+                params![event_key, read_at.to_rfc3339()],
+            )?;
+            let updated = tx.execute(
+                "UPDATE event_log_v2 SET read_at = ?2 WHERE event_key = ?1 AND read_at IS NULL",
+                params![event_key, read_at.to_rfc3339()],
+            )?;
+            if updated > 0 {
+                newly_read.push(event_key.clone());
+            }
+        }
+        tx.commit()?;
+        newly_read.sort();
+        Ok(newly_read)
+    }
+}
+
+impl RepoBatchPort for SqliteStateStore {
+    fn persist_repo_batch(&self, batch: &RepoPersistBatch) -> Result<PersistBatchResult> {
+        let mut conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let tx = conn.transaction()?;
+        tx.execute(
+            "
+INSERT INTO polling_cursors_v2 (repo, last_polled_at)
+VALUES (?1, ?2)
+ON CONFLICT(repo) DO UPDATE SET last_polled_at = excluded.last_polled_at
+",
+            params![batch.repo, batch.poll_started_at.to_rfc3339()],
+        )?;
+
+        let mut newly_resolved_threads = HashSet::new();
+        for thread in &batch.review_threads {
+            let previous = tx
+                .query_row(
+                    "SELECT is_resolved FROM review_thread_states WHERE repo = ?1 AND thread_id = ?2",
+                    params![batch.repo, thread.thread_id],
+                    |row| row.get::<_, bool>(0),
+                )
+                .optional()?;
+            tx.execute(
+                "
+INSERT INTO review_thread_states (repo, thread_id, is_resolved, observed_at)
+VALUES (?1, ?2, ?3, ?4)
+ON CONFLICT(repo, thread_id) DO UPDATE SET
+  is_resolved = excluded.is_resolved,
+  observed_at = excluded.observed_at
+",
+                params![
+                    batch.repo,
+                    thread.thread_id,
+                    thread.is_resolved,
+                    batch.poll_started_at.to_rfc3339()
+                ],
+            )?;
+            if thread.is_resolved && previous == Some(false) {
+                newly_resolved_threads.insert(thread.thread_id.as_str());
+            }
+        }
+
+        let mut newly_approved_prs = HashSet::new();
+        let mut newly_passed_prs = HashSet::new();
+        for pr in &batch.my_prs {
+            let previous = tx
+                .query_row(
+                    "SELECT review_decision, checks_state FROM my_pr_states WHERE repo = ?1 AND pr_number = ?2",
+                    params![batch.repo, pr.pr_number],
+                    |row| Ok((row.get::<_, Option<String>>(0)?, row.get::<_, Option<String>>(1)?)),
+                )
+                .optional()?;
+            tx.execute(
+                "
+INSERT INTO my_pr_states (repo, pr_number, review_decision, checks_state, observed_at)
+VALUES (?1, ?2, ?3, ?4, ?5)
+ON CONFLICT(repo, pr_number) DO UPDATE SET
+  review_decision = excluded.review_decision,
+  checks_state = excluded.checks_state,
+  observed_at = excluded.observed_at
+",
+                params![
+                    batch.repo,
+                    pr.pr_number,
+                    pr.review_decision,
+                    pr.checks_state,
+                    batch.poll_started_at.to_rfc3339()
+                ],
+            )?;
+            let Some((previous_decision, previous_checks)) = previous else {
+                continue;
+            };
+            if pr.review_decision.as_deref() == Some(MY_PR_APPROVED)
+                && previous_decision.as_deref() != Some(MY_PR_APPROVED)
+            {
+                newly_approved_prs.insert(pr.pr_number);
+            }
+            if pr.checks_state.as_deref() == Some(MY_PR_CHECKS_SUCCESS)
+                && previous_checks.as_deref() != Some(MY_PR_CHECKS_SUCCESS)
+            {
+                newly_passed_prs.insert(pr.pr_number);
+            }
+        }
+
+        for pull in &batch.review_requests {
+            Self::forget_withdrawn_review_requests(&tx, &batch.repo, pull)?;
+        }
+
+        let mut result = PersistBatchResult::default();
+        for event in &batch.events {
+            if event.repo != batch.repo {
+                return Err(anyhow!(
+                    "repo batch mismatch: batch repo={} event repo={}",
+                    batch.repo,
+                    event.repo
+                ));
+            }
+
+            if let Some((item_id, milestone_title)) = event.milestone_assignment() {
+                let previous = tx
+                    .query_row(
+                        "SELECT milestone_title FROM repo_item_milestones WHERE repo = ?1 AND item_id = ?2",
+                        params![event.repo, item_id],
+                        |row| row.get::<_, String>(0),
+                    )
+                    .optional()?;
+                tx.execute(
+                    "
+INSERT INTO repo_item_milestones (repo, item_id, milestone_title, observed_at)
+VALUES (?1, ?2, ?3, ?4)
+ON CONFLICT(repo, item_id) DO UPDATE SET
+  milestone_title = excluded.milestone_title,
+  observed_at = excluded.observed_at
+",
+                    params![
+                        event.repo,
+                        item_id,
+                        milestone_title,
+                        batch.poll_started_at.to_rfc3339()
+                    ],
+                )?;
+                // A poll only sees an item's current milestone, so the first sight of it is a
+                // baseline; a webhook delivery reports the assignment itself.
+                let assigned = match previous.as_deref() {
+                    Some(previous) => previous != milestone_title,
+                    None => batch.pushed,
+                };
+                if !assigned {
+                    continue;
+                }
+            }
+
+            // Threads first seen already resolved, or still resolved since the last poll,
+            // are not news.
+            if event.kind == EventKind::ReviewThreadResolved
+                && !newly_resolved_threads.contains(event.source_item_id.as_str())
+            {
+                continue;
+            }
+
+            // Likewise, only a pull request last seen unapproved or with other check results
+            // has become ready.
+            let my_pr_number = event.subject_number.unwrap_or_default();
+            if (event.kind == EventKind::MyPrApproved
+                && !newly_approved_prs.contains(&my_pr_number))
+                || (event.kind == EventKind::MyPrChecksPassed
+                    && !newly_passed_prs.contains(&my_pr_number))
+            {
+                continue;
+            }
+
+            // A pending request is fetched again, with a new timestamp, whenever its pull
+            // request is updated; only the first sighting is news, even once retention has
+            // dropped the logged event. A request withdrawn or answered since is forgotten
+            // above, and asking again replaces the event logged for the earlier one.
+            if event.kind == EventKind::PrReviewRequested {
+                if !Self::observe_review_request(&tx, event, batch.poll_started_at)? {
+                    continue;
+                }
+                Self::forget_logged_event(&tx, &event.event_key())?;
+            }
+
+            if Self::append_timeline_event(&tx, event, batch.poll_started_at)? {
+                result.newly_logged_event_keys.push(event.event_key());
+            }
+        }
+
+        tx.commit()?;
+        Ok(result)
+    }
+
+    fn load_pr_file_changes(
+        &self,
+        repo: &str,
+        pr_number: i64,
+        since: DateTime<Utc>,
+    ) -> Result<Option<Vec<PrFileChange>>> {
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let files_json = conn
+            .query_row(
+                "
+SELECT files_json
+FROM event_log_v2
+WHERE repo = ?1
+  AND files_json IS NOT NULL
+  AND json_extract(files_json, '$.pr_number') = ?2
+  AND observed_at >= ?3
+ORDER BY observed_at DESC
+LIMIT 1
+",
+                params![repo, pr_number, since.to_rfc3339()],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?;
+        files_json
+            .map(|files_json| {
+                let cached: CachedPrFiles = serde_json::from_str(&files_json)
+                    .with_context(|| format!("invalid cached files of {repo}#{pr_number}"))?;
+                Ok(cached.files)
+            })
+            .transpose()
+    }
+
+    fn save_pr_file_changes(
+        &self,
+        event_key: &str,
+        pr_number: i64,
+        files: &[PrFileChange],
+    ) -> Result<()> {
+        let files_json = serde_json::to_string(&CachedPrFiles {
+            pr_number,
+            files: files.to_vec(),
+        })?;
+        let conn = self.conn.lock().expect("sqlite mutex poisoned");
+        conn.execute(
+            "UPDATE event_log_v2 SET files_json = ?1 WHERE event_key = ?2",
+            params![files_json, event_key],
+        )?;
+        Ok(())
+    }
+
+    fn mark_events_notified(
+        &self,
+        event_keys: &[String],
+        notified_at: DateTime<Utc>,
+    ) -> Result<()> {
+        let mut conn = self.conn.lock().expect("sqlite mutex poisoned");
+        let tx = conn.transaction()?;
+        for event_key in event_keys {
+            tx.execute(
+                "UPDATE event_log_v2 SET notified_at = ?2 WHERE event_key = ?1 AND notified_at IS NULL",
+                params![event_key, notified_at.to_rfc3339()],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+fn compress_payload(json: &[u8]) -> Result<Vec<u8>> {
+    let mut payload = COMPRESSED_PAYLOAD_MARKER.to_vec();
+    payload.extend(zstd::encode_all(json, PAYLOAD_COMPRESSION_LEVEL)?);
+    Ok(payload)
+}
+
+fn fts_match_query(query: &str) -> Option<String> {
+    let terms = query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect::<Vec<_>>();
+    if terms.is_empty() {
+        return None;
     }
+    Some(terms.join(" "))
 }
@@ -0,0 +1,47 @@
+use std::{path::PathBuf, process::Command};
+
+use anyhow::{anyhow, Context, Result};
+
+const DOWNLOAD_TIMEOUT_SECONDS: u32 = 30;
+/// Config templates are a few KB; anything near this is not one.
+const MAX_TEMPLATE_BYTES: u32 = 1024 * 1024;
+
+/// Fetches a config template over HTTPS with `curl`, which ships with macOS, Windows and
+/// most Linux installs; redirects to other schemes are refused as well. `GH_WATCH_CURL_BIN`
+/// points at another binary.
+pub fn download_template(url: &str) -> Result<String> {
+    if !url.starts_with("https://") {
+        return Err(anyhow!("template URL must start with https://: {url}"));
+    }
+
+    let curl_bin = std::env::var_os("GH_WATCH_CURL_BIN")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("curl"));
+    let output = Command::new(&curl_bin)
+        .args([
+            "--fail",
+            "--silent",
+            "--show-error",
+            "--location",
+            "--proto",
+            "=https",
+            "--proto-redir",
+            "=https",
+            "--max-time",
+            &DOWNLOAD_TIMEOUT_SECONDS.to_string(),
+            "--max-filesize",
+            &MAX_TEMPLATE_BYTES.to_string(),
+            "--",
+            url,
+        ])
+        .output()
+        .with_context(|| format!("failed to run {}", curl_bin.display()))?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "failed to download template from {url}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    String::from_utf8(output.stdout).with_context(|| format!("template at {url} is not UTF-8"))
+}
@@ -0,0 +1,81 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::ports::ViewStatePort;
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct ViewState {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    split_ratio: Option<u16>,
+}
+
+/// TOML file next to the state DB holding the TUI view settings.
+#[derive(Debug, Clone)]
+pub struct ViewStateFile {
+    path: PathBuf,
+}
+
+impl ViewStateFile {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn load(&self) -> Result<ViewState> {
+        if !self.path.exists() {
+            return Ok(ViewState::default());
+        }
+
+        let raw = fs::read_to_string(&self.path)
+            .with_context(|| format!("failed to read view state: {}", self.path.display()))?;
+        toml::from_str(&raw)
+            .with_context(|| format!("failed to parse view state: {}", self.path.display()))
+    }
+
+    fn save(&self, state: &ViewState) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create directory: {}", parent.display()))?;
+        }
+        let raw = toml::to_string(state).context("failed to serialize view state")?;
+        fs::write(&self.path, raw)
+            .with_context(|| format!("failed to write view state: {}", self.path.display()))
+    }
+}
+
+impl ViewStatePort for ViewStateFile {
+    fn load_split_ratio(&self) -> Result<Option<u16>> {
+        Ok(self.load()?.split_ratio)
+    }
+
+    fn save_split_ratio(&self, percent: u16) -> Result<()> {
+        let mut state = self.load().unwrap_or_default();
+        state.split_ratio = Some(percent);
+        self.save(&state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ViewStateFile;
+    use crate::ports::ViewStatePort;
+
+    #[test]
+    fn split_ratio_round_trips_and_defaults_to_none() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = ViewStateFile::new(dir.path().join("nested").join("view_state.toml"));
+        assert_eq!(file.load_split_ratio().unwrap(), None);
+
+        file.save_split_ratio(65).unwrap();
+
+        assert_eq!(file.load_split_ratio().unwrap(), Some(65));
+    }
+}
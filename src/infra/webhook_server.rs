@@ -0,0 +1,365 @@
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use anyhow::{anyhow, Context, Result};
+use sha2::{Digest, Sha256};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::{mpsc, Semaphore},
+};
+
+/// Far below GitHub's 25 MB cap, but well above the payloads of the events `serve` handles.
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+const MAX_HEADER_BYTES: usize = 16 * 1024;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Connections handled at once; further ones wait in the listen backlog.
+const MAX_CONNECTIONS: usize = 64;
+const HEALTH_PATH: &str = "/healthz";
+const FEED_PATH: &str = "/feed.atom";
+
+/// Renders the Atom document served at `/feed.atom`.
+pub type FeedSource = Arc<dyn Fn() -> Result<String> + Send + Sync>;
+
+/// A webhook delivery whose signature checked out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebhookDelivery {
+    /// The `X-GitHub-Event` header, e.g. `pull_request`.
+    pub event: String,
+    /// The `X-GitHub-Delivery` header, when sent.
+    pub delivery_id: Option<String>,
+    pub body: String,
+}
+
+/// Minimal HTTP/1.1 endpoint for GitHub webhooks: `POST` to any path delivers a payload
+/// signed with the shared secret, `GET /healthz` answers `ok`, and
+/// `GET /feed.atom?token=<feed_token>` serves the feed when one is attached. One request
+/// per connection.
+pub struct WebhookServer {
+    listener: TcpListener,
+    secret: Arc<[u8]>,
+    feed: Option<FeedSource>,
+    connections: Arc<Semaphore>,
+}
+
+impl WebhookServer {
+    pub async fn bind(addr: &str, secret: &str) -> Result<Self> {
+        if secret.is_empty() {
+            return Err(anyhow!("webhook secret must not be empty"));
+        }
+        let listener = TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("failed to listen on {addr}"))?;
+        Ok(Self {
+            listener,
+            secret: Arc::from(secret.as_bytes()),
+            feed: None,
+            connections: Arc::new(Semaphore::new(MAX_CONNECTIONS)),
+        })
+    }
+
+    pub fn with_max_connections(mut self, max_connections: usize) -> Self {
+        self.connections = Arc::new(Semaphore::new(max_connections));
+        self
+    }
+
+    pub fn with_feed(mut self, feed: FeedSource) -> Self {
+        self.feed = Some(feed);
+        self
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Accepts connections until `deliveries` is closed, forwarding each verified delivery.
+    pub async fn run(self, deliveries: mpsc::Sender<WebhookDelivery>) -> Result<()> {
+        loop {
+            let permit = tokio::select! {
+                permit = Arc::clone(&self.connections).acquire_owned() => permit?,
+                () = deliveries.closed() => return Ok(()),
+            };
+            let (stream, peer) = tokio::select! {
+                accepted = self.listener.accept() => accepted?,
+                () = deliveries.closed() => return Ok(()),
+            };
+            let routes = Routes {
+                secret: Arc::clone(&self.secret),
+                feed: self.feed.clone(),
+                deliveries: deliveries.clone(),
+            };
+            tokio::spawn(async move {
+                let _permit = permit;
+                if let Err(err) = handle_connection(stream, &routes).await {
+                    tracing::debug!(error = %err, %peer, "webhook connection failed");
+                }
+            });
+        }
+    }
+}
+
+struct Routes {
+    secret: Arc<[u8]>,
+    feed: Option<FeedSource>,
+    deliveries: mpsc::Sender<WebhookDelivery>,
+}
+
+struct Request {
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+struct Response {
+    status: u16,
+    reason: &'static str,
+    content_type: &'static str,
+    body: String,
+}
+
+impl Response {
+    fn new(status: u16, reason: &'static str, body: &str) -> Self {
+        Self {
+            status,
+            reason,
+            content_type: "text/plain; charset=utf-8",
+            body: body.to_string(),
+        }
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, routes: &Routes) -> Result<()> {
+    let response = match tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream)).await {
+        Ok(Ok(request)) => route(request, routes).await,
+        Ok(Err(response)) => response,
+        Err(_) => Response::new(408, "Request Timeout", "request timed out\n"),
+    };
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        response.reason,
+        response.content_type,
+        response.body.len()
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(response.body.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+async fn route(request: Request, routes: &Routes) -> Response {
+    let path = request.path.split('?').next().unwrap_or_default();
+    match (request.method.as_str(), path) {
+        ("GET", HEALTH_PATH) => Response::new(200, "OK", "ok\n"),
+        ("GET", FEED_PATH) => serve_feed(&request, routes),
+        (_, HEALTH_PATH | FEED_PATH) => Response::new(405, "Method Not Allowed", "use GET\n"),
+        ("POST", _) => accept_delivery(request, &routes.secret, &routes.deliveries).await,
+        _ => Response::new(404, "Not Found", "not found\n"),
+    }
+}
+
+fn serve_feed(request: &Request, routes: &Routes) -> Response {
+    let Some(feed) = &routes.feed else {
+        return Response::new(404, "Not Found", "not found\n");
+    };
+    let token = request.path.split_once('?').and_then(|(_, query)| {
+        query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("token="))
+    });
+    if !token.is_some_and(|token| constant_time_eq(&feed_token(&routes.secret), token)) {
+        return Response::new(401, "Unauthorized", "missing or invalid token\n");
+    }
+    match feed() {
+        Ok(body) => Response {
+            status: 200,
+            reason: "OK",
+            content_type: "application/atom+xml; charset=utf-8",
+            body,
+        },
+        Err(err) => {
+            tracing::warn!(error = %err, "feed rendering failed");
+            Response::new(500, "Internal Server Error", "feed unavailable\n")
+        }
+    }
+}
+
+async fn accept_delivery(
+    request: Request,
+    secret: &[u8],
+    deliveries: &mpsc::Sender<WebhookDelivery>,
+) -> Response {
+    let signed = request
+        .header("X-Hub-Signature-256")
+        .is_some_and(|signature| verify_signature(secret, &request.body, signature));
+    if !signed {
+        return Response::new(401, "Unauthorized", "invalid signature\n");
+    }
+    let Some(event) = request.header("X-GitHub-Event").map(str::to_string) else {
+        return Response::new(400, "Bad Request", "missing X-GitHub-Event\n");
+    };
+    if event == "ping" {
+        return Response::new(200, "OK", "pong\n");
+    }
+    let delivery_id = request.header("X-GitHub-Delivery").map(str::to_string);
+    let Ok(body) = String::from_utf8(request.body) else {
+        return Response::new(400, "Bad Request", "payload is not UTF-8\n");
+    };
+
+    let delivery = WebhookDelivery {
+        event,
+        delivery_id,
+        body,
+    };
+    match deliveries.send(delivery).await {
+        Ok(()) => Response::new(202, "Accepted", "accepted\n"),
+        Err(_) => Response::new(503, "Service Unavailable", "shutting down\n"),
+    }
+}
+
+async fn read_request(stream: &mut TcpStream) -> Result<Request, Response> {
+    let bad_request = || Response::new(400, "Bad Request", "malformed request\n");
+
+    let mut buf = Vec::with_capacity(4096);
+    let head_end = loop {
+        if let Some(pos) = buf.windows(4).position(|window| window == b"\r\n\r\n") {
+            break pos;
+        }
+        if buf.len() > MAX_HEADER_BYTES {
+            return Err(Response::new(
+                431,
+                "Request Header Fields Too Large",
+                "headers too large\n",
+            ));
+        }
+        let mut chunk = [0u8; 4096];
+        let read = stream.read(&mut chunk).await.map_err(|_| bad_request())?;
+        if read == 0 {
+            return Err(bad_request());
+        }
+        buf.extend_from_slice(&chunk[..read]);
+    };
+
+    let head = std::str::from_utf8(&buf[..head_end]).map_err(|_| bad_request())?;
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split(' ');
+    let (Some(method), Some(path)) = (request_line.next(), request_line.next()) else {
+        return Err(bad_request());
+    };
+    let headers = lines
+        .map(|line| {
+            line.split_once(':')
+                .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+                .ok_or_else(bad_request)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut request = Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        headers,
+        body: Vec::new(),
+    };
+
+    if request.method != "POST" {
+        return Ok(request);
+    }
+    if request.header("Transfer-Encoding").is_some() {
+        return Err(Response::new(
+            411,
+            "Length Required",
+            "send Content-Length\n",
+        ));
+    }
+    let content_length = match request.header("Content-Length") {
+        Some(value) => value.parse::<usize>().map_err(|_| bad_request())?,
+        None => {
+            return Err(Response::new(
+                411,
+                "Length Required",
+                "send Content-Length\n",
+            ))
+        }
+    };
+    if content_length > MAX_BODY_BYTES {
+        return Err(Response::new(
+            413,
+            "Payload Too Large",
+            "payload too large\n",
+        ));
+    }
+
+    // The buffer grows with the bytes that arrive, not with what Content-Length claims.
+    let mut body = buf.split_off(head_end + 4);
+    body.truncate(content_length);
+    let remaining = (content_length - body.len()) as u64;
+    (&mut *stream)
+        .take(remaining)
+        .read_to_end(&mut body)
+        .await
+        .map_err(|_| bad_request())?;
+    if body.len() < content_length {
+        return Err(bad_request());
+    }
+    request.body = body;
+    Ok(request)
+}
+
+/// Whether `signature` (the `X-Hub-Signature-256` header, `sha256=<hex>`) is the
+/// HMAC-SHA256 of `body` under `secret`. Compared in constant time.
+pub fn verify_signature(secret: &[u8], body: &[u8], signature: &str) -> bool {
+    let Some(hex) = signature.strip_prefix("sha256=") else {
+        return false;
+    };
+    constant_time_eq(
+        &to_hex(&hmac_sha256(secret, body)),
+        &hex.to_ascii_lowercase(),
+    )
+}
+
+/// The `token` query parameter `/feed.atom` requires. Derived from the webhook secret, so
+/// feed readers never hold the secret that signs deliveries.
+pub fn feed_token(secret: &[u8]) -> String {
+    to_hex(&hmac_sha256(secret, b"gh-watch feed"))
+}
+
+fn constant_time_eq(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// HMAC (RFC 2104) over SHA-256.
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    const BLOCK_SIZE: usize = 64;
+
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(block.map(|byte| byte ^ 0x36));
+    inner.update(message);
+    let mut outer = Sha256::new();
+    outer.update(block.map(|byte| byte ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
@@ -1,23 +1,244 @@
-use std::collections::HashSet;
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
 
 use anyhow::Result;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use futures_util::Stream;
+use serde::{Deserialize, Serialize};
 
-use crate::domain::events::WatchEvent;
+use crate::domain::{
+    events::{EventKind, WatchEvent},
+    failure::FailureRecord,
+    subject_state::{SubjectRef, SubjectState},
+};
+
+/// The account gh is authenticated as.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthInfo {
+    pub login: String,
+    /// OAuth scopes of the token; empty when gh does not report them, e.g. for `GH_TOKEN`.
+    pub scopes: Vec<String>,
+    pub hostname: String,
+}
 
 #[async_trait]
 pub trait GhClientPort: Send + Sync {
-    async fn check_auth(&self) -> Result<()>;
+    async fn check_auth(&self) -> Result<AuthInfo>;
     async fn viewer_login(&self) -> Result<String>;
-    async fn fetch_repo_events(&self, repo: &str, since: DateTime<Utc>) -> Result<Vec<WatchEvent>>;
+    async fn fetch_repo_events(&self, repo: &str, since: DateTime<Utc>) -> Result<RepoEventFetch>;
+    /// `fetch_repo_events` for the first poll of a repository; clients may answer it from
+    /// the on-disk response cache (`poll.cache_ttl_seconds`).
+    async fn fetch_bootstrap_repo_events(
+        &self,
+        repo: &str,
+        since: DateTime<Utc>,
+    ) -> Result<RepoEventFetch> {
+        self.fetch_repo_events(repo, since).await
+    }
+    /// Events of several repositories, keyed by repository name. Repositories that could not
+    /// be fetched are left out of the map for the caller to retry one by one.
+    async fn batch_fetch_repo_events(
+        &self,
+        repos: &[(&str, DateTime<Utc>)],
+    ) -> Result<HashMap<String, RepoEventFetch>> {
+        let mut fetches = HashMap::new();
+        for (repo, since) in repos {
+            if let Ok(fetch) = self.fetch_repo_events(repo, *since).await {
+                fetches.insert((*repo).to_string(), fetch);
+            }
+        }
+        Ok(fetches)
+    }
+    async fn list_org_members(&self, org: &str) -> Result<Vec<String>>;
+    async fn list_team_members(&self, org: &str, team: &str) -> Result<Vec<String>>;
+    /// Review events (approved, changes requested, commented, dismissed) submitted after `since`.
+    async fn fetch_pr_reviews(
+        &self,
+        repo: &str,
+        pr_number: i64,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<WatchEvent>>;
+    /// Review threads of recently updated open pull requests, via GraphQL `reviewThreads`.
+    async fn fetch_review_threads(&self, repo: &str) -> Result<ReviewThreadFetch>;
+    /// Review decision and combined check status of the viewer's open pull requests in
+    /// `repos`, via GraphQL `search`, keyed by repository name.
+    async fn fetch_my_open_prs(&self, repos: &[&str]) -> Result<HashMap<String, MyPrFetch>>;
+    /// Whether the pull request is still open; clients may cache the answer for a while.
+    async fn is_pull_request_open(&self, repo: &str, pr_number: i64) -> Result<bool>;
+    /// Files changed by the pull request, via `pulls/{number}/files`.
+    async fn fetch_pr_file_changes(&self, repo: &str, pr_number: i64) -> Result<Vec<PrFileChange>>;
+    /// Content of `file_name` in the gist, or `None` when the gist has no such file.
+    async fn read_gist_file(&self, gist_id: &str, file_name: &str) -> Result<Option<String>>;
+    /// Creates or replaces `file_name` in the gist.
+    async fn write_gist_file(&self, gist_id: &str, file_name: &str, content: &str) -> Result<()>;
+    /// Up to `limit` issue search results for `query`, restricted to `repos` when given,
+    /// as created events.
+    async fn search_issues(
+        &self,
+        query: &str,
+        repos: &[&str],
+        limit: usize,
+    ) -> Result<Vec<WatchEvent>>;
+
+    /// Total gh invocations that reached GitHub (cache hits excluded).
+    fn api_call_count(&self) -> u64 {
+        0
+    }
+
+    /// Total gh invocations answered from the on-disk response cache.
+    fn response_cache_hits(&self) -> u64 {
+        0
+    }
+
+    /// Remaining core REST quota for the current hour, when the client can report it.
+    async fn rate_limit_remaining(&self) -> Result<Option<u64>> {
+        Ok(None)
+    }
+
+    /// GitHub's clock, from the `Date` header of a request that costs no quota; `None` when
+    /// the client cannot tell.
+    async fn server_time(&self) -> Result<Option<DateTime<Utc>>> {
+        Ok(None)
+    }
+
+    /// Whether `repo` is still reachable under its name; asked after a fetch of it failed.
+    async fn probe_repo_access(&self, _repo: &str) -> Result<RepoAccess> {
+        Ok(RepoAccess::Accessible)
+    }
+
+    /// Current state of each issue or pull request in `subjects`; subjects GitHub does not
+    /// know are left out.
+    async fn fetch_subject_states(
+        &self,
+        _subjects: &[SubjectRef],
+    ) -> Result<HashMap<SubjectRef, SubjectState>> {
+        Ok(HashMap::new())
+    }
+
+    /// GitHub topics of `repo`, for grouping the Repositories tab.
+    async fn fetch_repository_topics(&self, _repo: &str) -> Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    /// Teams the viewer belongs to, as lowercased `org/team`.
+    async fn viewer_teams(&self) -> Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    /// Runs `gh <args>` in `cwd` as the account that watches `repo`; the error carries gh's
+    /// stderr.
+    async fn run_gh_command(
+        &self,
+        _repo: &str,
+        _args: &[String],
+        _cwd: Option<&Path>,
+    ) -> Result<()> {
+        Err(anyhow::anyhow!("this client cannot run gh commands"))
+    }
+}
+
+/// What GitHub answers for a repository name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepoAccess {
+    Accessible,
+    /// The name redirects to `new_name`.
+    Renamed {
+        new_name: String,
+    },
+    /// 404 or 403: deleted, made private, or the token lost access.
+    Inaccessible {
+        status_code: u16,
+    },
+}
+
+/// A repository the state db remembers as renamed or inaccessible, keyed by the name that
+/// was polled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepoAccessMark {
+    Renamed {
+        new_name: String,
+        marked_at: DateTime<Utc>,
+    },
+    Inaccessible {
+        status_code: u16,
+        marked_at: DateTime<Utc>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReviewThreadState {
+    pub thread_id: String,
+    pub is_resolved: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReviewThreadFetch {
+    pub states: Vec<ReviewThreadState>,
+    /// One `ReviewThreadResolved` event per resolved thread, keyed by thread id; the state
+    /// store only logs those whose thread was last seen unresolved.
+    pub resolved_events: Vec<WatchEvent>,
+}
+
+/// Last seen `reviewDecision` and `statusCheckRollup.state` of one of the viewer's pull
+/// requests, upper-case as GraphQL reports them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MyPrState {
+    pub pr_number: i64,
+    pub review_decision: Option<String>,
+    pub checks_state: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MyPrFetch {
+    pub states: Vec<MyPrState>,
+    /// `MyPrApproved` for every approved pull request and `MyPrChecksPassed` for every one
+    /// whose checks succeeded; the state store only logs those that changed since last seen.
+    pub events: Vec<WatchEvent>,
+}
+
+/// One entry of `pulls/{number}/files`; `status` is `added`, `modified`, `removed`,
+/// `renamed` and so on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrFileChange {
+    pub filename: String,
+    pub status: String,
+    pub additions: u32,
+    pub deletions: u32,
+}
+
+/// Reviewers still requested on a pull request that changed since the poll's `since`;
+/// GitHub drops a reviewer from the list once they submit a review.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PendingReviewRequests {
+    pub pull_id: String,
+    pub reviewers: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepoEventFetch {
+    pub events: Vec<WatchEvent>,
+    /// Pending reviewers of every open, non-draft pull request the fetch looked at, so the
+    /// state store can forget requests that were withdrawn or answered.
+    pub review_requests: Vec<PendingReviewRequests>,
+    /// When the oldest response served from the on-disk cache was stored; anything created
+    /// after it may be missing, so the cursor must not move past it.
+    pub cached_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone)]
 pub struct RepoPersistBatch {
     pub repo: String,
     pub poll_started_at: DateTime<Utc>,
+    /// The events come from a webhook delivery, so a milestone event is an assignment that
+    /// just happened rather than one a poll observed.
+    pub pushed: bool,
     pub events: Vec<WatchEvent>,
+    pub review_requests: Vec<PendingReviewRequests>,
+    pub review_threads: Vec<ReviewThreadState>,
+    pub my_prs: Vec<MyPrState>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -28,32 +249,264 @@ pub struct PersistBatchResult {
 pub trait CursorPort: Send + Sync {
     fn get_cursor(&self, repo: &str) -> Result<Option<DateTime<Utc>>>;
     fn set_cursor(&self, repo: &str, at: DateTime<Utc>) -> Result<()>;
+    fn load_cursors_all(&self) -> Result<HashMap<String, DateTime<Utc>>>;
+    fn load_cursors_for(&self, repos: &[String]) -> Result<HashMap<String, DateTime<Utc>>>;
+
+    fn load_repo_access_marks(&self) -> Result<HashMap<String, RepoAccessMark>> {
+        Ok(HashMap::new())
+    }
+
+    /// Records that `repo` is now `new_name` and moves its cursor and stored rows there.
+    fn mark_repo_renamed(&self, _repo: &str, _new_name: &str, _at: DateTime<Utc>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Records that `repo` answered `status_code`; returns whether it was not marked before.
+    fn mark_repo_inaccessible(
+        &self,
+        _repo: &str,
+        _status_code: u16,
+        _at: DateTime<Utc>,
+    ) -> Result<bool> {
+        Ok(false)
+    }
+
+    fn clear_repo_access_mark(&self, _repo: &str) -> Result<()> {
+        Ok(())
+    }
 }
 
 pub trait TimelineQueryPort: Send + Sync {
     fn load_timeline_events(&self, limit: usize) -> Result<Vec<WatchEvent>>;
+    /// Newest-first events created at or before `before`, for paging back past `timeline_limit`.
+    fn load_timeline_events_before(
+        &self,
+        before: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<WatchEvent>>;
+    /// Newest-first events created at or after `since` and strictly before `before`.
+    /// Pass the `created_at` of the previous page's last event as `before` to continue.
+    fn find_events_page(
+        &self,
+        since: DateTime<Utc>,
+        before: Option<DateTime<Utc>>,
+        limit: usize,
+    ) -> Result<Vec<WatchEvent>>;
+    /// `find_events_page` restricted to events of `repos` by `actors` (logins compared
+    /// case-insensitively); an empty list matches every repository or actor.
+    fn load_timeline_events_filtered(
+        &self,
+        since: DateTime<Utc>,
+        before: Option<DateTime<Utc>>,
+        repos: &[String],
+        actors: &[String],
+        limit: usize,
+    ) -> Result<Vec<WatchEvent>>;
+    /// Newest-first events whose repository, actor, title or body contain every
+    /// whitespace-separated term of `query`; a blank query matches nothing.
+    fn search_events(&self, query: &str, limit: usize) -> Result<Vec<WatchEvent>>;
     fn load_read_event_keys(&self, event_keys: &[String]) -> Result<HashSet<String>>;
+    /// The logged event stored under `event_key`, or `None` when there is none.
+    fn get_event_by_key(&self, event_key: &str) -> Result<Option<WatchEvent>>;
+    /// The logged events stored under `event_keys`, in the order given; unknown and
+    /// repeated keys are skipped.
+    fn get_events_by_keys(&self, event_keys: &[String]) -> Result<Vec<WatchEvent>>;
+    /// Read and unread counts per repository of the events `load_timeline_events_filtered`
+    /// would return with no `before`, ordered by repository.
+    fn count_read_events_by_repo(
+        &self,
+        since: DateTime<Utc>,
+        repos: &[String],
+        actors: &[String],
+    ) -> Result<Vec<RepoReadCounts>>;
+}
+
+/// How many logged events of one repository have been read and how many have not.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepoReadCounts {
+    pub repo: String,
+    pub read: usize,
+    pub unread: usize,
+}
+
+/// How often `EventStreamPort::watch_event_stream` checks the log for new rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatchEventStreamConfig {
+    pub poll_interval_ms: u64,
+}
+
+impl Default for WatchEventStreamConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval_ms: 1000,
+        }
+    }
+}
+
+pub trait EventStreamPort: Send + Sync {
+    /// Events appended to the log at or after `since` (by when they were stored, not
+    /// `created_at`), oldest append first, then each new one as it is written. The stream
+    /// never ends; a failed read is yielded as an error and retried on the next tick.
+    fn watch_event_stream(
+        &self,
+        since: DateTime<Utc>,
+    ) -> impl Stream<Item = Result<WatchEvent>> + Send + '_;
 }
 
 pub trait TimelineReadMarkPort: Send + Sync {
     fn mark_timeline_event_read(&self, event_key: &str, read_at: DateTime<Utc>) -> Result<()>;
+    /// Marks every unread stored event read at `read_at` and returns how many were unread;
+    /// events already read keep their `read_at`.
+    fn mark_all_timeline_events_read(&self, read_at: DateTime<Utc>) -> Result<usize>;
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    pub default_days: u32,
+    pub days_by_kind: HashMap<EventKind, u32>,
+    pub keep_unread: bool,
+    /// Delivered and read events kept regardless of age; older ones are deleted first.
+    pub max_read_history: Option<usize>,
+}
+
+impl RetentionPolicy {
+    pub fn days_for(&self, kind: &EventKind) -> u32 {
+        self.days_by_kind
+            .get(kind)
+            .copied()
+            .unwrap_or(self.default_days)
+    }
 }
 
 pub trait RetentionPort: Send + Sync {
-    fn cleanup_old(&self, retention_days: u32, now: DateTime<Utc>) -> Result<()>;
+    fn cleanup_old(&self, policy: &RetentionPolicy, now: DateTime<Utc>) -> Result<()>;
+    /// Logged events that were both delivered and read, i.e. what `max_read_history` bounds.
+    fn count_delivered_read_events(&self) -> Result<usize>;
 }
 
 pub trait RepoBatchPort: Send + Sync {
     fn persist_repo_batch(&self, batch: &RepoPersistBatch) -> Result<PersistBatchResult>;
+
+    /// Changed files of the pull request last saved at or after `since` by
+    /// `save_pr_file_changes`; `None` when there are none to reuse.
+    fn load_pr_file_changes(
+        &self,
+        _repo: &str,
+        _pr_number: i64,
+        _since: DateTime<Utc>,
+    ) -> Result<Option<Vec<PrFileChange>>> {
+        Ok(None)
+    }
+
+    /// Caches the changed files of pull request `pr_number` on the logged event `event_key`.
+    fn save_pr_file_changes(
+        &self,
+        _event_key: &str,
+        _pr_number: i64,
+        _files: &[PrFileChange],
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Records when the logged events `event_keys` were notified about; the first time wins.
+    fn mark_events_notified(
+        &self,
+        _event_keys: &[String],
+        _notified_at: DateTime<Utc>,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+pub trait FailureLogPort: Send + Sync {
+    fn record_failure(&self, failure: &FailureRecord) -> Result<()>;
+    /// Failures logged for `repo` at or after `since`.
+    fn get_failure_count_for_repo(&self, repo: &str, since: DateTime<Utc>) -> Result<usize>;
+}
+
+/// The viewer's teams (`org/team`, lowercased) for team mentions under `only_involving_me`,
+/// so `gh api user/teams` runs about once a day.
+pub trait ViewerTeamsPort: Send + Sync {
+    /// Cached teams with when they were fetched; `None` when never fetched or cleared.
+    fn load_viewer_teams(&self) -> Result<Option<(Vec<String>, DateTime<Utc>)>>;
+    fn save_viewer_teams(&self, teams: &[String], fetched_at: DateTime<Utc>) -> Result<()>;
+    fn clear_viewer_teams(&self) -> Result<()>;
 }
 
-pub trait PollStatePort: CursorPort + RetentionPort + RepoBatchPort {}
+pub trait PollStatePort:
+    CursorPort + RetentionPort + RepoBatchPort + FailureLogPort + ViewerTeamsPort
+{
+}
 
-impl<T> PollStatePort for T where T: CursorPort + RetentionPort + RepoBatchPort {}
+impl<T> PollStatePort for T where
+    T: CursorPort + RetentionPort + RepoBatchPort + FailureLogPort + ViewerTeamsPort
+{
+}
 
-pub trait WatchStatePort: PollStatePort + TimelineQueryPort + TimelineReadMarkPort {}
+/// When the `[notifications.summary]` notification last went out, so restarts do not resend it.
+pub trait SummaryStatePort: Send + Sync {
+    fn load_summary_sent_at(&self) -> Result<Option<DateTime<Utc>>>;
+    fn save_summary_sent_at(&self, sent_at: DateTime<Utc>) -> Result<()>;
+}
 
-impl<T> WatchStatePort for T where T: PollStatePort + TimelineQueryPort + TimelineReadMarkPort {}
+/// When each pull request was last reminded about under `[reminders]`, so reminders go out
+/// at most once a day across restarts.
+pub trait ReminderStatePort: Send + Sync {
+    fn load_review_reminded_at(&self, repo: &str, pr_number: i64) -> Result<Option<DateTime<Utc>>>;
+    fn save_review_reminded_at(
+        &self,
+        repo: &str,
+        pr_number: i64,
+        reminded_at: DateTime<Utc>,
+    ) -> Result<()>;
+}
+
+/// Read marks exchanged with other machines by `[sync]`, keyed by event key.
+pub trait ReadSyncStatePort: Send + Sync {
+    /// Read timeline events plus every mark merged earlier, at the earliest read time per key.
+    fn load_read_marks(&self) -> Result<HashMap<String, DateTime<Utc>>>;
+    /// Keeps `marks` and marks the matching unread timeline events read; returns the keys of
+    /// the events that became read. Never clears a read mark.
+    fn merge_read_marks(&self, marks: &HashMap<String, DateTime<Utc>>) -> Result<Vec<String>>;
+}
+
+/// Subject states looked up for `display.resolve_subject_state`, so restarts and later
+/// polls reuse them until they go stale.
+pub trait SubjectStatePort: Send + Sync {
+    /// Cached state of each of `subjects` with when it was fetched; subjects never fetched
+    /// are left out.
+    fn load_subject_states(
+        &self,
+        subjects: &[SubjectRef],
+    ) -> Result<HashMap<SubjectRef, (SubjectState, DateTime<Utc>)>>;
+    fn save_subject_states(
+        &self,
+        states: &HashMap<SubjectRef, SubjectState>,
+        fetched_at: DateTime<Utc>,
+    ) -> Result<()>;
+}
+
+pub trait WatchStatePort:
+    PollStatePort
+    + TimelineQueryPort
+    + TimelineReadMarkPort
+    + SummaryStatePort
+    + ReminderStatePort
+    + ReadSyncStatePort
+    + SubjectStatePort
+{
+}
+
+impl<T> WatchStatePort for T where
+    T: PollStatePort
+        + TimelineQueryPort
+        + TimelineReadMarkPort
+        + SummaryStatePort
+        + ReminderStatePort
+        + ReadSyncStatePort
+        + SubjectStatePort
+{
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum NotificationClickSupport {
@@ -76,8 +529,20 @@ pub struct NotificationDigest {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum NotificationPayload {
-    Event(WatchEvent),
+    Event(Box<WatchEvent>),
     Digest(NotificationDigest),
+    Alert(String),
+    /// Scheduled `[notifications.summary]` text.
+    Summary(String),
+    ReviewReminderDue(Box<ReviewReminder>),
+}
+
+/// A review request to the viewer still waiting after `reminders.review_request_after_days`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReviewReminder {
+    /// The `PrReviewRequested` event the viewer has not acted on.
+    pub request: WatchEvent,
+    pub waiting_days: i64,
 }
 
 pub trait NotifierPort: Send + Sync {
@@ -88,8 +553,30 @@ pub trait NotifierPort: Send + Sync {
         payload: &NotificationPayload,
         include_url: bool,
     ) -> Result<NotificationDispatchResult>;
+
+    /// Sends notifications held back earlier, when they may go out now; returns how many.
+    fn drain_notification_queue(&self) -> Result<usize> {
+        Ok(0)
+    }
+
+    /// Whether notifications are being held back right now.
+    fn is_notification_suppressed(&self) -> bool {
+        false
+    }
 }
 
 pub trait ClockPort: Send + Sync {
     fn now(&self) -> DateTime<Utc>;
 }
+
+pub trait FilterProfilePort: Send + Sync {
+    fn load_active_filter_profile(&self) -> Result<Option<String>>;
+    fn save_active_filter_profile(&self, name: &str) -> Result<()>;
+}
+
+/// TUI view settings kept across restarts.
+pub trait ViewStatePort: Send + Sync {
+    /// Timeline share of the split layout's width, in percent.
+    fn load_split_ratio(&self) -> Result<Option<u16>>;
+    fn save_split_ratio(&self, percent: u16) -> Result<()>;
+}
@@ -1,12 +1,14 @@
+use chrono::{DateTime, Utc};
 use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
 use ratatui::layout::Rect;
 
 use super::{
-    layout::{contains_point, timeline_inner_area},
-    model::TuiModel,
+    keymap::Keymap,
+    layout::{contains_point, context_menu_area, shrink_by_border, timeline_inner_area},
+    model::{ActiveTab, ContextMenu, ContextMenuAction, FocusedPane, TuiModel, KIND_FILTER_KEYS},
 };
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum InputCommand {
     ScrollUp,
     ScrollDown,
@@ -15,9 +17,41 @@ pub enum InputCommand {
     PageDown,
     JumpTop,
     JumpBottom,
+    ToggleGroupByRepo,
+    LoadOlderHistory,
+    /// Opens the `/` prompt; the committed query searches the whole state db.
+    StartSearch,
+    NextSearchResult,
+    PrevSearchResult,
+    NarrowSplit,
+    WidenSplit,
+    /// Toggles the split layout for the session.
+    ToggleSplitPane,
+    /// Moves focus between the timeline and the detail pane; `next_tab` in the split layout.
+    SwitchPane,
+    /// 1-based slot into `KIND_FILTER_KEYS`.
+    ToggleKindFilter(usize),
+    ClearKindFilter,
+    /// Hides events whose issue or pull request is closed or merged.
+    ToggleHideClosed,
+    /// Marks every stored event read; Timeline tab only.
+    MarkAllRead,
     ToggleHelp,
     Refresh,
     OpenSelectedUrl,
+    GhViewSelected,
+    GhCheckoutSelected,
+    YankUrl,
+    YankEventKey,
+    /// Right click on an event row; the menu is anchored at the clicked cell.
+    OpenContextMenu {
+        index: usize,
+        column: u16,
+        row: u16,
+    },
+    /// Left click on an entry of the open context menu.
+    ContextMenuPick(usize),
+    DismissContextMenu,
     NextTab,
     PrevTab,
     EscapePressed,
@@ -25,31 +59,39 @@ pub enum InputCommand {
     None,
 }
 
-pub fn parse_input(key: KeyEvent) -> InputCommand {
+/// `Esc` and the `0`-`9` kind filter keys apply only when `keymap` does not bind them.
+pub fn parse_input(key: KeyEvent, keymap: &Keymap) -> InputCommand {
+    if let Some(command) = keymap.command_for(key.code) {
+        return command;
+    }
+
     match key.code {
-        KeyCode::Char('q') => InputCommand::Quit,
-        KeyCode::Char('r') => InputCommand::Refresh,
-        KeyCode::Char('?') => InputCommand::ToggleHelp,
-        KeyCode::Tab => InputCommand::NextTab,
-        KeyCode::BackTab => InputCommand::PrevTab,
         KeyCode::Esc => InputCommand::EscapePressed,
-        KeyCode::Enter => InputCommand::OpenSelectedUrl,
-        KeyCode::Up | KeyCode::Char('k') => InputCommand::ScrollUp,
-        KeyCode::Down | KeyCode::Char('j') => InputCommand::ScrollDown,
-        KeyCode::PageUp => InputCommand::PageUp,
-        KeyCode::PageDown => InputCommand::PageDown,
-        KeyCode::Home | KeyCode::Char('g') => InputCommand::JumpTop,
-        KeyCode::End | KeyCode::Char('G') => InputCommand::JumpBottom,
+        KeyCode::Char('0') => InputCommand::ClearKindFilter,
+        KeyCode::Char(digit @ '1'..='9') => {
+            InputCommand::ToggleKindFilter(digit as usize - '0' as usize)
+        }
         _ => InputCommand::None,
     }
 }
 
-pub fn parse_mouse_input(mouse: MouseEvent, terminal_area: Rect, model: &TuiModel) -> InputCommand {
+/// Clicking the row clicked last within `DOUBLE_CLICK_WINDOW_MS` of `now` opens it.
+pub fn parse_mouse_input(
+    mouse: MouseEvent,
+    terminal_area: Rect,
+    model: &TuiModel,
+    now: DateTime<Utc>,
+) -> InputCommand {
+    if let Some(menu) = model.context_menu {
+        return parse_context_menu_mouse(mouse, terminal_area, menu);
+    }
+
     if !model.active_tab.supports_timeline_navigation() {
         return InputCommand::None;
     }
 
-    let timeline_inner = timeline_inner_area(terminal_area);
+    let timeline_inner =
+        timeline_inner_area(terminal_area, model.split_ratio_for(terminal_area.width));
     if !contains_point(timeline_inner, mouse.column, mouse.row) {
         return InputCommand::None;
     }
@@ -58,74 +100,210 @@ pub fn parse_mouse_input(mouse: MouseEvent, terminal_area: Rect, model: &TuiMode
         MouseEventKind::ScrollUp => InputCommand::ScrollUp,
         MouseEventKind::ScrollDown => InputCommand::ScrollDown,
         MouseEventKind::Down(MouseButton::Left) => {
-            if model.timeline.is_empty() {
-                return InputCommand::None;
+            match event_index_at(mouse, timeline_inner, model) {
+                Some(index) if model.is_double_click(index, now) => InputCommand::OpenSelectedUrl,
+                Some(index) => InputCommand::SelectIndex(index),
+                None => InputCommand::None,
             }
+        }
+        MouseEventKind::Down(MouseButton::Right) => event_index_at(mouse, timeline_inner, model)
+            .map_or(InputCommand::None, |index| InputCommand::OpenContextMenu {
+                index,
+                column: mouse.column,
+                row: mouse.row,
+            }),
+        _ => InputCommand::None,
+    }
+}
 
-            let row = mouse.row.saturating_sub(timeline_inner.y) as usize;
-            if row == 0 {
-                return InputCommand::None;
-            }
+fn event_index_at(mouse: MouseEvent, timeline_inner: Rect, model: &TuiModel) -> Option<usize> {
+    if model.timeline.is_empty() {
+        return None;
+    }
+
+    let row = mouse.row.saturating_sub(timeline_inner.y) as usize;
+    if row == 0 {
+        return None;
+    }
+
+    model.event_index_at_row(model.timeline_offset + row.saturating_sub(1))
+}
 
-            let index = model.timeline_offset + row.saturating_sub(1);
-            if index < model.timeline.len() {
-                InputCommand::SelectIndex(index)
+/// While the menu is open the wheel moves its cursor and any click outside it closes it.
+fn parse_context_menu_mouse(
+    mouse: MouseEvent,
+    terminal_area: Rect,
+    menu: ContextMenu,
+) -> InputCommand {
+    let area = context_menu_area(
+        terminal_area,
+        menu.anchor,
+        ContextMenuAction::ALL.len() as u16,
+    );
+    let inner = shrink_by_border(area);
+    match mouse.kind {
+        MouseEventKind::ScrollUp => InputCommand::ScrollUp,
+        MouseEventKind::ScrollDown => InputCommand::ScrollDown,
+        MouseEventKind::Down(MouseButton::Left)
+            if contains_point(inner, mouse.column, mouse.row) =>
+        {
+            let entry = (mouse.row - inner.y) as usize;
+            if entry < ContextMenuAction::ALL.len() {
+                InputCommand::ContextMenuPick(entry)
             } else {
                 InputCommand::None
             }
         }
+        MouseEventKind::Down(_) if !contains_point(area, mouse.column, mouse.row) => {
+            InputCommand::DismissContextMenu
+        }
         _ => InputCommand::None,
     }
 }
 
 pub fn handle_input(model: &mut TuiModel, command: InputCommand) {
+    if let Some(menu) = model.context_menu.as_mut() {
+        match command {
+            InputCommand::ScrollUp => {
+                menu.selected = menu.selected.saturating_sub(1);
+                return;
+            }
+            InputCommand::ScrollDown => {
+                menu.selected = (menu.selected + 1).min(ContextMenuAction::ALL.len() - 1);
+                return;
+            }
+            InputCommand::DismissContextMenu => {
+                model.context_menu = None;
+                return;
+            }
+            _ => {}
+        }
+    }
+
+    let previous_selected = model.selected;
+    let detail_focused = model.focused_pane == FocusedPane::Detail;
     match command {
         InputCommand::ToggleHelp => {
             model.help_visible = !model.help_visible;
         }
-        InputCommand::NextTab => {
-            model.set_active_tab(model.active_tab.next());
+        InputCommand::SwitchPane => {
+            model.focused_pane = match model.focused_pane {
+                FocusedPane::Timeline => FocusedPane::Detail,
+                FocusedPane::Detail => FocusedPane::Timeline,
+            };
         }
-        InputCommand::PrevTab => {
-            model.set_active_tab(model.active_tab.prev());
+        InputCommand::ScrollUp if detail_focused => {
+            model.detail_scroll = model.detail_scroll.saturating_sub(1);
         }
-        InputCommand::ScrollUp => {
-            if model.active_tab.supports_timeline_navigation() {
-                model.selected = model.selected.saturating_sub(1);
-            }
+        InputCommand::ScrollDown if detail_focused => {
+            model.detail_scroll = model.detail_scroll.saturating_add(1);
         }
-        InputCommand::ScrollDown => {
-            if model.active_tab.supports_timeline_navigation() && !model.timeline.is_empty() {
-                model.selected = (model.selected + 1).min(model.timeline.len() - 1);
-            }
+        InputCommand::PageUp if detail_focused => {
+            model.detail_scroll = model.detail_scroll.saturating_sub(model.page_size() as u16);
         }
-        InputCommand::PageUp => {
-            if model.active_tab.supports_timeline_navigation() && !model.timeline.is_empty() {
-                model.selected = model.selected.saturating_sub(model.page_size());
-            }
+        InputCommand::PageDown if detail_focused => {
+            model.detail_scroll = model.detail_scroll.saturating_add(model.page_size() as u16);
         }
-        InputCommand::PageDown => {
-            if model.active_tab.supports_timeline_navigation() && !model.timeline.is_empty() {
-                model.selected = (model.selected + model.page_size()).min(model.timeline.len() - 1);
-            }
+        InputCommand::JumpTop if detail_focused => {
+            model.detail_scroll = 0;
         }
-        InputCommand::JumpTop => {
-            if model.active_tab.supports_timeline_navigation() && !model.timeline.is_empty() {
-                model.selected = 0;
+        InputCommand::NextTab | InputCommand::PrevTab => {
+            let tab = if command == InputCommand::NextTab {
+                model.active_tab.next()
+            } else {
+                model.active_tab.prev()
+            };
+            model.set_active_tab(tab);
+            if tab == ActiveTab::Repositories {
+                model.clear_error_badge();
             }
         }
-        InputCommand::JumpBottom => {
-            if model.active_tab.supports_timeline_navigation() && !model.timeline.is_empty() {
-                model.selected = model.timeline.len() - 1;
+        InputCommand::ToggleKindFilter(slot) if model.active_tab.supports_timeline_navigation() => {
+            if let Some(kind) = slot
+                .checked_sub(1)
+                .and_then(|index| KIND_FILTER_KEYS.get(index))
+            {
+                model.toggle_kind_filter(kind);
             }
         }
-        InputCommand::SelectIndex(index) => {
-            if model.active_tab.supports_timeline_navigation() && !model.timeline.is_empty() {
-                model.selected = index.min(model.timeline.len() - 1);
-            }
+        InputCommand::ClearKindFilter if model.active_tab.supports_timeline_navigation() => {
+            model.clear_kind_filter();
+        }
+        InputCommand::ToggleGroupByRepo if model.active_tab.supports_timeline_navigation() => {
+            model.set_group_by_repo(!model.group_by_repo());
+        }
+        InputCommand::ToggleHideClosed if model.active_tab.supports_timeline_navigation() => {
+            model.set_hide_closed(!model.hide_closed());
+        }
+        InputCommand::ScrollUp if model.active_tab.supports_timeline_navigation() => {
+            model.selected = model.selected.saturating_sub(1);
+        }
+        InputCommand::ScrollDown
+            if model.active_tab.supports_timeline_navigation() && !model.timeline.is_empty() =>
+        {
+            model.selected = (model.selected + 1).min(model.timeline.len() - 1);
+        }
+        InputCommand::PageUp
+            if model.active_tab.supports_timeline_navigation() && !model.timeline.is_empty() =>
+        {
+            model.selected = model.selected.saturating_sub(model.page_size());
+        }
+        InputCommand::PageDown
+            if model.active_tab.supports_timeline_navigation() && !model.timeline.is_empty() =>
+        {
+            model.selected = (model.selected + model.page_size()).min(model.timeline.len() - 1);
+        }
+        InputCommand::JumpTop
+            if model.active_tab.supports_timeline_navigation() && !model.timeline.is_empty() =>
+        {
+            model.selected = 0;
+        }
+        InputCommand::JumpBottom
+            if model.active_tab.supports_timeline_navigation() && !model.timeline.is_empty() =>
+        {
+            model.selected = model.timeline.len() - 1;
+        }
+        InputCommand::SelectIndex(index)
+            if model.active_tab.supports_timeline_navigation() && !model.timeline.is_empty() =>
+        {
+            model.selected = index.min(model.timeline.len() - 1);
+        }
+        InputCommand::NextSearchResult if model.active_tab.supports_timeline_navigation() => {
+            model.next_search_result();
+        }
+        InputCommand::PrevSearchResult if model.active_tab.supports_timeline_navigation() => {
+            model.prev_search_result();
+        }
+        InputCommand::OpenContextMenu { index, column, row }
+            if model.active_tab.supports_timeline_navigation() && !model.timeline.is_empty() =>
+        {
+            model.selected = index.min(model.timeline.len() - 1);
+            model.context_menu = Some(ContextMenu {
+                anchor: (column, row),
+                selected: 0,
+            });
+            model.last_click = None;
+        }
+        InputCommand::DismissContextMenu => {
+            model.context_menu = None;
         }
         _ => {}
     }
+    if model.selected != previous_selected {
+        model.detail_scroll = 0;
+    }
+
+    if matches!(
+        command,
+        InputCommand::ScrollUp
+            | InputCommand::ScrollDown
+            | InputCommand::PageUp
+            | InputCommand::PageDown
+            | InputCommand::JumpTop
+            | InputCommand::JumpBottom
+    ) {
+        model.last_click = None;
+    }
 
     if matches!(
         command,
@@ -136,6 +314,7 @@ pub fn handle_input(model: &mut TuiModel, command: InputCommand) {
             | InputCommand::JumpTop
             | InputCommand::JumpBottom
             | InputCommand::SelectIndex(_)
+            | InputCommand::OpenContextMenu { .. }
     ) && model.active_tab.supports_timeline_navigation()
     {
         model.sync_selected_event_key();
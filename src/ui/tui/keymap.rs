@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use crossterm::event::KeyCode;
+
+use super::input::InputCommand;
+use crate::config::{key_code_label, parse_key_code, KeymapConfig, DEFAULT_KEYMAP};
+
+/// Key bindings compiled from `[keymap]`, looked up by `parse_input`.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    commands: HashMap<KeyCode, InputCommand>,
+    shortcuts: HashMap<InputCommand, Vec<KeyCode>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::from_config(&KeymapConfig::default()).expect("default keymap is valid")
+    }
+}
+
+impl Keymap {
+    /// Configured actions are bound first, so their keys shadow another action's defaults.
+    pub fn from_config(config: &KeymapConfig) -> Result<Self> {
+        let mut keymap = Self {
+            commands: HashMap::new(),
+            shortcuts: HashMap::new(),
+        };
+
+        for (action, _) in DEFAULT_KEYMAP {
+            if let Some(keys) = config.keys_for(action) {
+                let command = action_command(action)?;
+                keymap.shortcuts.entry(command).or_default();
+                for key in keys {
+                    let code = parse_key_code(key)
+                        .ok_or_else(|| anyhow!("invalid key code '{key}' for keymap.{action}"))?;
+                    keymap.bind(code, command);
+                }
+            }
+        }
+
+        for (action, defaults) in DEFAULT_KEYMAP {
+            if config.keys_for(action).is_some() {
+                continue;
+            }
+            let command = action_command(action)?;
+            keymap.shortcuts.entry(command).or_default();
+            for key in *defaults {
+                let code = parse_key_code(key).expect("default key codes are valid");
+                if !keymap.commands.contains_key(&code) {
+                    keymap.bind(code, command);
+                }
+            }
+        }
+
+        Ok(keymap)
+    }
+
+    pub fn command_for(&self, code: KeyCode) -> Option<InputCommand> {
+        self.commands.get(&code).copied()
+    }
+
+    pub fn shortcuts(&self, command: InputCommand) -> &[KeyCode] {
+        self.shortcuts
+            .get(&command)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// `Up/k` style label for the help overlay; `-` when the action is unbound.
+    pub fn label(&self, command: InputCommand) -> String {
+        let keys = self.shortcuts(command);
+        if keys.is_empty() {
+            return "-".to_string();
+        }
+        keys.iter()
+            .map(|code| key_code_label(*code))
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Action names with their effective keys, in `DEFAULT_KEYMAP` order.
+    pub fn entries(&self) -> Vec<(&'static str, Vec<String>)> {
+        DEFAULT_KEYMAP
+            .iter()
+            .filter_map(|(action, _)| {
+                let command = action_command(action).ok()?;
+                let keys = self
+                    .shortcuts(command)
+                    .iter()
+                    .map(|code| key_code_label(*code))
+                    .collect();
+                Some((*action, keys))
+            })
+            .collect()
+    }
+
+    fn bind(&mut self, code: KeyCode, command: InputCommand) {
+        self.commands.insert(code, command);
+        self.shortcuts.entry(command).or_default().push(code);
+    }
+}
+
+fn action_command(action: &str) -> Result<InputCommand> {
+    Ok(match action {
+        "quit" => InputCommand::Quit,
+        "refresh" => InputCommand::Refresh,
+        "help" => InputCommand::ToggleHelp,
+        "next_tab" => InputCommand::NextTab,
+        "prev_tab" => InputCommand::PrevTab,
+        "open" => InputCommand::OpenSelectedUrl,
+        "gh_view" => InputCommand::GhViewSelected,
+        "gh_checkout" => InputCommand::GhCheckoutSelected,
+        "yank" => InputCommand::YankUrl,
+        "yank_event_key" => InputCommand::YankEventKey,
+        "scroll_up" => InputCommand::ScrollUp,
+        "scroll_down" => InputCommand::ScrollDown,
+        "page_up" => InputCommand::PageUp,
+        "page_down" => InputCommand::PageDown,
+        "top" => InputCommand::JumpTop,
+        "bottom" => InputCommand::JumpBottom,
+        "group_by_repo" => InputCommand::ToggleGroupByRepo,
+        "load_older" => InputCommand::LoadOlderHistory,
+        "next_match" => InputCommand::NextSearchResult,
+        "prev_match" => InputCommand::PrevSearchResult,
+        "split_narrower" => InputCommand::NarrowSplit,
+        "split_wider" => InputCommand::WidenSplit,
+        "split_pane" => InputCommand::ToggleSplitPane,
+        "hide_closed" => InputCommand::ToggleHideClosed,
+        "mark_all_read" => InputCommand::MarkAllRead,
+        "search" => InputCommand::StartSearch,
+        _ => return Err(anyhow!("unknown keymap action: {action}")),
+    })
+}
@@ -5,11 +5,18 @@ pub(crate) struct UiLayout {
     pub(crate) status: Rect,
     pub(crate) tabs: Rect,
     pub(crate) content: Rect,
+    /// Two-line `Sel` panel, or the detail pane right of the timeline when `split`.
     pub(crate) selected: Rect,
     pub(crate) keys: Rect,
+    pub(crate) split: bool,
 }
 
-pub(crate) fn ui_layout(area: Rect) -> UiLayout {
+/// `split_ratio` is the timeline's share of the width in percent; `None` stacks the panels.
+pub(crate) fn ui_layout(area: Rect, split_ratio: Option<u16>) -> UiLayout {
+    if let Some(ratio) = split_ratio {
+        return split_layout(area, ratio);
+    }
+
     let vertical_areas = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -31,11 +38,45 @@ pub(crate) fn ui_layout(area: Rect) -> UiLayout {
         content: main_areas[1],
         selected: vertical_areas[2],
         keys: vertical_areas[3],
+        split: false,
     }
 }
 
-pub(crate) fn timeline_inner_area(area: Rect) -> Rect {
-    let layout = ui_layout(area);
+fn split_layout(area: Rect, ratio: u16) -> UiLayout {
+    let vertical_areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(5),
+            Constraint::Length(3),
+        ])
+        .split(area);
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(ratio),
+            Constraint::Percentage(100 - ratio),
+        ])
+        .split(vertical_areas[1]);
+
+    let main_areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(1)])
+        .split(columns[0]);
+
+    UiLayout {
+        status: vertical_areas[0],
+        tabs: main_areas[0],
+        content: main_areas[1],
+        selected: columns[1],
+        keys: vertical_areas[2],
+        split: true,
+    }
+}
+
+pub(crate) fn timeline_inner_area(area: Rect, split_ratio: Option<u16>) -> Rect {
+    let layout = ui_layout(area, split_ratio);
     shrink_by_border(layout.content)
 }
 
@@ -57,6 +98,19 @@ pub(crate) fn contains_point(area: Rect, x: u16, y: u16) -> bool {
     x >= area.x && x < x_end && y >= area.y && y < y_end
 }
 
+const CONTEXT_MENU_WIDTH: u16 = 16;
+
+/// Bordered popup just below `(column, row)`, shifted left/up to stay on screen.
+pub(crate) fn context_menu_area(area: Rect, (column, row): (u16, u16), item_count: u16) -> Rect {
+    let width = CONTEXT_MENU_WIDTH.min(area.width);
+    let height = item_count.saturating_add(2).min(area.height);
+    let x = column.clamp(area.x, area.x + area.width - width);
+    let y = row
+        .saturating_add(1)
+        .clamp(area.y, area.y + area.height - height);
+    Rect::new(x, y, width, height)
+}
+
 pub(crate) fn centered_rect(area: Rect, width_percent: u16, height_percent: u16) -> Rect {
     let popup = Layout::default()
         .direction(Direction::Vertical)
@@ -81,14 +135,35 @@ pub(crate) fn centered_rect(area: Rect, width_percent: u16, height_percent: u16)
 mod tests {
     use ratatui::layout::Rect;
 
-    use super::ui_layout;
+    use super::{context_menu_area, ui_layout};
 
     #[test]
     fn ui_layout_uses_compact_panel_heights() {
-        let layout = ui_layout(Rect::new(0, 0, 120, 40));
+        let layout = ui_layout(Rect::new(0, 0, 120, 40), None);
+        assert!(!layout.split);
         assert_eq!(layout.status.height, 3);
         assert_eq!(layout.tabs.height, 3);
         assert_eq!(layout.selected.height, 4);
         assert_eq!(layout.keys.height, 3);
+        assert_eq!(layout.content, Rect::new(0, 6, 120, 27));
+    }
+
+    #[test]
+    fn split_layout_puts_the_detail_pane_right_of_the_timeline() {
+        let layout = ui_layout(Rect::new(0, 0, 200, 40), Some(60));
+        assert!(layout.split);
+        assert_eq!(layout.content, Rect::new(0, 6, 120, 31));
+        assert_eq!(layout.selected, Rect::new(120, 3, 80, 34));
+        assert_eq!(layout.keys.height, 3);
+    }
+
+    #[test]
+    fn context_menu_area_stays_inside_the_terminal() {
+        let area = Rect::new(0, 0, 80, 24);
+        assert_eq!(context_menu_area(area, (10, 5), 3), Rect::new(10, 6, 16, 5));
+        assert_eq!(
+            context_menu_area(area, (79, 23), 3),
+            Rect::new(64, 19, 16, 5)
+        );
     }
 }
@@ -1,9 +1,15 @@
 mod input;
+mod keymap;
 mod layout;
 mod model;
 mod presentation;
 mod render;
 
 pub use input::{handle_input, parse_input, parse_mouse_input, InputCommand};
-pub use model::{ActiveTab, TuiModel};
+pub use keymap::Keymap;
+pub use model::{
+    ActiveTab, ContextMenu, ContextMenuAction, FocusedPane, TimelineItem, TuiModel,
+    DEFAULT_SPLIT_RATIO, DOUBLE_CLICK_WINDOW_MS, KIND_FILTER_KEYS, MAX_SPLIT_RATIO,
+    MAX_TIMELINE_HISTORY, MIN_SPLIT_RATIO, NOTIFICATION_INDICATOR_SECONDS, SPLIT_RATIO_STEP,
+};
 pub use render::TerminalUi;
@@ -1,12 +1,39 @@
-use std::collections::HashSet;
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
 
 use chrono::{DateTime, Utc};
+use ratatui::style::{Color, Modifier, Style};
 
-use crate::domain::{
-    events::{event_matches_notification_filters, EventKind, WatchEvent},
-    failure::FailureRecord,
+use super::keymap::Keymap;
+use crate::{
+    config::{DisplayLayout, KindLabelMode, SPLIT_LAYOUT_MIN_WIDTH},
+    domain::{
+        events::{event_matches_notification_filters, EventKind, WatchEvent},
+        failure::{FailureRecord, FailureStreaks},
+        subject_state::{SubjectRef, SubjectState},
+    },
 };
 
+/// Upper bound on events held in memory once older history has been paged in.
+pub const MAX_TIMELINE_HISTORY: usize = 5000;
+
+/// Kinds toggled by the number keys: `1` is the first entry. The other kinds have no key: the
+/// view filter cannot select them, and they are hidden while it is on.
+pub const KIND_FILTER_KEYS: [EventKind; 9] = [
+    EventKind::PrCreated,
+    EventKind::IssueCreated,
+    EventKind::IssueCommentCreated,
+    EventKind::PrReviewCommentCreated,
+    EventKind::PrReviewRequested,
+    EventKind::PrReviewSubmitted,
+    EventKind::PrMerged,
+    EventKind::PrMilestoneAdded,
+    EventKind::IssueMilestoneAdded,
+];
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ActiveTab {
     Timeline,
@@ -44,6 +71,61 @@ impl ActiveTab {
     }
 }
 
+/// Pane that navigation keys move in the split layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FocusedPane {
+    #[default]
+    Timeline,
+    /// Scrolls the detail pane instead of moving the selection.
+    Detail,
+}
+
+/// Timeline share of the split layout's width, in percent, and its `[`/`]` bounds.
+pub const DEFAULT_SPLIT_RATIO: u16 = 60;
+pub const MIN_SPLIT_RATIO: u16 = 20;
+pub const MAX_SPLIT_RATIO: u16 = 80;
+pub const SPLIT_RATIO_STEP: u16 = 5;
+
+/// A second click on the same row within this window opens it.
+pub const DOUBLE_CLICK_WINDOW_MS: i64 = 400;
+
+/// How long the status line flags a poll that sent notifications.
+pub const NOTIFICATION_INDICATOR_SECONDS: i64 = 3;
+
+/// Entries of the right-click menu, top to bottom.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContextMenuAction {
+    Open,
+    CopyUrl,
+    MarkRead,
+}
+
+impl ContextMenuAction {
+    pub const ALL: [Self; 3] = [Self::Open, Self::CopyUrl, Self::MarkRead];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Open => "Open",
+            Self::CopyUrl => "Copy URL",
+            Self::MarkRead => "Mark read",
+        }
+    }
+}
+
+/// Right-click menu for the selected event, drawn next to the clicked cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextMenu {
+    pub anchor: (u16, u16),
+    pub selected: usize,
+}
+
+/// One rendered timeline row: an event, or a repository header when grouping by repo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimelineItem<'a> {
+    Event(&'a WatchEvent),
+    Separator(&'a str),
+}
+
 #[derive(Debug, Clone)]
 pub struct TuiModel {
     pub timeline: Vec<WatchEvent>,
@@ -56,17 +138,76 @@ pub struct TuiModel {
     pub selected_event_key: Option<String>,
     pub help_visible: bool,
     pub status_line: String,
+    /// How many times in a row `set_status_line` received the current `status_line`.
+    status_repeat: u32,
+    /// Informational text appended to the ready status line.
+    pub status_notice: Option<String>,
+    /// Compiled `[keymap]`; drives `parse_input` and the help overlay.
+    pub keyboard_shortcuts: Keymap,
     pub failure_count: u64,
+    /// Consecutive failed polls per repository, for the worst offender in the status line.
+    pub failure_streaks: FailureStreaks,
+    /// Repository fetch failures since the Repositories tab was last viewed.
+    pub error_badge_count: u64,
     pub latest_failure: Option<FailureRecord>,
     pub last_success_at: Option<DateTime<Utc>>,
+    /// When the last poll that sent notifications finished.
+    pub last_notification_at: Option<DateTime<Utc>>,
     pub next_poll_at: Option<DateTime<Utc>>,
     pub is_polling: bool,
+    /// True until the first successful poll; only then does an empty timeline show `Polling…`.
+    pub is_polling_first_time: bool,
     pub poll_started_at: Option<DateTime<Utc>>,
     pub queued_refresh: bool,
     pub active_tab: ActiveTab,
     pub esc_armed_until: Option<DateTime<Utc>>,
+    pub active_filter_profile: Option<String>,
+    /// Outside `[notification_schedule]`: notifications are queued until it opens.
+    pub is_notification_suppressed: bool,
+    /// API usage of the last successful poll, shown under the Repositories tab.
+    pub api_usage: Option<String>,
+    /// Latest terminal size seen while a resize is being debounced.
+    pub pending_resize: Option<(u16, u16)>,
+    /// When the pending resize is redrawn unless another resize arrives first.
+    pub resize_deadline: Option<Instant>,
+    /// Event index and time of the last left click, for double-click detection.
+    pub last_click: Option<(usize, DateTime<Utc>)>,
+    pub context_menu: Option<ContextMenu>,
+    /// Query typed after `/`; `None` while the search prompt is closed.
+    pub search_input: Option<String>,
+    /// Ascending indices into `timeline` of events matching the active search; `None`
+    /// when no search is active. Recomputed from `search_match_keys` whenever `timeline` is
+    /// rebuilt.
+    pub search_results: Option<Vec<usize>>,
+    /// Title cell style of rows in `search_results`.
+    pub search_highlight_style: Style,
+    /// `display.layout`; resolved against the terminal width by `split_ratio_for`.
+    pub layout: DisplayLayout,
+    /// `display.kind_labels`; `Icon` is downgraded per glyph mode when rendering.
+    pub kind_labels: KindLabelMode,
+    /// `display.group_repos_by_topic`: the Repositories tab lists `repo_topics` groups.
+    pub group_repos_by_topic: bool,
+    /// Topics by repository name, filled at startup when `group_repos_by_topic` is set.
+    pub repo_topics: HashMap<String, Vec<String>>,
+    /// Always `Timeline` unless the split layout applies.
+    pub focused_pane: FocusedPane,
+    /// Lines the detail pane is scrolled down; reset when the selection moves.
+    pub detail_scroll: u16,
+    /// Event keys found by the committed `/` search.
+    search_match_keys: Option<HashSet<String>>,
+    split_ratio: u16,
+    group_by_repo: bool,
+    kind_filter: Vec<EventKind>,
+    /// Known states of the issues and pull requests in the timeline, for the badges.
+    subject_states: HashMap<SubjectRef, SubjectState>,
+    /// View filter dropping events whose subject is known to be closed or merged.
+    hide_closed: bool,
     limit: usize,
+    /// `limit` plus older events paged in this session, capped at `MAX_TIMELINE_HISTORY`.
+    history_limit: usize,
     viewer_login: Option<String>,
+    viewer_teams: Vec<String>,
+    team_members: Vec<String>,
 }
 
 impl TuiModel {
@@ -82,17 +223,51 @@ impl TuiModel {
             selected_event_key: None,
             help_visible: false,
             status_line: "starting".to_string(),
+            status_repeat: 1,
+            status_notice: None,
+            keyboard_shortcuts: Keymap::default(),
             failure_count: 0,
+            failure_streaks: FailureStreaks::default(),
+            error_badge_count: 0,
             latest_failure: None,
             last_success_at: None,
+            last_notification_at: None,
             next_poll_at: None,
             is_polling: false,
+            is_polling_first_time: true,
             poll_started_at: None,
             queued_refresh: false,
             active_tab: ActiveTab::Timeline,
             esc_armed_until: None,
+            active_filter_profile: None,
+            is_notification_suppressed: false,
+            api_usage: None,
+            pending_resize: None,
+            resize_deadline: None,
+            last_click: None,
+            context_menu: None,
+            search_input: None,
+            search_results: None,
+            search_highlight_style: Style::default()
+                .add_modifier(Modifier::BOLD)
+                .fg(Color::Yellow),
+            layout: DisplayLayout::Auto,
+            kind_labels: KindLabelMode::Short,
+            group_repos_by_topic: false,
+            repo_topics: HashMap::new(),
+            focused_pane: FocusedPane::Timeline,
+            detail_scroll: 0,
+            search_match_keys: None,
+            split_ratio: DEFAULT_SPLIT_RATIO,
+            group_by_repo: false,
+            kind_filter: Vec::new(),
+            subject_states: HashMap::new(),
+            hide_closed: false,
             limit,
+            history_limit: limit,
             viewer_login: None,
+            viewer_teams: Vec::new(),
+            team_members: Vec::new(),
         }
     }
 
@@ -111,6 +286,67 @@ impl TuiModel {
         self.rebuild_timeline(previous_selected_key);
     }
 
+    /// Appends older events loaded from the store beyond `timeline_limit`; returns how many
+    /// were new. Once `MAX_TIMELINE_HISTORY` is reached the oldest events are dropped.
+    pub fn append_history(&mut self, mut events: Vec<WatchEvent>) -> usize {
+        let previous_selected_key = self.snapshot_selected_key();
+        let known = self
+            .timeline_all
+            .iter()
+            .map(WatchEvent::event_key)
+            .collect::<HashSet<_>>();
+        events.retain(|event| !known.contains(&event.event_key()));
+        let added = events.len();
+
+        self.history_limit = (self.history_limit + added).min(MAX_TIMELINE_HISTORY.max(self.limit));
+        self.timeline_all.append(&mut events);
+        self.normalize_timeline_all();
+        self.rebuild_timeline(previous_selected_key);
+        added
+    }
+
+    /// Adds the events a committed `/` search found in the store, wherever they are in
+    /// history, makes them the `n`/`N` matches and selects the newest one shown; returns how
+    /// many matches are held.
+    pub fn apply_search_matches(&mut self, matches: Vec<WatchEvent>) -> usize {
+        let keys = matches
+            .iter()
+            .map(WatchEvent::event_key)
+            .collect::<HashSet<_>>();
+        self.search_match_keys = Some(keys.clone());
+        self.append_history(matches);
+        if let Some(&first) = self.search_results.as_ref().and_then(|r| r.first()) {
+            self.select_search_result(first);
+        }
+        self.timeline_all
+            .iter()
+            .filter(|event| keys.contains(&event.event_key()))
+            .count()
+    }
+
+    /// Ends the active search; matches lose their highlight and `n`/`N` do nothing.
+    pub fn clear_search(&mut self) {
+        self.search_match_keys = None;
+        self.search_results = None;
+    }
+
+    pub fn has_active_search(&self) -> bool {
+        self.search_results.is_some()
+    }
+
+    /// Creation time of the oldest event held, the cursor for loading older history.
+    pub fn oldest_loaded_at(&self) -> Option<DateTime<Utc>> {
+        self.timeline_all.last().map(|event| event.created_at)
+    }
+
+    pub fn history_capacity_left(&self) -> usize {
+        MAX_TIMELINE_HISTORY.saturating_sub(self.timeline_all.len())
+    }
+
+    pub fn extend_read_event_keys(&mut self, read_event_keys: HashSet<String>) {
+        self.read_event_keys.extend(read_event_keys);
+    }
+
     pub fn replace_read_event_keys(&mut self, read_event_keys: HashSet<String>) {
         self.read_event_keys = read_event_keys;
     }
@@ -128,6 +364,24 @@ impl TuiModel {
         self.rebuild_timeline(previous_selected_key);
     }
 
+    /// Teams (`org/team`) whose mentions put an event in the My PR tab.
+    pub fn set_viewer_teams(&mut self, viewer_teams: Vec<String>) {
+        self.viewer_teams = viewer_teams;
+        let previous_selected_key = self.snapshot_selected_key();
+        self.rebuild_timeline(previous_selected_key);
+    }
+
+    /// Logins whose PRs also appear in the My PR tab.
+    pub fn set_team_members(&mut self, team_members: Vec<String>) {
+        self.team_members = team_members;
+        let previous_selected_key = self.snapshot_selected_key();
+        self.rebuild_timeline(previous_selected_key);
+    }
+
+    pub fn team_members(&self) -> &[String] {
+        &self.team_members
+    }
+
     pub fn set_active_tab(&mut self, active_tab: ActiveTab) {
         if self.active_tab == active_tab {
             return;
@@ -138,6 +392,270 @@ impl TuiModel {
         self.rebuild_timeline(previous_selected_key);
     }
 
+    pub fn is_double_click(&self, index: usize, now: DateTime<Utc>) -> bool {
+        self.last_click.is_some_and(|(last_index, clicked_at)| {
+            last_index == index
+                && now >= clicked_at
+                && now - clicked_at <= chrono::Duration::milliseconds(DOUBLE_CLICK_WINDOW_MS)
+        })
+    }
+
+    pub fn context_menu_action(&self) -> Option<ContextMenuAction> {
+        self.context_menu
+            .and_then(|menu| ContextMenuAction::ALL.get(menu.selected).copied())
+    }
+
+    pub fn is_search_result(&self, index: usize) -> bool {
+        self.search_results
+            .as_ref()
+            .is_some_and(|results| results.binary_search(&index).is_ok())
+    }
+
+    /// Selects the first match after `selected`, wrapping to the first match.
+    pub fn next_search_result(&mut self) {
+        let Some(results) = self.search_results.as_ref().filter(|r| !r.is_empty()) else {
+            return;
+        };
+        let next = results
+            .iter()
+            .copied()
+            .find(|&index| index > self.selected)
+            .unwrap_or(results[0]);
+        self.select_search_result(next);
+    }
+
+    /// Selects the last match before `selected`, wrapping to the last match.
+    pub fn prev_search_result(&mut self) {
+        let Some(results) = self.search_results.as_ref().filter(|r| !r.is_empty()) else {
+            return;
+        };
+        let prev = results
+            .iter()
+            .rev()
+            .copied()
+            .find(|&index| index < self.selected)
+            .unwrap_or(results[results.len() - 1]);
+        self.select_search_result(prev);
+    }
+
+    /// `(matches at or above the selection, total)` for the `3/12 matches` status.
+    pub fn search_position(&self) -> Option<(usize, usize)> {
+        let results = self.search_results.as_ref()?;
+        let position = results
+            .iter()
+            .filter(|&&index| index <= self.selected)
+            .count();
+        Some((position, results.len()))
+    }
+
+    fn select_search_result(&mut self, index: usize) {
+        if index < self.timeline.len() {
+            self.selected = index;
+            self.sync_selected_event_key();
+        }
+    }
+
+    /// Timeline width percentage when the split layout applies at `width` columns.
+    pub fn split_ratio_for(&self, width: u16) -> Option<u16> {
+        let split = match self.layout {
+            DisplayLayout::Auto => width >= SPLIT_LAYOUT_MIN_WIDTH,
+            DisplayLayout::Stacked => false,
+            DisplayLayout::Split => true,
+        };
+        split.then_some(self.split_ratio)
+    }
+
+    pub fn split_ratio(&self) -> u16 {
+        self.split_ratio
+    }
+
+    /// Switches between the split and stacked layouts at `width` columns for this session;
+    /// leaving the split layout returns focus to the timeline.
+    pub fn toggle_split_pane(&mut self, width: u16) {
+        if self.split_ratio_for(width).is_some() {
+            self.layout = DisplayLayout::Stacked;
+            self.focused_pane = FocusedPane::Timeline;
+        } else {
+            self.layout = DisplayLayout::Split;
+        }
+    }
+
+    /// Clamps to `MIN_SPLIT_RATIO..=MAX_SPLIT_RATIO`, e.g. for a ratio loaded from disk.
+    pub fn set_split_ratio(&mut self, percent: u16) {
+        self.split_ratio = percent.clamp(MIN_SPLIT_RATIO, MAX_SPLIT_RATIO);
+    }
+
+    /// Moves the split one `SPLIT_RATIO_STEP`; returns whether the ratio changed.
+    pub fn step_split_ratio(&mut self, wider: bool) -> bool {
+        let previous = self.split_ratio;
+        self.set_split_ratio(if wider {
+            previous.saturating_add(SPLIT_RATIO_STEP)
+        } else {
+            previous.saturating_sub(SPLIT_RATIO_STEP)
+        });
+        self.split_ratio != previous
+    }
+
+    pub fn clear_error_badge(&mut self) {
+        self.error_badge_count = 0;
+    }
+
+    pub fn group_by_repo(&self) -> bool {
+        self.group_by_repo
+    }
+
+    pub fn set_group_by_repo(&mut self, group_by_repo: bool) {
+        if self.group_by_repo == group_by_repo {
+            return;
+        }
+
+        self.group_by_repo = group_by_repo;
+        let previous_selected_key = self.snapshot_selected_key();
+        self.rebuild_timeline(previous_selected_key);
+    }
+
+    /// Keyed kinds shown in the timeline tabs, in `KIND_FILTER_KEYS` order; empty shows every
+    /// kind.
+    pub fn kind_filter(&self) -> &[EventKind] {
+        &self.kind_filter
+    }
+
+    /// Toggles `kind` in the view filter. Only the rendered timeline changes.
+    pub fn toggle_kind_filter(&mut self, kind: &EventKind) {
+        if let Some(position) = self.kind_filter.iter().position(|active| active == kind) {
+            self.kind_filter.remove(position);
+        } else {
+            self.kind_filter.push(kind.clone());
+            self.kind_filter.sort_by_key(|active| {
+                KIND_FILTER_KEYS
+                    .iter()
+                    .position(|candidate| candidate == active)
+            });
+        }
+        let previous_selected_key = self.snapshot_selected_key();
+        self.rebuild_timeline(previous_selected_key);
+    }
+
+    pub fn clear_kind_filter(&mut self) {
+        if self.kind_filter.is_empty() {
+            return;
+        }
+
+        self.kind_filter.clear();
+        let previous_selected_key = self.snapshot_selected_key();
+        self.rebuild_timeline(previous_selected_key);
+    }
+
+    /// Every event held, newest first, regardless of the active tab and view filters.
+    pub fn all_events(&self) -> &[WatchEvent] {
+        &self.timeline_all
+    }
+
+    pub fn subject_state(&self, event: &WatchEvent) -> Option<SubjectState> {
+        SubjectRef::of(event).and_then(|subject| self.subject_states.get(&subject).copied())
+    }
+
+    pub fn replace_subject_states(&mut self, subject_states: HashMap<SubjectRef, SubjectState>) {
+        self.subject_states = subject_states;
+        if self.hide_closed {
+            let previous_selected_key = self.snapshot_selected_key();
+            self.rebuild_timeline(previous_selected_key);
+        }
+    }
+
+    pub fn hide_closed(&self) -> bool {
+        self.hide_closed
+    }
+
+    /// Events whose subject state is unknown stay visible.
+    pub fn set_hide_closed(&mut self, hide_closed: bool) {
+        if self.hide_closed == hide_closed {
+            return;
+        }
+
+        self.hide_closed = hide_closed;
+        let previous_selected_key = self.snapshot_selected_key();
+        self.rebuild_timeline(previous_selected_key);
+    }
+
+    /// Rows as rendered: `timeline` with a separator before each repository group.
+    pub fn timeline_items(&self) -> Vec<TimelineItem<'_>> {
+        let mut items = Vec::with_capacity(self.timeline.len());
+        for (index, event) in self.timeline.iter().enumerate() {
+            if self.starts_repo_group(index) {
+                items.push(TimelineItem::Separator(&event.repo));
+            }
+            items.push(TimelineItem::Event(event));
+        }
+        items
+    }
+
+    /// Rendered row of `timeline[event_index]`, counting separator rows above it.
+    pub fn timeline_row_of(&self, event_index: usize) -> usize {
+        let separators = (0..=event_index.min(self.timeline.len().saturating_sub(1)))
+            .filter(|index| self.starts_repo_group(*index))
+            .count();
+        event_index + separators
+    }
+
+    /// Index into `timeline` for a rendered row, or `None` for separator rows.
+    pub fn event_index_at_row(&self, row: usize) -> Option<usize> {
+        let mut rendered = 0;
+        for index in 0..self.timeline.len() {
+            if self.starts_repo_group(index) {
+                if rendered == row {
+                    return None;
+                }
+                rendered += 1;
+            }
+            if rendered == row {
+                return Some(index);
+            }
+            rendered += 1;
+        }
+        None
+    }
+
+    /// Records a resize and pushes the redraw deadline back to `now + debounce`.
+    pub fn defer_resize(&mut self, size: (u16, u16), now: Instant, debounce: Duration) {
+        self.pending_resize = Some(size);
+        self.resize_deadline = Some(now + debounce);
+    }
+
+    /// Takes the pending resize once its deadline has passed.
+    pub fn take_due_resize(&mut self, now: Instant) -> Option<(u16, u16)> {
+        if self.resize_deadline.is_some_and(|deadline| now >= deadline) {
+            self.resize_deadline = None;
+            return self.pending_resize.take();
+        }
+        None
+    }
+
+    /// Whether `now` is within `NOTIFICATION_INDICATOR_SECONDS` of the last notification.
+    pub fn notification_indicator_active(&self, now: DateTime<Utc>) -> bool {
+        self.last_notification_at
+            .is_some_and(|at| (now - at).num_seconds() < NOTIFICATION_INDICATOR_SECONDS)
+    }
+
+    /// Replaces the status line, counting consecutive identical messages instead of
+    /// flickering between them.
+    pub fn set_status_line(&mut self, status: String) {
+        if status == self.status_line {
+            self.status_repeat += 1;
+        } else {
+            self.status_line = status;
+            self.status_repeat = 1;
+        }
+    }
+
+    pub fn status_repeat(&self) -> u32 {
+        self.status_repeat
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timeline.is_empty()
+    }
+
     pub fn has_viewer_login(&self) -> bool {
         self.viewer_login.is_some()
     }
@@ -150,12 +668,16 @@ impl TuiModel {
         self.read_event_keys.contains(event_key)
     }
 
+    /// Newest first, one entry per event key, cut to `history_limit`. The sort is stable and
+    /// the first occurrence wins, so an event already held keeps its place when a poll
+    /// delivers it again, even among other events with the same `created_at`.
     fn normalize_timeline_all(&mut self) {
         self.timeline_all
-            .sort_by(|a, b| b.created_at.cmp(&a.created_at));
+            .sort_by_key(|event| Reverse(event.created_at));
+        let mut seen = HashSet::new();
         self.timeline_all
-            .dedup_by(|a, b| a.event_key() == b.event_key());
-        self.timeline_all.truncate(self.limit);
+            .retain(|event| seen.insert(event.event_key()));
+        self.timeline_all.truncate(self.history_limit);
     }
 
     fn snapshot_selected_key(&self) -> Option<String> {
@@ -169,19 +691,65 @@ impl TuiModel {
             ActiveTab::Timeline | ActiveTab::Repositories => self.timeline_all.clone(),
             ActiveTab::MyPr => self.filtered_my_pr_timeline(),
         };
+        if !self.kind_filter.is_empty() {
+            self.timeline
+                .retain(|event| self.kind_filter.contains(&event.kind));
+        }
+        if self.hide_closed {
+            let subject_states = &self.subject_states;
+            self.timeline.retain(|event| {
+                SubjectRef::of(event)
+                    .and_then(|subject| subject_states.get(&subject))
+                    .is_none_or(|state| !state.is_resolved())
+            });
+        }
+        if self.group_by_repo {
+            // Stable sort keeps the newest-first order within each repository.
+            self.timeline.sort_by(|a, b| a.repo.cmp(&b.repo));
+        }
+        if let Some(keys) = &self.search_match_keys {
+            self.search_results = Some(
+                self.timeline
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, event)| keys.contains(&event.event_key()))
+                    .map(|(index, _)| index)
+                    .collect(),
+            );
+        }
         self.restore_selection(previous_selected_key);
     }
 
+    fn starts_repo_group(&self, index: usize) -> bool {
+        self.group_by_repo
+            && match index {
+                0 => !self.timeline.is_empty(),
+                _ => self
+                    .timeline
+                    .get(index)
+                    .is_some_and(|event| event.repo != self.timeline[index - 1].repo),
+            }
+    }
+
     fn filtered_my_pr_timeline(&self) -> Vec<WatchEvent> {
-        let Some(viewer_login) = self.viewer_login.as_deref() else {
+        if self.viewer_login.is_none() && self.team_members.is_empty() {
             return Vec::new();
-        };
+        }
 
         self.timeline_all
             .iter()
             .filter(|event| is_pr_related_for_my_pr_tab(event))
             .filter(|event| {
-                event_matches_notification_filters(event, &[], &[], true, Some(viewer_login))
+                event_matches_notification_filters(
+                    event,
+                    &[],
+                    &[],
+                    true,
+                    false,
+                    self.viewer_login.as_deref(),
+                    &self.viewer_teams,
+                    &self.team_members,
+                )
             })
             .cloned()
             .collect()
@@ -223,12 +791,20 @@ fn is_pr_related_for_my_pr_tab(event: &WatchEvent) -> bool {
         | EventKind::PrReviewCommentCreated
         | EventKind::PrReviewRequested
         | EventKind::PrReviewSubmitted
-        | EventKind::PrMerged => true,
-        EventKind::IssueCreated => false,
+        | EventKind::PrReviewApproved
+        | EventKind::PrReviewChangesRequested
+        | EventKind::PrReviewDismissed
+        | EventKind::PrMerged
+        | EventKind::PrMilestoneAdded
+        | EventKind::ReviewThreadResolved
+        | EventKind::MyPrApproved
+        | EventKind::MyPrChecksPassed => true,
+        EventKind::IssueCreated | EventKind::IssueMilestoneAdded => false,
         EventKind::IssueCommentCreated => issue_comment_targets_pr(event),
     }
 }
 
 fn issue_comment_targets_pr(event: &WatchEvent) -> bool {
-    event.url.contains("/pull/") || event.url.contains("/pulls/")
+    let url = event.subject_url_or_url();
+    url.contains("/pull/") || url.contains("/pulls/")
 }
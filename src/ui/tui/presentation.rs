@@ -1,14 +1,27 @@
+use std::collections::{BTreeMap, HashMap};
+
 use chrono::{DateTime, Local, TimeZone, Utc};
 use ratatui::{
     layout::Constraint,
-    style::{Color, Style},
-    text::Span,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
     widgets::{Cell, Row},
 };
 
-use crate::domain::events::{EventKind, WatchEvent};
+use crate::{
+    config::KindLabelMode,
+    domain::{
+        events::{EventKind, WatchEvent},
+        redact::redact_secrets,
+        subject_state::SubjectState,
+    },
+};
 
-use super::model::TuiModel;
+use super::{
+    input::InputCommand,
+    keymap::Keymap,
+    model::{TuiModel, KIND_FILTER_KEYS},
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum GlyphMode {
@@ -16,11 +29,25 @@ pub(crate) enum GlyphMode {
     Ascii,
 }
 
-pub(crate) fn timeline_constraints() -> Vec<Constraint> {
+/// `display.kind_labels` as rendered: icons need Nerd Font glyphs, so ASCII mode falls
+/// back to the short abbreviations.
+pub(crate) fn effective_kind_labels(mode: KindLabelMode, glyph_mode: GlyphMode) -> KindLabelMode {
+    match (mode, glyph_mode) {
+        (KindLabelMode::Icon, GlyphMode::Ascii) => KindLabelMode::Short,
+        (mode, _) => mode,
+    }
+}
+
+pub(crate) fn timeline_constraints(kind_labels: KindLabelMode) -> Vec<Constraint> {
+    let type_width = match kind_labels {
+        KindLabelMode::Short => 8,
+        KindLabelMode::Long => 18,
+        KindLabelMode::Icon => 4,
+    };
     vec![
         Constraint::Length(1),
         Constraint::Length(14),
-        Constraint::Length(8),
+        Constraint::Length(type_width),
         Constraint::Min(12),
     ]
 }
@@ -29,29 +56,78 @@ pub(crate) fn timeline_header() -> Row<'static> {
     Row::new(vec!["N", "Time", "Type", "Title"])
 }
 
-pub(crate) fn timeline_empty_row() -> Row<'static> {
-    timeline_empty_row_with_message("No events yet")
-}
-
-pub(crate) fn timeline_empty_row_with_message(message: &str) -> Row<'static> {
-    Row::new(vec![
-        Cell::from("-"),
-        Cell::from("-"),
-        Cell::from("-"),
-        Cell::from(message.to_string()),
-    ])
+/// Placeholder for an empty timeline: `Polling…` during the first poll, otherwise how
+/// to get events in. `message` replaces the whole text, e.g. for the My PR tab.
+pub(crate) fn build_empty_timeline_lines(model: &TuiModel, message: Option<&str>) -> Vec<String> {
+    if let Some(message) = message {
+        return vec![message.to_string()];
+    }
+    if model.is_polling && model.is_polling_first_time {
+        return vec!["Polling…".to_string()];
+    }
+    let mut lines = vec![
+        "No events yet".to_string(),
+        format!(
+            "Press '{}' to poll now",
+            model.keyboard_shortcuts.label(InputCommand::Refresh)
+        ),
+    ];
+    if model.next_poll_at.is_some() {
+        lines.push(format!(
+            "or wait for the next scheduled poll at {}",
+            format_compact_status_time(model.next_poll_at)
+        ));
+    }
+    lines
 }
 
-pub(crate) fn timeline_row(event: &WatchEvent, is_read: bool) -> Row<'static> {
+/// `title_style` highlights the Title cell, e.g. for search matches.
+pub(crate) fn timeline_row(
+    event: &WatchEvent,
+    is_read: bool,
+    kind_labels: KindLabelMode,
+    title_style: Option<Style>,
+    subject_state: Option<SubjectState>,
+) -> Row<'static> {
+    let title = truncate_tail(&badged_title(event, subject_state), 120);
     Row::new(vec![
         Cell::from(unread_marker(is_read)),
         Cell::from(format_timeline_time(event.created_at)),
         Cell::from(Span::styled(
-            event_kind_label(&event.kind),
+            event_kind_label(&event.kind, kind_labels),
             event_kind_style(&event.kind),
         )),
-        Cell::from(truncate_tail(&event.title, 120)),
+        match title_style {
+            Some(style) => Cell::from(Span::styled(title, style)),
+            None => Cell::from(title),
+        },
+    ])
+}
+
+/// `#1234 title` when the event's issue or pull request number is known.
+pub(crate) fn numbered_title(event: &WatchEvent) -> String {
+    match event.display_number() {
+        Some(number) => format!("#{number} {}", event.title),
+        None => event.title.clone(),
+    }
+}
+
+/// `numbered_title` behind a `[merged]` or `[closed]` badge once the subject is resolved.
+pub(crate) fn badged_title(event: &WatchEvent, subject_state: Option<SubjectState>) -> String {
+    match subject_state.filter(|state| state.is_resolved()) {
+        Some(state) => format!("[{}] {}", state.as_str(), numbered_title(event)),
+        None => numbered_title(event),
+    }
+}
+
+pub(crate) fn timeline_separator_row(repo: &str) -> Row<'static> {
+    Row::new(vec![
+        Cell::from(""),
+        Cell::from(""),
+        Cell::from(""),
+        Cell::from(format!("── {repo} ──")),
     ])
+    .style(Style::default().fg(Color::DarkGray))
 }
 
 pub(crate) fn build_status_line(
@@ -59,6 +135,10 @@ pub(crate) fn build_status_line(
     now: DateTime<Utc>,
     glyph_mode: GlyphMode,
 ) -> String {
+    if let Some(query) = &model.search_input {
+        return format!("/{}", sanitize_single_line(query));
+    }
+
     if model.is_polling {
         let elapsed_secs = model
             .poll_started_at
@@ -73,20 +153,27 @@ pub(crate) fn build_status_line(
         return match glyph_mode {
             GlyphMode::Nerd => format!(
                 "󰚩 {spinner} poll 󱑂 {elapsed_secs}s 󰏗 {refresh} 󰅚 {}",
-                model.failure_count
+                failure_summary(model)
             ),
             GlyphMode::Ascii => format!(
                 "~ {spinner} poll t={elapsed_secs}s refresh={refresh} fail={}",
-                model.failure_count
+                failure_summary(model)
             ),
         };
     }
 
     if is_error_status(&model.status_line) {
-        let detail = truncate_tail(&sanitize_single_line(&model.status_line), 48);
+        let detail = truncate_tail(
+            &sanitize_single_line(&redact_secrets(&model.status_line)),
+            48,
+        );
+        let repeat = match model.status_repeat() {
+            1 => String::new(),
+            count => format!(" (x{count})"),
+        };
         return match glyph_mode {
-            GlyphMode::Nerd => format!("󰅚 {detail} 󰅚 {}", model.failure_count),
-            GlyphMode::Ascii => format!("! {detail} fail={}", model.failure_count),
+            GlyphMode::Nerd => format!("󰅚 {detail}{repeat} 󰅚 {}", failure_summary(model)),
+            GlyphMode::Ascii => format!("! {detail}{repeat} fail={}", failure_summary(model)),
         };
     }
 
@@ -95,7 +182,34 @@ pub(crate) fn build_status_line(
     } else {
         "ready"
     };
+    let notified = match (model.notification_indicator_active(now), glyph_mode) {
+        (false, _) => "",
+        (true, GlyphMode::Nerd) => "🔔 ",
+        (true, GlyphMode::Ascii) => "[notified] ",
+    };
     let next_poll = format_compact_status_time(model.next_poll_at);
+    let filter_profile = model
+        .active_filter_profile
+        .as_deref()
+        .map(|name| match glyph_mode {
+            GlyphMode::Nerd => format!(" 󰈲 {name}"),
+            GlyphMode::Ascii => format!(" filter={name}"),
+        })
+        .unwrap_or_default();
+    let search = model
+        .search_position()
+        .map(|(position, total)| format!(" {position}/{total} matches"))
+        .unwrap_or_default();
+    let suppressed = if model.is_notification_suppressed {
+        " [notifications suppressed]"
+    } else {
+        ""
+    };
+    let notice = model
+        .status_notice
+        .as_deref()
+        .map(|notice| format!(" | {}", truncate_tail(&sanitize_single_line(notice), 64)))
+        .unwrap_or_default();
 
     match glyph_mode {
         GlyphMode::Nerd => {
@@ -104,7 +218,10 @@ pub(crate) fn build_status_line(
             } else {
                 "󰄬"
             };
-            format!("{prefix} {status} 󱑆 {next_poll} 󰅚 {}", model.failure_count)
+            format!(
+                "{prefix} {notified}{status} 󱑆 {next_poll} 󰅚 {}{filter_profile}{search}{suppressed}{notice}",
+                failure_summary(model)
+            )
         }
         GlyphMode::Ascii => {
             let prefix = if is_quit_armed_status(&model.status_line) {
@@ -113,37 +230,47 @@ pub(crate) fn build_status_line(
                 "+"
             };
             format!(
-                "{prefix} {status} next={next_poll} fail={}",
-                model.failure_count
+                "{prefix} {notified}{status} next={next_poll} fail={}{filter_profile}{search}{suppressed}{notice}",
+                failure_summary(model)
             )
         }
     }
 }
 
+/// `12`, or `12 (acme/api x6)` naming the repository with the longest failure streak.
+fn failure_summary(model: &TuiModel) -> String {
+    match model.failure_streaks.worst() {
+        Some((repo, streak)) => format!("{} ({repo} x{streak})", model.failure_count),
+        None => model.failure_count.to_string(),
+    }
+}
+
 pub(crate) fn build_selected_lines(
     model: &TuiModel,
     glyph_mode: GlyphMode,
     max_width: usize,
 ) -> [String; 2] {
+    let kind_labels = effective_kind_labels(model.kind_labels, glyph_mode);
     let (summary_raw, url_raw) = if let Some(event) = model.timeline.get(model.selected) {
+        let title = badged_title(event, model.subject_state(event));
         match glyph_mode {
             GlyphMode::Nerd => (
                 format!(
                     "󰀷 {} 󰳝 {} 󰀄 @{} 󰎚 {}",
-                    event_kind_label(&event.kind),
+                    event_kind_label(&event.kind, kind_labels),
                     event.repo,
                     event.actor,
-                    event.title
+                    title
                 ),
                 format!("󰌹 {}", event.url),
             ),
             GlyphMode::Ascii => (
                 format!(
                     "{} | {} | @{} | {}",
-                    event_kind_label(&event.kind),
+                    event_kind_label(&event.kind, kind_labels),
                     event.repo,
                     event.actor,
-                    event.title
+                    title
                 ),
                 event.url.clone(),
             ),
@@ -161,8 +288,55 @@ pub(crate) fn build_selected_lines(
     ]
 }
 
-pub(crate) fn build_keys_line() -> String {
-    "q quit | Esc Esc quit | r refresh | Tab switch | ? help | Enter open".to_string()
+/// Detail pane of the split layout: one field per line, wrapped by the pane.
+pub(crate) fn build_detail_lines(model: &TuiModel, glyph_mode: GlyphMode) -> Vec<String> {
+    let Some(event) = model.timeline.get(model.selected) else {
+        return vec![match glyph_mode {
+            GlyphMode::Nerd => "󰘕 no selection".to_string(),
+            GlyphMode::Ascii => "no selection".to_string(),
+        }];
+    };
+
+    let read = if model.is_event_read(&event.event_key()) {
+        "read"
+    } else {
+        "unread"
+    };
+    let mut lines = vec![
+        numbered_title(event),
+        String::new(),
+        format!(
+            "kind:  {}",
+            event_kind_label(
+                &event.kind,
+                effective_kind_labels(model.kind_labels, glyph_mode)
+            )
+        ),
+        format!("repo:  {}", event.repo),
+        format!("actor: @{}", event.actor),
+        format!("time:  {}", format_timeline_time(event.created_at)),
+        format!("state: {read}"),
+    ];
+    if let Some(subject_state) = model.subject_state(event) {
+        lines.push(format!("subject: {}", subject_state.as_str()));
+    }
+    if let Some(account) = event.account.as_deref() {
+        lines.push(format!("account: {account}"));
+    }
+    lines.push(String::new());
+    lines.push(event.url.clone());
+    lines
+}
+
+pub(crate) fn build_keys_line(keymap: &Keymap) -> String {
+    format!(
+        "{} quit | Esc Esc quit | {} refresh | {} switch | {} help | {} open",
+        keymap.label(InputCommand::Quit),
+        keymap.label(InputCommand::Refresh),
+        keymap.label(InputCommand::NextTab),
+        keymap.label(InputCommand::ToggleHelp),
+        keymap.label(InputCommand::OpenSelectedUrl)
+    )
 }
 
 pub(crate) fn detect_glyph_mode_from_env() -> GlyphMode {
@@ -190,15 +364,218 @@ where
     dt.with_timezone(timezone).format(pattern).to_string()
 }
 
-fn event_kind_label(kind: &EventKind) -> &'static str {
-    match kind {
-        EventKind::PrCreated => "PR",
-        EventKind::IssueCreated => "ISSUE",
-        EventKind::IssueCommentCreated => "I-CMT",
-        EventKind::PrReviewCommentCreated => "PR-CMT",
-        EventKind::PrReviewRequested => "PR-REQ",
-        EventKind::PrReviewSubmitted => "PR-REV",
-        EventKind::PrMerged => "PR-MRG",
+/// Help overlay body with the effective key bindings.
+/// Which kind each number key toggles, and the kinds left without one.
+fn kind_filter_keys_help() -> String {
+    let keyed = KIND_FILTER_KEYS
+        .iter()
+        .enumerate()
+        .map(|(index, kind)| format!("{} {kind}", index + 1))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let unkeyed = EventKind::ALL
+        .iter()
+        .filter(|kind| !KIND_FILTER_KEYS.contains(kind))
+        .map(EventKind::as_str)
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("  {keyed}; no key (hidden while a filter is on): {unkeyed}")
+}
+
+pub(crate) fn help_lines(keymap: &Keymap) -> Vec<String> {
+    let key = |command| keymap.label(command);
+    vec![
+        format!("{}: quit immediately", key(InputCommand::Quit)),
+        "Esc twice within 1.5s: quit".to_string(),
+        format!(
+            "{} / {}: switch Timeline, My PR and Repositories",
+            key(InputCommand::NextTab),
+            key(InputCommand::PrevTab)
+        ),
+        format!(
+            "{}: refresh, {}: toggle help, {}: open selected URL",
+            key(InputCommand::Refresh),
+            key(InputCommand::ToggleHelp),
+            key(InputCommand::OpenSelectedUrl)
+        ),
+        format!(
+            "{}: copy selected URL, {}: copy selected event key",
+            key(InputCommand::YankUrl),
+            key(InputCommand::YankEventKey)
+        ),
+        format!(
+            "{}: gh pr view --web, {}: gh pr checkout in the repo's local_path",
+            key(InputCommand::GhViewSelected),
+            key(InputCommand::GhCheckoutSelected)
+        ),
+        format!(
+            "{} / {}: move one row (Timeline/My PR tabs)",
+            key(InputCommand::ScrollUp),
+            key(InputCommand::ScrollDown)
+        ),
+        format!(
+            "{} / {}: move one page (Timeline/My PR tabs)",
+            key(InputCommand::PageUp),
+            key(InputCommand::PageDown)
+        ),
+        format!(
+            "{}: top, {}: bottom (Timeline/My PR tabs)",
+            key(InputCommand::JumpTop),
+            key(InputCommand::JumpBottom)
+        ),
+        format!(
+            "{}: toggle grouping by repository (Timeline/My PR tabs)",
+            key(InputCommand::ToggleGroupByRepo)
+        ),
+        "1-9: toggle an event kind in the view filter, 0: clear it".to_string(),
+        kind_filter_keys_help(),
+        format!(
+            "{}: hide events on closed or merged issues and pull requests",
+            key(InputCommand::ToggleHideClosed)
+        ),
+        format!(
+            "{}: mark every event read (Timeline tab)",
+            key(InputCommand::MarkAllRead)
+        ),
+        format!(
+            "{} or {} at the bottom: load older events from the state db",
+            key(InputCommand::LoadOlderHistory),
+            key(InputCommand::PageDown)
+        ),
+        format!(
+            "{}: search the state db by repository, actor, title or body; Enter runs it, Esc cancels",
+            key(InputCommand::StartSearch)
+        ),
+        format!(
+            "{} / {}: next / previous match of the `/` search; Esc ends the search (Timeline/My PR tabs)",
+            key(InputCommand::NextSearchResult),
+            key(InputCommand::PrevSearchResult)
+        ),
+        format!(
+            "{} / {}: narrow / widen the timeline in the split layout",
+            key(InputCommand::NarrowSplit),
+            key(InputCommand::WidenSplit)
+        ),
+        format!(
+            "{}: toggle the split layout; {} in it: move focus between timeline and detail",
+            key(InputCommand::ToggleSplitPane),
+            key(InputCommand::NextTab)
+        ),
+        "mouse: click to select, double-click to open, wheel to scroll (Timeline/My PR tabs)"
+            .to_string(),
+        "right click: Open / Copy URL / Mark read menu; arrows move, Enter runs, Esc closes"
+            .to_string(),
+    ]
+}
+
+/// Block title with the active view filters, e.g. `Timeline [PR, PR-MRG, open only]`.
+pub(crate) fn timeline_title(
+    base: &str,
+    kind_filter: &[EventKind],
+    kind_labels: KindLabelMode,
+    hide_closed: bool,
+) -> String {
+    let mut labels = kind_filter
+        .iter()
+        .map(|kind| event_kind_label(kind, kind_labels))
+        .collect::<Vec<_>>();
+    if hide_closed {
+        labels.push("open only");
+    }
+    if labels.is_empty() {
+        return base.to_string();
+    }
+    format!("{base} [{}]", labels.join(", "))
+}
+
+/// Repositories tab label with a red `(N!)` badge while fetch failures are unseen.
+pub(crate) fn repositories_tab_title(error_badge_count: u64) -> Line<'static> {
+    if error_badge_count == 0 {
+        return Line::from("Repositories");
+    }
+    Line::from(vec![
+        Span::raw("Repositories "),
+        Span::styled(
+            format!("({error_badge_count}!)"),
+            Style::default().fg(Color::Red),
+        ),
+    ])
+}
+
+/// Label of the Repositories tab group for repositories without topics.
+const NO_TOPIC_LABEL: &str = "(no topic)";
+
+/// Repositories tab rows: `repos` as they are, or with `group_by_topic` a bold header per
+/// topic (sorted, `(no topic)` last) above its repositories. A repository with several
+/// topics is listed under each of them.
+pub(crate) fn repository_rows(
+    repos: &[String],
+    topics: &HashMap<String, Vec<String>>,
+    group_by_topic: bool,
+) -> Vec<Line<'static>> {
+    if !group_by_topic {
+        return repos.iter().map(|repo| Line::from(repo.clone())).collect();
+    }
+
+    let mut groups: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    let mut untagged = Vec::new();
+    for repo in repos {
+        match topics.get(repo).filter(|topics| !topics.is_empty()) {
+            Some(repo_topics) => {
+                for topic in repo_topics {
+                    groups
+                        .entry(topic.as_str())
+                        .or_default()
+                        .push(repo.as_str());
+                }
+            }
+            None => untagged.push(repo.as_str()),
+        }
+    }
+    let header = |topic: &str| {
+        Line::from(Span::styled(
+            topic.to_string(),
+            Style::default().add_modifier(Modifier::BOLD),
+        ))
+    };
+    let mut rows = Vec::new();
+    for (topic, members) in groups
+        .into_iter()
+        .chain((!untagged.is_empty()).then_some((NO_TOPIC_LABEL, untagged)))
+    {
+        rows.push(header(topic));
+        rows.extend(
+            members
+                .into_iter()
+                .map(|repo| Line::from(format!("  {repo}"))),
+        );
+    }
+    rows
+}
+
+/// Type column text for `kind`; the match is exhaustive so a new kind needs all three labels.
+pub(crate) fn event_kind_label(kind: &EventKind, mode: KindLabelMode) -> &'static str {
+    let (short, long, icon) = match kind {
+        EventKind::PrCreated => ("PR", "Pull request", ""),
+        EventKind::IssueCreated => ("ISSUE", "Issue", ""),
+        EventKind::IssueCommentCreated => ("I-CMT", "Issue comment", ""),
+        EventKind::PrReviewCommentCreated => ("PR-CMT", "Review comment", ""),
+        EventKind::PrReviewRequested => ("PR-REQ", "Review requested", ""),
+        EventKind::PrReviewSubmitted => ("PR-REV", "Review", ""),
+        EventKind::PrReviewApproved => ("PR-APR", "Approved", ""),
+        EventKind::PrReviewChangesRequested => ("PR-CHG", "Changes requested", ""),
+        EventKind::PrReviewDismissed => ("PR-DIS", "Review dismissed", ""),
+        EventKind::PrMerged => ("PR-MRG", "Merged", ""),
+        EventKind::PrMilestoneAdded => ("PR-MS", "PR milestone", ""),
+        EventKind::IssueMilestoneAdded => ("I-MS", "Issue milestone", "󰰛"),
+        EventKind::ReviewThreadResolved => ("PR-RSV", "Thread resolved", ""),
+        EventKind::MyPrApproved => ("MY-APR", "My PR approved", ""),
+        EventKind::MyPrChecksPassed => ("MY-CHK", "My PR checks ok", ""),
+    };
+    match mode {
+        KindLabelMode::Short => short,
+        KindLabelMode::Long => long,
+        KindLabelMode::Icon => icon,
     }
 }
 
@@ -210,7 +587,17 @@ fn event_kind_style(kind: &EventKind) -> Style {
         EventKind::PrReviewCommentCreated => Style::default().fg(Color::Magenta),
         EventKind::PrReviewRequested => Style::default().fg(Color::Blue),
         EventKind::PrReviewSubmitted => Style::default().fg(Color::LightBlue),
+        EventKind::PrReviewApproved => Style::default().fg(Color::Green),
+        EventKind::PrReviewChangesRequested => Style::default().fg(Color::LightRed),
+        EventKind::PrReviewDismissed => Style::default().fg(Color::DarkGray),
         EventKind::PrMerged => Style::default().fg(Color::LightGreen),
+        EventKind::ReviewThreadResolved => Style::default().fg(Color::LightCyan),
+        EventKind::MyPrApproved | EventKind::MyPrChecksPassed => {
+            Style::default().fg(Color::LightGreen)
+        }
+        EventKind::PrMilestoneAdded | EventKind::IssueMilestoneAdded => {
+            Style::default().fg(Color::LightYellow)
+        }
     }
 }
 
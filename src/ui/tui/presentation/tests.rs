@@ -1,12 +1,28 @@
+use std::collections::HashMap;
+
 use chrono::{FixedOffset, TimeZone};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier},
+    text::Line,
+    widgets::{Table, Widget},
+};
 
 use super::{
-    build_selected_lines, build_status_line, detect_glyph_mode, format_compact_status_time,
-    format_time_in_timezone, truncate_tail, GlyphMode,
+    badged_title, build_empty_timeline_lines, build_keys_line, build_selected_lines,
+    build_status_line, detect_glyph_mode, effective_kind_labels, event_kind_label,
+    format_compact_status_time, format_time_in_timezone, help_lines, numbered_title,
+    repositories_tab_title, repository_rows, timeline_constraints, timeline_row, timeline_title,
+    truncate_tail, GlyphMode,
 };
 use crate::{
-    domain::events::{EventKind, WatchEvent},
-    ui::tui::TuiModel,
+    config::{KeymapConfig, KindLabelMode},
+    domain::{
+        events::{EventKind, WatchEvent},
+        subject_state::SubjectState,
+    },
+    ui::tui::{Keymap, TuiModel},
 };
 
 fn event(id: &str, created_at: chrono::DateTime<chrono::Utc>) -> WatchEvent {
@@ -22,6 +38,11 @@ fn event(id: &str, created_at: chrono::DateTime<chrono::Utc>) -> WatchEvent {
         subject_author: Some("dev".to_string()),
         requested_reviewer: None,
         mentions: Vec::new(),
+        team_mentions: Vec::new(),
+        subject_number: None,
+        subject_url: None,
+        account: None,
+        body: None,
     }
 }
 
@@ -42,6 +63,362 @@ fn loading_status_line_sanitizes_multiline_error_detail() {
     assert_eq!(line, "! open failed: launcher missing fail=0");
 }
 
+#[test]
+fn error_status_line_masks_tokens() {
+    let now = chrono::Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let mut model = TuiModel::new(10);
+    model.status_line = "sync failed: ghp_abc123 rejected".to_string();
+
+    let line = build_status_line(&model, now, GlyphMode::Ascii);
+    assert_eq!(line, "! sync failed: ghp_*** rejected fail=0");
+}
+
+#[test]
+fn status_line_names_worst_failure_streak_and_groups_repeats() {
+    let now = chrono::Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let mut model = TuiModel::new(10);
+    model.failure_count = 12;
+    for _ in 0..6 {
+        (model.failure_streaks, _) = model.failure_streaks.advance([], ["acme/api"]);
+    }
+    (model.failure_streaks, _) = model.failure_streaks.advance([], ["acme/web"]);
+    model.set_status_line("poll failed: boom".to_string());
+    model.set_status_line("poll failed: boom".to_string());
+
+    let line = build_status_line(&model, now, GlyphMode::Ascii);
+    assert_eq!(line, "! poll failed: boom (x2) fail=12 (acme/api x6)");
+
+    model.set_status_line("ok (new=0)".to_string());
+    let line = build_status_line(&model, now, GlyphMode::Ascii);
+    assert_eq!(line, "+ ready next=- fail=12 (acme/api x6)");
+}
+
+#[test]
+fn ready_status_line_shows_active_filter_profile() {
+    let now = chrono::Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let mut model = TuiModel::new(10);
+    model.status_line = "ready".to_string();
+    model.active_filter_profile = Some("work".to_string());
+
+    let line = build_status_line(&model, now, GlyphMode::Ascii);
+    assert_eq!(line, "+ ready next=- fail=0 filter=work");
+}
+
+#[test]
+fn ready_status_line_flags_a_recent_notification_in_both_glyph_modes() {
+    let notified_at = chrono::Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let mut model = TuiModel::new(10);
+    model.status_line = "ok (new=2)".to_string();
+    model.last_notification_at = Some(notified_at);
+    let now = notified_at + chrono::Duration::seconds(2);
+
+    assert_eq!(
+        build_status_line(&model, now, GlyphMode::Ascii),
+        "+ [notified] ready next=- fail=0"
+    );
+    assert!(build_status_line(&model, now, GlyphMode::Nerd).starts_with("󰄬 🔔 ready "));
+
+    let later = notified_at + chrono::Duration::seconds(3);
+    assert_eq!(
+        build_status_line(&model, later, GlyphMode::Ascii),
+        "+ ready next=- fail=0"
+    );
+    assert!(!build_status_line(&model, later, GlyphMode::Nerd).contains('🔔'));
+}
+
+#[test]
+fn ready_status_line_flags_suppressed_notifications() {
+    let now = chrono::Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let mut model = TuiModel::new(10);
+    model.status_line = "ready".to_string();
+    model.is_notification_suppressed = true;
+
+    let line = build_status_line(&model, now, GlyphMode::Ascii);
+    assert_eq!(line, "+ ready next=- fail=0 [notifications suppressed]");
+}
+
+#[test]
+fn ready_status_line_appends_status_notice() {
+    let now = chrono::Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let mut model = TuiModel::new(10);
+    model.status_line = "ok (new=3)".to_string();
+    model.status_notice = Some("bootstrapped 3 events".to_string());
+
+    let line = build_status_line(&model, now, GlyphMode::Ascii);
+    assert_eq!(line, "+ ready next=- fail=0 | bootstrapped 3 events");
+}
+
+#[test]
+fn ready_status_line_counts_search_matches() {
+    let now = chrono::Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let mut model = TuiModel::new(10);
+    model.status_line = "ready".to_string();
+    model.search_results = Some(vec![1, 4, 6]);
+    model.selected = 4;
+
+    let line = build_status_line(&model, now, GlyphMode::Ascii);
+    assert_eq!(line, "+ ready next=- fail=0 2/3 matches");
+}
+
+#[test]
+fn search_matches_render_the_title_with_the_highlight_style() {
+    let now = chrono::Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let model = TuiModel::new(10);
+    let area = Rect::new(0, 0, 80, 2);
+    let mut buffer = Buffer::empty(area);
+    let rows = vec![
+        timeline_row(
+            &event("1", now),
+            true,
+            KindLabelMode::Short,
+            Some(model.search_highlight_style),
+            None,
+        ),
+        timeline_row(&event("2", now), true, KindLabelMode::Short, None, None),
+    ];
+    Widget::render(
+        Table::new(rows, timeline_constraints(KindLabelMode::Short)),
+        area,
+        &mut buffer,
+    );
+
+    let title_x = (0..area.width)
+        .find(|&x| buffer[(x, 0)].symbol() == "c")
+        .expect("title is rendered");
+    let highlighted = buffer[(title_x, 0)].style();
+    assert_eq!(highlighted.fg, Some(Color::Yellow));
+    assert!(highlighted.add_modifier.contains(Modifier::BOLD));
+    assert_ne!(buffer[(title_x, 1)].style().fg, Some(Color::Yellow));
+}
+
+#[test]
+fn empty_timeline_hints_refresh_key_and_next_poll() {
+    let mut model = TuiModel::new(10);
+    assert!(model.is_empty());
+    assert_eq!(
+        build_empty_timeline_lines(&model, None),
+        vec!["No events yet", "Press 'r' to poll now"]
+    );
+
+    let next_poll = chrono::Utc.with_ymd_and_hms(2025, 1, 1, 9, 30, 0).unwrap();
+    model.next_poll_at = Some(next_poll);
+    assert_eq!(
+        build_empty_timeline_lines(&model, None),
+        vec![
+            "No events yet".to_string(),
+            "Press 'r' to poll now".to_string(),
+            format!(
+                "or wait for the next scheduled poll at {}",
+                format_compact_status_time(Some(next_poll))
+            ),
+        ]
+    );
+}
+
+#[test]
+fn empty_timeline_shows_polling_only_during_the_first_poll() {
+    let mut model = TuiModel::new(10);
+    model.is_polling = true;
+    assert_eq!(build_empty_timeline_lines(&model, None), vec!["Polling…"]);
+
+    model.is_polling_first_time = false;
+    assert_eq!(build_empty_timeline_lines(&model, None)[0], "No events yet");
+}
+
+#[test]
+fn empty_timeline_message_overrides_the_hints() {
+    let mut model = TuiModel::new(10);
+    model.is_polling = true;
+    assert_eq!(
+        build_empty_timeline_lines(&model, Some("Viewer login unavailable")),
+        vec!["Viewer login unavailable"]
+    );
+}
+
+#[test]
+fn timeline_title_lists_active_kind_filter() {
+    let kinds = [EventKind::PrCreated, EventKind::PrMerged];
+    assert_eq!(
+        timeline_title("Timeline", &[], KindLabelMode::Short, false),
+        "Timeline"
+    );
+    assert_eq!(
+        timeline_title("Timeline", &kinds, KindLabelMode::Short, false),
+        "Timeline [PR, PR-MRG]"
+    );
+    assert_eq!(
+        timeline_title("Timeline", &kinds, KindLabelMode::Long, false),
+        "Timeline [Pull request, Merged]"
+    );
+    assert_eq!(
+        timeline_title("Timeline", &kinds, KindLabelMode::Short, true),
+        "Timeline [PR, PR-MRG, open only]"
+    );
+    assert_eq!(
+        timeline_title("Timeline", &[], KindLabelMode::Short, true),
+        "Timeline [open only]"
+    );
+}
+
+#[test]
+fn every_event_kind_has_distinct_labels_that_fit_the_type_column() {
+    for mode in [
+        KindLabelMode::Short,
+        KindLabelMode::Long,
+        KindLabelMode::Icon,
+    ] {
+        let width = match timeline_constraints(mode)[2] {
+            ratatui::layout::Constraint::Length(width) => width as usize,
+            other => panic!("unexpected type column constraint: {other:?}"),
+        };
+        let labels = EventKind::ALL
+            .iter()
+            .map(|kind| event_kind_label(kind, mode))
+            .collect::<std::collections::HashSet<_>>();
+        assert_eq!(
+            labels.len(),
+            EventKind::ALL.len(),
+            "{mode:?} labels collide"
+        );
+        for label in labels {
+            assert!(!label.is_empty());
+            assert!(label.chars().count() < width, "{label} overflows {mode:?}");
+        }
+    }
+}
+
+#[test]
+fn icon_kind_labels_fall_back_to_short_in_ascii_mode() {
+    assert_eq!(
+        effective_kind_labels(KindLabelMode::Icon, GlyphMode::Ascii),
+        KindLabelMode::Short
+    );
+    assert_eq!(
+        effective_kind_labels(KindLabelMode::Icon, GlyphMode::Nerd),
+        KindLabelMode::Icon
+    );
+    assert_eq!(
+        effective_kind_labels(KindLabelMode::Long, GlyphMode::Ascii),
+        KindLabelMode::Long
+    );
+
+    let mut model = TuiModel::new(10);
+    model.kind_labels = KindLabelMode::Icon;
+    model.timeline = vec![event("1", chrono::Utc::now())];
+    let [summary, _] = build_selected_lines(&model, GlyphMode::Ascii, 200);
+    assert!(summary.starts_with("I-CMT | acme/api"));
+}
+
+#[test]
+fn repositories_tab_title_shows_red_error_badge() {
+    assert_eq!(repositories_tab_title(0).to_string(), "Repositories");
+
+    let title = repositories_tab_title(3);
+    assert_eq!(title.to_string(), "Repositories (3!)");
+    assert_eq!(title.spans[1].style.fg, Some(Color::Red));
+}
+
+#[test]
+fn repository_rows_group_by_topic_with_untagged_repositories_last() {
+    let repos = ["acme/web", "acme/api", "acme/docs"].map(String::from);
+    let topics = HashMap::from([
+        (
+            "acme/api".to_string(),
+            vec!["rust".to_string(), "internal".to_string()],
+        ),
+        ("acme/web".to_string(), vec!["rust".to_string()]),
+        ("acme/docs".to_string(), Vec::new()),
+    ]);
+
+    let text = |rows: Vec<Line<'static>>| rows.iter().map(ToString::to_string).collect::<Vec<_>>();
+    assert_eq!(
+        text(repository_rows(&repos, &topics, false)),
+        vec!["acme/web", "acme/api", "acme/docs"]
+    );
+
+    let grouped = repository_rows(&repos, &topics, true);
+    assert!(grouped[0].spans[0]
+        .style
+        .add_modifier
+        .contains(Modifier::BOLD));
+    assert_eq!(
+        text(grouped),
+        vec![
+            "internal",
+            "  acme/api",
+            "rust",
+            "  acme/web",
+            "  acme/api",
+            "(no topic)",
+            "  acme/docs",
+        ]
+    );
+}
+
+#[test]
+fn numbered_title_prefers_subject_number_and_falls_back_to_url() {
+    let now = chrono::Utc::now();
+    let mut ev = event("a", now);
+    ev.title = "Fix login".to_string();
+
+    ev.url = "https://github.com/acme/api/pull/42#discussion_r1".to_string();
+    assert_eq!(numbered_title(&ev), "#42 Fix login");
+
+    ev.url = "https://github.com/acme/api/issues/7#issuecomment-3".to_string();
+    assert_eq!(numbered_title(&ev), "#7 Fix login");
+
+    ev.subject_number = Some(1234);
+    assert_eq!(numbered_title(&ev), "#1234 Fix login");
+
+    ev.subject_number = None;
+    ev.url = "https://example.com/activity".to_string();
+    assert_eq!(numbered_title(&ev), "Fix login");
+}
+
+#[test]
+fn badged_title_marks_only_resolved_subjects() {
+    let mut ev = event("a", chrono::Utc::now());
+    ev.title = "Fix login".to_string();
+    ev.subject_number = Some(42);
+
+    assert_eq!(
+        badged_title(&ev, Some(SubjectState::Merged)),
+        "[merged] #42 Fix login"
+    );
+    assert_eq!(
+        badged_title(&ev, Some(SubjectState::Closed)),
+        "[closed] #42 Fix login"
+    );
+    assert_eq!(badged_title(&ev, Some(SubjectState::Open)), "#42 Fix login");
+    assert_eq!(badged_title(&ev, None), "#42 Fix login");
+}
+
+#[test]
+fn keys_and_help_lines_show_effective_bindings() {
+    let defaults = Keymap::default();
+    assert_eq!(
+        build_keys_line(&defaults),
+        "q quit | Esc Esc quit | r refresh | Tab switch | ? help | Enter open"
+    );
+    assert!(help_lines(&defaults)
+        .contains(&"Up/k / Down/j: move one row (Timeline/My PR tabs)".to_string()));
+    assert!(help_lines(&defaults).contains(
+        &"  1 pr_created, 2 issue_created, 3 issue_comment_created, 4 pr_review_comment_created, 5 pr_review_requested, 6 pr_review_submitted, 7 pr_merged, 8 pr_milestone_added, 9 issue_milestone_added; no key (hidden while a filter is on): pr_review_approved, pr_review_changes_requested, pr_review_dismissed, review_thread_resolved, my_pr_approved, my_pr_checks_passed".to_string()
+    ));
+
+    let keymap = Keymap::from_config(&KeymapConfig {
+        quit: Some(vec!["F10".to_string()]),
+        refresh: Some(Vec::new()),
+        ..KeymapConfig::default()
+    })
+    .expect("valid keymap");
+    assert_eq!(
+        build_keys_line(&keymap),
+        "F10 quit | Esc Esc quit | - refresh | Tab switch | ? help | Enter open"
+    );
+    assert_eq!(help_lines(&keymap)[0], "F10: quit immediately");
+}
+
 #[test]
 fn selected_lines_compact_event_detail_and_url_into_two_lines() {
     let now = chrono::Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
@@ -50,6 +427,12 @@ fn selected_lines_compact_event_detail_and_url_into_two_lines() {
     let [line1, line2] = build_selected_lines(&model, GlyphMode::Ascii, 200);
     assert_eq!(line1, "I-CMT | acme/api | @dev | comment");
     assert_eq!(line2, "https://example.com/a");
+
+    let mut numbered = event("b", now);
+    numbered.subject_number = Some(21);
+    model.replace_timeline(vec![numbered]);
+    let [line1, _] = build_selected_lines(&model, GlyphMode::Ascii, 200);
+    assert_eq!(line1, "I-CMT | acme/api | @dev | #21 comment");
 }
 
 #[test]
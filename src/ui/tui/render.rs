@@ -11,17 +11,22 @@ use ratatui::{
     backend::CrosstermBackend,
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Table, TableState, Tabs, Wrap},
+    widgets::{
+        Block, Borders, Clear, List, ListItem, ListState, Paragraph, Table, TableState, Tabs, Wrap,
+    },
     Frame, Terminal,
 };
 
+use crate::config::KindLabelMode;
+
 use super::{
-    layout::{centered_rect, shrink_by_border, ui_layout},
-    model::{ActiveTab, TuiModel},
+    layout::{centered_rect, context_menu_area, shrink_by_border, ui_layout},
+    model::{ActiveTab, ContextMenuAction, FocusedPane, TimelineItem, TuiModel},
     presentation::{
-        build_keys_line, build_selected_lines, build_status_line, detect_glyph_mode_from_env,
-        timeline_constraints, timeline_empty_row, timeline_empty_row_with_message, timeline_header,
-        timeline_row,
+        build_detail_lines, build_empty_timeline_lines, build_keys_line, build_selected_lines,
+        build_status_line, detect_glyph_mode_from_env, effective_kind_labels, help_lines,
+        repositories_tab_title, repository_rows, timeline_constraints, timeline_header,
+        timeline_row, timeline_separator_row, timeline_title,
     },
 };
 
@@ -44,6 +49,17 @@ impl TerminalUi {
         Ok(())
     }
 
+    /// Applies a debounced terminal resize and redraws at the new size.
+    pub fn resize_callback(
+        &mut self,
+        (width, height): (u16, u16),
+        model: &mut TuiModel,
+    ) -> Result<()> {
+        self.terminal
+            .resize(ratatui::layout::Rect::new(0, 0, width, height))?;
+        self.draw(model)
+    }
+
     pub fn terminal_area(&self) -> Result<ratatui::layout::Rect> {
         let size = self.terminal.size()?;
         Ok(ratatui::layout::Rect::new(0, 0, size.width, size.height))
@@ -62,17 +78,18 @@ impl Drop for TerminalUi {
 }
 
 fn render(frame: &mut Frame<'_>, model: &mut TuiModel) {
-    let layout = ui_layout(frame.area());
+    let layout = ui_layout(frame.area(), model.split_ratio_for(frame.area().width));
     let glyph_mode = detect_glyph_mode_from_env();
     let status = build_status_line(model, Utc::now(), glyph_mode);
     let header = Paragraph::new(Line::from(status))
         .block(Block::default().borders(Borders::ALL).title("Stat"));
     frame.render_widget(header, layout.status);
 
-    let tab_titles = ["Timeline", "My PR", "Repositories"]
-        .into_iter()
-        .map(Line::from)
-        .collect::<Vec<_>>();
+    let tab_titles = vec![
+        Line::from("Timeline"),
+        Line::from("My PR"),
+        repositories_tab_title(model.error_badge_count),
+    ];
     let tabs = Tabs::new(tab_titles)
         .select(model.active_tab.index())
         .block(Block::default().borders(Borders::ALL).title("View"))
@@ -83,79 +100,155 @@ fn render(frame: &mut Frame<'_>, model: &mut TuiModel) {
         );
     frame.render_widget(tabs, layout.tabs);
 
+    let kind_labels = effective_kind_labels(model.kind_labels, glyph_mode);
     match model.active_tab {
         ActiveTab::Timeline => {
-            render_timeline_panel(frame, model, layout.content, "Timeline", None)
+            render_timeline_panel(frame, model, layout.content, kind_labels, "Timeline", None)
         }
         ActiveTab::MyPr => {
-            let empty_message = if model.has_viewer_login() {
+            let empty_message = if model.has_viewer_login() || !model.team_members().is_empty() {
                 "No PR updates involving you"
             } else {
                 "Viewer login unavailable"
             };
-            render_timeline_panel(frame, model, layout.content, "My PR", Some(empty_message));
+            render_timeline_panel(
+                frame,
+                model,
+                layout.content,
+                kind_labels,
+                "My PR",
+                Some(empty_message),
+            );
         }
         ActiveTab::Repositories => render_repositories_panel(frame, model, layout.content),
     }
 
-    let selected_inner_width = shrink_by_border(layout.selected).width as usize;
-    let [selected_summary, selected_url] =
-        build_selected_lines(model, glyph_mode, selected_inner_width);
-    let selected = Paragraph::new(vec![Line::from(selected_summary), Line::from(selected_url)])
-        .block(Block::default().borders(Borders::ALL).title("Sel"));
-    frame.render_widget(selected, layout.selected);
+    if layout.split {
+        let mut detail_block = Block::default().borders(Borders::ALL).title("Detail");
+        if model.focused_pane == FocusedPane::Detail {
+            detail_block = detail_block.border_style(Style::default().fg(Color::Cyan));
+        }
+        let detail = Paragraph::new(
+            build_detail_lines(model, glyph_mode)
+                .into_iter()
+                .map(Line::from)
+                .collect::<Vec<_>>(),
+        )
+        .block(detail_block)
+        .wrap(Wrap { trim: false })
+        .scroll((model.detail_scroll, 0));
+        frame.render_widget(detail, layout.selected);
+    } else {
+        let selected_inner_width = shrink_by_border(layout.selected).width as usize;
+        let [selected_summary, selected_url] =
+            build_selected_lines(model, glyph_mode, selected_inner_width);
+        let selected = Paragraph::new(vec![Line::from(selected_summary), Line::from(selected_url)])
+            .block(Block::default().borders(Borders::ALL).title("Sel"));
+        frame.render_widget(selected, layout.selected);
+    }
 
-    let keys = Paragraph::new(Line::from(build_keys_line()))
+    let keys = Paragraph::new(Line::from(build_keys_line(&model.keyboard_shortcuts)))
         .block(Block::default().borders(Borders::ALL).title("Keys"));
     frame.render_widget(keys, layout.keys);
 
+    if model.context_menu.is_some() {
+        render_context_menu(frame, model);
+    }
+
     if model.help_visible {
-        render_help_overlay(frame);
+        render_help_overlay(frame, model);
     }
 }
 
+fn render_context_menu(frame: &mut Frame<'_>, model: &TuiModel) {
+    let Some(menu) = model.context_menu else {
+        return;
+    };
+    let area = context_menu_area(
+        frame.area(),
+        menu.anchor,
+        ContextMenuAction::ALL.len() as u16,
+    );
+    frame.render_widget(Clear, area);
+
+    let items = ContextMenuAction::ALL
+        .iter()
+        .map(|action| ListItem::new(action.label()))
+        .collect::<Vec<_>>();
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL))
+        .highlight_style(Style::default().bg(Color::DarkGray));
+    let mut state = ListState::default().with_selected(Some(menu.selected));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
 fn render_timeline_panel(
     frame: &mut Frame<'_>,
     model: &mut TuiModel,
     area: ratatui::layout::Rect,
+    kind_labels: KindLabelMode,
     title: &str,
     empty_message: Option<&str>,
 ) {
     let timeline_inner = shrink_by_border(area);
     model.timeline_page_size = (timeline_inner.height as usize).saturating_sub(1).max(1);
+    let block = Block::default().borders(Borders::ALL).title(timeline_title(
+        title,
+        model.kind_filter(),
+        kind_labels,
+        model.hide_closed(),
+    ));
 
-    let rows = if model.timeline.is_empty() {
-        vec![empty_message.map_or_else(timeline_empty_row, timeline_empty_row_with_message)]
-    } else {
-        model
-            .timeline
-            .iter()
-            .map(|event| timeline_row(event, model.is_event_read(&event.event_key())))
-            .collect()
-    };
+    if model.is_empty() {
+        model.selected = 0;
+        model.timeline_offset = 0;
+        model.selected_event_key = None;
+        let lines = build_empty_timeline_lines(model, empty_message)
+            .into_iter()
+            .map(Line::from)
+            .collect::<Vec<_>>();
+        let placeholder = Paragraph::new(lines)
+            .style(Style::default().add_modifier(Modifier::DIM))
+            .block(block);
+        frame.render_widget(placeholder, area);
+        return;
+    }
 
-    let table = Table::new(rows, timeline_constraints())
+    let mut event_index = 0;
+    let rows = model
+        .timeline_items()
+        .into_iter()
+        .map(|item| match item {
+            TimelineItem::Event(event) => {
+                let title_style = model
+                    .is_search_result(event_index)
+                    .then_some(model.search_highlight_style);
+                event_index += 1;
+                timeline_row(
+                    event,
+                    model.is_event_read(&event.event_key()),
+                    kind_labels,
+                    title_style,
+                    model.subject_state(event),
+                )
+            }
+            TimelineItem::Separator(repo) => timeline_separator_row(repo),
+        })
+        .collect::<Vec<_>>();
+
+    let table = Table::new(rows, timeline_constraints(kind_labels))
         .header(timeline_header().style(Style::default().add_modifier(Modifier::BOLD)))
-        .block(Block::default().borders(Borders::ALL).title(title))
+        .block(block)
         .row_highlight_style(Style::default().bg(Color::DarkGray))
         .highlight_symbol(">> ");
 
-    let mut state = TableState::default().with_offset(model.timeline_offset);
-    if model.timeline.is_empty() {
-        model.selected = 0;
-        model.timeline_offset = 0;
-        model.selected_event_key = None;
-    } else {
-        model.selected = model.selected.min(model.timeline.len() - 1);
-        model.sync_selected_event_key();
-        state.select(Some(model.selected));
-    }
+    model.selected = model.selected.min(model.timeline.len() - 1);
+    model.sync_selected_event_key();
+    let mut state = TableState::default()
+        .with_offset(model.timeline_offset)
+        .with_selected(Some(model.timeline_row_of(model.selected)));
     frame.render_stateful_widget(table, area, &mut state);
-    model.timeline_offset = if model.timeline.is_empty() {
-        0
-    } else {
-        state.offset()
-    };
+    model.timeline_offset = state.offset();
 }
 
 fn render_repositories_panel(
@@ -166,37 +259,38 @@ fn render_repositories_panel(
     let repo_items = if model.watched_repositories.is_empty() {
         vec![ListItem::new("No enabled repositories")]
     } else {
-        model
-            .watched_repositories
-            .iter()
-            .map(|repo| ListItem::new(repo.clone()))
-            .collect()
+        repository_rows(
+            &model.watched_repositories,
+            &model.repo_topics,
+            model.group_repos_by_topic,
+        )
+        .into_iter()
+        .map(ListItem::new)
+        .collect()
     };
-    let repo_list =
-        List::new(repo_items).block(Block::default().borders(Borders::ALL).title("Repositories"));
-    frame.render_widget(repo_list, area);
+    let mut block = Block::default().borders(Borders::ALL).title("Repositories");
+    if let Some(api_usage) = model.api_usage.as_deref() {
+        block = block.title_bottom(api_usage);
+    }
+    frame.render_widget(List::new(repo_items).block(block), area);
 }
 
-fn render_help_overlay(frame: &mut Frame<'_>) {
+fn render_help_overlay(frame: &mut Frame<'_>, model: &TuiModel) {
     let area = centered_rect(frame.area(), 80, 70);
     frame.render_widget(Clear, area);
 
-    let help = Paragraph::new(vec![
-        Line::from(vec![Span::styled(
-            "Keyboard",
-            Style::default().add_modifier(Modifier::BOLD),
-        )]),
-        Line::from("q: quit immediately"),
-        Line::from("Esc twice within 1.5s: quit"),
-        Line::from("Tab / Shift+Tab: switch Timeline, My PR and Repositories"),
-        Line::from("r: refresh, ?: toggle help, enter: open selected URL"),
-        Line::from("up/down or j/k: move one row (Timeline/My PR tabs)"),
-        Line::from("page up/page down: move one page (Timeline/My PR tabs)"),
-        Line::from("g/home: top, G/end: bottom (Timeline/My PR tabs)"),
-        Line::from("mouse: click to select, wheel to scroll (Timeline/My PR tabs)"),
-    ])
-    .block(Block::default().borders(Borders::ALL).title("Help"))
-    .wrap(Wrap { trim: true });
+    let mut lines = vec![Line::from(vec![Span::styled(
+        "Keyboard",
+        Style::default().add_modifier(Modifier::BOLD),
+    )])];
+    lines.extend(
+        help_lines(&model.keyboard_shortcuts)
+            .into_iter()
+            .map(Line::from),
+    );
+    let help = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title("Help"))
+        .wrap(Wrap { trim: true });
 
     frame.render_widget(help, area);
 }
@@ -9,12 +9,42 @@ fn commands_prints_core_commands_and_completion_hint() {
         .success()
         .stdout(contains("Core Commands"))
         .stdout(contains("gh-watch watch"))
+        .stdout(contains("--alert-after-idle <duration>"))
         .stdout(contains("gh-watch once"))
         .stdout(contains("gh-watch check"))
         .stdout(contains("gh-watch init"))
+        .stdout(contains("--from-org <org>"))
         .stdout(contains("gh-watch config open"))
         .stdout(contains("gh-watch config path"))
+        .stdout(contains("gh-watch config reset"))
+        .stdout(contains("gh-watch config defaults"))
+        .stdout(contains("gh-watch config doctor"))
+        .stdout(contains("gh-watch config rollback"))
+        .stdout(contains("gh-watch config show-keymap"))
+        .stdout(contains("gh-watch launchd"))
+        .stdout(contains("--no-tui"))
+        .stdout(contains("--export-on-exit <path>"))
+        .stdout(contains(
+            "gh-watch events [--search <query> | --since <duration>",
+        ))
+        .stdout(contains("gh-watch stream [--format text|json]"))
+        .stdout(contains("gh-watch state check"))
+        .stdout(contains("gh-watch state vacuum"))
+        .stdout(contains("gh-watch state export"))
+        .stdout(contains("gh-watch state import"))
+        .stdout(contains("gh-watch diagnose"))
+        .stdout(contains("gh-watch profile list"))
+        .stdout(contains("gh-watch filter-profile"))
         .stdout(contains("gh-watch commands"))
         .stdout(contains("gh-watch completion <shell>"))
         .stdout(contains("gh-watch completion zsh"));
 }
+
+#[test]
+fn watch_export_format_requires_export_on_exit() {
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.args(["watch", "--export-format", "csv"])
+        .assert()
+        .failure()
+        .stderr(contains("--export-on-exit"));
+}
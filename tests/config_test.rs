@@ -4,10 +4,16 @@ use std::{
     sync::{Mutex, OnceLock},
 };
 
+use chrono::{FixedOffset, NaiveTime, TimeZone, Utc, Weekday};
 use gh_watch::config::{
-    parse_config, resolve_config_path, resolve_config_path_with_source, stability_warnings,
-    ConfigPathSource,
+    api_budget_warning, apply_interval_override, estimated_hourly_api_calls,
+    find_unknown_config_keys, parse_config, parse_notification_schedule, parse_summary_schedule,
+    parse_summary_timezone, redact_proxy_url, resolve_config_path, resolve_config_path_with_source,
+    split_org_team, stability_warnings, validate_proxy_url, ConfigError, ConfigPathSource,
+    DisplayLayout, IntervalClamp, KindLabelMode, NotificationSchedule, NotificationScheduleConfig,
+    SummaryTimezone, SyncBackend, MIN_INTERVAL_SECONDS,
 };
+use gh_watch::domain::events::{EventKind, ReviewState};
 use tempfile::tempdir;
 
 #[test]
@@ -35,6 +41,7 @@ name = "octocat/hello-world"
     assert_eq!(cfg.bootstrap_lookback_hours, 24);
     assert_eq!(cfg.timeline_limit, 500);
     assert_eq!(cfg.retention_days, 90);
+    assert_eq!(cfg.max_notification_history, 10_000);
     assert_eq!(cfg.repositories.len(), 1);
     assert!(cfg.repositories[0].enabled);
     assert!(cfg.notifications.enabled);
@@ -43,6 +50,135 @@ name = "octocat/hello-world"
     assert!(cfg.filters.ignore_actors.is_empty());
     assert!(!cfg.filters.only_involving_me);
     assert_eq!(cfg.poll.timeout_seconds, 30);
+    assert!(!cfg.retention.keep_unread);
+    assert!(cfg.retention.days_by_kind.is_empty());
+}
+
+#[test]
+fn parse_config_reads_retention_overrides_by_kind() {
+    let src = r#"
+retention_days = 60
+
+[retention]
+keep_unread = true
+
+[retention.days_by_kind]
+pr_merged = 365
+issue_comment_created = 30
+
+[[repositories]]
+name = "octocat/hello-world"
+"#;
+
+    let cfg = parse_config(src).expect("config should parse");
+    assert_eq!(cfg.retention_days, 60);
+    assert!(cfg.retention.keep_unread);
+    assert_eq!(
+        cfg.retention.days_by_kind.get(&EventKind::PrMerged),
+        Some(&365)
+    );
+    assert_eq!(
+        cfg.retention
+            .days_by_kind
+            .get(&EventKind::IssueCommentCreated),
+        Some(&30)
+    );
+}
+
+#[test]
+fn parse_config_reads_notification_kinds_and_repository_overrides() {
+    let src = r#"
+[notifications.kinds]
+issue_comment_created = false
+pr_merged = true
+
+[[repositories]]
+name = "acme/api"
+notification_kinds = { issue_comment_created = true }
+
+[[repositories]]
+name = "acme/web"
+"#;
+
+    let cfg = parse_config(src).expect("config should parse");
+    assert_eq!(
+        cfg.notifications.kinds.get(&EventKind::IssueCommentCreated),
+        Some(&false)
+    );
+    assert_eq!(
+        cfg.repositories[0]
+            .notification_kinds
+            .get(&EventKind::IssueCommentCreated),
+        Some(&true)
+    );
+    assert!(cfg.repositories[1].notification_kinds.is_empty());
+
+    assert!(cfg.notifies_kind("acme/api", &EventKind::IssueCommentCreated));
+    assert!(!cfg.notifies_kind("acme/web", &EventKind::IssueCommentCreated));
+    assert!(cfg.notifies_kind("acme/web", &EventKind::PrMerged));
+    assert!(cfg.notifies_kind("acme/web", &EventKind::PrCreated));
+}
+
+#[test]
+fn notification_kinds_default_to_notifying_every_kind() {
+    let cfg = parse_config(
+        r#"
+[[repositories]]
+name = "acme/api"
+"#,
+    )
+    .expect("config should parse");
+
+    assert!(cfg.notifications.kinds.is_empty());
+    assert!(EventKind::ALL
+        .iter()
+        .all(|kind| cfg.notifies_kind("acme/api", kind)));
+}
+
+#[test]
+fn parse_config_rejects_unknown_notification_kind() {
+    let src = r#"
+[notifications.kinds]
+pr_exploded = false
+
+[[repositories]]
+name = "octocat/hello-world"
+"#;
+
+    assert!(parse_config(src).is_err());
+}
+
+#[test]
+fn max_event_age_hours_defaults_to_two_days_and_rejects_zero() {
+    let cfg = parse_config("[[repositories]]\nname = \"acme/api\"\n").unwrap();
+    assert_eq!(cfg.notifications.max_event_age_hours, 48);
+
+    let err = parse_config(
+        r#"
+[notifications]
+max_event_age_hours = 0
+
+[[repositories]]
+name = "acme/api"
+"#,
+    )
+    .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("notifications.max_event_age_hours must be >= 1"));
+}
+
+#[test]
+fn parse_config_rejects_unknown_retention_kind() {
+    let src = r#"
+[retention.days_by_kind]
+pr_exploded = 10
+
+[[repositories]]
+name = "octocat/hello-world"
+"#;
+
+    assert!(parse_config(src).is_err());
 }
 
 #[test]
@@ -62,6 +198,279 @@ name = "octocat/hello-world"
     assert!(warnings.iter().any(|warning| warning.contains(">= 30")));
 }
 
+#[test]
+fn api_budget_warning_only_when_estimate_exceeds_hourly_limit() {
+    let mut cfg = parse_config(
+        r#"
+interval_seconds = 300
+
+[[repositories]]
+name = "acme/api"
+
+[[repositories]]
+name = "acme/off"
+enabled = false
+"#,
+    )
+    .expect("config should parse");
+
+    assert_eq!(estimated_hourly_api_calls(&cfg), 72);
+    assert!(api_budget_warning(&cfg).is_none());
+
+    cfg.interval_seconds = 1;
+    assert_eq!(estimated_hourly_api_calls(&cfg), 21_600);
+    assert!(api_budget_warning(&cfg)
+        .unwrap()
+        .contains("exceeds the 5000 req/hour limit"));
+}
+
+#[test]
+fn parse_config_reads_review_states() {
+    let cfg = parse_config(
+        r#"
+[filters]
+review_states = ["approved", "changes_requested"]
+
+[filter_profiles.quiet]
+review_states = ["dismissed"]
+
+[[repositories]]
+name = "acme/api"
+"#,
+    )
+    .expect("config should parse");
+    assert_eq!(
+        cfg.filters.review_states,
+        vec![ReviewState::Approved, ReviewState::ChangesRequested]
+    );
+    assert_eq!(
+        cfg.filter_profiles["quiet"].review_states,
+        vec![ReviewState::Dismissed]
+    );
+
+    let err = parse_config(
+        r#"
+[filters]
+review_states = ["pending"]
+
+[[repositories]]
+name = "acme/api"
+"#,
+    )
+    .unwrap_err();
+    assert!(format!("{err:#}").contains("pending"));
+}
+
+#[test]
+fn parse_config_validates_auto_fetch_org_team() {
+    let valid = parse_config(
+        r#"
+[filters]
+only_involving_me = true
+team_members = ["alice"]
+auto_fetch_org_team = "acme/backend"
+
+[filter_profiles.org]
+auto_fetch_org_team = "acme"
+
+[[repositories]]
+name = "acme/api"
+"#,
+    )
+    .expect("config should parse");
+    assert_eq!(valid.filters.team_members, vec!["alice".to_string()]);
+    assert_eq!(
+        split_org_team("acme/backend").unwrap(),
+        ("acme", Some("backend"))
+    );
+    assert_eq!(split_org_team("acme").unwrap(), ("acme", None));
+
+    let err = parse_config(
+        r#"
+[filter_profiles.bad]
+auto_fetch_org_team = "acme/"
+
+[[repositories]]
+name = "acme/api"
+"#,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("invalid auto_fetch_org_team"));
+}
+
+#[test]
+fn parse_config_reads_accounts_and_repo_routing() {
+    let cfg = parse_config(
+        r#"
+[[accounts]]
+name = "work"
+gh_config_dir = "/tmp/gh-work"
+
+[[accounts]]
+name = "bot"
+host = "ghe.example.com"
+token_env = "BOT_GH_TOKEN"
+
+[[repositories]]
+name = "acme/api"
+account = "work"
+
+[[repositories]]
+name = "acme/web"
+"#,
+    )
+    .expect("config should parse");
+
+    assert_eq!(cfg.accounts.len(), 2);
+    assert_eq!(cfg.accounts[0].host, "github.com");
+    assert_eq!(
+        cfg.accounts[0].gh_config_dir.as_deref(),
+        Some("/tmp/gh-work")
+    );
+    assert_eq!(cfg.accounts[1].host, "ghe.example.com");
+    assert_eq!(cfg.repositories[0].account.as_deref(), Some("work"));
+    assert_eq!(cfg.repositories[1].account, None);
+}
+
+#[test]
+fn parse_config_rejects_invalid_accounts() {
+    let undefined = parse_config(
+        r#"
+[[repositories]]
+name = "acme/api"
+account = "work"
+"#,
+    )
+    .unwrap_err();
+    assert!(undefined
+        .to_string()
+        .contains("uses account 'work', which is not defined"));
+
+    let both = parse_config(
+        r#"
+[[accounts]]
+name = "work"
+gh_config_dir = "/tmp/gh-work"
+token_env = "WORK_TOKEN"
+
+[[repositories]]
+name = "acme/api"
+"#,
+    )
+    .unwrap_err();
+    assert!(both
+        .to_string()
+        .contains("exactly one of gh_config_dir or token_env"));
+
+    let duplicate = parse_config(
+        r#"
+[[accounts]]
+name = "work"
+token_env = "A"
+
+[[accounts]]
+name = "work"
+token_env = "B"
+
+[[repositories]]
+name = "acme/api"
+"#,
+    )
+    .unwrap_err();
+    assert!(duplicate.to_string().contains("defined more than once"));
+}
+
+#[test]
+fn parse_config_reads_gh_profiles_and_rejects_mixing_them_with_accounts() {
+    let cfg = parse_config(
+        r#"
+[[repositories]]
+name = "acme/api"
+gh_profile = "alice-work"
+
+[[repositories]]
+name = "me/dotfiles"
+"#,
+    )
+    .expect("config should parse");
+    assert_eq!(
+        cfg.repositories[0].gh_profile.as_deref(),
+        Some("alice-work")
+    );
+    assert_eq!(cfg.repositories[1].gh_profile, None);
+
+    let both = parse_config(
+        r#"
+[[accounts]]
+name = "work"
+gh_config_dir = "/tmp/gh-work"
+
+[[repositories]]
+name = "acme/api"
+account = "work"
+gh_profile = "alice-work"
+"#,
+    )
+    .unwrap_err();
+    assert!(both
+        .to_string()
+        .contains("must not set both account and gh_profile"));
+
+    let empty = parse_config(
+        r#"
+[[repositories]]
+name = "acme/api"
+gh_profile = " "
+"#,
+    )
+    .unwrap_err();
+    assert!(empty.to_string().contains("empty gh_profile"));
+}
+
+#[test]
+fn parse_config_reads_and_validates_network_proxy() {
+    let cfg = parse_config(
+        r#"
+[network]
+proxy = "socks5h://proxy.local:1080"
+no_proxy = ["localhost"]
+
+[[repositories]]
+name = "acme/api"
+"#,
+    )
+    .expect("config should parse");
+    assert_eq!(
+        cfg.network.proxy.as_deref(),
+        Some("socks5h://proxy.local:1080")
+    );
+    assert_eq!(cfg.network.no_proxy, vec!["localhost".to_string()]);
+
+    for (proxy, reason) in [
+        ("proxy.local:3128", "expected scheme://host:port"),
+        ("ftp://proxy.local:21", "unsupported scheme 'ftp'"),
+        ("http://user:pw@proxy.local:port", "invalid port 'port'"),
+        ("http://:3128", "expected scheme://host:port"),
+    ] {
+        let err = parse_config(&format!(
+            "[network]\nproxy = \"{proxy}\"\n\n[[repositories]]\nname = \"acme/api\"\n"
+        ))
+        .unwrap_err();
+        assert!(err.to_string().contains(reason), "{proxy}: {err}");
+        assert!(!err.to_string().contains(":pw@"), "{err}");
+    }
+
+    assert!(validate_proxy_url("http://[::1]:3128").is_ok());
+    assert_eq!(
+        redact_proxy_url("http://user:pw@proxy.local:3128"),
+        "http://***@proxy.local:3128"
+    );
+    assert_eq!(
+        redact_proxy_url("http://proxy.local:3128"),
+        "http://proxy.local:3128"
+    );
+}
+
 #[test]
 fn parse_config_rejects_removed_notification_sender_keys() {
     let src = r#"
@@ -127,6 +536,25 @@ name = "octocat/hello-world"
     assert!(err.to_string().contains("poll.timeout_seconds"));
 }
 
+#[test]
+fn parse_config_rejects_jitter_seconds_not_below_interval_seconds() {
+    let src = r#"
+interval_seconds = 60
+
+[poll]
+jitter_seconds = 60
+
+[[repositories]]
+name = "octocat/hello-world"
+"#;
+
+    let err = parse_config(src).expect_err("jitter_seconds >= interval_seconds should fail");
+    assert!(err.to_string().contains("poll.jitter_seconds (60)"));
+
+    let cfg = parse_config(&src.replace("jitter_seconds = 60", "jitter_seconds = 59")).unwrap();
+    assert_eq!(cfg.poll.jitter_seconds, 59);
+}
+
 #[test]
 fn parse_config_rejects_zero_bootstrap_lookback_hours() {
     let src = r#"
@@ -171,6 +599,215 @@ event_kinds = ["pr_created"]
     );
 }
 
+#[test]
+fn event_kinds_for_falls_back_from_repo_to_filters_to_default_event_kinds() {
+    let src = r#"
+default_event_kinds = ["pr_created", "issue_created"]
+
+[[repositories]]
+name = "acme/quiet"
+
+[[repositories]]
+name = "acme/noisy"
+event_kinds = ["pr_created"]
+"#;
+
+    let cfg = parse_config(src).expect("config should parse");
+    assert_eq!(
+        cfg.event_kinds_for(&cfg.repositories[0]),
+        vec![EventKind::PrCreated, EventKind::IssueCreated]
+    );
+    assert_eq!(
+        cfg.event_kinds_for(&cfg.repositories[1]),
+        vec![EventKind::PrCreated]
+    );
+
+    let with_filters = parse_config(&format!(
+        "{src}\n[filters]\nevent_kinds = [\"pr_merged\"]\n"
+    ))
+    .expect("config should parse");
+    assert_eq!(
+        with_filters.event_kinds_for(&with_filters.repositories[0]),
+        vec![EventKind::PrMerged]
+    );
+    assert_eq!(
+        with_filters.event_kinds_for(&with_filters.repositories[1]),
+        vec![EventKind::PrCreated]
+    );
+}
+
+#[test]
+fn event_kinds_for_allows_every_kind_without_default_event_kinds() {
+    let cfg = parse_config(
+        r#"
+[[repositories]]
+name = "acme/api"
+"#,
+    )
+    .expect("config should parse");
+
+    assert!(cfg.default_event_kinds.is_none());
+    assert!(cfg.event_kinds_for(&cfg.repositories[0]).is_empty());
+}
+
+#[test]
+fn parse_config_rejects_empty_default_event_kinds() {
+    let src = r#"
+default_event_kinds = []
+
+[[repositories]]
+name = "acme/api"
+"#;
+
+    let err = parse_config(src).expect_err("empty default_event_kinds should fail");
+    assert!(err
+        .to_string()
+        .contains("default_event_kinds must not be empty"));
+}
+
+#[test]
+fn parse_config_reads_and_validates_display_browser_command() {
+    let cfg = parse_config(
+        r#"
+[display]
+browser_command = 'open -a "Google Chrome" --args --profile-directory=Work %s'
+
+[[repositories]]
+name = "acme/api"
+"#,
+    )
+    .expect("config should parse");
+    assert_eq!(
+        cfg.display.browser_command.as_deref(),
+        Some(r#"open -a "Google Chrome" --args --profile-directory=Work %s"#)
+    );
+
+    let err = parse_config(
+        r#"
+[display]
+browser_command = 'open -a "Google Chrome'
+
+[[repositories]]
+name = "acme/api"
+"#,
+    )
+    .expect_err("unbalanced quotes should fail");
+    assert!(err.to_string().contains("display.browser_command"));
+}
+
+#[test]
+fn parse_config_reads_display_layout_and_defaults_to_auto() {
+    let minimal = parse_config(
+        r#"
+[[repositories]]
+name = "acme/api"
+"#,
+    )
+    .expect("config should parse");
+    assert_eq!(minimal.display.layout, DisplayLayout::Auto);
+
+    let split = parse_config(
+        r#"
+[display]
+layout = "split"
+
+[[repositories]]
+name = "acme/api"
+"#,
+    )
+    .expect("config should parse");
+    assert_eq!(split.display.layout, DisplayLayout::Split);
+
+    parse_config(
+        r#"
+[display]
+layout = "sideways"
+
+[[repositories]]
+name = "acme/api"
+"#,
+    )
+    .expect_err("unknown layout should fail");
+}
+
+#[test]
+fn parse_config_reads_display_kind_labels_and_defaults_to_short() {
+    let minimal = parse_config(
+        r#"
+[[repositories]]
+name = "acme/api"
+"#,
+    )
+    .expect("config should parse");
+    assert_eq!(minimal.display.kind_labels, KindLabelMode::Short);
+
+    let icon = parse_config(
+        r#"
+[display]
+kind_labels = "icon"
+
+[[repositories]]
+name = "acme/api"
+"#,
+    )
+    .expect("config should parse");
+    assert_eq!(icon.display.kind_labels, KindLabelMode::Icon);
+}
+
+#[test]
+fn parse_config_reads_display_resolve_subject_state_and_defaults_to_off() {
+    let minimal = parse_config(
+        r#"
+[[repositories]]
+name = "acme/api"
+"#,
+    )
+    .expect("config should parse");
+    assert!(!minimal.display.resolve_subject_state);
+
+    let enabled = parse_config(
+        r#"
+[display]
+resolve_subject_state = true
+
+[[repositories]]
+name = "acme/api"
+"#,
+    )
+    .expect("config should parse");
+    assert!(enabled.display.resolve_subject_state);
+}
+
+#[test]
+fn parse_config_reads_topic_tags_and_group_repos_by_topic() {
+    let minimal = parse_config(
+        r#"
+[[repositories]]
+name = "acme/api"
+"#,
+    )
+    .expect("config should parse");
+    assert!(!minimal.display.group_repos_by_topic);
+    assert_eq!(minimal.repositories[0].topic_tags, None);
+
+    let grouped = parse_config(
+        r#"
+[display]
+group_repos_by_topic = true
+
+[[repositories]]
+name = "acme/api"
+topic_tags = ["rust", "internal"]
+"#,
+    )
+    .expect("config should parse");
+    assert!(grouped.display.group_repos_by_topic);
+    assert_eq!(
+        grouped.repositories[0].topic_tags,
+        Some(vec!["rust".to_string(), "internal".to_string()])
+    );
+}
+
 #[test]
 fn parse_config_rejects_unknown_filter_event_kind() {
     let src = r#"
@@ -283,6 +920,305 @@ fn resolve_config_path_falls_back_to_binary_directory_when_cwd_missing() {
     });
 }
 
+#[test]
+fn parse_config_expands_env_vars_in_state_db_path() {
+    let _lock = env_lock().lock().unwrap();
+    env::set_var("GH_WATCH_TEST_DATA_HOME", "/data/home");
+    env::set_var("GH_WATCH_TEST_PROFILE_DIR", "work");
+
+    let src = r#"
+state_db_path = "${GH_WATCH_TEST_DATA_HOME}/gh-watch/$GH_WATCH_TEST_PROFILE_DIR/%GH_WATCH_TEST_PROFILE_DIR%.db"
+
+[[repositories]]
+name = "octocat/hello-world"
+"#;
+    let cfg = parse_config(src);
+    env::remove_var("GH_WATCH_TEST_DATA_HOME");
+    env::remove_var("GH_WATCH_TEST_PROFILE_DIR");
+
+    assert_eq!(
+        cfg.expect("config should parse").state_db_path.as_deref(),
+        Some("/data/home/gh-watch/work/work.db")
+    );
+}
+
+#[test]
+fn parse_config_expands_repository_local_path() {
+    let _lock = env_lock().lock().unwrap();
+    env::set_var("GH_WATCH_TEST_SRC", "/work/src");
+
+    let src = r#"
+[[repositories]]
+name = "octocat/hello-world"
+local_path = "${GH_WATCH_TEST_SRC}/hello-world"
+
+[[repositories]]
+name = "octocat/other"
+"#;
+    let cfg = parse_config(src);
+    env::remove_var("GH_WATCH_TEST_SRC");
+
+    let cfg = cfg.expect("config should parse");
+    assert_eq!(
+        cfg.repositories[0].local_path.as_deref(),
+        Some("/work/src/hello-world")
+    );
+    assert_eq!(cfg.repositories[1].local_path, None);
+}
+
+#[test]
+fn parse_config_does_not_re_expand_nested_variable_values() {
+    let _lock = env_lock().lock().unwrap();
+    env::set_var("GH_WATCH_TEST_OUTER", "${GH_WATCH_TEST_INNER}/state");
+    env::remove_var("GH_WATCH_TEST_INNER");
+
+    let src = r#"
+state_db_path = "${GH_WATCH_TEST_OUTER}.db"
+
+[[repositories]]
+name = "octocat/hello-world"
+"#;
+    let cfg = parse_config(src);
+    env::remove_var("GH_WATCH_TEST_OUTER");
+
+    assert_eq!(
+        cfg.expect("config should parse").state_db_path.as_deref(),
+        Some("${GH_WATCH_TEST_INNER}/state.db")
+    );
+}
+
+#[test]
+fn parse_config_rejects_unset_env_var_in_state_db_path() {
+    let _lock = env_lock().lock().unwrap();
+    env::remove_var("GH_WATCH_TEST_MISSING");
+
+    let src = r#"
+state_db_path = "$GH_WATCH_TEST_MISSING/state.db"
+
+[[repositories]]
+name = "octocat/hello-world"
+"#;
+    let err = parse_config(src).expect_err("unset variable should fail");
+
+    let msg = err.to_string();
+    assert!(msg.contains("state_db_path"));
+    assert!(msg.contains("GH_WATCH_TEST_MISSING"));
+}
+
+#[test]
+fn parse_config_expands_leading_tilde_and_keeps_literal_markers() {
+    let src = r#"
+state_db_path = "~/gh-watch/100%/$/state.db"
+
+[[repositories]]
+name = "octocat/hello-world"
+"#;
+    let cfg = parse_config(src).expect("config should parse");
+
+    let home = directories::BaseDirs::new()
+        .unwrap()
+        .home_dir()
+        .to_path_buf();
+    let expected = format!("{}/gh-watch/100%/$/state.db", home.display());
+    assert_eq!(cfg.state_db_path.as_deref(), Some(expected.as_str()));
+}
+
+#[test]
+fn find_unknown_config_keys_reports_every_key_with_location_and_suggestion() {
+    let src = r#"intervall_seconds = 30
+
+[notifcations]
+enabled = true
+
+[poll]
+timeout_secs = 10
+
+[retention.days_by_kind]
+pr_merged = 365
+
+[[repositories]]
+name = "octocat/hello-world"
+enabeld = false
+"#;
+
+    let unknown = find_unknown_config_keys(src).unwrap();
+    let rendered = unknown.iter().map(ToString::to_string).collect::<Vec<_>>();
+    assert_eq!(
+        rendered,
+        vec![
+            "unknown key `intervall_seconds` at line 1, column 1 (did you mean `interval_seconds`?)",
+            "unknown key `notifcations` at line 3, column 2 (did you mean `notifications`?)",
+            "unknown key `poll.timeout_secs` at line 7, column 1 (did you mean `poll.timeout_seconds`?)",
+            "unknown key `repositories.enabeld` at line 14, column 1 (did you mean `repositories.enabled`?)",
+        ]
+    );
+}
+
+#[test]
+fn find_unknown_config_keys_omits_suggestion_when_nothing_is_close() {
+    let src = r#"
+completely_different = 1
+
+[[repositories]]
+name = "octocat/hello-world"
+"#;
+
+    let unknown = find_unknown_config_keys(src).unwrap();
+    assert_eq!(unknown.len(), 1);
+    assert_eq!(unknown[0].path, "completely_different");
+    assert_eq!(unknown[0].suggestion, None);
+}
+
+#[test]
+fn parse_config_error_lists_all_unknown_keys() {
+    let src = r#"
+intervall_seconds = 30
+
+[notifcations]
+enabled = true
+
+[[repositories]]
+name = "octocat/hello-world"
+"#;
+
+    let err = parse_config(src).expect_err("unknown keys should fail");
+    let msg = format!("{err:#}");
+    assert!(msg.contains("unknown keys: intervall_seconds, notifcations"));
+    assert!(msg.contains("line 2, column 1"));
+}
+
+#[test]
+fn parse_config_selects_active_filter_profile_over_default_filters() {
+    let src = r#"
+active_filter_profile = "work"
+
+[filters]
+only_involving_me = false
+
+[filter_profiles.work]
+only_involving_me = true
+
+[[repositories]]
+name = "octocat/hello-world"
+"#;
+
+    let mut cfg = parse_config(src).expect("config should parse");
+    assert!(cfg.active_filters().only_involving_me);
+
+    cfg.active_filter_profile = None;
+    assert!(!cfg.active_filters().only_involving_me);
+}
+
+#[test]
+fn parse_config_rejects_undefined_or_reserved_filter_profiles() {
+    let undefined = r#"
+active_filter_profile = "missing"
+
+[[repositories]]
+name = "octocat/hello-world"
+"#;
+    let err = parse_config(undefined).expect_err("undefined profile should fail");
+    assert!(err.to_string().contains("active_filter_profile 'missing'"));
+
+    let reserved = r#"
+[filter_profiles.default]
+only_involving_me = true
+
+[[repositories]]
+name = "octocat/hello-world"
+"#;
+    let err = parse_config(reserved).expect_err("reserved profile name should fail");
+    assert!(err.to_string().contains("reserved"));
+}
+
+fn keymap_config(entries: &str) -> String {
+    format!(
+        r#"
+[keymap]
+{entries}
+
+[[repositories]]
+name = "acme/api"
+"#
+    )
+}
+
+#[test]
+fn parse_config_accepts_every_supported_key_code() {
+    let mut keys = vec![
+        "Backspace",
+        "Enter",
+        "Left",
+        "Right",
+        "Up",
+        "Down",
+        "Home",
+        "End",
+        "PageUp",
+        "PageDown",
+        "Tab",
+        "BackTab",
+        "Delete",
+        "Insert",
+        "Esc",
+        "Space",
+        "a",
+        "Z",
+        "F",
+        "?",
+        "é",
+    ]
+    .into_iter()
+    .map(str::to_string)
+    .collect::<Vec<_>>();
+    keys.extend((1..=12).map(|n| format!("F{n}")));
+
+    for key in keys {
+        let src = keymap_config(&format!("refresh = [{key:?}]"));
+        let cfg = parse_config(&src).unwrap_or_else(|err| panic!("{key:?} should parse: {err}"));
+        assert_eq!(cfg.keymap.refresh, Some(vec![key]));
+    }
+}
+
+#[test]
+fn parse_config_rejects_invalid_key_codes() {
+    for key in [
+        "F0", "F13", "ctrl-x", "C-x", "", " ", "ab", "enter", "pageup",
+    ] {
+        let src = keymap_config(&format!("open = [{key:?}]"));
+        let err = parse_config(&src).expect_err("invalid key code should fail");
+        assert_eq!(
+            err.downcast_ref::<ConfigError>(),
+            Some(&ConfigError::InvalidKeyCode {
+                action: "open".to_string(),
+                key: key.to_string(),
+            }),
+            "{key:?}"
+        );
+        assert!(err.to_string().contains("keymap.open"));
+    }
+}
+
+#[test]
+fn parse_config_rejects_key_bound_to_two_configured_actions() {
+    let src = keymap_config("refresh = [\"R\"]\nopen = [\"o\", \"R\"]");
+
+    let err = parse_config(&src).expect_err("duplicate binding should fail");
+    assert!(err
+        .to_string()
+        .contains("key 'R' is bound to both keymap.refresh and keymap.open"));
+}
+
+#[test]
+fn parse_config_allows_configured_key_to_shadow_default_binding() {
+    let cfg = parse_config(&keymap_config("refresh = [\"q\"]\nquit = []"))
+        .expect("shadowing a default is allowed");
+
+    assert_eq!(cfg.keymap.refresh, Some(vec!["q".to_string()]));
+    assert_eq!(cfg.keymap.quit, Some(Vec::new()));
+    assert_eq!(cfg.keymap.open, None);
+}
+
 fn env_lock() -> &'static Mutex<()> {
     static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
     LOCK.get_or_init(|| Mutex::new(()))
@@ -301,3 +1237,312 @@ fn with_current_dir(path: &Path, test: impl FnOnce()) {
     env::set_current_dir(path).expect("set current dir should succeed");
     test();
 }
+
+fn interval_config(extra: &str) -> gh_watch::config::Config {
+    parse_config(&format!(
+        "{extra}\n[[repositories]]\nname = \"octocat/hello-world\"\n"
+    ))
+    .expect("config should parse")
+}
+
+#[test]
+fn interval_flag_overrides_config_and_clamp_applies_last() {
+    let mut cfg = interval_config("interval_seconds = 60");
+    let clamp = apply_interval_override(&mut cfg, Some(5)).unwrap();
+    assert_eq!(
+        clamp,
+        Some(IntervalClamp {
+            requested: 5,
+            effective: MIN_INTERVAL_SECONDS,
+        })
+    );
+    assert_eq!(cfg.interval_seconds, MIN_INTERVAL_SECONDS);
+    assert!(clamp.unwrap().to_string().contains("i_know_what_im_doing"));
+
+    let mut cfg = interval_config("interval_seconds = 5");
+    assert_eq!(apply_interval_override(&mut cfg, Some(45)).unwrap(), None);
+    assert_eq!(cfg.interval_seconds, 45);
+
+    let mut cfg = interval_config("interval_seconds = 5");
+    assert!(apply_interval_override(&mut cfg, None).unwrap().is_some());
+    assert_eq!(cfg.interval_seconds, MIN_INTERVAL_SECONDS);
+}
+
+#[test]
+fn interval_clamp_is_skipped_when_explicitly_allowed() {
+    let mut cfg = interval_config("interval_seconds = 60\ni_know_what_im_doing = true");
+    assert_eq!(apply_interval_override(&mut cfg, Some(5)).unwrap(), None);
+    assert_eq!(cfg.interval_seconds, 5);
+    assert!(stability_warnings(&cfg)
+        .iter()
+        .any(|warning| warning.contains("recommend >= 30")));
+}
+
+#[test]
+fn interval_flag_rejects_zero() {
+    let mut cfg = interval_config("interval_seconds = 60");
+    let err = apply_interval_override(&mut cfg, Some(0)).unwrap_err();
+    assert!(err.to_string().contains("--interval-seconds must be >= 1"));
+    assert_eq!(cfg.interval_seconds, 60);
+}
+
+#[test]
+fn parse_config_reads_startup_search_queries_of_watched_repos() {
+    let cfg = parse_config(
+        r#"
+[[startup_search_queries]]
+query = "is:open assignee:@me"
+repos = ["acme/api"]
+
+[[startup_search_queries]]
+query = "label:bug"
+
+[[repositories]]
+name = "acme/api"
+"#,
+    )
+    .expect("config should parse");
+    assert_eq!(cfg.startup_search_queries.len(), 2);
+    assert_eq!(cfg.startup_search_queries[0].repos, vec!["acme/api"]);
+    assert!(cfg.startup_search_queries[1].repos.is_empty());
+
+    let err = parse_config(
+        r#"
+[[startup_search_queries]]
+query = "is:open"
+repos = ["acme/web"]
+
+[[repositories]]
+name = "acme/api"
+"#,
+    )
+    .expect_err("unwatched repository should fail");
+    assert!(err.to_string().contains("acme/web"));
+}
+
+#[test]
+fn summary_schedule_parses_weekday_and_time() {
+    let schedule = parse_summary_schedule("Mon 09:00").expect("schedule should parse");
+    assert_eq!(schedule.weekday, Weekday::Mon);
+    assert_eq!(schedule.time, NaiveTime::from_hms_opt(9, 0, 0).unwrap());
+
+    let schedule = parse_summary_schedule("  friday   17:30 ").expect("long names parse");
+    assert_eq!(schedule.weekday, Weekday::Fri);
+    assert_eq!(schedule.time, NaiveTime::from_hms_opt(17, 30, 0).unwrap());
+
+    for invalid in [
+        "",
+        "Mon",
+        "09:00",
+        "Mon 9am",
+        "Mon 24:00",
+        "Funday 09:00",
+        "Mon 09:00 x",
+    ] {
+        assert!(
+            parse_summary_schedule(invalid).is_none(),
+            "{invalid:?} should be rejected"
+        );
+    }
+}
+
+#[test]
+fn summary_timezone_accepts_local_utc_and_offsets() {
+    assert_eq!(
+        parse_summary_timezone("local"),
+        Some(SummaryTimezone::Local)
+    );
+    assert_eq!(
+        parse_summary_timezone("UTC"),
+        Some(SummaryTimezone::Fixed(FixedOffset::east_opt(0).unwrap()))
+    );
+    assert_eq!(
+        parse_summary_timezone("+09:00"),
+        Some(SummaryTimezone::Fixed(
+            FixedOffset::east_opt(9 * 3600).unwrap()
+        ))
+    );
+    assert_eq!(parse_summary_timezone("Asia/Tokyo"), None);
+}
+
+#[test]
+fn notification_schedule_hours_are_inclusive_at_both_ends() {
+    let schedule = NotificationSchedule {
+        enabled_days: Vec::new(),
+        enabled_hours: 9..=18,
+    };
+    // 2025-01-06 is a Monday.
+    let at = |hour, minute| Utc.with_ymd_and_hms(2025, 1, 6, hour, minute, 0).unwrap();
+
+    assert!(!schedule.allows(&at(8, 59)));
+    assert!(schedule.allows(&at(9, 0)));
+    assert!(schedule.allows(&at(18, 59)));
+    assert!(!schedule.allows(&at(19, 0)));
+}
+
+#[test]
+fn notification_schedule_skips_days_not_listed() {
+    let schedule = NotificationSchedule {
+        enabled_days: vec![Weekday::Mon, Weekday::Fri],
+        enabled_hours: 0..=23,
+    };
+
+    assert!(schedule.allows(&Utc.with_ymd_and_hms(2025, 1, 6, 0, 0, 0).unwrap()));
+    assert!(!schedule.allows(&Utc.with_ymd_and_hms(2025, 1, 7, 12, 0, 0).unwrap()));
+    assert!(schedule.allows(&Utc.with_ymd_and_hms(2025, 1, 10, 23, 59, 0).unwrap()));
+    assert!(!schedule.allows(&Utc.with_ymd_and_hms(2025, 1, 11, 0, 0, 0).unwrap()));
+}
+
+#[test]
+fn parse_notification_schedule_reads_day_names_and_hours() {
+    let schedule = parse_notification_schedule(&NotificationScheduleConfig {
+        enabled_days: vec!["Mon".to_string(), "tuesday".to_string()],
+        enabled_hours: [9, 18],
+    })
+    .expect("schedule should parse");
+
+    assert_eq!(schedule.enabled_days, vec![Weekday::Mon, Weekday::Tue]);
+    assert_eq!(schedule.enabled_hours, 9..=18);
+}
+
+#[test]
+fn parse_config_validates_notification_schedule() {
+    let cfg = parse_config(
+        r#"
+[notification_schedule]
+enabled_days = ["Mon", "Tue", "Wed", "Thu", "Fri"]
+enabled_hours = [9, 18]
+
+[[repositories]]
+name = "acme/api"
+"#,
+    )
+    .expect("config should parse");
+    let schedule = cfg.notification_schedule.expect("schedule should be set");
+    assert_eq!(schedule.enabled_hours, [9, 18]);
+
+    for (table, expected) in [
+        (
+            "enabled_days = [\"Someday\"]",
+            "notification_schedule.enabled_days",
+        ),
+        (
+            "enabled_hours = [9, 24]",
+            "notification_schedule.enabled_hours",
+        ),
+        (
+            "enabled_hours = [18, 9]",
+            "notification_schedule.enabled_hours",
+        ),
+    ] {
+        let err = parse_config(&format!(
+            "[notification_schedule]\n{table}\n\n[[repositories]]\nname = \"acme/api\"\n"
+        ))
+        .expect_err("invalid schedule should fail");
+        assert!(err.to_string().contains(expected), "{table}: {err}");
+    }
+}
+
+#[test]
+fn parse_config_validates_notifications_summary() {
+    let cfg = parse_config(
+        r#"
+[notifications.summary]
+schedule = "Mon 09:00"
+
+[[repositories]]
+name = "acme/api"
+"#,
+    )
+    .expect("config should parse");
+    let summary = cfg.notifications.summary.expect("summary should be set");
+    assert_eq!(summary.schedule, "Mon 09:00");
+    assert_eq!(summary.timezone, "local");
+
+    let err = parse_config(
+        r#"
+[notifications.summary]
+schedule = "Monday morning"
+
+[[repositories]]
+name = "acme/api"
+"#,
+    )
+    .expect_err("invalid schedule should fail");
+    assert!(err.to_string().contains("notifications.summary.schedule"));
+
+    let err = parse_config(
+        r#"
+[notifications.summary]
+schedule = "Mon 09:00"
+timezone = "JST"
+
+[[repositories]]
+name = "acme/api"
+"#,
+    )
+    .expect_err("invalid timezone should fail");
+    assert!(err.to_string().contains("notifications.summary.timezone"));
+}
+
+#[test]
+fn parse_config_reads_and_validates_review_reminders() {
+    let cfg = parse_config(
+        r#"
+[reminders]
+review_request_after_days = 2
+
+[[repositories]]
+name = "acme/api"
+"#,
+    )
+    .expect("config should parse");
+    assert_eq!(cfg.reminders.review_request_after_days, Some(2));
+
+    let err = parse_config(
+        r#"
+[reminders]
+review_request_after_days = 0
+
+[[repositories]]
+name = "acme/api"
+"#,
+    )
+    .expect_err("zero days should fail");
+    assert!(err
+        .to_string()
+        .contains("reminders.review_request_after_days"));
+}
+
+#[test]
+fn parse_config_reads_and_validates_gist_sync() {
+    let cfg = parse_config(
+        r#"
+[sync]
+backend = "gist"
+gist_id = "0123abcd"
+interval_minutes = 15
+
+[[repositories]]
+name = "acme/api"
+"#,
+    )
+    .expect("config should parse");
+    let sync = cfg.sync.expect("sync should be set");
+    assert_eq!(sync.backend, SyncBackend::Gist);
+    assert_eq!(sync.gist_id, "0123abcd");
+    assert_eq!(sync.interval_minutes, Some(15));
+
+    let err = parse_config(
+        r#"
+[sync]
+backend = "gist"
+gist_id = "https://gist.github.com/octocat/0123abcd"
+
+[[repositories]]
+name = "acme/api"
+"#,
+    )
+    .expect_err("a gist URL should fail");
+    assert!(err.to_string().contains("invalid sync.gist_id"));
+}
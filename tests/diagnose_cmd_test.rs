@@ -0,0 +1,146 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use predicates::{prelude::*, str::contains};
+use tempfile::tempdir;
+
+fn write_stub_gh(dir: &Path) -> PathBuf {
+    let path = dir.join("gh");
+    fs::write(
+        &path,
+        r#"#!/usr/bin/env bash
+set -euo pipefail
+if [[ "$1" == "--version" ]]; then
+  echo "gh version 2.62.0 (2024-11-14)"
+  echo "https://github.com/cli/cli/releases/tag/v2.62.0"
+  exit 0
+fi
+if [[ "$1" == "auth" && "$2" == "status" ]]; then
+  echo "  Logged in to github.com account octocat (keyring)"
+  exit 0
+fi
+if [[ "$1" == "api" ]]; then
+  endpoint="${@: -1}"
+  if [[ "$endpoint" == "repos/acme/secret-web/"* ]]; then
+    echo "HTTP 404: Not Found (https://api.github.com/repos/acme/secret-web/pulls)" >&2
+    exit 1
+  fi
+  if [[ "$endpoint" == *"/comments"* ]]; then
+    echo '[[]]'
+  else
+    echo '[]'
+  fi
+  exit 0
+fi
+echo "unexpected args: $@" >&2
+exit 1
+"#,
+    )
+    .unwrap();
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perm = fs::metadata(&path).unwrap().permissions();
+        perm.set_mode(0o755);
+        fs::set_permissions(&path, perm).unwrap();
+    }
+
+    path
+}
+
+fn write_config(dir: &Path) -> PathBuf {
+    let config_path = dir.join("config.toml");
+    fs::write(
+        &config_path,
+        format!(
+            "state_db_path = {:?}\n\n[[repositories]]\nname = \"acme/api\"\n\n[[repositories]]\nname = \"acme/secret-web\"\n",
+            dir.join("state.db").display().to_string()
+        ),
+    )
+    .unwrap();
+    config_path
+}
+
+#[test]
+fn diagnose_prints_a_redacted_markdown_report() {
+    let dir = tempdir().unwrap();
+    let config_path = write_config(dir.path());
+    let gh_path = write_stub_gh(dir.path());
+
+    cargo_bin_cmd!("gh-watch")
+        .arg("diagnose")
+        .arg("--config")
+        .arg(&config_path)
+        .env("GH_WATCH_GH_BIN", gh_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("```text\n"))
+        .stdout(contains(format!("gh-watch: {}", env!("CARGO_PKG_VERSION"))))
+        .stdout(contains("/gh (gh version 2.62.0 (2024-11-14))\n"))
+        .stdout(contains("config status: ok (2 repositories)"))
+        .stdout(contains("state db schema: "))
+        .stdout(contains("notification backend: "))
+        .stdout(contains("recent failures: none"))
+        .stdout(contains("dry-run poll: 1 fetched, 1 failed"))
+        .stdout(contains(
+            "  - owner/REPO_2: failed to fetch pulls for owner/REPO_2",
+        ))
+        .stdout(contains("acme/").not());
+}
+
+#[test]
+fn diagnose_writes_unredacted_output_file() {
+    let dir = tempdir().unwrap();
+    let config_path = write_config(dir.path());
+    let gh_path = write_stub_gh(dir.path());
+    let output = dir.path().join("diagnosis.md");
+
+    cargo_bin_cmd!("gh-watch")
+        .arg("diagnose")
+        .arg("--config")
+        .arg(&config_path)
+        .arg("--output")
+        .arg(&output)
+        .arg("--no-redact")
+        .env("GH_WATCH_GH_BIN", gh_path)
+        .assert()
+        .success()
+        .stdout(contains("wrote diagnosis to"));
+
+    let report = fs::read_to_string(&output).unwrap();
+    assert!(report.contains("  - acme/secret-web: "), "{report}");
+    assert!(!report.contains("REPO_"), "{report}");
+}
+
+#[test]
+fn diagnose_reports_config_errors_without_their_source_lines() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    fs::write(&config_path, "token = \"ghp_secret\" oops\n").unwrap();
+    let gh_path = write_stub_gh(dir.path());
+
+    cargo_bin_cmd!("gh-watch")
+        .arg("diagnose")
+        .arg("--config")
+        .arg(&config_path)
+        .env("GH_WATCH_GH_BIN", gh_path)
+        .assert()
+        .success()
+        .stdout(contains(
+            "config status: error: failed to parse config TOML",
+        ))
+        .stdout(contains("ghp_secret").not());
+}
+
+#[test]
+fn version_flag_prints_the_package_version() {
+    cargo_bin_cmd!("gh-watch")
+        .arg("--version")
+        .assert()
+        .success()
+        .stdout(format!("gh-watch {}\n", env!("CARGO_PKG_VERSION")));
+}
@@ -1,8 +1,12 @@
 use std::collections::HashSet;
+use std::str::FromStr;
 
 use chrono::{TimeZone, Utc};
 use gh_watch::domain::decision::{decide_notification, sort_timeline_desc, NotificationDecision};
-use gh_watch::domain::events::{EventKind, WatchEvent};
+use gh_watch::domain::events::{
+    event_matches_milestone_filter, event_matches_notification_filters,
+    event_matches_review_state_filter, EventKind, ParseEventKindError, ReviewState, WatchEvent,
+};
 
 fn sample_event(id: &str, created_at: chrono::DateTime<Utc>) -> WatchEvent {
     WatchEvent {
@@ -17,6 +21,11 @@ fn sample_event(id: &str, created_at: chrono::DateTime<Utc>) -> WatchEvent {
         subject_author: Some("alice".to_string()),
         requested_reviewer: None,
         mentions: Vec::new(),
+        team_mentions: Vec::new(),
+        subject_number: None,
+        subject_url: None,
+        account: None,
+        body: None,
     }
 }
 
@@ -59,3 +68,360 @@ fn timeline_sort_is_descending() {
     assert_eq!(sorted[0].event_id, newer.event_id);
     assert_eq!(sorted[1].event_id, older.event_id);
 }
+
+#[test]
+fn milestone_filter_only_applies_to_milestone_events() {
+    let created_at = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let milestone = WatchEvent {
+        kind: EventKind::PrMilestoneAdded,
+        source_item_id: "1:v1.0".to_string(),
+        ..sample_event("m", created_at)
+    };
+    let other = sample_event("o", created_at);
+    let wanted = vec!["v2.0".to_string()];
+
+    assert!(event_matches_milestone_filter(&milestone, &[]));
+    assert!(!event_matches_milestone_filter(&milestone, &wanted));
+    assert!(event_matches_milestone_filter(
+        &milestone,
+        &["v1.0".to_string()]
+    ));
+    assert!(event_matches_milestone_filter(&other, &wanted));
+}
+
+#[test]
+fn review_state_filter_only_applies_to_review_events() {
+    let created_at = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let review = |kind| WatchEvent {
+        kind,
+        ..sample_event("r", created_at)
+    };
+    let wanted = [ReviewState::Approved, ReviewState::ChangesRequested];
+
+    assert!(event_matches_review_state_filter(
+        &review(EventKind::PrReviewDismissed),
+        &[]
+    ));
+    assert!(event_matches_review_state_filter(
+        &review(EventKind::PrReviewApproved),
+        &wanted
+    ));
+    assert!(event_matches_review_state_filter(
+        &review(EventKind::PrReviewChangesRequested),
+        &wanted
+    ));
+    assert!(!event_matches_review_state_filter(
+        &review(EventKind::PrReviewSubmitted),
+        &wanted
+    ));
+    assert!(!event_matches_review_state_filter(
+        &review(EventKind::PrReviewDismissed),
+        &wanted
+    ));
+    assert!(event_matches_review_state_filter(
+        &sample_event("o", created_at),
+        &wanted
+    ));
+}
+
+#[test]
+fn only_involving_me_matches_team_members_as_actor_author_or_reviewer() {
+    let created_at = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let team = vec!["Bob".to_string()];
+    let by_actor = WatchEvent {
+        actor: "bob".to_string(),
+        ..sample_event("a", created_at)
+    };
+    let by_author = WatchEvent {
+        kind: EventKind::IssueCommentCreated,
+        actor: "carol".to_string(),
+        subject_author: Some("bob".to_string()),
+        ..sample_event("b", created_at)
+    };
+    let by_reviewer = WatchEvent {
+        kind: EventKind::PrReviewRequested,
+        requested_reviewer: Some("bob".to_string()),
+        ..sample_event("c", created_at)
+    };
+    let unrelated = sample_event("d", created_at);
+
+    for event in [&by_actor, &by_author, &by_reviewer] {
+        assert!(event_matches_notification_filters(
+            event,
+            &[],
+            &[],
+            true,
+            false,
+            None,
+            &[],
+            &team
+        ));
+    }
+    assert!(!event_matches_notification_filters(
+        &unrelated,
+        &[],
+        &[],
+        true,
+        false,
+        Some("dave"),
+        &[],
+        &team
+    ));
+    assert!(event_matches_notification_filters(
+        &unrelated,
+        &[],
+        &[],
+        false,
+        false,
+        None,
+        &[],
+        &team
+    ));
+}
+
+#[test]
+fn ignore_self_drops_events_the_viewer_caused() {
+    let created_at = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let own_comment = WatchEvent {
+        kind: EventKind::IssueCommentCreated,
+        actor: "Alice".to_string(),
+        ..sample_event("a", created_at)
+    };
+    let others_comment = WatchEvent {
+        kind: EventKind::IssueCommentCreated,
+        actor: "bob".to_string(),
+        ..sample_event("b", created_at)
+    };
+
+    assert!(!event_matches_notification_filters(
+        &own_comment,
+        &[],
+        &[],
+        false,
+        true,
+        Some("alice"),
+        &[],
+        &[]
+    ));
+    assert!(event_matches_notification_filters(
+        &others_comment,
+        &[],
+        &[],
+        false,
+        true,
+        Some("alice"),
+        &[],
+        &[]
+    ));
+    assert!(event_matches_notification_filters(
+        &own_comment,
+        &[],
+        &[],
+        false,
+        false,
+        Some("alice"),
+        &[],
+        &[]
+    ));
+}
+
+#[test]
+fn ignore_self_with_only_involving_me_keeps_what_others_do_on_my_work() {
+    let created_at = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let my_update = WatchEvent {
+        kind: EventKind::IssueCommentCreated,
+        actor: "alice".to_string(),
+        subject_author: Some("alice".to_string()),
+        ..sample_event("a", created_at)
+    };
+    let review_on_my_pr = WatchEvent {
+        kind: EventKind::PrReviewApproved,
+        actor: "bob".to_string(),
+        subject_author: Some("alice".to_string()),
+        ..sample_event("b", created_at)
+    };
+    let unrelated = WatchEvent {
+        kind: EventKind::IssueCommentCreated,
+        actor: "bob".to_string(),
+        subject_author: Some("carol".to_string()),
+        ..sample_event("c", created_at)
+    };
+
+    let matches = |event: &WatchEvent| {
+        event_matches_notification_filters(event, &[], &[], true, true, Some("alice"), &[], &[])
+    };
+    assert!(!matches(&my_update));
+    assert!(matches(&review_on_my_pr));
+    assert!(!matches(&unrelated));
+}
+
+#[test]
+fn actor_and_mention_filters_ignore_login_case() {
+    let created_at = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let event = WatchEvent {
+        kind: EventKind::IssueCommentCreated,
+        actor: "Dependabot".to_string(),
+        mentions: vec!["alice".to_string()],
+        ..sample_event("a", created_at)
+    };
+
+    assert!(!event_matches_notification_filters(
+        &event,
+        &[],
+        &["dependabot".to_string()],
+        false,
+        false,
+        None,
+        &[],
+        &[]
+    ));
+    assert!(event_matches_notification_filters(
+        &event,
+        &[],
+        &[],
+        true,
+        false,
+        Some("Alice"),
+        &[],
+        &[]
+    ));
+}
+
+#[test]
+fn only_involving_me_matches_a_mention_of_the_viewer_in_another_case() {
+    let created_at = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let event = WatchEvent {
+        kind: EventKind::IssueCommentCreated,
+        actor: "carol".to_string(),
+        subject_author: Some("bob".to_string()),
+        mentions: vec!["alice".to_string()],
+        ..sample_event("a", created_at)
+    };
+
+    assert!(event_matches_notification_filters(
+        &event,
+        &[],
+        &[],
+        true,
+        false,
+        Some("Alice"),
+        &[],
+        &[]
+    ));
+    assert!(!event_matches_notification_filters(
+        &WatchEvent {
+            mentions: Vec::new(),
+            ..event.clone()
+        },
+        &[],
+        &[],
+        true,
+        false,
+        Some("Alice"),
+        &[],
+        &[]
+    ));
+}
+
+#[test]
+fn only_involving_me_counts_mentions_of_the_viewers_teams() {
+    let created_at = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let event = WatchEvent {
+        kind: EventKind::IssueCommentCreated,
+        subject_author: Some("bob".to_string()),
+        team_mentions: vec!["acme/platform-team".to_string()],
+        ..sample_event("a", created_at)
+    };
+    let matches = |viewer_teams: &[String]| {
+        event_matches_notification_filters(
+            &event,
+            &[],
+            &[],
+            true,
+            false,
+            Some("alice"),
+            viewer_teams,
+            &[],
+        )
+    };
+
+    assert!(matches(&["Acme/Platform-Team".to_string()]));
+    assert!(!matches(&["acme/design".to_string()]));
+    assert!(!matches(&[]));
+}
+
+#[test]
+fn ignore_self_without_a_viewer_login_drops_nothing() {
+    let created_at = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let event = sample_event("a", created_at);
+
+    assert!(event_matches_notification_filters(
+        &event,
+        &[],
+        &[],
+        false,
+        true,
+        None,
+        &[],
+        &[]
+    ));
+}
+
+#[test]
+fn every_event_kind_round_trips_through_its_name() {
+    for kind in EventKind::ALL {
+        assert_eq!(EventKind::from_str(&kind.to_string()), Ok(kind.clone()));
+        // Config files and the state db use the serde name, which must agree.
+        assert_eq!(serde_json::to_string(&kind).unwrap(), format!("\"{kind}\""));
+    }
+    let names = EventKind::ALL
+        .iter()
+        .map(EventKind::as_str)
+        .collect::<HashSet<_>>();
+    assert_eq!(names.len(), EventKind::ALL.len());
+}
+
+#[test]
+fn event_kind_all_lists_every_variant_once() {
+    // No wildcard arm: a new variant stops this compiling until it gets the next position
+    // here, in VARIANT_COUNT and in EventKind::ALL.
+    fn position(kind: &EventKind) -> usize {
+        match kind {
+            EventKind::PrCreated => 0,
+            EventKind::IssueCreated => 1,
+            EventKind::IssueCommentCreated => 2,
+            EventKind::PrReviewCommentCreated => 3,
+            EventKind::PrReviewRequested => 4,
+            EventKind::PrReviewSubmitted => 5,
+            EventKind::PrReviewApproved => 6,
+            EventKind::PrReviewChangesRequested => 7,
+            EventKind::PrReviewDismissed => 8,
+            EventKind::PrMerged => 9,
+            EventKind::PrMilestoneAdded => 10,
+            EventKind::IssueMilestoneAdded => 11,
+            EventKind::ReviewThreadResolved => 12,
+            EventKind::MyPrApproved => 13,
+            EventKind::MyPrChecksPassed => 14,
+        }
+    }
+    const VARIANT_COUNT: usize = 15;
+
+    assert_eq!(EventKind::ALL.len(), VARIANT_COUNT);
+    let positions = EventKind::ALL.iter().map(position).collect::<Vec<_>>();
+    assert_eq!(positions, (0..VARIANT_COUNT).collect::<Vec<_>>());
+}
+
+#[test]
+fn unknown_event_kind_lists_the_valid_names() {
+    let err = "pr_opened".parse::<EventKind>().unwrap_err();
+
+    assert_eq!(
+        err,
+        ParseEventKindError {
+            value: "pr_opened".to_string()
+        }
+    );
+    let message = err.to_string();
+    assert!(message.starts_with("unknown event kind `pr_opened` (expected one of: pr_created, "));
+    assert!(message.ends_with("my_pr_checks_passed)"));
+}
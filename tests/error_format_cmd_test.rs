@@ -0,0 +1,190 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use serde_json::Value;
+use tempfile::tempdir;
+
+const AUTH_OK: &str = r#"if [[ "$1" == "auth" && "$2" == "status" ]]; then
+  echo "  Logged in to github.com account octocat (keyring)"
+  exit 0
+fi
+"#;
+
+fn write_stub_gh(dir: &Path, body: &str) -> PathBuf {
+    let path = dir.join("gh");
+    fs::write(
+        &path,
+        format!(
+            "#!/usr/bin/env bash\nset -euo pipefail\n{body}echo \"unexpected args: $@\" >&2\nexit 1\n"
+        ),
+    )
+    .unwrap();
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perm = fs::metadata(&path).unwrap().permissions();
+        perm.set_mode(0o755);
+        fs::set_permissions(&path, perm).unwrap();
+    }
+
+    path
+}
+
+fn write_config(dir: &Path, state_db_path: &Path, repositories: &[&str]) -> PathBuf {
+    let config_path = dir.join("config.toml");
+    let mut src = format!(
+        "state_db_path = {:?}\n",
+        state_db_path.display().to_string()
+    );
+    for repo in repositories {
+        src.push_str(&format!("\n[[repositories]]\nname = \"{repo}\"\n"));
+    }
+    fs::write(&config_path, src).unwrap();
+    config_path
+}
+
+/// Runs `gh-watch --error-format json <args>` and returns its exit code and stderr envelope.
+fn run_json(args: &[&str], config_path: &Path, gh_path: &Path) -> (Option<i32>, Value) {
+    let output = cargo_bin_cmd!("gh-watch")
+        .args(["--error-format", "json"])
+        .args(args)
+        .arg("--config")
+        .arg(config_path)
+        .env("GH_WATCH_GH_BIN", gh_path)
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let envelope = stderr
+        .lines()
+        .last()
+        .and_then(|line| serde_json::from_str(line).ok())
+        .unwrap_or_else(|| panic!("no JSON envelope in stderr: {stderr}"));
+    (output.status.code(), envelope)
+}
+
+#[test]
+fn partial_repo_failures_exit_with_2() {
+    let dir = tempdir().unwrap();
+    let config_path = write_config(
+        dir.path(),
+        &dir.path().join("state.db"),
+        &["acme/api", "acme/web"],
+    );
+    let gh_path = write_stub_gh(
+        dir.path(),
+        &format!(
+            r#"{AUTH_OK}if [[ "$1" == "api" ]]; then
+  endpoint="${{@: -1}}"
+  if [[ "$endpoint" == "repos/acme/web/"* ]]; then
+    echo "boom" >&2
+    exit 1
+  fi
+  if [[ "$endpoint" == *"/comments"* ]]; then
+    echo '[[]]'
+  else
+    echo '[]'
+  fi
+  exit 0
+fi
+"#
+        ),
+    );
+
+    let (code, envelope) = run_json(&["once", "--json"], &config_path, &gh_path);
+
+    assert_eq!(code, Some(2));
+    assert_eq!(envelope["code"], "partial_failure");
+    assert_eq!(envelope["message"], "1 of 2 repository fetches failed");
+    assert!(envelope["hint"].is_null());
+}
+
+#[test]
+fn auth_failures_exit_with_3() {
+    let dir = tempdir().unwrap();
+    let config_path = write_config(dir.path(), &dir.path().join("state.db"), &["acme/api"]);
+    let gh_path = write_stub_gh(
+        dir.path(),
+        r#"if [[ "$1" == "auth" && "$2" == "status" ]]; then
+  echo "token invalid" >&2
+  exit 1
+fi
+"#,
+    );
+
+    let (code, envelope) = run_json(&["check"], &config_path, &gh_path);
+
+    assert_eq!(code, Some(3));
+    assert_eq!(envelope["code"], "auth");
+    assert!(envelope["message"]
+        .as_str()
+        .unwrap()
+        .starts_with("GitHub authentication is invalid"));
+    assert_eq!(
+        envelope["hint"],
+        "Run `gh auth login -h github.com` or set GH_TOKEN."
+    );
+}
+
+#[test]
+fn config_errors_exit_with_4() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    fs::write(&config_path, "interval_seconds = \"soon\"\n").unwrap();
+    let gh_path = write_stub_gh(dir.path(), AUTH_OK);
+
+    let (code, envelope) = run_json(&["once"], &config_path, &gh_path);
+
+    assert_eq!(code, Some(4));
+    assert_eq!(envelope["code"], "config");
+    assert!(envelope["message"]
+        .as_str()
+        .unwrap()
+        .contains("failed to parse config TOML"));
+    assert!(envelope["hint"]
+        .as_str()
+        .unwrap()
+        .contains("gh-watch config doctor"));
+}
+
+#[test]
+fn state_db_errors_exit_with_5() {
+    let dir = tempdir().unwrap();
+    // A directory cannot be opened as a database.
+    let state_db_path = dir.path().join("state.db");
+    fs::create_dir(&state_db_path).unwrap();
+    let config_path = write_config(dir.path(), &state_db_path, &["acme/api"]);
+    let gh_path = write_stub_gh(dir.path(), AUTH_OK);
+
+    let (code, envelope) = run_json(&["check"], &config_path, &gh_path);
+
+    assert_eq!(code, Some(5));
+    assert_eq!(envelope["code"], "state_db");
+    assert!(envelope["message"]
+        .as_str()
+        .unwrap()
+        .starts_with("failed to open state db"));
+}
+
+#[test]
+fn text_errors_keep_the_chain_and_add_the_hint() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    fs::write(&config_path, "interval_seconds = \"soon\"\n").unwrap();
+
+    cargo_bin_cmd!("gh-watch")
+        .arg("check")
+        .arg("--config")
+        .arg(&config_path)
+        .assert()
+        .code(4)
+        .stderr(predicates::str::contains(
+            "failed to load config: failed to parse config TOML",
+        ))
+        .stderr(predicates::str::contains(
+            "hint: Run `gh-watch config doctor`",
+        ));
+}
@@ -0,0 +1,125 @@
+use std::time::Duration;
+
+use chrono::{TimeZone, Utc};
+use futures_util::StreamExt;
+use gh_watch::domain::events::{EventKind, WatchEvent};
+use gh_watch::infra::state_sqlite::SqliteStateStore;
+use gh_watch::ports::{EventStreamPort, RepoBatchPort, RepoPersistBatch, WatchEventStreamConfig};
+use rusqlite::{params, Connection};
+use tempfile::tempdir;
+
+const STREAM_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn sample_event(id: &str) -> WatchEvent {
+    WatchEvent {
+        event_id: id.to_string(),
+        repo: "acme/api".to_string(),
+        kind: EventKind::IssueCreated,
+        actor: "bob".to_string(),
+        title: format!("Issue {id}"),
+        url: format!("https://example.com/issues/{id}"),
+        created_at: Utc.with_ymd_and_hms(2025, 1, 1, 10, 0, 0).unwrap(),
+        source_item_id: id.to_string(),
+        subject_author: Some("bob".to_string()),
+        requested_reviewer: None,
+        mentions: Vec::new(),
+        team_mentions: Vec::new(),
+        subject_number: None,
+        subject_url: None,
+        account: None,
+        body: None,
+    }
+}
+
+fn persist(store: &SqliteStateStore, ids: &[String]) {
+    store
+        .persist_repo_batch(&RepoPersistBatch {
+            repo: "acme/api".to_string(),
+            poll_started_at: Utc::now(),
+            pushed: false,
+            events: ids.iter().map(|id| sample_event(id)).collect(),
+            review_requests: Vec::new(),
+            review_threads: Vec::new(),
+            my_prs: Vec::new(),
+        })
+        .unwrap();
+}
+
+fn fast_stream(store: SqliteStateStore) -> SqliteStateStore {
+    store.with_event_stream_config(WatchEventStreamConfig {
+        poll_interval_ms: 10,
+    })
+}
+
+#[tokio::test]
+async fn stream_follows_inserts_from_another_connection_in_order() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("state.db");
+    let reader = fast_stream(SqliteStateStore::new(&db).unwrap());
+    let writer = SqliteStateStore::new(&db).unwrap();
+
+    let expected = (0..40).map(|n| format!("ev-{n:02}")).collect::<Vec<_>>();
+    let batches = expected.clone();
+    let writing = std::thread::spawn(move || {
+        for batch in batches.chunks(4) {
+            persist(&writer, batch);
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    });
+
+    let since = Utc::now() - chrono::Duration::seconds(1);
+    let received = tokio::time::timeout(
+        STREAM_TIMEOUT,
+        reader
+            .watch_event_stream(since)
+            .take(expected.len())
+            .map(|event| event.unwrap().event_id)
+            .collect::<Vec<_>>(),
+    )
+    .await
+    .expect("stream stalled");
+    writing.join().unwrap();
+
+    assert_eq!(received, expected);
+}
+
+#[tokio::test]
+async fn stream_replays_events_stored_since_then_waits_for_new_ones() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("state.db");
+    let store = fast_stream(SqliteStateStore::new(&db).unwrap());
+    persist(&store, &["old".to_string(), "recent".to_string()]);
+    let since = Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap();
+    Connection::open(&db)
+        .unwrap()
+        .execute(
+            "UPDATE event_log_v2 SET observed_at = ?1 WHERE event_key LIKE '%old%'",
+            params![Utc
+                .with_ymd_and_hms(2025, 5, 1, 0, 0, 0)
+                .unwrap()
+                .to_rfc3339()],
+        )
+        .unwrap();
+
+    let mut events = Box::pin(store.watch_event_stream(since));
+    let first = tokio::time::timeout(STREAM_TIMEOUT, events.next())
+        .await
+        .expect("backlog not replayed")
+        .unwrap()
+        .unwrap();
+    assert_eq!(first.event_id, "recent");
+    assert!(
+        tokio::time::timeout(Duration::from_millis(100), events.next())
+            .await
+            .is_err(),
+        "stream should wait for new events"
+    );
+
+    persist(&store, &["new".to_string()]);
+    let next = tokio::time::timeout(STREAM_TIMEOUT, events.next())
+        .await
+        .expect("new event not streamed")
+        .unwrap()
+        .unwrap();
+    assert_eq!(next.event_id, "new");
+}
@@ -0,0 +1,151 @@
+use std::{fs, path::Path};
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use chrono::{Duration, SecondsFormat, Utc};
+use gh_watch::infra::state_sqlite::SqliteStateStore;
+use gh_watch::ports::CursorPort;
+use predicates::str::contains;
+use tempfile::tempdir;
+
+fn write_config(dir: &std::path::Path) -> std::path::PathBuf {
+    let config_path = dir.join("config.toml");
+    let state_db_path = dir.join("state.db");
+    fs::write(
+        &config_path,
+        format!(
+            "state_db_path = \"{}\"\n\n[filters]\nonly_involving_me = false\n\n[[repositories]]\nname = \"acme/api\"\n",
+            state_db_path.display()
+        ),
+    )
+    .unwrap();
+    config_path
+}
+
+#[test]
+fn filter_profile_create_switch_and_list() {
+    let dir = tempdir().unwrap();
+    let config_path = write_config(dir.path());
+
+    cargo_bin_cmd!("gh-watch")
+        .args(["filter-profile", "create", "work", "--config"])
+        .arg(&config_path)
+        .assert()
+        .success()
+        .stdout(contains("created filter profile: work"));
+    let content = fs::read_to_string(&config_path).unwrap();
+    assert!(content.contains("[filter_profiles.work]"));
+
+    cargo_bin_cmd!("gh-watch")
+        .args(["filter-profile", "list", "--config"])
+        .arg(&config_path)
+        .assert()
+        .success()
+        .stdout(contains("* default"))
+        .stdout(contains("  work"));
+
+    cargo_bin_cmd!("gh-watch")
+        .args(["filter-profile", "switch", "work", "--config"])
+        .arg(&config_path)
+        .assert()
+        .success()
+        .stdout(contains("active filter profile: work"));
+    assert_eq!(
+        fs::read_to_string(dir.path().join("active_filter_profile")).unwrap(),
+        "work\n"
+    );
+
+    cargo_bin_cmd!("gh-watch")
+        .args(["filter-profile", "list", "--config"])
+        .arg(&config_path)
+        .assert()
+        .success()
+        .stdout(contains("  default"))
+        .stdout(contains("* work"));
+}
+
+#[test]
+fn filter_profile_switch_rejects_unknown_profile() {
+    let dir = tempdir().unwrap();
+    let config_path = write_config(dir.path());
+
+    cargo_bin_cmd!("gh-watch")
+        .args(["filter-profile", "switch", "missing", "--config"])
+        .arg(&config_path)
+        .assert()
+        .failure()
+        .stderr(contains("unknown filter profile: missing"));
+    assert!(!dir.path().join("active_filter_profile").exists());
+}
+
+fn write_stub_gh(dir: &Path, pr_created_at: &str) -> std::path::PathBuf {
+    let path = dir.join("gh");
+    let script = r#"#!/usr/bin/env bash
+set -euo pipefail
+if [[ "$1" == "auth" && "$2" == "status" ]]; then
+  echo "  Logged in to github.com account octocat (keyring)"
+  exit 0
+fi
+endpoint="${@: -1}"
+case "$endpoint" in
+  repos/acme/api/pulls/comments*|repos/acme/api/issues/comments*|repos/acme/api/pulls/1/reviews*) echo '[[]]' ;;
+  repos/acme/api/pulls*page=1)
+    echo '[{"id":101,"number":1,"title":"Add API","html_url":"https://example.com/pr/1","created_at":"__AT__","updated_at":"__AT__","user":{"login":"dependabot"},"requested_reviewers":[]}]' ;;
+  repos/acme/api/pulls*|repos/acme/api/issues*) echo '[]' ;;
+  *) echo "unexpected args: $*" >&2; exit 1 ;;
+esac
+"#
+    .replace("__AT__", pr_created_at);
+    fs::write(&path, script).unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    path
+}
+
+/// `once --json` output after a PR by `dependabot`, which only the `quiet` profile ignores.
+fn once_with_active_profile(profile: Option<&str>) -> String {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    let state_db_path = dir.path().join("state.db");
+    fs::write(
+        &config_path,
+        format!(
+            "state_db_path = \"{}\"\n\n[notifications]\nenabled = false\n\n[filters]\nonly_involving_me = false\n\n[filter_profiles.quiet]\nonly_involving_me = false\nignore_actors = [\"dependabot\"]\n\n[[repositories]]\nname = \"acme/api\"\n",
+            state_db_path.display()
+        ),
+    )
+    .unwrap();
+    let now = Utc::now();
+    SqliteStateStore::new(&state_db_path)
+        .unwrap()
+        .set_cursor("acme/api", now - Duration::hours(2))
+        .unwrap();
+    let created_at = (now - Duration::hours(1)).to_rfc3339_opts(SecondsFormat::Secs, true);
+    let gh_path = write_stub_gh(dir.path(), &created_at);
+    if let Some(profile) = profile {
+        cargo_bin_cmd!("gh-watch")
+            .args(["filter-profile", "switch", profile, "--config"])
+            .arg(&config_path)
+            .assert()
+            .success();
+    }
+
+    let output = cargo_bin_cmd!("gh-watch")
+        .args(["once", "--json", "--config"])
+        .arg(&config_path)
+        .env("GH_WATCH_GH_BIN", gh_path)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn once_applies_the_switched_filter_profile() {
+    assert!(once_with_active_profile(None).contains("\"title\":\"Add API\""));
+    assert!(!once_with_active_profile(Some("quiet")).contains("\"title\":\"Add API\""));
+}
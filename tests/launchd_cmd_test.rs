@@ -0,0 +1,81 @@
+use std::fs;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use predicates::str::contains;
+use tempfile::tempdir;
+
+const CONFIG: &str = r#"
+[[repositories]]
+name = "acme/api"
+"#;
+
+#[test]
+fn launchd_writes_plist_and_prints_launchctl_commands() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    fs::write(&config_path, CONFIG).unwrap();
+    let output = dir.path().join("agent.plist");
+    let log_dir = dir.path().join("logs");
+
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.arg("launchd")
+        .arg("--config")
+        .arg(&config_path)
+        .arg("--output")
+        .arg(&output)
+        .arg("--log-dir")
+        .arg(&log_dir)
+        .assert()
+        .success()
+        .stdout(contains("wrote: "))
+        .stdout(contains("Library/LaunchAgents/com.gh-watch.agent.plist"))
+        .stdout(contains("launchctl load -w"))
+        .stdout(contains("launchctl start com.gh-watch.agent"));
+
+    let plist = fs::read_to_string(&output).unwrap();
+    assert!(plist.contains("<string>com.gh-watch.agent</string>"));
+    assert!(plist.contains("<string>--no-tui</string>"));
+    assert!(plist.contains(&format!("<string>{}</string>", config_path.display())));
+    assert!(plist.contains(&format!(
+        "<string>{}</string>",
+        log_dir.join("gh-watch.err.log").display()
+    )));
+}
+
+#[test]
+fn launchd_prints_plist_to_stdout_by_default() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    fs::write(&config_path, CONFIG).unwrap();
+
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.arg("launchd")
+        .arg("--config")
+        .arg(&config_path)
+        .assert()
+        .success()
+        .stdout(contains("<plist version=\"1.0\">"))
+        .stdout(contains("<key>RunAtLoad</key>"));
+}
+
+#[test]
+fn launchd_rejects_invalid_config_and_start_without_install() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    fs::write(&config_path, "interval_seconds = 0\n").unwrap();
+
+    let mut invalid = cargo_bin_cmd!("gh-watch");
+    invalid
+        .arg("launchd")
+        .arg("--config")
+        .arg(&config_path)
+        .assert()
+        .failure();
+
+    let mut start = cargo_bin_cmd!("gh-watch");
+    start
+        .args(["launchd", "--start"])
+        .assert()
+        .failure()
+        .stderr(contains("--install"));
+}
@@ -74,7 +74,12 @@ fn notification_test_runs_notify_once_with_include_url_enabled() {
     );
     match &calls[0].0 {
         NotificationPayload::Event(event) => assert_eq!(outcome.event_key, event.event_key()),
-        NotificationPayload::Digest(_) => panic!("notification test should send an event payload"),
+        NotificationPayload::Digest(_)
+        | NotificationPayload::Alert(_)
+        | NotificationPayload::Summary(_)
+        | NotificationPayload::ReviewReminderDue(_) => {
+            panic!("notification test should send an event payload")
+        }
     }
 }
 
@@ -16,9 +16,42 @@ fn notification_body_contains_url_when_enabled() {
         subject_author: Some("alice".to_string()),
         requested_reviewer: None,
         mentions: Vec::new(),
+        team_mentions: Vec::new(),
+        subject_number: None,
+        subject_url: None,
+        account: None,
+        body: None,
     };
 
-    let body = build_notification_body(&event, true);
+    let body = build_notification_body(&event, true, 200);
     assert!(body.contains("Add API"));
     assert!(body.contains("https://example.com/pr/1"));
 }
+
+#[test]
+fn comment_notification_links_to_comment_anchor_not_subject() {
+    let event = WatchEvent {
+        event_id: "issue-comment:32".to_string(),
+        repo: "acme/api".to_string(),
+        kind: EventKind::IssueCommentCreated,
+        actor: "erin".to_string(),
+        title: "on the pull".to_string(),
+        url: "https://github.com/acme/api/pull/10#issuecomment-32".to_string(),
+        created_at: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+        source_item_id: "32".to_string(),
+        subject_author: Some("bob".to_string()),
+        requested_reviewer: None,
+        mentions: Vec::new(),
+        team_mentions: Vec::new(),
+        subject_number: Some(10),
+        subject_url: Some("https://github.com/acme/api/pull/10".to_string()),
+        account: None,
+        body: None,
+    };
+
+    let body = build_notification_body(&event, true, 200);
+    assert_eq!(
+        body.lines().last(),
+        Some("https://github.com/acme/api/pull/10#issuecomment-32")
+    );
+}
@@ -1,14 +1,16 @@
-use std::{fs, path::Path, path::PathBuf};
+use std::{fs, io::Read, path::Path, path::PathBuf};
 
 use assert_cmd::cargo::cargo_bin_cmd;
 use chrono::{Duration, Utc};
+use flate2::read::MultiGzDecoder;
+use gh_watch::domain::events::{EventKind, WatchEvent};
 use gh_watch::infra::state_sqlite::SqliteStateStore;
-use gh_watch::ports::CursorPort;
+use gh_watch::ports::{CursorPort, RepoBatchPort, RepoPersistBatch};
 use predicates::prelude::*;
 use tempfile::tempdir;
 
 #[test]
-fn once_json_returns_success_exit_code_0_when_some_repos_fail() {
+fn once_json_exits_with_2_when_some_repos_fail() {
     let dir = tempdir().unwrap();
     let config_path = dir.path().join("config.toml");
     let state_db_path = dir.path().join("state.db");
@@ -19,6 +21,7 @@ fn once_json_returns_success_exit_code_0_when_some_repos_fail() {
         r#"#!/usr/bin/env bash
 set -euo pipefail
 if [[ "$1" == "auth" && "$2" == "status" ]]; then
+  echo "  Logged in to github.com account octocat (keyring)"
   exit 0
 fi
 if [[ "$1" == "api" ]]; then
@@ -68,8 +71,7 @@ exit 1
         .arg("--json")
         .env("GH_WATCH_GH_BIN", gh_path)
         .assert()
-        .success()
-        .code(0)
+        .code(2)
         .stdout(predicate::str::contains("\"fetch_failures\""))
         .stdout(predicate::str::contains("\"repo\":\"acme/web\""));
 }
@@ -86,6 +88,7 @@ fn once_text_reports_repo_fetch_failures_when_partial_failures_happen() {
         r#"#!/usr/bin/env bash
 set -euo pipefail
 if [[ "$1" == "auth" && "$2" == "status" ]]; then
+  echo "  Logged in to github.com account octocat (keyring)"
   exit 0
 fi
 if [[ "$1" == "api" ]]; then
@@ -122,6 +125,10 @@ if [[ "$1" == "api" ]]; then
     echo '[[]]'
     exit 0
   fi
+  if [[ "$2" == "rate_limit" ]]; then
+    echo '4200'
+    exit 0
+  fi
 fi
 echo "unexpected args: $@" >&2
 exit 1
@@ -134,9 +141,11 @@ exit 1
         .arg(&config_path)
         .env("GH_WATCH_GH_BIN", gh_path)
         .assert()
-        .success()
+        .code(2)
+        .stderr(predicate::str::contains("1 of 2 repository fetches failed"))
         .stdout(predicate::str::contains("repo_fetch_failures: 1"))
-        .stdout(predicate::str::contains("- acme/web:"));
+        .stdout(predicate::str::contains("- acme/web:"))
+        .stdout(predicate::str::contains("calls/cycle, 4200 remaining"));
 }
 
 #[test]
@@ -151,6 +160,7 @@ fn once_returns_failure_exit_code_1_when_all_repos_fail() {
         r#"#!/usr/bin/env bash
 set -euo pipefail
 if [[ "$1" == "auth" && "$2" == "status" ]]; then
+  echo "  Logged in to github.com account octocat (keyring)"
   exit 0
 fi
 if [[ "$1" == "api" ]]; then
@@ -205,6 +215,102 @@ exit 1
         .stderr(predicate::str::contains("all repository fetches failed"));
 }
 
+const EMPTY_REPO_STUB: &str = r#"#!/usr/bin/env bash
+set -euo pipefail
+if [[ "$1" == "auth" && "$2" == "status" ]]; then
+  echo "  Logged in to github.com account octocat (keyring)"
+  exit 0
+fi
+if [[ "$1" == "api" ]]; then
+  if [[ "$*" == *"--slurp"* ]]; then
+    echo '[[]]'
+  else
+    echo '[]'
+  fi
+  exit 0
+fi
+echo "unexpected args: $@" >&2
+exit 1
+"#;
+
+#[test]
+fn once_output_file_replaces_json_result_atomically_and_keeps_text_summary() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    let state_db_path = dir.path().join("state.db");
+    let output_path = dir.path().join("poll.json");
+    write_config(&config_path, &state_db_path, &["acme/api"]);
+    fs::write(
+        &output_path,
+        "stale partial content that is longer than before",
+    )
+    .unwrap();
+    let gh_path = write_stub_gh(dir.path(), EMPTY_REPO_STUB);
+
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.arg("once")
+        .arg("--config")
+        .arg(&config_path)
+        .arg("--output-file")
+        .arg(&output_path)
+        .env("GH_WATCH_GH_BIN", gh_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("notified: 0"));
+
+    let written = fs::read_to_string(&output_path).unwrap();
+    assert_eq!(written.lines().count(), 1);
+    let outcome: serde_json::Value = serde_json::from_str(&written).unwrap();
+    assert_eq!(outcome["notified_count"], 0);
+    assert_eq!(
+        outcome["config_path"].as_str(),
+        Some(config_path.display().to_string().as_str())
+    );
+    let polled_at = outcome["polled_at"].as_str().unwrap();
+    assert!(chrono::DateTime::parse_from_rfc3339(polled_at).is_ok());
+    assert!(!dir.path().join("poll.json.tmp").exists());
+}
+
+#[test]
+fn once_output_file_append_adds_one_ndjson_line_per_run() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    let state_db_path = dir.path().join("state.db");
+    let output_path = dir.path().join("polls.ndjson");
+    write_config(&config_path, &state_db_path, &["acme/api"]);
+    let gh_path = write_stub_gh(dir.path(), EMPTY_REPO_STUB);
+
+    for _ in 0..2 {
+        let mut cmd = cargo_bin_cmd!("gh-watch");
+        cmd.arg("once")
+            .arg("--config")
+            .arg(&config_path)
+            .arg("--output-file")
+            .arg(&output_path)
+            .arg("--append")
+            .env("GH_WATCH_GH_BIN", &gh_path)
+            .assert()
+            .success();
+    }
+
+    let written = fs::read_to_string(&output_path).unwrap();
+    let lines = written.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 2);
+    for line in lines {
+        let outcome: serde_json::Value = serde_json::from_str(line).unwrap();
+        assert!(outcome["polled_at"].is_string());
+    }
+}
+
+#[test]
+fn once_append_requires_output_file() {
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.args(["once", "--append"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--output-file"));
+}
+
 #[test]
 fn help_lists_minimal_commands_and_hides_removed_commands() {
     let mut cmd = cargo_bin_cmd!("gh-watch");
@@ -218,14 +324,14 @@ fn help_lists_minimal_commands_and_hides_removed_commands() {
         .stdout(predicate::str::contains("config"))
         .stdout(predicate::str::contains("commands"))
         .stdout(predicate::str::contains("completion"))
-        .stdout(predicate::str::contains("report").not())
+        .stdout(predicate::str::contains("report"))
         .stdout(predicate::str::contains("doctor").not())
         .stdout(predicate::str::contains("notification-test").not());
 }
 
 #[test]
 fn removed_top_level_commands_are_unavailable() {
-    for command in ["report", "doctor", "notification-test"] {
+    for command in ["doctor", "notification-test"] {
         let mut cmd = cargo_bin_cmd!("gh-watch");
         cmd.arg(command)
             .assert()
@@ -234,6 +340,337 @@ fn removed_top_level_commands_are_unavailable() {
     }
 }
 
+#[test]
+fn report_writes_markdown_digest_split_by_repo() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    let state_db_path = dir.path().join("state.db");
+    let out_path = dir.path().join("digest.md");
+    write_config(&config_path, &state_db_path, &["acme/api", "acme/web"]);
+
+    let store = SqliteStateStore::new(&state_db_path).unwrap();
+    let now = Utc::now();
+    for (repo, title, age) in [
+        ("acme/api", "Fix [crash]", Duration::hours(2)),
+        ("acme/web", "New page", Duration::hours(3)),
+        ("acme/web", "Old news", Duration::days(3)),
+    ] {
+        store
+            .persist_repo_batch(&RepoPersistBatch {
+                repo: repo.to_string(),
+                poll_started_at: now,
+                pushed: false,
+                events: vec![report_event(repo, title, now - age)],
+                review_requests: Vec::new(),
+                review_threads: Vec::new(),
+                my_prs: Vec::new(),
+            })
+            .unwrap();
+    }
+
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.arg("report")
+        .arg("--config")
+        .arg(&config_path)
+        .arg("--since")
+        .arg("1d")
+        .arg("--split-by")
+        .arg("repo")
+        .arg("--out")
+        .arg(&out_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("wrote 2 events"));
+
+    let digest = fs::read_to_string(&out_path).unwrap();
+    assert!(digest.starts_with("# Activity since "));
+    assert!(digest.contains("2 events in 2 repositories"));
+    assert!(digest.contains("0 read, 2 unread"));
+    assert!(digest.contains("## Unread\n- acme/api: 1\n- acme/web: 1\n"));
+    assert!(digest.contains("## acme/api"));
+    assert!(digest.contains("## acme/web"));
+    assert!(digest.contains("Fix \\[crash\\]"));
+    assert!(!digest.contains("Old news"));
+}
+
+fn seed_report_events(state_db_path: &Path) {
+    let store = SqliteStateStore::new(state_db_path).unwrap();
+    let now = Utc::now();
+    for (repo, title, age) in [
+        ("acme/api", "Fix crash", Duration::hours(2)),
+        ("acme/web", "New page", Duration::hours(3)),
+    ] {
+        store
+            .persist_repo_batch(&RepoPersistBatch {
+                repo: repo.to_string(),
+                poll_started_at: now,
+                pushed: false,
+                events: vec![report_event(repo, title, now - age)],
+                review_requests: Vec::new(),
+                review_threads: Vec::new(),
+                my_prs: Vec::new(),
+            })
+            .unwrap();
+    }
+}
+
+#[test]
+fn report_json_output_file_holds_totals_and_events_and_append_adds_lines() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    let state_db_path = dir.path().join("state.db");
+    let out_path = dir.path().join("report.ndjson");
+    write_config(&config_path, &state_db_path, &["acme/api", "acme/web"]);
+    seed_report_events(&state_db_path);
+
+    let run = |append: bool| {
+        let mut cmd = cargo_bin_cmd!("gh-watch");
+        cmd.arg("report")
+            .arg("--config")
+            .arg(&config_path)
+            .arg("--since")
+            .arg("1d")
+            .arg("--format")
+            .arg("json")
+            .arg("--output-file")
+            .arg(&out_path);
+        if append {
+            cmd.arg("--append");
+        }
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains(if append {
+                "appended 2 events"
+            } else {
+                "wrote 2 events"
+            }));
+    };
+
+    run(false);
+    let written = fs::read_to_string(&out_path).unwrap();
+    let report: serde_json::Value = serde_json::from_str(written.trim_end()).unwrap();
+    assert_eq!(report["unread"], 2);
+    assert_eq!(report["read"], 0);
+    assert_eq!(report["unread_by_repo"]["acme/api"], 1);
+    assert_eq!(report["events"].as_array().unwrap().len(), 2);
+    assert_eq!(report["events"][0]["title"], "New page");
+
+    run(false);
+    assert_eq!(fs::read_to_string(&out_path).unwrap().lines().count(), 1);
+
+    run(true);
+    let lines = fs::read_to_string(&out_path).unwrap();
+    assert_eq!(lines.lines().count(), 2);
+    for line in lines.lines() {
+        serde_json::from_str::<serde_json::Value>(line).unwrap();
+    }
+}
+
+#[test]
+fn report_compress_writes_gzip_next_to_the_requested_path() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    let state_db_path = dir.path().join("state.db");
+    let out_path = dir.path().join("digest.md");
+    write_config(&config_path, &state_db_path, &["acme/api", "acme/web"]);
+    seed_report_events(&state_db_path);
+
+    for append in [false, true] {
+        let mut cmd = cargo_bin_cmd!("gh-watch");
+        cmd.arg("report")
+            .arg("--config")
+            .arg(&config_path)
+            .arg("--since")
+            .arg("1d")
+            .arg("--out")
+            .arg(&out_path)
+            .arg("--compress");
+        if append {
+            cmd.arg("--append");
+        }
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("digest.md.gz"));
+    }
+
+    assert!(!out_path.exists());
+    let mut digest = String::new();
+    MultiGzDecoder::new(fs::File::open(dir.path().join("digest.md.gz")).unwrap())
+        .read_to_string(&mut digest)
+        .unwrap();
+    assert_eq!(digest.matches("# Activity since ").count(), 2);
+    assert!(digest.contains("Fix crash"));
+}
+
+#[test]
+fn report_json_rejects_split_by_and_compress_requires_out() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    let state_db_path = dir.path().join("state.db");
+    write_config(&config_path, &state_db_path, &["acme/api"]);
+
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.arg("report")
+        .arg("--config")
+        .arg(&config_path)
+        .arg("--format")
+        .arg("json")
+        .arg("--split-by")
+        .arg("repo")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--split-by"));
+
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.arg("report")
+        .arg("--config")
+        .arg(&config_path)
+        .arg("--compress")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--out"));
+}
+
+#[test]
+fn feed_writes_atom_entries_for_the_selected_repos() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    let state_db_path = dir.path().join("state.db");
+    let out_path = dir.path().join("feed.xml");
+    write_config(&config_path, &state_db_path, &["acme/api", "acme/web"]);
+
+    let store = SqliteStateStore::new(&state_db_path).unwrap();
+    let now = Utc::now();
+    for (repo, title, age) in [
+        ("acme/api", "Fix <crash>", Duration::hours(2)),
+        ("acme/api", "Older fix", Duration::days(3)),
+        ("acme/web", "New page", Duration::hours(1)),
+    ] {
+        store
+            .persist_repo_batch(&RepoPersistBatch {
+                repo: repo.to_string(),
+                poll_started_at: now,
+                pushed: false,
+                events: vec![report_event(repo, title, now - age)],
+                review_requests: Vec::new(),
+                review_threads: Vec::new(),
+                my_prs: Vec::new(),
+            })
+            .unwrap();
+    }
+
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.arg("feed")
+        .arg("--config")
+        .arg(&config_path)
+        .args(["--repo", "acme/api", "--limit", "1", "--out"])
+        .arg(&out_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("wrote 1 entries"));
+
+    let feed = fs::read_to_string(&out_path).unwrap();
+    assert!(feed.contains("<feed xmlns=\"http://www.w3.org/2005/Atom\">"));
+    assert!(feed.contains("<title>[acme/api] #1 Fix &lt;crash&gt;</title>"));
+    assert!(feed.contains("<author><name>dev</name></author>"));
+    assert!(!feed.contains("Older fix"));
+    assert!(!feed.contains("New page"));
+}
+
+/// Digest printed by `report --since 1d` with `filters`, over one issue per
+/// `(repo, actor)` in `events` titled `<repo> by <actor>`.
+fn scoped_report(events: &[(&str, &str)], filters: &[&str]) -> String {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    let state_db_path = dir.path().join("state.db");
+    write_config(&config_path, &state_db_path, &["acme/api", "acme/web"]);
+
+    let store = SqliteStateStore::new(&state_db_path).unwrap();
+    let now = Utc::now();
+    for (repo, actor) in events {
+        store
+            .persist_repo_batch(&RepoPersistBatch {
+                repo: repo.to_string(),
+                poll_started_at: now,
+                pushed: false,
+                events: vec![WatchEvent {
+                    actor: actor.to_string(),
+                    ..report_event(
+                        repo,
+                        &format!("{repo} by {actor}"),
+                        now - Duration::hours(1),
+                    )
+                }],
+                review_requests: Vec::new(),
+                review_threads: Vec::new(),
+                my_prs: Vec::new(),
+            })
+            .unwrap();
+    }
+
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    let output = cmd
+        .arg("report")
+        .arg("--config")
+        .arg(&config_path)
+        .args(["--since", "1d", "--format", "text"])
+        .args(filters)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+const SCOPED_EVENTS: &[(&str, &str)] = &[
+    ("acme/api", "alice"),
+    ("acme/api", "bob"),
+    ("acme/web", "alice"),
+    ("acme/web", "carol"),
+];
+
+#[test]
+fn report_repo_flag_keeps_only_those_repositories() {
+    let digest = scoped_report(SCOPED_EVENTS, &["--repo", "acme/web"]);
+
+    assert!(digest.starts_with("Activity in acme/web since "));
+    assert!(digest.contains("2 events in 1 repositories"));
+    assert!(digest.contains("acme/web by alice"));
+    assert!(digest.contains("acme/web by carol"));
+    assert!(!digest.contains("acme/api by"));
+}
+
+#[test]
+fn report_actor_flag_keeps_only_those_actors() {
+    let digest = scoped_report(SCOPED_EVENTS, &["--actor", "Alice", "--actor", "bob"]);
+
+    assert!(digest.starts_with("Activity by @Alice, @bob since "));
+    assert!(digest.contains("3 events in 2 repositories"));
+    assert!(digest.contains("acme/api by alice"));
+    assert!(digest.contains("acme/api by bob"));
+    assert!(digest.contains("acme/web by alice"));
+    assert!(!digest.contains("by carol"));
+}
+
+#[test]
+fn report_repo_and_actor_flags_combine() {
+    let digest = scoped_report(
+        SCOPED_EVENTS,
+        &["--repo", "acme/api", "--actor", "alice", "--actor", "carol"],
+    );
+
+    assert!(digest.starts_with("Activity in acme/api by @alice, @carol since "));
+    assert!(digest.contains("1 events in 1 repositories"));
+    assert!(digest.contains("acme/api by alice"));
+    assert!(!digest.contains("by bob"));
+    assert!(!digest.contains("acme/web by"));
+}
+
+#[test]
+fn report_open_requires_out() {
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.arg("report").arg("--open").assert().failure();
+}
+
 #[test]
 fn once_dry_run_keeps_cursor_and_tables_unchanged() {
     let dir = tempdir().unwrap();
@@ -250,6 +687,7 @@ fn once_dry_run_keeps_cursor_and_tables_unchanged() {
         r#"#!/usr/bin/env bash
 set -euo pipefail
 if [[ "$1" == "auth" && "$2" == "status" ]]; then
+  echo "  Logged in to github.com account octocat (keyring)"
   exit 0
 fi
 if [[ "$1" == "api" ]]; then
@@ -303,6 +741,162 @@ exit 1
     assert_eq!(cursor_after, cursor.to_rfc3339());
 }
 
+#[test]
+fn check_warns_when_interval_and_repo_count_exceed_api_budget() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    let state_db_path = dir.path().join("state.db");
+    let repos = (0..30)
+        .map(|idx| format!("acme/repo-{idx}"))
+        .collect::<Vec<_>>();
+    let repo_refs = repos.iter().map(String::as_str).collect::<Vec<_>>();
+    write_config(&config_path, &state_db_path, &repo_refs);
+    let src = fs::read_to_string(&config_path)
+        .unwrap()
+        .replace("interval_seconds = 300", "interval_seconds = 60");
+    fs::write(&config_path, src).unwrap();
+
+    let gh_path = write_stub_gh(
+        dir.path(),
+        r#"#!/usr/bin/env bash
+set -euo pipefail
+if [[ "$1" == "auth" && "$2" == "status" ]]; then
+  echo "  Logged in to github.com account octocat (keyring)"
+  exit 0
+fi
+if [[ "$1" == "api" && "$2" == "rate_limit" ]]; then
+  echo '4200'
+  exit 0
+fi
+echo "unexpected args: $@" >&2
+exit 1
+"#,
+    );
+
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.arg("check")
+        .arg("--config")
+        .arg(&config_path)
+        .env("GH_WATCH_GH_BIN", gh_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("api remaining: 4200"))
+        .stdout(predicate::str::contains(
+            "api budget: ~10800 calls/hour (limit 5000)",
+        ))
+        .stdout(predicate::str::contains("api budget warning"));
+}
+
+#[test]
+fn check_warns_on_small_clock_skew_and_fails_on_large_skew() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    write_config(&config_path, &dir.path().join("state.db"), &["acme/api"]);
+
+    let run_check = |github_ahead: Duration| {
+        let script = r#"#!/usr/bin/env bash
+set -euo pipefail
+if [[ "$1" == "auth" && "$2" == "status" ]]; then
+  echo "  Logged in to github.com account octocat (keyring)"
+  exit 0
+fi
+if [[ "$*" == "api --include rate_limit" ]]; then
+  printf 'HTTP/2.0 200 OK\r\nDate: __DATE__\r\n\r\n{}\n'
+  exit 0
+fi
+if [[ "$1" == "api" && "$2" == "rate_limit" ]]; then
+  echo '4200'
+  exit 0
+fi
+echo "unexpected args: $@" >&2
+exit 1
+"#
+        .replace("__DATE__", &(Utc::now() + github_ahead).to_rfc2822());
+        let gh_path = write_stub_gh(dir.path(), &script);
+
+        let mut cmd = cargo_bin_cmd!("gh-watch");
+        cmd.arg("check")
+            .arg("--config")
+            .arg(&config_path)
+            .env("GH_WATCH_GH_BIN", gh_path)
+            .assert()
+    };
+
+    run_check(Duration::minutes(5))
+        .success()
+        .stdout(predicate::str::contains(
+            "warning: clock: local clock is 4m5",
+        ))
+        .stdout(predicate::str::contains("behind GitHub"));
+    run_check(Duration::minutes(-30))
+        .code(1)
+        .stderr(predicate::str::contains("clock: local clock is 30m0"))
+        .stderr(predicate::str::contains("ahead of GitHub"));
+}
+
+#[test]
+fn check_fails_fast_when_gh_binary_is_missing() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    write_config(&config_path, &dir.path().join("state.db"), &["acme/api"]);
+
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.arg("check")
+        .arg("--config")
+        .arg(&config_path)
+        .env("GH_WATCH_GH_BIN", dir.path().join("missing-gh"))
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("gh not found: "))
+        .stderr(predicate::str::contains(
+            "install gh (https://cli.github.com) or set GH_WATCH_GH_BIN",
+        ));
+}
+
+#[test]
+fn check_reports_gh_path_and_warns_when_gh_is_too_old() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    write_config(&config_path, &dir.path().join("state.db"), &["acme/api"]);
+
+    let gh_path = write_stub_gh(
+        dir.path(),
+        r#"#!/usr/bin/env bash
+set -euo pipefail
+if [[ "$1" == "--version" ]]; then
+  echo "gh version 2.30.0 (2023-05-24)"
+  exit 0
+fi
+if [[ "$1" == "auth" && "$2" == "status" ]]; then
+  echo "  Logged in to github.com account octocat (keyring)"
+  exit 0
+fi
+if [[ "$1" == "api" && "$2" == "rate_limit" ]]; then
+  echo '4200'
+  exit 0
+fi
+echo "unexpected args: $@" >&2
+exit 1
+"#,
+    );
+
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.arg("check")
+        .arg("--config")
+        .arg(&config_path)
+        .env("GH_WATCH_GH_BIN", &gh_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "gh: {} (gh version 2.30.0 (2023-05-24))",
+            gh_path.display()
+        )))
+        .stderr(predicate::str::contains(
+            "warning: gh 2.30.0 is too old for: ",
+        ))
+        .stderr(predicate::str::contains("`gh api --slurp`"));
+}
+
 #[test]
 fn check_fails_with_reset_hint_when_state_schema_is_legacy() {
     let dir = tempdir().unwrap();
@@ -316,6 +910,7 @@ fn check_fails_with_reset_hint_when_state_schema_is_legacy() {
         r#"#!/usr/bin/env bash
 set -euo pipefail
 if [[ "$1" == "auth" && "$2" == "status" ]]; then
+  echo "  Logged in to github.com account octocat (keyring)"
   exit 0
 fi
 echo "unexpected args: $@" >&2
@@ -346,6 +941,7 @@ fn once_fails_with_reset_hint_when_state_schema_is_legacy() {
         r#"#!/usr/bin/env bash
 set -euo pipefail
 if [[ "$1" == "auth" && "$2" == "status" ]]; then
+  echo "  Logged in to github.com account octocat (keyring)"
   exit 0
 fi
 echo "unexpected args: $@" >&2
@@ -378,6 +974,27 @@ fn write_stub_gh(dir: &Path, script: &str) -> PathBuf {
     path
 }
 
+fn report_event(repo: &str, title: &str, created_at: chrono::DateTime<Utc>) -> WatchEvent {
+    WatchEvent {
+        event_id: format!("{repo}:{title}"),
+        repo: repo.to_string(),
+        kind: EventKind::IssueCreated,
+        actor: "dev".to_string(),
+        title: title.to_string(),
+        url: format!("https://github.com/{repo}/issues/1"),
+        created_at,
+        source_item_id: format!("{repo}:{title}"),
+        subject_author: None,
+        requested_reviewer: None,
+        mentions: Vec::new(),
+        team_mentions: Vec::new(),
+        subject_number: Some(1),
+        subject_url: None,
+        account: None,
+        body: None,
+    }
+}
+
 fn write_config(config_path: &Path, state_db_path: &Path, repositories: &[&str]) {
     let escaped = state_db_path.display().to_string().replace('\\', "\\\\");
     let mut src = format!(
@@ -6,30 +6,60 @@ use std::{
 
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
-use chrono::{TimeZone, Utc};
+use chrono::{Duration, TimeZone, Utc};
 use gh_watch::{
-    app::poll_once::poll_once,
-    config::{Config, FiltersConfig, NotificationConfig, PollConfig, RepositoryConfig},
-    domain::events::{EventKind, WatchEvent},
+    app::poll_once::{ingest_pushed_events, poll_once, poll_once_as},
+    config::{
+        Config, DisplayConfig, FiltersConfig, KeymapConfig, NetworkConfig, NotificationConfig,
+        PollConfig, RemindersConfig, RepositoryConfig, RetentionConfig, StartupSearchQuery,
+    },
+    domain::{
+        error::GhRateLimitError,
+        events::{EventKind, WatchEvent},
+        failure::{FailureRecord, FAILURE_KIND_REPO_POLL},
+    },
     ports::{
-        ClockPort, CursorPort, GhClientPort, NotificationClickSupport, NotificationDispatchResult,
-        NotificationPayload, NotifierPort, PersistBatchResult, RepoBatchPort, RepoPersistBatch,
-        RetentionPort,
+        AuthInfo, ClockPort, CursorPort, FailureLogPort, GhClientPort, MyPrFetch, MyPrState,
+        NotificationClickSupport, NotificationDispatchResult, NotificationPayload, NotifierPort,
+        PendingReviewRequests, PersistBatchResult, PrFileChange, RepoAccess, RepoBatchPort,
+        RepoEventFetch, RepoPersistBatch, RetentionPolicy, RetentionPort, ReviewThreadFetch,
+        ReviewThreadState, TimelineQueryPort, ViewerTeamsPort,
     },
 };
 
-type CleanupCall = (u32, chrono::DateTime<Utc>);
+type CleanupCall = (RetentionPolicy, chrono::DateTime<Utc>);
+type SearchCall = (String, Vec<String>);
 
 #[derive(Clone, Default)]
 struct FakeGh {
     viewer_login: Arc<Mutex<String>>,
+    viewer_login_calls: Arc<Mutex<usize>>,
     events_by_repo: Arc<Mutex<HashMap<String, Vec<WatchEvent>>>>,
+    review_requests_by_repo: Arc<Mutex<HashMap<String, Vec<PendingReviewRequests>>>>,
     fail_repos: Arc<Mutex<HashMap<String, String>>>,
+    rate_limited_repos: Arc<Mutex<HashMap<String, GhRateLimitError>>>,
     fail_n_times_repos: Arc<Mutex<HashMap<String, (usize, String)>>>,
+    api_calls: Arc<Mutex<u64>>,
+    members_by_org_team: Arc<Mutex<HashMap<String, Vec<String>>>>,
     fetch_delay_ms_by_repo: Arc<Mutex<HashMap<String, u64>>>,
     fetch_attempts_by_repo: Arc<Mutex<HashMap<String, usize>>>,
     in_flight_fetches: Arc<Mutex<usize>>,
     max_concurrent_fetches: Arc<Mutex<usize>>,
+    review_threads_by_repo: Arc<Mutex<HashMap<String, ReviewThreadFetch>>>,
+    review_thread_fetches: Arc<Mutex<Vec<String>>>,
+    my_prs_by_repo: Arc<Mutex<HashMap<String, MyPrFetch>>>,
+    search_results: Arc<Mutex<Vec<WatchEvent>>>,
+    search_calls: Arc<Mutex<Vec<SearchCall>>>,
+    bootstrap_fetches: Arc<Mutex<Vec<String>>>,
+    cached_pages_per_bootstrap: Arc<Mutex<u64>>,
+    bootstrap_cached_at: Arc<Mutex<Option<chrono::DateTime<Utc>>>>,
+    response_cache_hits: Arc<Mutex<u64>>,
+    pr_files: Arc<Mutex<HashMap<i64, Vec<String>>>>,
+    pr_file_fetches: Arc<Mutex<Vec<i64>>>,
+    repo_access: Arc<Mutex<HashMap<String, RepoAccess>>>,
+    /// `None` makes `viewer_teams` fail, like a token without `read:org`.
+    viewer_teams: Arc<Mutex<Option<Vec<String>>>>,
+    viewer_teams_calls: Arc<Mutex<usize>>,
 }
 
 impl FakeGh {
@@ -40,6 +70,27 @@ impl FakeGh {
             .insert(repo.to_string(), events);
     }
 
+    fn set_review_requests(&self, repo: &str, review_requests: Vec<PendingReviewRequests>) {
+        self.review_requests_by_repo
+            .lock()
+            .unwrap()
+            .insert(repo.to_string(), review_requests);
+    }
+
+    fn set_review_threads(&self, repo: &str, threads: ReviewThreadFetch) {
+        self.review_threads_by_repo
+            .lock()
+            .unwrap()
+            .insert(repo.to_string(), threads);
+    }
+
+    fn set_my_prs(&self, repo: &str, prs: MyPrFetch) {
+        self.my_prs_by_repo
+            .lock()
+            .unwrap()
+            .insert(repo.to_string(), prs);
+    }
+
     fn fail_repo(&self, repo: &str, message: &str) {
         self.fail_repos
             .lock()
@@ -47,6 +98,13 @@ impl FakeGh {
             .insert(repo.to_string(), message.to_string());
     }
 
+    fn rate_limit_repo(&self, repo: &str, err: GhRateLimitError) {
+        self.rate_limited_repos
+            .lock()
+            .unwrap()
+            .insert(repo.to_string(), err);
+    }
+
     fn fail_repo_n_times(&self, repo: &str, times: usize, message: &str) {
         self.fail_n_times_repos
             .lock()
@@ -73,6 +131,55 @@ impl FakeGh {
     fn max_in_flight_fetches(&self) -> usize {
         *self.max_concurrent_fetches.lock().unwrap()
     }
+
+    fn set_members(&self, org_team: &str, members: &[&str]) {
+        self.members_by_org_team.lock().unwrap().insert(
+            org_team.to_string(),
+            members.iter().map(|member| member.to_string()).collect(),
+        );
+    }
+
+    fn set_api_calls(&self, calls: u64) {
+        *self.api_calls.lock().unwrap() = calls;
+    }
+
+    fn set_search_results(&self, events: Vec<WatchEvent>) {
+        *self.search_results.lock().unwrap() = events;
+    }
+
+    fn search_calls(&self) -> Vec<SearchCall> {
+        self.search_calls.lock().unwrap().clone()
+    }
+
+    fn set_cached_pages_per_bootstrap(&self, pages: u64) {
+        *self.cached_pages_per_bootstrap.lock().unwrap() = pages;
+    }
+
+    fn set_bootstrap_cached_at(&self, at: chrono::DateTime<Utc>) {
+        *self.bootstrap_cached_at.lock().unwrap() = Some(at);
+    }
+
+    fn bootstrap_fetches(&self) -> Vec<String> {
+        self.bootstrap_fetches.lock().unwrap().clone()
+    }
+
+    fn set_pr_files(&self, pr_number: i64, files: &[&str]) {
+        self.pr_files.lock().unwrap().insert(
+            pr_number,
+            files.iter().map(|file| file.to_string()).collect(),
+        );
+    }
+
+    fn pr_file_fetches(&self) -> Vec<i64> {
+        self.pr_file_fetches.lock().unwrap().clone()
+    }
+
+    fn set_repo_access(&self, repo: &str, access: RepoAccess) {
+        self.repo_access
+            .lock()
+            .unwrap()
+            .insert(repo.to_string(), access);
+    }
 }
 
 struct InFlightGuard {
@@ -102,19 +209,167 @@ impl Drop for InFlightGuard {
 
 #[async_trait]
 impl GhClientPort for FakeGh {
-    async fn check_auth(&self) -> Result<()> {
+    async fn search_issues(
+        &self,
+        query: &str,
+        repos: &[&str],
+        limit: usize,
+    ) -> Result<Vec<WatchEvent>> {
+        self.search_calls.lock().unwrap().push((
+            query.to_string(),
+            repos.iter().map(|repo| repo.to_string()).collect(),
+        ));
+        let mut events = self.search_results.lock().unwrap().clone();
+        events.retain(|event| repos.contains(&event.repo.as_str()));
+        events.truncate(limit);
+        Ok(events)
+    }
+
+    async fn check_auth(&self) -> Result<AuthInfo> {
+        Ok(AuthInfo {
+            login: self.viewer_login.lock().unwrap().clone(),
+            scopes: vec!["repo".to_string()],
+            hostname: "github.com".to_string(),
+        })
+    }
+
+    async fn fetch_bootstrap_repo_events(
+        &self,
+        repo: &str,
+        since: chrono::DateTime<Utc>,
+    ) -> Result<RepoEventFetch> {
+        self.bootstrap_fetches
+            .lock()
+            .unwrap()
+            .push(repo.to_string());
+        *self.response_cache_hits.lock().unwrap() +=
+            *self.cached_pages_per_bootstrap.lock().unwrap();
+        let mut fetch = self.fetch_repo_events(repo, since).await?;
+        fetch.cached_at = *self.bootstrap_cached_at.lock().unwrap();
+        Ok(fetch)
+    }
+
+    fn response_cache_hits(&self) -> u64 {
+        *self.response_cache_hits.lock().unwrap()
+    }
+
+    async fn list_org_members(&self, org: &str) -> Result<Vec<String>> {
+        self.members_by_org_team
+            .lock()
+            .unwrap()
+            .get(org)
+            .cloned()
+            .ok_or_else(|| anyhow!("unknown org: {org}"))
+    }
+
+    async fn list_team_members(&self, org: &str, team: &str) -> Result<Vec<String>> {
+        self.list_org_members(&format!("{org}/{team}")).await
+    }
+
+    async fn fetch_pr_reviews(
+        &self,
+        _repo: &str,
+        _pr_number: i64,
+        _since: chrono::DateTime<Utc>,
+    ) -> Result<Vec<WatchEvent>> {
+        Ok(Vec::new())
+    }
+
+    async fn fetch_review_threads(&self, repo: &str) -> Result<ReviewThreadFetch> {
+        self.review_thread_fetches
+            .lock()
+            .unwrap()
+            .push(repo.to_string());
+        Ok(self
+            .review_threads_by_repo
+            .lock()
+            .unwrap()
+            .get(repo)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn fetch_my_open_prs(&self, repos: &[&str]) -> Result<HashMap<String, MyPrFetch>> {
+        let my_prs = self.my_prs_by_repo.lock().unwrap();
+        Ok(repos
+            .iter()
+            .filter_map(|repo| Some((repo.to_string(), my_prs.get(*repo)?.clone())))
+            .collect())
+    }
+
+    async fn is_pull_request_open(&self, _repo: &str, _pr_number: i64) -> Result<bool> {
+        Ok(true)
+    }
+
+    async fn fetch_pr_file_changes(
+        &self,
+        _repo: &str,
+        pr_number: i64,
+    ) -> Result<Vec<PrFileChange>> {
+        self.pr_file_fetches.lock().unwrap().push(pr_number);
+        let files = self.pr_files.lock().unwrap().get(&pr_number).cloned();
+        Ok(files
+            .ok_or_else(|| anyhow!("unknown pull request: {pr_number}"))?
+            .into_iter()
+            .map(|filename| PrFileChange {
+                filename,
+                status: "modified".to_string(),
+                additions: 1,
+                deletions: 0,
+            })
+            .collect())
+    }
+
+    async fn read_gist_file(&self, _gist_id: &str, _file_name: &str) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    async fn write_gist_file(
+        &self,
+        _gist_id: &str,
+        _file_name: &str,
+        _content: &str,
+    ) -> Result<()> {
         Ok(())
     }
 
+    fn api_call_count(&self) -> u64 {
+        *self.api_calls.lock().unwrap()
+    }
+
+    async fn rate_limit_remaining(&self) -> Result<Option<u64>> {
+        Ok(Some(4200))
+    }
+
+    async fn probe_repo_access(&self, repo: &str) -> Result<RepoAccess> {
+        Ok(self
+            .repo_access
+            .lock()
+            .unwrap()
+            .get(repo)
+            .cloned()
+            .unwrap_or(RepoAccess::Accessible))
+    }
+
     async fn viewer_login(&self) -> Result<String> {
+        *self.viewer_login_calls.lock().unwrap() += 1;
         Ok(self.viewer_login.lock().unwrap().clone())
     }
 
+    async fn viewer_teams(&self) -> Result<Vec<String>> {
+        *self.viewer_teams_calls.lock().unwrap() += 1;
+        self.viewer_teams
+            .lock()
+            .unwrap()
+            .clone()
+            .ok_or_else(|| anyhow!("gh: HTTP 403: Resource not accessible by integration"))
+    }
+
     async fn fetch_repo_events(
         &self,
         repo: &str,
         _since: chrono::DateTime<Utc>,
-    ) -> Result<Vec<WatchEvent>> {
+    ) -> Result<RepoEventFetch> {
         let _guard = InFlightGuard::enter(
             self.in_flight_fetches.clone(),
             self.max_concurrent_fetches.clone(),
@@ -126,6 +381,7 @@ impl GhClientPort for FakeGh {
             .entry(repo.to_string())
             .and_modify(|attempts| *attempts += 1)
             .or_insert(1);
+        *self.api_calls.lock().unwrap() += 2;
 
         let delay_ms = self
             .fetch_delay_ms_by_repo
@@ -158,16 +414,32 @@ impl GhClientPort for FakeGh {
         if let Some(message) = self.fail_repos.lock().unwrap().get(repo).cloned() {
             return Err(anyhow!(message));
         }
-        Ok(self
-            .events_by_repo
-            .lock()
-            .unwrap()
-            .get(repo)
-            .cloned()
-            .unwrap_or_default())
+        if let Some(err) = self.rate_limited_repos.lock().unwrap().get(repo).cloned() {
+            return Err(anyhow::Error::new(err).context(format!("failed to fetch {repo}")));
+        }
+        Ok(RepoEventFetch {
+            events: self
+                .events_by_repo
+                .lock()
+                .unwrap()
+                .get(repo)
+                .cloned()
+                .unwrap_or_default(),
+            review_requests: self
+                .review_requests_by_repo
+                .lock()
+                .unwrap()
+                .get(repo)
+                .cloned()
+                .unwrap_or_default(),
+            cached_at: None,
+        })
     }
 }
 
+/// `ViewerTeamsPort::load_viewer_teams` of `FakeState`.
+type CachedViewerTeams = Option<(Vec<String>, chrono::DateTime<Utc>)>;
+
 #[derive(Clone, Default)]
 struct FakeState {
     cursors: Arc<Mutex<HashMap<String, chrono::DateTime<Utc>>>>,
@@ -175,6 +447,8 @@ struct FakeState {
     fail_persist_repo: Arc<Mutex<HashSet<String>>>,
     event_log: Arc<Mutex<HashSet<String>>>,
     cleanup_calls: Arc<Mutex<Vec<CleanupCall>>>,
+    failures: Arc<Mutex<Vec<FailureRecord>>>,
+    viewer_teams: Arc<Mutex<CachedViewerTeams>>,
 }
 
 impl FakeState {
@@ -209,16 +483,169 @@ impl CursorPort for FakeState {
         self.cursors.lock().unwrap().insert(repo.to_string(), at);
         Ok(())
     }
+
+    fn load_cursors_all(&self) -> Result<HashMap<String, chrono::DateTime<Utc>>> {
+        if let Some(repo) = self.fail_get_cursor.lock().unwrap().iter().next() {
+            return Err(anyhow!("cursor read failed for {repo}"));
+        }
+        Ok(self.cursors.lock().unwrap().clone())
+    }
+
+    fn load_cursors_for(&self, repos: &[String]) -> Result<HashMap<String, chrono::DateTime<Utc>>> {
+        let mut cursors = HashMap::new();
+        for repo in repos {
+            if let Some(at) = self.get_cursor(repo)? {
+                cursors.insert(repo.clone(), at);
+            }
+        }
+        Ok(cursors)
+    }
+}
+
+#[derive(Clone, Default)]
+struct QueryCountingStateStore {
+    inner: FakeState,
+    calls: Arc<Mutex<Vec<&'static str>>>,
+}
+
+impl QueryCountingStateStore {
+    fn record(&self, method: &'static str) {
+        self.calls.lock().unwrap().push(method);
+    }
+
+    fn count(&self, method: &str) -> usize {
+        self.calls
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|call| **call == method)
+            .count()
+    }
+}
+
+impl CursorPort for QueryCountingStateStore {
+    fn get_cursor(&self, repo: &str) -> Result<Option<chrono::DateTime<Utc>>> {
+        self.record("get_cursor");
+        self.inner.get_cursor(repo)
+    }
+
+    fn set_cursor(&self, repo: &str, at: chrono::DateTime<Utc>) -> Result<()> {
+        self.record("set_cursor");
+        CursorPort::set_cursor(&self.inner, repo, at)
+    }
+
+    fn load_cursors_all(&self) -> Result<HashMap<String, chrono::DateTime<Utc>>> {
+        self.record("load_cursors_all");
+        self.inner.load_cursors_all()
+    }
+
+    fn load_cursors_for(&self, repos: &[String]) -> Result<HashMap<String, chrono::DateTime<Utc>>> {
+        self.record("load_cursors_for");
+        self.inner.load_cursors_for(repos)
+    }
+}
+
+impl RetentionPort for QueryCountingStateStore {
+    fn cleanup_old(&self, policy: &RetentionPolicy, now: chrono::DateTime<Utc>) -> Result<()> {
+        self.record("cleanup_old");
+        self.inner.cleanup_old(policy, now)
+    }
+
+    fn count_delivered_read_events(&self) -> Result<usize> {
+        self.record("count_delivered_read_events");
+        self.inner.count_delivered_read_events()
+    }
+}
+
+impl RepoBatchPort for QueryCountingStateStore {
+    fn persist_repo_batch(&self, batch: &RepoPersistBatch) -> Result<PersistBatchResult> {
+        self.record("persist_repo_batch");
+        self.inner.persist_repo_batch(batch)
+    }
+}
+
+impl FailureLogPort for QueryCountingStateStore {
+    fn record_failure(&self, failure: &FailureRecord) -> Result<()> {
+        self.record("record_failure");
+        self.inner.record_failure(failure)
+    }
+
+    fn get_failure_count_for_repo(
+        &self,
+        repo: &str,
+        since: chrono::DateTime<Utc>,
+    ) -> Result<usize> {
+        self.record("get_failure_count_for_repo");
+        self.inner.get_failure_count_for_repo(repo, since)
+    }
+}
+
+impl ViewerTeamsPort for QueryCountingStateStore {
+    fn load_viewer_teams(&self) -> Result<Option<(Vec<String>, chrono::DateTime<Utc>)>> {
+        self.record("load_viewer_teams");
+        self.inner.load_viewer_teams()
+    }
+
+    fn save_viewer_teams(&self, teams: &[String], fetched_at: chrono::DateTime<Utc>) -> Result<()> {
+        self.record("save_viewer_teams");
+        self.inner.save_viewer_teams(teams, fetched_at)
+    }
+
+    fn clear_viewer_teams(&self) -> Result<()> {
+        self.record("clear_viewer_teams");
+        self.inner.clear_viewer_teams()
+    }
+}
+
+impl ViewerTeamsPort for FakeState {
+    fn load_viewer_teams(&self) -> Result<Option<(Vec<String>, chrono::DateTime<Utc>)>> {
+        Ok(self.viewer_teams.lock().unwrap().clone())
+    }
+
+    fn save_viewer_teams(&self, teams: &[String], fetched_at: chrono::DateTime<Utc>) -> Result<()> {
+        *self.viewer_teams.lock().unwrap() = Some((teams.to_vec(), fetched_at));
+        Ok(())
+    }
+
+    fn clear_viewer_teams(&self) -> Result<()> {
+        *self.viewer_teams.lock().unwrap() = None;
+        Ok(())
+    }
+}
+
+impl FailureLogPort for FakeState {
+    fn record_failure(&self, failure: &FailureRecord) -> Result<()> {
+        self.failures.lock().unwrap().push(failure.clone());
+        Ok(())
+    }
+
+    fn get_failure_count_for_repo(
+        &self,
+        repo: &str,
+        since: chrono::DateTime<Utc>,
+    ) -> Result<usize> {
+        Ok(self
+            .failures
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|failure| failure.repo == repo && failure.failed_at >= since)
+            .count())
+    }
 }
 
 impl RetentionPort for FakeState {
-    fn cleanup_old(&self, retention_days: u32, now: chrono::DateTime<Utc>) -> Result<()> {
+    fn cleanup_old(&self, policy: &RetentionPolicy, now: chrono::DateTime<Utc>) -> Result<()> {
         self.cleanup_calls
             .lock()
             .unwrap()
-            .push((retention_days, now));
+            .push((policy.clone(), now));
         Ok(())
     }
+
+    fn count_delivered_read_events(&self) -> Result<usize> {
+        Ok(0)
+    }
 }
 
 impl RepoBatchPort for FakeState {
@@ -300,6 +727,9 @@ impl NotifierPort for FakeNotifier {
                     return Err(anyhow!("digest notify failed once"));
                 }
             }
+            NotificationPayload::Alert(_)
+            | NotificationPayload::Summary(_)
+            | NotificationPayload::ReviewReminderDue(_) => {}
         }
         self.sent.lock().unwrap().push(payload.clone());
         Ok(NotificationDispatchResult::Delivered)
@@ -320,30 +750,69 @@ impl ClockPort for FixedClock {
 fn cfg() -> Config {
     Config {
         interval_seconds: 300,
+        i_know_what_im_doing: false,
         bootstrap_lookback_hours: 24,
         timeline_limit: 500,
         retention_days: 90,
+        max_notification_history: 10_000,
+        debounce_resize_ms: 50,
+        retention: RetentionConfig::default(),
+        reminders: RemindersConfig::default(),
+        sync: None,
         state_db_path: None,
+        accounts: Vec::new(),
+        startup_search_queries: Vec::new(),
+        watch_my_prs: false,
         repositories: vec![
             RepositoryConfig {
                 name: "acme/api".to_string(),
                 enabled: true,
                 event_kinds: None,
+                local_path: None,
+                watch_review_threads: false,
+                account: None,
+                gh_profile: None,
+                path_filters: None,
+                topic_tags: None,
+                notification_kinds: HashMap::new(),
             },
             RepositoryConfig {
                 name: "acme/web".to_string(),
                 enabled: true,
                 event_kinds: None,
+                local_path: None,
+                watch_review_threads: false,
+                account: None,
+                gh_profile: None,
+                path_filters: None,
+                topic_tags: None,
+                notification_kinds: HashMap::new(),
             },
         ],
         notifications: NotificationConfig {
             enabled: true,
             include_url: true,
+            yank_fallback_print: false,
+            summary: None,
+            kinds: HashMap::new(),
+            max_event_age_hours: 48,
+            body_max_chars: 200,
         },
+        notification_schedule: None,
         filters: FiltersConfig::default(),
+        default_event_kinds: None,
+        filter_profiles: HashMap::new(),
+        active_filter_profile: None,
         poll: PollConfig {
             timeout_seconds: 30,
+            api_cache_ttl_seconds: None,
+            cache_ttl_seconds: 0,
+            jitter_seconds: 0,
+            use_graphql_batch: false,
         },
+        network: NetworkConfig::default(),
+        display: DisplayConfig::default(),
+        keymap: KeymapConfig::default(),
     }
 }
 
@@ -360,6 +829,11 @@ fn event(repo: &str, id: &str, created_at: chrono::DateTime<Utc>) -> WatchEvent
         subject_author: Some("dev".to_string()),
         requested_reviewer: None,
         mentions: Vec::new(),
+        team_mentions: Vec::new(),
+        subject_number: None,
+        subject_url: None,
+        account: None,
+        body: None,
     }
 }
 
@@ -385,6 +859,7 @@ async fn bootstrap_poll_populates_timeline_without_notifications() {
     let out = poll_once(&cfg(), &gh, &state, &notifier, &clock)
         .await
         .unwrap();
+    assert_eq!(out.polled_at, clock.now);
     assert_eq!(out.bootstrap_repos, 2);
     assert_eq!(out.timeline_events.len(), 1);
     assert_eq!(out.notified_count, 0);
@@ -392,367 +867,1771 @@ async fn bootstrap_poll_populates_timeline_without_notifications() {
 }
 
 #[tokio::test]
-async fn non_bootstrap_poll_notifies_new_events() {
+async fn startup_search_results_seed_only_bootstrapping_repos() {
     let gh = FakeGh::default();
     let state = FakeState::default();
     let notifier = FakeNotifier::default();
     let clock = FixedClock {
-        now: Utc.with_ymd_and_hms(2025, 1, 20, 0, 10, 0).unwrap(),
+        now: Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
     };
-
-    state.set_cursor(
-        "acme/api",
-        Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
-    );
     state.set_cursor(
         "acme/web",
-        Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
-    );
-    gh.set_events(
-        "acme/api",
-        vec![event(
-            "acme/api",
-            "ev-2",
-            Utc.with_ymd_and_hms(2025, 1, 20, 0, 5, 0).unwrap(),
-        )],
+        Utc.with_ymd_and_hms(2025, 1, 19, 0, 0, 0).unwrap(),
     );
+    gh.set_events("acme/api", Vec::new());
     gh.set_events("acme/web", Vec::new());
-
-    let out = poll_once(&cfg(), &gh, &state, &notifier, &clock)
+    gh.set_search_results(vec![
+        event(
+            "acme/api",
+            "issue:old",
+            Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap(),
+        ),
+        event(
+            "acme/web",
+            "issue:web",
+            Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap(),
+        ),
+    ]);
+    let mut config = cfg();
+    config.startup_search_queries = vec![StartupSearchQuery {
+        query: "is:open assignee:@me".to_string(),
+        repos: Vec::new(),
+    }];
+
+    let out = poll_once(&config, &gh, &state, &notifier, &clock)
         .await
         .unwrap();
-    assert_eq!(out.bootstrap_repos, 0);
-    assert_eq!(out.notified_count, 1);
-    let sent = notifier.sent();
-    assert_eq!(sent.len(), 1);
-    assert!(matches!(sent[0], NotificationPayload::Event(_)));
-    assert_eq!(state.cleanup_calls.lock().unwrap().len(), 1);
+
+    assert_eq!(
+        gh.search_calls(),
+        vec![(
+            "is:open assignee:@me".to_string(),
+            vec!["acme/api".to_string()]
+        )]
+    );
+    let ids = out
+        .timeline_events
+        .iter()
+        .map(|event| event.event_id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec!["issue:old"]);
+    assert!(notifier.sent().is_empty());
 }
 
 #[tokio::test]
-async fn repo_fetch_partial_failure_returns_success_with_failure_details() {
+async fn poll_reports_api_calls_for_this_poll_only() {
     let gh = FakeGh::default();
     let state = FakeState::default();
     let notifier = FakeNotifier::default();
     let clock = FixedClock {
         now: Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
     };
-
-    state.set_cursor(
-        "acme/api",
-        Utc.with_ymd_and_hms(2025, 1, 19, 0, 0, 0).unwrap(),
-    );
-    state.set_cursor(
-        "acme/web",
-        Utc.with_ymd_and_hms(2025, 1, 19, 0, 0, 0).unwrap(),
-    );
-    gh.fail_repo("acme/api", "boom");
-    gh.set_events(
-        "acme/web",
-        vec![event(
-            "acme/web",
-            "ev-web-1",
-            Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
-        )],
-    );
+    gh.set_events("acme/api", Vec::new());
+    gh.set_events("acme/web", Vec::new());
+    gh.set_api_calls(7);
 
     let out = poll_once(&cfg(), &gh, &state, &notifier, &clock)
         .await
-        .expect("partial failure should still return success");
-    assert_eq!(out.notified_count, 1);
-    assert_eq!(out.fetch_failures.len(), 1);
-    assert_eq!(out.fetch_failures[0].repo, "acme/api");
-    assert!(out.fetch_failures[0].message.contains("boom"));
+        .unwrap();
+
+    assert_eq!(out.api_calls, 4);
+    assert_eq!(out.rate_limit_remaining, Some(4200));
+    assert_eq!(
+        out.api_usage_summary(),
+        "api: 4 calls/cycle, 4200 remaining"
+    );
 }
 
 #[tokio::test]
-async fn repo_fetch_returns_error_when_all_repositories_fail() {
+async fn only_bootstrap_fetches_go_through_the_response_cache() {
     let gh = FakeGh::default();
     let state = FakeState::default();
     let notifier = FakeNotifier::default();
     let clock = FixedClock {
-        now: Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+        now: Utc.with_ymd_and_hms(2025, 1, 20, 0, 10, 0).unwrap(),
     };
-
+    gh.set_events("acme/api", Vec::new());
+    gh.set_events("acme/web", Vec::new());
     state.set_cursor(
-        "acme/api",
-        Utc.with_ymd_and_hms(2025, 1, 19, 0, 0, 0).unwrap(),
+        "acme/web",
+        Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+    );
+    gh.set_cached_pages_per_bootstrap(6);
+
+    let out = poll_once(&cfg(), &gh, &state, &notifier, &clock)
+        .await
+        .unwrap();
+
+    assert_eq!(gh.bootstrap_fetches(), vec!["acme/api".to_string()]);
+    assert_eq!(out.response_cache_hits, 6);
+    assert_eq!(
+        out.api_usage_summary(),
+        "api: 4 calls/cycle, 6 cached, 4200 remaining"
     );
+}
+
+#[tokio::test]
+async fn bootstrap_served_from_the_response_cache_leaves_the_cursor_at_the_cached_time() {
+    let gh = FakeGh::default();
+    let state = FakeState::default();
+    let notifier = FakeNotifier::default();
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 20, 0, 10, 0).unwrap(),
+    };
+    gh.set_events("acme/api", Vec::new());
+    gh.set_events("acme/web", Vec::new());
     state.set_cursor(
         "acme/web",
-        Utc.with_ymd_and_hms(2025, 1, 19, 0, 0, 0).unwrap(),
+        Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
     );
-    gh.fail_repo("acme/api", "api boom");
-    gh.fail_repo("acme/web", "web boom");
+    let cached_at = Utc.with_ymd_and_hms(2025, 1, 20, 0, 7, 0).unwrap();
+    gh.set_bootstrap_cached_at(cached_at);
 
-    let err = poll_once(&cfg(), &gh, &state, &notifier, &clock)
+    poll_once(&cfg(), &gh, &state, &notifier, &clock)
         .await
-        .expect_err("all repo failures should return error");
-    let message = err.to_string();
-    assert!(message.contains("all repository fetches failed"));
-    assert!(message.contains("acme/api"));
-    assert!(message.contains("acme/web"));
+        .unwrap();
+
+    assert_eq!(
+        state.cursors.lock().unwrap().get("acme/api").copied(),
+        Some(cached_at)
+    );
+    assert_eq!(
+        state.cursors.lock().unwrap().get("acme/web").copied(),
+        Some(clock.now)
+    );
 }
 
 #[tokio::test]
-async fn repo_fetch_retries_temporary_failures_and_succeeds() {
+async fn non_bootstrap_poll_notifies_new_events() {
     let gh = FakeGh::default();
     let state = FakeState::default();
     let notifier = FakeNotifier::default();
     let clock = FixedClock {
-        now: Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+        now: Utc.with_ymd_and_hms(2025, 1, 20, 0, 10, 0).unwrap(),
     };
 
     state.set_cursor(
         "acme/api",
-        Utc.with_ymd_and_hms(2025, 1, 19, 0, 0, 0).unwrap(),
+        Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
     );
     state.set_cursor(
         "acme/web",
-        Utc.with_ymd_and_hms(2025, 1, 19, 0, 0, 0).unwrap(),
+        Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
     );
-    gh.fail_repo_n_times("acme/api", 2, "transient");
     gh.set_events(
         "acme/api",
         vec![event(
             "acme/api",
-            "ev-retry-1",
-            Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+            "ev-2",
+            Utc.with_ymd_and_hms(2025, 1, 20, 0, 5, 0).unwrap(),
         )],
     );
     gh.set_events("acme/web", Vec::new());
 
     let out = poll_once(&cfg(), &gh, &state, &notifier, &clock)
         .await
-        .expect("third attempt should succeed");
-    assert_eq!(gh.fetch_attempt_count("acme/api"), 3);
-    assert_eq!(out.fetch_failures.len(), 0);
+        .unwrap();
+    assert_eq!(out.bootstrap_repos, 0);
     assert_eq!(out.notified_count, 1);
+    let sent = notifier.sent();
+    assert_eq!(sent.len(), 1);
+    assert!(matches!(sent[0], NotificationPayload::Event(_)));
+    assert_eq!(state.cleanup_calls.lock().unwrap().len(), 1);
 }
 
 #[tokio::test]
-async fn repo_fetch_runs_sequentially() {
+async fn notification_kinds_keep_events_in_the_timeline_without_notifying() {
     let gh = FakeGh::default();
     let state = FakeState::default();
     let notifier = FakeNotifier::default();
     let clock = FixedClock {
-        now: Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+        now: Utc.with_ymd_and_hms(2025, 1, 20, 0, 10, 0).unwrap(),
     };
-
-    state.set_cursor(
+    let mut config = cfg();
+    config.filters.event_kinds = vec![EventKind::IssueCommentCreated, EventKind::PrCreated];
+    config
+        .notifications
+        .kinds
+        .insert(EventKind::IssueCommentCreated, false);
+    config.repositories[1]
+        .notification_kinds
+        .insert(EventKind::IssueCommentCreated, true);
+
+    let since = Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap();
+    state.set_cursor("acme/api", since);
+    state.set_cursor("acme/web", since);
+    let at = Utc.with_ymd_and_hms(2025, 1, 20, 0, 5, 0).unwrap();
+    let with_kind = |repo: &str, id: &str, kind: EventKind| WatchEvent {
+        kind,
+        ..event(repo, id, at)
+    };
+    gh.set_events(
         "acme/api",
-        Utc.with_ymd_and_hms(2025, 1, 19, 0, 0, 0).unwrap(),
+        vec![
+            with_kind("acme/api", "comment", EventKind::IssueCommentCreated),
+            with_kind("acme/api", "pr", EventKind::PrCreated),
+            with_kind("acme/api", "merged", EventKind::PrMerged),
+        ],
     );
-    state.set_cursor(
+    gh.set_events(
         "acme/web",
-        Utc.with_ymd_and_hms(2025, 1, 19, 0, 0, 0).unwrap(),
+        vec![with_kind(
+            "acme/web",
+            "web-comment",
+            EventKind::IssueCommentCreated,
+        )],
     );
-    gh.set_fetch_delay_ms("acme/api", 30);
-    gh.set_fetch_delay_ms("acme/web", 30);
-    gh.set_events("acme/api", Vec::new());
-    gh.set_events("acme/web", Vec::new());
 
-    let out = poll_once(&cfg(), &gh, &state, &notifier, &clock)
+    let out = poll_once(&config, &gh, &state, &notifier, &clock)
         .await
-        .expect("poll should succeed");
-    assert_eq!(out.fetch_failures.len(), 0);
-    assert_eq!(gh.max_in_flight_fetches(), 1);
+        .unwrap();
+
+    let ids = |events: &[WatchEvent]| {
+        let mut ids = events
+            .iter()
+            .map(|event| event.event_id.clone())
+            .collect::<Vec<_>>();
+        ids.sort();
+        ids
+    };
+    // filters.event_kinds still decides what is recorded; notifications.kinds only what of
+    // that is notified, with the repository entry winning over the global one.
+    assert_eq!(
+        ids(&out.timeline_events),
+        vec!["comment", "pr", "web-comment"]
+    );
+    assert_eq!(ids(&out.notified_events), vec!["pr", "web-comment"]);
 }
 
 #[tokio::test]
-async fn multiple_events_in_single_poll_send_digest_notification_once() {
+async fn events_older_than_max_event_age_are_recorded_but_not_notified() {
     let gh = FakeGh::default();
     let state = FakeState::default();
     let notifier = FakeNotifier::default();
-    let clock = FixedClock {
-        now: Utc.with_ymd_and_hms(2025, 1, 21, 0, 0, 0).unwrap(),
-    };
-
-    state.set_cursor(
-        "acme/api",
-        Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
-    );
-    state.set_cursor(
-        "acme/web",
-        Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
-    );
-
-    let ev_latest = event(
-        "acme/api",
-        "ev-latest",
-        Utc.with_ymd_and_hms(2025, 1, 20, 0, 7, 0).unwrap(),
-    );
-    let ev_tie_api = event(
+    let now = Utc.with_ymd_and_hms(2025, 1, 20, 0, 10, 0).unwrap();
+    let clock = FixedClock { now };
+    let config = cfg();
+    assert_eq!(config.notifications.max_event_age_hours, 48);
+
+    // A cursor weeks behind, as after restoring an old state db backup.
+    let since = now - Duration::days(30);
+    state.set_cursor("acme/api", since);
+    state.set_cursor("acme/web", since);
+    let cutoff = now - Duration::hours(48);
+    gh.set_events(
         "acme/api",
-        "ev-tie-api",
-        Utc.with_ymd_and_hms(2025, 1, 20, 0, 6, 0).unwrap(),
-    );
-    let ev_tie_web = event(
-        "acme/web",
-        "ev-tie-web",
-        Utc.with_ymd_and_hms(2025, 1, 20, 0, 6, 0).unwrap(),
+        vec![
+            event("acme/api", "ancient", since + Duration::days(1)),
+            event("acme/api", "just-stale", cutoff - Duration::seconds(1)),
+            event("acme/api", "at-cutoff", cutoff),
+            event("acme/api", "fresh", now - Duration::minutes(5)),
+        ],
     );
 
-    gh.set_events("acme/api", vec![ev_tie_api.clone(), ev_latest.clone()]);
-    gh.set_events("acme/web", vec![ev_tie_web.clone()]);
-
-    let out = poll_once(&cfg(), &gh, &state, &notifier, &clock)
+    let out = poll_once(&config, &gh, &state, &notifier, &clock)
         .await
-        .expect("poll with multiple events should succeed");
+        .unwrap();
 
-    let expected_keys = vec![
-        ev_latest.event_key(),
-        ev_tie_api.event_key(),
-        ev_tie_web.event_key(),
-    ];
-    let notified_keys = out
+    assert_eq!(out.timeline_events.len(), 4);
+    let mut notified = out
         .notified_events
         .iter()
-        .map(|event| event.event_key())
+        .map(|event| event.event_id.as_str())
         .collect::<Vec<_>>();
-    assert_eq!(out.notified_count, 1);
-    assert_eq!(notified_keys, expected_keys);
+    notified.sort();
+    assert_eq!(notified, vec!["at-cutoff", "fresh"]);
+    assert_eq!(out.stale_skipped_count, 2);
+}
 
-    let sent = notifier.sent();
-    assert_eq!(sent.len(), 1);
-    match &sent[0] {
-        NotificationPayload::Digest(digest) => {
-            assert_eq!(digest.total_events, 3);
-            let sample_keys = digest
-                .sample_events
-                .iter()
-                .map(|event| event.event_key())
-                .collect::<Vec<_>>();
-            assert_eq!(sample_keys, expected_keys);
-        }
-        NotificationPayload::Event(_) => panic!("expected digest payload for multiple events"),
-    }
+#[tokio::test]
+async fn bootstrap_counts_no_stale_events_since_it_never_notifies() {
+    let gh = FakeGh::default();
+    let state = FakeState::default();
+    let notifier = FakeNotifier::default();
+    let now = Utc.with_ymd_and_hms(2025, 1, 20, 0, 10, 0).unwrap();
+    let clock = FixedClock { now };
+    let mut config = cfg();
+    config.bootstrap_lookback_hours = 72;
+    gh.set_events(
+        "acme/api",
+        vec![
+            event("acme/api", "old", now - Duration::hours(60)),
+            event("acme/api", "new", now - Duration::hours(1)),
+        ],
+    );
+
+    let out = poll_once(&config, &gh, &state, &notifier, &clock)
+        .await
+        .unwrap();
+
+    assert_eq!(out.bootstrap_repos, 2);
+    assert_eq!(out.timeline_events.len(), 2);
+    assert!(out.notified_events.is_empty());
+    assert_eq!(out.stale_skipped_count, 0);
 }
 
 #[tokio::test]
-async fn notification_failure_returns_error() {
+async fn only_involving_me_notifies_events_from_fetched_team_members() {
     let gh = FakeGh::default();
     let state = FakeState::default();
     let notifier = FakeNotifier::default();
     let clock = FixedClock {
-        now: Utc.with_ymd_and_hms(2025, 1, 21, 0, 0, 0).unwrap(),
+        now: Utc.with_ymd_and_hms(2025, 1, 20, 0, 10, 0).unwrap(),
     };
-
-    let ev = event(
-        "acme/api",
-        "ev-3",
-        Utc.with_ymd_and_hms(2025, 1, 21, 0, 0, 0).unwrap(),
-    );
-    state.set_cursor(
+    let cursor = Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap();
+    state.set_cursor("acme/api", cursor);
+    state.set_cursor("acme/web", cursor);
+    *gh.viewer_login.lock().unwrap() = "me".to_string();
+    gh.set_members("acme/backend", &["alice", "bob"]);
+    let created_at = Utc.with_ymd_and_hms(2025, 1, 20, 0, 5, 0).unwrap();
+    gh.set_events(
         "acme/api",
-        Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
-    );
-    state.set_cursor(
-        "acme/web",
-        Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+        vec![
+            WatchEvent {
+                actor: "bob".to_string(),
+                ..event("acme/api", "ev-team", created_at)
+            },
+            event("acme/api", "ev-other", created_at),
+        ],
     );
-    gh.set_events("acme/api", vec![ev.clone()]);
     gh.set_events("acme/web", Vec::new());
-    notifier.fail_once_for_event(&ev.event_key());
+    let mut config = cfg();
+    config.filters.only_involving_me = true;
+    config.filters.auto_fetch_org_team = Some("acme/backend".to_string());
 
-    let err = poll_once(&cfg(), &gh, &state, &notifier, &clock)
+    let out = poll_once(&config, &gh, &state, &notifier, &clock)
         .await
-        .expect_err("notification should fail");
-    assert!(err.to_string().contains("notification failed for"));
+        .unwrap();
+
+    assert_eq!(out.notified_count, 1);
+    assert_eq!(out.notified_events[0].event_id, "ev-team");
 }
 
 #[tokio::test]
-async fn digest_notification_failure_returns_error() {
+async fn only_involving_me_falls_back_to_configured_team_members_when_lookup_fails() {
     let gh = FakeGh::default();
     let state = FakeState::default();
     let notifier = FakeNotifier::default();
     let clock = FixedClock {
-        now: Utc.with_ymd_and_hms(2025, 1, 21, 0, 30, 0).unwrap(),
+        now: Utc.with_ymd_and_hms(2025, 1, 20, 0, 10, 0).unwrap(),
     };
-
-    state.set_cursor(
-        "acme/api",
-        Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
-    );
-    state.set_cursor(
-        "acme/web",
-        Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
-    );
-
+    let cursor = Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap();
+    state.set_cursor("acme/api", cursor);
+    state.set_cursor("acme/web", cursor);
+    *gh.viewer_login.lock().unwrap() = "me".to_string();
+    let created_at = Utc.with_ymd_and_hms(2025, 1, 20, 0, 5, 0).unwrap();
     gh.set_events(
         "acme/api",
-        vec![event(
-            "acme/api",
-            "ev-digest-a",
-            Utc.with_ymd_and_hms(2025, 1, 21, 0, 10, 0).unwrap(),
-        )],
-    );
-    gh.set_events(
-        "acme/web",
-        vec![event(
-            "acme/web",
-            "ev-digest-b",
-            Utc.with_ymd_and_hms(2025, 1, 21, 0, 11, 0).unwrap(),
-        )],
+        vec![
+            WatchEvent {
+                actor: "alice".to_string(),
+                ..event("acme/api", "ev-team", created_at)
+            },
+            event("acme/api", "ev-other", created_at),
+        ],
     );
-    notifier.fail_digest_once();
+    gh.set_events("acme/web", Vec::new());
+    let mut config = cfg();
+    config.filters.only_involving_me = true;
+    config.filters.team_members = vec!["alice".to_string()];
+    config.filters.auto_fetch_org_team = Some("acme/backend".to_string());
 
-    let err = poll_once(&cfg(), &gh, &state, &notifier, &clock)
+    let out = poll_once(&config, &gh, &state, &notifier, &clock)
         .await
-        .expect_err("digest notification should fail");
-    assert!(err.to_string().contains("digest notification failed"));
+        .unwrap();
+
+    assert_eq!(out.fetched_repos.len(), 2);
+    assert_eq!(out.notified_count, 1);
+    assert_eq!(out.notified_events[0].event_id, "ev-team");
 }
 
 #[tokio::test]
-async fn cursor_failure_returns_error() {
+async fn only_involving_me_uses_known_login_without_asking_gh() {
     let gh = FakeGh::default();
     let state = FakeState::default();
     let notifier = FakeNotifier::default();
     let clock = FixedClock {
-        now: Utc.with_ymd_and_hms(2025, 1, 22, 0, 0, 0).unwrap(),
+        now: Utc.with_ymd_and_hms(2025, 1, 20, 0, 10, 0).unwrap(),
     };
+    let cursor = Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap();
+    state.set_cursor("acme/api", cursor);
+    state.set_cursor("acme/web", cursor);
+    let created_at = Utc.with_ymd_and_hms(2025, 1, 20, 0, 5, 0).unwrap();
+    gh.set_events(
+        "acme/api",
+        vec![
+            WatchEvent {
+                mentions: vec!["me".to_string()],
+                ..event("acme/api", "ev-mention", created_at)
+            },
+            event("acme/api", "ev-other", created_at),
+        ],
+    );
+    gh.set_events("acme/web", Vec::new());
+    let mut config = cfg();
+    config.filters.only_involving_me = true;
 
-    state.fail_cursor_for_repo("acme/api");
-    let err = poll_once(&cfg(), &gh, &state, &notifier, &clock)
+    let out = poll_once_as(&config, &gh, &state, &notifier, &clock, Some("me"))
         .await
-        .expect_err("cursor load should fail");
-    assert!(err
-        .to_string()
-        .contains("failed to load cursor for acme/api"));
+        .unwrap();
+
+    assert_eq!(out.notified_count, 1);
+    assert_eq!(out.notified_events[0].event_id, "ev-mention");
+    assert_eq!(*gh.viewer_login_calls.lock().unwrap(), 0);
+}
+
+fn team_mention_events(created_at: chrono::DateTime<Utc>) -> Vec<WatchEvent> {
+    vec![
+        WatchEvent {
+            team_mentions: vec!["acme/platform".to_string()],
+            ..event("acme/api", "ev-my-team", created_at)
+        },
+        WatchEvent {
+            team_mentions: vec!["acme/design".to_string()],
+            ..event("acme/api", "ev-other-team", created_at)
+        },
+    ]
 }
 
 #[tokio::test]
-async fn persist_failure_returns_error() {
+async fn only_involving_me_notifies_mentions_of_the_viewers_teams_and_caches_them() {
     let gh = FakeGh::default();
     let state = FakeState::default();
     let notifier = FakeNotifier::default();
-    let clock = FixedClock {
-        now: Utc.with_ymd_and_hms(2025, 1, 23, 0, 0, 0).unwrap(),
-    };
-
+    let now = Utc.with_ymd_and_hms(2025, 1, 20, 0, 10, 0).unwrap();
+    let clock = FixedClock { now };
     state.set_cursor(
         "acme/api",
-        Utc.with_ymd_and_hms(2025, 1, 22, 0, 0, 0).unwrap(),
+        Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
     );
     state.set_cursor(
         "acme/web",
-        Utc.with_ymd_and_hms(2025, 1, 22, 0, 0, 0).unwrap(),
+        Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
     );
-    state.fail_persist_for_repo("acme/api");
+    *gh.viewer_teams.lock().unwrap() = Some(vec!["acme/platform".to_string()]);
     gh.set_events(
         "acme/api",
-        vec![event(
-            "acme/api",
-            "ev-4",
-            Utc.with_ymd_and_hms(2025, 1, 23, 0, 0, 0).unwrap(),
-        )],
+        team_mention_events(Utc.with_ymd_and_hms(2025, 1, 20, 0, 5, 0).unwrap()),
     );
     gh.set_events("acme/web", Vec::new());
+    let mut config = cfg();
+    config.filters.only_involving_me = true;
 
-    let err = poll_once(&cfg(), &gh, &state, &notifier, &clock)
+    let out = poll_once_as(&config, &gh, &state, &notifier, &clock, Some("me"))
         .await
-        .expect_err("persist should fail");
-    assert!(err
-        .to_string()
-        .contains("failed to persist event batch for acme/api"));
+        .unwrap();
+
+    assert_eq!(out.notified_count, 1);
+    assert_eq!(out.notified_events[0].event_id, "ev-my-team");
+    assert_eq!(
+        state.load_viewer_teams().unwrap(),
+        Some((vec!["acme/platform".to_string()], now))
+    );
+
+    // Within a day the cached teams are used without asking gh again.
+    let later = FixedClock {
+        now: now + chrono::Duration::hours(23),
+    };
+    poll_once_as(&config, &gh, &state, &notifier, &later, Some("me"))
+        .await
+        .unwrap();
+    assert_eq!(*gh.viewer_teams_calls.lock().unwrap(), 1);
+
+    let next_day = FixedClock {
+        now: now + chrono::Duration::hours(24),
+    };
+    poll_once_as(&config, &gh, &state, &notifier, &next_day, Some("me"))
+        .await
+        .unwrap();
+    assert_eq!(*gh.viewer_teams_calls.lock().unwrap(), 2);
+}
+
+#[tokio::test]
+async fn failed_team_lookup_falls_back_to_login_only_involvement() {
+    let gh = FakeGh::default();
+    let state = FakeState::default();
+    let notifier = FakeNotifier::default();
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 20, 0, 10, 0).unwrap(),
+    };
+    state.set_cursor(
+        "acme/api",
+        Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+    );
+    state.set_cursor(
+        "acme/web",
+        Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+    );
+    let created_at = Utc.with_ymd_and_hms(2025, 1, 20, 0, 5, 0).unwrap();
+    let mut events = team_mention_events(created_at);
+    events.push(WatchEvent {
+        mentions: vec!["me".to_string()],
+        ..event("acme/api", "ev-mention", created_at)
+    });
+    gh.set_events("acme/api", events);
+    gh.set_events("acme/web", Vec::new());
+    let mut config = cfg();
+    config.filters.only_involving_me = true;
+
+    let out = poll_once_as(&config, &gh, &state, &notifier, &clock, Some("me"))
+        .await
+        .unwrap();
+
+    assert_eq!(out.notified_count, 1);
+    assert_eq!(out.notified_events[0].event_id, "ev-mention");
+    assert_eq!(state.load_viewer_teams().unwrap(), None);
+}
+
+#[tokio::test]
+async fn ignore_self_resolves_the_viewer_and_skips_their_events() {
+    let gh = FakeGh::default();
+    let state = FakeState::default();
+    let notifier = FakeNotifier::default();
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 20, 0, 10, 0).unwrap(),
+    };
+    let cursor = Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap();
+    state.set_cursor("acme/api", cursor);
+    state.set_cursor("acme/web", cursor);
+    *gh.viewer_login.lock().unwrap() = "me".to_string();
+    let created_at = Utc.with_ymd_and_hms(2025, 1, 20, 0, 5, 0).unwrap();
+    gh.set_events(
+        "acme/api",
+        vec![
+            WatchEvent {
+                actor: "me".to_string(),
+                ..event("acme/api", "ev-mine", created_at)
+            },
+            event("acme/api", "ev-other", created_at),
+        ],
+    );
+    gh.set_events("acme/web", Vec::new());
+    let mut config = cfg();
+    config.filters.ignore_self = true;
+
+    let out = poll_once(&config, &gh, &state, &notifier, &clock)
+        .await
+        .unwrap();
+
+    assert_eq!(out.notified_count, 1);
+    assert_eq!(out.notified_events[0].event_id, "ev-other");
+    assert_eq!(*gh.viewer_login_calls.lock().unwrap(), 1);
+}
+
+#[tokio::test]
+async fn poll_passes_retention_policy_from_config_to_cleanup() {
+    let gh = FakeGh::default();
+    let state = FakeState::default();
+    let notifier = FakeNotifier::default();
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+    };
+    let mut config = cfg();
+    config.retention.keep_unread = true;
+    config
+        .retention
+        .days_by_kind
+        .insert(EventKind::PrMerged, 365);
+
+    poll_once(&config, &gh, &state, &notifier, &clock)
+        .await
+        .unwrap();
+
+    let calls = state.cleanup_calls.lock().unwrap().clone();
+    assert_eq!(calls.len(), 1);
+    let (policy, at) = &calls[0];
+    assert_eq!(*at, clock.now);
+    assert_eq!(policy.default_days, 90);
+    assert!(policy.keep_unread);
+    assert_eq!(policy.days_for(&EventKind::PrMerged), 365);
+    assert_eq!(policy.days_for(&EventKind::IssueCreated), 90);
+}
+
+#[tokio::test]
+async fn repo_fetch_failures_are_stored_and_reported_with_secrets_masked() {
+    let gh = FakeGh::default();
+    let state = FakeState::default();
+    let notifier = FakeNotifier::default();
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+    };
+    gh.fail_repo(
+        "acme/api",
+        "gh api -H 'Authorization: Bearer ghs_live123' https://me:pw@proxy:3128/x?token=abc failed with GH_TOKEN=github_pat_11AB_cd",
+    );
+
+    let out = poll_once(&cfg(), &gh, &state, &notifier, &clock)
+        .await
+        .unwrap();
+
+    let expected = "gh api -H 'Authorization: Bearer ***' https://***@proxy:3128/x?token=*** failed with GH_TOKEN=***";
+    assert!(out.fetch_failures[0].message.contains(expected));
+    let logged = state.failures.lock().unwrap().clone();
+    assert!(logged[0].message.contains(expected));
+    for secret in ["ghs_live123", "me:pw", "abc", "github_pat_11AB_cd"] {
+        assert!(!logged[0].message.contains(secret));
+    }
+}
+
+#[tokio::test]
+async fn repo_fetch_partial_failure_returns_success_with_failure_details() {
+    let gh = FakeGh::default();
+    let state = FakeState::default();
+    let notifier = FakeNotifier::default();
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+    };
+
+    state.set_cursor(
+        "acme/api",
+        Utc.with_ymd_and_hms(2025, 1, 19, 0, 0, 0).unwrap(),
+    );
+    state.set_cursor(
+        "acme/web",
+        Utc.with_ymd_and_hms(2025, 1, 19, 0, 0, 0).unwrap(),
+    );
+    gh.fail_repo("acme/api", "boom");
+    gh.set_events(
+        "acme/web",
+        vec![event(
+            "acme/web",
+            "ev-web-1",
+            Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+        )],
+    );
+
+    let out = poll_once(&cfg(), &gh, &state, &notifier, &clock)
+        .await
+        .expect("partial failure should still return success");
+    assert_eq!(out.notified_count, 1);
+    assert_eq!(out.fetch_failures.len(), 1);
+    assert_eq!(out.fetch_failures[0].repo, "acme/api");
+    assert!(out.fetch_failures[0].message.contains("boom"));
+
+    let logged = state.failures.lock().unwrap().clone();
+    assert_eq!(logged.len(), 1);
+    assert_eq!(logged[0].kind, FAILURE_KIND_REPO_POLL);
+    assert_eq!(logged[0].repo, "acme/api");
+    assert_eq!(logged[0].failed_at, clock.now);
+    assert_eq!(
+        logged[0].context,
+        HashMap::from([("attempt_count".to_string(), "3".to_string())])
+    );
+    assert_eq!(
+        state
+            .get_failure_count_for_repo("acme/api", clock.now - chrono::Duration::hours(1))
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        state
+            .get_failure_count_for_repo("acme/web", clock.now - chrono::Duration::hours(1))
+            .unwrap(),
+        0
+    );
+}
+
+#[tokio::test]
+async fn rate_limited_repo_failure_records_structured_context() {
+    let gh = FakeGh::default();
+    let state = FakeState::default();
+    let notifier = FakeNotifier::default();
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+    };
+    state.set_cursor(
+        "acme/api",
+        Utc.with_ymd_and_hms(2025, 1, 19, 0, 0, 0).unwrap(),
+    );
+    state.set_cursor(
+        "acme/web",
+        Utc.with_ymd_and_hms(2025, 1, 19, 0, 0, 0).unwrap(),
+    );
+    gh.rate_limit_repo(
+        "acme/api",
+        GhRateLimitError {
+            endpoint: "repos/acme/api/pulls".to_string(),
+            status_code: Some(403),
+            retry_after_seconds: Some(120),
+            stderr: "API rate limit exceeded".to_string(),
+        },
+    );
+
+    let out = poll_once(&cfg(), &gh, &state, &notifier, &clock)
+        .await
+        .expect("partial failure should still return success");
+    assert_eq!(out.fetch_failures[0].context["status_code"], "403");
+    assert_eq!(out.fetched_repos, vec!["acme/web".to_string()]);
+
+    let logged = state.failures.lock().unwrap().clone();
+    assert_eq!(logged.len(), 1);
+    assert_eq!(
+        logged[0].context,
+        HashMap::from([
+            ("endpoint".to_string(), "repos/acme/api/pulls".to_string()),
+            ("status_code".to_string(), "403".to_string()),
+            ("retry_after_seconds".to_string(), "120".to_string()),
+            ("attempt_count".to_string(), "3".to_string()),
+        ])
+    );
+}
+
+#[tokio::test]
+async fn graphql_batch_retries_repos_missing_from_the_batch_one_by_one() {
+    let gh = FakeGh::default();
+    let state = FakeState::default();
+    let notifier = FakeNotifier::default();
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+    };
+    let mut config = cfg();
+    config.poll.use_graphql_batch = true;
+
+    state.set_cursor(
+        "acme/api",
+        Utc.with_ymd_and_hms(2025, 1, 19, 0, 0, 0).unwrap(),
+    );
+    state.set_cursor(
+        "acme/web",
+        Utc.with_ymd_and_hms(2025, 1, 19, 0, 0, 0).unwrap(),
+    );
+    gh.fail_repo_n_times("acme/api", 1, "graphql boom");
+    gh.set_events(
+        "acme/web",
+        vec![event(
+            "acme/web",
+            "ev-web-1",
+            Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+        )],
+    );
+
+    let out = poll_once(&config, &gh, &state, &notifier, &clock)
+        .await
+        .unwrap();
+
+    assert!(out.fetch_failures.is_empty());
+    assert_eq!(out.notified_count, 1);
+    let attempts = gh.fetch_attempts_by_repo.lock().unwrap().clone();
+    assert_eq!(attempts.get("acme/api"), Some(&2));
+    assert_eq!(attempts.get("acme/web"), Some(&1));
+}
+
+#[tokio::test]
+async fn repo_fetch_returns_error_when_all_repositories_fail() {
+    let gh = FakeGh::default();
+    let state = FakeState::default();
+    let notifier = FakeNotifier::default();
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+    };
+
+    state.set_cursor(
+        "acme/api",
+        Utc.with_ymd_and_hms(2025, 1, 19, 0, 0, 0).unwrap(),
+    );
+    state.set_cursor(
+        "acme/web",
+        Utc.with_ymd_and_hms(2025, 1, 19, 0, 0, 0).unwrap(),
+    );
+    gh.fail_repo("acme/api", "api boom");
+    gh.fail_repo("acme/web", "web boom");
+
+    let err = poll_once(&cfg(), &gh, &state, &notifier, &clock)
+        .await
+        .expect_err("all repo failures should return error");
+    let message = err.to_string();
+    assert!(message.contains("all repository fetches failed"));
+    assert!(message.contains("acme/api"));
+    assert!(message.contains("acme/web"));
+}
+
+#[tokio::test]
+async fn repo_fetch_retries_temporary_failures_and_succeeds() {
+    let gh = FakeGh::default();
+    let state = FakeState::default();
+    let notifier = FakeNotifier::default();
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+    };
+
+    state.set_cursor(
+        "acme/api",
+        Utc.with_ymd_and_hms(2025, 1, 19, 0, 0, 0).unwrap(),
+    );
+    state.set_cursor(
+        "acme/web",
+        Utc.with_ymd_and_hms(2025, 1, 19, 0, 0, 0).unwrap(),
+    );
+    gh.fail_repo_n_times("acme/api", 2, "transient");
+    gh.set_events(
+        "acme/api",
+        vec![event(
+            "acme/api",
+            "ev-retry-1",
+            Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+        )],
+    );
+    gh.set_events("acme/web", Vec::new());
+
+    let out = poll_once(&cfg(), &gh, &state, &notifier, &clock)
+        .await
+        .expect("third attempt should succeed");
+    assert_eq!(gh.fetch_attempt_count("acme/api"), 3);
+    assert_eq!(out.fetch_failures.len(), 0);
+    assert_eq!(out.notified_count, 1);
+}
+
+#[tokio::test]
+async fn repo_fetch_runs_sequentially() {
+    let gh = FakeGh::default();
+    let state = FakeState::default();
+    let notifier = FakeNotifier::default();
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+    };
+
+    state.set_cursor(
+        "acme/api",
+        Utc.with_ymd_and_hms(2025, 1, 19, 0, 0, 0).unwrap(),
+    );
+    state.set_cursor(
+        "acme/web",
+        Utc.with_ymd_and_hms(2025, 1, 19, 0, 0, 0).unwrap(),
+    );
+    gh.set_fetch_delay_ms("acme/api", 30);
+    gh.set_fetch_delay_ms("acme/web", 30);
+    gh.set_events("acme/api", Vec::new());
+    gh.set_events("acme/web", Vec::new());
+
+    let out = poll_once(&cfg(), &gh, &state, &notifier, &clock)
+        .await
+        .expect("poll should succeed");
+    assert_eq!(out.fetch_failures.len(), 0);
+    assert_eq!(gh.max_in_flight_fetches(), 1);
+}
+
+#[tokio::test]
+async fn multiple_events_in_single_poll_send_digest_notification_once() {
+    let gh = FakeGh::default();
+    let state = FakeState::default();
+    let notifier = FakeNotifier::default();
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 21, 0, 0, 0).unwrap(),
+    };
+
+    state.set_cursor(
+        "acme/api",
+        Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+    );
+    state.set_cursor(
+        "acme/web",
+        Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+    );
+
+    let ev_latest = event(
+        "acme/api",
+        "ev-latest",
+        Utc.with_ymd_and_hms(2025, 1, 20, 0, 7, 0).unwrap(),
+    );
+    let ev_tie_api = event(
+        "acme/api",
+        "ev-tie-api",
+        Utc.with_ymd_and_hms(2025, 1, 20, 0, 6, 0).unwrap(),
+    );
+    let ev_tie_web = event(
+        "acme/web",
+        "ev-tie-web",
+        Utc.with_ymd_and_hms(2025, 1, 20, 0, 6, 0).unwrap(),
+    );
+
+    gh.set_events("acme/api", vec![ev_tie_api.clone(), ev_latest.clone()]);
+    gh.set_events("acme/web", vec![ev_tie_web.clone()]);
+
+    let out = poll_once(&cfg(), &gh, &state, &notifier, &clock)
+        .await
+        .expect("poll with multiple events should succeed");
+
+    let expected_keys = vec![
+        ev_latest.event_key(),
+        ev_tie_api.event_key(),
+        ev_tie_web.event_key(),
+    ];
+    let notified_keys = out
+        .notified_events
+        .iter()
+        .map(|event| event.event_key())
+        .collect::<Vec<_>>();
+    assert_eq!(out.notified_count, 1);
+    assert_eq!(notified_keys, expected_keys);
+
+    let sent = notifier.sent();
+    assert_eq!(sent.len(), 1);
+    match &sent[0] {
+        NotificationPayload::Digest(digest) => {
+            assert_eq!(digest.total_events, 3);
+            let sample_keys = digest
+                .sample_events
+                .iter()
+                .map(|event| event.event_key())
+                .collect::<Vec<_>>();
+            assert_eq!(sample_keys, expected_keys);
+        }
+        NotificationPayload::Event(_)
+        | NotificationPayload::Alert(_)
+        | NotificationPayload::Summary(_)
+        | NotificationPayload::ReviewReminderDue(_) => {
+            panic!("expected digest payload for multiple events")
+        }
+    }
+}
+
+#[tokio::test]
+async fn notification_failure_returns_error() {
+    let gh = FakeGh::default();
+    let state = FakeState::default();
+    let notifier = FakeNotifier::default();
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 21, 0, 0, 0).unwrap(),
+    };
+
+    let ev = event(
+        "acme/api",
+        "ev-3",
+        Utc.with_ymd_and_hms(2025, 1, 21, 0, 0, 0).unwrap(),
+    );
+    state.set_cursor(
+        "acme/api",
+        Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+    );
+    state.set_cursor(
+        "acme/web",
+        Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+    );
+    gh.set_events("acme/api", vec![ev.clone()]);
+    gh.set_events("acme/web", Vec::new());
+    notifier.fail_once_for_event(&ev.event_key());
+
+    let err = poll_once(&cfg(), &gh, &state, &notifier, &clock)
+        .await
+        .expect_err("notification should fail");
+    assert!(err.to_string().contains("notification failed for"));
+}
+
+#[tokio::test]
+async fn digest_notification_failure_returns_error() {
+    let gh = FakeGh::default();
+    let state = FakeState::default();
+    let notifier = FakeNotifier::default();
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 21, 0, 30, 0).unwrap(),
+    };
+
+    state.set_cursor(
+        "acme/api",
+        Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+    );
+    state.set_cursor(
+        "acme/web",
+        Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+    );
+
+    gh.set_events(
+        "acme/api",
+        vec![event(
+            "acme/api",
+            "ev-digest-a",
+            Utc.with_ymd_and_hms(2025, 1, 21, 0, 10, 0).unwrap(),
+        )],
+    );
+    gh.set_events(
+        "acme/web",
+        vec![event(
+            "acme/web",
+            "ev-digest-b",
+            Utc.with_ymd_and_hms(2025, 1, 21, 0, 11, 0).unwrap(),
+        )],
+    );
+    notifier.fail_digest_once();
+
+    let err = poll_once(&cfg(), &gh, &state, &notifier, &clock)
+        .await
+        .expect_err("digest notification should fail");
+    assert!(err.to_string().contains("digest notification failed"));
+}
+
+#[tokio::test]
+async fn cursor_failure_returns_error() {
+    let gh = FakeGh::default();
+    let state = FakeState::default();
+    let notifier = FakeNotifier::default();
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 22, 0, 0, 0).unwrap(),
+    };
+
+    state.fail_cursor_for_repo("acme/api");
+    let err = poll_once(&cfg(), &gh, &state, &notifier, &clock)
+        .await
+        .expect_err("cursor load should fail");
+    let msg = format!("{err:#}");
+    assert!(msg.contains("failed to load polling cursors"));
+    assert!(msg.contains("cursor read failed for acme/api"));
+}
+
+#[tokio::test]
+async fn planning_loads_all_cursors_with_a_single_query() {
+    let gh = FakeGh::default();
+    let state = QueryCountingStateStore::default();
+    let notifier = FakeNotifier::default();
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 22, 0, 0, 0).unwrap(),
+    };
+    let mut config = cfg();
+    config.repositories = (0..50)
+        .map(|index| RepositoryConfig {
+            name: format!("acme/repo-{index}"),
+            enabled: true,
+            event_kinds: None,
+            local_path: None,
+            watch_review_threads: false,
+            account: None,
+            gh_profile: None,
+            path_filters: None,
+            topic_tags: None,
+            notification_kinds: HashMap::new(),
+        })
+        .collect();
+    for repo in &config.repositories {
+        state.inner.set_cursor(
+            &repo.name,
+            Utc.with_ymd_and_hms(2025, 1, 21, 0, 0, 0).unwrap(),
+        );
+    }
+
+    poll_once(&config, &gh, &state, &notifier, &clock)
+        .await
+        .unwrap();
+
+    assert_eq!(state.count("load_cursors_all"), 1);
+    assert_eq!(state.count("get_cursor"), 0);
+    assert_eq!(state.count("persist_repo_batch"), 50);
+}
+
+#[tokio::test]
+async fn persist_failure_returns_error() {
+    let gh = FakeGh::default();
+    let state = FakeState::default();
+    let notifier = FakeNotifier::default();
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 23, 0, 0, 0).unwrap(),
+    };
+
+    state.set_cursor(
+        "acme/api",
+        Utc.with_ymd_and_hms(2025, 1, 22, 0, 0, 0).unwrap(),
+    );
+    state.set_cursor(
+        "acme/web",
+        Utc.with_ymd_and_hms(2025, 1, 22, 0, 0, 0).unwrap(),
+    );
+    state.fail_persist_for_repo("acme/api");
+    gh.set_events(
+        "acme/api",
+        vec![event(
+            "acme/api",
+            "ev-4",
+            Utc.with_ymd_and_hms(2025, 1, 23, 0, 0, 0).unwrap(),
+        )],
+    );
+    gh.set_events("acme/web", Vec::new());
+
+    let err = poll_once(&cfg(), &gh, &state, &notifier, &clock)
+        .await
+        .expect_err("persist should fail");
+    assert!(err
+        .to_string()
+        .contains("failed to persist event batch for acme/api"));
+}
+
+fn review_threads(resolved: bool) -> ReviewThreadFetch {
+    let resolved_events = if resolved {
+        vec![WatchEvent {
+            event_id: "review-thread-resolved:PRRT_1".to_string(),
+            kind: EventKind::ReviewThreadResolved,
+            source_item_id: "PRRT_1".to_string(),
+            ..event(
+                "acme/api",
+                "PRRT_1",
+                Utc.with_ymd_and_hms(2025, 1, 20, 0, 5, 0).unwrap(),
+            )
+        }]
+    } else {
+        Vec::new()
+    };
+    ReviewThreadFetch {
+        states: vec![ReviewThreadState {
+            thread_id: "PRRT_1".to_string(),
+            is_resolved: resolved,
+        }],
+        resolved_events,
+    }
+}
+
+#[tokio::test]
+async fn review_threads_are_fetched_only_for_opted_in_repos() {
+    let gh = FakeGh::default();
+    let state = FakeState::default();
+    let notifier = FakeNotifier::default();
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 20, 0, 10, 0).unwrap(),
+    };
+    let mut config = cfg();
+    config.repositories[0].watch_review_threads = true;
+
+    poll_once(&config, &gh, &state, &notifier, &clock)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        *gh.review_thread_fetches.lock().unwrap(),
+        vec!["acme/api".to_string()]
+    );
+}
+
+#[tokio::test]
+async fn resolved_review_thread_notifies_once_after_transition() {
+    let dir = tempfile::tempdir().unwrap();
+    let state =
+        gh_watch::infra::state_sqlite::SqliteStateStore::new(dir.path().join("state.db")).unwrap();
+    let gh = FakeGh::default();
+    let notifier = FakeNotifier::default();
+    let mut config = cfg();
+    config.repositories.truncate(1);
+    config.repositories[0].watch_review_threads = true;
+    let poll_at = |minute| FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 20, 0, minute, 0).unwrap(),
+    };
+    state
+        .set_cursor(
+            "acme/api",
+            Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+        )
+        .unwrap();
+
+    gh.set_review_threads("acme/api", review_threads(false));
+    poll_once(&config, &gh, &state, &notifier, &poll_at(10))
+        .await
+        .unwrap();
+    assert!(notifier.sent().is_empty());
+
+    gh.set_review_threads("acme/api", review_threads(true));
+    let out = poll_once(&config, &gh, &state, &notifier, &poll_at(20))
+        .await
+        .unwrap();
+    assert_eq!(out.notified_events.len(), 1);
+    assert_eq!(out.notified_events[0].kind, EventKind::ReviewThreadResolved);
+    assert_eq!(notifier.sent().len(), 1);
+
+    let out = poll_once(&config, &gh, &state, &notifier, &poll_at(30))
+        .await
+        .unwrap();
+    assert!(out.notified_events.is_empty());
+    assert_eq!(notifier.sent().len(), 1);
+}
+
+#[tokio::test]
+async fn pending_review_request_notifies_once_across_pull_request_updates() {
+    let dir = tempfile::tempdir().unwrap();
+    let state =
+        gh_watch::infra::state_sqlite::SqliteStateStore::new(dir.path().join("state.db")).unwrap();
+    let gh = FakeGh::default();
+    let notifier = FakeNotifier::default();
+    let mut config = cfg();
+    config.repositories.truncate(1);
+    config
+        .retention
+        .days_by_kind
+        .insert(EventKind::PrReviewRequested, 7);
+    let first_poll = Utc.with_ymd_and_hms(2025, 1, 20, 0, 10, 0).unwrap();
+    let second_poll = Utc.with_ymd_and_hms(2025, 1, 30, 0, 10, 0).unwrap();
+    // Review request events carry the pull request's updated_at, not the request time.
+    let request = |pr_updated_at| WatchEvent {
+        event_id: "pr-review-requested:7:alice".to_string(),
+        kind: EventKind::PrReviewRequested,
+        source_item_id: "7:alice".to_string(),
+        requested_reviewer: Some("alice".to_string()),
+        ..event("acme/api", "pr-review-requested:7:alice", pr_updated_at)
+    };
+    state
+        .set_cursor(
+            "acme/api",
+            Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+        )
+        .unwrap();
+
+    gh.set_events(
+        "acme/api",
+        vec![request(first_poll - chrono::Duration::minutes(5))],
+    );
+    let out = poll_once(
+        &config,
+        &gh,
+        &state,
+        &notifier,
+        &FixedClock { now: first_poll },
+    )
+    .await
+    .unwrap();
+    assert_eq!(out.notified_count, 1);
+
+    // Retention drops the logged request before the pull request is updated again.
+    gh.set_events(
+        "acme/api",
+        vec![request(second_poll - chrono::Duration::minutes(5))],
+    );
+    let out = poll_once(
+        &config,
+        &gh,
+        &state,
+        &notifier,
+        &FixedClock { now: second_poll },
+    )
+    .await
+    .unwrap();
+    assert_eq!(out.notified_count, 0);
+    assert_eq!(notifier.sent().len(), 1);
+}
+
+#[tokio::test]
+async fn review_request_notifies_again_once_withdrawn_and_made_again() {
+    let dir = tempfile::tempdir().unwrap();
+    let state =
+        gh_watch::infra::state_sqlite::SqliteStateStore::new(dir.path().join("state.db")).unwrap();
+    let gh = FakeGh::default();
+    let notifier = FakeNotifier::default();
+    let mut config = cfg();
+    config.repositories.truncate(1);
+    let poll_at = |minute| FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 20, 0, minute, 0).unwrap(),
+    };
+    let request = |minute| WatchEvent {
+        event_id: "pr-review-requested:7:alice".to_string(),
+        kind: EventKind::PrReviewRequested,
+        source_item_id: "7:alice".to_string(),
+        requested_reviewer: Some("alice".to_string()),
+        ..event(
+            "acme/api",
+            "pr-review-requested:7:alice",
+            Utc.with_ymd_and_hms(2025, 1, 20, 0, minute, 0).unwrap(),
+        )
+    };
+    let pending = |reviewers: &[&str]| {
+        vec![PendingReviewRequests {
+            pull_id: "7".to_string(),
+            reviewers: reviewers.iter().map(|login| login.to_string()).collect(),
+        }]
+    };
+    state
+        .set_cursor(
+            "acme/api",
+            Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+        )
+        .unwrap();
+
+    gh.set_events("acme/api", vec![request(1)]);
+    gh.set_review_requests("acme/api", pending(&["alice"]));
+    let out = poll_once(&config, &gh, &state, &notifier, &poll_at(10))
+        .await
+        .unwrap();
+    assert_eq!(out.notified_count, 1);
+
+    // The request is withdrawn: the pull request was updated and lists no reviewer.
+    gh.set_events("acme/api", Vec::new());
+    gh.set_review_requests("acme/api", pending(&[]));
+    poll_once(&config, &gh, &state, &notifier, &poll_at(20))
+        .await
+        .unwrap();
+
+    gh.set_events("acme/api", vec![request(25)]);
+    gh.set_review_requests("acme/api", pending(&["alice"]));
+    let out = poll_once(&config, &gh, &state, &notifier, &poll_at(30))
+        .await
+        .unwrap();
+    assert_eq!(out.notified_count, 1);
+    assert_eq!(notifier.sent().len(), 2);
+    let logged = state.load_timeline_events(10).unwrap();
+    assert_eq!(logged.len(), 1);
+    assert_eq!(
+        logged[0].created_at,
+        Utc.with_ymd_and_hms(2025, 1, 20, 0, 25, 0).unwrap()
+    );
+}
+
+fn pr_comment(id: &str, pr_number: i64, created_at: chrono::DateTime<Utc>) -> WatchEvent {
+    WatchEvent {
+        url: format!("https://github.com/acme/api/pull/{pr_number}#{id}"),
+        subject_number: Some(pr_number),
+        subject_url: Some(format!("https://github.com/acme/api/pull/{pr_number}")),
+        ..event("acme/api", id, created_at)
+    }
+}
+
+#[tokio::test]
+async fn path_filters_skip_pull_requests_that_touch_no_matching_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let state =
+        gh_watch::infra::state_sqlite::SqliteStateStore::new(dir.path().join("state.db")).unwrap();
+    let gh = FakeGh::default();
+    let notifier = FakeNotifier::default();
+    let mut config = cfg();
+    config.repositories.truncate(1);
+    config.filters.global_path_filters = vec!["docs/**".to_string()];
+    config.repositories[0].path_filters = Some(vec!["src/**".to_string()]);
+    let poll_at = |minute| FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 20, 0, minute, 0).unwrap(),
+    };
+    state
+        .set_cursor(
+            "acme/api",
+            Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+        )
+        .unwrap();
+    gh.set_pr_files(1, &["docs/guide.md", "README.md"]);
+    gh.set_pr_files(2, &["src/lib.rs"]);
+    let at = |minute| Utc.with_ymd_and_hms(2025, 1, 20, 0, minute, 0).unwrap();
+
+    gh.set_events(
+        "acme/api",
+        vec![
+            pr_comment("c-1", 1, at(1)),
+            pr_comment("c-2", 1, at(2)),
+            pr_comment("c-3", 2, at(3)),
+            event("acme/api", "issue-comment", at(4)),
+        ],
+    );
+    let out = poll_once(&config, &gh, &state, &notifier, &poll_at(10))
+        .await
+        .unwrap();
+    let mut notified = out
+        .notified_events
+        .iter()
+        .map(|event| event.event_id.as_str())
+        .collect::<Vec<_>>();
+    notified.sort_unstable();
+    assert_eq!(notified, ["c-3", "issue-comment"]);
+    // Skipped events still reach the timeline.
+    assert_eq!(out.timeline_events.len(), 4);
+    assert_eq!(gh.pr_file_fetches(), [1, 2]);
+
+    // The next poll reuses the files cached in the state db.
+    gh.set_events("acme/api", vec![pr_comment("c-4", 1, at(12))]);
+    let out = poll_once(&config, &gh, &state, &notifier, &poll_at(20))
+        .await
+        .unwrap();
+    assert!(out.notified_events.is_empty());
+    assert_eq!(gh.pr_file_fetches(), [1, 2]);
+}
+
+fn my_pr(decision: &str, checks: &str) -> MyPrFetch {
+    let ready_event = |kind: EventKind| WatchEvent {
+        event_id: format!("{kind}:7:abc123"),
+        kind,
+        source_item_id: "7:abc123".to_string(),
+        subject_number: Some(7),
+        // Later than the poll start; the poll clamps it.
+        ..event(
+            "acme/api",
+            "7",
+            Utc.with_ymd_and_hms(2025, 1, 21, 0, 0, 0).unwrap(),
+        )
+    };
+    let mut events = Vec::new();
+    if decision == "APPROVED" {
+        events.push(ready_event(EventKind::MyPrApproved));
+    }
+    if checks == "SUCCESS" {
+        events.push(ready_event(EventKind::MyPrChecksPassed));
+    }
+    MyPrFetch {
+        states: vec![MyPrState {
+            pr_number: 7,
+            review_decision: Some(decision.to_string()),
+            checks_state: Some(checks.to_string()),
+        }],
+        events,
+    }
+}
+
+#[tokio::test]
+async fn my_pr_readiness_notifies_each_transition_once_outside_the_digest() {
+    let dir = tempfile::tempdir().unwrap();
+    let state =
+        gh_watch::infra::state_sqlite::SqliteStateStore::new(dir.path().join("state.db")).unwrap();
+    let gh = FakeGh::default();
+    let notifier = FakeNotifier::default();
+    let mut config = cfg();
+    config.repositories.truncate(1);
+    config.watch_my_prs = true;
+    let poll_at = |minute| FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 20, 0, minute, 0).unwrap(),
+    };
+    state
+        .set_cursor(
+            "acme/api",
+            Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+        )
+        .unwrap();
+
+    gh.set_my_prs("acme/api", my_pr("REVIEW_REQUIRED", "PENDING"));
+    poll_once(&config, &gh, &state, &notifier, &poll_at(10))
+        .await
+        .unwrap();
+    assert!(notifier.sent().is_empty());
+
+    gh.set_my_prs("acme/api", my_pr("APPROVED", "SUCCESS"));
+    gh.set_events(
+        "acme/api",
+        vec![
+            event(
+                "acme/api",
+                "ev-1",
+                Utc.with_ymd_and_hms(2025, 1, 20, 0, 12, 0).unwrap(),
+            ),
+            event(
+                "acme/api",
+                "ev-2",
+                Utc.with_ymd_and_hms(2025, 1, 20, 0, 13, 0).unwrap(),
+            ),
+        ],
+    );
+    let out = poll_once(&config, &gh, &state, &notifier, &poll_at(20))
+        .await
+        .unwrap();
+    assert_eq!(out.notified_events.len(), 4);
+    assert_eq!(out.notified_count, 3);
+    let sent = notifier.sent();
+    let urgent_kinds = sent
+        .iter()
+        .filter_map(|payload| match payload {
+            NotificationPayload::Event(event) => Some(event.kind.clone()),
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+    assert_eq!(
+        urgent_kinds,
+        HashSet::from([EventKind::MyPrApproved, EventKind::MyPrChecksPassed])
+    );
+    assert!(matches!(
+        sent.last(),
+        Some(NotificationPayload::Digest(digest)) if digest.total_events == 2
+    ));
+
+    let out = poll_once(&config, &gh, &state, &notifier, &poll_at(30))
+        .await
+        .unwrap();
+    assert!(out.notified_events.is_empty());
+    assert_eq!(notifier.sent().len(), 3);
+}
+
+#[tokio::test]
+async fn review_thread_first_seen_resolved_is_not_notified() {
+    let dir = tempfile::tempdir().unwrap();
+    let state =
+        gh_watch::infra::state_sqlite::SqliteStateStore::new(dir.path().join("state.db")).unwrap();
+    let gh = FakeGh::default();
+    let notifier = FakeNotifier::default();
+    let mut config = cfg();
+    config.repositories.truncate(1);
+    config.repositories[0].watch_review_threads = true;
+    state
+        .set_cursor(
+            "acme/api",
+            Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+        )
+        .unwrap();
+    gh.set_review_threads("acme/api", review_threads(true));
+
+    let out = poll_once(
+        &config,
+        &gh,
+        &state,
+        &notifier,
+        &FixedClock {
+            now: Utc.with_ymd_and_hms(2025, 1, 20, 0, 10, 0).unwrap(),
+        },
+    )
+    .await
+    .unwrap();
+
+    assert!(out.timeline_events.is_empty());
+    assert!(notifier.sent().is_empty());
+}
+
+#[tokio::test]
+async fn pushed_events_notify_once_and_leave_the_polling_cursor_alone() {
+    let gh = FakeGh::default();
+    let state = FakeState::default();
+    let notifier = FakeNotifier::default();
+    let cursor = Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap();
+    let pushed_at = Utc.with_ymd_and_hms(2025, 1, 20, 0, 6, 0).unwrap();
+    state.set_cursor("acme/api", cursor);
+    state.set_cursor("acme/web", cursor);
+
+    let pushed = event(
+        "Acme/API",
+        "ev-2",
+        Utc.with_ymd_and_hms(2025, 1, 20, 0, 5, 0).unwrap(),
+    );
+    let out = ingest_pushed_events(
+        &cfg(),
+        &state,
+        &notifier,
+        None,
+        Vec::new(),
+        Vec::new(),
+        vec![pushed.clone()],
+        pushed_at,
+    )
+    .unwrap();
+
+    assert_eq!(out.timeline_events.len(), 1);
+    assert_eq!(out.timeline_events[0].repo, "acme/api");
+    assert_eq!(out.notified_count, 1);
+    assert_eq!(
+        state.cursors.lock().unwrap().get("acme/api").copied(),
+        Some(cursor)
+    );
+
+    // The next poll fetches the same activity and stays quiet about it.
+    gh.set_events(
+        "acme/api",
+        vec![WatchEvent {
+            repo: "acme/api".to_string(),
+            ..pushed
+        }],
+    );
+    gh.set_events("acme/web", Vec::new());
+    let clock = FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 20, 0, 10, 0).unwrap(),
+    };
+    let out = poll_once(&cfg(), &gh, &state, &notifier, &clock)
+        .await
+        .unwrap();
+    assert_eq!(out.notified_count, 0);
+    assert_eq!(notifier.sent().len(), 1);
+}
+
+#[tokio::test]
+async fn pushed_events_of_unpolled_or_unwatched_repos_are_dropped() {
+    let state = FakeState::default();
+    let notifier = FakeNotifier::default();
+    let mut config = cfg();
+    config.repositories[1].enabled = false;
+    let now = Utc.with_ymd_and_hms(2025, 1, 20, 0, 10, 0).unwrap();
+    state.set_cursor("acme/web", now);
+
+    let out = ingest_pushed_events(
+        &config,
+        &state,
+        &notifier,
+        None,
+        Vec::new(),
+        Vec::new(),
+        vec![
+            event("acme/api", "not-bootstrapped", now),
+            event("acme/web", "disabled", now),
+            event("acme/other", "unwatched", now),
+        ],
+        now,
+    )
+    .unwrap();
+
+    assert!(out.timeline_events.is_empty());
+    assert!(state.event_log.lock().unwrap().is_empty());
+    assert!(notifier.sent().is_empty());
+    assert!(!state.cursors.lock().unwrap().contains_key("acme/api"));
+}
+
+#[tokio::test]
+async fn notified_events_record_when_they_were_notified() {
+    let dir = tempfile::tempdir().unwrap();
+    let state =
+        gh_watch::infra::state_sqlite::SqliteStateStore::new(dir.path().join("state.db")).unwrap();
+    let gh = FakeGh::default();
+    let notifier = FakeNotifier::default();
+    let mut config = cfg();
+    config.repositories.truncate(1);
+    let polled_at = Utc.with_ymd_and_hms(2025, 1, 20, 0, 10, 0).unwrap();
+    let created_at = polled_at - chrono::Duration::minutes(4);
+    state
+        .set_cursor(
+            "acme/api",
+            Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+        )
+        .unwrap();
+    gh.set_events("acme/api", vec![event("acme/api", "c1", created_at)]);
+
+    poll_once(
+        &config,
+        &gh,
+        &state,
+        &notifier,
+        &FixedClock { now: polled_at },
+    )
+    .await
+    .unwrap();
+
+    let timings = state
+        .load_notification_timings(polled_at - chrono::Duration::days(7))
+        .unwrap();
+    assert_eq!(timings.len(), 1);
+    assert_eq!(timings[0].created_at, created_at);
+    assert_eq!(timings[0].notified_at, polled_at);
+}
+
+#[tokio::test]
+async fn events_logged_without_a_notification_have_no_notification_time() {
+    let dir = tempfile::tempdir().unwrap();
+    let state =
+        gh_watch::infra::state_sqlite::SqliteStateStore::new(dir.path().join("state.db")).unwrap();
+    let gh = FakeGh::default();
+    let notifier = FakeNotifier::default();
+    let mut config = cfg();
+    config.repositories.truncate(1);
+    config.notifications.enabled = false;
+    let polled_at = Utc.with_ymd_and_hms(2025, 1, 20, 0, 10, 0).unwrap();
+    state
+        .set_cursor(
+            "acme/api",
+            Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap(),
+        )
+        .unwrap();
+    gh.set_events(
+        "acme/api",
+        vec![event(
+            "acme/api",
+            "c1",
+            polled_at - chrono::Duration::minutes(4),
+        )],
+    );
+
+    let out = poll_once(
+        &config,
+        &gh,
+        &state,
+        &notifier,
+        &FixedClock { now: polled_at },
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(out.timeline_events.len(), 1);
+    assert!(state
+        .load_notification_timings(polled_at - chrono::Duration::days(7))
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn renamed_repository_is_polled_under_its_new_name_with_its_state() {
+    let dir = tempfile::tempdir().unwrap();
+    let state =
+        gh_watch::infra::state_sqlite::SqliteStateStore::new(dir.path().join("state.db")).unwrap();
+    let gh = FakeGh::default();
+    let notifier = FakeNotifier::default();
+    let config = cfg();
+    let poll_at = |minute| FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 20, 0, minute, 0).unwrap(),
+    };
+    let cursor = Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap();
+    state.set_cursor("acme/api", cursor).unwrap();
+    state.set_cursor("acme/web", cursor).unwrap();
+    gh.fail_repo("acme/api", "gh: Moved Permanently (HTTP 301)");
+    gh.set_repo_access(
+        "acme/api",
+        RepoAccess::Renamed {
+            new_name: "acme/platform".to_string(),
+        },
+    );
+
+    let out = poll_once(&config, &gh, &state, &notifier, &poll_at(10))
+        .await
+        .unwrap();
+    assert_eq!(out.fetch_failures.len(), 1);
+    assert!(out.fetch_failures[0]
+        .message
+        .contains("renamed to acme/platform"));
+    assert_eq!(out.fetch_failures[0].context["renamed_to"], "acme/platform");
+    assert_eq!(state.get_cursor("acme/platform").unwrap(), Some(cursor));
+    assert_eq!(state.get_cursor("acme/api").unwrap(), None);
+
+    gh.set_events(
+        "acme/platform",
+        vec![event(
+            "acme/platform",
+            "ev-renamed-1",
+            Utc.with_ymd_and_hms(2025, 1, 20, 0, 15, 0).unwrap(),
+        )],
+    );
+    let out = poll_once(&config, &gh, &state, &notifier, &poll_at(20))
+        .await
+        .unwrap();
+    assert!(out.fetch_failures.is_empty());
+    assert_eq!(out.fetched_repos, vec!["acme/platform", "acme/web"]);
+    assert_eq!(out.notified_events.len(), 1);
+    assert_eq!(gh.fetch_attempt_count("acme/api"), 3);
+}
+
+#[tokio::test]
+async fn inaccessible_repository_alerts_once_and_is_skipped_until_retry() {
+    let dir = tempfile::tempdir().unwrap();
+    let state =
+        gh_watch::infra::state_sqlite::SqliteStateStore::new(dir.path().join("state.db")).unwrap();
+    let gh = FakeGh::default();
+    let notifier = FakeNotifier::default();
+    let config = cfg();
+    let poll_at = |hour, minute| FixedClock {
+        now: Utc.with_ymd_and_hms(2025, 1, 20, hour, minute, 0).unwrap(),
+    };
+    let cursor = Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap();
+    state.set_cursor("acme/api", cursor).unwrap();
+    state.set_cursor("acme/web", cursor).unwrap();
+    gh.fail_repo("acme/api", "gh: Not Found (HTTP 404)");
+    gh.set_repo_access("acme/api", RepoAccess::Inaccessible { status_code: 404 });
+    let alerts = |notifier: &FakeNotifier| {
+        notifier
+            .sent()
+            .into_iter()
+            .filter(|payload| matches!(payload, NotificationPayload::Alert(_)))
+            .collect::<Vec<_>>()
+    };
+
+    let out = poll_once(&config, &gh, &state, &notifier, &poll_at(0, 10))
+        .await
+        .unwrap();
+    assert_eq!(out.fetch_failures[0].context["status_code"], "404");
+    assert_eq!(
+        alerts(&notifier),
+        vec![NotificationPayload::Alert(
+            "acme/api is no longer accessible".to_string()
+        )]
+    );
+
+    let out = poll_once(&config, &gh, &state, &notifier, &poll_at(0, 40))
+        .await
+        .unwrap();
+    assert!(out.fetch_failures.is_empty());
+    assert_eq!(out.fetched_repos, vec!["acme/web"]);
+    assert_eq!(gh.fetch_attempt_count("acme/api"), 3);
+
+    poll_once(&config, &gh, &state, &notifier, &poll_at(1, 15))
+        .await
+        .unwrap();
+    assert_eq!(gh.fetch_attempt_count("acme/api"), 6);
+    assert_eq!(alerts(&notifier).len(), 1);
+
+    gh.fail_repos.lock().unwrap().clear();
+    poll_once(&config, &gh, &state, &notifier, &poll_at(2, 20))
+        .await
+        .unwrap();
+    assert!(state.load_repo_access_marks().unwrap().is_empty());
 }
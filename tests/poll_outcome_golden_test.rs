@@ -0,0 +1,139 @@
+//! Contract for `once --json` and `--output-file` consumers. A failure here means the JSON
+//! shape changed: update `fixtures/poll_outcome_v1.json` (or add a v2) and these tests in
+//! the same change, as a record of the break.
+
+use std::collections::BTreeSet;
+
+use chrono::{TimeZone, Utc};
+use gh_watch::app::poll_once::{PollOutcome, RepoFetchFailure};
+use gh_watch::domain::events::{EventKind, WatchEvent};
+use serde_json::Value;
+
+const POLL_OUTCOME_V1: &str = include_str!("fixtures/poll_outcome_v1.json");
+
+const REQUIRED_KEYS: [&str; 12] = [
+    "polled_at",
+    "config_path",
+    "notified_count",
+    "stale_skipped_count",
+    "bootstrap_repos",
+    "notified_events",
+    "timeline_events",
+    "fetched_repos",
+    "fetch_failures",
+    "api_calls",
+    "response_cache_hits",
+    "rate_limit_remaining",
+];
+
+const EVENT_KEYS: [&str; 14] = [
+    "event_id",
+    "repo",
+    "kind",
+    "actor",
+    "title",
+    "url",
+    "created_at",
+    "source_item_id",
+    "subject_author",
+    "requested_reviewer",
+    "mentions",
+    "subject_number",
+    "subject_url",
+    "account",
+];
+
+fn keys(value: &Value) -> BTreeSet<&str> {
+    value
+        .as_object()
+        .expect("expected a JSON object")
+        .keys()
+        .map(String::as_str)
+        .collect()
+}
+
+#[test]
+fn v1_fixture_deserializes_with_every_field() {
+    let outcome: PollOutcome = serde_json::from_str(POLL_OUTCOME_V1).unwrap();
+
+    assert_eq!(
+        outcome.polled_at,
+        Utc.with_ymd_and_hms(2025, 1, 20, 0, 10, 0).unwrap()
+    );
+    assert_eq!(outcome.config_path, "/home/me/.config/gh-watch/config.toml");
+    assert_eq!(outcome.notified_count, 1);
+    assert_eq!(outcome.stale_skipped_count, 2);
+    assert_eq!(outcome.bootstrap_repos, 1);
+    assert_eq!(outcome.notified_events.len(), 1);
+    let event = &outcome.timeline_events[0];
+    assert_eq!(event.kind, EventKind::IssueCommentCreated);
+    assert_eq!(event.subject_number, Some(12));
+    assert_eq!(event.mentions, vec!["alice".to_string()]);
+    assert_eq!(outcome.fetched_repos, vec!["acme/api", "acme/docs"]);
+    assert_eq!(outcome.fetch_failures[0].repo, "acme/web");
+    assert_eq!(outcome.fetch_failures[0].context["attempt_count"], "3");
+    assert_eq!(outcome.api_calls, 18);
+    assert_eq!(outcome.response_cache_hits, 0);
+    assert_eq!(outcome.rate_limit_remaining, Some(4200));
+}
+
+#[test]
+fn v1_fixture_round_trips_unchanged() {
+    let fixture: Value = serde_json::from_str(POLL_OUTCOME_V1).unwrap();
+    let outcome: PollOutcome = serde_json::from_value(fixture.clone()).unwrap();
+
+    // Catches removed fields too, which deserializing alone would silently ignore.
+    assert_eq!(serde_json::to_value(&outcome).unwrap(), fixture);
+}
+
+#[test]
+fn serialized_outcome_has_the_required_keys() {
+    let created_at = Utc.with_ymd_and_hms(2025, 1, 20, 0, 5, 0).unwrap();
+    let event = WatchEvent {
+        event_id: "pr:1".to_string(),
+        repo: "acme/api".to_string(),
+        kind: EventKind::PrCreated,
+        actor: "bob".to_string(),
+        title: "Add endpoint".to_string(),
+        url: "https://github.com/acme/api/pull/1".to_string(),
+        created_at,
+        source_item_id: "1".to_string(),
+        subject_author: Some("bob".to_string()),
+        requested_reviewer: None,
+        mentions: Vec::new(),
+        team_mentions: Vec::new(),
+        subject_number: Some(1),
+        subject_url: None,
+        account: None,
+        body: None,
+    };
+    let outcome = PollOutcome {
+        polled_at: created_at,
+        notified_count: 1,
+        notified_events: vec![event.clone()],
+        timeline_events: vec![event],
+        fetched_repos: vec!["acme/api".to_string()],
+        fetch_failures: vec![RepoFetchFailure {
+            repo: "acme/web".to_string(),
+            message: "boom".to_string(),
+            ..RepoFetchFailure::default()
+        }],
+        ..PollOutcome::default()
+    };
+
+    let json = serde_json::to_value(&outcome).unwrap();
+
+    assert_eq!(keys(&json), BTreeSet::from(REQUIRED_KEYS));
+    assert_eq!(
+        keys(&json["timeline_events"][0]),
+        BTreeSet::from(EVENT_KEYS)
+    );
+    assert_eq!(json["notified_events"][0]["kind"], "pr_created");
+    assert_eq!(json["polled_at"], "2025-01-20T00:05:00Z");
+    // An empty failure context is left out.
+    assert_eq!(
+        keys(&json["fetch_failures"][0]),
+        BTreeSet::from(["repo", "message"])
+    );
+    assert!(json["rate_limit_remaining"].is_null());
+}
@@ -0,0 +1,82 @@
+#![cfg(unix)]
+
+use std::fs;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use predicates::{prelude::PredicateBooleanExt, str::contains};
+use tempfile::tempdir;
+
+#[test]
+fn profile_init_config_path_and_state_db_are_isolated_per_profile() {
+    let home = tempdir().unwrap();
+    let work_config = home
+        .path()
+        .join(".config")
+        .join("gh-watch")
+        .join("work")
+        .join("config.toml");
+
+    let mut init = cargo_bin_cmd!("gh-watch");
+    init.args(["--profile", "work", "init"])
+        .env("HOME", home.path())
+        .env_remove("GH_WATCH_CONFIG")
+        .assert()
+        .success();
+    assert!(work_config.exists());
+
+    let mut path = cargo_bin_cmd!("gh-watch");
+    path.args(["config", "path", "--profile", "work"])
+        .env("HOME", home.path())
+        .env_remove("GH_WATCH_CONFIG")
+        .assert()
+        .success()
+        .stdout(contains(work_config.to_string_lossy().to_string()))
+        .stdout(contains("source: profile"));
+
+    let mut vacuum = cargo_bin_cmd!("gh-watch");
+    vacuum
+        .args(["state", "vacuum"])
+        .env("HOME", home.path())
+        .env("GH_WATCH_PROFILE", "work")
+        .env_remove("GH_WATCH_CONFIG")
+        .assert()
+        .success()
+        .stdout(contains(".local/share/gh-watch/work/state.db"));
+}
+
+#[test]
+fn profile_list_enumerates_profiles_and_marks_active_one() {
+    let home = tempdir().unwrap();
+    for name in ["personal", "work"] {
+        let dir = home.path().join(".config").join("gh-watch").join(name);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("config.toml"),
+            "[[repositories]]\nname = \"acme/api\"\n",
+        )
+        .unwrap();
+    }
+    fs::create_dir_all(home.path().join(".config/gh-watch/empty")).unwrap();
+
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.args(["profile", "list"])
+        .env("HOME", home.path())
+        .env("GH_WATCH_PROFILE", "work")
+        .assert()
+        .success()
+        .stdout(contains("  personal"))
+        .stdout(contains("* work"))
+        .stdout(contains("empty").not());
+}
+
+#[test]
+fn profile_rejects_path_like_names() {
+    let home = tempdir().unwrap();
+
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.args(["--profile", "../escape", "config", "path"])
+        .env("HOME", home.path())
+        .assert()
+        .failure()
+        .stderr(contains("invalid profile name"));
+}
@@ -0,0 +1,38 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use predicates::str::contains;
+use tempfile::tempdir;
+
+#[test]
+fn serve_requires_a_secret_from_the_environment_or_a_file() {
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.env_remove("GH_WATCH_WEBHOOK_SECRET")
+        .arg("serve")
+        .assert()
+        .failure()
+        .stderr(contains("GH_WATCH_WEBHOOK_SECRET"))
+        .stderr(contains("--secret-file"));
+}
+
+#[test]
+fn serve_reports_an_unreadable_secret_file() {
+    let dir = tempdir().unwrap();
+    let missing = dir.path().join("webhook-secret");
+
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.env("GH_WATCH_WEBHOOK_SECRET", "from-env")
+        .arg("serve")
+        .arg("--secret-file")
+        .arg(&missing)
+        .assert()
+        .failure()
+        .stderr(contains("failed to read webhook secret"));
+}
+
+#[test]
+fn serve_no_longer_takes_the_secret_as_an_argument() {
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.args(["serve", "--secret", "hunter2"])
+        .assert()
+        .failure()
+        .stderr(contains("unexpected argument '--secret'"));
+}
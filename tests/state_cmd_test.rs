@@ -0,0 +1,129 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use chrono::Utc;
+use gh_watch::{infra::state_sqlite::SqliteStateStore, ports::CursorPort};
+use predicates::str::contains;
+use tempfile::tempdir;
+
+fn write_config(dir: &Path, state_path: &Path) -> PathBuf {
+    let config_path = dir.join("config.toml");
+    fs::write(
+        &config_path,
+        format!(
+            "state_db_path = {:?}\n\n[[repositories]]\nname = \"acme/api\"\n",
+            state_path.display().to_string()
+        ),
+    )
+    .unwrap();
+    config_path
+}
+
+#[test]
+fn state_check_fails_on_stale_cursor_until_repaired() {
+    let dir = tempdir().unwrap();
+    let state_path = dir.path().join("state.db");
+    let config_path = write_config(dir.path(), &state_path);
+    SqliteStateStore::new(&state_path)
+        .unwrap()
+        .set_cursor("acme/removed", Utc::now())
+        .unwrap();
+
+    let mut check = cargo_bin_cmd!("gh-watch");
+    check
+        .args(["state", "check", "--config"])
+        .arg(&config_path)
+        .assert()
+        .failure()
+        .stdout(contains("stale_cursors: 1"))
+        .stdout(contains("- acme/removed"))
+        .stderr(contains("gh-watch state check --repair"));
+
+    let mut repair = cargo_bin_cmd!("gh-watch");
+    repair
+        .args(["state", "check", "--repair", "--config"])
+        .arg(&config_path)
+        .assert()
+        .success()
+        .stdout(contains("removed 1 cursor(s)"));
+
+    let mut recheck = cargo_bin_cmd!("gh-watch");
+    recheck
+        .args(["state", "check", "--config"])
+        .arg(&config_path)
+        .assert()
+        .success()
+        .stdout(contains(
+            "delivered_read_events: 0 (max_notification_history 10000)",
+        ))
+        .stdout(contains("result: clean"));
+}
+
+#[test]
+fn state_vacuum_compress_reports_compressed_rows() {
+    let dir = tempdir().unwrap();
+    let state_path = dir.path().join("state.db");
+    let config_path = write_config(dir.path(), &state_path);
+
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.arg("state")
+        .arg("vacuum")
+        .arg("--config")
+        .arg(&config_path)
+        .arg("--compress")
+        .assert()
+        .success()
+        .stdout(contains("compressed 0 event payloads"))
+        .stdout(contains("vacuumed state db"));
+
+    assert!(state_path.exists());
+}
+
+#[test]
+fn events_search_reports_when_nothing_matches() {
+    let dir = tempdir().unwrap();
+    let state_path = dir.path().join("state.db");
+    let config_path = write_config(dir.path(), &state_path);
+
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.arg("events")
+        .arg("--config")
+        .arg(&config_path)
+        .arg("--search")
+        .arg("circuit breaker")
+        .assert()
+        .success()
+        .stdout(contains("no events matched: circuit breaker"));
+}
+
+#[test]
+fn state_export_and_import_report_counts() {
+    let dir = tempdir().unwrap();
+    let state_path = dir.path().join("state.db");
+    let config_path = write_config(dir.path(), &state_path);
+    let export_path = dir.path().join("events.ndjson");
+
+    let mut export = cargo_bin_cmd!("gh-watch");
+    export
+        .args(["state", "export", "--config"])
+        .arg(&config_path)
+        .arg("--output")
+        .arg(&export_path)
+        .assert()
+        .success()
+        .stdout(contains("exported 0 events"));
+
+    let mut import = cargo_bin_cmd!("gh-watch");
+    import
+        .args(["state", "import", "--config"])
+        .arg(&config_path)
+        .arg(&export_path)
+        .assert()
+        .success()
+        .stdout(contains(
+            "imported: total_read=0 inserted=0 skipped_duplicate=0 failed=0",
+        ));
+}
@@ -1,9 +1,15 @@
+use std::collections::{HashMap, HashSet};
+
 use chrono::{Duration, TimeZone, Utc};
-use gh_watch::domain::events::{EventKind, WatchEvent};
+use gh_watch::domain::events::{EventKind, WatchEvent, MAX_STORED_BODY_BYTES};
+use gh_watch::domain::failure::{FailureRecord, FAILURE_KIND_REPO_POLL};
+use gh_watch::domain::subject_state::{SubjectRef, SubjectState};
 use gh_watch::infra::state_sqlite::{SqliteStateStore, StateSchemaMismatchError};
 use gh_watch::ports::{
-    CursorPort, RepoBatchPort, RepoPersistBatch, RetentionPort, TimelineQueryPort,
-    TimelineReadMarkPort,
+    CursorPort, FailureLogPort, MyPrState, PendingReviewRequests, PrFileChange, ReadSyncStatePort,
+    ReminderStatePort, RepoAccessMark, RepoBatchPort, RepoPersistBatch, RepoReadCounts,
+    RetentionPolicy, RetentionPort, ReviewThreadState, SubjectStatePort, SummaryStatePort,
+    TimelineQueryPort, TimelineReadMarkPort, ViewerTeamsPort,
 };
 use rusqlite::params;
 use tempfile::tempdir;
@@ -21,6 +27,18 @@ fn sample_event(id: &str, created_at: chrono::DateTime<Utc>) -> WatchEvent {
         subject_author: Some("bob".to_string()),
         requested_reviewer: None,
         mentions: Vec::new(),
+        team_mentions: Vec::new(),
+        subject_number: None,
+        subject_url: None,
+        account: None,
+        body: None,
+    }
+}
+
+fn uniform_policy(days: u32) -> RetentionPolicy {
+    RetentionPolicy {
+        default_days: days,
+        ..RetentionPolicy::default()
     }
 }
 
@@ -36,6 +54,41 @@ fn cursor_roundtrip() {
     assert_eq!(out, ts);
 }
 
+#[test]
+fn load_cursors_all_returns_every_stored_cursor() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("state.db");
+    let store = SqliteStateStore::new(&db).unwrap();
+    let api = Utc.with_ymd_and_hms(2025, 1, 1, 10, 0, 0).unwrap();
+    let web = Utc.with_ymd_and_hms(2025, 1, 2, 10, 0, 0).unwrap();
+
+    store.set_cursor("acme/api", api).unwrap();
+    store.set_cursor("acme/web", web).unwrap();
+
+    let cursors = store.load_cursors_all().unwrap();
+    assert_eq!(cursors.len(), 2);
+    assert_eq!(cursors.get("acme/api"), Some(&api));
+    assert_eq!(cursors.get("acme/web"), Some(&web));
+}
+
+#[test]
+fn load_cursors_for_returns_only_requested_repos_with_cursors() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("state.db");
+    let store = SqliteStateStore::new(&db).unwrap();
+    let ts = Utc.with_ymd_and_hms(2025, 1, 1, 10, 0, 0).unwrap();
+
+    store.set_cursor("acme/api", ts).unwrap();
+    store.set_cursor("acme/web", ts).unwrap();
+
+    let cursors = store
+        .load_cursors_for(&["acme/api".to_string(), "acme/missing".to_string()])
+        .unwrap();
+    assert_eq!(cursors.len(), 1);
+    assert_eq!(cursors.get("acme/api"), Some(&ts));
+    assert!(store.load_cursors_for(&[]).unwrap().is_empty());
+}
+
 #[test]
 fn cleanup_removes_old_events() {
     let dir = tempdir().unwrap();
@@ -49,15 +102,302 @@ fn cleanup_removes_old_events() {
     let batch = RepoPersistBatch {
         repo: "acme/api".to_string(),
         poll_started_at: old,
+        pushed: false,
         events: vec![ev.clone()],
+        review_requests: Vec::new(),
+        review_threads: Vec::new(),
+        my_prs: Vec::new(),
     };
     store.persist_repo_batch(&batch).unwrap();
 
-    store.cleanup_old(90, now).unwrap();
+    store.cleanup_old(&uniform_policy(90), now).unwrap();
     let timeline = store.load_timeline_events(10).unwrap();
     assert!(timeline.is_empty());
 }
 
+#[test]
+fn cleanup_keep_unread_exempts_unread_events() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("state.db");
+    let store = SqliteStateStore::new(&db).unwrap();
+
+    let old = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let now = old + Duration::days(120);
+    let unread = sample_event("old-unread", old);
+    let read = sample_event("old-read", old);
+    store
+        .persist_repo_batch(&RepoPersistBatch {
+            repo: "acme/api".to_string(),
+            poll_started_at: old,
+            pushed: false,
+            events: vec![unread.clone(), read.clone()],
+            review_requests: Vec::new(),
+            review_threads: Vec::new(),
+            my_prs: Vec::new(),
+        })
+        .unwrap();
+    store
+        .mark_timeline_event_read(&read.event_key(), old)
+        .unwrap();
+
+    let policy = RetentionPolicy {
+        keep_unread: true,
+        ..uniform_policy(90)
+    };
+    store.cleanup_old(&policy, now).unwrap();
+
+    let keys = store
+        .load_timeline_events(10)
+        .unwrap()
+        .into_iter()
+        .map(|event| event.event_key())
+        .collect::<Vec<_>>();
+    assert_eq!(keys, vec![unread.event_key()]);
+}
+
+#[test]
+fn cleanup_applies_per_kind_cutoffs_and_default_for_other_kinds() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("state.db");
+    let store = SqliteStateStore::new(&db).unwrap();
+
+    let now = Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap();
+    let merged_200d = WatchEvent {
+        kind: EventKind::PrMerged,
+        ..sample_event("merged-200d", now - Duration::days(200))
+    };
+    let comment_40d = WatchEvent {
+        kind: EventKind::IssueCommentCreated,
+        ..sample_event("comment-40d", now - Duration::days(40))
+    };
+    let comment_10d = WatchEvent {
+        kind: EventKind::IssueCommentCreated,
+        ..sample_event("comment-10d", now - Duration::days(10))
+    };
+    let issue_100d = sample_event("issue-100d", now - Duration::days(100));
+    let issue_60d = sample_event("issue-60d", now - Duration::days(60));
+    store
+        .persist_repo_batch(&RepoPersistBatch {
+            repo: "acme/api".to_string(),
+            poll_started_at: now,
+            pushed: false,
+            events: vec![
+                merged_200d.clone(),
+                comment_40d,
+                comment_10d.clone(),
+                issue_100d,
+                issue_60d.clone(),
+            ],
+            review_requests: Vec::new(),
+            review_threads: Vec::new(),
+            my_prs: Vec::new(),
+        })
+        .unwrap();
+
+    let mut policy = uniform_policy(90);
+    policy.days_by_kind.insert(EventKind::PrMerged, 365);
+    policy
+        .days_by_kind
+        .insert(EventKind::IssueCommentCreated, 30);
+    store.cleanup_old(&policy, now).unwrap();
+
+    let mut keys = store
+        .load_timeline_events(10)
+        .unwrap()
+        .into_iter()
+        .map(|event| event.event_key())
+        .collect::<Vec<_>>();
+    keys.sort();
+    let mut expected = vec![
+        merged_200d.event_key(),
+        comment_10d.event_key(),
+        issue_60d.event_key(),
+    ];
+    expected.sort();
+    assert_eq!(keys, expected);
+}
+
+#[test]
+fn opening_v3_schema_migrates_and_backfills_kind_column() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("state.db");
+    let event = WatchEvent {
+        kind: EventKind::PrMerged,
+        ..sample_event("v3-1", Utc.with_ymd_and_hms(2025, 1, 7, 9, 0, 0).unwrap())
+    };
+
+    let conn = rusqlite::Connection::open(&db).unwrap();
+    conn.execute_batch(
+        "
+CREATE TABLE schema_meta (
+  key TEXT PRIMARY KEY,
+  value TEXT NOT NULL
+);
+CREATE TABLE polling_cursors_v2 (
+  repo TEXT PRIMARY KEY,
+  last_polled_at TEXT NOT NULL
+);
+CREATE TABLE event_log_v2 (
+  event_key TEXT PRIMARY KEY,
+  repo TEXT NOT NULL,
+  payload_json TEXT NOT NULL,
+  created_at TEXT NOT NULL,
+  observed_at TEXT NOT NULL,
+  delivered_at TEXT,
+  read_at TEXT
+);
+INSERT INTO schema_meta (key, value) VALUES ('schema_version', '3');
+",
+    )
+    .unwrap();
+    conn.execute(
+        "
+INSERT INTO event_log_v2 (event_key, repo, payload_json, created_at, observed_at)
+VALUES (?1, ?2, ?3, ?4, ?4)
+",
+        params![
+            event.event_key(),
+            event.repo,
+            serde_json::to_string(&event).unwrap(),
+            event.created_at.to_rfc3339(),
+        ],
+    )
+    .unwrap();
+    drop(conn);
+
+    let store = SqliteStateStore::new(&db).unwrap();
+    assert_eq!(store.load_timeline_events(10).unwrap(), vec![event.clone()]);
+    drop(store);
+
+    let conn = rusqlite::Connection::open(&db).unwrap();
+    let kind: String = conn
+        .query_row(
+            "SELECT kind FROM event_log_v2 WHERE event_key = ?1",
+            params![event.event_key()],
+            |row| row.get(0),
+        )
+        .unwrap();
+    let version: String = conn
+        .query_row(
+            "SELECT value FROM schema_meta WHERE key = 'schema_version'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(kind, "pr_merged");
+    assert_eq!(version, "21");
+    assert_eq!(store_search_count(&db, "Bug"), 1);
+}
+
+/// A state db at schema `version` (3 or 4) holding `event` plus a row whose payload was
+/// cut off mid-write; returns the key of the broken row.
+fn write_legacy_db_with_truncated_payload(
+    db: &std::path::Path,
+    version: u32,
+    event: &WatchEvent,
+) -> String {
+    let conn = rusqlite::Connection::open(db).unwrap();
+    conn.execute_batch(
+        "
+CREATE TABLE schema_meta (
+  key TEXT PRIMARY KEY,
+  value TEXT NOT NULL
+);
+CREATE TABLE polling_cursors_v2 (
+  repo TEXT PRIMARY KEY,
+  last_polled_at TEXT NOT NULL
+);
+CREATE TABLE event_log_v2 (
+  event_key TEXT PRIMARY KEY,
+  repo TEXT NOT NULL,
+  payload_json TEXT NOT NULL,
+  created_at TEXT NOT NULL,
+  observed_at TEXT NOT NULL,
+  delivered_at TEXT,
+  read_at TEXT
+);
+",
+    )
+    .unwrap();
+    if version >= 4 {
+        conn.execute_batch("ALTER TABLE event_log_v2 ADD COLUMN kind TEXT NOT NULL DEFAULT '';")
+            .unwrap();
+    }
+    conn.execute(
+        "INSERT INTO schema_meta (key, value) VALUES ('schema_version', ?1)",
+        params![version.to_string()],
+    )
+    .unwrap();
+
+    let payload = serde_json::to_string(event).unwrap();
+    let broken_key = "acme/api:issue_created:truncated".to_string();
+    for (event_key, payload) in [
+        (event.event_key(), payload.as_str()),
+        (broken_key.clone(), &payload[..payload.len() / 2]),
+    ] {
+        conn.execute(
+            "
+INSERT INTO event_log_v2 (event_key, repo, payload_json, created_at, observed_at)
+VALUES (?1, ?2, ?3, ?4, ?4)
+",
+            params![
+                event_key,
+                event.repo,
+                payload,
+                event.created_at.to_rfc3339()
+            ],
+        )
+        .unwrap();
+    }
+    broken_key
+}
+
+#[test]
+fn opening_v3_schema_with_a_truncated_payload_leaves_it_for_repair() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("state.db");
+    let event = sample_event("v3-1", Utc.with_ymd_and_hms(2025, 1, 7, 9, 0, 0).unwrap());
+    let broken_key = write_legacy_db_with_truncated_payload(&db, 3, &event);
+
+    let store = SqliteStateStore::new(&db).unwrap();
+    assert_eq!(store.load_timeline_events(10).unwrap(), vec![event]);
+    assert_eq!(
+        store.check(&[]).unwrap().unparsable_event_keys,
+        vec![broken_key]
+    );
+    store.repair(&[]).unwrap();
+    assert!(store.check(&[]).unwrap().unparsable_event_keys.is_empty());
+    drop(store);
+
+    let conn = rusqlite::Connection::open(&db).unwrap();
+    let kinds = conn
+        .prepare("SELECT kind FROM event_log_v2")
+        .unwrap()
+        .query_map([], |row| row.get::<_, String>(0))
+        .unwrap()
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(kinds, vec!["issue_created".to_string()]);
+}
+
+#[test]
+fn opening_v4_schema_with_a_truncated_payload_leaves_it_for_repair() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("state.db");
+    let event = sample_event("v4-1", Utc.with_ymd_and_hms(2025, 1, 7, 9, 0, 0).unwrap());
+    let broken_key = write_legacy_db_with_truncated_payload(&db, 4, &event);
+
+    let store = SqliteStateStore::new(&db).unwrap();
+    assert_eq!(store.load_timeline_events(10).unwrap(), vec![event.clone()]);
+    assert_eq!(store.search_events("Bug", 10).unwrap(), vec![event]);
+    assert_eq!(
+        store.check(&[]).unwrap().unparsable_event_keys,
+        vec![broken_key.clone()]
+    );
+    store.repair(&[]).unwrap();
+    assert!(store.check(&[]).unwrap().unparsable_event_keys.is_empty());
+}
+
 #[test]
 fn opening_legacy_timeline_schema_returns_schema_mismatch_error() {
     let dir = tempdir().unwrap();
@@ -150,7 +490,11 @@ fn persisted_event_is_unread_until_explicitly_marked_read() {
     let batch = RepoPersistBatch {
         repo: "acme/api".to_string(),
         poll_started_at: event.created_at,
+        pushed: false,
         events: vec![event],
+        review_requests: Vec::new(),
+        review_threads: Vec::new(),
+        my_prs: Vec::new(),
     };
     store.persist_repo_batch(&batch).unwrap();
 
@@ -168,6 +512,238 @@ fn persisted_event_is_unread_until_explicitly_marked_read() {
     assert!(read.contains(&key));
 }
 
+#[test]
+fn mark_all_read_counts_unread_events_and_keeps_earlier_read_times() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("state.db");
+    let store = SqliteStateStore::new(&db).unwrap();
+    let at = Utc.with_ymd_and_hms(2025, 1, 8, 10, 0, 0).unwrap();
+    let events = (0..3)
+        .map(|n| sample_event(&format!("all-read-{n}"), at + Duration::minutes(n)))
+        .collect::<Vec<_>>();
+    let keys = events.iter().map(WatchEvent::event_key).collect::<Vec<_>>();
+    store
+        .persist_repo_batch(&RepoPersistBatch {
+            repo: "acme/api".to_string(),
+            poll_started_at: at,
+            pushed: false,
+            events,
+            review_requests: Vec::new(),
+            review_threads: Vec::new(),
+            my_prs: Vec::new(),
+        })
+        .unwrap();
+    let first_read = at + Duration::hours(1);
+    store
+        .mark_timeline_event_read(&keys[0], first_read)
+        .unwrap();
+
+    let all_read = at + Duration::hours(2);
+    assert_eq!(store.mark_all_timeline_events_read(all_read).unwrap(), 2);
+    assert_eq!(store.mark_all_timeline_events_read(all_read).unwrap(), 0);
+
+    assert_eq!(store.load_read_event_keys(&keys).unwrap().len(), 3);
+    let marks = store.load_read_marks().unwrap();
+    assert_eq!(marks[&keys[0]], first_read);
+    assert_eq!(marks[&keys[2]], all_read);
+}
+
+#[test]
+fn get_event_by_key_returns_the_logged_event_or_none() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("state.db");
+    let store = SqliteStateStore::new(&db).unwrap();
+    let event = sample_event(
+        "lookup-1",
+        Utc.with_ymd_and_hms(2025, 1, 8, 10, 0, 0).unwrap(),
+    );
+
+    store
+        .persist_repo_batch(&RepoPersistBatch {
+            repo: "acme/api".to_string(),
+            poll_started_at: event.created_at,
+            pushed: false,
+            events: vec![event.clone()],
+            review_requests: Vec::new(),
+            review_threads: Vec::new(),
+            my_prs: Vec::new(),
+        })
+        .unwrap();
+
+    assert_eq!(
+        store.get_event_by_key(&event.event_key()).unwrap(),
+        Some(event)
+    );
+    assert_eq!(
+        store
+            .get_event_by_key("acme/api:issue_created:missing")
+            .unwrap(),
+        None
+    );
+}
+
+#[test]
+fn get_events_by_keys_keeps_requested_order_and_skips_unknown_keys() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("state.db");
+    let store = SqliteStateStore::new(&db).unwrap();
+    let older = sample_event(
+        "batch-1",
+        Utc.with_ymd_and_hms(2025, 1, 8, 10, 0, 0).unwrap(),
+    );
+    let newer = sample_event(
+        "batch-2",
+        Utc.with_ymd_and_hms(2025, 1, 8, 11, 0, 0).unwrap(),
+    );
+
+    store
+        .persist_repo_batch(&RepoPersistBatch {
+            repo: "acme/api".to_string(),
+            poll_started_at: newer.created_at,
+            pushed: false,
+            events: vec![older.clone(), newer.clone()],
+            review_requests: Vec::new(),
+            review_threads: Vec::new(),
+            my_prs: Vec::new(),
+        })
+        .unwrap();
+
+    let events = store
+        .get_events_by_keys(&[
+            older.event_key(),
+            "acme/api:issue_created:missing".to_string(),
+            newer.event_key(),
+            older.event_key(),
+        ])
+        .unwrap();
+    assert_eq!(events, vec![older, newer]);
+    assert!(store.get_events_by_keys(&[]).unwrap().is_empty());
+}
+
+#[test]
+fn viewer_teams_are_cached_until_cleared() {
+    let dir = tempdir().unwrap();
+    let store = SqliteStateStore::new(dir.path().join("state.db")).unwrap();
+    let fetched_at = Utc.with_ymd_and_hms(2025, 1, 8, 10, 0, 0).unwrap();
+    assert_eq!(store.load_viewer_teams().unwrap(), None);
+
+    store.save_viewer_teams(&[], fetched_at).unwrap();
+    assert_eq!(
+        store.load_viewer_teams().unwrap(),
+        Some((Vec::new(), fetched_at))
+    );
+
+    let teams = vec!["acme/platform".to_string(), "acme/core".to_string()];
+    store
+        .save_viewer_teams(&teams, fetched_at + Duration::days(1))
+        .unwrap();
+    assert_eq!(
+        store.load_viewer_teams().unwrap(),
+        Some((teams, fetched_at + Duration::days(1)))
+    );
+
+    store.clear_viewer_teams().unwrap();
+    assert_eq!(store.load_viewer_teams().unwrap(), None);
+}
+
+#[test]
+fn subject_states_round_trip_and_are_overwritten_by_later_lookups() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("state.db");
+    let store = SqliteStateStore::new(&db).unwrap();
+    let subject = |number| SubjectRef {
+        repo: "acme/api".to_string(),
+        number,
+    };
+    let first_at = Utc.with_ymd_and_hms(2025, 1, 8, 10, 0, 0).unwrap();
+    let later_at = first_at + Duration::hours(1);
+
+    store
+        .save_subject_states(
+            &HashMap::from([
+                (subject(1), SubjectState::Open),
+                (subject(2), SubjectState::Closed),
+            ]),
+            first_at,
+        )
+        .unwrap();
+    store
+        .save_subject_states(
+            &HashMap::from([(subject(1), SubjectState::Merged)]),
+            later_at,
+        )
+        .unwrap();
+
+    let states = store
+        .load_subject_states(&[subject(1), subject(2), subject(3)])
+        .unwrap();
+    assert_eq!(
+        states,
+        HashMap::from([
+            (subject(1), (SubjectState::Merged, later_at)),
+            (subject(2), (SubjectState::Closed, first_at)),
+        ])
+    );
+}
+
+#[test]
+fn read_counts_are_grouped_by_repo_within_the_window() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("state.db");
+    let store = SqliteStateStore::new(&db).unwrap();
+    let since = Utc.with_ymd_and_hms(2025, 1, 8, 0, 0, 0).unwrap();
+    let at = since + Duration::hours(1);
+    let events = [
+        ("acme/api", "api-read", at),
+        ("acme/api", "api-unread", at),
+        ("acme/web", "web-unread", at),
+        ("acme/web", "web-too-old", since - Duration::hours(1)),
+    ]
+    .map(|(repo, id, created_at)| WatchEvent {
+        repo: repo.to_string(),
+        ..sample_event(id, created_at)
+    });
+    for event in &events {
+        store
+            .persist_repo_batch(&RepoPersistBatch {
+                repo: event.repo.clone(),
+                poll_started_at: at,
+                pushed: false,
+                events: vec![event.clone()],
+                review_requests: Vec::new(),
+                review_threads: Vec::new(),
+                my_prs: Vec::new(),
+            })
+            .unwrap();
+    }
+    store
+        .mark_timeline_event_read(&events[0].event_key(), at)
+        .unwrap();
+
+    let counts = store.count_read_events_by_repo(since, &[], &[]).unwrap();
+
+    assert_eq!(
+        counts,
+        vec![
+            RepoReadCounts {
+                repo: "acme/api".to_string(),
+                read: 1,
+                unread: 1,
+            },
+            RepoReadCounts {
+                repo: "acme/web".to_string(),
+                read: 0,
+                unread: 1,
+            },
+        ]
+    );
+    let scoped = store
+        .count_read_events_by_repo(since, &["acme/web".to_string()], &["BOB".to_string()])
+        .unwrap();
+    assert_eq!(scoped.len(), 1);
+    assert_eq!(scoped[0].repo, "acme/web");
+}
+
 #[test]
 fn persist_batch_deduplicates_existing_event_keys() {
     let dir = tempdir().unwrap();
@@ -182,12 +758,20 @@ fn persist_batch_deduplicates_existing_event_keys() {
     let first = RepoPersistBatch {
         repo: "acme/api".to_string(),
         poll_started_at,
+        pushed: false,
         events: vec![event.clone()],
+        review_requests: Vec::new(),
+        review_threads: Vec::new(),
+        my_prs: Vec::new(),
     };
     let second = RepoPersistBatch {
         repo: "acme/api".to_string(),
         poll_started_at: poll_started_at + Duration::minutes(1),
+        pushed: false,
         events: vec![event.clone()],
+        review_requests: Vec::new(),
+        review_threads: Vec::new(),
+        my_prs: Vec::new(),
     };
 
     let first_result = store.persist_repo_batch(&first).unwrap();
@@ -198,3 +782,1506 @@ fn persist_batch_deduplicates_existing_event_keys() {
     );
     assert!(second_result.newly_logged_event_keys.is_empty());
 }
+
+#[test]
+fn review_request_is_not_logged_again_after_its_event_is_pruned() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("state.db");
+    let store = SqliteStateStore::new(&db).unwrap();
+    let first_poll = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let later_poll = Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap();
+    let request = |updated_at| WatchEvent {
+        event_id: "pr-review-requested:7:alice".to_string(),
+        kind: EventKind::PrReviewRequested,
+        source_item_id: "7:alice".to_string(),
+        requested_reviewer: Some("alice".to_string()),
+        ..sample_event("pr-review-requested:7:alice", updated_at)
+    };
+    let batch = |poll_started_at, event| RepoPersistBatch {
+        repo: "acme/api".to_string(),
+        poll_started_at,
+        pushed: false,
+        events: vec![event],
+        review_requests: Vec::new(),
+        review_threads: Vec::new(),
+        my_prs: Vec::new(),
+    };
+
+    let first = store
+        .persist_repo_batch(&batch(first_poll, request(first_poll)))
+        .unwrap();
+    assert_eq!(first.newly_logged_event_keys.len(), 1);
+
+    let mut policy = uniform_policy(30);
+    policy.days_by_kind.insert(EventKind::PrReviewRequested, 7);
+    store.cleanup_old(&policy, later_poll).unwrap();
+    assert!(store.load_timeline_events(10).unwrap().is_empty());
+
+    // The pull request was updated, so the still-pending request is fetched again.
+    let second = store
+        .persist_repo_batch(&batch(later_poll, request(later_poll)))
+        .unwrap();
+    assert!(second.newly_logged_event_keys.is_empty());
+}
+
+#[test]
+fn answered_review_request_is_logged_unread_when_made_again() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("state.db");
+    let store = SqliteStateStore::new(&db).unwrap();
+    let at = |day| Utc.with_ymd_and_hms(2025, 1, day, 0, 0, 0).unwrap();
+    let request = |reviewer: &str, updated_at| WatchEvent {
+        event_id: format!("pr-review-requested:7:{reviewer}"),
+        kind: EventKind::PrReviewRequested,
+        source_item_id: format!("7:{reviewer}"),
+        requested_reviewer: Some(reviewer.to_string()),
+        ..sample_event(&format!("pr-review-requested:7:{reviewer}"), updated_at)
+    };
+    let batch = |poll_started_at, events, reviewers: &[&str]| RepoPersistBatch {
+        repo: "acme/api".to_string(),
+        poll_started_at,
+        pushed: false,
+        events,
+        review_requests: vec![PendingReviewRequests {
+            pull_id: "7".to_string(),
+            reviewers: reviewers.iter().map(|login| login.to_string()).collect(),
+        }],
+        review_threads: Vec::new(),
+        my_prs: Vec::new(),
+    };
+
+    let first = store
+        .persist_repo_batch(&batch(
+            at(1),
+            vec![request("alice", at(1)), request("bob", at(1))],
+            &["alice", "bob"],
+        ))
+        .unwrap();
+    assert_eq!(first.newly_logged_event_keys.len(), 2);
+    store.mark_all_timeline_events_read(at(2)).unwrap();
+
+    // alice reviewed, which drops her from the requested reviewers; bob is still pending.
+    store
+        .persist_repo_batch(&batch(at(3), vec![request("bob", at(3))], &["bob"]))
+        .unwrap();
+
+    let again = store
+        .persist_repo_batch(&batch(
+            at(4),
+            vec![request("alice", at(4)), request("bob", at(4))],
+            &["alice", "bob"],
+        ))
+        .unwrap();
+    assert_eq!(
+        again.newly_logged_event_keys,
+        vec![request("alice", at(4)).event_key()]
+    );
+    let keys = [
+        request("alice", at(4)).event_key(),
+        request("bob", at(4)).event_key(),
+    ];
+    let read = store.load_read_event_keys(&keys).unwrap();
+    assert_eq!(read, HashSet::from([keys[1].clone()]));
+    let logged = store.load_timeline_events(10).unwrap();
+    assert_eq!(logged[0].event_key(), keys[0]);
+    assert_eq!(logged[0].created_at, at(4));
+}
+
+#[test]
+fn pr_file_changes_round_trip_through_the_logged_event() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("state.db");
+    let store = SqliteStateStore::new(&db).unwrap();
+    let poll_started_at = Utc.with_ymd_and_hms(2025, 1, 12, 0, 0, 0).unwrap();
+    let event = sample_event("comment-1", poll_started_at - Duration::minutes(5));
+    store
+        .persist_repo_batch(&RepoPersistBatch {
+            repo: "acme/api".to_string(),
+            poll_started_at,
+            pushed: false,
+            events: vec![event.clone()],
+            review_requests: Vec::new(),
+            review_threads: Vec::new(),
+            my_prs: Vec::new(),
+        })
+        .unwrap();
+    let files = vec![
+        PrFileChange {
+            filename: "src/lib.rs".to_string(),
+            status: "modified".to_string(),
+            additions: 12,
+            deletions: 3,
+        },
+        PrFileChange {
+            filename: "docs/old.md".to_string(),
+            status: "removed".to_string(),
+            additions: 0,
+            deletions: 40,
+        },
+    ];
+    let since = poll_started_at - Duration::hours(1);
+
+    assert_eq!(
+        store.load_pr_file_changes("acme/api", 7, since).unwrap(),
+        None
+    );
+    store
+        .save_pr_file_changes(&event.event_key(), 7, &files)
+        .unwrap();
+
+    assert_eq!(
+        store.load_pr_file_changes("acme/api", 7, since).unwrap(),
+        Some(files)
+    );
+    assert_eq!(
+        store.load_pr_file_changes("acme/api", 8, since).unwrap(),
+        None
+    );
+    assert_eq!(
+        store.load_pr_file_changes("acme/web", 7, since).unwrap(),
+        None
+    );
+    // Files cached before `since` are stale.
+    assert_eq!(
+        store
+            .load_pr_file_changes("acme/api", 7, poll_started_at + Duration::minutes(1))
+            .unwrap(),
+        None
+    );
+}
+
+#[test]
+fn persisted_payload_is_compressed_and_round_trips() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("state.db");
+    let store = SqliteStateStore::new(&db).unwrap();
+    let event = WatchEvent {
+        title: "long title ".repeat(200),
+        mentions: vec!["alice".to_string(), "bob".to_string()],
+        subject_number: None,
+        subject_url: None,
+        account: None,
+        ..sample_event("zstd-1", Utc.with_ymd_and_hms(2025, 1, 9, 0, 0, 0).unwrap())
+    };
+
+    store
+        .persist_repo_batch(&RepoPersistBatch {
+            repo: "acme/api".to_string(),
+            poll_started_at: event.created_at,
+            pushed: false,
+            events: vec![event.clone()],
+            review_requests: Vec::new(),
+            review_threads: Vec::new(),
+            my_prs: Vec::new(),
+        })
+        .unwrap();
+
+    let conn = rusqlite::Connection::open(&db).unwrap();
+    let (payload_type, payload_len): (String, i64) = conn
+        .query_row(
+            "SELECT typeof(payload_json), length(payload_json) FROM event_log_v2",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!(payload_type, "blob");
+    assert!((payload_len as usize) < serde_json::to_string(&event).unwrap().len());
+
+    assert_eq!(store.load_timeline_events(10).unwrap(), vec![event]);
+}
+
+#[test]
+fn stored_body_is_cut_to_the_size_limit_on_a_char_boundary() {
+    let dir = tempdir().unwrap();
+    let store = SqliteStateStore::new(dir.path().join("state.db")).unwrap();
+    let event = WatchEvent {
+        kind: EventKind::IssueCommentCreated,
+        body: Some(format!("a{}", "é".repeat(MAX_STORED_BODY_BYTES))),
+        ..sample_event("body-1", Utc.with_ymd_and_hms(2025, 1, 9, 0, 0, 0).unwrap())
+    };
+
+    store
+        .persist_repo_batch(&RepoPersistBatch {
+            repo: "acme/api".to_string(),
+            poll_started_at: event.created_at,
+            pushed: false,
+            events: vec![event],
+            review_requests: Vec::new(),
+            review_threads: Vec::new(),
+            my_prs: Vec::new(),
+        })
+        .unwrap();
+
+    let loaded = store.load_timeline_events(10).unwrap();
+    let body = loaded[0].body.as_deref().unwrap();
+    assert_eq!(body.len(), MAX_STORED_BODY_BYTES - 1);
+    assert!(body.starts_with("aé"));
+}
+
+#[test]
+fn compress_plain_payloads_rewrites_legacy_json_rows() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("state.db");
+    let store = SqliteStateStore::new(&db).unwrap();
+    let plain = sample_event(
+        "plain-1",
+        Utc.with_ymd_and_hms(2025, 1, 9, 0, 0, 0).unwrap(),
+    );
+    let compressed = sample_event("zstd-1", Utc.with_ymd_and_hms(2025, 1, 8, 0, 0, 0).unwrap());
+
+    store
+        .persist_repo_batch(&RepoPersistBatch {
+            repo: "acme/api".to_string(),
+            poll_started_at: plain.created_at,
+            pushed: false,
+            events: vec![compressed.clone()],
+            review_requests: Vec::new(),
+            review_threads: Vec::new(),
+            my_prs: Vec::new(),
+        })
+        .unwrap();
+    let conn = rusqlite::Connection::open(&db).unwrap();
+    conn.execute(
+        "
+INSERT INTO event_log_v2 (event_key, repo, kind, payload_json, created_at, observed_at)
+VALUES (?1, ?2, ?3, ?4, ?5, ?5)
+",
+        params![
+            plain.event_key(),
+            plain.repo,
+            plain.kind.as_str(),
+            serde_json::to_string(&plain).unwrap(),
+            plain.created_at.to_rfc3339(),
+        ],
+    )
+    .unwrap();
+
+    let expected = vec![plain, compressed];
+    assert_eq!(store.load_timeline_events(10).unwrap(), expected);
+
+    assert_eq!(store.compress_plain_payloads().unwrap(), 1);
+    assert_eq!(store.compress_plain_payloads().unwrap(), 0);
+    store.vacuum().unwrap();
+
+    let text_rows: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM event_log_v2 WHERE typeof(payload_json) = 'text'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(text_rows, 0);
+    assert_eq!(store.load_timeline_events(10).unwrap(), expected);
+}
+
+#[test]
+fn payloads_without_subject_number_load_with_none() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("state.db");
+    let store = SqliteStateStore::new(&db).unwrap();
+    let legacy = sample_event(
+        "legacy-1",
+        Utc.with_ymd_and_hms(2025, 1, 9, 0, 0, 0).unwrap(),
+    );
+    let mut numbered = sample_event(
+        "numbered-1",
+        Utc.with_ymd_and_hms(2025, 1, 8, 0, 0, 0).unwrap(),
+    );
+    numbered.subject_number = Some(1);
+
+    store
+        .persist_repo_batch(&RepoPersistBatch {
+            repo: "acme/api".to_string(),
+            poll_started_at: numbered.created_at,
+            pushed: false,
+            events: vec![numbered.clone()],
+            review_requests: Vec::new(),
+            review_threads: Vec::new(),
+            my_prs: Vec::new(),
+        })
+        .unwrap();
+    let mut payload = serde_json::to_value(&legacy).unwrap();
+    payload.as_object_mut().unwrap().remove("subject_number");
+    let conn = rusqlite::Connection::open(&db).unwrap();
+    conn.execute(
+        "
+INSERT INTO event_log_v2 (event_key, repo, kind, payload_json, created_at, observed_at)
+VALUES (?1, ?2, ?3, ?4, ?5, ?5)
+",
+        params![
+            legacy.event_key(),
+            legacy.repo,
+            legacy.kind.as_str(),
+            payload.to_string(),
+            legacy.created_at.to_rfc3339(),
+        ],
+    )
+    .unwrap();
+
+    let loaded = store.load_timeline_events(10).unwrap();
+    assert_eq!(loaded, vec![legacy, numbered]);
+    assert_eq!(loaded[0].subject_number, None);
+    assert_eq!(loaded[0].display_number(), Some(1));
+    assert_eq!(loaded[1].subject_number, Some(1));
+}
+
+#[test]
+#[ignore = "timing comparison; run with `cargo test --release -- --ignored`"]
+fn load_timeline_events_with_compressed_payloads_stays_close_to_plain_json() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("state.db");
+    let store = SqliteStateStore::new(&db).unwrap();
+    let base = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let events = (0..10_000)
+        .map(|index| sample_event(&format!("perf-{index}"), base + Duration::seconds(index)))
+        .collect::<Vec<_>>();
+    store
+        .persist_repo_batch(&RepoPersistBatch {
+            repo: "acme/api".to_string(),
+            poll_started_at: base,
+            pushed: false,
+            events: events.clone(),
+            review_requests: Vec::new(),
+            review_threads: Vec::new(),
+            my_prs: Vec::new(),
+        })
+        .unwrap();
+
+    let started = std::time::Instant::now();
+    assert_eq!(store.load_timeline_events(10_000).unwrap().len(), 10_000);
+    let compressed_elapsed = started.elapsed();
+
+    let conn = rusqlite::Connection::open(&db).unwrap();
+    for event in &events {
+        conn.execute(
+            "UPDATE event_log_v2 SET payload_json = ?2 WHERE event_key = ?1",
+            params![event.event_key(), serde_json::to_string(event).unwrap()],
+        )
+        .unwrap();
+    }
+
+    let started = std::time::Instant::now();
+    assert_eq!(store.load_timeline_events(10_000).unwrap().len(), 10_000);
+    let plain_elapsed = started.elapsed();
+
+    assert!(
+        compressed_elapsed < plain_elapsed * 3,
+        "compressed={compressed_elapsed:?} plain={plain_elapsed:?}"
+    );
+}
+
+fn store_search_count(db: &std::path::Path, query: &str) -> usize {
+    SqliteStateStore::new(db)
+        .unwrap()
+        .search_events(query, 100)
+        .unwrap()
+        .len()
+}
+
+#[test]
+fn search_events_matches_title_terms_across_full_history() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("state.db");
+    let store = SqliteStateStore::new(&db).unwrap();
+    let base = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let mut events = (0..600)
+        .map(|index| sample_event(&format!("noise-{index}"), base + Duration::minutes(index)))
+        .collect::<Vec<_>>();
+    let needle = WatchEvent {
+        title: "Add circuit breaker to poller".to_string(),
+        ..sample_event("needle", base - Duration::days(30))
+    };
+    events.push(needle.clone());
+    store
+        .persist_repo_batch(&RepoPersistBatch {
+            repo: "acme/api".to_string(),
+            poll_started_at: base,
+            pushed: false,
+            events,
+            review_requests: Vec::new(),
+            review_threads: Vec::new(),
+            my_prs: Vec::new(),
+        })
+        .unwrap();
+
+    assert_eq!(
+        store.search_events("circuit breaker", 10).unwrap(),
+        vec![needle.clone()]
+    );
+    assert_eq!(
+        store.search_events("breaker \"circuit", 10).unwrap(),
+        vec![needle]
+    );
+    assert!(store.search_events("   ", 10).unwrap().is_empty());
+    assert!(store
+        .search_events("missing-phrase", 10)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn search_events_matches_comment_bodies() {
+    let dir = tempdir().unwrap();
+    let store = SqliteStateStore::new(dir.path().join("state.db")).unwrap();
+    let at = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let comment = WatchEvent {
+        body: Some("We should add a circuit breaker here.".to_string()),
+        ..sample_event("comment-1", at)
+    };
+    store
+        .persist_repo_batch(&RepoPersistBatch {
+            repo: "acme/api".to_string(),
+            poll_started_at: at,
+            pushed: false,
+            events: vec![comment.clone(), sample_event("other-1", at)],
+            review_requests: Vec::new(),
+            review_threads: Vec::new(),
+            my_prs: Vec::new(),
+        })
+        .unwrap();
+
+    assert_eq!(
+        store.search_events("circuit breaker", 10).unwrap(),
+        vec![comment]
+    );
+}
+
+#[test]
+fn opening_v20_schema_backfills_comment_bodies_into_search() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("state.db");
+    let at = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let comment = WatchEvent {
+        body: Some("We should add a circuit breaker here.".to_string()),
+        ..sample_event("comment-1", at)
+    };
+    let store = SqliteStateStore::new(&db).unwrap();
+    store
+        .persist_repo_batch(&RepoPersistBatch {
+            repo: "acme/api".to_string(),
+            poll_started_at: at,
+            pushed: false,
+            events: vec![comment.clone()],
+            review_requests: Vec::new(),
+            review_threads: Vec::new(),
+            my_prs: Vec::new(),
+        })
+        .unwrap();
+    drop(store);
+    {
+        let conn = rusqlite::Connection::open(&db).unwrap();
+        conn.execute_batch(
+            "
+DROP TABLE event_search;
+CREATE VIRTUAL TABLE event_search USING fts5(event_key UNINDEXED, repo, actor, title);
+UPDATE schema_meta SET value = '20' WHERE key = 'schema_version';
+",
+        )
+        .unwrap();
+    }
+
+    let store = SqliteStateStore::new(&db).unwrap();
+    assert_eq!(
+        store.stored_schema_version().unwrap().as_deref(),
+        Some("21")
+    );
+    assert_eq!(store.search_events("breaker", 10).unwrap(), vec![comment]);
+}
+
+#[test]
+fn cleanup_removes_search_entries_for_deleted_events() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("state.db");
+    let store = SqliteStateStore::new(&db).unwrap();
+    let old = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    store
+        .persist_repo_batch(&RepoPersistBatch {
+            repo: "acme/api".to_string(),
+            poll_started_at: old,
+            pushed: false,
+            events: vec![sample_event("old-1", old)],
+            review_requests: Vec::new(),
+            review_threads: Vec::new(),
+            my_prs: Vec::new(),
+        })
+        .unwrap();
+    assert_eq!(store.search_events("bug", 10).unwrap().len(), 1);
+
+    store
+        .cleanup_old(&uniform_policy(90), old + Duration::days(120))
+        .unwrap();
+
+    let conn = rusqlite::Connection::open(&db).unwrap();
+    let indexed: i64 = conn
+        .query_row("SELECT COUNT(*) FROM event_search", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(indexed, 0);
+}
+
+fn seed_corrupted_state(db: &std::path::Path) -> (SqliteStateStore, WatchEvent) {
+    let store = SqliteStateStore::new(db).unwrap();
+    let ts = Utc.with_ymd_and_hms(2025, 1, 9, 0, 0, 0).unwrap();
+    let good = sample_event("good-1", ts);
+    store
+        .persist_repo_batch(&RepoPersistBatch {
+            repo: "acme/api".to_string(),
+            poll_started_at: ts,
+            pushed: false,
+            events: vec![good.clone()],
+            review_requests: Vec::new(),
+            review_threads: Vec::new(),
+            my_prs: Vec::new(),
+        })
+        .unwrap();
+    store.set_cursor("acme/removed", ts).unwrap();
+
+    let conn = rusqlite::Connection::open(db).unwrap();
+    conn.execute(
+        "
+INSERT INTO event_log_v2 (event_key, repo, kind, payload_json, created_at, observed_at, delivered_at, read_at)
+VALUES
+  ('acme/api:issue_created:broken-json', 'acme/api', 'issue_created', '{\"event_id\": ', ?1, ?1, ?1, NULL),
+  ('acme/api:issue_created:orphan-read', 'acme/api', 'issue_created', ?2, ?1, ?1, NULL, ?1)
+",
+        params![
+            ts.to_rfc3339(),
+            serde_json::to_string(&sample_event("orphan-read", ts)).unwrap()
+        ],
+    )
+    .unwrap();
+    conn.execute(
+        "
+INSERT INTO event_log_v2 (event_key, repo, kind, payload_json, created_at, observed_at, delivered_at)
+VALUES ('acme/api:issue_created:broken-blob', 'acme/api', 'issue_created', ?1, ?2, ?2, ?2)
+",
+        params![b"zstd1:not-zstd".to_vec(), ts.to_rfc3339()],
+    )
+    .unwrap();
+
+    (store, good)
+}
+
+#[test]
+fn load_timeline_events_skips_unparsable_payloads() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("state.db");
+    let (store, good) = seed_corrupted_state(&db);
+
+    let timeline = store.load_timeline_events(10).unwrap();
+    assert_eq!(timeline.len(), 2);
+    assert!(timeline.contains(&good));
+}
+
+#[test]
+fn check_reports_corruption_without_modifying_state() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("state.db");
+    let (store, _) = seed_corrupted_state(&db);
+    let configured = vec!["acme/api".to_string()];
+
+    let report = store.check(&configured).unwrap();
+    assert!(report.integrity_errors.is_empty());
+    assert_eq!(
+        report.unparsable_event_keys,
+        vec![
+            "acme/api:issue_created:broken-blob".to_string(),
+            "acme/api:issue_created:broken-json".to_string(),
+        ]
+    );
+    assert_eq!(
+        report.orphaned_flag_event_keys,
+        vec!["acme/api:issue_created:orphan-read".to_string()]
+    );
+    assert_eq!(report.stale_cursor_repos, vec!["acme/removed".to_string()]);
+    assert_eq!(store.check(&configured).unwrap(), report);
+}
+
+#[test]
+fn repair_removes_bad_rows_and_leaves_a_clean_state() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("state.db");
+    let (store, good) = seed_corrupted_state(&db);
+    let configured = vec!["acme/api".to_string()];
+
+    let repaired = store.repair(&configured).unwrap();
+    assert_eq!(repaired.unparsable_event_keys.len(), 2);
+
+    assert!(store.check(&configured).unwrap().is_clean());
+    assert!(store.get_cursor("acme/removed").unwrap().is_none());
+    assert!(store.get_cursor("acme/api").unwrap().is_some());
+    let timeline = store.load_timeline_events(10).unwrap();
+    assert_eq!(timeline.len(), 2);
+    assert!(timeline.contains(&good));
+    assert!(store
+        .load_read_event_keys(&["acme/api:issue_created:orphan-read".to_string()])
+        .unwrap()
+        .is_empty());
+}
+
+fn insert_raw_event(db: &std::path::Path, event_key: &str, event: &WatchEvent, key_version: i64) {
+    let conn = rusqlite::Connection::open(db).unwrap();
+    conn.execute(
+        "
+INSERT INTO event_log_v2 (event_key, repo, kind, payload_json, created_at, observed_at, delivered_at, key_version)
+VALUES (?1, ?2, ?3, ?4, ?5, ?5, ?5, ?6)
+",
+        params![
+            event_key,
+            event.repo,
+            event.kind.as_str(),
+            serde_json::to_string(event).unwrap(),
+            event.created_at.to_rfc3339(),
+            key_version
+        ],
+    )
+    .unwrap();
+}
+
+#[test]
+fn opening_rekeys_events_stored_under_an_older_key_format() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("state.db");
+    let ts = Utc.with_ymd_and_hms(2025, 1, 9, 0, 0, 0).unwrap();
+    let event = sample_event("legacy-1", ts);
+    drop(SqliteStateStore::new(&db).unwrap());
+    insert_raw_event(&db, "legacy:issue:legacy-1", &event, 0);
+    {
+        let conn = rusqlite::Connection::open(&db).unwrap();
+        conn.execute_batch(
+            "
+UPDATE event_log_v2 SET read_at = delivered_at;
+INSERT INTO read_sync_marks (event_key, read_at) VALUES ('legacy:issue:legacy-1', '2025-01-09T00:00:00+00:00');
+",
+        )
+        .unwrap();
+    }
+
+    let store = SqliteStateStore::new(&db).unwrap();
+    assert_eq!(
+        store.load_read_event_keys(&[event.event_key()]).unwrap(),
+        HashSet::from([event.event_key()])
+    );
+    assert!(store.check(&["acme/api".to_string()]).unwrap().is_clean());
+    let conn = rusqlite::Connection::open(&db).unwrap();
+    let (key_version, marks): (i64, i64) = conn
+        .query_row(
+            "
+SELECT key_version, (SELECT COUNT(*) FROM read_sync_marks WHERE event_key = ?1)
+FROM event_log_v2 WHERE event_key = ?1
+",
+            params![event.event_key()],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!((key_version, marks), (1, 1));
+}
+
+#[test]
+fn opening_v16_schema_adds_key_version_column() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("state.db");
+    let (store, good) = seed_corrupted_state(&db);
+    drop(store);
+    {
+        let conn = rusqlite::Connection::open(&db).unwrap();
+        conn.execute_batch(
+            "
+ALTER TABLE event_log_v2 DROP COLUMN key_version;
+UPDATE schema_meta SET value = '16' WHERE key = 'schema_version';
+",
+        )
+        .unwrap();
+    }
+
+    let store = SqliteStateStore::new(&db).unwrap();
+    assert_eq!(
+        store.stored_schema_version().unwrap().as_deref(),
+        Some("21")
+    );
+    assert!(store.load_timeline_events(10).unwrap().contains(&good));
+}
+
+#[test]
+fn check_reports_key_collisions_and_orphaned_duplicates() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("state.db");
+    let (store, good) = seed_corrupted_state(&db);
+    let configured = vec!["acme/api".to_string()];
+    // A copy of `good` whose rekey found its new key taken, and a different event with the same key.
+    insert_raw_event(&db, "legacy:good-1", &good, 1);
+    let other = WatchEvent {
+        event_id: "other-1".to_string(),
+        source_item_id: "shared".to_string(),
+        ..good.clone()
+    };
+    insert_raw_event(&db, "acme/api:issue_created:shared", &other, 1);
+    insert_raw_event(
+        &db,
+        "legacy:shared",
+        &WatchEvent {
+            event_id: "other-2".to_string(),
+            ..other.clone()
+        },
+        1,
+    );
+
+    let report = store.check(&configured).unwrap();
+    assert_eq!(
+        report.event_key_collisions,
+        vec![
+            "acme/api:issue_created:shared: acme/api:issue_created:shared, legacy:shared"
+                .to_string()
+        ]
+    );
+    assert_eq!(
+        report.orphaned_duplicate_event_keys,
+        vec!["legacy:good-1".to_string()]
+    );
+
+    let repaired = store.repair(&configured).unwrap();
+    assert_eq!(repaired.orphaned_duplicate_event_keys.len(), 1);
+    let after = store.check(&configured).unwrap();
+    assert!(after.orphaned_duplicate_event_keys.is_empty());
+    assert_eq!(after.event_key_collisions.len(), 1);
+    assert!(store.load_timeline_events(10).unwrap().contains(&good));
+}
+
+#[test]
+fn renaming_a_repository_moves_its_cursor_events_and_read_flags() {
+    let dir = tempdir().unwrap();
+    let store = SqliteStateStore::new(dir.path().join("state.db")).unwrap();
+    let ts = Utc.with_ymd_and_hms(2025, 1, 9, 0, 0, 0).unwrap();
+    let event = sample_event("moved-1", ts);
+    store
+        .persist_repo_batch(&RepoPersistBatch {
+            repo: "acme/api".to_string(),
+            poll_started_at: ts,
+            pushed: false,
+            events: vec![event.clone()],
+            review_requests: Vec::new(),
+            review_threads: Vec::new(),
+            my_prs: Vec::new(),
+        })
+        .unwrap();
+    store
+        .mark_timeline_event_read(&event.event_key(), ts)
+        .unwrap();
+
+    store
+        .mark_repo_renamed("acme/api", "acme/platform", ts)
+        .unwrap();
+
+    let moved = WatchEvent {
+        repo: "acme/platform".to_string(),
+        ..event
+    };
+    assert_eq!(store.get_cursor("acme/api").unwrap(), None);
+    assert_eq!(store.get_cursor("acme/platform").unwrap(), Some(ts));
+    assert_eq!(store.load_timeline_events(10).unwrap(), vec![moved.clone()]);
+    assert_eq!(
+        store.load_read_event_keys(&[moved.event_key()]).unwrap(),
+        HashSet::from([moved.event_key()])
+    );
+    assert_eq!(
+        store.load_repo_access_marks().unwrap()["acme/api"],
+        RepoAccessMark::Renamed {
+            new_name: "acme/platform".to_string(),
+            marked_at: ts,
+        }
+    );
+    assert!(store
+        .check(&["acme/platform".to_string()])
+        .unwrap()
+        .is_clean());
+}
+
+#[test]
+fn inaccessible_mark_is_new_only_until_cleared() {
+    let dir = tempdir().unwrap();
+    let store = SqliteStateStore::new(dir.path().join("state.db")).unwrap();
+    let ts = Utc.with_ymd_and_hms(2025, 1, 9, 0, 0, 0).unwrap();
+
+    assert!(store.mark_repo_inaccessible("acme/api", 404, ts).unwrap());
+    assert!(!store
+        .mark_repo_inaccessible("acme/api", 403, ts + Duration::hours(1))
+        .unwrap());
+    assert_eq!(
+        store.load_repo_access_marks().unwrap()["acme/api"],
+        RepoAccessMark::Inaccessible {
+            status_code: 403,
+            marked_at: ts + Duration::hours(1),
+        }
+    );
+
+    store.clear_repo_access_mark("acme/api").unwrap();
+    assert!(store.load_repo_access_marks().unwrap().is_empty());
+    assert!(store.mark_repo_inaccessible("acme/api", 404, ts).unwrap());
+}
+
+#[test]
+fn persist_repo_batch_logs_milestone_events_only_when_milestone_changes() {
+    let dir = tempdir().unwrap();
+    let store = SqliteStateStore::new(dir.path().join("state.db")).unwrap();
+    let now = Utc.with_ymd_and_hms(2025, 1, 5, 0, 0, 0).unwrap();
+    let milestone_event = |title: &str| WatchEvent {
+        event_id: format!("issue-milestone:7:{title}"),
+        kind: EventKind::IssueMilestoneAdded,
+        source_item_id: format!("7:{title}"),
+        ..sample_event("unused", now)
+    };
+    let persist = |event: WatchEvent| {
+        store
+            .persist_repo_batch(&RepoPersistBatch {
+                repo: "acme/api".to_string(),
+                poll_started_at: now,
+                pushed: false,
+                events: vec![event],
+                review_requests: Vec::new(),
+                review_threads: Vec::new(),
+                my_prs: Vec::new(),
+            })
+            .unwrap()
+            .newly_logged_event_keys
+    };
+
+    // An item already in a milestone when first polled is a baseline, not news.
+    assert!(persist(milestone_event("v1")).is_empty());
+
+    let mut same_milestone_new_id = milestone_event("v1");
+    same_milestone_new_id.event_id = "issue-milestone:7:v1:refetch".to_string();
+    assert!(persist(same_milestone_new_id).is_empty());
+
+    assert_eq!(persist(milestone_event("v2")).len(), 1);
+}
+
+#[test]
+fn pushed_milestone_event_is_logged_on_first_sight() {
+    let dir = tempdir().unwrap();
+    let store = SqliteStateStore::new(dir.path().join("state.db")).unwrap();
+    let now = Utc.with_ymd_and_hms(2025, 1, 5, 0, 0, 0).unwrap();
+    let event = WatchEvent {
+        event_id: "issue-milestone:7:v1".to_string(),
+        kind: EventKind::IssueMilestoneAdded,
+        source_item_id: "7:v1".to_string(),
+        ..sample_event("unused", now)
+    };
+    let batch = |pushed: bool| RepoPersistBatch {
+        repo: "acme/api".to_string(),
+        poll_started_at: now,
+        pushed,
+        events: vec![event.clone()],
+        review_requests: Vec::new(),
+        review_threads: Vec::new(),
+        my_prs: Vec::new(),
+    };
+
+    let logged = store.persist_repo_batch(&batch(true)).unwrap();
+    assert_eq!(logged.newly_logged_event_keys, vec![event.event_key()]);
+    assert!(store
+        .persist_repo_batch(&batch(false))
+        .unwrap()
+        .newly_logged_event_keys
+        .is_empty());
+}
+
+#[test]
+fn persist_repo_batch_logs_resolved_thread_only_after_seeing_it_unresolved() {
+    let dir = tempdir().unwrap();
+    let store = SqliteStateStore::new(dir.path().join("state.db")).unwrap();
+    let now = Utc.with_ymd_and_hms(2025, 1, 5, 0, 0, 0).unwrap();
+    let resolved_event = |thread_id: &str| WatchEvent {
+        event_id: format!("review-thread-resolved:{thread_id}"),
+        kind: EventKind::ReviewThreadResolved,
+        source_item_id: thread_id.to_string(),
+        ..sample_event("unused", now)
+    };
+    let persist = |threads: &[(&str, bool)]| {
+        store
+            .persist_repo_batch(&RepoPersistBatch {
+                repo: "acme/api".to_string(),
+                poll_started_at: now,
+                pushed: false,
+                events: threads
+                    .iter()
+                    .filter(|(_, is_resolved)| *is_resolved)
+                    .map(|(thread_id, _)| resolved_event(thread_id))
+                    .collect(),
+                review_threads: threads
+                    .iter()
+                    .map(|(thread_id, is_resolved)| ReviewThreadState {
+                        thread_id: thread_id.to_string(),
+                        is_resolved: *is_resolved,
+                    })
+                    .collect(),
+                my_prs: Vec::new(),
+                review_requests: Vec::new(),
+            })
+            .unwrap()
+            .newly_logged_event_keys
+    };
+
+    assert!(persist(&[("PRRT_a", false), ("PRRT_b", true)]).is_empty());
+    assert_eq!(
+        persist(&[("PRRT_a", true), ("PRRT_b", true)]),
+        vec!["acme/api:review_thread_resolved:PRRT_a".to_string()]
+    );
+    assert!(persist(&[("PRRT_a", true), ("PRRT_b", true)]).is_empty());
+}
+
+#[test]
+fn persist_repo_batch_logs_my_pr_readiness_only_on_transition() {
+    let dir = tempdir().unwrap();
+    let store = SqliteStateStore::new(dir.path().join("state.db")).unwrap();
+    let now = Utc.with_ymd_and_hms(2025, 1, 5, 0, 0, 0).unwrap();
+    let ready_event = |kind: EventKind, number: i64| WatchEvent {
+        event_id: format!("{kind}:{number}:abc123"),
+        kind,
+        source_item_id: format!("{number}:abc123"),
+        subject_number: Some(number),
+        ..sample_event("unused", now)
+    };
+    let persist = |prs: &[(i64, Option<&str>, Option<&str>)]| {
+        let mut events = Vec::new();
+        for (number, decision, checks) in prs {
+            if *decision == Some("APPROVED") {
+                events.push(ready_event(EventKind::MyPrApproved, *number));
+            }
+            if *checks == Some("SUCCESS") {
+                events.push(ready_event(EventKind::MyPrChecksPassed, *number));
+            }
+        }
+        store
+            .persist_repo_batch(&RepoPersistBatch {
+                repo: "acme/api".to_string(),
+                poll_started_at: now,
+                pushed: false,
+                events,
+                review_requests: Vec::new(),
+                review_threads: Vec::new(),
+                my_prs: prs
+                    .iter()
+                    .map(|(number, decision, checks)| MyPrState {
+                        pr_number: *number,
+                        review_decision: decision.map(str::to_string),
+                        checks_state: checks.map(str::to_string),
+                    })
+                    .collect(),
+            })
+            .unwrap()
+            .newly_logged_event_keys
+    };
+
+    // Already approved and green when first seen: not news.
+    assert!(persist(&[
+        (7, Some("REVIEW_REQUIRED"), Some("PENDING")),
+        (8, Some("APPROVED"), Some("SUCCESS")),
+    ])
+    .is_empty());
+    assert_eq!(
+        persist(&[
+            (7, Some("APPROVED"), Some("PENDING")),
+            (8, Some("APPROVED"), Some("SUCCESS")),
+        ]),
+        vec!["acme/api:my_pr_approved:7:abc123".to_string()]
+    );
+    assert_eq!(
+        persist(&[(7, Some("APPROVED"), Some("SUCCESS"))]),
+        vec!["acme/api:my_pr_checks_passed:7:abc123".to_string()]
+    );
+    assert!(persist(&[(7, Some("APPROVED"), Some("SUCCESS"))]).is_empty());
+}
+
+#[test]
+fn export_then_import_round_trips_events_and_skips_duplicates() {
+    let dir = tempdir().unwrap();
+    let source = SqliteStateStore::new(dir.path().join("source.db")).unwrap();
+    let created = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let events = vec![
+        sample_event("ev-1", created),
+        sample_event("ev-2", created + Duration::minutes(1)),
+    ];
+    source
+        .persist_repo_batch(&RepoPersistBatch {
+            repo: "acme/api".to_string(),
+            poll_started_at: created,
+            pushed: false,
+            events: events.clone(),
+            review_requests: Vec::new(),
+            review_threads: Vec::new(),
+            my_prs: Vec::new(),
+        })
+        .unwrap();
+
+    let mut exported = Vec::new();
+    assert_eq!(source.export_to_json(&mut exported).unwrap(), 2);
+    exported.extend_from_slice(b"\nnot json\n");
+
+    let target = SqliteStateStore::new(dir.path().join("target.db")).unwrap();
+    let summary = target.import_from_json(&mut exported.as_slice()).unwrap();
+    assert_eq!(summary.total_read, 3);
+    assert_eq!(summary.inserted, 2);
+    assert_eq!(summary.skipped_duplicate, 0);
+    assert_eq!(summary.failed, 1);
+
+    let mut imported = target.load_timeline_events(10).unwrap();
+    imported.sort_by_key(|event| event.created_at);
+    assert_eq!(imported, events);
+
+    let again = target.import_from_json(&mut exported.as_slice()).unwrap();
+    assert_eq!(again.inserted, 0);
+    assert_eq!(again.skipped_duplicate, 2);
+}
+
+#[test]
+fn load_timeline_events_before_pages_older_events_newest_first() {
+    let dir = tempdir().unwrap();
+    let store = SqliteStateStore::new(dir.path().join("state.db")).unwrap();
+    let base = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let events = (0..4)
+        .map(|idx| sample_event(&format!("ev-{idx}"), base + Duration::hours(idx)))
+        .collect::<Vec<_>>();
+    store
+        .persist_repo_batch(&RepoPersistBatch {
+            repo: "acme/api".to_string(),
+            poll_started_at: base,
+            pushed: false,
+            events,
+            review_requests: Vec::new(),
+            review_threads: Vec::new(),
+            my_prs: Vec::new(),
+        })
+        .unwrap();
+
+    let older = store
+        .load_timeline_events_before(base + Duration::hours(2), 2)
+        .unwrap();
+
+    let ids = older
+        .iter()
+        .map(|event| event.event_id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec!["ev-2", "ev-1"]);
+}
+
+fn seed_hourly_events(store: &SqliteStateStore, base: chrono::DateTime<Utc>, count: i64) {
+    let events = (0..count)
+        .map(|idx| sample_event(&format!("ev-{idx}"), base + Duration::hours(idx)))
+        .collect::<Vec<_>>();
+    store
+        .persist_repo_batch(&RepoPersistBatch {
+            repo: "acme/api".to_string(),
+            poll_started_at: base,
+            pushed: false,
+            events,
+            review_requests: Vec::new(),
+            review_threads: Vec::new(),
+            my_prs: Vec::new(),
+        })
+        .unwrap();
+}
+
+fn event_ids(events: &[WatchEvent]) -> Vec<&str> {
+    events.iter().map(|event| event.event_id.as_str()).collect()
+}
+
+#[test]
+fn find_events_page_walks_pages_with_the_last_created_at_as_cursor() {
+    let dir = tempdir().unwrap();
+    let store = SqliteStateStore::new(dir.path().join("state.db")).unwrap();
+    let base = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    seed_hourly_events(&store, base, 5);
+    let since = base + Duration::hours(1);
+
+    let first = store.find_events_page(since, None, 2).unwrap();
+    assert_eq!(event_ids(&first), vec!["ev-4", "ev-3"]);
+
+    let middle = store
+        .find_events_page(since, Some(first[1].created_at), 2)
+        .unwrap();
+    assert_eq!(event_ids(&middle), vec!["ev-2", "ev-1"]);
+
+    let last = store
+        .find_events_page(since, Some(middle[1].created_at), 2)
+        .unwrap();
+    assert!(last.is_empty());
+}
+
+#[test]
+fn find_events_page_returns_a_short_single_item_page() {
+    let dir = tempdir().unwrap();
+    let store = SqliteStateStore::new(dir.path().join("state.db")).unwrap();
+    let base = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    seed_hourly_events(&store, base, 3);
+
+    let page = store
+        .find_events_page(base, Some(base + Duration::hours(1)), 10)
+        .unwrap();
+
+    assert_eq!(event_ids(&page), vec!["ev-0"]);
+}
+
+#[test]
+fn failure_count_for_repo_only_counts_that_repo_within_window() {
+    let dir = tempdir().unwrap();
+    let store = SqliteStateStore::new(dir.path().join("state.db")).unwrap();
+    let now = Utc.with_ymd_and_hms(2025, 1, 20, 12, 0, 0).unwrap();
+    for (repo, hours_ago) in [
+        ("acme/api", 1),
+        ("acme/api", 5),
+        ("acme/api", 30),
+        ("acme/web", 1),
+    ] {
+        store
+            .record_failure(&FailureRecord::new(
+                FAILURE_KIND_REPO_POLL,
+                repo,
+                now - Duration::hours(hours_ago),
+                "boom",
+            ))
+            .unwrap();
+    }
+
+    let day_ago = now - Duration::hours(24);
+    assert_eq!(
+        store
+            .get_failure_count_for_repo("acme/api", day_ago)
+            .unwrap(),
+        2
+    );
+    assert_eq!(
+        store
+            .get_failure_count_for_repo("acme/web", day_ago)
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        store
+            .get_failure_count_for_repo("acme/api", now - Duration::hours(48))
+            .unwrap(),
+        3
+    );
+    assert_eq!(
+        store.get_failure_count_for_repo("acme/api", now).unwrap(),
+        0
+    );
+    assert_eq!(
+        store
+            .get_failure_count_for_repo("acme/missing", day_ago)
+            .unwrap(),
+        0
+    );
+}
+
+#[test]
+fn failure_context_round_trips_through_the_store() {
+    let dir = tempdir().unwrap();
+    let store = SqliteStateStore::new(dir.path().join("state.db")).unwrap();
+    let at = Utc.with_ymd_and_hms(2025, 1, 20, 12, 0, 0).unwrap();
+    let rate_limited = FailureRecord::new(FAILURE_KIND_REPO_POLL, "acme/api", at, "rate limited")
+        .with_context("status_code", "403")
+        .with_context("retry_after_seconds", "120");
+    let plain = FailureRecord::new(
+        FAILURE_KIND_REPO_POLL,
+        "acme/web",
+        at + Duration::minutes(1),
+        "boom",
+    );
+    store.record_failure(&rate_limited).unwrap();
+    store.record_failure(&plain).unwrap();
+    store
+        .record_failure(&FailureRecord::new(
+            FAILURE_KIND_REPO_POLL,
+            "acme/api",
+            at - Duration::hours(1),
+            "old",
+        ))
+        .unwrap();
+
+    assert_eq!(
+        store.load_failures_since(at).unwrap(),
+        vec![rate_limited, plain]
+    );
+}
+
+#[test]
+fn recent_failures_come_newest_first_up_to_the_limit() {
+    let dir = tempdir().unwrap();
+    let store = SqliteStateStore::new(dir.path().join("state.db")).unwrap();
+    let at = Utc.with_ymd_and_hms(2025, 1, 20, 12, 0, 0).unwrap();
+    let failures = (0..4)
+        .map(|minute| {
+            FailureRecord::new(
+                FAILURE_KIND_REPO_POLL,
+                "acme/api",
+                at + Duration::minutes(minute),
+                format!("failure {minute}"),
+            )
+        })
+        .collect::<Vec<_>>();
+    for failure in &failures {
+        store.record_failure(failure).unwrap();
+    }
+
+    assert_eq!(
+        store.load_recent_failures(3).unwrap(),
+        vec![
+            failures[3].clone(),
+            failures[2].clone(),
+            failures[1].clone()
+        ]
+    );
+    assert_eq!(
+        store.stored_schema_version().unwrap().as_deref(),
+        Some("21")
+    );
+}
+
+#[test]
+fn opening_v8_schema_adds_failure_context_column() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("state.db");
+    {
+        let store = SqliteStateStore::new(&path).unwrap();
+        drop(store);
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "
+ALTER TABLE failure_events DROP COLUMN context_json;
+UPDATE schema_meta SET value = '8' WHERE key = 'schema_version';
+INSERT INTO failure_events (kind, repo, failed_at, message)
+VALUES ('repo_poll', 'acme/api', '2025-01-20T12:00:00+00:00', 'boom');
+",
+        )
+        .unwrap();
+    }
+
+    let store = SqliteStateStore::new(&path).unwrap();
+    let at = Utc.with_ymd_and_hms(2025, 1, 20, 12, 0, 0).unwrap();
+    let failures = store.load_failures_since(at).unwrap();
+    assert_eq!(failures.len(), 1);
+    assert!(failures[0].context.is_empty());
+}
+
+#[test]
+fn failure_count_window_start_is_inclusive() {
+    let dir = tempdir().unwrap();
+    let store = SqliteStateStore::new(dir.path().join("state.db")).unwrap();
+    let at = Utc.with_ymd_and_hms(2025, 1, 20, 12, 0, 0).unwrap();
+    store
+        .record_failure(&FailureRecord::new(
+            FAILURE_KIND_REPO_POLL,
+            "acme/api",
+            at,
+            "boom",
+        ))
+        .unwrap();
+
+    assert_eq!(store.get_failure_count_for_repo("acme/api", at).unwrap(), 1);
+    assert_eq!(
+        store
+            .get_failure_count_for_repo("acme/api", at + Duration::seconds(1))
+            .unwrap(),
+        0
+    );
+}
+
+#[test]
+fn cleanup_removes_old_failures() {
+    let dir = tempdir().unwrap();
+    let store = SqliteStateStore::new(dir.path().join("state.db")).unwrap();
+    let old = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let now = old + Duration::days(120);
+    for at in [old, now - Duration::days(1)] {
+        store
+            .record_failure(&FailureRecord::new(
+                FAILURE_KIND_REPO_POLL,
+                "acme/api",
+                at,
+                "boom",
+            ))
+            .unwrap();
+    }
+
+    store.cleanup_old(&uniform_policy(90), now).unwrap();
+
+    assert_eq!(
+        store.get_failure_count_for_repo("acme/api", old).unwrap(),
+        1
+    );
+}
+
+#[test]
+fn summary_sent_at_round_trips_and_survives_reopen() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("state.db");
+    let first = Utc.with_ymd_and_hms(2025, 1, 6, 9, 0, 0).unwrap();
+    let second = Utc.with_ymd_and_hms(2025, 1, 13, 9, 5, 0).unwrap();
+    {
+        let store = SqliteStateStore::new(&path).unwrap();
+        assert_eq!(store.load_summary_sent_at().unwrap(), None);
+        store.save_summary_sent_at(first).unwrap();
+        store.save_summary_sent_at(second).unwrap();
+    }
+
+    let store = SqliteStateStore::new(&path).unwrap();
+    assert_eq!(store.load_summary_sent_at().unwrap(), Some(second));
+}
+
+#[test]
+fn opening_v9_schema_adds_notification_marks_table() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("state.db");
+    {
+        let store = SqliteStateStore::new(&path).unwrap();
+        drop(store);
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "
+DROP TABLE notification_marks;
+UPDATE schema_meta SET value = '9' WHERE key = 'schema_version';
+",
+        )
+        .unwrap();
+    }
+
+    let store = SqliteStateStore::new(&path).unwrap();
+    let at = Utc.with_ymd_and_hms(2025, 1, 6, 9, 0, 0).unwrap();
+    store.save_summary_sent_at(at).unwrap();
+    assert_eq!(store.load_summary_sent_at().unwrap(), Some(at));
+}
+
+#[test]
+fn review_reminded_at_is_kept_per_pull_request() {
+    let dir = tempdir().unwrap();
+    let store = SqliteStateStore::new(dir.path().join("state.db")).unwrap();
+    let first = Utc.with_ymd_and_hms(2025, 1, 6, 9, 0, 0).unwrap();
+    let second = first + Duration::days(1);
+
+    assert_eq!(store.load_review_reminded_at("acme/api", 7).unwrap(), None);
+    store.save_review_reminded_at("acme/api", 7, first).unwrap();
+    store
+        .save_review_reminded_at("acme/api", 7, second)
+        .unwrap();
+    store.save_review_reminded_at("acme/web", 7, first).unwrap();
+
+    assert_eq!(
+        store.load_review_reminded_at("acme/api", 7).unwrap(),
+        Some(second)
+    );
+    assert_eq!(
+        store.load_review_reminded_at("acme/web", 7).unwrap(),
+        Some(first)
+    );
+    assert_eq!(store.load_review_reminded_at("acme/api", 8).unwrap(), None);
+}
+
+#[test]
+fn merged_read_marks_never_unread_and_wait_for_events_logged_later() {
+    let dir = tempdir().unwrap();
+    let store = SqliteStateStore::new(dir.path().join("state.db")).unwrap();
+    let at = Utc.with_ymd_and_hms(2025, 1, 6, 9, 0, 0).unwrap();
+    let read_here = sample_event("read-here", at);
+    let read_elsewhere = sample_event("read-elsewhere", at);
+    let not_yet_polled = sample_event("not-yet-polled", at);
+    let batch = |events: Vec<WatchEvent>| RepoPersistBatch {
+        repo: "acme/api".to_string(),
+        poll_started_at: at,
+        pushed: false,
+        events,
+        review_requests: Vec::new(),
+        review_threads: Vec::new(),
+        my_prs: Vec::new(),
+    };
+    store
+        .persist_repo_batch(&batch(vec![read_here.clone(), read_elsewhere.clone()]))
+        .unwrap();
+    store
+        .mark_timeline_event_read(&read_here.event_key(), at + Duration::hours(2))
+        .unwrap();
+
+    let remote = HashMap::from([
+        (read_here.event_key(), at + Duration::hours(1)),
+        (read_elsewhere.event_key(), at + Duration::hours(1)),
+        (not_yet_polled.event_key(), at + Duration::hours(1)),
+    ]);
+    assert_eq!(
+        store.merge_read_marks(&remote).unwrap(),
+        vec![read_elsewhere.event_key()]
+    );
+    assert_eq!(store.load_read_marks().unwrap(), remote);
+
+    store
+        .persist_repo_batch(&batch(vec![not_yet_polled.clone()]))
+        .unwrap();
+    assert_eq!(
+        store
+            .merge_read_marks(&store.load_read_marks().unwrap())
+            .unwrap(),
+        vec![not_yet_polled.event_key()]
+    );
+    assert_eq!(
+        store
+            .load_read_event_keys(&[
+                read_here.event_key(),
+                read_elsewhere.event_key(),
+                not_yet_polled.event_key(),
+            ])
+            .unwrap()
+            .len(),
+        3
+    );
+}
+
+#[test]
+fn cleanup_keeps_only_the_newest_delivered_read_events_and_every_unread_one() {
+    let dir = tempdir().unwrap();
+    let store = SqliteStateStore::new(dir.path().join("state.db")).unwrap();
+    let now = Utc.with_ymd_and_hms(2025, 1, 6, 9, 0, 0).unwrap();
+    let events = (0..60)
+        .map(|idx| sample_event(&format!("ev-{idx:02}"), now - Duration::minutes(idx)))
+        .collect::<Vec<_>>();
+    store
+        .persist_repo_batch(&RepoPersistBatch {
+            repo: "acme/api".to_string(),
+            poll_started_at: now,
+            pushed: false,
+            events: events.clone(),
+            review_requests: Vec::new(),
+            review_threads: Vec::new(),
+            my_prs: Vec::new(),
+        })
+        .unwrap();
+    // Every other event is read: 30 read, 30 unread.
+    for event in events.iter().step_by(2) {
+        store
+            .mark_timeline_event_read(&event.event_key(), now)
+            .unwrap();
+    }
+    assert_eq!(store.count_delivered_read_events().unwrap(), 30);
+
+    let policy = RetentionPolicy {
+        max_read_history: Some(10),
+        ..uniform_policy(90)
+    };
+    store.cleanup_old(&policy, now).unwrap();
+    store.cleanup_old(&policy, now).unwrap();
+
+    assert_eq!(store.count_delivered_read_events().unwrap(), 10);
+    let remaining = store.load_timeline_events(100).unwrap();
+    assert_eq!(remaining.len(), 40);
+    let read = store
+        .load_read_event_keys(
+            &remaining
+                .iter()
+                .map(WatchEvent::event_key)
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+    let newest_read = events
+        .iter()
+        .step_by(2)
+        .take(10)
+        .map(WatchEvent::event_key)
+        .collect::<HashSet<_>>();
+    assert_eq!(read, newest_read);
+}
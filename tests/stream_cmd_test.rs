@@ -0,0 +1,99 @@
+use std::{
+    fs,
+    io::{BufRead, BufReader},
+    process::{Command, Stdio},
+    sync::mpsc,
+    time::Duration,
+};
+
+use chrono::{TimeZone, Utc};
+use gh_watch::domain::events::{EventKind, WatchEvent};
+use gh_watch::infra::state_sqlite::SqliteStateStore;
+use gh_watch::ports::{RepoBatchPort, RepoPersistBatch};
+use serde_json::Value;
+use tempfile::tempdir;
+
+fn sample_event(id: &str) -> WatchEvent {
+    WatchEvent {
+        event_id: id.to_string(),
+        repo: "acme/api".to_string(),
+        kind: EventKind::IssueCreated,
+        actor: "bob".to_string(),
+        title: "Bug report".to_string(),
+        url: "https://example.com/issues/1".to_string(),
+        created_at: Utc.with_ymd_and_hms(2025, 1, 1, 10, 0, 0).unwrap(),
+        source_item_id: id.to_string(),
+        subject_author: Some("bob".to_string()),
+        requested_reviewer: None,
+        mentions: Vec::new(),
+        team_mentions: Vec::new(),
+        subject_number: None,
+        subject_url: None,
+        account: None,
+        body: None,
+    }
+}
+
+#[test]
+fn stream_prints_new_events_as_json_lines() {
+    let dir = tempdir().unwrap();
+    let state_db_path = dir.path().join("state.db");
+    let config_path = dir.path().join("config.toml");
+    fs::write(
+        &config_path,
+        format!(
+            "state_db_path = {:?}\n\n[[repositories]]\nname = \"acme/api\"\n",
+            state_db_path.display().to_string()
+        ),
+    )
+    .unwrap();
+    let store = SqliteStateStore::new(&state_db_path).unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_gh-watch"))
+        .args(["stream", "--format", "json", "--config"])
+        .arg(&config_path)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let stdout = child.stdout.take().unwrap();
+    let (lines_tx, lines) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            if lines_tx.send(line.unwrap()).is_err() {
+                break;
+            }
+        }
+    });
+
+    // Events stored after the command started are streamed whether they land before or
+    // after its first read.
+    std::thread::sleep(Duration::from_millis(200));
+    store
+        .persist_repo_batch(&RepoPersistBatch {
+            repo: "acme/api".to_string(),
+            poll_started_at: Utc::now(),
+            pushed: false,
+            events: vec![sample_event("ev-1"), sample_event("ev-2")],
+            review_requests: Vec::new(),
+            review_threads: Vec::new(),
+            my_prs: Vec::new(),
+        })
+        .unwrap();
+
+    let received = (0..2)
+        .map(|_| lines.recv_timeout(Duration::from_secs(10)))
+        .collect::<Result<Vec<_>, _>>();
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    let ids = received
+        .expect("stream printed no events")
+        .iter()
+        .map(|line| {
+            let event: Value = serde_json::from_str(line).unwrap();
+            event["event_id"].as_str().unwrap().to_string()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(ids, ["ev-1", "ev-2"]);
+}
@@ -0,0 +1,159 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use chrono::{Duration, Utc};
+use gh_watch::{
+    domain::events::{EventKind, WatchEvent},
+    infra::state_sqlite::SqliteStateStore,
+    ports::{RepoBatchPort, RepoPersistBatch, TimelineQueryPort, TimelineReadMarkPort},
+};
+use predicates::str::contains;
+use tempfile::tempdir;
+
+fn write_config(dir: &Path, state_path: &Path, sync: &str) -> PathBuf {
+    let config_path = dir.join("config.toml");
+    fs::write(
+        &config_path,
+        format!(
+            "state_db_path = {:?}\n\n[[repositories]]\nname = \"acme/api\"\n{sync}",
+            state_path.display().to_string()
+        ),
+    )
+    .unwrap();
+    config_path
+}
+
+fn write_stub_gh(dir: &Path, script: &str) -> PathBuf {
+    let path = dir.join("gh");
+    fs::write(&path, script).unwrap();
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perm = fs::metadata(&path).unwrap().permissions();
+        perm.set_mode(0o755);
+        fs::set_permissions(&path, perm).unwrap();
+    }
+
+    path
+}
+
+fn issue(number: i64) -> WatchEvent {
+    WatchEvent {
+        event_id: format!("issue-{number}"),
+        repo: "acme/api".to_string(),
+        kind: EventKind::IssueCreated,
+        actor: "dev".to_string(),
+        title: format!("Issue {number}"),
+        url: format!("https://github.com/acme/api/issues/{number}"),
+        created_at: Utc::now() - Duration::hours(2),
+        source_item_id: number.to_string(),
+        subject_author: None,
+        requested_reviewer: None,
+        mentions: Vec::new(),
+        team_mentions: Vec::new(),
+        subject_number: Some(number),
+        subject_url: None,
+        account: None,
+        body: None,
+    }
+}
+
+#[test]
+fn sync_now_merges_remote_reads_and_pushes_local_ones() {
+    let dir = tempdir().unwrap();
+    let state_path = dir.path().join("state.db");
+    let config_path = write_config(
+        dir.path(),
+        &state_path,
+        "\n[sync]\nbackend = \"gist\"\ngist_id = \"abc123\"\n",
+    );
+
+    let read_elsewhere = issue(1);
+    let read_here = issue(2);
+    let store = SqliteStateStore::new(&state_path).unwrap();
+    store
+        .persist_repo_batch(&RepoPersistBatch {
+            repo: "acme/api".to_string(),
+            poll_started_at: Utc::now(),
+            pushed: false,
+            events: vec![read_elsewhere.clone(), read_here.clone()],
+            review_requests: Vec::new(),
+            review_threads: Vec::new(),
+            my_prs: Vec::new(),
+        })
+        .unwrap();
+    store
+        .mark_timeline_event_read(&read_here.event_key(), Utc::now())
+        .unwrap();
+    drop(store);
+
+    let remote_read_at = (Utc::now() - Duration::hours(1)).to_rfc3339();
+    let pushed_path = dir.path().join("pushed");
+    let gh_path = write_stub_gh(
+        dir.path(),
+        &format!(
+            r#"#!/usr/bin/env bash
+set -euo pipefail
+if [[ "$1" == "auth" && "$2" == "status" ]]; then
+  echo "  Logged in to github.com account octocat (keyring)"
+  exit 0
+fi
+if [[ "$1" == "api" && "$2" == "gists/abc123" ]]; then
+  echo '{{"version":1,"read":{{"{key}":"{remote_read_at}"}}}}'
+  exit 0
+fi
+if [[ "$1" == "api" && "$2" == "-X" && "$3" == "PATCH" && "$4" == "gists/abc123" && "$5 $6" == "--input -" ]]; then
+  cat > {pushed:?}
+  echo abc123
+  exit 0
+fi
+echo "unexpected args: $@" >&2
+exit 1
+"#,
+            key = read_elsewhere.event_key(),
+            pushed = pushed_path.display().to_string(),
+        ),
+    );
+
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.args(["sync", "now", "--config"])
+        .arg(&config_path)
+        .env("GH_WATCH_GH_BIN", gh_path)
+        .assert()
+        .success()
+        .stdout(contains(
+            "synced read state with gist abc123: 1 events read elsewhere, remote updated",
+        ));
+
+    let store = SqliteStateStore::new(&state_path).unwrap();
+    let read = store
+        .load_read_event_keys(&[read_elsewhere.event_key(), read_here.event_key()])
+        .unwrap();
+    assert_eq!(read.len(), 2);
+
+    let body: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&pushed_path).unwrap()).unwrap();
+    let pushed = body["files"]["gh-watch-sync.json"]["content"]
+        .as_str()
+        .unwrap();
+    assert!(pushed.contains(&read_elsewhere.event_key()), "{pushed}");
+    assert!(pushed.contains(&read_here.event_key()), "{pushed}");
+}
+
+#[test]
+fn sync_now_requires_a_sync_section() {
+    let dir = tempdir().unwrap();
+    let state_path = dir.path().join("state.db");
+    let config_path = write_config(dir.path(), &state_path, "");
+
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.args(["sync", "now", "--config"])
+        .arg(&config_path)
+        .assert()
+        .failure()
+        .stderr(contains("sync is not configured"));
+}
@@ -0,0 +1,106 @@
+use chrono::{Duration, TimeZone, Utc};
+use gh_watch::app::timeline_export::{export_timeline, TimelineExportFormat};
+use gh_watch::domain::events::{EventKind, WatchEvent};
+use gh_watch::infra::state_sqlite::SqliteStateStore;
+use gh_watch::ports::{RepoBatchPort, RepoPersistBatch};
+use serde_json::Value;
+use tempfile::tempdir;
+
+fn sample_event(id: &str, title: &str, minutes: i64) -> WatchEvent {
+    WatchEvent {
+        event_id: id.to_string(),
+        repo: "acme/api".to_string(),
+        kind: EventKind::IssueCreated,
+        actor: "bob".to_string(),
+        title: title.to_string(),
+        url: format!("https://github.com/acme/api/issues/{id}"),
+        created_at: Utc.with_ymd_and_hms(2025, 1, 1, 10, 0, 0).unwrap()
+            + Duration::minutes(minutes),
+        source_item_id: id.to_string(),
+        subject_author: Some("bob".to_string()),
+        requested_reviewer: None,
+        mentions: Vec::new(),
+        team_mentions: Vec::new(),
+        subject_number: None,
+        subject_url: None,
+        account: None,
+        body: None,
+    }
+}
+
+fn store_with_events(path: &std::path::Path) -> SqliteStateStore {
+    let store = SqliteStateStore::new(path).unwrap();
+    store
+        .persist_repo_batch(&RepoPersistBatch {
+            repo: "acme/api".to_string(),
+            poll_started_at: Utc::now(),
+            pushed: false,
+            events: vec![
+                sample_event("2", "Later, with a comma", 5),
+                sample_event("1", "First", 0),
+            ],
+            review_requests: Vec::new(),
+            review_threads: Vec::new(),
+            my_prs: Vec::new(),
+        })
+        .unwrap();
+    store
+}
+
+#[test]
+fn ndjson_export_writes_one_event_per_line_oldest_first() {
+    let dir = tempdir().unwrap();
+    let store = store_with_events(&dir.path().join("state.db"));
+    let out = dir.path().join("timeline.ndjson");
+
+    let written = export_timeline(&store, &out, TimelineExportFormat::Ndjson).unwrap();
+
+    assert_eq!(written, 2);
+    let ids = std::fs::read_to_string(&out)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str::<WatchEvent>(line).unwrap().event_id)
+        .collect::<Vec<_>>();
+    assert_eq!(ids, ["1", "2"]);
+}
+
+#[test]
+fn json_export_writes_one_array() {
+    let dir = tempdir().unwrap();
+    let store = store_with_events(&dir.path().join("state.db"));
+    let out = dir.path().join("timeline.json");
+
+    export_timeline(&store, &out, TimelineExportFormat::Json).unwrap();
+
+    let json: Value = serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+    let events = json.as_array().expect("expected an array");
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0]["title"], "First");
+}
+
+#[test]
+fn csv_export_has_a_header_and_quotes_titles_with_commas() {
+    let dir = tempdir().unwrap();
+    let store = store_with_events(&dir.path().join("state.db"));
+    let out = dir.path().join("timeline.csv");
+
+    export_timeline(&store, &out, TimelineExportFormat::Csv).unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(&out).unwrap(),
+        "created_at,repo,kind,actor,title,url\n\
+         2025-01-01T10:00:00+00:00,acme/api,issue_created,bob,First,https://github.com/acme/api/issues/1\n\
+         2025-01-01T10:05:00+00:00,acme/api,issue_created,bob,\"Later, with a comma\",https://github.com/acme/api/issues/2\n"
+    );
+}
+
+#[test]
+fn export_into_a_missing_directory_fails_with_the_path() {
+    let dir = tempdir().unwrap();
+    let store = store_with_events(&dir.path().join("state.db"));
+    let out = dir.path().join("missing").join("timeline.ndjson");
+
+    let err = export_timeline(&store, &out, TimelineExportFormat::Ndjson).unwrap_err();
+
+    assert!(err.to_string().contains("timeline.ndjson"), "{err}");
+}
@@ -1,8 +1,13 @@
+use std::collections::HashMap;
+
 use chrono::{TimeZone, Utc};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use gh_watch::config::KeymapConfig;
 use gh_watch::domain::events::{EventKind, WatchEvent};
+use gh_watch::domain::subject_state::{SubjectRef, SubjectState};
 use gh_watch::ui::tui::{
-    handle_input, parse_input, parse_mouse_input, ActiveTab, InputCommand, TuiModel,
+    handle_input, parse_input, parse_mouse_input, ActiveTab, ContextMenuAction, InputCommand,
+    Keymap, TimelineItem, TuiModel, KIND_FILTER_KEYS,
 };
 use ratatui::layout::Rect;
 
@@ -37,6 +42,11 @@ fn ev_with(
         subject_author: Some(actor.to_string()),
         requested_reviewer: None,
         mentions: Vec::new(),
+        team_mentions: Vec::new(),
+        subject_number: None,
+        subject_url: None,
+        account: None,
+        body: None,
     }
 }
 
@@ -67,6 +77,11 @@ fn ev_for_my_pr(
         subject_author: meta.subject_author.map(|s| s.to_string()),
         requested_reviewer: meta.requested_reviewer.map(|s| s.to_string()),
         mentions: meta.mentions.iter().map(|m| m.to_string()).collect(),
+        team_mentions: Vec::new(),
+        subject_number: None,
+        subject_url: None,
+        account: None,
+        body: None,
     }
 }
 
@@ -122,69 +137,220 @@ fn watched_repositories_start_empty_and_can_be_set() {
 #[test]
 fn enter_key_maps_to_open_selected_url() {
     let key = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
-    assert_eq!(parse_input(key), InputCommand::OpenSelectedUrl);
+    assert_eq!(
+        parse_input(key, &Keymap::default()),
+        InputCommand::OpenSelectedUrl
+    );
+}
+
+#[test]
+fn configured_keymap_overrides_and_shadows_default_bindings() {
+    let keymap = Keymap::from_config(&KeymapConfig {
+        refresh: Some(vec!["q".to_string(), "F5".to_string()]),
+        scroll_down: Some(vec!["n".to_string()]),
+        gh_checkout: Some(Vec::new()),
+        ..KeymapConfig::default()
+    })
+    .expect("valid keymap");
+    let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+
+    assert_eq!(
+        parse_input(key(KeyCode::Char('q')), &keymap),
+        InputCommand::Refresh
+    );
+    assert_eq!(
+        parse_input(key(KeyCode::F(5)), &keymap),
+        InputCommand::Refresh
+    );
+    assert_eq!(
+        parse_input(key(KeyCode::Char('r')), &keymap),
+        InputCommand::None
+    );
+    assert_eq!(
+        parse_input(key(KeyCode::Char('n')), &keymap),
+        InputCommand::ScrollDown
+    );
+    assert_eq!(
+        parse_input(key(KeyCode::Char('j')), &keymap),
+        InputCommand::None
+    );
+    assert_eq!(
+        parse_input(key(KeyCode::Char('c')), &keymap),
+        InputCommand::None
+    );
+    assert_eq!(
+        parse_input(key(KeyCode::Char('?')), &keymap),
+        InputCommand::ToggleHelp
+    );
+    assert_eq!(keymap.label(InputCommand::Refresh), "q/F5");
+    assert_eq!(keymap.label(InputCommand::Quit), "-");
+    assert_eq!(keymap.label(InputCommand::GhCheckoutSelected), "-");
+}
+
+#[test]
+fn configured_keymap_can_take_over_fixed_keys() {
+    let keymap = Keymap::from_config(&KeymapConfig {
+        top: Some(vec!["0".to_string()]),
+        ..KeymapConfig::default()
+    })
+    .expect("valid keymap");
+    let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+
+    assert_eq!(
+        parse_input(key(KeyCode::Char('0')), &keymap),
+        InputCommand::JumpTop
+    );
+    assert_eq!(
+        parse_input(key(KeyCode::Char('1')), &keymap),
+        InputCommand::ToggleKindFilter(1)
+    );
+    assert_eq!(
+        parse_input(key(KeyCode::Esc), &keymap),
+        InputCommand::EscapePressed
+    );
+}
+
+#[test]
+fn yank_keys_map_to_url_and_event_key_commands() {
+    assert_eq!(
+        parse_input(
+            KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE),
+            &Keymap::default()
+        ),
+        InputCommand::YankUrl
+    );
+    assert_eq!(
+        parse_input(
+            KeyEvent::new(KeyCode::Char('Y'), KeyModifiers::SHIFT),
+            &Keymap::default()
+        ),
+        InputCommand::YankEventKey
+    );
+}
+
+#[test]
+fn gh_keys_map_to_view_and_checkout_commands() {
+    assert_eq!(
+        parse_input(
+            KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE),
+            &Keymap::default()
+        ),
+        InputCommand::GhViewSelected
+    );
+    assert_eq!(
+        parse_input(
+            KeyEvent::new(KeyCode::Char('c'), KeyModifiers::NONE),
+            &Keymap::default()
+        ),
+        InputCommand::GhCheckoutSelected
+    );
 }
 
 #[test]
 fn extended_navigation_keys_map_to_commands() {
     assert_eq!(
-        parse_input(KeyEvent::new(KeyCode::Char('?'), KeyModifiers::NONE)),
+        parse_input(
+            KeyEvent::new(KeyCode::Char('?'), KeyModifiers::NONE),
+            &Keymap::default()
+        ),
         InputCommand::ToggleHelp
     );
     assert_eq!(
-        parse_input(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE)),
+        parse_input(
+            KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE),
+            &Keymap::default()
+        ),
         InputCommand::ScrollDown
     );
     assert_eq!(
-        parse_input(KeyEvent::new(KeyCode::Char('k'), KeyModifiers::NONE)),
+        parse_input(
+            KeyEvent::new(KeyCode::Char('k'), KeyModifiers::NONE),
+            &Keymap::default()
+        ),
         InputCommand::ScrollUp
     );
     assert_eq!(
-        parse_input(KeyEvent::new(KeyCode::PageDown, KeyModifiers::NONE)),
+        parse_input(
+            KeyEvent::new(KeyCode::PageDown, KeyModifiers::NONE),
+            &Keymap::default()
+        ),
         InputCommand::PageDown
     );
     assert_eq!(
-        parse_input(KeyEvent::new(KeyCode::PageUp, KeyModifiers::NONE)),
+        parse_input(
+            KeyEvent::new(KeyCode::PageUp, KeyModifiers::NONE),
+            &Keymap::default()
+        ),
         InputCommand::PageUp
     );
     assert_eq!(
-        parse_input(KeyEvent::new(KeyCode::Char('g'), KeyModifiers::NONE)),
-        InputCommand::JumpTop
+        parse_input(
+            KeyEvent::new(KeyCode::Char('g'), KeyModifiers::NONE),
+            &Keymap::default()
+        ),
+        InputCommand::ToggleGroupByRepo
     );
     assert_eq!(
-        parse_input(KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT)),
+        parse_input(
+            KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT),
+            &Keymap::default()
+        ),
         InputCommand::JumpBottom
     );
     assert_eq!(
-        parse_input(KeyEvent::new(KeyCode::Home, KeyModifiers::NONE)),
+        parse_input(
+            KeyEvent::new(KeyCode::Home, KeyModifiers::NONE),
+            &Keymap::default()
+        ),
         InputCommand::JumpTop
     );
     assert_eq!(
-        parse_input(KeyEvent::new(KeyCode::End, KeyModifiers::NONE)),
+        parse_input(
+            KeyEvent::new(KeyCode::End, KeyModifiers::NONE),
+            &Keymap::default()
+        ),
         InputCommand::JumpBottom
     );
     assert_eq!(
-        parse_input(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE)),
+        parse_input(
+            KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE),
+            &Keymap::default()
+        ),
         InputCommand::NextTab
     );
     assert_eq!(
-        parse_input(KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT)),
+        parse_input(
+            KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT),
+            &Keymap::default()
+        ),
         InputCommand::PrevTab
     );
     assert_eq!(
-        parse_input(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)),
+        parse_input(
+            KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE),
+            &Keymap::default()
+        ),
         InputCommand::EscapePressed
     );
     assert_eq!(
-        parse_input(KeyEvent::new(KeyCode::Char('/'), KeyModifiers::NONE)),
-        InputCommand::None
+        parse_input(
+            KeyEvent::new(KeyCode::Char('/'), KeyModifiers::NONE),
+            &Keymap::default()
+        ),
+        InputCommand::StartSearch
     );
     assert_eq!(
-        parse_input(KeyEvent::new(KeyCode::Char('f'), KeyModifiers::NONE)),
+        parse_input(
+            KeyEvent::new(KeyCode::Char('f'), KeyModifiers::NONE),
+            &Keymap::default()
+        ),
         InputCommand::None
     );
     assert_eq!(
-        parse_input(KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE)),
+        parse_input(
+            KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE),
+            &Keymap::default()
+        ),
         InputCommand::None
     );
 }
@@ -222,6 +388,26 @@ fn tab_switch_cycles_between_timeline_my_pr_and_repositories() {
     assert_eq!(model.active_tab, ActiveTab::MyPr);
 }
 
+#[test]
+fn switching_to_repositories_tab_clears_error_badge() {
+    let mut model = TuiModel::new(10);
+    model.error_badge_count = 3;
+
+    handle_input(&mut model, InputCommand::NextTab);
+    assert_eq!(model.error_badge_count, 3);
+
+    handle_input(&mut model, InputCommand::NextTab);
+    assert_eq!(model.active_tab, ActiveTab::Repositories);
+    assert_eq!(model.error_badge_count, 0);
+
+    model.error_badge_count = 2;
+    handle_input(&mut model, InputCommand::NextTab);
+    assert_eq!(model.error_badge_count, 2);
+
+    handle_input(&mut model, InputCommand::PrevTab);
+    assert_eq!(model.error_badge_count, 0);
+}
+
 #[test]
 fn page_navigation_uses_page_size() {
     let mut model = TuiModel::new(10);
@@ -277,6 +463,45 @@ fn timeline_keeps_selected_event_after_refresh_when_event_survives() {
     assert_eq!(model.timeline[model.selected].event_id, "a");
 }
 
+#[test]
+fn timeline_drops_repeated_events_that_share_a_timestamp_with_others() {
+    let mut model = TuiModel::new(10);
+    let at = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let api = ev_with("1", at, EventKind::IssueCreated, "acme/api", "dev", "api");
+    let web = ev_with("1", at, EventKind::IssueCreated, "acme/web", "dev", "web");
+    let other = ev_with("2", at, EventKind::IssueCreated, "acme/api", "dev", "other");
+    model.push_timeline(vec![api.clone(), web.clone()]);
+
+    model.push_timeline(vec![other.clone(), api.clone(), web.clone()]);
+
+    let keys = model
+        .timeline
+        .iter()
+        .map(WatchEvent::event_key)
+        .collect::<Vec<_>>();
+    assert_eq!(keys, [api.event_key(), web.event_key(), other.event_key()]);
+}
+
+#[test]
+fn truncated_event_reappearing_does_not_move_the_selection() {
+    let mut model = TuiModel::new(2);
+    let at = |day| Utc.with_ymd_and_hms(2025, 1, day, 0, 0, 0).unwrap();
+    let truncated = ev_with("c", at(1), EventKind::IssueCreated, "acme/web", "dev", "c");
+    model.push_timeline(vec![ev("a", at(2)), ev("b", at(1)), truncated.clone()]);
+    model.selected = 1;
+    model.selected_event_key = Some(model.timeline[1].event_key());
+
+    model.push_timeline(vec![truncated]);
+
+    let ids = model
+        .timeline
+        .iter()
+        .map(|event| event.event_id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, ["a", "b"]);
+    assert_eq!(model.timeline[model.selected].event_id, "b");
+}
+
 #[test]
 fn timeline_selection_falls_back_when_selected_event_drops_out() {
     let mut model = TuiModel::new(2);
@@ -420,6 +645,49 @@ fn my_pr_tab_is_empty_when_viewer_login_is_unavailable() {
     assert!(model.timeline.is_empty());
 }
 
+#[test]
+fn my_pr_tab_includes_team_member_prs_without_viewer_login() {
+    let mut model = TuiModel::new(20);
+    model.set_team_members(vec!["bob".to_string()]);
+    model.push_timeline(vec![
+        ev_for_my_pr(
+            "team",
+            Utc.with_ymd_and_hms(2025, 1, 6, 0, 0, 0).unwrap(),
+            EventKind::PrCreated,
+            "team pr",
+            "https://example.com/pull/10",
+            MyPrEventMeta {
+                actor: "bob",
+                subject_author: Some("bob"),
+                requested_reviewer: None,
+                mentions: &[],
+            },
+        ),
+        ev_for_my_pr(
+            "other",
+            Utc.with_ymd_and_hms(2025, 1, 5, 0, 0, 0).unwrap(),
+            EventKind::PrCreated,
+            "other pr",
+            "https://example.com/pull/11",
+            MyPrEventMeta {
+                actor: "carol",
+                subject_author: Some("carol"),
+                requested_reviewer: None,
+                mentions: &[],
+            },
+        ),
+    ]);
+
+    model.set_active_tab(ActiveTab::MyPr);
+
+    let ids = model
+        .timeline
+        .iter()
+        .map(|event| event.event_id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec!["team"]);
+}
+
 #[test]
 fn my_pr_tab_accepts_timeline_navigation_and_mouse() {
     let mut model = TuiModel::new(20);
@@ -467,7 +735,7 @@ fn my_pr_tab_accepts_timeline_navigation_and_mouse() {
         modifiers: KeyModifiers::NONE,
     };
 
-    let cmd = parse_mouse_input(click, area, &model);
+    let cmd = parse_mouse_input(click, area, &model, Utc::now());
     assert_eq!(cmd, InputCommand::SelectIndex(1));
 }
 
@@ -496,10 +764,200 @@ fn mouse_click_in_timeline_selects_row_using_offset() {
         modifiers: KeyModifiers::NONE,
     };
 
-    let cmd = parse_mouse_input(click, area, &model);
+    let cmd = parse_mouse_input(click, area, &model, Utc::now());
     assert_eq!(cmd, InputCommand::SelectIndex(1));
 }
 
+fn two_event_model() -> TuiModel {
+    let mut model = TuiModel::new(10);
+    model.push_timeline(vec![
+        ev("1", Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap()),
+        ev("2", Utc.with_ymd_and_hms(2025, 1, 2, 0, 0, 0).unwrap()),
+    ]);
+    model
+}
+
+fn click_at(button: MouseButton, column: u16, row: u16) -> MouseEvent {
+    MouseEvent {
+        kind: MouseEventKind::Down(button),
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    }
+}
+
+#[test]
+fn second_click_on_the_same_row_within_the_window_opens_it() {
+    let mut model = two_event_model();
+    let area = Rect::new(0, 0, 100, 30);
+    let clicked_at = Utc.with_ymd_and_hms(2025, 1, 5, 12, 0, 0).unwrap();
+    let click = click_at(MouseButton::Left, 2, 8);
+
+    assert_eq!(
+        parse_mouse_input(click, area, &model, clicked_at),
+        InputCommand::SelectIndex(0)
+    );
+    model.last_click = Some((0, clicked_at));
+
+    let soon = clicked_at + chrono::Duration::milliseconds(400);
+    assert_eq!(
+        parse_mouse_input(click, area, &model, soon),
+        InputCommand::OpenSelectedUrl
+    );
+    let late = clicked_at + chrono::Duration::milliseconds(401);
+    assert_eq!(
+        parse_mouse_input(click, area, &model, late),
+        InputCommand::SelectIndex(0)
+    );
+    assert_eq!(
+        parse_mouse_input(click_at(MouseButton::Left, 2, 9), area, &model, soon),
+        InputCommand::SelectIndex(1)
+    );
+}
+
+#[test]
+fn scrolling_between_clicks_resets_double_click() {
+    let mut model = two_event_model();
+    let area = Rect::new(0, 0, 100, 30);
+    let clicked_at = Utc.with_ymd_and_hms(2025, 1, 5, 12, 0, 0).unwrap();
+    model.last_click = Some((0, clicked_at));
+
+    handle_input(&mut model, InputCommand::ScrollDown);
+    handle_input(&mut model, InputCommand::ScrollUp);
+
+    assert_eq!(model.last_click, None);
+    assert_eq!(
+        parse_mouse_input(
+            click_at(MouseButton::Left, 2, 8),
+            area,
+            &model,
+            clicked_at + chrono::Duration::milliseconds(100)
+        ),
+        InputCommand::SelectIndex(0)
+    );
+}
+
+#[test]
+fn right_click_opens_a_context_menu_navigable_by_arrows() {
+    let mut model = two_event_model();
+    let area = Rect::new(0, 0, 100, 30);
+
+    let cmd = parse_mouse_input(
+        click_at(MouseButton::Right, 20, 9),
+        area,
+        &model,
+        Utc::now(),
+    );
+    assert_eq!(
+        cmd,
+        InputCommand::OpenContextMenu {
+            index: 1,
+            column: 20,
+            row: 9
+        }
+    );
+    handle_input(&mut model, cmd);
+    assert_eq!(model.selected, 1);
+    assert_eq!(model.context_menu_action(), Some(ContextMenuAction::Open));
+
+    handle_input(&mut model, InputCommand::ScrollDown);
+    handle_input(&mut model, InputCommand::ScrollDown);
+    handle_input(&mut model, InputCommand::ScrollDown);
+    assert_eq!(
+        model.context_menu_action(),
+        Some(ContextMenuAction::MarkRead)
+    );
+    assert_eq!(model.selected, 1, "arrows move the menu, not the timeline");
+
+    handle_input(&mut model, InputCommand::ScrollUp);
+    assert_eq!(
+        model.context_menu_action(),
+        Some(ContextMenuAction::CopyUrl)
+    );
+
+    // The menu sits one row below the click, so its entries start at row 11.
+    assert_eq!(
+        parse_mouse_input(
+            click_at(MouseButton::Left, 22, 12),
+            area,
+            &model,
+            Utc::now()
+        ),
+        InputCommand::ContextMenuPick(1)
+    );
+    let outside = parse_mouse_input(click_at(MouseButton::Left, 2, 8), area, &model, Utc::now());
+    assert_eq!(outside, InputCommand::DismissContextMenu);
+    handle_input(&mut model, outside);
+    assert_eq!(model.context_menu, None);
+}
+
+fn searchable_model(results: Option<Vec<usize>>) -> TuiModel {
+    let mut model = TuiModel::new(10);
+    model.push_timeline(
+        (1..=6)
+            .map(|day| {
+                ev(
+                    &day.to_string(),
+                    Utc.with_ymd_and_hms(2025, 1, day, 0, 0, 0).unwrap(),
+                )
+            })
+            .collect(),
+    );
+    model.search_results = results;
+    model
+}
+
+#[test]
+fn next_and_prev_search_result_wrap_around() {
+    let mut model = searchable_model(Some(vec![1, 3, 4]));
+
+    let mut visited = Vec::new();
+    for _ in 0..4 {
+        handle_input(&mut model, InputCommand::NextSearchResult);
+        visited.push(model.selected);
+    }
+    assert_eq!(visited, vec![1, 3, 4, 1]);
+    assert_eq!(
+        model.selected_event_key,
+        Some(model.timeline[1].event_key())
+    );
+
+    let mut visited = Vec::new();
+    for _ in 0..3 {
+        handle_input(&mut model, InputCommand::PrevSearchResult);
+        visited.push(model.selected);
+    }
+    assert_eq!(visited, vec![4, 3, 1]);
+    assert_eq!(model.search_position(), Some((1, 3)));
+}
+
+#[test]
+fn search_navigation_without_results_keeps_selection() {
+    for results in [None, Some(Vec::new())] {
+        let mut model = searchable_model(results);
+        model.selected = 2;
+
+        handle_input(&mut model, InputCommand::NextSearchResult);
+        handle_input(&mut model, InputCommand::PrevSearchResult);
+
+        assert_eq!(model.selected, 2);
+    }
+}
+
+#[test]
+fn n_and_shift_n_map_to_search_navigation() {
+    let keymap = Keymap::default();
+    let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+    assert_eq!(
+        parse_input(key(KeyCode::Char('n')), &keymap),
+        InputCommand::NextSearchResult
+    );
+    assert_eq!(
+        parse_input(key(KeyCode::Char('N')), &keymap),
+        InputCommand::PrevSearchResult
+    );
+}
+
 #[test]
 fn polling_state_fields_are_mutable_for_loading_transitions() {
     let mut model = TuiModel::new(10);
@@ -530,7 +988,7 @@ fn mouse_click_outside_timeline_returns_none() {
         modifiers: KeyModifiers::NONE,
     };
 
-    let cmd = parse_mouse_input(click, area, &model);
+    let cmd = parse_mouse_input(click, area, &model, Utc::now());
     assert_eq!(cmd, InputCommand::None);
 }
 
@@ -557,11 +1015,11 @@ fn mouse_wheel_maps_to_scroll_commands() {
     };
 
     assert_eq!(
-        parse_mouse_input(wheel_up, area, &model),
+        parse_mouse_input(wheel_up, area, &model, Utc::now()),
         InputCommand::ScrollUp
     );
     assert_eq!(
-        parse_mouse_input(wheel_down, area, &model),
+        parse_mouse_input(wheel_down, area, &model, Utc::now()),
         InputCommand::ScrollDown
     );
 }
@@ -589,6 +1047,411 @@ fn mouse_input_is_disabled_on_repositories_tab() {
         modifiers: KeyModifiers::NONE,
     };
 
-    assert_eq!(parse_mouse_input(wheel, area, &model), InputCommand::None);
-    assert_eq!(parse_mouse_input(click, area, &model), InputCommand::None);
+    assert_eq!(
+        parse_mouse_input(wheel, area, &model, Utc::now()),
+        InputCommand::None
+    );
+    assert_eq!(
+        parse_mouse_input(click, area, &model, Utc::now()),
+        InputCommand::None
+    );
+}
+
+fn grouped_model() -> TuiModel {
+    let mut model = TuiModel::new(10);
+    model.push_timeline(vec![
+        ev_with(
+            "web-old",
+            Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+            EventKind::IssueCreated,
+            "acme/web",
+            "dev",
+            "web old",
+        ),
+        ev_with(
+            "api-old",
+            Utc.with_ymd_and_hms(2025, 1, 2, 0, 0, 0).unwrap(),
+            EventKind::IssueCreated,
+            "acme/api",
+            "dev",
+            "api old",
+        ),
+        ev_with(
+            "web-new",
+            Utc.with_ymd_and_hms(2025, 1, 3, 0, 0, 0).unwrap(),
+            EventKind::IssueCreated,
+            "acme/web",
+            "dev",
+            "web new",
+        ),
+        ev_with(
+            "api-new",
+            Utc.with_ymd_and_hms(2025, 1, 4, 0, 0, 0).unwrap(),
+            EventKind::IssueCreated,
+            "acme/api",
+            "dev",
+            "api new",
+        ),
+    ]);
+    handle_input(&mut model, InputCommand::ToggleGroupByRepo);
+    model
+}
+
+#[test]
+fn group_by_repo_orders_by_repo_then_newest_first() {
+    let mut model = grouped_model();
+    assert!(model.group_by_repo());
+
+    let ids = model
+        .timeline
+        .iter()
+        .map(|event| event.event_id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec!["api-new", "api-old", "web-new", "web-old"]);
+
+    handle_input(&mut model, InputCommand::ToggleGroupByRepo);
+    let ids = model
+        .timeline
+        .iter()
+        .map(|event| event.event_id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec!["api-new", "web-new", "api-old", "web-old"]);
+    assert!(model
+        .timeline_items()
+        .iter()
+        .all(|item| matches!(item, TimelineItem::Event(_))));
+}
+
+#[test]
+fn hide_closed_drops_events_on_resolved_subjects_and_keeps_unknown_ones() {
+    let base = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let numbered = |id: &str, number: i64, minutes: i64| {
+        let mut event = ev(id, base + chrono::Duration::minutes(minutes));
+        event.subject_number = Some(number);
+        event
+    };
+    let mut model = TuiModel::new(10);
+    model.replace_timeline(vec![
+        numbered("merged", 1, 3),
+        numbered("open", 2, 2),
+        numbered("closed", 3, 1),
+        numbered("unknown", 4, 0),
+    ]);
+    let subject = |number| SubjectRef {
+        repo: "acme/api".to_string(),
+        number,
+    };
+    model.replace_subject_states(HashMap::from([
+        (subject(1), SubjectState::Merged),
+        (subject(2), SubjectState::Open),
+        (subject(3), SubjectState::Closed),
+    ]));
+    assert_eq!(model.timeline.len(), 4);
+
+    handle_input(&mut model, InputCommand::ToggleHideClosed);
+    assert!(model.hide_closed());
+    let ids = model
+        .timeline
+        .iter()
+        .map(|event| event.event_id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec!["open", "unknown"]);
+
+    model.replace_subject_states(HashMap::from([(subject(2), SubjectState::Closed)]));
+    let ids = model
+        .timeline
+        .iter()
+        .map(|event| event.event_id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec!["merged", "closed", "unknown"]);
+
+    handle_input(&mut model, InputCommand::ToggleHideClosed);
+    assert_eq!(model.timeline.len(), 4);
+}
+
+#[test]
+fn group_by_repo_inserts_separator_before_each_repo_group() {
+    let model = grouped_model();
+
+    let rows = model
+        .timeline_items()
+        .into_iter()
+        .map(|item| match item {
+            TimelineItem::Event(event) => event.event_id.clone(),
+            TimelineItem::Separator(repo) => format!("== {repo}"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        rows,
+        vec![
+            "== acme/api",
+            "api-new",
+            "api-old",
+            "== acme/web",
+            "web-new",
+            "web-old"
+        ]
+    );
+    assert_eq!(model.timeline_row_of(0), 1);
+    assert_eq!(model.timeline_row_of(2), 4);
+}
+
+#[test]
+fn group_by_repo_navigation_skips_separator_rows() {
+    let mut model = grouped_model();
+    model.selected = 1;
+
+    handle_input(&mut model, InputCommand::ScrollDown);
+    assert_eq!(model.selected, 2);
+    assert_eq!(model.timeline[model.selected].event_id, "web-new");
+    assert_eq!(model.event_index_at_row(3), None);
+    assert_eq!(model.event_index_at_row(4), Some(2));
+
+    model.timeline_offset = 0;
+    let area = Rect::new(0, 0, 100, 30);
+    let click_separator = MouseEvent {
+        kind: MouseEventKind::Down(MouseButton::Left),
+        column: 2,
+        row: 11,
+        modifiers: KeyModifiers::NONE,
+    };
+    assert_eq!(
+        parse_mouse_input(click_separator, area, &model, Utc::now()),
+        InputCommand::None
+    );
+    let click_event = MouseEvent {
+        row: 12,
+        ..click_separator
+    };
+    assert_eq!(
+        parse_mouse_input(click_event, area, &model, Utc::now()),
+        InputCommand::SelectIndex(2)
+    );
+}
+
+#[test]
+fn toggling_group_by_repo_keeps_selected_event() {
+    let mut model = grouped_model();
+    handle_input(&mut model, InputCommand::ToggleGroupByRepo);
+    handle_input(&mut model, InputCommand::ScrollDown);
+    assert_eq!(model.timeline[model.selected].event_id, "web-new");
+
+    handle_input(&mut model, InputCommand::ToggleGroupByRepo);
+    assert_eq!(model.selected, 2);
+    assert_eq!(model.timeline[model.selected].event_id, "web-new");
+}
+
+fn kind_mixed_model() -> TuiModel {
+    let mut model = TuiModel::new(10);
+    model.push_timeline(vec![
+        ev_with(
+            "api-pr",
+            Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+            EventKind::PrCreated,
+            "acme/api",
+            "dev",
+            "api pr",
+        ),
+        ev_with(
+            "web-issue",
+            Utc.with_ymd_and_hms(2025, 1, 2, 0, 0, 0).unwrap(),
+            EventKind::IssueCreated,
+            "acme/web",
+            "dev",
+            "web issue",
+        ),
+        ev_with(
+            "web-merged",
+            Utc.with_ymd_and_hms(2025, 1, 3, 0, 0, 0).unwrap(),
+            EventKind::PrMerged,
+            "acme/web",
+            "dev",
+            "web merged",
+        ),
+        ev_with(
+            "api-comment",
+            Utc.with_ymd_and_hms(2025, 1, 4, 0, 0, 0).unwrap(),
+            EventKind::IssueCommentCreated,
+            "acme/api",
+            "dev",
+            "api comment",
+        ),
+    ]);
+    model
+}
+
+fn timeline_ids(model: &TuiModel) -> Vec<&str> {
+    model
+        .timeline
+        .iter()
+        .map(|event| event.event_id.as_str())
+        .collect()
+}
+
+#[test]
+fn number_keys_map_to_kind_filter_commands() {
+    let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+    assert_eq!(
+        parse_input(key('1'), &Keymap::default()),
+        InputCommand::ToggleKindFilter(1)
+    );
+    assert_eq!(
+        parse_input(key('7'), &Keymap::default()),
+        InputCommand::ToggleKindFilter(7)
+    );
+    assert_eq!(
+        parse_input(key('9'), &Keymap::default()),
+        InputCommand::ToggleKindFilter(9)
+    );
+    assert_eq!(
+        parse_input(key('0'), &Keymap::default()),
+        InputCommand::ClearKindFilter
+    );
+    assert_eq!(KIND_FILTER_KEYS[0], EventKind::PrCreated);
+    assert_eq!(KIND_FILTER_KEYS[6], EventKind::PrMerged);
+}
+
+#[test]
+fn kind_filter_keeps_only_toggled_kinds_in_key_order() {
+    let mut model = kind_mixed_model();
+
+    handle_input(&mut model, InputCommand::ToggleKindFilter(7));
+    handle_input(&mut model, InputCommand::ToggleKindFilter(1));
+    assert_eq!(
+        model.kind_filter(),
+        &[EventKind::PrCreated, EventKind::PrMerged]
+    );
+    assert_eq!(timeline_ids(&model), vec!["web-merged", "api-pr"]);
+
+    handle_input(&mut model, InputCommand::ToggleKindFilter(7));
+    assert_eq!(model.kind_filter(), &[EventKind::PrCreated]);
+    assert_eq!(timeline_ids(&model), vec!["api-pr"]);
+
+    handle_input(&mut model, InputCommand::ClearKindFilter);
+    assert!(model.kind_filter().is_empty());
+    assert_eq!(model.timeline.len(), 4);
+}
+
+#[test]
+fn kind_filter_survives_push_timeline() {
+    let mut model = kind_mixed_model();
+    handle_input(&mut model, InputCommand::ToggleKindFilter(2));
+
+    model.push_timeline(vec![
+        ev_with(
+            "api-issue",
+            Utc.with_ymd_and_hms(2025, 1, 5, 0, 0, 0).unwrap(),
+            EventKind::IssueCreated,
+            "acme/api",
+            "dev",
+            "api issue",
+        ),
+        ev_with(
+            "api-pr-2",
+            Utc.with_ymd_and_hms(2025, 1, 6, 0, 0, 0).unwrap(),
+            EventKind::PrCreated,
+            "acme/api",
+            "dev",
+            "api pr 2",
+        ),
+    ]);
+
+    assert_eq!(timeline_ids(&model), vec!["api-issue", "web-issue"]);
+
+    handle_input(&mut model, InputCommand::ClearKindFilter);
+    assert_eq!(model.timeline.len(), 6);
+}
+
+#[test]
+fn kind_filter_intersects_with_grouping_and_drives_mouse_rows() {
+    let mut model = kind_mixed_model();
+    handle_input(&mut model, InputCommand::ToggleGroupByRepo);
+    handle_input(&mut model, InputCommand::ToggleKindFilter(1));
+    handle_input(&mut model, InputCommand::ToggleKindFilter(7));
+
+    assert_eq!(timeline_ids(&model), vec!["api-pr", "web-merged"]);
+    assert_eq!(model.event_index_at_row(3), Some(1));
+
+    model.timeline_offset = 0;
+    let area = Rect::new(0, 0, 100, 30);
+    let click = MouseEvent {
+        kind: MouseEventKind::Down(MouseButton::Left),
+        column: 2,
+        row: 11,
+        modifiers: KeyModifiers::NONE,
+    };
+    assert_eq!(
+        parse_mouse_input(click, area, &model, Utc::now()),
+        InputCommand::SelectIndex(1)
+    );
+}
+
+#[test]
+fn kind_filter_intersects_with_my_pr_tab() {
+    let mut model = TuiModel::new(10);
+    model.set_viewer_login(Some("alice".to_string()));
+    let ts = |day| Utc.with_ymd_and_hms(2025, 1, day, 0, 0, 0).unwrap();
+    let mine = || MyPrEventMeta {
+        actor: "bob",
+        subject_author: Some("alice"),
+        requested_reviewer: None,
+        mentions: &[],
+    };
+    model.push_timeline(vec![
+        ev_for_my_pr(
+            "mine-created",
+            ts(1),
+            EventKind::PrCreated,
+            "mine created",
+            "https://example.com/pull/1",
+            mine(),
+        ),
+        ev_for_my_pr(
+            "mine-merged",
+            ts(2),
+            EventKind::PrMerged,
+            "mine merged",
+            "https://example.com/pull/1",
+            mine(),
+        ),
+        ev_for_my_pr(
+            "other-merged",
+            ts(3),
+            EventKind::PrMerged,
+            "other merged",
+            "https://example.com/pull/2",
+            MyPrEventMeta {
+                subject_author: Some("carol"),
+                ..mine()
+            },
+        ),
+    ]);
+    handle_input(&mut model, InputCommand::ToggleKindFilter(7));
+    assert_eq!(timeline_ids(&model), vec!["other-merged", "mine-merged"]);
+
+    handle_input(&mut model, InputCommand::NextTab);
+    assert_eq!(model.active_tab, ActiveTab::MyPr);
+    assert_eq!(timeline_ids(&model), vec!["mine-merged"]);
+}
+
+#[test]
+fn kind_filter_keys_are_ignored_on_repositories_tab() {
+    let mut model = kind_mixed_model();
+    model.set_active_tab(ActiveTab::Repositories);
+
+    handle_input(&mut model, InputCommand::ToggleKindFilter(1));
+    assert!(model.kind_filter().is_empty());
+}
+
+#[test]
+fn notification_indicator_lasts_under_three_seconds() {
+    let notified_at = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let mut model = TuiModel::new(10);
+    assert!(!model.notification_indicator_active(notified_at));
+
+    model.last_notification_at = Some(notified_at);
+    assert!(model.notification_indicator_active(notified_at));
+    assert!(model.notification_indicator_active(notified_at + chrono::Duration::milliseconds(2999)));
+    assert!(!model.notification_indicator_active(notified_at + chrono::Duration::seconds(3)));
+    assert!(!model.notification_indicator_active(notified_at + chrono::Duration::minutes(1)));
 }
@@ -0,0 +1,180 @@
+use std::{fs, io::Write};
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use tempfile::tempdir;
+
+#[test]
+fn watch_poll_once_then_watch_reports_initial_poll_before_starting_tui() {
+    let dir = tempdir().unwrap();
+
+    let gh_path = dir.path().join("gh");
+    let script = r#"#!/usr/bin/env bash
+set -euo pipefail
+if [[ "$1" == "auth" && "$2" == "status" ]]; then
+  echo "  Logged in to github.com account octocat (keyring)"
+  exit 0
+fi
+if [[ "$1" == "api" ]]; then
+  echo '[]'
+  exit 0
+fi
+echo "unexpected: $@" >&2
+exit 1
+"#;
+    fs::write(&gh_path, script).unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perm = fs::metadata(&gh_path).unwrap().permissions();
+        perm.set_mode(0o755);
+        fs::set_permissions(&gh_path, perm).unwrap();
+    }
+
+    let cfg_path = dir.path().join("config.toml");
+    let mut cfg = fs::File::create(&cfg_path).unwrap();
+    writeln!(
+        cfg,
+        "state_db_path = \"{}\"",
+        dir.path().join("state.db").display()
+    )
+    .unwrap();
+    writeln!(cfg, "[[repositories]]").unwrap();
+    writeln!(cfg, "name = \"acme/api\"").unwrap();
+
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.arg("watch")
+        .arg("--config")
+        .arg(&cfg_path)
+        .arg("--poll-once-then-watch")
+        .arg("--json")
+        .env("GH_WATCH_GH_BIN", &gh_path)
+        .write_stdin("")
+        .assert()
+        .stderr(predicates::str::contains("\"bootstrap_repos\":1"));
+}
+
+#[test]
+fn watch_json_requires_poll_once_then_watch() {
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.args(["watch", "--json"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("--poll-once-then-watch"));
+}
+
+#[test]
+fn watch_without_enabled_repositories_exits_4_outside_a_terminal() {
+    let dir = tempdir().unwrap();
+    let cfg_path = dir.path().join("config.toml");
+    fs::write(
+        &cfg_path,
+        format!(
+            "state_db_path = \"{}\"\n[[repositories]]\nname = \"acme/api\"\nenabled = false\n",
+            dir.path().join("state.db").display()
+        ),
+    )
+    .unwrap();
+
+    let mut cmd = cargo_bin_cmd!("gh-watch");
+    cmd.arg("watch")
+        .arg("--config")
+        .arg(&cfg_path)
+        .env("GH_WATCH_GH_BIN", dir.path().join("missing-gh"))
+        .write_stdin("")
+        .assert()
+        .code(4)
+        .stderr(predicates::str::contains(format!(
+            "no enabled repositories in {}",
+            cfg_path.display()
+        )))
+        .stderr(predicates::str::contains("[[repositories]]"));
+}
+
+#[cfg(unix)]
+#[test]
+fn headless_watch_exports_when_terminated_during_a_poll() {
+    use std::{
+        process::{Command, Stdio},
+        time::{Duration, Instant},
+    };
+
+    let dir = tempdir().unwrap();
+    let marker = dir.path().join("polling");
+    let gh_path = dir.path().join("gh");
+    let script = r#"#!/usr/bin/env bash
+set -euo pipefail
+if [[ "$1" == "auth" && "$2" == "status" ]]; then
+  echo "  Logged in to github.com account octocat (keyring)"
+  exit 0
+fi
+if [[ "$*" == *"/pulls?"* ]]; then
+  touch "__MARKER__"
+  sleep 20
+fi
+if [[ "$1" == "api" ]]; then
+  echo '[]'
+  exit 0
+fi
+echo "unexpected: $@" >&2
+exit 1
+"#
+    .replace("__MARKER__", &marker.display().to_string());
+    fs::write(&gh_path, script).unwrap();
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perm = fs::metadata(&gh_path).unwrap().permissions();
+        perm.set_mode(0o755);
+        fs::set_permissions(&gh_path, perm).unwrap();
+    }
+
+    let cfg_path = dir.path().join("config.toml");
+    fs::write(
+        &cfg_path,
+        format!(
+            "state_db_path = \"{}\"\n[[repositories]]\nname = \"acme/api\"\n",
+            dir.path().join("state.db").display()
+        ),
+    )
+    .unwrap();
+    let export_path = dir.path().join("timeline.ndjson");
+
+    let mut child = Command::new(assert_cmd::cargo::cargo_bin!("gh-watch"))
+        .arg("watch")
+        .arg("--no-tui")
+        .arg("--config")
+        .arg(&cfg_path)
+        .arg("--export-on-exit")
+        .arg(&export_path)
+        .env("GH_WATCH_GH_BIN", &gh_path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !marker.exists() {
+        assert!(Instant::now() < deadline, "poll never started");
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    let killed = Command::new("kill")
+        .arg("-TERM")
+        .arg(child.id().to_string())
+        .status()
+        .unwrap();
+    assert!(killed.success());
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let status = loop {
+        if let Some(status) = child.try_wait().unwrap() {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            child.kill().unwrap();
+            panic!("watch did not stop on SIGTERM during a poll");
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    };
+    assert!(status.success(), "watch exited with {status}");
+    assert!(export_path.exists());
+}
@@ -1,8 +1,11 @@
-use std::fs;
+use std::{collections::HashMap, fs};
 
 use chrono::{TimeZone, Utc};
 use gh_watch::app::poll_once::poll_once;
-use gh_watch::config::{Config, FiltersConfig, NotificationConfig, PollConfig, RepositoryConfig};
+use gh_watch::config::{
+    Config, DisplayConfig, FiltersConfig, KeymapConfig, NetworkConfig, NotificationConfig,
+    PollConfig, RemindersConfig, RepositoryConfig, RetentionConfig,
+};
 use gh_watch::infra::gh_client::GhCliClient;
 use gh_watch::infra::notifier::NoopNotifier;
 use gh_watch::infra::state_sqlite::SqliteStateStore;
@@ -27,6 +30,7 @@ async fn poll_once_with_stubbed_gh_binary() {
     let script = r#"#!/usr/bin/env bash
 set -euo pipefail
 if [[ "$1" == "auth" && "$2" == "status" ]]; then
+  echo "  Logged in to github.com account octocat (keyring)"
   exit 0
 fi
 if [[ "$1" == "api" ]]; then
@@ -72,23 +76,55 @@ exit 1
 
     let cfg = Config {
         interval_seconds: 300,
+        i_know_what_im_doing: false,
         bootstrap_lookback_hours: 24,
         timeline_limit: 500,
         retention_days: 90,
+        max_notification_history: 10_000,
+        debounce_resize_ms: 50,
+        retention: RetentionConfig::default(),
+        reminders: RemindersConfig::default(),
+        sync: None,
         state_db_path: None,
+        accounts: Vec::new(),
+        startup_search_queries: Vec::new(),
+        watch_my_prs: false,
         repositories: vec![RepositoryConfig {
             name: "acme/api".to_string(),
             enabled: true,
             event_kinds: None,
+            local_path: None,
+            watch_review_threads: false,
+            account: None,
+            gh_profile: None,
+            path_filters: None,
+            topic_tags: None,
+            notification_kinds: HashMap::new(),
         }],
         notifications: NotificationConfig {
             enabled: true,
             include_url: true,
+            yank_fallback_print: false,
+            summary: None,
+            kinds: HashMap::new(),
+            max_event_age_hours: 48,
+            body_max_chars: 200,
         },
+        notification_schedule: None,
         filters: FiltersConfig::default(),
+        default_event_kinds: None,
+        filter_profiles: HashMap::new(),
+        active_filter_profile: None,
         poll: PollConfig {
             timeout_seconds: 30,
+            api_cache_ttl_seconds: None,
+            cache_ttl_seconds: 0,
+            jitter_seconds: 0,
+            use_graphql_batch: false,
         },
+        network: NetworkConfig::default(),
+        display: DisplayConfig::default(),
+        keymap: KeymapConfig::default(),
     };
 
     let c1 = FixedClock {
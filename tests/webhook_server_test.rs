@@ -0,0 +1,252 @@
+use std::sync::Arc;
+
+use gh_watch::infra::webhook_server::{
+    feed_token, hmac_sha256, verify_signature, WebhookDelivery, WebhookServer,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+    sync::mpsc,
+};
+
+const SECRET: &str = "It's a Secret to Everybody";
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+async fn start_server() -> (String, mpsc::Receiver<WebhookDelivery>) {
+    let server = WebhookServer::bind("127.0.0.1:0", SECRET).await.unwrap();
+    let addr = server.local_addr().unwrap().to_string();
+    let (sender, receiver) = mpsc::channel(8);
+    tokio::spawn(server.run(sender));
+    (addr, receiver)
+}
+
+async fn send(addr: &str, request: &str) -> String {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+fn post(event: &str, body: &str, signature: &str) -> String {
+    format!(
+        "POST /webhook HTTP/1.1\r\nHost: localhost\r\nX-GitHub-Event: {event}\r\n\
+         X-GitHub-Delivery: d-1\r\nX-Hub-Signature-256: {signature}\r\n\
+         Content-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+#[test]
+fn hmac_matches_rfc_4231_and_github_examples() {
+    assert_eq!(
+        hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+        "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+    );
+    assert_eq!(
+        hex(&hmac_sha256(
+            &[0xaa; 131],
+            b"Test Using Larger Than Block-Size Key - Hash Key First"
+        )),
+        "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+    );
+
+    let github = "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17";
+    assert!(verify_signature(
+        SECRET.as_bytes(),
+        b"Hello, World!",
+        github
+    ));
+    assert!(!verify_signature(
+        SECRET.as_bytes(),
+        b"Hello, World?",
+        github
+    ));
+    assert!(!verify_signature(
+        SECRET.as_bytes(),
+        b"Hello, World!",
+        &github.replace("sha256=", "sha1=")
+    ));
+}
+
+#[tokio::test]
+async fn healthz_answers_ok() {
+    let (addr, _deliveries) = start_server().await;
+
+    let response = send(&addr, "GET /healthz HTTP/1.1\r\nHost: localhost\r\n\r\n").await;
+
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{response}");
+    assert!(response.ends_with("\r\n\r\nok\n"), "{response}");
+}
+
+#[tokio::test]
+async fn signed_delivery_is_forwarded() {
+    let (addr, mut deliveries) = start_server().await;
+    let body = r#"{"action":"opened"}"#;
+    let signature = format!(
+        "sha256={}",
+        hex(&hmac_sha256(SECRET.as_bytes(), body.as_bytes()))
+    );
+
+    let response = send(&addr, &post("issues", body, &signature)).await;
+
+    assert!(
+        response.starts_with("HTTP/1.1 202 Accepted\r\n"),
+        "{response}"
+    );
+    assert_eq!(
+        deliveries.recv().await.unwrap(),
+        WebhookDelivery {
+            event: "issues".to_string(),
+            delivery_id: Some("d-1".to_string()),
+            body: body.to_string(),
+        }
+    );
+}
+
+#[tokio::test]
+async fn delivery_with_a_bad_signature_is_rejected() {
+    let (addr, mut deliveries) = start_server().await;
+    let body = r#"{"action":"opened"}"#;
+    let signature = format!(
+        "sha256={}",
+        hex(&hmac_sha256(b"other secret", body.as_bytes()))
+    );
+
+    let response = send(&addr, &post("issues", body, &signature)).await;
+
+    assert!(
+        response.starts_with("HTTP/1.1 401 Unauthorized\r\n"),
+        "{response}"
+    );
+    assert!(deliveries.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn oversized_delivery_is_rejected_before_its_body_is_read() {
+    let (addr, mut deliveries) = start_server().await;
+    let request = "POST /webhook HTTP/1.1\r\nHost: localhost\r\nX-GitHub-Event: issues\r\n\
+                   Content-Length: 2097152\r\n\r\n";
+
+    let response = send(&addr, request).await;
+
+    assert!(
+        response.starts_with("HTTP/1.1 413 Payload Too Large\r\n"),
+        "{response}"
+    );
+    assert!(deliveries.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn delivery_cut_short_of_its_content_length_is_rejected() {
+    let (addr, mut deliveries) = start_server().await;
+    let body = r#"{"action":"opened"}"#;
+    let signature = format!(
+        "sha256={}",
+        hex(&hmac_sha256(SECRET.as_bytes(), body.as_bytes()))
+    );
+    let request = post("issues", body, &signature).replace(
+        &format!("Content-Length: {}", body.len()),
+        &format!("Content-Length: {}", body.len() + 100),
+    );
+
+    let mut stream = TcpStream::connect(&addr).await.unwrap();
+    stream.write_all(request.as_bytes()).await.unwrap();
+    stream.shutdown().await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+
+    assert!(
+        response.starts_with("HTTP/1.1 400 Bad Request\r\n"),
+        "{response}"
+    );
+    assert!(deliveries.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn feed_is_served_only_when_attached() {
+    let (addr, _deliveries) = start_server().await;
+    let response = send(&addr, "GET /feed.atom HTTP/1.1\r\nHost: localhost\r\n\r\n").await;
+    assert!(
+        response.starts_with("HTTP/1.1 404 Not Found\r\n"),
+        "{response}"
+    );
+
+    let server = WebhookServer::bind("127.0.0.1:0", SECRET)
+        .await
+        .unwrap()
+        .with_feed(Arc::new(|| Ok("<feed/>".to_string())));
+    let addr = server.local_addr().unwrap().to_string();
+    let (sender, _deliveries) = mpsc::channel(8);
+    tokio::spawn(server.run(sender));
+
+    let response = send(
+        &addr,
+        &format!(
+            "GET /feed.atom?token={} HTTP/1.1\r\nHost: localhost\r\n\r\n",
+            feed_token(SECRET.as_bytes())
+        ),
+    )
+    .await;
+
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{response}");
+    assert!(
+        response.contains("Content-Type: application/atom+xml"),
+        "{response}"
+    );
+    assert!(response.ends_with("\r\n\r\n<feed/>"), "{response}");
+}
+
+#[tokio::test]
+async fn feed_requires_the_token_derived_from_the_secret() {
+    let server = WebhookServer::bind("127.0.0.1:0", SECRET)
+        .await
+        .unwrap()
+        .with_feed(Arc::new(|| Ok("<feed/>".to_string())));
+    let addr = server.local_addr().unwrap().to_string();
+    let (sender, _deliveries) = mpsc::channel(8);
+    tokio::spawn(server.run(sender));
+
+    for path in [
+        "/feed.atom",
+        "/feed.atom?token=wrong",
+        "/feed.atom?token=It's%20a%20Secret%20to%20Everybody",
+    ] {
+        let response = send(
+            &addr,
+            &format!("GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n"),
+        )
+        .await;
+        assert!(
+            response.starts_with("HTTP/1.1 401 Unauthorized\r\n"),
+            "{path}: {response}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn connections_past_the_limit_wait_for_a_free_slot() {
+    let server = WebhookServer::bind("127.0.0.1:0", SECRET)
+        .await
+        .unwrap()
+        .with_max_connections(1);
+    let addr = server.local_addr().unwrap().to_string();
+    let (sender, _deliveries) = mpsc::channel(8);
+    tokio::spawn(server.run(sender));
+
+    let idle = TcpStream::connect(&addr).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    let waiting = tokio::spawn({
+        let addr = addr.clone();
+        async move { send(&addr, "GET /healthz HTTP/1.1\r\nHost: localhost\r\n\r\n").await }
+    });
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    assert!(!waiting.is_finished());
+
+    drop(idle);
+    let response = waiting.await.unwrap();
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{response}");
+}